@@ -0,0 +1,104 @@
+//! Benchmarks for the per-check cost of watching a region: hashing, capture and PNG encoding
+//! Hashing and encoding run on synthetic frames; capture needs a display (real or Xvfb).
+//!
+//! To run: cargo bench --bench capture
+//! Compare against a saved run: cargo bench --bench capture -- --save-baseline before, then --baseline before
+
+use std::io::Cursor;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use image::{ImageOutputFormat, RgbaImage};
+use loopautoma_lib::os::hash::hash_rgba;
+
+/// Region sizes from a status line up to a full 4K screen
+const SIZES: &[(&str, u32, u32)] = &[
+    ("64x64", 64, 64),
+    ("640x480", 640, 480),
+    ("1920x1080", 1920, 1080),
+    ("3840x2160", 3840, 2160),
+];
+
+/// RGBA pixels with enough variation that PNG cannot compress them away
+fn frame(width: u32, height: u32) -> Vec<u8> {
+    let mut state = 0x2545_F491_4F6C_DD1Du64;
+    (0..width * height)
+        .flat_map(|i| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let (x, y) = (i % width, i / width);
+            [(x & 0xFF) as u8, (y & 0xFF) as u8, (state & 0x0F) as u8, 0xFF]
+        })
+        .collect()
+}
+
+fn bench_hash_region(c: &mut Criterion) {
+    let mut group = c.benchmark_group("hash_region");
+    for &(name, width, height) in SIZES {
+        let pixels = frame(width, height);
+        group.throughput(Throughput::Bytes(pixels.len() as u64));
+        for downscale in [1, 4] {
+            group.bench_with_input(BenchmarkId::new(format!("downscale_{}", downscale), name), &pixels, |b, pixels| {
+                b.iter(|| hash_rgba(pixels, width, height, downscale))
+            });
+        }
+    }
+    group.finish();
+}
+
+fn bench_png_encoding(c: &mut Criterion) {
+    let mut group = c.benchmark_group("png_encoding");
+    group.sample_size(10);
+    for &(name, width, height) in SIZES {
+        let pixels = frame(width, height);
+        group.throughput(Throughput::Bytes(pixels.len() as u64));
+        // Encoded the way region previews, screenshots and MCP captures are
+        group.bench_with_input(BenchmarkId::from_parameter(name), &pixels, |b, pixels| {
+            b.iter(|| {
+                let image = RgbaImage::from_vec(width, height, pixels.clone()).unwrap();
+                let mut png = Vec::new();
+                image.write_to(&mut Cursor::new(&mut png), ImageOutputFormat::Png).unwrap();
+                png
+            })
+        });
+    }
+    group.finish();
+}
+
+#[cfg(all(target_os = "linux", feature = "os-linux-capture-xcap"))]
+fn bench_capture_region(c: &mut Criterion) {
+    use loopautoma_lib::domain::{Rect, Region, ScreenCapture};
+    use loopautoma_lib::os::linux::LinuxCapture;
+
+    if std::env::var("DISPLAY").is_err() {
+        eprintln!("Skipping capture_region: DISPLAY not set");
+        return;
+    }
+    let capture = LinuxCapture;
+    let mut group = c.benchmark_group("capture_region");
+    group.sample_size(20);
+    for &(name, width, height) in SIZES {
+        let region = Region {
+            id: name.into(),
+            rect: Rect { x: 0, y: 0, width, height },
+            name: None,
+        };
+        // Regions larger than the screen cannot be captured
+        if let Err(e) = capture.capture_region(&region) {
+            eprintln!("Skipping capture_region/{}: {}", name, e);
+            continue;
+        }
+        group.throughput(Throughput::Bytes(u64::from(width * height * 4)));
+        group.bench_function(BenchmarkId::new("capture", name), |b| b.iter(|| capture.capture_region(&region)));
+        group.bench_function(BenchmarkId::new("hash", name), |b| b.iter(|| capture.hash_region(&region, 1)));
+    }
+    group.finish();
+}
+
+#[cfg(not(all(target_os = "linux", feature = "os-linux-capture-xcap")))]
+fn bench_capture_region(_c: &mut Criterion) {
+    eprintln!("Skipping capture_region: built without a capture backend");
+}
+
+criterion_group!(benches, bench_hash_region, bench_png_encoding, bench_capture_region);
+criterion_main!(benches);
@@ -1,5 +1,9 @@
-use crate::domain::{Action, ActionContext, Automation, MouseButton, Region, ScreenCapture};
+use crate::domain::{
+    Action, ActionContext, Automation, ElementFinder, ElementQuery, Error, MouseButton, Region,
+    ScreenCapture, UiElement,
+};
 use crate::llm::{build_risk_guidance, capture_region_images, LLMClient};
+use serde_json::json;
 
 pub struct MoveCursor {
     pub x: u32,
@@ -13,9 +17,12 @@ impl Action for MoveCursor {
         &self,
         automation: &dyn Automation,
         _context: &mut ActionContext,
-    ) -> Result<(), String> {
+    ) -> Result<(), Error> {
         automation.move_cursor(self.x, self.y)
     }
+    fn debug_params(&self, _context: &ActionContext) -> serde_json::Value {
+        json!({ "x": self.x, "y": self.y })
+    }
 }
 
 pub struct Click {
@@ -29,11 +36,17 @@ impl Action for Click {
         &self,
         automation: &dyn Automation,
         _context: &mut ActionContext,
-    ) -> Result<(), String> {
+    ) -> Result<(), Error> {
         automation.click(self.button)
     }
+    fn debug_params(&self, _context: &ActionContext) -> serde_json::Value {
+        json!({ "button": self.button })
+    }
 }
 
+/// Characters typed per `type_text` call
+const TYPE_CHUNK_CHARS: usize = 32;
+
 pub struct TypeText {
     pub text: String,
 }
@@ -45,9 +58,9 @@ impl Action for TypeText {
         &self,
         automation: &dyn Automation,
         context: &mut ActionContext,
-    ) -> Result<(), String> {
-        // Expand variables like $prompt
-        let expanded = context.expand(&self.text);
+    ) -> Result<(), Error> {
+        // Expand variables like $prompt and the profile's {{secret:name}} references
+        let expanded = context.expand_with_secrets(&self.text).map_err(Error::Config)?;
 
         // Check for inline key syntax like {Key:Enter}
         if expanded.starts_with("{Key:") && expanded.ends_with("}") {
@@ -55,7 +68,81 @@ impl Action for TypeText {
             return automation.key(&key);
         }
 
-        automation.type_text(&expanded)
+        // In pieces, so a pause or stop lands within a few characters
+        let chars: Vec<char> = expanded.chars().collect();
+        for piece in chars.chunks(TYPE_CHUNK_CHARS) {
+            if context.cancel.is_cancelled() {
+                return Err(Error::Cancelled("typing was cancelled".into()));
+            }
+            automation.type_text(&piece.iter().collect::<String>())?;
+        }
+        Ok(())
+    }
+    fn debug_params(&self, context: &ActionContext) -> serde_json::Value {
+        json!({ "text": context.expand(&self.text) })
+    }
+}
+
+/// The first element matching `query`, or an error saying what was missing
+fn find_element(finder: &dyn ElementFinder, query: &ElementQuery) -> Result<UiElement, Error> {
+    finder
+        .find(query)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| Error::Action(format!("No {} found on screen", query)))
+}
+
+/// Clicks a UI element looked up in the accessibility tree each time it runs,
+/// so theme and layout changes do not break the profile
+pub struct ClickElement {
+    pub query: ElementQuery,
+    pub button: MouseButton,
+    /// Use the element's default accessibility action instead of the mouse
+    pub invoke: bool,
+    pub finder: std::sync::Arc<dyn ElementFinder + Send + Sync>,
+}
+impl Action for ClickElement {
+    fn name(&self) -> &'static str {
+        "ClickElement"
+    }
+    fn execute(
+        &self,
+        automation: &dyn Automation,
+        _context: &mut ActionContext,
+    ) -> Result<(), Error> {
+        if self.invoke {
+            return Ok(self.finder.invoke(&self.query).map(|_| ())?);
+        }
+        let (x, y) = find_element(self.finder.as_ref(), &self.query)?.center();
+        automation.move_cursor(x, y)?;
+        automation.click(self.button)
+    }
+    fn debug_params(&self, _context: &ActionContext) -> serde_json::Value {
+        json!({ "element": self.query, "button": self.button, "invoke": self.invoke })
+    }
+}
+
+/// Stores the text or value of a UI element in a variable for later actions
+pub struct ReadElementText {
+    pub query: ElementQuery,
+    pub variable_name: String,
+    pub finder: std::sync::Arc<dyn ElementFinder + Send + Sync>,
+}
+impl Action for ReadElementText {
+    fn name(&self) -> &'static str {
+        "ReadElementText"
+    }
+    fn execute(
+        &self,
+        _automation: &dyn Automation,
+        context: &mut ActionContext,
+    ) -> Result<(), Error> {
+        let element = find_element(self.finder.as_ref(), &self.query)?;
+        context.set(self.variable_name.clone(), element.text);
+        Ok(())
+    }
+    fn debug_params(&self, _context: &ActionContext) -> serde_json::Value {
+        json!({ "element": self.query, "variable_name": self.variable_name })
     }
 }
 
@@ -76,18 +163,35 @@ impl Action for LLMPromptGenerationAction {
         "LLMPromptGeneration"
     }
 
+    fn requires_network(&self) -> bool {
+        true
+    }
+
+    fn uses_llm(&self) -> bool {
+        true
+    }
+
+    fn debug_params(&self, _context: &ActionContext) -> serde_json::Value {
+        json!({
+            "region_ids": self.region_ids,
+            "risk_threshold": self.risk_threshold,
+            "variable_name": self.variable_name,
+            "ocr_mode": self.ocr_mode,
+        })
+    }
+
     fn execute(
         &self,
         _automation: &dyn Automation,
         context: &mut ActionContext,
-    ) -> Result<(), String> {
+    ) -> Result<(), Error> {
         // 1. Validate region_ids and collect regions
         let mut captured_regions = Vec::new();
         for region_id in &self.region_ids {
             if let Some(region) = self.all_regions.iter().find(|r| &r.id == region_id) {
                 captured_regions.push(region.clone());
             } else {
-                return Err(format!("Region '{}' not found", region_id));
+                return Err(Error::Config(format!("Region '{}' not found", region_id)));
             }
         }
 
@@ -95,7 +199,9 @@ impl Action for LLMPromptGenerationAction {
         let (region_images, extracted_text) = match self.ocr_mode {
             crate::domain::OcrMode::None => {
                 // None mode: No OCR or vision, return error (LLM prompt generation requires at least vision mode)
-                return Err("LLM prompt generation requires ocr_mode to be 'local' or 'vision' (currently 'none')".to_string());
+                return Err(Error::Config(
+                    "LLM prompt generation requires ocr_mode to be 'local' or 'vision' (currently 'none')".to_string(),
+                ));
             }
             crate::domain::OcrMode::Local => {
                 // Local mode: Extract text from regions using OCR, send text-only to LLM
@@ -103,13 +209,13 @@ impl Action for LLMPromptGenerationAction {
                 {
                     use crate::domain::OCRCapture;
                     let ocr = crate::os::linux::LinuxOCR::new()
-                        .map_err(|e| format!("Failed to initialize OCR: {}", e.message))?;
+                        .map_err(|e| Error::Backend(e.context("Failed to initialize OCR")))?;
                     
                     let mut texts = Vec::new();
                     for region in &captured_regions {
                         let region_hash = self.capture.hash_region(region, 1);
                         let text = ocr.extract_text_cached(region, region_hash)
-                            .map_err(|e| format!("OCR extraction failed for '{}': {}", region.id, e.message))?;
+                            .map_err(|e| Error::Backend(e.context(&format!("OCR extraction failed for '{}'", region.id))))?;
                         texts.push(format!("Region '{}': {}", region.id, text));
                     }
                     
@@ -118,7 +224,7 @@ impl Action for LLMPromptGenerationAction {
                 }
                 #[cfg(not(feature = "ocr-integration"))]
                 {
-                    return Err("Local OCR mode requires 'ocr-integration' feature".to_string());
+                    return Err(Error::Config("Local OCR mode requires 'ocr-integration' feature".to_string()));
                 }
             }
             crate::domain::OcrMode::Vision => {
@@ -142,17 +248,40 @@ impl Action for LLMPromptGenerationAction {
         };
 
         // 5. Call LLM with regions and images/text
-        let llm_response = self.llm_client.generate_prompt(
-            &captured_regions,
-            region_images,
-            effective_system_prompt.as_deref(),
-            &risk_guidance,
-        )?;
+        let span = tracing::info_span!(
+            "llm_call",
+            regions = %self.region_ids.join(","),
+            success = tracing::field::Empty,
+            risk = tracing::field::Empty,
+            task_complete = tracing::field::Empty,
+            error = tracing::field::Empty
+        );
+        let llm_response = span.in_scope(|| {
+            self.llm_client.generate_prompt(
+                &captured_regions,
+                region_images,
+                effective_system_prompt.as_deref(),
+                &risk_guidance,
+            )
+        });
+        span.record("success", llm_response.is_ok());
+        match &llm_response {
+            Ok(response) => {
+                span.record("risk", response.continuation_prompt_risk);
+                span.record("task_complete", response.task_complete);
+            }
+            Err(e) => {
+                span.record("error", e.to_string());
+            }
+        }
+        drop(span);
+        let llm_response = llm_response?;
 
         // 5. Check if task is complete (new structured termination)
         if llm_response.task_complete {
             let reason = llm_response.task_complete_reason.clone()
                 .unwrap_or_else(|| "LLM signaled task complete".to_string());
+            context.set("task_complete_reason", reason.clone());
             context.request_termination(reason);
             
             // Still set variables for logging/inspection
@@ -166,50 +295,39 @@ impl Action for LLMPromptGenerationAction {
 
         // 6. Validate continuation prompt exists
         let continuation_prompt = llm_response.continuation_prompt.as_ref()
-            .ok_or("LLM did not provide continuation_prompt")?;
+            .ok_or_else(|| Error::Llm("LLM did not provide continuation_prompt".to_string()))?;
 
-        // 7. Validate risk threshold (use new continuation_prompt_risk)
+        // 7. Validate risk threshold (use new continuation_prompt_risk); a rejected risk is still reported
         let risk = llm_response.continuation_prompt_risk;
+        context.set(crate::domain::RISK_VARIABLE, risk.to_string());
+        context.set(crate::domain::PROMPT_VARIABLE, continuation_prompt.clone());
         if risk > self.risk_threshold {
-            // Play audible alarm
-            self.play_alarm();
-            return Err(format!(
+            // The runner's notification router sounds the risk alarm for this error
+            return Err(Error::Action(format!(
                 "Risk threshold exceeded: {} > {} (generated prompt: '{}')",
                 risk, self.risk_threshold, continuation_prompt
-            ));
+            )));
         }
 
         // 8. Validate prompt
         if continuation_prompt.is_empty() {
-            return Err("LLM returned empty continuation_prompt".to_string());
+            return Err(Error::Llm("LLM returned empty continuation_prompt".to_string()));
         }
         if continuation_prompt.len() > 200 {
-            return Err(format!(
+            return Err(Error::Llm(format!(
                 "LLM prompt too long: {} characters (max 200)",
                 continuation_prompt.len()
-            ));
+            )));
         }
 
         // 9. Set the variables in context
         context.set(&self.variable_name, continuation_prompt.clone());
-        context.set("continuation_prompt_risk", risk.to_string());
         context.set("task_complete", "false");
 
         Ok(())
     }
 }
 
-impl LLMPromptGenerationAction {
-    /// Play audible alarm when risk threshold is exceeded
-    fn play_alarm(&self) {
-        // In a real implementation, this would:
-        // - Use platform-specific audio API
-        // - Play a beep or alert sound
-        // For now, just print to stderr
-        eprintln!("⚠️  RISK THRESHOLD EXCEEDED - ALARM ⚠️");
-    }
-}
-
 /// Termination check action that evaluates conditions and requests termination
 pub struct TerminationCheckAction {
     pub check_type: String,
@@ -227,11 +345,27 @@ impl Action for TerminationCheckAction {
         "TerminationCheck"
     }
 
+    fn requires_network(&self) -> bool {
+        self.check_type == "ai_query"
+    }
+
+    fn uses_llm(&self) -> bool {
+        self.check_type == "ai_query"
+    }
+
+    fn debug_params(&self, _context: &crate::domain::ActionContext) -> serde_json::Value {
+        json!({
+            "check_type": self.check_type,
+            "context_vars": self.context_vars,
+            "termination_condition": self.termination_condition,
+        })
+    }
+
     fn execute(
         &self,
         _automation: &dyn crate::domain::Automation,
         context: &mut crate::domain::ActionContext,
-    ) -> Result<(), String> {
+    ) -> Result<(), Error> {
         use regex::Regex;
         
         let condition_met = match self.check_type.as_str() {
@@ -246,7 +380,7 @@ impl Action for TerminationCheckAction {
                 
                 // Check if termination_condition regex matches any value
                 let pattern = Regex::new(&self.termination_condition)
-                    .map_err(|e| format!("Invalid termination condition regex: {}", e))?;
+                    .map_err(|e| Error::Config(format!("Invalid termination condition regex: {}", e)))?;
                 
                 values.iter().any(|v| pattern.is_match(v))
             }
@@ -256,10 +390,10 @@ impl Action for TerminationCheckAction {
                 {
                     use crate::domain::OCRCapture;
                     let ocr = crate::os::linux::LinuxOCR::new()
-                        .map_err(|e| format!("Failed to initialize OCR: {}", e.message))?;
+                        .map_err(|e| Error::Backend(e.context("Failed to initialize OCR")))?;
                     
                     let pattern = Regex::new(&self.termination_condition)
-                        .map_err(|e| format!("Invalid termination condition regex: {}", e))?;
+                        .map_err(|e| Error::Config(format!("Invalid termination condition regex: {}", e)))?;
                     
                     let mut found = false;
                     for region_id in &self.ocr_region_ids {
@@ -277,13 +411,13 @@ impl Action for TerminationCheckAction {
                 }
                 #[cfg(not(feature = "ocr-integration"))]
                 {
-                    return Err("OCR termination check requires 'ocr-integration' feature".to_string());
+                    return Err(Error::Config("OCR termination check requires 'ocr-integration' feature".to_string()));
                 }
             }
             "ai_query" => {
                 // Call LLM with custom query and check task_complete
                 let query_prompt = self.ai_query_prompt.as_deref()
-                    .ok_or("ai_query_prompt required for ai_query check_type")?;
+                    .ok_or_else(|| Error::Config("ai_query_prompt required for ai_query check_type".to_string()))?;
                 
                 // Collect all regions for LLM
                 let mut captured_regions = Vec::new();
@@ -306,7 +440,7 @@ impl Action for TerminationCheckAction {
                 llm_response.task_complete
             }
             _ => {
-                return Err(format!("Unknown check_type: {}", self.check_type));
+                return Err(Error::Config(format!("Unknown check_type: {}", self.check_type)));
             }
         };
         
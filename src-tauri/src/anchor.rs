@@ -0,0 +1,418 @@
+/// Regions positioned relative to a template match
+///
+/// An anchored region keeps the size of its rectangle, but its position follows
+/// a small template image (a label, an icon) wherever that is on screen now.
+/// `AnchoredCapture` finds the template in the anchor's search area, moves the
+/// region to the match plus the anchor's offset, and captures there, so regions
+/// survive scrolling and layout shifts that break absolute coordinates.
+///
+/// Matching is zero-mean normalized cross-correlation of grayscale pixels, which
+/// ignores uniform brightness changes. The search runs on a shrunken copy of
+/// screen and template first and is refined at full resolution around the best
+/// hits. The last match of each region is checked first; while the template is
+/// still there, the search is skipped.
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use base64::engine::general_purpose::STANDARD as Base64Standard;
+use base64::Engine as _;
+use image::{ImageOutputFormat, RgbaImage};
+use tracing::debug;
+
+use crate::domain::{BackendError, DisplayInfo, Rect, Region, RegionAnchor, ScreenCapture, ScreenFrame};
+use crate::region_picker;
+
+/// Short edge of the template in the coarse search
+const COARSE_EDGE: u32 = 8;
+/// Coarse hits refined at full resolution
+const CANDIDATES: usize = 4;
+/// Pixel variance below which an area counts as flat and matches nothing
+const MIN_VARIANCE: f64 = 1.0;
+
+/// Grayscale pixels, row by row
+#[derive(Clone)]
+struct Gray {
+    width: u32,
+    height: u32,
+    pixels: Vec<f32>,
+}
+
+impl Gray {
+    fn from_rgba(bytes: &[u8], width: u32, height: u32, stride: u32) -> Self {
+        let mut pixels = Vec::with_capacity((width * height) as usize);
+        for row in 0..height as usize {
+            let line = &bytes[row * stride as usize..];
+            for px in line.chunks_exact(4).take(width as usize) {
+                pixels.push(0.299 * px[0] as f32 + 0.587 * px[1] as f32 + 0.114 * px[2] as f32);
+            }
+        }
+        Self { width, height, pixels }
+    }
+
+    fn from_frame(frame: &ScreenFrame) -> Self {
+        Self::from_rgba(&frame.bytes, frame.width, frame.height, frame.stride)
+    }
+
+    /// Average of every `factor`×`factor` block; a remainder at the edges is dropped
+    fn shrink(&self, factor: u32) -> Self {
+        if factor <= 1 {
+            return self.clone();
+        }
+        let (width, height) = (self.width / factor, self.height / factor);
+        let mut pixels = vec![0.0; (width * height) as usize];
+        for y in 0..height * factor {
+            for x in 0..width * factor {
+                pixels[((y / factor) * width + x / factor) as usize] += self.pixels[(y * self.width + x) as usize];
+            }
+        }
+        let area = (factor * factor) as f32;
+        pixels.iter_mut().for_each(|p| *p /= area);
+        Self { width, height, pixels }
+    }
+}
+
+/// Template pixels with their mean removed, ready for correlation
+struct Pattern {
+    width: u32,
+    height: u32,
+    pixels: Vec<f32>,
+    norm: f64,
+}
+
+impl Pattern {
+    fn new(gray: &Gray) -> Self {
+        let n = gray.pixels.len().max(1) as f64;
+        let mean = gray.pixels.iter().map(|&p| p as f64).sum::<f64>() / n;
+        let pixels: Vec<f32> = gray.pixels.iter().map(|&p| (p as f64 - mean) as f32).collect();
+        let norm = pixels.iter().map(|&p| (p as f64).powi(2)).sum::<f64>().sqrt();
+        Self {
+            width: gray.width,
+            height: gray.height,
+            pixels,
+            norm,
+        }
+    }
+
+    fn is_flat(&self) -> bool {
+        self.norm * self.norm < MIN_VARIANCE * self.pixels.len() as f64
+    }
+
+    /// Correlation with `image` at (x, y), from -1 to 1
+    fn score(&self, image: &Gray, x: u32, y: u32) -> f64 {
+        let w = self.width as usize;
+        let (mut sum, mut sum_sq, mut cross) = (0.0f64, 0.0f64, 0.0f64);
+        for row in 0..self.height {
+            let start = ((y + row) * image.width + x) as usize;
+            let line = &image.pixels[start..start + w];
+            let pattern = &self.pixels[row as usize * w..(row as usize + 1) * w];
+            for (&i, &t) in line.iter().zip(pattern) {
+                sum += i as f64;
+                sum_sq += (i as f64).powi(2);
+                cross += (i * t) as f64;
+            }
+        }
+        let n = self.pixels.len() as f64;
+        let variance = sum_sq - sum * sum / n;
+        if variance < MIN_VARIANCE * n {
+            return 0.0;
+        }
+        cross / (self.norm * variance.sqrt())
+    }
+}
+
+/// An anchor's template, decoded once
+pub struct Template {
+    full: Pattern,
+    coarse: Pattern,
+    factor: u32,
+}
+
+/// Where a template matched: top-left corner in the searched image, and the score
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Match {
+    pub x: u32,
+    pub y: u32,
+    pub score: f64,
+}
+
+impl Template {
+    /// Decode a base64 PNG; flat images are refused, since they match any flat area
+    pub fn decode(png_base64: &str) -> Result<Self, String> {
+        let bytes = Base64Standard
+            .decode(png_base64.trim())
+            .map_err(|e| format!("Anchor template is not base64: {}", e))?;
+        let image = image::load_from_memory(&bytes)
+            .map_err(|e| format!("Anchor template is not an image: {}", e))?
+            .to_rgba8();
+        let (width, height) = image.dimensions();
+        if width == 0 || height == 0 {
+            return Err("Anchor template is empty".into());
+        }
+        let gray = Gray::from_rgba(image.as_raw(), width, height, width * 4);
+        let full = Pattern::new(&gray);
+        if full.is_flat() {
+            return Err("Anchor template is a flat color; pick an area with text or edges".into());
+        }
+        let factor = (width.min(height) / COARSE_EDGE).max(1);
+        let coarse = Pattern::new(&gray.shrink(factor));
+        // Thin strokes can average out when shrunk; search at full resolution then
+        let (coarse, factor) = if coarse.is_flat() { (Pattern::new(&gray), 1) } else { (coarse, factor) };
+        Ok(Self { full, coarse, factor })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.full.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.full.height
+    }
+
+    /// Score of the template against the top-left corner of `frame`
+    pub fn score_at_origin(&self, frame: &ScreenFrame) -> f64 {
+        if frame.width < self.width() || frame.height < self.height() {
+            return 0.0;
+        }
+        self.full.score(&Gray::from_frame(frame), 0, 0)
+    }
+
+    /// Best match of the template anywhere in `frame`; `None` when the frame is smaller
+    pub fn find(&self, frame: &ScreenFrame) -> Option<Match> {
+        if frame.width < self.width() || frame.height < self.height() {
+            return None;
+        }
+        let image = Gray::from_frame(frame);
+        let small = image.shrink(self.factor);
+        let mut hits: Vec<(f64, u32, u32)> = Vec::with_capacity(CANDIDATES + 1);
+        for y in 0..=small.height.saturating_sub(self.coarse.height) {
+            for x in 0..=small.width.saturating_sub(self.coarse.width) {
+                let score = self.coarse.score(&small, x, y);
+                if hits.len() < CANDIDATES || score > hits[hits.len() - 1].0 {
+                    let at = hits.iter().position(|h| h.0 < score).unwrap_or(hits.len());
+                    hits.insert(at, (score, x, y));
+                    hits.truncate(CANDIDATES);
+                }
+            }
+        }
+
+        let f = self.factor;
+        let (max_x, max_y) = (image.width - self.width(), image.height - self.height());
+        let mut best: Option<Match> = None;
+        for &(_, cx, cy) in &hits {
+            for y in (cy * f).saturating_sub(f)..=(cy * f + f).min(max_y) {
+                for x in (cx * f).saturating_sub(f)..=(cx * f + f).min(max_x) {
+                    let score = self.full.score(&image, x, y);
+                    if !best.is_some_and(|b| b.score >= score) {
+                        best = Some(Match { x, y, score });
+                    }
+                }
+            }
+        }
+        best
+    }
+}
+
+struct Entry {
+    template_png: String,
+    template: Arc<Template>,
+    last: Option<(u32, u32)>,
+}
+
+/// Decoded templates and the last match position, by region id
+#[derive(Default)]
+pub struct AnchorCache {
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+impl AnchorCache {
+    fn template(&self, region_id: &str, template_png: &str) -> Result<Arc<Template>, String> {
+        let mut entries = self.entries.lock().unwrap();
+        if let Some(entry) = entries.get(region_id).filter(|e| e.template_png == template_png) {
+            return Ok(entry.template.clone());
+        }
+        let template = Arc::new(Template::decode(template_png)?);
+        entries.insert(
+            region_id.to_string(),
+            Entry {
+                template_png: template_png.to_string(),
+                template: template.clone(),
+                last: None,
+            },
+        );
+        Ok(template)
+    }
+
+    fn last(&self, region_id: &str) -> Option<(u32, u32)> {
+        self.entries.lock().unwrap().get(region_id).and_then(|e| e.last)
+    }
+
+    fn remember(&self, region_id: &str, at: Option<(u32, u32)>) {
+        if let Some(entry) = self.entries.lock().unwrap().get_mut(region_id) {
+            entry.last = at;
+        }
+    }
+}
+
+/// Cache shared by every capture, so previews, runs and OCR reuse each other's matches
+pub fn cache() -> Arc<AnchorCache> {
+    static CACHE: OnceLock<Arc<AnchorCache>> = OnceLock::new();
+    CACHE.get_or_init(Default::default).clone()
+}
+
+/// Captures anchored regions where their anchor is now; other regions pass through
+pub struct AnchoredCapture {
+    pub inner: Box<dyn ScreenCapture + Send + Sync>,
+    pub cache: Arc<AnchorCache>,
+}
+
+impl AnchoredCapture {
+    pub fn new(inner: Box<dyn ScreenCapture + Send + Sync>) -> Self {
+        Self { inner, cache: cache() }
+    }
+
+    /// `region` at its anchored position, without the anchor
+    pub fn resolve<'r>(&self, region: &'r Region) -> Result<Cow<'r, Region>, BackendError> {
+        let Some(anchor) = &region.anchor else {
+            return Ok(Cow::Borrowed(region));
+        };
+        let template = self
+            .cache
+            .template(&region.id, &anchor.template_png)
+            .map_err(|e| BackendError::new("invalid_anchor", e))?;
+        let held = self.cache.last(&region.id).filter(|&(x, y)| self.still_at(region, &template, x, y, anchor.threshold));
+        let (x, y) = match held {
+            Some(at) => at,
+            None => {
+                let found = self.search(region, anchor, &template);
+                self.cache.remember(&region.id, found.as_ref().ok().copied());
+                found?
+            }
+        };
+        let place = |at: u32, offset: i32| {
+            u32::try_from(at as i64 + offset as i64).map_err(|_| {
+                BackendError::new(
+                    "anchor_out_of_bounds",
+                    format!("Region '{}' would start off screen from its anchor", region.id),
+                )
+            })
+        };
+        Ok(Cow::Owned(Region {
+            id: region.id.clone(),
+            rect: Rect {
+                x: place(x, anchor.offset_x)?,
+                y: place(y, anchor.offset_y)?,
+                ..region.rect
+            },
+            name: region.name.clone(),
+            anchor: None,
+        }))
+    }
+
+    fn still_at(&self, region: &Region, template: &Template, x: u32, y: u32, threshold: f64) -> bool {
+        let probe = probe(region, Rect { x, y, width: template.width(), height: template.height() });
+        self.inner
+            .capture_region(&probe)
+            .is_ok_and(|frame| template.score_at_origin(&frame) >= threshold)
+    }
+
+    fn search(&self, region: &Region, anchor: &RegionAnchor, template: &Template) -> Result<(u32, u32), BackendError> {
+        let area = match anchor.search {
+            Some(area) => area,
+            None => region_picker::display_rect(&display_of(&region.rect, &self.inner.displays()?)?),
+        };
+        let frame = self.inner.capture_region(&probe(region, area))?;
+        match template.find(&frame) {
+            Some(m) if m.score >= anchor.threshold => Ok((area.x + m.x, area.y + m.y)),
+            best => Err(BackendError::new(
+                "anchor_not_found",
+                format!(
+                    "Anchor of region '{}' not found on screen (best match {:.2}, needs {:.2})",
+                    region.id,
+                    best.map_or(0.0, |m| m.score),
+                    anchor.threshold
+                ),
+            )),
+        }
+    }
+}
+
+fn probe(region: &Region, rect: Rect) -> Region {
+    Region {
+        id: format!("{}-anchor", region.id),
+        rect,
+        name: None,
+        anchor: None,
+    }
+}
+
+/// The display holding the centre of `rect`, else the primary one
+fn display_of(rect: &Rect, displays: &[DisplayInfo]) -> Result<DisplayInfo, BackendError> {
+    let (cx, cy) = ((rect.x + rect.width / 2) as i64, (rect.y + rect.height / 2) as i64);
+    displays
+        .iter()
+        .find(|d| {
+            cx >= d.x as i64 && cy >= d.y as i64 && cx < d.x as i64 + d.width as i64 && cy < d.y as i64 + d.height as i64
+        })
+        .or_else(|| displays.iter().find(|d| d.is_primary))
+        .or(displays.first())
+        .cloned()
+        .ok_or_else(|| BackendError::new("no_display", "No display to search for the anchor"))
+}
+
+impl ScreenCapture for AnchoredCapture {
+    fn hash_region(&self, region: &Region, downscale: u32) -> u64 {
+        match self.resolve(region) {
+            Ok(region) => self.inner.hash_region(&region, downscale),
+            // Like a failed capture: a missing anchor reads as a changed region
+            Err(e) => {
+                debug!("{}", e);
+                0
+            }
+        }
+    }
+
+    fn capture_region(&self, region: &Region) -> Result<ScreenFrame, BackendError> {
+        let region = self.resolve(region)?;
+        self.inner.capture_region(&region)
+    }
+
+    fn capture_and_hash(&self, region: &Region, downscale: u32) -> Result<(ScreenFrame, u64), BackendError> {
+        let region = self.resolve(region)?;
+        self.inner.capture_and_hash(&region, downscale)
+    }
+
+    fn displays(&self) -> Result<Vec<DisplayInfo>, BackendError> {
+        self.inner.displays()
+    }
+}
+
+/// Anchor `region` to what is on screen at `anchor` now, keeping the region where it is
+pub fn create(region: Rect, anchor: Rect, capture: &dyn ScreenCapture) -> Result<RegionAnchor, BackendError> {
+    let frame = capture.capture_region(&Region {
+        id: "anchor-template".into(),
+        rect: anchor,
+        name: None,
+        anchor: None,
+    })?;
+    let mut bytes = Vec::with_capacity((frame.width * frame.height * 4) as usize);
+    for row in frame.bytes.chunks(frame.stride.max(1) as usize).take(frame.height as usize) {
+        bytes.extend_from_slice(&row[..(frame.width * 4) as usize]);
+    }
+    let image = RgbaImage::from_vec(frame.width, frame.height, bytes)
+        .ok_or_else(|| BackendError::new("capture_failed", "Captured frame has an unexpected size"))?;
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), ImageOutputFormat::Png)
+        .map_err(|e| BackendError::new("encode_failed", e.to_string()))?;
+    let template_png = Base64Standard.encode(png);
+    Template::decode(&template_png).map_err(|e| BackendError::new("invalid_anchor", e))?;
+    Ok(RegionAnchor {
+        template_png,
+        offset_x: (region.x as i64 - anchor.x as i64) as i32,
+        offset_y: (region.y as i64 - anchor.y as i64) as i32,
+        search: None,
+        threshold: crate::domain::DEFAULT_ANCHOR_THRESHOLD,
+    })
+}
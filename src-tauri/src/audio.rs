@@ -1,20 +1,230 @@
-/// Audio notification system for user intervention and profile completion alerts
+/// Audio notification system for runner alerts
 ///
 /// Provides trait-based abstraction for audio playback with rodio backend.
+/// Each alert event (intervention needed, profile ended, risk exceeded, ...) is
+/// mapped to one of the sounds, or to silence; the mapping is a setting.
+/// Both sounds ship as embedded WAV files; each can be replaced by a custom
+/// sound file, which falls back to the built-in sound if it cannot be played.
+/// When neither can be played, or the build leaves out `bundled-sounds`, a
+/// synthesized tone pattern distinct for each event plays instead, so an
+/// alert is never dropped silently.
+/// Sounds are queued on one long-lived output stream shared by all notifiers,
+/// reopened when the default device changes or the stream breaks.
 
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use crate::error::Error;
+
+#[cfg(feature = "bundled-sounds")]
+const INTERVENTION_WAV: &[u8] = include_bytes!("../sounds/intervention.wav");
+#[cfg(feature = "bundled-sounds")]
+const COMPLETION_WAV: &[u8] = include_bytes!("../sounds/completion.wav");
+
+/// Sample rate of synthesized tones
+pub const TONE_SAMPLE_RATE: u32 = 44_100;
+
+/// File extensions accepted for custom sounds
+pub const SUPPORTED_SOUND_EXTENSIONS: &[&str] = &["wav", "ogg", "mp3", "flac"];
+
+/// The sounds an alert can play
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertSound {
+    Intervention,
+    Completion,
+}
+
+impl AlertSound {
+    /// Sound shipped with the app; builds without `bundled-sounds` have none
+    #[cfg(feature = "bundled-sounds")]
+    pub fn builtin(self) -> Option<&'static [u8]> {
+        match self {
+            AlertSound::Intervention => Some(INTERVENTION_WAV),
+            AlertSound::Completion => Some(COMPLETION_WAV),
+        }
+    }
+
+    #[cfg(not(feature = "bundled-sounds"))]
+    pub fn builtin(self) -> Option<&'static [u8]> {
+        None
+    }
+
+    /// Event whose tone pattern stands in for this sound when previewing it
+    #[cfg_attr(not(feature = "audio-notifications"), allow(dead_code))]
+    pub fn tone_event(self) -> AlertEvent {
+        match self {
+            AlertSound::Intervention => AlertEvent::InterventionNeeded,
+            AlertSound::Completion => AlertEvent::ProfileEnded,
+        }
+    }
+}
+
+/// Runner situations that can play a sound
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertEvent {
+    RunStarted,
+    /// The run is blocked and waiting for the user
+    InterventionNeeded,
+    /// The run completed
+    ProfileEnded,
+    /// An LLM prompt was rejected for exceeding the risk threshold
+    RiskExceeded,
+    /// A per-profile budget ended the run
+    BudgetExceeded,
+    /// A failure check or watchdog ended the run
+    Error,
+    /// Step-through debugging is holding before an action
+    ApprovalRequested,
+}
+
+impl AlertEvent {
+    /// Sound played when the map does not list the event; approval requests are silent
+    pub fn default_sound(self) -> Option<AlertSound> {
+        match self {
+            AlertEvent::RunStarted | AlertEvent::ProfileEnded => Some(AlertSound::Completion),
+            AlertEvent::InterventionNeeded
+            | AlertEvent::RiskExceeded
+            | AlertEvent::BudgetExceeded
+            | AlertEvent::Error => Some(AlertSound::Intervention),
+            AlertEvent::ApprovalRequested => None,
+        }
+    }
+
+    /// Notes of the synthesized fallback; each event has its own pattern
+    pub fn tone_pattern(self) -> &'static [Tone] {
+        const fn note(freq_hz: f32, ms: u32) -> Tone {
+            Tone { freq_hz, ms }
+        }
+        const REST: Tone = note(0.0, 60);
+        // Rising pair
+        const STARTED: &[Tone] = &[note(660.0, 120), note(880.0, 160)];
+        // Three even beeps
+        const INTERVENTION: &[Tone] = &[note(880.0, 150), REST, note(880.0, 150), REST, note(880.0, 150)];
+        // Major arpeggio
+        const ENDED: &[Tone] = &[note(523.3, 130), note(659.3, 130), note(784.0, 260)];
+        // Fast two-tone siren
+        const RISK: &[Tone] = &[
+            note(988.0, 110),
+            note(784.0, 110),
+            note(988.0, 110),
+            note(784.0, 110),
+            note(988.0, 110),
+            note(784.0, 110),
+        ];
+        // Falling triad
+        const BUDGET: &[Tone] = &[note(659.3, 150), note(523.3, 150), note(440.0, 300)];
+        // Two long low buzzes
+        const ERROR: &[Tone] = &[note(330.0, 300), REST, note(330.0, 300)];
+        // Single high ping
+        const APPROVAL: &[Tone] = &[note(1046.5, 120)];
+        match self {
+            AlertEvent::RunStarted => STARTED,
+            AlertEvent::InterventionNeeded => INTERVENTION,
+            AlertEvent::ProfileEnded => ENDED,
+            AlertEvent::RiskExceeded => RISK,
+            AlertEvent::BudgetExceeded => BUDGET,
+            AlertEvent::Error => ERROR,
+            AlertEvent::ApprovalRequested => APPROVAL,
+        }
+    }
+}
+
+/// One note of a synthesized alert; a zero frequency is a rest
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tone {
+    pub freq_hz: f32,
+    pub ms: u32,
+}
+
+/// Attack, decay and release in ms, and the sustain level, shaping every note
+const ENVELOPE: (f32, f32, f32, f32) = (8.0, 40.0, 0.6, 30.0);
+
+/// Mono samples at `TONE_SAMPLE_RATE` for `pattern`, each note a sine under an ADSR envelope
+pub fn synthesize(pattern: &[Tone]) -> Vec<f32> {
+    let (attack, decay, sustain, release) = ENVELOPE;
+    let per_ms = TONE_SAMPLE_RATE as f32 / 1000.0;
+    let mut samples = Vec::new();
+    for tone in pattern {
+        let len = (tone.ms as f32 * per_ms) as usize;
+        for i in 0..len {
+            if tone.freq_hz <= 0.0 {
+                samples.push(0.0);
+                continue;
+            }
+            let t_ms = i as f32 / per_ms;
+            let left_ms = (len - i) as f32 / per_ms;
+            let level = if t_ms < attack {
+                t_ms / attack
+            } else if t_ms < attack + decay {
+                1.0 - (1.0 - sustain) * (t_ms - attack) / decay
+            } else {
+                sustain
+            };
+            let level = level * (left_ms / release).min(1.0);
+            let phase = 2.0 * std::f32::consts::PI * tone.freq_hz * i as f32 / TONE_SAMPLE_RATE as f32;
+            samples.push(phase.sin() * level);
+        }
+    }
+    samples
+}
+
+/// Sound per event; `None` silences the event, unlisted events use their default
+pub type SoundMap = BTreeMap<AlertEvent, Option<AlertSound>>;
+
+pub fn sound_for(map: &SoundMap, event: AlertEvent) -> Option<AlertSound> {
+    map.get(&event).copied().unwrap_or_else(|| event.default_sound())
+}
+
+/// Bytes to play for `sound`: the custom file when set and readable, else the
+/// built-in sound; None when neither exists and the tone must stand in
+pub fn load_sound(sound: AlertSound, custom: Option<&Path>) -> Option<Cow<'static, [u8]>> {
+    custom
+        .and_then(|path| std::fs::read(path).ok())
+        .map(Cow::Owned)
+        .or_else(|| sound.builtin().map(Cow::Borrowed))
+}
+
+/// Check that `path` is a readable sound file in a supported format
+pub fn check_sound_file(path: &Path) -> Result<(), String> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+    if !SUPPORTED_SOUND_EXTENSIONS.contains(&extension.as_str()) {
+        return Err(format!(
+            "Unsupported sound file '{}'; use {}",
+            path.display(),
+            SUPPORTED_SOUND_EXTENSIONS.join(", ")
+        ));
+    }
+    let bytes = std::fs::read(path).map_err(|e| format!("Failed to read sound file '{}': {}", path.display(), e))?;
+    #[cfg(feature = "audio-notifications")]
+    rodio::Decoder::new(std::io::Cursor::new(bytes))
+        .map_err(|e| format!("Cannot decode sound file '{}': {}", path.display(), e))?;
+    #[cfg(not(feature = "audio-notifications"))]
+    drop(bytes);
+    Ok(())
+}
 
 /// Trait for audio notification playback
 pub trait AudioNotifier: Send + Sync {
-    /// Play intervention needed sound (watchdog alert)
-    fn play_intervention_needed(&self) -> Result<(), String>;
-    
-    /// Play profile ended sound (task completion)
-    fn play_profile_ended(&self) -> Result<(), String>;
+    /// Play the sound mapped to `event`; a silenced event plays nothing
+    fn play(&self, event: AlertEvent) -> Result<(), Error>;
+
+    /// Play `sound` regardless of the event map (settings previews)
+    fn play_sound(&self, sound: AlertSound) -> Result<(), Error>;
+
+    /// Replace the event-to-sound map
+    fn set_sound_map(&self, map: SoundMap);
     
     /// Set volume (0.0 to 1.0)
     #[allow(dead_code)]
-    fn set_volume(&self, volume: f32) -> Result<(), String>;
+    fn set_volume(&self, volume: f32) -> Result<(), Error>;
     
     /// Enable or disable audio notifications
     #[allow(dead_code)]
@@ -22,6 +232,9 @@ pub trait AudioNotifier: Send + Sync {
     
     /// Check if audio is enabled
     fn is_enabled(&self) -> bool;
+
+    /// Play `path` instead of the built-in sound for `sound`; None restores the built-in sound
+    fn set_custom_sound(&self, sound: AlertSound, path: Option<PathBuf>);
 }
 
 /// Mock audio notifier for testing
@@ -29,6 +242,9 @@ pub trait AudioNotifier: Send + Sync {
 pub struct MockAudioNotifier {
     enabled: Arc<Mutex<bool>>,
     volume: Arc<Mutex<f32>>,
+    custom: Mutex<HashMap<AlertSound, PathBuf>>,
+    sound_map: Mutex<SoundMap>,
+    played: Mutex<Vec<AlertSound>>,
 }
 
 impl MockAudioNotifier {
@@ -37,30 +253,49 @@ impl MockAudioNotifier {
         Self {
             enabled: Arc::new(Mutex::new(true)),
             volume: Arc::new(Mutex::new(0.5)),
+            custom: Mutex::new(HashMap::new()),
+            sound_map: Mutex::new(SoundMap::new()),
+            played: Mutex::new(Vec::new()),
         }
     }
+
+    /// Sounds played so far, in order
+    #[allow(dead_code)]
+    pub fn played(&self) -> Vec<AlertSound> {
+        self.played.lock().unwrap().clone()
+    }
+
+    /// Bytes the notifier would play for `sound`; None means the synthesized tone
+    #[allow(dead_code)]
+    pub fn sound_bytes(&self, sound: AlertSound) -> Option<Vec<u8>> {
+        load_sound(sound, self.custom.lock().unwrap().get(&sound).map(PathBuf::as_path)).map(Cow::into_owned)
+    }
 }
 
 impl AudioNotifier for MockAudioNotifier {
-    fn play_intervention_needed(&self) -> Result<(), String> {
-        if *self.enabled.lock().unwrap() {
-            Ok(())
-        } else {
-            Err("Audio disabled".to_string())
+    fn play(&self, event: AlertEvent) -> Result<(), Error> {
+        match sound_for(&self.sound_map.lock().unwrap(), event) {
+            Some(sound) => self.play_sound(sound),
+            None => Ok(()),
         }
     }
-    
-    fn play_profile_ended(&self) -> Result<(), String> {
+
+    fn play_sound(&self, sound: AlertSound) -> Result<(), Error> {
         if *self.enabled.lock().unwrap() {
+            self.played.lock().unwrap().push(sound);
             Ok(())
         } else {
-            Err("Audio disabled".to_string())
+            Err(Error::Audio("Audio disabled".to_string()))
         }
     }
+
+    fn set_sound_map(&self, map: SoundMap) {
+        *self.sound_map.lock().unwrap() = map;
+    }
     
-    fn set_volume(&self, volume: f32) -> Result<(), String> {
+    fn set_volume(&self, volume: f32) -> Result<(), Error> {
         if !(0.0..=1.0).contains(&volume) {
-            return Err("Volume must be between 0.0 and 1.0".to_string());
+            return Err(Error::Config("Volume must be between 0.0 and 1.0".to_string()));
         }
         *self.volume.lock().unwrap() = volume;
         Ok(())
@@ -73,83 +308,248 @@ impl AudioNotifier for MockAudioNotifier {
     fn is_enabled(&self) -> bool {
         *self.enabled.lock().unwrap()
     }
+
+    fn set_custom_sound(&self, sound: AlertSound, path: Option<PathBuf>) {
+        let mut custom = self.custom.lock().unwrap();
+        match path {
+            Some(path) => custom.insert(sound, path),
+            None => custom.remove(&sound),
+        };
+    }
+}
+
+/// An open audio output as the playback thread sees it
+#[cfg_attr(not(feature = "audio-notifications"), allow(dead_code))]
+trait SoundOutput {
+    type Source;
+    /// Queue `source`; a broken stream hands it back with the reason
+    fn play(&mut self, source: Self::Source) -> Result<(), (Self::Source, String)>;
+    /// Whether this output is still on the default device
+    fn is_current(&self) -> bool;
+}
+
+/// Queue `source` on `output`, opening one if needed. An output on a device that
+/// is no longer the default, or one that fails to open or play, is dropped and
+/// opened again once.
+#[cfg_attr(not(feature = "audio-notifications"), allow(dead_code))]
+fn play_on<O: SoundOutput>(
+    output: &mut Option<O>,
+    open: impl Fn() -> Result<O, String>,
+    mut source: O::Source,
+) -> Result<(), String> {
+    if output.as_ref().is_some_and(|o| !o.is_current()) {
+        *output = None;
+    }
+    let mut error = String::new();
+    for _ in 0..2 {
+        let mut current = match output.take().map(Ok).unwrap_or_else(&open) {
+            Ok(current) => current,
+            Err(e) => {
+                error = e;
+                continue;
+            }
+        };
+        match current.play(source) {
+            Ok(()) => {
+                *output = Some(current);
+                return Ok(());
+            }
+            Err((returned, e)) => {
+                tracing::warn!("Audio output broken, reopening it: {}", e);
+                source = returned;
+                error = e;
+            }
+        }
+    }
+    Err(error)
 }
 
 #[cfg(feature = "audio-notifications")]
 mod rodio_impl {
     use super::*;
-    use rodio::{OutputStream, Sink, Source};
-    use std::time::Duration;
-    
+    use rodio::cpal::traits::HostTrait;
+    use rodio::buffer::SamplesBuffer;
+    use rodio::{Decoder, DeviceTrait, OutputStream, OutputStreamHandle, Sink, Source};
+    use std::io::Cursor;
+    use std::sync::mpsc::{self, Sender};
+
+    type SoundSource = Box<dyn Source<Item = f32> + Send>;
+
+    /// Sound to queue and where to report whether it could be queued
+    struct PlayRequest {
+        source: SoundSource,
+        reply: Sender<Result<(), String>>,
+    }
+
+    /// Output stream and sink kept open between sounds
+    ///
+    /// `OutputStream` must stay on the thread that created it, so one playback
+    /// thread owns it and all notifiers queue sounds through a channel.
+    struct Output {
+        _stream: OutputStream,
+        handle: OutputStreamHandle,
+        sink: Sink,
+        device: Option<String>,
+    }
+
+    impl SoundOutput for Output {
+        type Source = SoundSource;
+
+        /// Sounds queue on the sink while it plays; once it is idle a new sink is
+        /// created, which fails when the stream behind it is gone
+        fn play(&mut self, source: SoundSource) -> Result<(), (SoundSource, String)> {
+            if self.sink.empty() {
+                match Sink::try_new(&self.handle) {
+                    Ok(sink) => self.sink = sink,
+                    Err(e) => return Err((source, format!("Failed to create audio sink: {}", e))),
+                }
+            }
+            self.sink.append(source);
+            Ok(())
+        }
+
+        fn is_current(&self) -> bool {
+            self.device == default_device_name()
+        }
+    }
+
+    fn default_device_name() -> Option<String> {
+        rodio::cpal::default_host().default_output_device()?.name().ok()
+    }
+
+    fn open_output() -> Result<Output, String> {
+        let (stream, handle) =
+            OutputStream::try_default().map_err(|e| format!("Failed to initialize audio output: {}", e))?;
+        let sink = Sink::try_new(&handle).map_err(|e| format!("Failed to create audio sink: {}", e))?;
+        Ok(Output {
+            _stream: stream,
+            handle,
+            sink,
+            device: default_device_name(),
+        })
+    }
+
+    /// Playback thread: opens the output on first use and reopens it when the default device changes or the stream breaks
+    fn run_player(requests: mpsc::Receiver<PlayRequest>) {
+        let mut output: Option<Output> = None;
+        for request in requests {
+            let _ = request.reply.send(play_on(&mut output, open_output, request.source));
+        }
+    }
+
+    /// Channel to the shared playback thread, started lazily and restarted if it died
+    static PLAYER: Mutex<Option<Sender<PlayRequest>>> = Mutex::new(None);
+
+    fn queue(source: SoundSource) -> Result<(), String> {
+        let (reply, result) = mpsc::channel();
+        let mut request = PlayRequest { source, reply };
+        let mut player = PLAYER.lock().unwrap();
+        for _ in 0..2 {
+            let sender = player.get_or_insert_with(|| {
+                let (tx, rx) = mpsc::channel();
+                std::thread::spawn(move || run_player(rx));
+                tx
+            });
+            match sender.send(request) {
+                Ok(()) => {
+                    drop(player);
+                    return result.recv().map_err(|_| "Audio playback thread stopped".to_string())?;
+                }
+                Err(mpsc::SendError(returned)) => {
+                    request = returned;
+                    *player = None;
+                }
+            }
+        }
+        Err("Audio playback thread unavailable".to_string())
+    }
+
     /// Rodio-based audio notifier
     pub struct RodioAudioNotifier {
         enabled: Arc<Mutex<bool>>,
         volume: Arc<Mutex<f32>>,
+        custom: Mutex<HashMap<AlertSound, PathBuf>>,
+        sound_map: Mutex<SoundMap>,
     }
-    
+
     impl RodioAudioNotifier {
         /// Create new audio notifier
         pub fn new() -> Result<Self, String> {
             Ok(Self {
                 enabled: Arc::new(Mutex::new(true)),
                 volume: Arc::new(Mutex::new(0.5)),
+                custom: Mutex::new(HashMap::new()),
+                sound_map: Mutex::new(SoundMap::new()),
             })
         }
-        
-        fn play_tone(&self, frequency: f32, duration_ms: u64, description: &str) -> Result<(), String> {
+
+        /// Decode the custom sound, then the built-in one, then fall back to the tone pattern of `event`
+        fn decode(&self, sound: AlertSound, event: AlertEvent) -> SoundSource {
+            let custom = self.custom.lock().unwrap().get(&sound).cloned();
+            if let Some(path) = custom {
+                match Decoder::new(Cursor::new(load_sound(sound, Some(&path)).unwrap_or_default())) {
+                    Ok(decoder) => return Box::new(decoder.convert_samples()),
+                    Err(e) => tracing::warn!("Custom sound '{}' unplayable, using built-in: {}", path.display(), e),
+                }
+            }
+            if let Some(bytes) = sound.builtin() {
+                match Decoder::new(Cursor::new(bytes)) {
+                    Ok(decoder) => return Box::new(decoder.convert_samples()),
+                    Err(e) => tracing::warn!("Built-in {:?} sound unplayable, using tone: {}", sound, e),
+                }
+            }
+            Box::new(SamplesBuffer::new(1, TONE_SAMPLE_RATE, synthesize(event.tone_pattern())))
+        }
+
+        /// Queue the sound on the shared output; returns once queued, not when it finishes
+        fn queue_sound(&self, sound: AlertSound, event: AlertEvent) -> Result<(), Error> {
             if !self.is_enabled() {
                 return Ok(()); // Silently skip if disabled
             }
-            
-            let volume = *self.volume.lock().unwrap();
-            
-            // Create audio output stream
-            let (_stream, stream_handle) = OutputStream::try_default()
-                .map_err(|e| format!("Failed to initialize audio output for {}: {}", description, e))?;
-            
-            // Create sink for playback
-            let sink = Sink::try_new(&stream_handle)
-                .map_err(|e| format!("Failed to create audio sink for {}: {}", description, e))?;
-            
-            // Use rodio's built-in sine wave source
-            let source = rodio::source::SineWave::new(frequency)
-                .take_duration(Duration::from_millis(duration_ms))
-                .amplify(volume);
-            
-            // Play and wait for completion
-            sink.append(source);
-            sink.sleep_until_end();
-            
-            Ok(())
+            let source = self.decode(sound, event).amplify(*self.volume.lock().unwrap());
+            queue(Box::new(source)).map_err(|e| Error::Audio(format!("{} ({:?} sound)", e, sound)))
         }
     }
-    
+
     impl AudioNotifier for RodioAudioNotifier {
-        fn play_intervention_needed(&self) -> Result<(), String> {
-            // Alert tone: 880Hz (A5) for 200ms - higher pitch for urgency
-            self.play_tone(880.0, 200, "intervention")
+        fn play(&self, event: AlertEvent) -> Result<(), Error> {
+            match sound_for(&self.sound_map.lock().unwrap(), event) {
+                Some(sound) => self.queue_sound(sound, event),
+                None => Ok(()),
+            }
         }
-        
-        fn play_profile_ended(&self) -> Result<(), String> {
-            // Completion tone: 440Hz (A4) for 300ms - lower, calmer tone
-            self.play_tone(440.0, 300, "completion")
+
+        fn play_sound(&self, sound: AlertSound) -> Result<(), Error> {
+            self.queue_sound(sound, sound.tone_event())
         }
-        
-        fn set_volume(&self, volume: f32) -> Result<(), String> {
+
+        fn set_sound_map(&self, map: SoundMap) {
+            *self.sound_map.lock().unwrap() = map;
+        }
+
+        fn set_volume(&self, volume: f32) -> Result<(), Error> {
             if !(0.0..=1.0).contains(&volume) {
-                return Err("Volume must be between 0.0 and 1.0".to_string());
+                return Err(Error::Config("Volume must be between 0.0 and 1.0".to_string()));
             }
             *self.volume.lock().unwrap() = volume;
             Ok(())
         }
-        
+
         fn set_enabled(&self, enabled: bool) {
             *self.enabled.lock().unwrap() = enabled;
         }
-        
+
         fn is_enabled(&self) -> bool {
             *self.enabled.lock().unwrap()
         }
+
+        fn set_custom_sound(&self, sound: AlertSound, path: Option<PathBuf>) {
+            let mut custom = self.custom.lock().unwrap();
+            match path {
+                Some(path) => custom.insert(sound, path),
+                None => custom.remove(&sound),
+            };
+        }
     }
 }
 
@@ -171,20 +571,192 @@ pub fn create_audio_notifier() -> Result<Box<dyn AudioNotifier>, String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    /// Output that records what it plays and fails once `broken` is set
+    struct FakeOutput {
+        id: usize,
+        broken: Arc<Mutex<bool>>,
+        played: Vec<&'static str>,
+    }
+
+    impl SoundOutput for FakeOutput {
+        type Source = &'static str;
+
+        fn play(&mut self, source: &'static str) -> Result<(), (&'static str, String)> {
+            if *self.broken.lock().unwrap() {
+                return Err((source, "stream closed".into()));
+            }
+            self.played.push(source);
+            Ok(())
+        }
+
+        fn is_current(&self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn a_broken_output_is_reopened_once_and_the_sound_still_plays() {
+        let opened = Mutex::new(0);
+        let broken = Arc::new(Mutex::new(false));
+        let open = || {
+            let mut opened = opened.lock().unwrap();
+            *opened += 1;
+            *broken.lock().unwrap() = false;
+            Ok(FakeOutput { id: *opened, broken: broken.clone(), played: Vec::new() })
+        };
+        let mut output = None;
+        play_on(&mut output, open, "first").unwrap();
+        *broken.lock().unwrap() = true;
+        play_on(&mut output, open, "second").unwrap();
+        let output = output.unwrap();
+        assert_eq!(output.id, 2, "the broken stream was replaced");
+        assert_eq!(output.played, ["second"]);
+    }
+
+    #[test]
+    fn playing_fails_when_the_reopened_output_is_broken_too() {
+        let broken = Arc::new(Mutex::new(true));
+        let opened = Mutex::new(0);
+        let open = || {
+            *opened.lock().unwrap() += 1;
+            Ok(FakeOutput { id: 0, broken: broken.clone(), played: Vec::new() })
+        };
+        let mut output = None;
+        assert_eq!(play_on(&mut output, open, "alert").unwrap_err(), "stream closed");
+        assert!(output.is_none());
+        assert_eq!(*opened.lock().unwrap(), 2, "retried once");
+
+        let err = play_on(&mut None::<FakeOutput>, || Err("No audio device".to_string()), "alert").unwrap_err();
+        assert_eq!(err, "No audio device");
+    }
     
     #[test]
     fn mock_audio_notifier_works() {
         let notifier = MockAudioNotifier::new();
         assert!(notifier.is_enabled());
         
-        assert!(notifier.play_intervention_needed().is_ok());
-        assert!(notifier.play_profile_ended().is_ok());
+        assert!(notifier.play(AlertEvent::InterventionNeeded).is_ok());
+        assert!(notifier.play(AlertEvent::ProfileEnded).is_ok());
         
         notifier.set_enabled(false);
         assert!(!notifier.is_enabled());
-        assert!(notifier.play_intervention_needed().is_err());
+        assert!(notifier.play(AlertEvent::InterventionNeeded).is_err());
+    }
+
+    #[test]
+    fn sound_map_overrides_defaults_and_silences_events() {
+        let notifier = MockAudioNotifier::new();
+        for event in [
+            AlertEvent::RunStarted,
+            AlertEvent::InterventionNeeded,
+            AlertEvent::ProfileEnded,
+            AlertEvent::RiskExceeded,
+            AlertEvent::BudgetExceeded,
+            AlertEvent::Error,
+            AlertEvent::ApprovalRequested,
+        ] {
+            notifier.play(event).unwrap();
+        }
+        assert_eq!(
+            notifier.played(),
+            vec![
+                AlertSound::Completion,
+                AlertSound::Intervention,
+                AlertSound::Completion,
+                AlertSound::Intervention,
+                AlertSound::Intervention,
+                AlertSound::Intervention,
+            ],
+            "approval requests are silent by default"
+        );
+
+        let map: SoundMap = serde_json::from_value(serde_json::json!({
+            "risk_exceeded": "completion",
+            "approval_requested": "intervention",
+            "error": null
+        }))
+        .unwrap();
+        notifier.set_sound_map(map);
+        assert_eq!(sound_for(&notifier.sound_map.lock().unwrap(), AlertEvent::ProfileEnded), Some(AlertSound::Completion));
+        notifier.play(AlertEvent::RiskExceeded).unwrap();
+        notifier.play(AlertEvent::ApprovalRequested).unwrap();
+        notifier.play(AlertEvent::Error).unwrap();
+        assert_eq!(notifier.played()[6..], [AlertSound::Completion, AlertSound::Intervention]);
     }
     
+    #[cfg(feature = "bundled-sounds")]
+    #[test]
+    fn builtin_sounds_are_embedded_wav_files() {
+        for sound in [AlertSound::Intervention, AlertSound::Completion] {
+            let bytes = sound.builtin().unwrap();
+            assert_eq!(&bytes[..4], b"RIFF");
+            assert_eq!(&bytes[8..12], b"WAVE");
+        }
+    }
+
+    #[cfg(not(feature = "bundled-sounds"))]
+    #[test]
+    fn minimal_build_has_no_builtin_sounds() {
+        assert_eq!(load_sound(AlertSound::Intervention, None), None, "the synthesized tone stands in");
+    }
+
+    #[test]
+    fn custom_sound_replaces_builtin_until_cleared() {
+        let dir = std::env::temp_dir().join(format!("loopautoma_sound_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let custom = dir.join("ding.wav");
+        let ding: &[u8] = include_bytes!("../sounds/completion.wav");
+        std::fs::write(&custom, ding).unwrap();
+        let builtin = || AlertSound::Intervention.builtin().map(<[u8]>::to_vec);
+
+        let notifier = MockAudioNotifier::new();
+        notifier.set_custom_sound(AlertSound::Intervention, Some(custom.clone()));
+        assert_eq!(notifier.sound_bytes(AlertSound::Intervention).as_deref(), Some(ding));
+        notifier.set_custom_sound(AlertSound::Intervention, None);
+        assert_eq!(notifier.sound_bytes(AlertSound::Intervention), builtin());
+
+        notifier.set_custom_sound(AlertSound::Intervention, Some(dir.join("missing.wav")));
+        assert_eq!(
+            notifier.sound_bytes(AlertSound::Intervention),
+            builtin(),
+            "an unreadable custom file falls back to the built-in sound"
+        );
+
+        assert!(check_sound_file(&custom).is_ok());
+        assert!(check_sound_file(&dir.join("missing.wav")).is_err());
+        let text = dir.join("notes.txt");
+        std::fs::write(&text, "not a sound").unwrap();
+        assert!(check_sound_file(&text).unwrap_err().contains("Unsupported"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn tones_fade_in_and_out_and_differ_per_event() {
+        let pattern = AlertEvent::Error.tone_pattern();
+        let samples = synthesize(pattern);
+        let total_ms: u32 = pattern.iter().map(|t| t.ms).sum();
+        assert_eq!(samples.len(), (total_ms * TONE_SAMPLE_RATE / 1000) as usize);
+        assert!(samples.iter().all(|s| s.abs() <= 1.0));
+        assert!(samples[0].abs() < 0.01 && samples[samples.len() - 1].abs() < 0.01, "no clicks at the edges");
+        assert!(samples.iter().any(|s| s.abs() > 0.5), "the note is audible");
+
+        let events = [
+            AlertEvent::RunStarted,
+            AlertEvent::InterventionNeeded,
+            AlertEvent::ProfileEnded,
+            AlertEvent::RiskExceeded,
+            AlertEvent::BudgetExceeded,
+            AlertEvent::Error,
+            AlertEvent::ApprovalRequested,
+        ];
+        for (i, a) in events.iter().enumerate() {
+            for b in &events[i + 1..] {
+                assert_ne!(a.tone_pattern(), b.tone_pattern(), "{:?} and {:?} sound alike", a, b);
+            }
+        }
+    }
+
     #[test]
     fn volume_bounds_enforced() {
         let notifier = MockAudioNotifier::new();
@@ -203,8 +775,8 @@ mod tests {
         let result = RodioAudioNotifier::new();
         if let Ok(notifier) = result {
             // Try to play sounds (will succeed if audio hardware available)
-            let _ = notifier.play_intervention_needed();
-            let _ = notifier.play_profile_ended();
+            let _ = notifier.play(AlertEvent::InterventionNeeded);
+            let _ = notifier.play(AlertEvent::ProfileEnded);
         }
     }
     
@@ -216,13 +788,14 @@ mod tests {
         println!("Testing audio playback...");
         let notifier = RodioAudioNotifier::new().expect("Failed to create audio notifier");
         
-        println!("Playing intervention sound (880 Hz)...");
-        notifier.play_intervention_needed().expect("Failed to play intervention sound");
+        println!("Playing intervention sound...");
+        notifier.play_sound(AlertSound::Intervention).expect("Failed to play intervention sound");
         
-        std::thread::sleep(std::time::Duration::from_millis(500));
-        
-        println!("Playing completion sound (440 Hz)...");
-        notifier.play_profile_ended().expect("Failed to play completion sound");
+        println!("Queueing completion sound...");
+        notifier.play_sound(AlertSound::Completion).expect("Failed to play completion sound");
+
+        // Sounds play on the shared output after play returns
+        std::thread::sleep(std::time::Duration::from_secs(2));
         
         println!("Audio test complete!");
     }
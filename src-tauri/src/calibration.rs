@@ -0,0 +1,260 @@
+/// Cursor calibration: where `move_cursor` really puts the pointer
+///
+/// `calibrate` moves the pointer to points spread over every display the
+/// capture backend reports, in the desktop coordinates regions and captures
+/// use, and reads back where it landed. A fit per axis turns the landings into
+/// a scale (display scaling applied twice or not at all) and a shift (a
+/// monitor offset). Its inverse, a `CursorTransform`, is saved as the
+/// `input.calibration` setting; `CalibratedAutomation` applies it to every
+/// later `move_cursor`.
+///
+/// Screen captures do not contain the pointer, so the landing point comes from
+/// the input backend (`Automation::cursor_position`). Backends that cannot
+/// report it, like ydotool on Wayland, cannot be calibrated.
+use std::sync::RwLock;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{Automation, BackendError, DisplayInfo, Error, MouseButton};
+use crate::region_picker::display_rect;
+
+/// Landings this close to their target need no correction
+pub const TOLERANCE_PX: f64 = 2.0;
+
+/// Where on each display the pointer is sent, as fractions of its size
+const POINTS: [(f64, f64); 5] = [(0.1, 0.1), (0.9, 0.1), (0.5, 0.5), (0.1, 0.9), (0.9, 0.9)];
+
+/// Correction for pointer moves: a target `(x, y)` is sent as `(x * scale + offset)` per axis
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CursorTransform {
+    pub scale_x: f64,
+    pub scale_y: f64,
+    pub offset_x: f64,
+    pub offset_y: f64,
+}
+
+impl CursorTransform {
+    /// Where to send the pointer so it lands at `(x, y)`
+    pub fn apply(&self, x: u32, y: u32) -> (u32, u32) {
+        let send = |v: u32, scale: f64, offset: f64| (v as f64 * scale + offset).round().clamp(0.0, u32::MAX as f64) as u32;
+        (send(x, self.scale_x, self.offset_x), send(y, self.scale_y, self.offset_y))
+    }
+
+    /// Rejects transforms no display setup produces
+    pub fn check(&self) -> Result<(), String> {
+        let values = [self.scale_x, self.scale_y, self.offset_x, self.offset_y];
+        if values.iter().any(|v| !v.is_finite()) {
+            return Err("Calibration values must be finite".into());
+        }
+        if [self.scale_x, self.scale_y].iter().any(|s| !(0.2..=5.0).contains(s)) {
+            return Err("Calibration scale must be between 0.2 and 5".into());
+        }
+        Ok(())
+    }
+}
+
+/// One calibration move: where the pointer was sent and where it was
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CalibrationSample {
+    pub display_id: u32,
+    pub target_x: u32,
+    pub target_y: u32,
+    pub landed_x: i32,
+    pub landed_y: i32,
+}
+
+/// What `calibrate` measured and the correction it derived
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CalibrationReport {
+    pub samples: Vec<CalibrationSample>,
+    /// Correction for later moves; `None` when the pointer landed where it was sent
+    pub transform: Option<CursorTransform>,
+    /// Largest distance in pixels between a corrected move and its target, as far as the fit predicts
+    pub residual_px: f64,
+    /// What the landings point to, e.g. display scaling, in words
+    pub findings: Vec<String>,
+}
+
+/// `landed = a * target + b` by least squares
+fn fit(pairs: &[(f64, f64)]) -> (f64, f64) {
+    let n = pairs.len() as f64;
+    let mean_t = pairs.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_l = pairs.iter().map(|p| p.1).sum::<f64>() / n;
+    let var = pairs.iter().map(|p| (p.0 - mean_t).powi(2)).sum::<f64>();
+    if var == 0.0 {
+        return (1.0, mean_l - mean_t);
+    }
+    let a = pairs.iter().map(|p| (p.0 - mean_t) * (p.1 - mean_l)).sum::<f64>() / var;
+    (a, mean_l - a * mean_t)
+}
+
+/// The transform and findings `samples` call for
+pub fn analyze(samples: Vec<CalibrationSample>, displays: &[DisplayInfo]) -> Result<CalibrationReport, BackendError> {
+    if samples.is_empty() {
+        return Err(BackendError::new("no_display", "No display to calibrate on"));
+    }
+    let error = |s: &CalibrationSample| (s.landed_x as f64 - s.target_x as f64).hypot(s.landed_y as f64 - s.target_y as f64);
+    if samples.iter().all(|s| error(s) <= TOLERANCE_PX) {
+        return Ok(CalibrationReport {
+            findings: vec!["The pointer landed where it was sent on every display".into()],
+            samples,
+            transform: None,
+            residual_px: 0.0,
+        });
+    }
+    let xs: Vec<(f64, f64)> = samples.iter().map(|s| (s.target_x as f64, s.landed_x as f64)).collect();
+    let ys: Vec<(f64, f64)> = samples.iter().map(|s| (s.target_y as f64, s.landed_y as f64)).collect();
+    let ((ax, bx), (ay, by)) = (fit(&xs), fit(&ys));
+    if !(ax > 0.0 && ay > 0.0) {
+        return Err(BackendError::new(
+            "calibration_failed",
+            "The pointer did not follow the calibration moves; is another input device moving it?",
+        ));
+    }
+    let transform = CursorTransform {
+        scale_x: 1.0 / ax,
+        scale_y: 1.0 / ay,
+        offset_x: -bx / ax,
+        offset_y: -by / ay,
+    };
+    transform.check().map_err(|e| BackendError::new("calibration_failed", e))?;
+    let residual_px = samples
+        .iter()
+        .map(|s| (ax * s.target_x as f64 + bx - s.landed_x as f64).hypot(ay * s.target_y as f64 + by - s.landed_y as f64))
+        .fold(0.0, f64::max);
+
+    let mut findings = Vec::new();
+    if (ax - 1.0).abs() > 0.01 || (ay - 1.0).abs() > 0.01 {
+        findings.push(format!(
+            "The pointer lands at {:.2}× / {:.2}× the requested x / y: input and capture disagree on display scaling",
+            ax, ay
+        ));
+    }
+    if bx.abs() > TOLERANCE_PX || by.abs() > TOLERANCE_PX {
+        findings.push(format!(
+            "The pointer lands {:+}, {:+} px from where it was sent: the displays are offset differently for input",
+            bx.round() as i64,
+            by.round() as i64
+        ));
+    }
+    for d in displays.iter().filter(|d| (d.scale_factor - 1.0).abs() > 0.01) {
+        findings.push(format!("Display {} is scaled by {}", d.name.as_deref().unwrap_or(&d.id.to_string()), d.scale_factor));
+    }
+    if residual_px > TOLERANCE_PX * 2.0 {
+        findings.push(format!(
+            "Landings are up to {:.0} px off one scale and offset, e.g. displays with different scaling; the correction is approximate",
+            residual_px
+        ));
+    }
+    Ok(CalibrationReport {
+        samples,
+        transform: Some(transform),
+        residual_px,
+        findings,
+    })
+}
+
+/// Move the pointer over every display, read where it lands and derive the correction
+///
+/// `automation` must be the uncorrected backend; the pointer is put back where
+/// it was afterwards.
+pub fn calibrate(automation: &dyn Automation, displays: &[DisplayInfo], settle: Duration) -> Result<CalibrationReport, BackendError> {
+    let unsupported = || {
+        BackendError::new(
+            "calibration_unsupported",
+            "The input backend cannot report where the pointer is (ydotool on Wayland cannot), so moves cannot be checked",
+        )
+    };
+    let start = automation.cursor_position()?.ok_or_else(unsupported)?;
+    let mut samples = Vec::new();
+    for display in displays {
+        let bounds = display_rect(display);
+        if bounds.width == 0 || bounds.height == 0 {
+            continue;
+        }
+        for (fx, fy) in POINTS {
+            let target_x = bounds.x + (bounds.width as f64 * fx) as u32;
+            let target_y = bounds.y + (bounds.height as f64 * fy) as u32;
+            automation.move_cursor(target_x, target_y)?;
+            std::thread::sleep(settle);
+            let (landed_x, landed_y) = automation.cursor_position()?.ok_or_else(unsupported)?;
+            samples.push(CalibrationSample {
+                display_id: display.id,
+                target_x,
+                target_y,
+                landed_x,
+                landed_y,
+            });
+        }
+    }
+    if let Err(e) = automation.move_cursor(start.0.max(0) as u32, start.1.max(0) as u32) {
+        tracing::warn!("Failed to put the pointer back after calibrating: {}", e);
+    }
+    analyze(samples, displays)
+}
+
+fn current_transform() -> &'static RwLock<Option<CursorTransform>> {
+    static CURRENT: RwLock<Option<CursorTransform>> = RwLock::new(None);
+    &CURRENT
+}
+
+/// The correction `CalibratedAutomation` applies
+pub fn current() -> Option<CursorTransform> {
+    *current_transform().read().unwrap()
+}
+
+/// Apply `transform` to pointer moves from now on; `None` moves uncorrected
+pub fn set_current(transform: Option<CursorTransform>) {
+    *current_transform().write().unwrap() = transform;
+}
+
+/// Automation that corrects pointer moves by the current calibration
+pub struct CalibratedAutomation {
+    inner: Box<dyn Automation + Send + Sync>,
+}
+
+impl CalibratedAutomation {
+    pub fn new(inner: Box<dyn Automation + Send + Sync>) -> Self {
+        Self { inner }
+    }
+}
+
+impl Automation for CalibratedAutomation {
+    fn move_cursor(&self, x: u32, y: u32) -> Result<(), Error> {
+        let (x, y) = current().map_or((x, y), |t| t.apply(x, y));
+        self.inner.move_cursor(x, y)
+    }
+
+    fn click(&self, button: MouseButton) -> Result<(), Error> {
+        self.inner.click(button)
+    }
+
+    fn type_text(&self, text: &str) -> Result<(), Error> {
+        self.inner.type_text(text)
+    }
+
+    fn key(&self, key: &str) -> Result<(), Error> {
+        self.inner.key(key)
+    }
+
+    fn mouse_down(&self, button: MouseButton) -> Result<(), Error> {
+        self.inner.mouse_down(button)
+    }
+
+    fn mouse_up(&self, button: MouseButton) -> Result<(), Error> {
+        self.inner.mouse_up(button)
+    }
+
+    fn key_down(&self, key: &str) -> Result<(), Error> {
+        self.inner.key_down(key)
+    }
+
+    fn key_up(&self, key: &str) -> Result<(), Error> {
+        self.inner.key_up(key)
+    }
+
+    fn cursor_position(&self) -> Result<Option<(i32, i32)>, Error> {
+        self.inner.cursor_position()
+    }
+}
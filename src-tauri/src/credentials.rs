@@ -0,0 +1,335 @@
+/// Credentials for LLM providers
+///
+/// Each provider is stored under an id: its kind (`openai`, `anthropic`, ...),
+/// or `custom:<name>` for extra OpenAI-compatible endpoints. The API key goes
+/// to the secret entry `<id>_api_key`, so the OpenAI key keeps the entry it has
+/// always had; base URL and model are plain settings. Listing providers reports
+/// only whether a key is set, never the key.
+///
+/// For every key the store also records when it was set and when it last
+/// worked, so a run can warn about keys that stopped working or are old enough
+/// to have been rotated.
+///
+/// Profiles can hold named secrets of their own, e.g. a JIRA token typed by
+/// one profile, each in the secret entry `profile_secret:<profile id>:<name>`.
+/// The store keeps an index of their names, since a keyring cannot list them.
+///
+/// Keys set as environment variables (`OPENAI_API_KEY`, `ANTHROPIC_API_KEY`,
+/// ...) can be imported once; after that the stored key wins over the variable.
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProviderKind {
+    #[serde(rename = "openai")]
+    OpenAi,
+    Anthropic,
+    Gemini,
+    /// Azure OpenAI; needs the resource endpoint as base URL
+    Azure,
+    /// Local Ollama server; needs no key
+    Ollama,
+    /// Any other OpenAI-compatible endpoint
+    Custom,
+}
+
+impl ProviderKind {
+    pub fn id(self) -> &'static str {
+        match self {
+            ProviderKind::OpenAi => "openai",
+            ProviderKind::Anthropic => "anthropic",
+            ProviderKind::Gemini => "gemini",
+            ProviderKind::Azure => "azure",
+            ProviderKind::Ollama => "ollama",
+            ProviderKind::Custom => "custom",
+        }
+    }
+
+    fn needs_key(self) -> bool {
+        self != ProviderKind::Ollama
+    }
+
+    fn needs_base_url(self) -> bool {
+        matches!(self, ProviderKind::Azure | ProviderKind::Ollama | ProviderKind::Custom)
+    }
+}
+
+/// Credentials of one provider as entered in settings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProviderCredentials {
+    pub kind: ProviderKind,
+    /// New key; `None` keeps the stored one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    #[serde(default)]
+    pub base_url: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+/// Provider settings kept in the store; the key is held separately
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProviderSettings {
+    pub kind: ProviderKind,
+    #[serde(default)]
+    pub base_url: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+pub type Providers = BTreeMap<String, ProviderSettings>;
+
+/// A configured provider as shown in settings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProviderStatus {
+    pub id: String,
+    pub kind: ProviderKind,
+    pub base_url: Option<String>,
+    pub model: Option<String>,
+    pub has_key: bool,
+    /// When the key was set and last worked, if known
+    #[serde(default)]
+    pub key: Option<KeyMetadata>,
+}
+
+/// Secret entry holding the API key of provider `id`
+pub fn key_entry(id: &str) -> String {
+    format!("{}_api_key", id)
+}
+
+/// Kind named by a provider id: a built-in kind, or `custom:<name>`
+pub fn kind_of(id: &str) -> Result<ProviderKind, String> {
+    if let Some(name) = id.strip_prefix("custom:") {
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+        return if valid {
+            Ok(ProviderKind::Custom)
+        } else {
+            Err(format!(
+                "Custom provider '{}' needs a name of lowercase letters, digits, '-' or '_'",
+                id
+            ))
+        };
+    }
+    serde_json::from_value(serde_json::Value::String(id.to_string()))
+        .ok()
+        .filter(|kind| *kind != ProviderKind::Custom)
+        .ok_or_else(|| format!("Unknown provider '{}'", id))
+}
+
+impl ProviderCredentials {
+    /// Check `self` for provider `id`; `has_stored_key` tells whether a key is already saved
+    pub fn validate(&self, id: &str, has_stored_key: bool) -> Result<(), String> {
+        let kind = kind_of(id)?;
+        if kind != self.kind {
+            return Err(format!("Provider '{}' is not of kind '{}'", id, self.kind.id()));
+        }
+        if let Some(key) = &self.api_key {
+            if key.trim().is_empty() {
+                return Err("API key cannot be empty".into());
+            }
+        } else if kind.needs_key() && !has_stored_key {
+            return Err(format!("Provider '{}' needs an API key", id));
+        }
+        let url = self.base_url.as_deref().map(str::trim).unwrap_or_default();
+        if url.is_empty() {
+            if kind.needs_base_url() {
+                return Err(format!("Provider '{}' needs a base URL", id));
+            }
+        } else if !(url.starts_with("https://") || url.starts_with("http://")) {
+            return Err(format!("Base URL must start with http:// or https://, got '{}'", url));
+        }
+        Ok(())
+    }
+
+    pub fn settings(&self) -> ProviderSettings {
+        let trimmed = |v: &Option<String>| v.as_deref().map(str::trim).filter(|s| !s.is_empty()).map(str::to_string);
+        ProviderSettings {
+            kind: self.kind,
+            base_url: trimmed(&self.base_url),
+            model: trimmed(&self.model),
+        }
+    }
+}
+
+/// Configured providers without their keys; an OpenAI key saved before providers existed counts too
+pub fn statuses(providers: &Providers, has_key: impl Fn(&str) -> bool) -> Vec<ProviderStatus> {
+    let mut list: Vec<ProviderStatus> = providers
+        .iter()
+        .map(|(id, settings)| ProviderStatus {
+            id: id.clone(),
+            kind: settings.kind,
+            base_url: settings.base_url.clone(),
+            model: settings.model.clone(),
+            has_key: has_key(id),
+            key: None,
+        })
+        .collect();
+    let openai = ProviderKind::OpenAi.id();
+    if !providers.contains_key(openai) && has_key(openai) {
+        list.insert(
+            0,
+            ProviderStatus {
+                id: openai.into(),
+                kind: ProviderKind::OpenAi,
+                base_url: None,
+                model: None,
+                has_key: true,
+                key: None,
+            },
+        );
+    }
+    list
+}
+
+const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+/// When a key was set and used; kept per secret entry, without the key
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyMetadata {
+    /// When the key was saved, or first used if it predates this record
+    pub set_at_ms: u64,
+    #[serde(default)]
+    pub last_used_ms: Option<u64>,
+    #[serde(default)]
+    pub last_failed_ms: Option<u64>,
+}
+
+/// Key metadata by secret entry, e.g. `openai_api_key`
+pub type KeyMetadataMap = BTreeMap<String, KeyMetadata>;
+
+/// When to warn about a key; 0 turns a check off
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyAgePolicy {
+    /// Warn once a key has failed and last worked this many days ago
+    pub failing_days: u32,
+    /// Warn once a key was set this many days ago
+    pub max_age_days: u32,
+}
+
+impl Default for KeyAgePolicy {
+    fn default() -> Self {
+        Self {
+            failing_days: 3,
+            max_age_days: 90,
+        }
+    }
+}
+
+impl KeyMetadata {
+    pub fn new(now_ms: u64) -> Self {
+        Self {
+            set_at_ms: now_ms,
+            ..Default::default()
+        }
+    }
+
+    pub fn record_use(&mut self, ok: bool, now_ms: u64) {
+        if ok {
+            self.last_used_ms = Some(now_ms);
+        } else {
+            self.last_failed_ms = Some(now_ms);
+        }
+    }
+
+    /// Why the key needs attention at `now_ms`, if it does
+    pub fn warning(&self, policy: &KeyAgePolicy, now_ms: u64) -> Option<String> {
+        let days = |since: u64| now_ms.saturating_sub(since) / DAY_MS;
+        let last_ok = self.last_used_ms.unwrap_or(self.set_at_ms);
+        let failing = self.last_failed_ms.is_some_and(|failed| failed > last_ok);
+        if policy.failing_days > 0 && failing && days(last_ok) >= policy.failing_days as u64 {
+            return Some(match self.last_used_ms {
+                Some(used) => format!("has not worked for {} days; it may have been revoked", days(used)),
+                None => format!("has not worked since it was set {} days ago", days(self.set_at_ms)),
+            });
+        }
+        if policy.max_age_days > 0 && days(self.set_at_ms) >= policy.max_age_days as u64 {
+            return Some(format!(
+                "was set {} days ago; replace it if your provider rotates keys",
+                days(self.set_at_ms)
+            ));
+        }
+        None
+    }
+}
+
+/// Secret names by profile id
+pub type ProfileSecretIndex = BTreeMap<String, BTreeSet<String>>;
+
+pub const PROFILE_SECRET_PREFIX: &str = "profile_secret:";
+
+/// Secret entry holding secret `name` of profile `profile_id`
+pub fn profile_secret_entry(profile_id: &str, name: &str) -> String {
+    format!("{}{}:{}", PROFILE_SECRET_PREFIX, profile_id, name)
+}
+
+/// Check a name used as `{{secret:name}}`
+pub fn check_secret_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Secret name '{}' may only contain letters, digits, '_' or '-'", name))
+    }
+}
+
+/// An environment variable holding a provider's API key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnvCredential {
+    pub var: &'static str,
+    pub kind: ProviderKind,
+    /// Variable holding the base URL the provider needs
+    pub base_url_var: Option<&'static str>,
+}
+
+/// Variables `import_env_credentials` looks for, in order of preference per provider
+pub const ENV_CREDENTIALS: &[EnvCredential] = &[
+    EnvCredential { var: "OPENAI_API_KEY", kind: ProviderKind::OpenAi, base_url_var: None },
+    EnvCredential { var: "ANTHROPIC_API_KEY", kind: ProviderKind::Anthropic, base_url_var: None },
+    EnvCredential { var: "GEMINI_API_KEY", kind: ProviderKind::Gemini, base_url_var: None },
+    EnvCredential { var: "GOOGLE_API_KEY", kind: ProviderKind::Gemini, base_url_var: None },
+    EnvCredential {
+        var: "AZURE_OPENAI_API_KEY",
+        kind: ProviderKind::Azure,
+        base_url_var: Some("AZURE_OPENAI_ENDPOINT"),
+    },
+];
+
+/// A key found in the environment, without its value
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnvKeyStatus {
+    pub var: String,
+    pub provider: String,
+    /// The provider already has a stored key, which takes precedence
+    pub stored: bool,
+    /// Why the key cannot be imported, e.g. a missing base URL
+    #[serde(default)]
+    pub problem: Option<String>,
+}
+
+/// Keys set in the environment, the first variable per provider only
+pub fn detect_env_keys(env: &dyn Fn(&str) -> Option<String>, has_stored_key: &dyn Fn(&str) -> bool) -> Vec<EnvKeyStatus> {
+    let mut found: Vec<EnvKeyStatus> = Vec::new();
+    for c in ENV_CREDENTIALS {
+        let provider = c.kind.id();
+        let set = env(c.var).is_some_and(|v| !v.trim().is_empty());
+        if !set || found.iter().any(|f| f.provider == provider) {
+            continue;
+        }
+        let problem = c
+            .base_url_var
+            .filter(|url| env(url).is_none_or(|v| v.trim().is_empty()))
+            .map(|url| format!("{} is not set", url));
+        found.push(EnvKeyStatus {
+            var: c.var.to_string(),
+            provider: provider.to_string(),
+            stored: has_stored_key(provider),
+            problem,
+        });
+    }
+    found
+}
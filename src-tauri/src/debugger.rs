@@ -0,0 +1,106 @@
+/// Step-through debugging for action execution
+///
+/// While stepping, the runner holds before every action and emits
+/// `Event::DebugPaused` with the action's expanded parameters and the current
+/// context variables. `step` releases a single action; `continue_run` leaves
+/// step mode for the rest of the run.
+use std::sync::{Condvar, Mutex};
+
+use crate::domain::{Action, ActionContext, Event, StepGate};
+
+#[derive(Default)]
+struct StepState {
+    stepping: bool,
+    /// True while the runner is blocked before an action
+    waiting: bool,
+    released: bool,
+    cancelled: bool,
+}
+
+pub struct StepController {
+    state: Mutex<StepState>,
+    wake: Condvar,
+    /// Delivers events to the UI immediately; the runner blocks before its tick completes
+    sink: Box<dyn Fn(Event) + Send + Sync>,
+}
+
+impl StepController {
+    /// Create a controller in step mode; `sink` receives events that must be seen before blocking
+    pub fn new(sink: impl Fn(Event) + Send + Sync + 'static) -> Self {
+        Self {
+            state: Mutex::new(StepState {
+                stepping: true,
+                ..StepState::default()
+            }),
+            wake: Condvar::new(),
+            sink: Box::new(sink),
+        }
+    }
+
+    /// Run the action the runner is holding before, then hold again at the next one
+    pub fn step(&self) -> Result<(), String> {
+        let mut st = self.state.lock().unwrap();
+        if !st.waiting {
+            return Err("Runner is not waiting at an action".to_string());
+        }
+        st.released = true;
+        self.wake.notify_all();
+        Ok(())
+    }
+
+    /// Leave step mode and let the run continue normally
+    pub fn continue_run(&self) {
+        self.state.lock().unwrap().stepping = false;
+        self.wake.notify_all();
+    }
+
+    /// Wake a blocked runner so it can shut down; the held action is not executed
+    pub fn cancel(&self) {
+        self.state.lock().unwrap().cancelled = true;
+        self.wake.notify_all();
+    }
+}
+
+impl StepGate for StepController {
+    fn before_action(
+        &self,
+        index: usize,
+        action: &dyn Action,
+        context: &ActionContext,
+        events: &mut Vec<Event>,
+    ) -> bool {
+        {
+            let st = self.state.lock().unwrap();
+            if st.cancelled {
+                return false;
+            }
+            if !st.stepping {
+                return true;
+            }
+        }
+
+        // Flush what this tick emitted so far so the UI sees events in order
+        for e in events.drain(..) {
+            (self.sink)(e);
+        }
+        (self.sink)(Event::DebugPaused {
+            index,
+            action: action.name().to_string(),
+            params: action.debug_params(context),
+            variables: context
+                .variables
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+        });
+
+        let mut st = self.state.lock().unwrap();
+        st.waiting = true;
+        while st.stepping && !st.released && !st.cancelled {
+            st = self.wake.wait(st).unwrap();
+        }
+        st.waiting = false;
+        st.released = false;
+        !st.cancelled
+    }
+}
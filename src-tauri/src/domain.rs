@@ -1,7 +1,10 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+pub use crate::error::Error;
+
 /// OCR/Vision mode for text extraction and LLM integration
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -21,7 +24,7 @@ impl Default for OcrMode {
 }
 
 // Basic geometry and region types
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Rect {
     pub x: u32,
     pub y: u32,
@@ -29,11 +32,40 @@ pub struct Rect {
     pub height: u32,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Region {
     pub id: String,
     pub rect: Rect,
     pub name: Option<String>,
+    /// Position the region relative to a template match instead of at `rect.x`/`rect.y`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anchor: Option<RegionAnchor>,
+}
+
+/// A small image the region is positioned against, resolved at capture time
+///
+/// The region keeps the size of its `rect` and moves to the template's matched
+/// location plus the offset, e.g. 200 px right of a "Build" label.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegionAnchor {
+    /// Base64 PNG of what to look for
+    pub template_png: String,
+    /// Region origin relative to the top-left corner of the match; may be negative
+    pub offset_x: i32,
+    pub offset_y: i32,
+    /// Screen area searched for the template; the display holding the region when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub search: Option<Rect>,
+    /// Lowest match score (0..1) accepted as the template
+    #[serde(default = "default_anchor_threshold")]
+    pub threshold: f64,
+}
+
+/// Match score an anchor needs unless it sets its own
+pub const DEFAULT_ANCHOR_THRESHOLD: f64 = 0.8;
+
+fn default_anchor_threshold() -> f64 {
+    DEFAULT_ANCHOR_THRESHOLD
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -48,6 +80,171 @@ pub struct DisplayInfo {
     pub is_primary: bool,
 }
 
+/// A top-level window as listed by `enumerate_windows`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WindowInfo {
+    /// Platform window id (X11 window, HWND)
+    pub id: u64,
+    pub title: String,
+    /// Executable or application name of the owning process
+    pub process_name: String,
+    pub pid: u32,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub minimized: bool,
+}
+
+/// Which input the automation backend can send, as reported by `automation_capabilities`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AutomationCapabilities {
+    /// Desktop session: `x11`, `wayland`, `unknown`, or the OS (`macos`, `windows`)
+    pub session: String,
+    /// Input backend: `xtest`, `ydotool`, `cgevent`, `sendinput`, `fake`, or `none`
+    pub backend: String,
+    pub mouse: bool,
+    pub keyboard: bool,
+    pub text: bool,
+    /// What limits the backend or how to enable it
+    pub hint: Option<String>,
+}
+
+/// Which UI elements `find_elements` looks for, e.g. the button named "Run"
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ElementQuery {
+    /// Role such as `button`, `text field`, `check box`; platform names (`push button`, `AXButton`, `Edit`) work too
+    #[serde(default)]
+    pub role: Option<String>,
+    /// Accessible name, compared case-insensitively
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Only search applications whose name contains this
+    #[serde(default)]
+    pub app: Option<String>,
+}
+
+impl ElementQuery {
+    pub fn validate(&self) -> Result<(), String> {
+        let given = |v: &Option<String>| v.as_deref().is_some_and(|s| !s.trim().is_empty());
+        if !given(&self.role) && !given(&self.name) {
+            return Err("Element query needs a role or a name".into());
+        }
+        Ok(())
+    }
+
+    /// Whether elements of this application are searched at all
+    pub fn matches_app(&self, app: &str) -> bool {
+        match self.app.as_deref().map(str::trim) {
+            Some(wanted) if !wanted.is_empty() => app.to_lowercase().contains(&wanted.to_lowercase()),
+            _ => true,
+        }
+    }
+
+    pub fn matches_role(&self, role: &str) -> bool {
+        match self.role.as_deref().map(str::trim) {
+            Some(wanted) if !wanted.is_empty() => canonical_role(wanted) == canonical_role(role),
+            _ => true,
+        }
+    }
+
+    pub fn matches_name(&self, name: &str) -> bool {
+        match self.name.as_deref().map(str::trim) {
+            Some(wanted) if !wanted.is_empty() => name.trim().to_lowercase() == wanted.to_lowercase(),
+            _ => true,
+        }
+    }
+
+    pub fn matches(&self, role: &str, name: &str) -> bool {
+        self.matches_role(role) && self.matches_name(name)
+    }
+}
+
+/// `button "Run" in Firefox`, for error messages
+impl std::fmt::Display for ElementQuery {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let given = |v: &Option<String>| v.as_deref().map(str::trim).filter(|s| !s.is_empty()).map(str::to_string);
+        match (given(&self.role), given(&self.name)) {
+            (Some(role), Some(name)) => write!(f, "{} \"{}\"", role, name)?,
+            (Some(role), None) => write!(f, "{}", role)?,
+            (None, Some(name)) => write!(f, "element \"{}\"", name)?,
+            (None, None) => write!(f, "element")?,
+        }
+        match given(&self.app) {
+            Some(app) => write!(f, " in {}", app),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Map a platform role name to the shared vocabulary, so `push button` (AT-SPI),
+/// `AXButton` (macOS) and `Button` (UI Automation) all become `button`
+pub fn canonical_role(raw: &str) -> String {
+    let raw = raw.trim();
+    // macOS roles are AXCamelCase
+    let raw = match raw.strip_prefix("AX") {
+        Some(rest) if rest.starts_with(|c: char| c.is_ascii_uppercase()) => rest,
+        _ => raw,
+    };
+    let mut words = String::new();
+    for (i, c) in raw.chars().enumerate() {
+        if c == '_' || c == '-' || c.is_whitespace() {
+            words.push(' ');
+        } else {
+            if c.is_uppercase() && i > 0 && !words.ends_with(' ') {
+                words.push(' ');
+            }
+            words.extend(c.to_lowercase());
+        }
+    }
+    let words = words.split_whitespace().collect::<Vec<_>>().join(" ");
+    let canonical = match words.as_str() {
+        "push button" | "toggle button" | "pop up button" | "split button" | "menu button" => "button",
+        "checkbox" | "check box" | "check menu item" => "check box",
+        "radio button" | "radio menu item" => "radio button",
+        "entry" | "edit" | "text field" | "text area" | "password text" | "secure text field" | "search field" => {
+            "text field"
+        }
+        "hyperlink" | "link" => "link",
+        "label" | "static text" | "text" => "text",
+        "page tab" | "tab item" | "tab" | "radio tab" => "tab",
+        "page tab list" | "tab group" | "tab list" => "tab list",
+        "combobox" | "combo box" => "combo box",
+        "frame" | "window" | "dialog" => "window",
+        "panel" | "pane" | "group" | "filler" => "group",
+        "list item" | "row" => "list item",
+        "image" | "icon" => "image",
+        other => other,
+    };
+    canonical.to_string()
+}
+
+/// An element of the accessibility tree as returned by `find_elements`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UiElement {
+    /// Canonical role, see [`canonical_role`]
+    pub role: String,
+    pub name: String,
+    /// Application the element belongs to
+    pub app: String,
+    /// Text content or value (text fields, labels), else the name
+    #[serde(default)]
+    pub text: String,
+    /// Screen rectangle, in the coordinates the automation backend clicks at
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl UiElement {
+    /// Where a click lands; parts left of or above the screen origin are ignored
+    pub fn center(&self) -> (u32, u32) {
+        let mid = |start: i32, len: u32| (start as i64 + len as i64 / 2).max(0) as u32;
+        (mid(self.x, self.width), mid(self.y, self.height))
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ScreenFrame {
     pub display: DisplayInfo,
@@ -59,7 +256,7 @@ pub struct ScreenFrame {
 }
 
 // Events flowing through the system (minimal for MVP)
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum Event {
     TriggerFired,
@@ -76,6 +273,24 @@ pub enum Event {
     MonitorStateChanged {
         state: MonitorState,
     },
+    /// An LLM-backed action is about to call its model
+    LlmCallStarted {
+        action: String,
+    },
+    /// The call of an LLM-backed action returned
+    LlmCallFinished {
+        action: String,
+        success: bool,
+        /// Risk the model rated its continuation prompt, when it produced one
+        risk: Option<f64>,
+        /// The continuation prompt, also when its risk got it rejected
+        prompt: Option<String>,
+    },
+    /// The action sequence ran once, up to its first failure
+    IterationCompleted {
+        iteration: u32,
+        success: bool,
+    },
     WatchdogTripped {
         reason: String,
     },
@@ -85,6 +300,36 @@ pub enum Event {
     Error {
         message: String,
     },
+    /// Step-through debugging: the runner is holding before this action
+    DebugPaused {
+        index: usize,
+        action: String,
+        params: serde_json::Value,
+        variables: BTreeMap<String, String>,
+    },
+    /// A per-profile budget was exhausted; the run stops
+    BudgetExceeded {
+        budget: String,
+        limit: u64,
+    },
+    /// A finished run handed off to a follow-up profile
+    ProfileChained {
+        from: String,
+        to: String,
+        /// "complete" or "failure"
+        on: String,
+    },
+    /// Quiet hours held back a notification's sound and desktop popup
+    NotificationSuppressed {
+        event: NotificationEvent,
+        channels: Vec<NotificationChannel>,
+    },
+    /// A stored API key stopped working or is due for rotation
+    CredentialWarning {
+        /// Secret entry, e.g. `openai_api_key`
+        credential: String,
+        reason: String,
+    },
     /// Emitted on each tick with timing information
     MonitorTick {
         next_check_ms: u64,
@@ -98,6 +343,53 @@ pub enum MonitorState {
     Stopped,
     Running,
     Stopping,
+    /// Paused because an LLM-dependent iteration found no network connectivity
+    WaitingForNetwork,
+    /// Paused by the user; triggers are held and context is kept
+    Paused,
+    /// Held until the profile's run window opens; context is kept
+    OutsideRunWindow,
+    /// Held after an action overran `action_timeout`; the user resumes or stops the run
+    ActionTimedOut,
+}
+
+/// How a run ended, recorded in run history
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum RunOutcome {
+    /// Stopped from the UI (or the runner was cancelled)
+    Stopped,
+    /// An action or termination check signalled that the task is done
+    Completed { reason: String },
+    /// A per-profile budget (iterations, runtime, LLM failures) was exhausted
+    BudgetExceeded { budget: String },
+    /// A watchdog (heartbeat stall, panic stop) ended the run
+    WatchdogTripped { reason: String },
+    /// A failure check (e.g. an OCR failure keyword) ended the run
+    Failed { reason: String },
+}
+
+impl RunOutcome {
+    /// Whether the run succeeded; `None` when it was stopped by the user
+    pub fn succeeded(&self) -> Option<bool> {
+        match self {
+            RunOutcome::Completed { .. } => Some(true),
+            RunOutcome::Failed { .. } | RunOutcome::BudgetExceeded { .. } => Some(false),
+            RunOutcome::WatchdogTripped { reason } if reason != "panic_stop" => Some(false),
+            RunOutcome::Stopped | RunOutcome::WatchdogTripped { .. } => None,
+        }
+    }
+
+    /// The variant in snake case, as metrics and traces label it
+    pub fn kind(&self) -> &'static str {
+        match self {
+            RunOutcome::Stopped => "stopped",
+            RunOutcome::Completed { .. } => "completed",
+            RunOutcome::BudgetExceeded { .. } => "budget_exceeded",
+            RunOutcome::WatchdogTripped { .. } => "watchdog_tripped",
+            RunOutcome::Failed { .. } => "failed",
+        }
+    }
 }
 
 // Traits
@@ -111,9 +403,38 @@ pub trait ScreenCapture {
     // A fast hash of a region (already downscaled by the impl as appropriate)
     fn hash_region(&self, region: &Region, downscale: u32) -> u64;
     fn capture_region(&self, region: &Region) -> Result<ScreenFrame, BackendError>;
+    /// Capture a region and hash it; the hash equals what `hash_region` returns for the
+    /// same pixels. Backends that hash captured pixels override this to capture once.
+    fn capture_and_hash(&self, region: &Region, downscale: u32) -> Result<(ScreenFrame, u64), BackendError> {
+        let frame = self.capture_region(region)?;
+        Ok((frame, self.hash_region(region, downscale)))
+    }
     fn displays(&self) -> Result<Vec<DisplayInfo>, BackendError>;
 }
 
+/// Lists the top-level windows of the desktop
+pub trait WindowEnumerator {
+    fn windows(&self) -> Result<Vec<WindowInfo>, BackendError>;
+}
+
+/// Elements an `ElementFinder` looks at before giving up, so a huge document cannot stall a lookup
+pub const ELEMENT_SEARCH_MAX_NODES: usize = 20_000;
+pub const ELEMENT_SEARCH_MAX_DEPTH: usize = 64;
+
+/// Looks up UI elements in the platform accessibility tree
+pub trait ElementFinder {
+    /// Visible elements matching `query`, in tree order
+    fn find(&self, query: &ElementQuery) -> Result<Vec<UiElement>, BackendError>;
+
+    /// Perform the default accessibility action (press, activate) of the first match
+    fn invoke(&self, _query: &ElementQuery) -> Result<UiElement, BackendError> {
+        Err(BackendError::new(
+            "invoke_unsupported",
+            "this platform cannot invoke elements; click them instead",
+        ))
+    }
+}
+
 /// Trait for OCR text extraction from screen regions
 pub trait OCRCapture: Send + Sync {
     /// Extract text from a screen region
@@ -139,22 +460,51 @@ pub enum MouseButton {
 }
 
 pub trait Automation {
-    fn move_cursor(&self, x: u32, y: u32) -> Result<(), String>;
-    fn click(&self, button: MouseButton) -> Result<(), String>;
-    fn type_text(&self, text: &str) -> Result<(), String>;
-    fn key(&self, key: &str) -> Result<(), String>;
-    fn mouse_down(&self, button: MouseButton) -> Result<(), String> {
+    fn move_cursor(&self, x: u32, y: u32) -> Result<(), Error>;
+    fn click(&self, button: MouseButton) -> Result<(), Error>;
+    fn type_text(&self, text: &str) -> Result<(), Error>;
+    fn key(&self, key: &str) -> Result<(), Error>;
+    fn mouse_down(&self, button: MouseButton) -> Result<(), Error> {
         self.click(button)
     }
-    fn mouse_up(&self, _button: MouseButton) -> Result<(), String> {
+    fn mouse_up(&self, _button: MouseButton) -> Result<(), Error> {
         Ok(())
     }
-    fn key_down(&self, key: &str) -> Result<(), String> {
+    fn key_down(&self, key: &str) -> Result<(), Error> {
         self.key(key)
     }
-    fn key_up(&self, _key: &str) -> Result<(), String> {
+    fn key_up(&self, _key: &str) -> Result<(), Error> {
         Ok(())
     }
+    /// Where the pointer is on the desktop; `None` when the backend cannot tell
+    fn cursor_position(&self) -> Result<Option<(i32, i32)>, Error> {
+        Ok(None)
+    }
+}
+
+/// Looks up the running profile's `{{secret:name}}` values when an action needs them
+#[derive(Clone, Default)]
+pub struct SecretLookup(Option<Arc<SecretFn>>);
+
+type SecretFn = dyn Fn(&str) -> Result<Option<String>, String> + Send + Sync;
+
+impl SecretLookup {
+    pub fn new(lookup: impl Fn(&str) -> Result<Option<String>, String> + Send + Sync + 'static) -> Self {
+        Self(Some(Arc::new(lookup)))
+    }
+
+    pub fn get(&self, name: &str) -> Result<Option<String>, String> {
+        match &self.0 {
+            Some(lookup) => lookup(name),
+            None => Ok(None),
+        }
+    }
+}
+
+impl std::fmt::Debug for SecretLookup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SecretLookup")
+    }
 }
 
 /// ActionContext holds global variables that can be referenced by actions
@@ -162,18 +512,24 @@ pub trait Automation {
 #[derive(Debug, Clone, Default)]
 pub struct ActionContext {
     pub variables: HashMap<String, String>,
+    /// The profile's secrets, read only while an action executes
+    pub secrets: SecretLookup,
     /// Flag set by TerminationCheck or LLM actions to signal monitor should stop
     pub should_terminate: bool,
     /// Reason for termination (if should_terminate is true)
     pub termination_reason: Option<String>,
+    /// Cancelled when the run is paused or stopped; actions that wait return early
+    pub cancel: crate::shutdown::ShutdownToken,
 }
 
 impl ActionContext {
     pub fn new() -> Self {
         Self {
             variables: HashMap::new(),
+            secrets: SecretLookup::default(),
             should_terminate: false,
             termination_reason: None,
+            cancel: crate::shutdown::ShutdownToken::default(),
         }
     }
 
@@ -195,6 +551,11 @@ impl ActionContext {
         result
     }
     
+    /// Like `expand`, also filling `{{secret:name}}` references; the result must not be logged
+    pub fn expand_with_secrets(&self, text: &str) -> Result<String, String> {
+        crate::expand::resolve_secrets(text, &|t| self.expand(t), &|name| self.secrets.get(name))
+    }
+
     /// Request termination of the monitor
     pub fn request_termination(&mut self, reason: impl Into<String>) {
         self.should_terminate = true;
@@ -213,16 +574,67 @@ pub trait Action {
         &self,
         automation: &dyn Automation,
         context: &mut ActionContext,
-    ) -> Result<(), String>;
+    ) -> Result<(), Error>;
+    /// Whether this action needs network connectivity (e.g., LLM calls)
+    fn requires_network(&self) -> bool {
+        false
+    }
+    /// Whether failures of this action count toward `max_consecutive_llm_failures`
+    fn uses_llm(&self) -> bool {
+        false
+    }
+    /// Parameters with context variables expanded, shown when step-through debugging
+    fn debug_params(&self, _context: &ActionContext) -> serde_json::Value {
+        serde_json::Value::Null
+    }
+}
+
+/// Context variable in which LLM actions leave the risk of their continuation prompt
+pub const RISK_VARIABLE: &str = "continuation_prompt_risk";
+
+/// Context variable in which LLM actions leave their continuation prompt, accepted or not
+pub const PROMPT_VARIABLE: &str = "continuation_prompt";
+
+/// Hook consulted before each action runs; used by step-through debugging
+pub trait StepGate: Send + Sync {
+    /// Block until the action at `index` may run; `events` holds everything emitted so far
+    /// in this tick. Returning false cancels the rest of the sequence, as a pause would.
+    fn before_action(
+        &self,
+        index: usize,
+        action: &dyn Action,
+        context: &ActionContext,
+        events: &mut Vec<Event>,
+    ) -> bool;
 }
 
 pub struct ActionSequence {
-    pub actions: Vec<Box<dyn Action + Send + Sync>>, // keep it simple for now
+    /// Shared so a watchdog worker can hold an action past its deadline
+    pub actions: Vec<Arc<dyn Action + Send + Sync>>,
+    /// Optional gate consulted before each action (step-through debugging)
+    pub step_gate: Option<std::sync::Arc<dyn StepGate>>,
+    /// Frames the change check captured; valid until the first action has run
+    pub frame_cache: Option<std::sync::Arc<crate::frames::FrameCache>>,
+    /// Runs each action on a supervised worker that a deadline or cancellation abandons
+    pub watchdog: Option<crate::watchdog::ActionWatchdog>,
+    /// Masks personal data in what the actions capture; also applied to the frame archive
+    pub privacy: Option<Arc<crate::privacy::PrivacyFilter>>,
 }
 
 impl ActionSequence {
     pub fn new(actions: Vec<Box<dyn Action + Send + Sync>>) -> Self {
-        Self { actions }
+        Self {
+            actions: actions.into_iter().map(Arc::from).collect(),
+            step_gate: None,
+            frame_cache: None,
+            watchdog: None,
+            privacy: None,
+        }
+    }
+
+    /// True if any action in the sequence needs network connectivity
+    pub fn requires_network(&self) -> bool {
+        self.actions.iter().any(|a| a.requires_network())
     }
 
     pub fn run(
@@ -231,16 +643,92 @@ impl ActionSequence {
         context: &mut ActionContext,
         events: &mut Vec<Event>,
     ) -> bool {
+        self.execute(automation, context, events).is_ok()
+    }
+
+    /// Run the sequence; on failure returns the index of the action that failed
+    pub fn execute(
+        &self,
+        automation: &dyn Automation,
+        context: &mut ActionContext,
+        events: &mut Vec<Event>,
+    ) -> Result<(), usize> {
         for (i, a) in self.actions.iter().enumerate() {
+            // Paused or stopped since the last action: the rest of the sequence is not run
+            if context.cancel.is_cancelled() {
+                return Err(i);
+            }
+            if let Some(gate) = &self.step_gate {
+                if !gate.before_action(i, a.as_ref(), context, events) {
+                    // Abandoned at the gate, e.g. by a stop: cancelled like a pause, so the
+                    // partial run is not counted
+                    tracing::info!("Action cancelled at the step gate: {}", a.name());
+                    context.cancel.cancel();
+                    return Err(i);
+                }
+            }
+            let span = tracing::info_span!(
+                "action",
+                index = i,
+                action = a.name(),
+                success = tracing::field::Empty,
+                error = tracing::field::Empty
+            )
+            .entered();
             events.push(Event::ActionStarted {
                 action: a.name().to_string(),
             });
-            match a.execute(automation, context) {
+            if a.uses_llm() {
+                context.variables.remove(RISK_VARIABLE);
+                context.variables.remove(PROMPT_VARIABLE);
+                events.push(Event::LlmCallStarted {
+                    action: a.name().to_string(),
+                });
+            }
+            let result = match &self.watchdog {
+                Some(watchdog) => watchdog.run(a.clone(), context),
+                None => {
+                    let cancel = context.cancel.clone();
+                    a.execute(&crate::shutdown::CancellableAutomation::new(automation, &cancel), context)
+                }
+            };
+            // The action may have changed the screen
+            if let Some(frames) = &self.frame_cache {
+                frames.clear();
+            }
+            span.record("success", result.is_ok());
+            if let Err(e) = &result {
+                span.record("error", e.to_string());
+            }
+            if a.uses_llm() {
+                events.push(Event::LlmCallFinished {
+                    action: a.name().to_string(),
+                    success: result.is_ok(),
+                    risk: context.get(RISK_VARIABLE).and_then(|r| r.parse().ok()),
+                    prompt: context.get(PROMPT_VARIABLE).map(str::to_string),
+                });
+            }
+            match result {
                 Ok(()) => events.push(Event::ActionCompleted {
                     action: a.name().to_string(),
                     success: true,
                 }),
+                // Not a failure: the user paused or stopped the run
+                Err(e @ Error::Cancelled(_)) => {
+                    tracing::info!("Action cancelled: {}", e);
+                    events.push(Event::ActionCompleted {
+                        action: a.name().to_string(),
+                        success: false,
+                    });
+                    return Err(i);
+                }
                 Err(e) => {
+                    tracing::warn!(code = e.code(), "Action failed: {}", e);
+                    if let Error::Timeout(_) = e {
+                        events.push(Event::WatchdogTripped {
+                            reason: crate::watchdog::ACTION_TIMEOUT.into(),
+                        });
+                    }
                     events.push(Event::Error {
                         message: format!("action '{}': {}", a.name(), e),
                     });
@@ -248,7 +736,7 @@ impl ActionSequence {
                         action: a.name().to_string(),
                         success: false,
                     });
-                    return false;
+                    return Err(i);
                 }
             }
             
@@ -257,7 +745,7 @@ impl ActionSequence {
                 events.push(Event::TerminationCheckTriggered {
                     reason: context.get("termination_reason").unwrap_or_default().to_string(),
                 });
-                return true; // Return success but stop sequence
+                return Ok(()); // Return success but stop sequence
             }
             
             // Add delay between actions to allow window manager to process events
@@ -266,7 +754,7 @@ impl ActionSequence {
                 std::thread::sleep(std::time::Duration::from_millis(50));
             }
         }
-        true
+        Ok(())
     }
 }
 
@@ -276,8 +764,14 @@ pub struct Guardrails {
     pub cooldown: Duration,
     pub max_runtime: Option<Duration>,
     pub max_activations_per_hour: Option<u32>,
+    /// Budget: stop after this many action-sequence iterations
+    pub max_iterations: Option<u32>,
+    /// Budget: stop after this many LLM action failures in a row
+    pub max_consecutive_llm_failures: Option<u32>,
     /// Maximum time without action progress before watchdog triggers (heartbeat pattern)
     pub heartbeat_timeout: Option<Duration>,
+    /// Longest a single action may run before the watchdog abandons it
+    pub action_timeout: Option<Duration>,
     /// OCR mode for termination pattern matching
     pub ocr_mode: OcrMode,
     /// Keywords/patterns that indicate task success (terminate with success)
@@ -296,7 +790,10 @@ impl Default for Guardrails {
             cooldown: Duration::from_millis(0),
             max_runtime: None,
             max_activations_per_hour: None,
+            max_iterations: None,
+            max_consecutive_llm_failures: None,
             heartbeat_timeout: None,
+            action_timeout: None,
             ocr_mode: OcrMode::default(),
             success_keywords: Vec::new(),
             failure_keywords: Vec::new(),
@@ -317,12 +814,207 @@ pub struct Profile {
     pub condition: ConditionConfig,
     pub actions: Vec<ActionConfig>,
     pub guardrails: Option<GuardrailsConfig>,
+    /// Profile to start when a run completes successfully
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_complete: Option<ProfileHandoff>,
+    /// Profile to start when a run fails (failure check, budget or watchdog)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_failure: Option<ProfileHandoff>,
+    /// Environment variables the profile may reference as `${NAME}`; all others are rejected
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub env_allowlist: Vec<String>,
+    /// Channels per notification event; events not listed use the default routing
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub notifications: BTreeMap<NotificationEvent, Vec<NotificationChannel>>,
+    /// Local times the profile may run; empty means any time
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub run_windows: Vec<RunWindow>,
+    /// Keep a frame of every region after each iteration, for animated exports
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frame_archive: Option<FrameArchiveConfig>,
+    /// Mask personal data in frames before they are archived or sent to an LLM
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub privacy: Option<PrivacyConfig>,
+    /// Phrases that start and stop this profile by voice; see `voice`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub voice: Option<VoicePhrases>,
+}
+
+impl Profile {
+    /// Follow-up profile for a finished run; user stops and panic stops never chain
+    pub fn handoff_for(&self, outcome: &RunOutcome) -> Option<&ProfileHandoff> {
+        match outcome.succeeded()? {
+            true => self.on_complete.as_ref(),
+            false => self.on_failure.as_ref(),
+        }
+    }
+}
+
+/// Run events a profile can route to notification channels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationEvent {
+    /// A run began (or was queued until its run window opens)
+    Started,
+    /// The run is blocked and needs the user (e.g. waiting for the network)
+    InterventionNeeded,
+    Completed,
+    /// The run ended on a failure check, budget or watchdog
+    Failed,
+    /// An LLM-generated prompt was rejected for exceeding the risk threshold
+    RiskExceeded,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationChannel {
+    /// Alert tone through the audio notifier
+    Sound,
+    /// OS notification; clicking it focuses the app on the run's profile
+    Desktop,
+    /// Every Slack/Discord/Telegram webhook configured in settings
+    Webhook,
+    /// Run summary mailed through the SMTP server configured in settings
+    Email,
+    /// Every notifier of the loaded plugins
+    Plugin,
+}
+
+/// Daily span of local time in which a profile may run
+///
+/// An `end` earlier than `start` crosses midnight: `22:00`–`06:00` opens on each
+/// listed day at 22:00 and closes the next morning. Equal times span the whole day.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunWindow {
+    /// Days the window opens on; empty means every day
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub days: Vec<Weekday>,
+    /// Opening time, `HH:MM`
+    pub start: String,
+    /// Closing time, `HH:MM`
+    pub end: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Weekday {
+    Mon,
+    Tue,
+    Wed,
+    Thu,
+    Fri,
+    Sat,
+    Sun,
+}
+
+/// Personal data the privacy filter recognizes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PiiKind {
+    Email,
+    /// 13 to 19 digits passing the Luhn check, also split into groups
+    CreditCard,
+    /// Tokens of well-known providers (`sk-…`, `ghp_…`, `AKIA…`) and long mixed-case strings
+    ApiKey,
+}
+
+impl PiiKind {
+    pub const ALL: [PiiKind; 3] = [PiiKind::Email, PiiKind::CreditCard, PiiKind::ApiKey];
+}
+
+/// What a profile's privacy filter masks; see `privacy`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrivacyConfig {
+    #[serde(default = "PrivacyConfig::default_detectors")]
+    pub detectors: Vec<PiiKind>,
+}
+
+impl PrivacyConfig {
+    fn default_detectors() -> Vec<PiiKind> {
+        PiiKind::ALL.to_vec()
+    }
+}
+
+impl Default for PrivacyConfig {
+    fn default() -> Self {
+        Self {
+            detectors: Self::default_detectors(),
+        }
+    }
+}
+
+/// What to say, after the wake word, to start or stop a profile; see `voice`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VoicePhrases {
+    /// Starts the profile, or resumes it when it is running and paused
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start: Option<String>,
+    /// Stops the profile when it is running
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop: Option<String>,
+}
+
+/// How a profile's region frames are archived; see `timelapse`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrameArchiveConfig {
+    /// Longest side of an archived frame in pixels; larger regions are scaled down
+    #[serde(default = "FrameArchiveConfig::default_max_size")]
+    pub max_size: u32,
+    /// Iterations archived per run; later ones are not
+    #[serde(default = "FrameArchiveConfig::default_max_frames")]
+    pub max_frames: u32,
+}
+
+impl FrameArchiveConfig {
+    fn default_max_size() -> u32 {
+        480
+    }
+
+    fn default_max_frames() -> u32 {
+        500
+    }
+}
+
+impl Default for FrameArchiveConfig {
+    fn default() -> Self {
+        Self {
+            max_size: Self::default_max_size(),
+            max_frames: Self::default_max_frames(),
+        }
+    }
+}
+
+/// Follow-up profile started when a run ends
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileHandoff {
+    pub profile_id: String,
+    /// Context variables copied into the follow-up run
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pass_variables: Vec<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TriggerConfig {
     pub r#type: String,
     pub check_interval_sec: f64,
+    /// Global hotkey (e.g. "Ctrl+Alt+D") that starts the profile; used by `HotkeyTrigger`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hotkey: Option<String>,
+    /// Plugin trigger asked once per interval; used by `PluginTrigger`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plugin: Option<PluginTriggerConfig>,
+}
+
+/// A trigger provided by a plugin; see `plugins`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginTriggerConfig {
+    /// Id of the plugin, as in its manifest
+    pub plugin: String,
+    /// Trigger the plugin registered
+    pub kind: String,
+    /// Handed to the plugin as is
+    #[serde(default)]
+    pub config: serde_json::Value,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -348,6 +1040,21 @@ pub enum ActionConfig {
     Type {
         text: String,
     },
+    /// Click the first UI element matching `element`, looked up when the action runs
+    ClickElement {
+        element: ElementQuery,
+        button: MouseButton,
+        /// Use the element's default accessibility action instead of the mouse
+        #[serde(default)]
+        invoke: bool,
+    },
+    /// Store the text or value of the first UI element matching `element` in a variable
+    ReadElementText {
+        element: ElementQuery,
+        /// Variable name to store the text (default: "element_text")
+        #[serde(default)]
+        variable_name: Option<String>,
+    },
     LLMPromptGeneration {
         /// Region IDs to capture and send to LLM
         region_ids: Vec<String>,
@@ -375,6 +1082,16 @@ pub enum ActionConfig {
         /// Regex pattern or logic expression for termination condition
         termination_condition: String,
     },
+    /// An action provided by a plugin; see `plugins`
+    Plugin {
+        /// Id of the plugin, as in its manifest
+        plugin: String,
+        /// Action the plugin registered
+        kind: String,
+        /// Handed to the plugin as is
+        #[serde(default)]
+        config: serde_json::Value,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -382,9 +1099,18 @@ pub struct GuardrailsConfig {
     pub max_runtime_ms: Option<u64>,
     pub max_activations_per_hour: Option<u32>,
     pub cooldown_ms: u64,
+    /// Budget: stop after this many action-sequence iterations
+    #[serde(default)]
+    pub max_iterations: Option<u32>,
+    /// Budget: stop after this many LLM action failures in a row
+    #[serde(default)]
+    pub max_consecutive_llm_failures: Option<u32>,
     /// Maximum time without action progress before watchdog triggers (heartbeat pattern)
     #[serde(default)]
     pub heartbeat_timeout_ms: Option<u64>,
+    /// Longest a single action may run before the watchdog abandons it
+    #[serde(default)]
+    pub action_timeout_ms: Option<u64>,
     /// OCR mode for termination pattern matching
     #[serde(default)]
     pub ocr_mode: OcrMode,
@@ -429,6 +1155,18 @@ pub struct LLMPromptResponse {
     /// Human-readable explanation of why task is complete (if task_complete is true)
     #[serde(default)]
     pub task_complete_reason: Option<String>,
+    /// Tokens the provider billed for this answer; never part of the model's JSON
+    #[serde(skip)]
+    pub usage: Option<LlmUsage>,
+}
+
+/// Tokens billed for one LLM answer, retries included
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct LlmUsage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+    /// Estimated price in US dollars; None when the model's price is unknown
+    pub cost_usd: Option<f64>,
 }
 
 impl LLMPromptResponse {
@@ -441,6 +1179,7 @@ impl LLMPromptResponse {
             continuation_prompt_risk: risk,
             task_complete: false,
             task_complete_reason: None,
+            usage: None,
         }
     }
     
@@ -453,6 +1192,7 @@ impl LLMPromptResponse {
             continuation_prompt_risk: 0.0,
             task_complete: true,
             task_complete_reason: Some(reason),
+            usage: None,
         }
     }
     
@@ -465,6 +1205,7 @@ impl LLMPromptResponse {
             continuation_prompt_risk: risk,
             task_complete: false,
             task_complete_reason: None,
+            usage: None,
         }
     }
 }
@@ -482,6 +1223,14 @@ impl BackendError {
             message: message.into(),
         }
     }
+
+    /// The same error, its message prefixed with `what` was being done
+    pub fn context(self, what: &str) -> Self {
+        Self {
+            code: self.code,
+            message: format!("{}: {}", what, self.message),
+        }
+    }
 }
 
 impl std::fmt::Display for BackendError {
@@ -0,0 +1,185 @@
+/// SMTP email notifications with a run summary
+///
+/// The summary names the profile and reports the outcome, iteration count,
+/// duration and the LLM's last stated reason, optionally with a screenshot of
+/// the primary display attached. The SMTP settings, password included, live in
+/// secure storage. Mail is sent on a background thread so a slow server never
+/// holds up a run.
+use serde::{Deserialize, Serialize};
+
+use crate::domain::NotificationEvent;
+use crate::notify;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpSecurity {
+    /// Upgrade a plain connection with STARTTLS (usually port 587)
+    #[default]
+    StartTls,
+    /// TLS from the first byte (usually port 465)
+    Tls,
+    /// Unencrypted; only for relays on the local network
+    None,
+}
+
+impl SmtpSecurity {
+    pub fn default_port(self) -> u16 {
+        match self {
+            SmtpSecurity::StartTls => 587,
+            SmtpSecurity::Tls => 465,
+            SmtpSecurity::None => 25,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SmtpConfig {
+    pub host: String,
+    /// 0 uses the default port of `security`
+    #[serde(default)]
+    pub port: u16,
+    #[serde(default)]
+    pub security: SmtpSecurity,
+    /// Login user; empty sends without authentication
+    #[serde(default)]
+    pub username: String,
+    #[serde(default)]
+    pub password: String,
+    pub from: String,
+    pub to: Vec<String>,
+    /// Attach a screenshot of the primary display
+    #[serde(default)]
+    pub include_screenshot: bool,
+}
+
+impl SmtpConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.host.trim().is_empty() {
+            return Err("SMTP server host is required".into());
+        }
+        check_address(&self.from).map_err(|e| format!("From: {}", e))?;
+        if self.to.is_empty() {
+            return Err("At least one recipient is required".into());
+        }
+        for to in &self.to {
+            check_address(to).map_err(|e| format!("To: {}", e))?;
+        }
+        Ok(())
+    }
+
+    pub fn effective_port(&self) -> u16 {
+        match self.port {
+            0 => self.security.default_port(),
+            port => port,
+        }
+    }
+}
+
+/// Accepts `user@host` or `Name <user@host>`; the server has the final say
+fn check_address(address: &str) -> Result<(), String> {
+    let address = address.trim();
+    let addr = match (address.find('<'), address.strip_suffix('>')) {
+        (Some(open), Some(rest)) => &rest[open + 1..],
+        _ => address,
+    };
+    match addr.split_once('@') {
+        Some((user, host)) if !user.is_empty() && !host.is_empty() && !addr.contains(char::is_whitespace) => Ok(()),
+        _ => Err(format!("'{}' is not an email address", address)),
+    }
+}
+
+/// What the email reports about a run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunSummary {
+    pub profile_name: String,
+    pub event: NotificationEvent,
+    /// Why the run ended, if it did
+    pub detail: Option<String>,
+    pub iterations: u32,
+    pub duration_ms: u64,
+    pub last_llm_reason: Option<String>,
+}
+
+impl RunSummary {
+    pub fn subject(&self) -> String {
+        let (title, body) = notify::desktop_message(self.event, &self.profile_name);
+        format!("{} - {}", title, body)
+    }
+
+    pub fn body(&self) -> String {
+        let (_, status) = notify::desktop_message(self.event, &self.profile_name);
+        let mut lines = vec![
+            format!("Profile: {}", self.profile_name),
+            match &self.detail {
+                Some(detail) => format!("Outcome: {} ({})", status, detail),
+                None => format!("Outcome: {}", status),
+            },
+            format!("Iterations: {}", self.iterations),
+            format!("Duration: {}", format_duration(self.duration_ms)),
+        ];
+        // An LLM completion is already the outcome reason
+        if let Some(reason) = self.last_llm_reason.as_ref().filter(|r| Some(*r) != self.detail.as_ref()) {
+            lines.push(format!("Last LLM reason: {}", reason));
+        }
+        lines.join("\n") + "\n"
+    }
+}
+
+/// `1h 02m 05s`, `4m 10s` or `12s`
+pub fn format_duration(ms: u64) -> String {
+    let secs = ms / 1000;
+    let (h, m, s) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if h > 0 {
+        format!("{}h {:02}m {:02}s", h, m, s)
+    } else if m > 0 {
+        format!("{}m {:02}s", m, s)
+    } else {
+        format!("{}s", s)
+    }
+}
+
+#[cfg(feature = "email-notifications")]
+pub fn send(config: &SmtpConfig, summary: &RunSummary, screenshot: Option<&[u8]>) -> Result<(), String> {
+    use lettre::message::{header::ContentType, Attachment, Mailbox, MultiPart, SinglePart};
+    use lettre::transport::smtp::authentication::Credentials;
+    use lettre::{Message, SmtpTransport, Transport};
+
+    let mailbox = |a: &str| a.trim().parse::<Mailbox>().map_err(|e| format!("Invalid address '{}': {}", a, e));
+    let mut message = Message::builder().from(mailbox(&config.from)?).subject(summary.subject());
+    for to in &config.to {
+        message = message.to(mailbox(to)?);
+    }
+    let text = SinglePart::plain(summary.body());
+    let email = match screenshot {
+        Some(png) => message.multipart(
+            MultiPart::mixed().singlepart(text).singlepart(
+                Attachment::new("screenshot.png".into()).body(png.to_vec(), ContentType::parse("image/png").unwrap()),
+            ),
+        ),
+        None => message.singlepart(text),
+    }
+    .map_err(|e| format!("Failed to build email: {}", e))?;
+
+    let host = config.host.trim();
+    let mut transport = match config.security {
+        SmtpSecurity::StartTls => SmtpTransport::starttls_relay(host),
+        SmtpSecurity::Tls => SmtpTransport::relay(host),
+        SmtpSecurity::None => Ok(SmtpTransport::builder_dangerous(host)),
+    }
+    .map_err(|e| format!("Invalid SMTP server '{}': {}", host, e))?
+    .port(config.effective_port())
+    .timeout(Some(std::time::Duration::from_secs(30)));
+    if !config.username.is_empty() {
+        transport = transport.credentials(Credentials::new(config.username.clone(), config.password.clone()));
+    }
+    transport
+        .build()
+        .send(&email)
+        .map(|_| ())
+        .map_err(|e| format!("Failed to send email: {}", e))
+}
+
+#[cfg(not(feature = "email-notifications"))]
+pub fn send(_config: &SmtpConfig, _summary: &RunSummary, _screenshot: Option<&[u8]>) -> Result<(), String> {
+    Err("This build cannot send email notifications".into())
+}
@@ -0,0 +1,128 @@
+/// The crate's error type for actions, automation, LLM calls and sound playback
+///
+/// Each variant says what kind of failure happened. Callers can use that to tell
+/// a failure worth retrying (a dropped connection, a rate limit) from one that will
+/// recur until the user changes something (a rejected API key, a missing feature).
+/// `Display` is the bare message; backend errors keep their `code: message` form.
+/// So event logs and history read as they did when these were strings.
+/// `code` gives a stable identifier from the same vocabulary as `BackendError`
+/// codes. Tauri commands return it serialized like a `BackendError`, as
+/// `{ code, message }`; helpers that still fail with a string become `Other`.
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+use crate::domain::BackendError;
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum Error {
+    /// Invalid profile or settings, missing credentials or features; retrying cannot help
+    #[error("{0}")]
+    Config(String),
+    /// Connection failure, timeout, rate limit or server error
+    #[error("{0}")]
+    Network(String),
+    /// The LLM answered, but not with anything usable
+    #[error("{0}")]
+    Llm(String),
+    /// Sending input to the desktop failed
+    #[error("{0}")]
+    Automation(String),
+    /// Playing a sound failed
+    #[error("{0}")]
+    Audio(String),
+    /// An action declined to go on, e.g. a prompt over the risk threshold
+    #[error("{0}")]
+    Action(String),
+    /// An action overran its deadline and was abandoned
+    #[error("{0}")]
+    Timeout(String),
+    /// An action was abandoned because the run was paused or stopped
+    #[error("{0}")]
+    Cancelled(String),
+    /// Failure reported by a platform backend
+    #[error(transparent)]
+    Backend(#[from] BackendError),
+    /// Any other failure, e.g. saving settings or an unknown profile
+    #[error("{0}")]
+    Other(String),
+}
+
+impl Error {
+    /// Stable identifier of the failure; backend errors keep their own code
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::Config(_) => "config_invalid",
+            Error::Network(_) => "network_failed",
+            Error::Llm(_) => "llm_bad_response",
+            Error::Automation(_) => "automation_failed",
+            Error::Audio(_) => "audio_failed",
+            Error::Action(_) => "action_failed",
+            Error::Timeout(_) => "action_timed_out",
+            Error::Cancelled(_) => "action_cancelled",
+            Error::Backend(e) => e.code,
+            Error::Other(_) => "failed",
+        }
+    }
+
+    /// The same error, its message prefixed with `what` was being done
+    pub fn context(self, what: &str) -> Self {
+        let prefix = |m: String| format!("{}: {}", what, m);
+        match self {
+            Error::Config(m) => Error::Config(prefix(m)),
+            Error::Network(m) => Error::Network(prefix(m)),
+            Error::Llm(m) => Error::Llm(prefix(m)),
+            Error::Automation(m) => Error::Automation(prefix(m)),
+            Error::Audio(m) => Error::Audio(prefix(m)),
+            Error::Action(m) => Error::Action(prefix(m)),
+            Error::Timeout(m) => Error::Timeout(prefix(m)),
+            Error::Cancelled(m) => Error::Cancelled(prefix(m)),
+            Error::Backend(e) => Error::Backend(e.context(what)),
+            Error::Other(m) => Error::Other(prefix(m)),
+        }
+    }
+
+    /// Whether the same call may succeed if made again unchanged
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Error::Network(_) | Error::Llm(_))
+    }
+}
+
+impl From<Error> for String {
+    fn from(e: Error) -> Self {
+        e.to_string()
+    }
+}
+
+impl From<String> for Error {
+    fn from(message: String) -> Self {
+        Error::Other(message)
+    }
+}
+
+impl From<&str> for Error {
+    fn from(message: &str) -> Self {
+        Error::Other(message.to_string())
+    }
+}
+
+impl From<Error> for BackendError {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::Backend(e) => e,
+            other => BackendError::new(other.code(), other.to_string()),
+        }
+    }
+}
+
+impl Serialize for Error {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let message = match self {
+            Error::Backend(e) => e.message.clone(),
+            other => other.to_string(),
+        };
+        let mut error = serializer.serialize_struct("Error", 2)?;
+        error.serialize_field("code", self.code())?;
+        error.serialize_field("message", &message)?;
+        error.end()
+    }
+}
@@ -0,0 +1,146 @@
+/// `${ENV_VAR}` and `~` expansion in profile string fields
+///
+/// Expansion happens when a profile is loaded for a run, never when it is saved,
+/// so the stored profile stays portable. Only variables named in the profile's
+/// `env_allowlist` may be read; a shared profile cannot pull arbitrary secrets
+/// from the environment into typed text or LLM prompts.
+///
+/// `{{secret:name}}` references to per-profile secrets are different: they stay
+/// in the profile and are filled only when a Type action executes, so the value
+/// never reaches a saved, exported or debugged profile.
+use std::path::Path;
+
+use regex::{Captures, Regex};
+
+use crate::domain::{ActionConfig, Profile};
+
+fn env_ref_regex() -> Regex {
+    Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)\}").expect("valid regex")
+}
+
+/// Names of the `${NAME}` references in `text`
+pub fn env_references(text: &str) -> Vec<String> {
+    env_ref_regex()
+        .captures_iter(text)
+        .map(|c| c[1].to_string())
+        .collect()
+}
+
+fn secret_ref_regex() -> Regex {
+    Regex::new(r"\{\{secret:([A-Za-z0-9_-]+)\}\}").expect("valid regex")
+}
+
+/// Names of the `{{secret:name}}` references in `text`
+pub fn secret_references(text: &str) -> Vec<String> {
+    secret_ref_regex()
+        .captures_iter(text)
+        .map(|c| c[1].to_string())
+        .collect()
+}
+
+/// Fill the `{{secret:name}}` references in `template` through `lookup`.
+/// `vars` expands only the text around them, so a variable's value (e.g. an
+/// LLM-generated prompt) can never name a secret to reveal.
+pub fn resolve_secrets(
+    template: &str,
+    vars: &dyn Fn(&str) -> String,
+    lookup: &dyn Fn(&str) -> Result<Option<String>, String>,
+) -> Result<String, String> {
+    let mut out = String::new();
+    let mut rest = 0;
+    for c in secret_ref_regex().captures_iter(template) {
+        let whole = c.get(0).expect("match");
+        out.push_str(&vars(&template[rest..whole.start()]));
+        let name = &c[1];
+        out.push_str(&lookup(name)?.ok_or_else(|| format!("Secret '{}' is not set for this profile", name))?);
+        rest = whole.end();
+    }
+    out.push_str(&vars(&template[rest..]));
+    Ok(out)
+}
+
+/// Expandable string fields of a profile, with their field paths
+pub fn string_fields_mut(profile: &mut Profile) -> Vec<(String, &mut String)> {
+    let mut fields = Vec::new();
+    for (i, action) in profile.actions.iter_mut().enumerate() {
+        match action {
+            ActionConfig::Type { text } => fields.push((format!("actions[{}].text", i), text)),
+            ActionConfig::LLMPromptGeneration {
+                system_prompt: Some(prompt),
+                ..
+            } => fields.push((format!("actions[{}].system_prompt", i), prompt)),
+            ActionConfig::TerminationCheck {
+                ai_query_prompt,
+                termination_condition,
+                ..
+            } => {
+                if let Some(prompt) = ai_query_prompt {
+                    fields.push((format!("actions[{}].ai_query_prompt", i), prompt));
+                }
+                fields.push((format!("actions[{}].termination_condition", i), termination_condition));
+            }
+            _ => {}
+        }
+    }
+    if let Some(g) = &mut profile.guardrails {
+        for (i, k) in g.success_keywords.iter_mut().enumerate() {
+            fields.push((format!("guardrails.success_keywords[{}]", i), k));
+        }
+        for (i, k) in g.failure_keywords.iter_mut().enumerate() {
+            fields.push((format!("guardrails.failure_keywords[{}]", i), k));
+        }
+        if let Some(pattern) = &mut g.ocr_termination_pattern {
+            fields.push(("guardrails.ocr_termination_pattern".into(), pattern));
+        }
+    }
+    fields
+}
+
+/// Expand one string: a leading `~` becomes `home`, `${NAME}` the allowlisted variable's value
+pub fn expand_str(
+    text: &str,
+    allowlist: &[String],
+    lookup: &dyn Fn(&str) -> Option<String>,
+    home: Option<&Path>,
+) -> Result<String, String> {
+    let mut error = None;
+    let expanded = env_ref_regex().replace_all(text, |c: &Captures| {
+        let name = &c[1];
+        if !allowlist.iter().any(|a| a == name) {
+            error.get_or_insert_with(|| format!("${{{}}} is not in the profile's env_allowlist", name));
+            return String::new();
+        }
+        lookup(name).unwrap_or_else(|| {
+            error.get_or_insert_with(|| format!("Environment variable {} is not set", name));
+            String::new()
+        })
+    });
+    if let Some(e) = error {
+        return Err(e);
+    }
+    if text == "~" || text.starts_with("~/") {
+        let home = home.ok_or_else(|| "Cannot expand '~': home directory unknown".to_string())?;
+        return Ok(format!("{}{}", home.display(), &expanded[1..]));
+    }
+    Ok(expanded.into_owned())
+}
+
+/// Copy of `profile` with every string field expanded; `lookup` resolves environment variables
+pub fn expand_profile_with(
+    profile: &Profile,
+    lookup: &dyn Fn(&str) -> Option<String>,
+    home: Option<&Path>,
+) -> Result<Profile, String> {
+    let mut expanded = profile.clone();
+    let allowlist = profile.env_allowlist.clone();
+    for (field, value) in string_fields_mut(&mut expanded) {
+        *value = expand_str(value, &allowlist, lookup, home).map_err(|e| format!("{}: {}", field, e))?;
+    }
+    Ok(expanded)
+}
+
+/// Expand against the process environment and the user's home directory
+pub fn expand_profile(profile: &Profile) -> Result<Profile, String> {
+    let home = dirs::home_dir();
+    expand_profile_with(profile, &|name| std::env::var(name).ok(), home.as_deref())
+}
@@ -1,5 +1,14 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use serde::{Deserialize, Serialize};
+
 use crate::domain::{
-    Automation, BackendError, DisplayInfo, MouseButton, Region, ScreenCapture, ScreenFrame,
+    Automation, BackendError, DisplayInfo, ElementFinder, ElementQuery, Error, MouseButton, Region,
+    ScreenCapture, ScreenFrame, UiElement, WindowEnumerator, WindowInfo,
 };
 
 pub struct FakeCapture;
@@ -42,18 +51,402 @@ impl ScreenCapture for FakeCapture {
     }
 }
 
+impl WindowEnumerator for FakeCapture {
+    fn windows(&self) -> Result<Vec<WindowInfo>, BackendError> {
+        Ok(vec![WindowInfo {
+            id: 1,
+            title: "Fake Editor".into(),
+            process_name: "fake-editor".into(),
+            pid: 4242,
+            x: 100,
+            y: 80,
+            width: 1280,
+            height: 800,
+            minimized: false,
+        }])
+    }
+}
+
+impl ElementFinder for FakeCapture {
+    fn find(&self, query: &ElementQuery) -> Result<Vec<UiElement>, BackendError> {
+        let element = |role: &str, name: &str, text: &str, x: i32, y: i32, width: u32| UiElement {
+            role: role.into(),
+            name: name.into(),
+            app: "Fake Editor".into(),
+            text: text.into(),
+            x,
+            y,
+            width,
+            height: 28,
+        };
+        let elements = [
+            element("button", "Run", "Run", 120, 100, 80),
+            element("button", "Stop", "Stop", 210, 100, 80),
+            element("text field", "Prompt", "All tests passed", 120, 140, 600),
+        ];
+        Ok(elements
+            .into_iter()
+            .filter(|e| query.matches_app(&e.app) && query.matches(&e.role, &e.name))
+            .collect())
+    }
+
+    fn invoke(&self, query: &ElementQuery) -> Result<UiElement, BackendError> {
+        self.find(query)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| BackendError::new("element_not_found", format!("no {} found", query)))
+    }
+}
+
 pub struct FakeAutomation;
 impl Automation for FakeAutomation {
-    fn move_cursor(&self, _x: u32, _y: u32) -> Result<(), String> {
+    fn move_cursor(&self, _x: u32, _y: u32) -> Result<(), Error> {
+        Ok(())
+    }
+    fn click(&self, _button: MouseButton) -> Result<(), Error> {
         Ok(())
     }
-    fn click(&self, _button: MouseButton) -> Result<(), String> {
+    fn type_text(&self, _text: &str) -> Result<(), Error> {
         Ok(())
     }
-    fn type_text(&self, _text: &str) -> Result<(), String> {
+    fn key(&self, _key: &str) -> Result<(), Error> {
         Ok(())
     }
-    fn key(&self, _key: &str) -> Result<(), String> {
+}
+
+/// Whether `LOOPAUTOMA_BACKEND=fake` replaces the OS backends
+pub fn selected() -> bool {
+    std::env::var("LOOPAUTOMA_BACKEND").ok().as_deref() == Some("fake")
+}
+
+/// The process's fake backend, scripted by `LOOPAUTOMA_FAKE_SCRIPT`
+///
+/// Capture and input share it, so a run's typing moves the script on.
+pub fn backend() -> FakeBackend {
+    static BACKEND: OnceLock<FakeBackend> = OnceLock::new();
+    BACKEND.get_or_init(FakeBackend::from_env).clone()
+}
+
+/// What the fake backend's virtual screen shows, read from a JSON file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FakeScript {
+    #[serde(default = "default_width")]
+    pub width: u32,
+    #[serde(default = "default_height")]
+    pub height: u32,
+    /// Shown in order; the last one stays unless `repeat`
+    #[serde(default)]
+    pub frames: Vec<FakeFrame>,
+    /// Start over after the last frame
+    #[serde(default)]
+    pub repeat: bool,
+}
+
+fn default_width() -> u32 {
+    1920
+}
+
+fn default_height() -> u32 {
+    1080
+}
+
+impl Default for FakeScript {
+    fn default() -> Self {
+        Self {
+            width: default_width(),
+            height: default_height(),
+            frames: Vec::new(),
+            repeat: false,
+        }
+    }
+}
+
+impl FakeScript {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        serde_json::from_str(&text).map_err(|e| format!("Invalid fake backend script {}: {}", path.display(), e))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FakeFrame {
+    /// RGB of every pixel no rectangle covers
+    #[serde(default)]
+    pub background: [u8; 3],
+    /// Painted in order, later ones on top
+    #[serde(default)]
+    pub rects: Vec<FakeRect>,
+    /// Region captures the frame is shown for
+    #[serde(default = "one")]
+    pub captures: u32,
+    /// Shown until the next injected input instead, however often it is captured
+    #[serde(default)]
+    pub until_input: bool,
+}
+
+fn one() -> u32 {
+    1
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FakeRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    pub color: [u8; 3],
+}
+
+/// An input the fake backend received, in the order it came
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "input", rename_all = "snake_case")]
+pub enum FakeInput {
+    MoveCursor { x: u32, y: u32 },
+    Click { button: MouseButton },
+    MouseDown { button: MouseButton },
+    MouseUp { button: MouseButton },
+    TypeText { text: String },
+    Key { key: String },
+    KeyDown { key: String },
+    KeyUp { key: String },
+}
+
+struct FakeState {
+    script: FakeScript,
+    frame: usize,
+    /// Captures of the current frame so far
+    shown: u32,
+    inputs: Vec<FakeInput>,
+}
+
+impl FakeState {
+    fn current(&self) -> Option<&FakeFrame> {
+        self.script.frames.get(self.frame)
+    }
+
+    fn advance(&mut self) {
+        self.shown = 0;
+        if self.frame + 1 < self.script.frames.len() {
+            self.frame += 1;
+        } else if self.script.repeat {
+            self.frame = 0;
+        }
+    }
+}
+
+/// Deterministic OS backend on an in-memory virtual screen
+///
+/// Every region capture shows the script's current frame and counts towards
+/// how long it is shown, so a run sees the same pixels on every machine.
+/// Injected input is recorded, not performed, and can be appended to a JSON
+/// lines file (`LOOPAUTOMA_FAKE_INPUT_LOG`) for tests that drive the whole
+/// app. Clones share the screen and the recorded input.
+#[derive(Clone)]
+pub struct FakeBackend {
+    state: Arc<Mutex<FakeState>>,
+    input_log: Option<PathBuf>,
+}
+
+impl FakeBackend {
+    pub fn new(script: FakeScript) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(FakeState {
+                script,
+                frame: 0,
+                shown: 0,
+                inputs: Vec::new(),
+            })),
+            input_log: None,
+        }
+    }
+
+    /// Append every input to `path` as a JSON line
+    pub fn with_input_log(mut self, path: PathBuf) -> Self {
+        self.input_log = Some(path);
+        self
+    }
+
+    /// Script from `LOOPAUTOMA_FAKE_SCRIPT`, else a black screen; input logged to `LOOPAUTOMA_FAKE_INPUT_LOG`
+    pub fn from_env() -> Self {
+        let script = match std::env::var_os("LOOPAUTOMA_FAKE_SCRIPT") {
+            Some(path) => FakeScript::load(Path::new(&path)).unwrap_or_else(|e| {
+                tracing::warn!("{}; the fake screen stays black", e);
+                FakeScript::default()
+            }),
+            None => FakeScript::default(),
+        };
+        let backend = Self::new(script);
+        match std::env::var_os("LOOPAUTOMA_FAKE_INPUT_LOG") {
+            Some(path) => backend.with_input_log(PathBuf::from(path)),
+            None => backend,
+        }
+    }
+
+    /// Everything injected so far; the app only writes it to the input log
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn inputs(&self) -> Vec<FakeInput> {
+        self.state.lock().unwrap().inputs.clone()
+    }
+
+    /// Index of the frame the next capture shows
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn frame_index(&self) -> usize {
+        self.state.lock().unwrap().frame
+    }
+
+    /// The pixels of `region` in the current frame, counted as one capture
+    fn render(&self, region: &Region) -> ScreenFrame {
+        let mut state = self.state.lock().unwrap();
+        let (width, height) = (region.rect.width.max(1), region.rect.height.max(1));
+        let mut bytes = vec![0; (width * height * 4) as usize];
+        let (screen_w, screen_h) = (state.script.width, state.script.height);
+        let frame = state.current();
+        let background = frame.map(|f| f.background).unwrap_or_default();
+        let rects = frame.map(|f| f.rects.as_slice()).unwrap_or_default();
+        for row in 0..height {
+            for col in 0..width {
+                let (x, y) = (region.rect.x + col, region.rect.y + row);
+                let rgb = if x >= screen_w || y >= screen_h {
+                    [0, 0, 0]
+                } else {
+                    rects
+                        .iter()
+                        .rev()
+                        .find(|r| x >= r.x && y >= r.y && x - r.x < r.width && y - r.y < r.height)
+                        .map_or(background, |r| r.color)
+                };
+                let i = ((row * width + col) * 4) as usize;
+                bytes[i..i + 4].copy_from_slice(&[rgb[0], rgb[1], rgb[2], 255]);
+            }
+        }
+        if let Some(frame) = state.current() {
+            let last = frame.captures.max(1);
+            let until_input = frame.until_input;
+            state.shown += 1;
+            if !until_input && state.shown >= last {
+                state.advance();
+            }
+        }
+        ScreenFrame {
+            display: self.display(screen_w, screen_h),
+            width,
+            height,
+            stride: width * 4,
+            bytes,
+            timestamp_ms: 0,
+        }
+    }
+
+    fn display(&self, width: u32, height: u32) -> DisplayInfo {
+        DisplayInfo {
+            id: 0,
+            name: Some("fake".into()),
+            x: 0,
+            y: 0,
+            width,
+            height,
+            scale_factor: 1.0,
+            is_primary: true,
+        }
+    }
+
+    fn record(&self, input: FakeInput) -> Result<(), Error> {
+        let mut state = self.state.lock().unwrap();
+        if state.current().is_some_and(|f| f.until_input) {
+            state.advance();
+        }
+        state.inputs.push(input.clone());
+        drop(state);
+        if let Some(path) = &self.input_log {
+            let line = serde_json::to_string(&input).map_err(|e| Error::Action(e.to_string()))?;
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .and_then(|mut file| writeln!(file, "{}", line))
+                .map_err(|e| Error::Action(format!("Failed to log fake input to {}: {}", path.display(), e)))?;
+        }
         Ok(())
     }
 }
+
+fn hash_frame(frame: &ScreenFrame, downscale: u32) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    (frame.width, frame.height).hash(&mut hasher);
+    for pixel in frame.bytes.chunks_exact(4).step_by(downscale.max(1) as usize) {
+        pixel.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+impl ScreenCapture for FakeBackend {
+    fn hash_region(&self, region: &Region, downscale: u32) -> u64 {
+        hash_frame(&self.render(region), downscale)
+    }
+
+    fn capture_region(&self, region: &Region) -> Result<ScreenFrame, BackendError> {
+        Ok(self.render(region))
+    }
+
+    fn capture_and_hash(&self, region: &Region, downscale: u32) -> Result<(ScreenFrame, u64), BackendError> {
+        let frame = self.render(region);
+        let hash = hash_frame(&frame, downscale);
+        Ok((frame, hash))
+    }
+
+    fn displays(&self) -> Result<Vec<DisplayInfo>, BackendError> {
+        let state = self.state.lock().unwrap();
+        Ok(vec![self.display(state.script.width, state.script.height)])
+    }
+}
+
+impl Automation for FakeBackend {
+    fn move_cursor(&self, x: u32, y: u32) -> Result<(), Error> {
+        self.record(FakeInput::MoveCursor { x, y })
+    }
+    fn click(&self, button: MouseButton) -> Result<(), Error> {
+        self.record(FakeInput::Click { button })
+    }
+    fn type_text(&self, text: &str) -> Result<(), Error> {
+        self.record(FakeInput::TypeText { text: text.into() })
+    }
+    fn key(&self, key: &str) -> Result<(), Error> {
+        self.record(FakeInput::Key { key: key.into() })
+    }
+    fn mouse_down(&self, button: MouseButton) -> Result<(), Error> {
+        self.record(FakeInput::MouseDown { button })
+    }
+    fn mouse_up(&self, button: MouseButton) -> Result<(), Error> {
+        self.record(FakeInput::MouseUp { button })
+    }
+    fn key_down(&self, key: &str) -> Result<(), Error> {
+        self.record(FakeInput::KeyDown { key: key.into() })
+    }
+    fn key_up(&self, key: &str) -> Result<(), Error> {
+        self.record(FakeInput::KeyUp { key: key.into() })
+    }
+    fn cursor_position(&self) -> Result<Option<(i32, i32)>, Error> {
+        let state = self.state.lock().unwrap();
+        let last = state.inputs.iter().rev().find_map(|input| match input {
+            FakeInput::MoveCursor { x, y } => Some((*x as i32, *y as i32)),
+            _ => None,
+        });
+        Ok(Some(last.unwrap_or((0, 0))))
+    }
+}
+
+impl WindowEnumerator for FakeBackend {
+    fn windows(&self) -> Result<Vec<WindowInfo>, BackendError> {
+        FakeCapture.windows()
+    }
+}
+
+impl ElementFinder for FakeBackend {
+    fn find(&self, query: &ElementQuery) -> Result<Vec<UiElement>, BackendError> {
+        FakeCapture.find(query)
+    }
+
+    fn invoke(&self, query: &ElementQuery) -> Result<UiElement, BackendError> {
+        FakeCapture.invoke(query)
+    }
+}
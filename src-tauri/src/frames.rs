@@ -0,0 +1,147 @@
+/// Reuse of the frames the change check captured
+///
+/// Every tick the condition hashes each region, which takes a full capture.
+/// When the change fires, the LLM actions would capture the same regions again.
+/// During the check, the monitor routes hashing through `Recording`, which keeps
+/// each captured frame in the profile's `FrameCache`. The actions capture through
+/// `CachedCapture`, which serves those frames instead of capturing again. The
+/// cache is emptied after every action, because input may have changed the
+/// screen, and entries older than `FRAME_MAX_AGE` are never served.
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::domain::{BackendError, DisplayInfo, Rect, Region, ScreenCapture, ScreenFrame};
+
+/// Longest time a recorded frame may be served after its capture
+pub const FRAME_MAX_AGE: Duration = Duration::from_secs(2);
+
+struct CachedFrame {
+    frame: ScreenFrame,
+    hash: u64,
+    downscale: u32,
+    captured_at: Instant,
+}
+
+/// Last captured frame per screen rectangle
+pub struct FrameCache {
+    frames: Mutex<HashMap<Rect, CachedFrame>>,
+    max_age: Duration,
+}
+
+impl Default for FrameCache {
+    fn default() -> Self {
+        Self::new(FRAME_MAX_AGE)
+    }
+}
+
+impl FrameCache {
+    pub fn new(max_age: Duration) -> Self {
+        Self {
+            frames: Mutex::new(HashMap::new()),
+            max_age,
+        }
+    }
+
+    fn store(&self, region: &Region, frame: ScreenFrame, hash: u64, downscale: u32) {
+        let entry = CachedFrame {
+            frame,
+            hash,
+            downscale,
+            captured_at: Instant::now(),
+        };
+        self.frames.lock().unwrap().insert(region.rect, entry);
+    }
+
+    fn with_fresh<T>(&self, region: &Region, f: impl FnOnce(&CachedFrame) -> Option<T>) -> Option<T> {
+        let frames = self.frames.lock().unwrap();
+        frames
+            .get(&region.rect)
+            .filter(|c| c.captured_at.elapsed() <= self.max_age)
+            .and_then(f)
+    }
+
+    /// The recorded frame of `region`, if still fresh
+    pub fn frame(&self, region: &Region) -> Option<ScreenFrame> {
+        self.with_fresh(region, |c| Some(c.frame.clone()))
+    }
+
+    /// The recorded hash of `region` at `downscale`, if still fresh
+    pub fn hash(&self, region: &Region, downscale: u32) -> Option<u64> {
+        self.with_fresh(region, |c| (c.downscale == downscale).then_some(c.hash))
+    }
+
+    pub fn clear(&self) {
+        self.frames.lock().unwrap().clear();
+    }
+
+    /// View of `inner` that always captures and records what it captured
+    pub fn recording<'a>(&'a self, inner: &'a dyn ScreenCapture) -> Recording<'a> {
+        Recording { inner, cache: self }
+    }
+}
+
+/// Captures through the wrapped backend and records every hashed frame
+pub struct Recording<'a> {
+    inner: &'a dyn ScreenCapture,
+    cache: &'a FrameCache,
+}
+
+impl ScreenCapture for Recording<'_> {
+    fn hash_region(&self, region: &Region, downscale: u32) -> u64 {
+        match self.inner.capture_and_hash(region, downscale) {
+            Ok((frame, hash)) => {
+                self.cache.store(region, frame, hash, downscale);
+                hash
+            }
+            // Backends hash to 0 or a placeholder when capture fails; keep their answer
+            Err(_) => self.inner.hash_region(region, downscale),
+        }
+    }
+
+    fn capture_region(&self, region: &Region) -> Result<ScreenFrame, BackendError> {
+        self.inner.capture_region(region)
+    }
+
+    fn capture_and_hash(&self, region: &Region, downscale: u32) -> Result<(ScreenFrame, u64), BackendError> {
+        let (frame, hash) = self.inner.capture_and_hash(region, downscale)?;
+        self.cache.store(region, frame.clone(), hash, downscale);
+        Ok((frame, hash))
+    }
+
+    fn displays(&self) -> Result<Vec<DisplayInfo>, BackendError> {
+        self.inner.displays()
+    }
+}
+
+/// Serves recorded frames and captures through the wrapped backend on a miss
+pub struct CachedCapture {
+    pub inner: Arc<dyn ScreenCapture + Send + Sync>,
+    pub cache: Arc<FrameCache>,
+}
+
+impl ScreenCapture for CachedCapture {
+    fn hash_region(&self, region: &Region, downscale: u32) -> u64 {
+        self.cache
+            .hash(region, downscale)
+            .unwrap_or_else(|| self.inner.hash_region(region, downscale))
+    }
+
+    fn capture_region(&self, region: &Region) -> Result<ScreenFrame, BackendError> {
+        match self.cache.frame(region) {
+            Some(frame) => Ok(frame),
+            None => self.inner.capture_region(region),
+        }
+    }
+
+    fn capture_and_hash(&self, region: &Region, downscale: u32) -> Result<(ScreenFrame, u64), BackendError> {
+        match (self.cache.frame(region), self.cache.hash(region, downscale)) {
+            (Some(frame), Some(hash)) => Ok((frame, hash)),
+            _ => self.inner.capture_and_hash(region, downscale),
+        }
+    }
+
+    fn displays(&self) -> Result<Vec<DisplayInfo>, BackendError> {
+        self.inner.displays()
+    }
+}
@@ -0,0 +1,764 @@
+/// Run history: one record per finished profile run, persisted in SQLite
+///
+/// Each run keeps its timing, outcome, iterations, LLM calls and estimated
+/// cost, the errors it reported, the screenshots archived for it, the region
+/// frames archived per iteration for `timelapse`, what its privacy filter
+/// masked, a timeline of its iterations for `report` and its `RunMetrics`,
+/// which `get_metrics` aggregates per profile. The database sits next to
+/// profiles.json and is bounded to the most recent runs;
+/// the UI can prune it further. A `run_history.json` written by older versions
+/// is imported once and kept as `run_history.json.bak`.
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{Event, RunOutcome};
+use crate::metrics::RunMetrics;
+use crate::privacy::MaskedPii;
+
+/// Maximum number of runs kept; the oldest are dropped first
+pub const MAX_RUN_HISTORY: usize = 1000;
+
+/// Errors stored per run; later ones are only counted
+pub const MAX_RUN_ERRORS: usize = 50;
+
+/// Iterations stored in a run's timeline; later ones are only counted
+pub const MAX_RUN_ITERATIONS: usize = 200;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunRecord {
+    /// Row id in the history database; 0 until recorded
+    #[serde(default)]
+    pub id: i64,
+    pub profile_id: String,
+    pub started_at_ms: u64,
+    pub ended_at_ms: u64,
+    /// Action-sequence runs executed before the run ended
+    pub iterations: u32,
+    pub outcome: RunOutcome,
+    /// LLM requests made during the run, failed ones included
+    #[serde(default)]
+    pub llm_calls: u32,
+    /// Estimated LLM spend in US dollars
+    #[serde(default)]
+    pub llm_cost_usd: f64,
+    /// Errors reported during the run, including ones beyond `MAX_RUN_ERRORS`
+    #[serde(default)]
+    pub error_count: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunError {
+    pub at_ms: u64,
+    pub message: String,
+}
+
+/// An action an iteration ran
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunAction {
+    pub action: String,
+    pub success: bool,
+}
+
+/// An LLM call an iteration made, with what the model answered
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunLlmCall {
+    pub action: String,
+    pub success: bool,
+    pub risk: Option<f64>,
+    /// The continuation prompt, also when its risk got it rejected
+    pub prompt: Option<String>,
+}
+
+/// One run of the action sequence
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunIteration {
+    pub iteration: u32,
+    pub started_at_ms: u64,
+    pub ended_at_ms: u64,
+    pub success: bool,
+    /// In order, up to the first failure
+    pub actions: Vec<RunAction>,
+    pub llm_calls: Vec<RunLlmCall>,
+}
+
+/// A frame of a region archived after an iteration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunFrame {
+    pub region_id: String,
+    pub iteration: u32,
+    pub at_ms: u64,
+    /// PNG in the screenshot archive
+    pub path: String,
+}
+
+/// A run with everything recorded about it, as returned by `get_run_details`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunDetails {
+    #[serde(flatten)]
+    pub record: RunRecord,
+    pub llm_prompt_tokens: u64,
+    pub llm_completion_tokens: u64,
+    pub errors: Vec<RunError>,
+    /// Paths of PNG screenshots archived for the run
+    pub screenshots: Vec<String>,
+    #[serde(default)]
+    pub metrics: RunMetrics,
+    /// The first `MAX_RUN_ITERATIONS` iterations
+    #[serde(default)]
+    pub timeline: Vec<RunIteration>,
+    /// Region frames, oldest first; only for profiles with a frame archive
+    #[serde(default)]
+    pub frames: Vec<RunFrame>,
+    /// Personal data the profile's privacy filter masked, counted per region and kind
+    #[serde(default)]
+    pub masked: Vec<MaskedPii>,
+    /// The iteration whose actions are running
+    #[serde(skip)]
+    current: Option<RunIteration>,
+}
+
+impl RunDetails {
+    pub fn new(record: RunRecord) -> Self {
+        Self {
+            record,
+            llm_prompt_tokens: 0,
+            llm_completion_tokens: 0,
+            errors: Vec::new(),
+            screenshots: Vec::new(),
+            metrics: RunMetrics::default(),
+            timeline: Vec::new(),
+            frames: Vec::new(),
+            masked: Vec::new(),
+            current: None,
+        }
+    }
+
+    /// Add what the privacy filter masked to the run's counts
+    pub fn add_masked(&mut self, masked: Vec<MaskedPii>) {
+        for m in masked {
+            match self.masked.iter_mut().find(|e| e.source == m.source && e.kind == m.kind) {
+                Some(entry) => entry.count += m.count,
+                None => self.masked.push(m),
+            }
+        }
+    }
+
+    /// Keep the error an event reports and follow the iterations for the timeline
+    pub fn observe(&mut self, at_ms: u64, event: &Event) {
+        match event {
+            Event::Error { message } => {
+                self.record.error_count += 1;
+                if self.errors.len() < MAX_RUN_ERRORS {
+                    self.errors.push(RunError {
+                        at_ms,
+                        message: message.clone(),
+                    });
+                }
+            }
+            Event::ActionStarted { .. } => {
+                self.current.get_or_insert_with(|| RunIteration {
+                    started_at_ms: at_ms,
+                    ..RunIteration::default()
+                });
+            }
+            Event::ActionCompleted { action, success } => {
+                if let Some(current) = &mut self.current {
+                    current.actions.push(RunAction {
+                        action: action.clone(),
+                        success: *success,
+                    });
+                }
+            }
+            Event::LlmCallFinished {
+                action,
+                success,
+                risk,
+                prompt,
+            } => {
+                if let Some(current) = &mut self.current {
+                    current.llm_calls.push(RunLlmCall {
+                        action: action.clone(),
+                        success: *success,
+                        risk: *risk,
+                        prompt: prompt.clone(),
+                    });
+                }
+            }
+            Event::IterationCompleted { iteration, success } => {
+                let mut done = self.current.take().unwrap_or(RunIteration {
+                    started_at_ms: at_ms,
+                    ..RunIteration::default()
+                });
+                done.iteration = *iteration;
+                done.ended_at_ms = at_ms;
+                done.success = *success;
+                if self.timeline.len() < MAX_RUN_ITERATIONS {
+                    self.timeline.push(done);
+                }
+            }
+            // A pause or stop cut the iteration short; like the monitor, leave it out
+            Event::MonitorStateChanged { .. } => self.current = None,
+            _ => {}
+        }
+    }
+}
+
+/// Filter for `list_runs`; runs come newest first
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunQuery {
+    #[serde(default)]
+    pub profile_id: Option<String>,
+    #[serde(default)]
+    pub limit: Option<u32>,
+    #[serde(default)]
+    pub offset: Option<u32>,
+}
+
+/// What `prune_runs` removes; a run matching either rule goes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct PruneRule {
+    /// Remove runs that ended before this time
+    #[serde(default)]
+    pub ended_before_ms: Option<u64>,
+    /// Remove all but this many most recent runs
+    #[serde(default)]
+    pub keep_latest: Option<u32>,
+}
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS runs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    profile_id TEXT NOT NULL,
+    started_at_ms INTEGER NOT NULL,
+    ended_at_ms INTEGER NOT NULL,
+    iterations INTEGER NOT NULL,
+    outcome TEXT NOT NULL,
+    llm_calls INTEGER NOT NULL DEFAULT 0,
+    llm_prompt_tokens INTEGER NOT NULL DEFAULT 0,
+    llm_completion_tokens INTEGER NOT NULL DEFAULT 0,
+    llm_cost_usd REAL NOT NULL DEFAULT 0,
+    error_count INTEGER NOT NULL DEFAULT 0
+);
+CREATE INDEX IF NOT EXISTS runs_by_start ON runs (started_at_ms);
+CREATE TABLE IF NOT EXISTS run_errors (
+    run_id INTEGER NOT NULL,
+    at_ms INTEGER NOT NULL,
+    message TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS run_errors_by_run ON run_errors (run_id);
+CREATE TABLE IF NOT EXISTS run_screenshots (
+    run_id INTEGER NOT NULL,
+    path TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS run_screenshots_by_run ON run_screenshots (run_id);
+CREATE TABLE IF NOT EXISTS run_iterations (
+    run_id INTEGER NOT NULL,
+    iteration INTEGER NOT NULL,
+    started_at_ms INTEGER NOT NULL,
+    ended_at_ms INTEGER NOT NULL,
+    success INTEGER NOT NULL,
+    actions TEXT NOT NULL,
+    llm_calls TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS run_iterations_by_run ON run_iterations (run_id);
+CREATE TABLE IF NOT EXISTS run_frames (
+    run_id INTEGER NOT NULL,
+    region_id TEXT NOT NULL,
+    iteration INTEGER NOT NULL,
+    at_ms INTEGER NOT NULL,
+    path TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS run_frames_by_run ON run_frames (run_id);
+CREATE TABLE IF NOT EXISTS run_masked (
+    run_id INTEGER NOT NULL,
+    source TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    count INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS run_masked_by_run ON run_masked (run_id);
+CREATE TABLE IF NOT EXISTS run_metrics (
+    run_id INTEGER PRIMARY KEY,
+    iterations INTEGER NOT NULL,
+    successful_iterations INTEGER NOT NULL,
+    iteration_ms REAL NOT NULL,
+    captures INTEGER NOT NULL,
+    capture_ms REAL NOT NULL,
+    llm_ms REAL NOT NULL,
+    interventions INTEGER NOT NULL
+);
+";
+
+/// Tables holding rows per run besides `runs`
+const RUN_TABLES: [&str; 6] = [
+    "run_errors",
+    "run_screenshots",
+    "run_metrics",
+    "run_iterations",
+    "run_frames",
+    "run_masked",
+];
+
+const RECORD_COLUMNS: &str =
+    "id, profile_id, started_at_ms, ended_at_ms, iterations, outcome, llm_calls, llm_cost_usd, error_count";
+
+pub struct RunHistory {
+    conn: Connection,
+    /// Where screenshots of runs are archived; None keeps none
+    screenshot_dir: Option<PathBuf>,
+}
+
+impl RunHistory {
+    /// Open (or create) the database at `path`, importing a legacy JSON history next to it
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let conn = Connection::open(path).map_err(|e| format!("Failed to open run history: {}", e))?;
+        let history = Self::init(conn, path.parent().map(|dir| dir.join("screenshots")))?;
+        let legacy = path.with_extension("json");
+        if legacy.exists() {
+            history.import_legacy(&legacy)?;
+        }
+        Ok(history)
+    }
+
+    /// History that lasts for this session only and archives no screenshots
+    pub fn in_memory() -> Result<Self, String> {
+        let conn = Connection::open_in_memory().map_err(|e| format!("Failed to open run history: {}", e))?;
+        Self::init(conn, None)
+    }
+
+    fn init(conn: Connection, screenshot_dir: Option<PathBuf>) -> Result<Self, String> {
+        conn.execute_batch(SCHEMA)
+            .map_err(|e| format!("Failed to create run history tables: {}", e))?;
+        Ok(Self { conn, screenshot_dir })
+    }
+
+    fn import_legacy(&self, legacy: &Path) -> Result<(), String> {
+        let records: Vec<RunRecord> = std::fs::read_to_string(legacy)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        for record in records {
+            self.insert(&RunDetails::new(record))?;
+        }
+        let mut backup = legacy.as_os_str().to_owned();
+        backup.push(".bak");
+        std::fs::rename(legacy, backup).map_err(|e| format!("Failed to move legacy run history aside: {}", e))
+    }
+
+    /// Store a finished run, then drop runs beyond `MAX_RUN_HISTORY`; returns the run id
+    pub fn record(&mut self, details: RunDetails) -> Result<i64, String> {
+        let id = self.insert(&details)?;
+        self.prune(PruneRule {
+            keep_latest: Some(MAX_RUN_HISTORY as u32),
+            ..Default::default()
+        })?;
+        Ok(id)
+    }
+
+    fn insert(&self, details: &RunDetails) -> Result<i64, String> {
+        let r = &details.record;
+        let outcome = serde_json::to_string(&r.outcome).map_err(|e| format!("Failed to serialize run outcome: {}", e))?;
+        let tx = self.conn.unchecked_transaction().map_err(db_error)?;
+        tx.execute(
+            "INSERT INTO runs (profile_id, started_at_ms, ended_at_ms, iterations, outcome, llm_calls,
+                llm_prompt_tokens, llm_completion_tokens, llm_cost_usd, error_count)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                r.profile_id,
+                r.started_at_ms as i64,
+                r.ended_at_ms as i64,
+                r.iterations,
+                outcome,
+                r.llm_calls,
+                details.llm_prompt_tokens as i64,
+                details.llm_completion_tokens as i64,
+                r.llm_cost_usd,
+                r.error_count,
+            ],
+        )
+        .map_err(db_error)?;
+        let id = tx.last_insert_rowid();
+        for e in &details.errors {
+            tx.execute(
+                "INSERT INTO run_errors (run_id, at_ms, message) VALUES (?1, ?2, ?3)",
+                params![id, e.at_ms as i64, e.message],
+            )
+            .map_err(db_error)?;
+        }
+        for path in &details.screenshots {
+            tx.execute("INSERT INTO run_screenshots (run_id, path) VALUES (?1, ?2)", params![id, path])
+                .map_err(db_error)?;
+        }
+        for it in &details.timeline {
+            tx.execute(
+                "INSERT INTO run_iterations (run_id, iteration, started_at_ms, ended_at_ms, success, actions, llm_calls)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    id,
+                    it.iteration,
+                    it.started_at_ms as i64,
+                    it.ended_at_ms as i64,
+                    it.success,
+                    serde_json::to_string(&it.actions).map_err(|e| e.to_string())?,
+                    serde_json::to_string(&it.llm_calls).map_err(|e| e.to_string())?,
+                ],
+            )
+            .map_err(db_error)?;
+        }
+        for f in &details.frames {
+            tx.execute(
+                "INSERT INTO run_frames (run_id, region_id, iteration, at_ms, path) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![id, f.region_id, f.iteration, f.at_ms as i64, f.path],
+            )
+            .map_err(db_error)?;
+        }
+        for m in &details.masked {
+            tx.execute(
+                "INSERT INTO run_masked (run_id, source, kind, count) VALUES (?1, ?2, ?3, ?4)",
+                params![id, m.source, serde_json::to_string(&m.kind).map_err(|e| e.to_string())?, m.count],
+            )
+            .map_err(db_error)?;
+        }
+        let m = &details.metrics;
+        tx.execute(
+            "INSERT INTO run_metrics (run_id, iterations, successful_iterations, iteration_ms, captures, capture_ms,
+                llm_ms, interventions)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                id,
+                m.iterations,
+                m.successful_iterations,
+                m.iteration_ms,
+                m.captures,
+                m.capture_ms,
+                m.llm_ms,
+                m.interventions,
+            ],
+        )
+        .map_err(db_error)?;
+        tx.commit().map_err(db_error)?;
+        Ok(id)
+    }
+
+    /// Runs matching `query`, newest first
+    pub fn list(&self, query: &RunQuery) -> Result<Vec<RunRecord>, String> {
+        let sql = format!(
+            "SELECT {} FROM runs WHERE (?1 IS NULL OR profile_id = ?1)
+             ORDER BY started_at_ms DESC, id DESC LIMIT ?2 OFFSET ?3",
+            RECORD_COLUMNS
+        );
+        let mut stmt = self.conn.prepare(&sql).map_err(db_error)?;
+        let limit = query.limit.map_or(-1, i64::from);
+        let offset = query.offset.unwrap_or(0);
+        let rows = stmt
+            .query_map(params![query.profile_id, limit, offset], read_record)
+            .map_err(db_error)?;
+        rows.collect::<Result<_, _>>().map_err(db_error)
+    }
+
+    /// All runs, oldest first
+    pub fn records(&self) -> Vec<RunRecord> {
+        let mut records = self.list(&RunQuery::default()).unwrap_or_else(|e| {
+            tracing::warn!("Failed to read run history: {}", e);
+            Vec::new()
+        });
+        records.reverse();
+        records
+    }
+
+    /// Everything recorded about run `id`
+    pub fn details(&self, id: i64) -> Result<Option<RunDetails>, String> {
+        let sql = format!(
+            "SELECT {}, llm_prompt_tokens, llm_completion_tokens FROM runs WHERE id = ?1",
+            RECORD_COLUMNS
+        );
+        let found = self
+            .conn
+            .query_row(&sql, [id], |row| {
+                Ok((read_record(row)?, row.get::<_, i64>(9)? as u64, row.get::<_, i64>(10)? as u64))
+            })
+            .optional()
+            .map_err(db_error)?;
+        let Some((record, llm_prompt_tokens, llm_completion_tokens)) = found else {
+            return Ok(None);
+        };
+        let mut stmt = self
+            .conn
+            .prepare("SELECT at_ms, message FROM run_errors WHERE run_id = ?1 ORDER BY rowid")
+            .map_err(db_error)?;
+        let errors = stmt
+            .query_map([id], |row| {
+                Ok(RunError {
+                    at_ms: row.get::<_, i64>(0)? as u64,
+                    message: row.get(1)?,
+                })
+            })
+            .map_err(db_error)?
+            .collect::<Result<_, _>>()
+            .map_err(db_error)?;
+        Ok(Some(RunDetails {
+            record,
+            llm_prompt_tokens,
+            llm_completion_tokens,
+            errors,
+            screenshots: self.screenshots_of(&[id])?,
+            metrics: self.metrics_of(id)?,
+            timeline: self.timeline_of(id)?,
+            frames: self.frames_of(id)?,
+            masked: self.masked_of(id)?,
+            current: None,
+        }))
+    }
+
+    fn frames_of(&self, id: i64) -> Result<Vec<RunFrame>, String> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT region_id, iteration, at_ms, path FROM run_frames WHERE run_id = ?1 ORDER BY rowid")
+            .map_err(db_error)?;
+        let rows = stmt
+            .query_map([id], |row| {
+                Ok(RunFrame {
+                    region_id: row.get(0)?,
+                    iteration: row.get(1)?,
+                    at_ms: row.get::<_, i64>(2)? as u64,
+                    path: row.get(3)?,
+                })
+            })
+            .map_err(db_error)?;
+        rows.collect::<Result<_, _>>().map_err(db_error)
+    }
+
+    fn masked_of(&self, id: i64) -> Result<Vec<MaskedPii>, String> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT source, kind, count FROM run_masked WHERE run_id = ?1 ORDER BY rowid")
+            .map_err(db_error)?;
+        let rows = stmt
+            .query_map([id], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get(2)?)))
+            .map_err(db_error)?;
+        rows.map(|row| {
+            let (source, kind, count) = row.map_err(db_error)?;
+            let kind = serde_json::from_str(&kind).map_err(|e| format!("Invalid masked kind '{}': {}", kind, e))?;
+            Ok(MaskedPii { source, kind, count })
+        })
+        .collect()
+    }
+
+    fn timeline_of(&self, id: i64) -> Result<Vec<RunIteration>, String> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT iteration, started_at_ms, ended_at_ms, success, actions, llm_calls
+                 FROM run_iterations WHERE run_id = ?1 ORDER BY rowid",
+            )
+            .map_err(db_error)?;
+        let rows = stmt
+            .query_map([id], |row| {
+                let (actions, llm_calls): (String, String) = (row.get(4)?, row.get(5)?);
+                Ok(RunIteration {
+                    iteration: row.get(0)?,
+                    started_at_ms: row.get::<_, i64>(1)? as u64,
+                    ended_at_ms: row.get::<_, i64>(2)? as u64,
+                    success: row.get(3)?,
+                    actions: serde_json::from_str(&actions).unwrap_or_default(),
+                    llm_calls: serde_json::from_str(&llm_calls).unwrap_or_default(),
+                })
+            })
+            .map_err(db_error)?;
+        rows.collect::<Result<_, _>>().map_err(db_error)
+    }
+
+    fn metrics_of(&self, id: i64) -> Result<RunMetrics, String> {
+        self.conn
+            .query_row(
+                "SELECT iterations, successful_iterations, iteration_ms, captures, capture_ms, llm_ms, interventions
+                 FROM run_metrics WHERE run_id = ?1",
+                [id],
+                |row| read_metrics(row, 0),
+            )
+            .optional()
+            .map(Option::unwrap_or_default)
+            .map_err(db_error)
+    }
+
+    /// Runs with their metrics, optionally of one profile and started since a time; runs without metrics count as zeros
+    pub fn runs_with_metrics(&self, profile_id: Option<&str>, since_ms: Option<u64>) -> Result<Vec<(RunRecord, RunMetrics)>, String> {
+        let sql = format!(
+            "SELECT r.*, m.iterations, m.successful_iterations, m.iteration_ms, m.captures, m.capture_ms, m.llm_ms,
+                m.interventions
+             FROM (SELECT {} FROM runs WHERE (?1 IS NULL OR profile_id = ?1) AND started_at_ms >= ?2) r
+             LEFT JOIN run_metrics m ON m.run_id = r.id
+             ORDER BY r.started_at_ms",
+            RECORD_COLUMNS
+        );
+        let mut stmt = self.conn.prepare(&sql).map_err(db_error)?;
+        let since = since_ms.map_or(0, |ms| ms as i64);
+        let rows = stmt
+            .query_map(params![profile_id, since], |row| {
+                let metrics = match row.get::<_, Option<u32>>(9)? {
+                    Some(_) => read_metrics(row, 9)?,
+                    None => RunMetrics::default(),
+                };
+                Ok((read_record(row)?, metrics))
+            })
+            .map_err(db_error)?;
+        rows.collect::<Result<_, _>>().map_err(db_error)
+    }
+
+    /// Delete the runs `rule` selects with their errors and archived screenshots; returns how many went
+    pub fn prune(&self, rule: PruneRule) -> Result<usize, String> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT id FROM runs WHERE ended_at_ms < ?1
+                 UNION SELECT id FROM (SELECT id FROM runs ORDER BY started_at_ms DESC, id DESC LIMIT -1 OFFSET ?2)",
+            )
+            .map_err(db_error)?;
+        let before = rule.ended_before_ms.map_or(i64::MIN, |ms| ms as i64);
+        let keep = rule.keep_latest.map_or(i64::MAX, i64::from);
+        let ids: Vec<i64> = stmt
+            .query_map(params![before, keep], |row| row.get(0))
+            .map_err(db_error)?
+            .collect::<Result<_, _>>()
+            .map_err(db_error)?;
+        if ids.is_empty() {
+            return Ok(0);
+        }
+        let screenshots = self.screenshots_of(&ids)?;
+        let frames = self.paths_in("run_frames", &ids)?;
+        let tx = self.conn.unchecked_transaction().map_err(db_error)?;
+        for id in &ids {
+            for table in RUN_TABLES {
+                tx.execute(&format!("DELETE FROM {} WHERE run_id = ?1", table), [id])
+                    .map_err(db_error)?;
+            }
+            tx.execute("DELETE FROM runs WHERE id = ?1", [id]).map_err(db_error)?;
+        }
+        tx.commit().map_err(db_error)?;
+        for path in screenshots.iter().chain(&frames) {
+            let _ = std::fs::remove_file(path);
+        }
+        // Each run's frames have a folder of their own
+        for path in &frames {
+            if let Some(dir) = Path::new(path).parent() {
+                let _ = std::fs::remove_dir(dir);
+            }
+        }
+        Ok(ids.len())
+    }
+
+    fn screenshots_of(&self, ids: &[i64]) -> Result<Vec<String>, String> {
+        self.paths_in("run_screenshots", ids)
+    }
+
+    /// The `path` column of `table` for the runs `ids`
+    fn paths_in(&self, table: &str, ids: &[i64]) -> Result<Vec<String>, String> {
+        let mut stmt = self
+            .conn
+            .prepare(&format!("SELECT path FROM {} WHERE run_id = ?1 ORDER BY rowid", table))
+            .map_err(db_error)?;
+        let mut paths = Vec::new();
+        for id in ids {
+            let rows = stmt.query_map([id], |row| row.get(0)).map_err(db_error)?;
+            for path in rows {
+                paths.push(path.map_err(db_error)?);
+            }
+        }
+        Ok(paths)
+    }
+
+    /// Write a PNG of a run to the screenshot archive; returns its path for `RunDetails::screenshots`
+    pub fn archive_screenshot(&self, profile_id: &str, started_at_ms: u64, png: &[u8]) -> Result<Option<String>, String> {
+        let Some(dir) = &self.screenshot_dir else {
+            return Ok(None);
+        };
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create screenshot archive: {}", e))?;
+        let path = dir.join(screenshot_file_name(profile_id, started_at_ms));
+        std::fs::write(&path, png).map_err(|e| format!("Failed to archive screenshot: {}", e))?;
+        Ok(Some(path.to_string_lossy().into_owned()))
+    }
+
+    /// Write a region frame of a run to its folder in the screenshot archive; returns its path for `RunDetails::frames`
+    pub fn archive_frame(
+        &self,
+        profile_id: &str,
+        started_at_ms: u64,
+        region_id: &str,
+        iteration: u32,
+        png: &[u8],
+    ) -> Result<Option<String>, String> {
+        let Some(dir) = &self.screenshot_dir else {
+            return Ok(None);
+        };
+        let dir = dir.join(format!("{}-{}", safe_file_name(profile_id), started_at_ms));
+        std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create frame archive: {}", e))?;
+        let path = dir.join(format!("{}-{:05}.png", safe_file_name(region_id), iteration));
+        std::fs::write(&path, png).map_err(|e| format!("Failed to archive frame: {}", e))?;
+        Ok(Some(path.to_string_lossy().into_owned()))
+    }
+}
+
+impl Default for RunHistory {
+    fn default() -> Self {
+        Self::in_memory().expect("SQLite can always open an in-memory database")
+    }
+}
+
+/// `<profile>-<start>.png`, with characters unsafe in file names replaced
+pub fn screenshot_file_name(profile_id: &str, started_at_ms: u64) -> String {
+    format!("{}-{}.png", safe_file_name(profile_id), started_at_ms)
+}
+
+fn safe_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
+
+fn read_record(row: &rusqlite::Row) -> rusqlite::Result<RunRecord> {
+    let outcome: String = row.get(5)?;
+    Ok(RunRecord {
+        id: row.get(0)?,
+        profile_id: row.get(1)?,
+        started_at_ms: row.get::<_, i64>(2)? as u64,
+        ended_at_ms: row.get::<_, i64>(3)? as u64,
+        iterations: row.get(4)?,
+        outcome: serde_json::from_str(&outcome).unwrap_or(RunOutcome::Stopped),
+        llm_calls: row.get(6)?,
+        llm_cost_usd: row.get(7)?,
+        error_count: row.get(8)?,
+    })
+}
+
+fn read_metrics(row: &rusqlite::Row, first: usize) -> rusqlite::Result<RunMetrics> {
+    Ok(RunMetrics {
+        iterations: row.get(first)?,
+        successful_iterations: row.get(first + 1)?,
+        iteration_ms: row.get(first + 2)?,
+        captures: row.get(first + 3)?,
+        capture_ms: row.get(first + 4)?,
+        llm_ms: row.get(first + 5)?,
+        interventions: row.get(first + 6)?,
+    })
+}
+
+fn db_error(e: rusqlite::Error) -> String {
+    format!("Run history database error: {}", e)
+}
+
+/// Default location: `<config dir>/loopautoma/run_history.db`
+pub fn default_history_path() -> Option<PathBuf> {
+    let app_dir = dirs::config_dir()?.join("loopautoma");
+    std::fs::create_dir_all(&app_dir).ok()?;
+    Some(app_dir.join("run_history.db"))
+}
+
+/// Milliseconds since the Unix epoch
+pub fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
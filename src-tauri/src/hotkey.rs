@@ -0,0 +1,161 @@
+/// Global hotkeys that launch profiles on demand
+///
+/// Profiles whose trigger type is `HotkeyTrigger` bind an accelerator string
+/// (e.g. "Ctrl+Alt+D") that starts the profile from anywhere on the desktop.
+/// Saving profiles only touches the bindings that changed, and keeps the
+/// previous ones when a new hotkey cannot be registered.
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::domain::Profile;
+
+pub const HOTKEY_TRIGGER: &str = "HotkeyTrigger";
+
+/// A shortcut and the id of the profile it starts
+pub type Binding = (Shortcut, String);
+
+/// Bindings registered with the global shortcut plugin
+static REGISTERED: Mutex<Vec<Binding>> = Mutex::new(Vec::new());
+
+/// Resolve the hotkey bindings of all hotkey-triggered profiles
+///
+/// Fails on missing, unparsable or duplicate hotkeys so a broken binding is
+/// reported when the profiles are saved rather than silently ignored.
+pub fn hotkey_bindings(profiles: &[Profile]) -> Result<Vec<Binding>, String> {
+    let mut bindings = Vec::new();
+    let mut owners: HashMap<Shortcut, &str> = HashMap::new();
+    for p in profiles.iter().filter(|p| p.trigger.r#type == HOTKEY_TRIGGER) {
+        let spec = p
+            .trigger
+            .hotkey
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| format!("Profile '{}' uses a hotkey trigger but has no hotkey", p.name))?;
+        let shortcut: Shortcut = spec
+            .parse()
+            .map_err(|e| format!("Profile '{}' has an invalid hotkey '{}': {}", p.name, spec, e))?;
+        if let Some(other) = owners.insert(shortcut, &p.name) {
+            return Err(format!(
+                "Hotkey '{}' is bound to both '{}' and '{}'",
+                spec, other, p.name
+            ));
+        }
+        bindings.push((shortcut, p.id.clone()));
+    }
+    Ok(bindings)
+}
+
+/// Where global shortcuts are registered
+pub trait ShortcutRegistry {
+    /// Start the profile `profile_id` when `shortcut` is pressed
+    fn register(&self, shortcut: Shortcut, profile_id: &str) -> Result<(), String>;
+    fn unregister(&self, shortcut: Shortcut) -> Result<(), String>;
+}
+
+/// Change the registered bindings from `current` to `wanted`
+///
+/// Shortcuts that are free are registered before any old binding is removed,
+/// and bindings in both lists are left alone. When a step fails, what was
+/// changed is undone so `current` stays registered, and the error is returned.
+pub fn update_bindings(registry: &dyn ShortcutRegistry, current: &[Binding], wanted: &[Binding]) -> Result<(), String> {
+    let removed: Vec<&Binding> = current.iter().filter(|b| !wanted.contains(b)).collect();
+    // A shortcut moving to another profile can only be registered once its old binding is gone
+    let (moved, fresh): (Vec<&Binding>, Vec<&Binding>) = wanted
+        .iter()
+        .filter(|b| !current.contains(b))
+        .partition(|(shortcut, _)| current.iter().any(|(s, _)| s == shortcut));
+
+    let mut registered = Vec::new();
+    let mut released = Vec::new();
+    let result = apply(registry, &fresh, &removed, &moved, &mut registered, &mut released);
+    if result.is_err() {
+        for shortcut in registered {
+            if let Err(e) = registry.unregister(shortcut) {
+                tracing::warn!("Failed to undo hotkey registration: {}", e);
+            }
+        }
+        for (shortcut, profile_id) in released {
+            if let Err(e) = registry.register(*shortcut, profile_id) {
+                tracing::warn!("Failed to restore hotkey of profile '{}': {}", profile_id, e);
+            }
+        }
+    }
+    result
+}
+
+/// The steps of `update_bindings`, noting what they changed in `registered` and `released`
+fn apply<'a>(
+    registry: &dyn ShortcutRegistry,
+    fresh: &[&Binding],
+    removed: &[&'a Binding],
+    moved: &[&Binding],
+    registered: &mut Vec<Shortcut>,
+    released: &mut Vec<&'a Binding>,
+) -> Result<(), String> {
+    for (shortcut, profile_id) in fresh {
+        registry.register(*shortcut, profile_id)?;
+        registered.push(*shortcut);
+    }
+    for binding in removed {
+        registry.unregister(binding.0)?;
+        released.push(binding);
+    }
+    for (shortcut, profile_id) in moved {
+        registry.register(*shortcut, profile_id)?;
+        registered.push(*shortcut);
+    }
+    Ok(())
+}
+
+/// The app's global shortcut plugin, calling `on_press` with the profile id
+struct PluginRegistry<'a, F> {
+    app: &'a tauri::AppHandle,
+    on_press: F,
+}
+
+impl<F> ShortcutRegistry for PluginRegistry<'_, F>
+where
+    F: Fn(&tauri::AppHandle, &str) + Clone + Send + Sync + 'static,
+{
+    fn register(&self, shortcut: Shortcut, profile_id: &str) -> Result<(), String> {
+        let on_press = self.on_press.clone();
+        let profile_id = profile_id.to_string();
+        self.app
+            .global_shortcut()
+            .on_shortcut(shortcut, move |app, _shortcut, event| {
+                if event.state == ShortcutState::Pressed {
+                    on_press(app, &profile_id);
+                }
+            })
+            .map_err(|e| format!("Failed to register hotkey: {}", e))
+    }
+
+    fn unregister(&self, shortcut: Shortcut) -> Result<(), String> {
+        self.app
+            .global_shortcut()
+            .unregister(shortcut)
+            .map_err(|e| format!("Failed to unregister hotkey: {}", e))
+    }
+}
+
+/// Register the bindings of `profiles` in place of the previous ones
+///
+/// `on_press` receives the id of the profile whose hotkey was pressed. On an
+/// error the previous bindings stay registered.
+pub fn register_profile_hotkeys<F>(
+    app: &tauri::AppHandle,
+    profiles: &[Profile],
+    on_press: F,
+) -> Result<(), String>
+where
+    F: Fn(&tauri::AppHandle, &str) + Clone + Send + Sync + 'static,
+{
+    let wanted = hotkey_bindings(profiles)?;
+    let mut registered = REGISTERED.lock().unwrap();
+    update_bindings(&PluginRegistry { app, on_press }, &registered, &wanted)?;
+    *registered = wanted;
+    Ok(())
+}
@@ -1,29 +1,74 @@
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 mod action;
+mod anchor;
 mod audio;
+mod calibration;
 mod condition;
+mod credentials;
+mod debugger;
 pub mod domain;
+mod email;
+mod error;
+mod expand;
+mod frames;
+mod history;
+mod hotkey;
 mod llm;
+mod logging;
+mod mcp;
+mod metrics;
+mod migration;
 mod monitor;
+mod network;
+mod notify;
+mod otel;
+mod plugins;
+mod privacy;
+mod recovery;
+mod region_picker;
+mod remote;
+mod report;
+mod run_loop;
+mod runner_state;
+mod runtime;
+mod schedule;
 
 use domain::OcrMode;
 mod secure_storage;
+mod settings;
+mod shutdown;
 #[cfg(any(
     feature = "os-linux-capture-xcap",
     feature = "os-linux-automation",
+    feature = "os-linux-accessibility",
     feature = "os-macos",
     feature = "os-windows"
 ))]
 pub mod os;
+mod profile_crypto;
+mod preview;
+mod profile_io;
+// Collected either way; only the endpoint renders them
+#[cfg_attr(not(feature = "prometheus-metrics"), allow(dead_code))]
+mod prometheus;
 mod soak;
+mod sync;
+mod templates;
 #[cfg(test)]
 mod tests;
+mod timelapse;
+mod tray;
 mod trigger;
+mod validation;
+mod voice;
+mod watchdog;
+mod webhooks;
 
+use std::collections::{BTreeMap, HashMap};
 use std::io::Cursor;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
 use base64::engine::general_purpose::STANDARD as Base64Standard;
 use base64::Engine as _;
@@ -31,12 +76,12 @@ use domain::*;
 use image::imageops::FilterType;
 use image::{DynamicImage, ImageOutputFormat, RgbaImage};
 use tauri::Emitter; // for Window.emit
-use tauri::Manager;
+use tauri::{Listener, Manager};
 mod fakes;
 use fakes::{FakeAutomation, FakeCapture};
 use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
 pub use soak::{run_soak, SoakConfig, SoakReport};
-use std::env;
 
 fn default_profile() -> Profile {
     Profile {
@@ -52,6 +97,7 @@ fn default_profile() -> Profile {
                     height: 450,
                 },
                 name: Some("Chat Output".into()),
+                anchor: None,
             },
             Region {
                 id: "chat-in".into(),
@@ -62,11 +108,14 @@ fn default_profile() -> Profile {
                     height: 150,
                 },
                 name: Some("Chat Input".into()),
+                anchor: None,
             },
         ],
         trigger: TriggerConfig {
             r#type: "IntervalTrigger".into(),
             check_interval_sec: 60.0,
+            hotkey: None,
+            plugin: None,
         },
         condition: ConditionConfig {
             r#type: "RegionCondition".into(),
@@ -89,14 +138,25 @@ fn default_profile() -> Profile {
         guardrails: Some(GuardrailsConfig {
             max_runtime_ms: Some(3 * 60 * 60 * 1000),
             max_activations_per_hour: Some(120),
+            max_iterations: None,
+            max_consecutive_llm_failures: None,
             cooldown_ms: 5_000,
             heartbeat_timeout_ms: None,
+            action_timeout_ms: None,
             ocr_mode: OcrMode::default(),
             success_keywords: Vec::new(),
             failure_keywords: Vec::new(),
             ocr_termination_pattern: None,
             ocr_region_ids: Vec::new(),
         }),
+        on_complete: None,
+        on_failure: None,
+        env_allowlist: vec![],
+        notifications: Default::default(),
+        run_windows: vec![],
+        frame_archive: None,
+        privacy: None,
+        voice: None,
     }
 }
 
@@ -104,6 +164,8 @@ fn default_profile() -> Profile {
 #[serde(default)]
 struct ProfilesConfig {
     version: Option<u32>,
+    /// Profile format version; older files are migrated on load (see `migration`)
+    schema_version: u32,
     profiles: Vec<Profile>,
 }
 
@@ -111,6 +173,7 @@ impl Default for ProfilesConfig {
     fn default() -> Self {
         Self {
             version: Some(1),
+            schema_version: migration::CURRENT_SCHEMA_VERSION,
             profiles: vec![default_profile()],
         }
     }
@@ -124,6 +187,7 @@ impl ProfilesConfig {
         if self.version.is_none() {
             self.version = Some(1);
         }
+        self.schema_version = migration::CURRENT_SCHEMA_VERSION;
         self
     }
 }
@@ -143,50 +207,104 @@ fn get_profiles_path() -> Result<std::path::PathBuf, String> {
     Ok(app_dir.join("profiles.json"))
 }
 
-// Load profiles from disk, or return default if file doesn't exist
-fn load_profiles_from_disk() -> ProfilesConfig {
+// Load profiles from disk, or return default if file doesn't exist; the flag tells whether the file was encrypted
+fn load_profiles_from_disk() -> (ProfilesConfig, bool) {
     match get_profiles_path() {
         Ok(path) => {
             if path.exists() {
-                match std::fs::read_to_string(&path) {
-                    Ok(contents) => match serde_json::from_str(&contents) {
-                        Ok(config) => {
-                            println!("[Config] Loaded profiles from {:?}", path);
-                            return config;
-                        }
-                        Err(e) => eprintln!("[Config] Failed to parse profiles.json: {}", e),
-                    },
-                    Err(e) => eprintln!("[Config] Failed to read profiles.json: {}", e),
+                match read_profiles_file(&path, &profile_crypto::KeyringKeyStore::PROFILES) {
+                    Ok(loaded) => {
+                        info!("Loaded profiles from {:?}", path);
+                        return loaded;
+                    }
+                    Err(e) => warn!("Failed to load profiles: {}", e),
                 }
             }
         }
-        Err(e) => eprintln!("[Config] Failed to get profiles path: {}", e),
+        Err(e) => warn!("Failed to get profiles path: {}", e),
     }
-    ProfilesConfig::default()
+    (ProfilesConfig::default(), false)
 }
 
-// Save profiles to disk
-fn save_profiles_to_disk(config: &ProfilesConfig) -> Result<(), String> {
-    let path = get_profiles_path()?;
+// Read a profiles file, decrypting it if needed and migrating an older schema in place after backing up the original
+fn read_profiles_file(
+    path: &std::path::Path,
+    keys: &dyn profile_crypto::KeyStore,
+) -> Result<(ProfilesConfig, bool), String> {
+    let contents =
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read profiles.json: {}", e))?;
+    let (plaintext, encrypted) = profile_crypto::open(&contents, keys)?;
+    let mut raw: serde_json::Value = serde_json::from_str(&plaintext)
+        .map_err(|e| format!("Failed to parse profiles.json: {}", e))?;
+    let from = migration::migrate_config(&mut raw)?;
+    let config: ProfilesConfig = serde_json::from_value(raw.clone())
+        .map_err(|e| format!("Failed to parse profiles.json: {}", e))?;
+
+    if from < migration::CURRENT_SCHEMA_VERSION {
+        // The backup keeps the original bytes, so an encrypted file stays encrypted
+        let backup = migration::backup_path(path, from);
+        std::fs::write(&backup, &contents)
+            .map_err(|e| format!("Failed to back up profiles before migration: {}", e))?;
+        write_profiles_file(path, &config, encrypted.then_some(keys))?;
+        info!(
+            "Migrated profiles from schema v{} to v{} (original kept at {:?})",
+            from,
+            migration::CURRENT_SCHEMA_VERSION,
+            backup
+        );
+    }
+    Ok((config, encrypted))
+}
+
+// Write a profiles file, encrypted when a key store is given
+fn write_profiles_file(
+    path: &std::path::Path,
+    config: &ProfilesConfig,
+    encrypt_with: Option<&dyn profile_crypto::KeyStore>,
+) -> Result<(), String> {
     let json = serde_json::to_string_pretty(config)
         .map_err(|e| format!("Failed to serialize profiles: {}", e))?;
-    std::fs::write(&path, json)
-        .map_err(|e| format!("Failed to write profiles.json: {}", e))?;
-    println!("[Config] Saved profiles to {:?}", path);
+    let contents = match encrypt_with {
+        Some(keys) => profile_crypto::seal(&json, keys)?,
+        None => json,
+    };
+    std::fs::write(path, contents).map_err(|e| format!("Failed to write profiles.json: {}", e))
+}
+
+// Save profiles to disk
+fn save_profiles_to_disk(config: &ProfilesConfig, encrypt: bool) -> Result<(), String> {
+    let path = get_profiles_path()?;
+    let keys = profile_crypto::KeyringKeyStore::PROFILES;
+    write_profiles_file(&path, config, encrypt.then_some(&keys as &dyn profile_crypto::KeyStore))?;
+    debug!("Saved profiles to {:?}", path);
     Ok(())
 }
 
-#[derive(Default)]
 struct AppState<R: tauri::Runtime = tauri::Wry> {
     profiles: Mutex<ProfilesConfig>,      // in-memory cache, persisted to disk
     runner: Mutex<Option<MonitorRunner>>, // current monitor runner
     secure_storage: Option<secure_storage::SecureStorage<R>>, // OS keyring access
+    run_history: Arc<Mutex<history::RunHistory>>, // finished runs, persisted to disk
+    checkpoints: Arc<recovery::CheckpointStore>, // active-run checkpoint for crash recovery
+    interrupted_run: Mutex<Option<recovery::RunCheckpoint>>, // left behind by a crash, until resumed or discarded
+    profiles_encrypted: AtomicBool, // profiles.json is sealed with the key from the OS keyring
+    last_sync: Mutex<Option<sync::SyncStatus>>, // outcome of the latest remote profile sync
+    quiet_hours: Arc<Mutex<notify::QuietPolicy>>, // read by running notifiers at each notification
+    region_previews: preview::PreviewStreams, // live region preview streams by region id
+    region_picker: Mutex<Option<region_picker::PickerSession>>, // the open region overlay, if any
+    runner_status: Mutex<runner_state::RunnerStatus>, // followed from runner://state for the tray and remote API
+    remote_api: Mutex<Option<remote::RemoteServer>>, // the remote control server, while enabled
+    prometheus: Arc<prometheus::Metrics>, // counters and histograms of every run since start
+    metrics_exporter: Mutex<Option<prometheus::MetricsServer>>, // the /metrics endpoint, while enabled
+    voice: Mutex<Option<voice::VoiceListener>>, // the microphone listener for voice commands, while enabled
+    runtime: runtime::AsyncRuntime, // shared tokio runtime for HTTP and other async work
+    shutdown: Arc<shutdown::Shutdown>, // background threads to stop and cleanup to run at exit
 }
 
 struct MonitorRunner {
-    cancel: Arc<AtomicBool>,
-    panic: Arc<AtomicBool>,
-    #[allow(dead_code)]
+    profile_id: String,
+    controls: run_loop::RunControls, // its cancel token is a child of the app token: cancelled by stop or app exit
+    stepper: Option<Arc<debugger::StepController>>, // set when started in step-through debug mode
     handle: std::thread::JoinHandle<()>,
 }
 
@@ -209,9 +327,32 @@ enum StopReason {
 }
 
 pub fn build_monitor_from_profile<'a>(p: &Profile, api_key: Option<String>, model: Option<String>) -> (monitor::Monitor<'a>, Vec<Region>) {
+    let llm_client = llm::create_llm_client(api_key, model).unwrap_or_else(|e| {
+        warn!("Failed to create LLM client: {}", e);
+        Arc::new(llm::MockLLMClient::new())
+    });
+    build_monitor_with_llm(p, llm_client)
+}
+
+/// Like `build_monitor_from_profile`, with the LLM client the actions should use
+fn build_monitor_with_llm<'a>(p: &Profile, llm_client: Arc<dyn llm::LLMClient>) -> (monitor::Monitor<'a>, Vec<Region>) {
+    build_monitor_with_backends(p, llm_client, Arc::from(make_capture()), Arc::from(make_element_finder()))
+}
+
+/// Like `build_monitor_with_llm`, with the capture and element lookup the actions should use
+fn build_monitor_with_backends<'a>(
+    p: &Profile,
+    llm_client: Arc<dyn llm::LLMClient>,
+    capture: Arc<dyn ScreenCapture + Send + Sync>,
+    finder: Arc<dyn ElementFinder + Send + Sync>,
+) -> (monitor::Monitor<'a>, Vec<Region>) {
     // Trigger
     let secs = p.trigger.check_interval_sec.clamp(0.1, 86_400.0);
-    let trig = Box::new(trigger::IntervalTrigger::new(Duration::from_secs_f64(secs)));
+    let interval = Duration::from_secs_f64(secs);
+    let trig: Box<dyn Trigger + Send> = match &p.trigger.plugin {
+        Some(t) => Box::new(plugins::PluginTrigger::new(interval, t, plugins::registry().get(&t.plugin))),
+        None => Box::new(trigger::IntervalTrigger::new(interval)),
+    };
 
     // Condition
     let cond = Box::new(condition::RegionCondition::new(
@@ -221,11 +362,29 @@ pub fn build_monitor_from_profile<'a>(p: &Profile, api_key: Option<String>, mode
 
     // Actions
     let mut acts: Vec<Box<dyn Action + Send + Sync>> = vec![];
-    let capture: Arc<dyn ScreenCapture + Send + Sync> = Arc::from(make_capture());
-    let llm_client = llm::create_llm_client(api_key, model).unwrap_or_else(|e| {
-        eprintln!("Warning: Failed to create LLM client: {}", e);
-        Arc::new(llm::MockLLMClient::new())
+    let frame_cache = Arc::new(frames::FrameCache::default());
+    let capture: Arc<dyn ScreenCapture + Send + Sync> = Arc::new(frames::CachedCapture {
+        inner: capture,
+        cache: frame_cache.clone(),
     });
+    // Actions see frames and OCR text with personal data masked
+    let privacy = p
+        .privacy
+        .as_ref()
+        .map(|c| Arc::new(privacy::PrivacyFilter::new(c, Box::new(privacy::TesseractReader::from_env()))));
+    let (capture, llm_client) = match &privacy {
+        Some(filter) => (
+            Arc::new(privacy::MaskingCapture {
+                inner: capture,
+                filter: filter.clone(),
+            }) as Arc<dyn ScreenCapture + Send + Sync>,
+            Arc::new(privacy::RedactingClient {
+                inner: llm_client,
+                filter: filter.clone(),
+            }) as Arc<dyn llm::LLMClient>,
+        ),
+        None => (capture, llm_client),
+    };
 
     for a in &p.actions {
         match a {
@@ -236,6 +395,21 @@ pub fn build_monitor_from_profile<'a>(p: &Profile, api_key: Option<String>, mode
             ActionConfig::Type { text } => {
                 acts.push(Box::new(action::TypeText { text: text.clone() }))
             }
+            ActionConfig::ClickElement { element, button, invoke } => acts.push(Box::new(action::ClickElement {
+                query: element.clone(),
+                button: *button,
+                invoke: *invoke,
+                finder: finder.clone(),
+            })),
+            ActionConfig::ReadElementText { element, variable_name } => {
+                acts.push(Box::new(action::ReadElementText {
+                    query: element.clone(),
+                    variable_name: variable_name
+                        .clone()
+                        .unwrap_or_else(|| "element_text".to_string()),
+                    finder: finder.clone(),
+                }))
+            }
             ActionConfig::LLMPromptGeneration {
                 region_ids,
                 risk_threshold,
@@ -270,9 +444,19 @@ pub fn build_monitor_from_profile<'a>(p: &Profile, api_key: Option<String>, mode
                 capture: capture.clone(),
                 llm_client: llm_client.clone(),
             })),
+            ActionConfig::Plugin { plugin, kind, config } => acts.push(Box::new(plugins::PluginAction {
+                plugin_id: plugin.clone(),
+                kind: kind.clone(),
+                config: config.clone(),
+                plugin: plugins::registry().get(plugin),
+                capture: capture.clone(),
+                regions: p.regions.clone(),
+            })),
         }
     }
-    let seq = ActionSequence::new(acts);
+    let mut seq = ActionSequence::new(acts);
+    seq.frame_cache = Some(frame_cache);
+    seq.privacy = privacy;
 
     // Guardrails
     let gr = p
@@ -282,7 +466,10 @@ pub fn build_monitor_from_profile<'a>(p: &Profile, api_key: Option<String>, mode
             cooldown: Duration::from_millis(g.cooldown_ms),
             max_runtime: g.max_runtime_ms.map(Duration::from_millis),
             max_activations_per_hour: g.max_activations_per_hour,
+            max_iterations: g.max_iterations,
+            max_consecutive_llm_failures: g.max_consecutive_llm_failures,
             heartbeat_timeout: g.heartbeat_timeout_ms.map(Duration::from_millis),
+            action_timeout: g.action_timeout_ms.map(Duration::from_millis),
             ocr_mode: g.ocr_mode,
             success_keywords: g.success_keywords.clone(),
             failure_keywords: g.failure_keywords.clone(),
@@ -297,9 +484,14 @@ pub fn build_monitor_from_profile<'a>(p: &Profile, api_key: Option<String>, mode
     (monitor::Monitor::new(trig, cond, seq, gr), regions)
 }
 
+/// The capture backend; anchored regions are captured where their anchor is now
 fn make_capture() -> Box<dyn ScreenCapture + Send + Sync> {
-    if env::var("LOOPAUTOMA_BACKEND").ok().as_deref() == Some("fake") {
-        return Box::new(FakeCapture);
+    Box::new(anchor::AnchoredCapture::new(make_backend_capture()))
+}
+
+fn make_backend_capture() -> Box<dyn ScreenCapture + Send + Sync> {
+    if fakes::selected() {
+        return Box::new(fakes::backend());
     }
     #[cfg(feature = "os-linux-capture-xcap")]
     {
@@ -327,23 +519,35 @@ fn make_capture() -> Box<dyn ScreenCapture + Send + Sync> {
     }
 }
 
+/// The input backend with pointer moves corrected by the saved calibration
 fn make_automation() -> Box<dyn Automation + Send + Sync> {
-    if env::var("LOOPAUTOMA_BACKEND").ok().as_deref() == Some("fake") {
-        return Box::new(FakeAutomation);
+    Box::new(calibration::CalibratedAutomation::new(make_backend_automation()))
+}
+
+fn make_backend_automation() -> Box<dyn Automation + Send + Sync> {
+    if fakes::selected() {
+        return Box::new(fakes::backend());
     }
     #[cfg(feature = "os-linux-automation")]
     {
-        return match crate::os::linux::LinuxAutomation::new() {
+        // XTest only reaches XWayland windows on Wayland; prefer ydotool there
+        if crate::os::wayland::SessionType::detect() == crate::os::wayland::SessionType::Wayland {
+            match crate::os::wayland::YdotoolAutomation::new() {
+                Ok(auto) => return Box::new(auto),
+                Err(err) => warn!("Wayland automation unavailable, falling back to X11: {}", err),
+            }
+        }
+        return match crate::os::linux::LinuxAutomation::shared() {
             Ok(auto) => Box::new(auto),
             Err(err) => {
-                eprintln!("linux automation unavailable: {}", err);
+                warn!("Linux automation unavailable: {}", err);
                 Box::new(FakeAutomation)
             }
         };
     }
     #[cfg(all(not(feature = "os-linux-automation"), feature = "os-macos"))]
     {
-        return Box::new(crate::os::macos::MacAutomation);
+        return Box::new(crate::os::macos::MacAutomation::default());
     }
     #[cfg(all(
         not(feature = "os-linux-automation"),
@@ -363,33 +567,468 @@ fn make_automation() -> Box<dyn Automation + Send + Sync> {
     }
 }
 
+/// What `make_automation` would give in this session
+fn probe_automation() -> AutomationCapabilities {
+    let available = |session: &str, backend: &str, hint: Option<&str>| AutomationCapabilities {
+        session: session.into(),
+        backend: backend.into(),
+        mouse: true,
+        keyboard: true,
+        text: true,
+        hint: hint.map(Into::into),
+    };
+    if fakes::selected() {
+        return available("unknown", "fake", Some("LOOPAUTOMA_BACKEND=fake: input is simulated"));
+    }
+    #[cfg(feature = "os-linux-automation")]
+    {
+        return crate::os::wayland::probe_session();
+    }
+    #[cfg(all(not(feature = "os-linux-automation"), feature = "os-macos"))]
+    {
+        return available(
+            "macos",
+            "cgevent",
+            Some("Needs Accessibility access under System Settings → Privacy & Security"),
+        );
+    }
+    #[cfg(all(
+        not(feature = "os-linux-automation"),
+        not(feature = "os-macos"),
+        feature = "os-windows"
+    ))]
+    {
+        return available("windows", "sendinput", None);
+    }
+    #[cfg(all(
+        not(feature = "os-linux-automation"),
+        not(feature = "os-macos"),
+        not(feature = "os-windows")
+    ))]
+    {
+        available("unknown", "fake", Some("This build has no input backend: input is simulated"))
+    }
+}
+
+fn make_window_enumerator() -> Box<dyn WindowEnumerator + Send + Sync> {
+    if fakes::selected() {
+        return Box::new(fakes::backend());
+    }
+    #[cfg(feature = "os-linux-capture-xcap")]
+    {
+        return Box::new(crate::os::linux::LinuxCapture);
+    }
+    #[cfg(all(not(feature = "os-linux-capture-xcap"), feature = "os-macos"))]
+    {
+        return Box::new(crate::os::macos::MacCapture);
+    }
+    #[cfg(all(
+        not(feature = "os-linux-capture-xcap"),
+        not(feature = "os-macos"),
+        feature = "os-windows"
+    ))]
+    {
+        return Box::new(crate::os::windows::WinCapture);
+    }
+    #[cfg(all(
+        not(feature = "os-linux-capture-xcap"),
+        not(feature = "os-macos"),
+        not(feature = "os-windows")
+    ))]
+    {
+        Box::new(FakeCapture)
+    }
+}
+
+fn make_element_finder() -> Box<dyn ElementFinder + Send + Sync> {
+    if fakes::selected() {
+        return Box::new(fakes::backend());
+    }
+    #[cfg(feature = "os-linux-accessibility")]
+    {
+        return Box::new(crate::os::atspi::AtspiFinder);
+    }
+    #[cfg(all(not(feature = "os-linux-accessibility"), feature = "os-macos"))]
+    {
+        return Box::new(crate::os::macos::MacCapture);
+    }
+    #[cfg(all(
+        not(feature = "os-linux-accessibility"),
+        not(feature = "os-macos"),
+        feature = "os-windows"
+    ))]
+    {
+        return Box::new(crate::os::windows::WinCapture);
+    }
+    #[cfg(all(
+        not(feature = "os-linux-accessibility"),
+        not(feature = "os-macos"),
+        not(feature = "os-windows")
+    ))]
+    {
+        Box::new(FakeCapture)
+    }
+}
+
 #[tauri::command]
-fn profiles_load(state: tauri::State<AppState>) -> Result<ProfilesConfig, String> {
+fn profiles_load(state: tauri::State<AppState>) -> Result<ProfilesConfig, Error> {
     // Return in-memory cache (already loaded from disk on startup)
     Ok(state.profiles.lock().unwrap().clone())
 }
 
 #[tauri::command]
-fn profiles_save(config: ProfilesConfig, state: tauri::State<AppState>) -> Result<(), String> {
+fn profiles_save(
+    config: ProfilesConfig,
+    app: tauri::AppHandle,
+    state: tauri::State<AppState>,
+) -> Result<(), Error> {
+    Ok(apply_profiles(config, &app, &state)?)
+}
+
+/// Normalize, bind hotkeys, cache and persist a full profiles config
+fn apply_profiles(config: ProfilesConfig, app: &tauri::AppHandle, state: &tauri::State<AppState>) -> Result<(), String> {
     let normalized = config.normalize();
-    
+
+    // Rebind hotkeys first so an invalid or conflicting hotkey rejects the save
+    voice::voice_commands(&normalized.profiles)?;
+    hotkey::register_profile_hotkeys(app, &normalized.profiles, hotkey_start_profile)?;
+
     // Update in-memory cache
     *state.profiles.lock().unwrap() = normalized.clone();
     
     // Persist to disk
-    save_profiles_to_disk(&normalized)?;
+    save_profiles_to_disk(&normalized, state.profiles_encrypted.load(Ordering::Relaxed))?;
+    refresh_tray(app);
+    if state.voice.lock().unwrap().is_some() {
+        if let Err(e) = voice_config(state).and_then(|config| restart_voice_commands(app, config.as_ref())) {
+            warn!("Voice commands stopped: {}", e);
+        }
+    }
     
     Ok(())
 }
 
+/// Whether profiles.json is encrypted at rest
+#[tauri::command]
+fn profiles_encryption_get(state: tauri::State<AppState>) -> bool {
+    state.profiles_encrypted.load(Ordering::Relaxed)
+}
+
+/// Turn encryption of profiles.json on or off and rewrite the file accordingly
+#[tauri::command]
+fn profiles_encryption_set(enabled: bool, state: tauri::State<AppState>) -> Result<(), Error> {
+    let config = state.profiles.lock().unwrap().clone();
+    save_profiles_to_disk(&config, enabled)?;
+    state.profiles_encrypted.store(enabled, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Check a (possibly unsaved) profile against the saved profiles and the current displays
+#[tauri::command]
+fn validate_profile(profile: Profile, state: tauri::State<AppState>) -> Result<Vec<validation::Diagnostic>, Error> {
+    let mut profiles = state.profiles.lock().unwrap().profiles.clone();
+    // The draft replaces its saved version so renamed ids and handoffs are seen as edited
+    profiles.retain(|p| p.id != profile.id);
+    profiles.push(profile.clone());
+    let mut diagnostics = Vec::new();
+    let displays = make_capture().displays().unwrap_or_else(|e| {
+        diagnostics.push(validation::Diagnostic {
+            severity: validation::Severity::Warning,
+            field: "regions".into(),
+            message: format!("Display layout unavailable ({}); coordinates were not checked", e),
+        });
+        Vec::new()
+    });
+    diagnostics.extend(validation::validate_profile(&profile, &profiles, &displays));
+    Ok(diagnostics)
+}
+
+/// Write one profile to a standalone file; `.yaml`/`.yml` paths get YAML, others JSON
+#[tauri::command]
+fn profile_export(profile_id: String, path: String, state: tauri::State<AppState>) -> Result<(), Error> {
+    let profile = state
+        .profiles
+        .lock()
+        .unwrap()
+        .profiles
+        .iter()
+        .find(|p| p.id == profile_id)
+        .cloned()
+        .ok_or_else(|| "profile not found".to_string())?;
+    let path = std::path::PathBuf::from(path);
+    let contents = profile_io::export_profile(&profile, profile_io::ProfileFormat::from_path(&path))?;
+    Ok(std::fs::write(&path, contents).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?)
+}
+
+/// Animate the archived frames of a region of run `run_id` into `path`; `.mp4` paths get MP4, others GIF
+///
+/// Returns the number of frames written. `region_id` may be left out when the run archived one region.
+#[tauri::command]
+fn export_run_frames(run_id: i64, region_id: Option<String>, path: String, state: tauri::State<AppState>) -> Result<usize, Error> {
+    let run = get_run_details(run_id, state)?;
+    Ok(timelapse::export_animation(&run.frames, region_id.as_deref(), std::path::Path::new(&path))?)
+}
+
+/// Validate and add a profile exported by `profile_export`; a clashing id gets a numeric suffix
+#[tauri::command]
+fn profile_import(path: String, app: tauri::AppHandle, state: tauri::State<AppState>) -> Result<Profile, Error> {
+    let path = std::path::PathBuf::from(path);
+    let contents =
+        std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let mut profile = profile_io::import_profile(&contents, profile_io::ProfileFormat::from_path(&path))?;
+
+    let mut config = state.profiles.lock().unwrap().clone();
+    profile.id = profile_io::unique_profile_id(&profile.id, &config.profiles);
+    config.profiles.push(profile.clone());
+    apply_profiles(config, &app, &state)?;
+    Ok(profile)
+}
+
+/// Built-in templates with the parameters and regions each one needs
+#[tauri::command]
+fn list_templates() -> Vec<templates::ProfileTemplate> {
+    templates::builtin_templates()
+}
+
+/// Create and save a profile from a built-in template
+#[tauri::command]
+fn create_from_template(
+    template_id: String,
+    input: templates::TemplateInput,
+    app: tauri::AppHandle,
+    state: tauri::State<AppState>,
+) -> Result<Profile, Error> {
+    let template = templates::find_template(&template_id)?;
+    let mut config = state.profiles.lock().unwrap().clone();
+    let profile = templates::instantiate(&template, &input, &config.profiles)?;
+    config.profiles.push(profile.clone());
+    apply_profiles(config, &app, &state)?;
+    Ok(profile)
+}
+
 #[tauri::command]
 fn monitor_start(
     profile_id: String,
+    debug: Option<bool>,
     window: tauri::Window,
     state: tauri::State<AppState>,
-) -> Result<(), String> {
+) -> Result<(), Error> {
+    let options = RunOptions {
+        debug: debug.unwrap_or(false),
+        ..RunOptions::default()
+    };
+    Ok(start_profile_run(profile_id, window, &state, options)?)
+}
+
+/// Start a hotkey-triggered profile unless it is already running
+fn hotkey_start_profile(app: &tauri::AppHandle, profile_id: &str) {
+    if let Err(e) = start_profile_outside_ui(app, profile_id, "loopautoma://hotkey_profile_started") {
+        warn!("Failed to start profile {}: {}", profile_id, e);
+    }
+}
+
+/// Start a profile unless it is already running, announcing it on `started_event`
+/// so the UI shows it as running
+fn start_profile_outside_ui(app: &tauri::AppHandle, profile_id: &str, started_event: &str) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let already_running = state
+        .runner
+        .lock()
+        .unwrap()
+        .as_ref()
+        .is_some_and(|r| r.profile_id == profile_id && !r.handle.is_finished());
+    if already_running {
+        return Ok(());
+    }
+    start_profile_run(profile_id.to_string(), app.clone(), &state, RunOptions::default())?;
+    let _ = app.emit(started_event, profile_id);
+    Ok(())
+}
+
+/// The remote control API's view of the app, through the same calls as the commands
+struct AppRemote(tauri::AppHandle);
+
+impl remote::RemoteControl for AppRemote {
+    fn profiles(&self) -> Vec<remote::ProfileSummary> {
+        let state = self.0.state::<AppState>();
+        let config = state.profiles.lock().unwrap();
+        config
+            .profiles
+            .iter()
+            .map(|p| remote::ProfileSummary {
+                id: p.id.clone(),
+                name: p.name.clone(),
+            })
+            .collect()
+    }
+
+    fn status(&self) -> runner_state::RunnerStatus {
+        self.0.state::<AppState>().runner_status.lock().unwrap().clone()
+    }
+
+    fn start(&self, profile_id: &str) -> Result<(), String> {
+        start_profile_outside_ui(&self.0, profile_id, "loopautoma://remote_profile_started")
+    }
+
+    fn stop(&self) {
+        monitor_stop_impl(&self.0.state::<AppState>(), StopReason::Graceful);
+    }
+
+    fn set_paused(&self, paused: bool) -> Result<(), String> {
+        set_runner_paused(&self.0.state::<AppState>(), paused)
+    }
+
+    fn runs(&self, query: &history::RunQuery) -> Result<Vec<history::RunRecord>, String> {
+        self.0.state::<AppState>().run_history.lock().unwrap().list(query)
+    }
+
+    fn run_details(&self, run_id: i64) -> Result<Option<history::RunDetails>, String> {
+        self.0.state::<AppState>().run_history.lock().unwrap().details(run_id)
+    }
+}
+
+/// Stop the remote control server and, given a config, listen again with it; returns its URL
+fn restart_remote_api(app: &tauri::AppHandle, config: Option<&remote::RemoteApiConfig>) -> Result<Option<String>, String> {
+    let state = app.state::<AppState>();
+    let mut server = state.remote_api.lock().unwrap();
+    // Free the port before binding it again
+    *server = None;
+    let Some(config) = config else {
+        return Ok(None);
+    };
+    let started = remote::serve(config, Arc::new(AppRemote(app.clone())))?;
+    let url = started.url.clone();
+    *server = Some(started);
+    Ok(Some(url))
+}
+
+/// Stop the metrics exporter and, given a port, listen again on it; returns its URL
+fn restart_metrics_exporter(state: &AppState, port: Option<u16>) -> Result<Option<String>, String> {
+    let mut server = state.metrics_exporter.lock().unwrap();
+    // Free the port before binding it again
+    *server = None;
+    let Some(port) = port else {
+        return Ok(None);
+    };
+    let started = prometheus::serve(port, state.prometheus.clone())?;
+    let url = started.url.clone();
+    *server = Some(started);
+    Ok(Some(url))
+}
+
+/// Stop listening for voice commands and, given a config, listen again for the phrases of the
+/// saved profiles; returns what is listened for
+fn restart_voice_commands(app: &tauri::AppHandle, config: Option<&voice::VoiceConfig>) -> Result<Option<Vec<String>>, String> {
+    let state = app.state::<AppState>();
+    let mut listener = state.voice.lock().unwrap();
+    // Release the microphone before opening it again
+    *listener = None;
+    let Some(config) = config else {
+        return Ok(None);
+    };
+    let commands = voice::voice_commands(&state.profiles.lock().unwrap().profiles)?;
+    let handle = app.clone();
+    let started = voice::listen(config, commands, move |command| voice_command(&handle, command))?;
+    let phrases = started.phrases.clone();
+    *listener = Some(started);
+    Ok(Some(phrases))
+}
+
+/// Saved voice command settings
+fn voice_config(state: &AppState) -> Result<Option<voice::VoiceConfig>, String> {
+    match &state.secure_storage {
+        Some(storage) => storage.get_setting(&settings::VOICE_COMMANDS),
+        None => Ok(None),
+    }
+}
+
+/// Run a command heard by the voice listener
+fn voice_command(app: &tauri::AppHandle, command: &voice::VoiceCommand) {
+    let state = app.state::<AppState>();
+    let (running, paused) = match state.runner.lock().unwrap().as_ref() {
+        Some(r) if r.profile_id == command.profile_id && !r.handle.is_finished() => (true, r.controls.pause.load(Ordering::Relaxed)),
+        _ => (false, false),
+    };
+    let result = match command.action {
+        voice::VoiceAction::Start if paused => set_runner_paused(&state, false),
+        voice::VoiceAction::Start => start_profile_outside_ui(app, &command.profile_id, "loopautoma://voice_profile_started"),
+        voice::VoiceAction::Stop if running => {
+            monitor_stop_impl(&state, StopReason::Graceful);
+            Ok(())
+        }
+        voice::VoiceAction::Stop => Ok(()),
+    };
+    if let Err(e) = result {
+        warn!("Voice command '{}' for profile {} failed: {}", command.phrase, command.profile_id, e);
+    }
+}
+
+/// Run a tray menu entry
+fn tray_command(app: &tauri::AppHandle, command: tray::TrayCommand) {
+    let state = app.state::<AppState>();
+    let result = match command {
+        tray::TrayCommand::Start(profile_id) => {
+            start_profile_outside_ui(app, &profile_id, "loopautoma://tray_profile_started")
+        }
+        tray::TrayCommand::Stop => {
+            monitor_stop_impl(&state, StopReason::Graceful);
+            Ok(())
+        }
+        tray::TrayCommand::Pause => set_runner_paused(&state, true),
+        tray::TrayCommand::Resume => set_runner_paused(&state, false),
+        tray::TrayCommand::ShowWindow => {
+            restore_main_window(app);
+            Ok(())
+        }
+        tray::TrayCommand::Quit => app_quit(app.clone()).map_err(|e| e.to_string()),
+    };
+    if let Err(e) = result {
+        warn!("Tray command failed: {}", e);
+    }
+}
+
+fn tray_view(state: &AppState) -> tray::TrayView {
+    let profiles = state.profiles.lock().unwrap().profiles.clone();
+    let query = history::RunQuery {
+        limit: Some(50),
+        ..Default::default()
+    };
+    let runs = state.run_history.lock().unwrap().list(&query).unwrap_or_default();
+    tray::TrayView::new(&state.runner_status.lock().unwrap(), &profiles, &runs)
+}
+
+/// Show the current runner state and recent profiles on the tray icon
+fn refresh_tray(app: &tauri::AppHandle) {
+    if let Err(e) = tray::update(app, &tray_view(&app.state::<AppState>())) {
+        warn!("Failed to update tray: {}", e);
+    }
+}
+
+/// Per-run options for `start_profile_run`
+#[derive(Default)]
+struct RunOptions {
+    /// Hold before every action until `debug_step`/`debug_continue`
+    debug: bool,
+    /// Context variables the run starts with (handed off by a chained profile)
+    variables: HashMap<String, String>,
+    /// Checkpoint of an interrupted run to continue from
+    resume_from: Option<recovery::RunCheckpoint>,
+}
+
+/// Start the monitor loop for a profile, emitting runtime events through `emitter`
+///
+/// When the run ends on its own, the profile's `on_complete`/`on_failure` handoff is started.
+fn start_profile_run<E>(
+    profile_id: String,
+    emitter: E,
+    state: &tauri::State<AppState>,
+    options: RunOptions,
+) -> Result<(), String>
+where
+    E: Manager<tauri::Wry> + Clone + Send + Sync + 'static,
+{
     // Stop any existing runner
-    monitor_stop_impl(&state, StopReason::Graceful);
+    monitor_stop_impl(state, StopReason::Graceful);
 
     let profiles_cfg = state.profiles.lock().unwrap().clone();
     let profile = profiles_cfg
@@ -401,118 +1040,755 @@ fn monitor_start(
     let (api_key, model) = match &state.secure_storage {
         Some(storage) => {
             let key = storage.get_openai_key().ok().flatten();
-            let mdl = storage.get_openai_model().ok().flatten();
+            let mdl = storage.get_setting(&settings::OPENAI_MODEL).ok().flatten();
             (key, mdl)
         }
         None => (None, None)
     };
-    
-    let (mut mon, regions) = build_monitor_from_profile(&profile, api_key, model);
-    let cancel = Arc::new(AtomicBool::new(false));
-    let cancel_clone = cancel.clone();
-    let panic_flag = Arc::new(AtomicBool::new(false));
-    let panic_clone = panic_flag.clone();
-
-    // backends: OS adapters by default; set LOOPAUTOMA_BACKEND=fake to force fakes
-    let cap = make_capture();
-    let auto = make_automation();
+    let has_key = api_key.is_some();
+    let mut llm_client = llm::create_llm_client(api_key, model).unwrap_or_else(|e| {
+        warn!("Failed to create LLM client: {}", e);
+        Arc::new(llm::MockLLMClient::new())
+    });
+    if has_key && !llm_client.is_mock() {
+        let app = emitter.clone();
+        llm_client = Arc::new(llm::TrackedClient {
+            inner: llm_client,
+            on_result: Box::new(move |ok| {
+                if let Some(storage) = &app.state::<AppState>().secure_storage {
+                    let entry = credentials::key_entry(credentials::ProviderKind::OpenAi.id());
+                    if let Err(e) = storage.record_key_use(&entry, ok) {
+                        warn!("Failed to record key use: {}", e);
+                    }
+                }
+            }),
+        });
+    }
+    let secrets = {
+        let app = emitter.clone();
+        let profile_id = profile.id.clone();
+        SecretLookup::new(move |name| match &app.state::<AppState>().secure_storage {
+            Some(storage) => storage.get_profile_secret(&profile_id, name),
+            None => Err("Secure storage not initialized".to_string()),
+        })
+    };
+    let sinks = run_loop::RunSinks {
+        metrics: state.prometheus.clone(),
+        checkpoints: state.checkpoints.clone(),
+        history: state.run_history.clone(),
+    };
+    let mut run = run_loop::ProfileRun::prepare(&profile, llm_client, secrets, options.resume_from.as_ref(), sinks)?;
+    let profile = run.profile.clone();
+    let started_at_ms = run.started_at_ms;
+    let desktop = RunDesktopNotifier {
+        emitter: emitter.clone(),
+        profile_id: profile.id.clone(),
+        started_at_ms,
+    };
+    let mut router = notify::NotificationRouter::new(profile.name.clone(), profile.notifications.clone(), started_at_ms)
+        .with(Arc::new(desktop))
+        .with(Arc::new(RunWebhookNotifier::from_state(state)))
+        .with(Arc::new(RunEmailNotifier::from_state(state)))
+        .with(Arc::new(plugins::PluginNotifier {
+            plugins: plugins::registry().loaded(),
+            shutdown: state.shutdown.clone(),
+        }))
+        .with_quiet_hours(state.quiet_hours.clone(), {
+            let emitter = emitter.clone();
+            move |e| {
+                let _ = emitter.emit("loopautoma://event", e);
+            }
+        });
+    if let Some(audio) = make_runner_audio_notifier(state) {
+        router = router.with(Arc::new(notify::SoundNotifier(Arc::from(audio))));
+    }
+    let notifications = Arc::new(router);
+    let stepper = options.debug.then(|| {
+        let sink_emitter = emitter.clone();
+        let sink_notifications = notifications.clone();
+        let sink_profile = profile.id.clone();
+        Arc::new(debugger::StepController::new(move |e| {
+            emit_runner_event(&sink_emitter, &sink_notifications, &sink_profile, &e)
+        }))
+    });
+    if let Some(s) = &stepper {
+        run.mon.actions.step_gate = Some(s.clone());
+    }
+    let controls = run_loop::RunControls::new(state.shutdown.token().child());
+
+    // Keys or buttons a stopped run leaves down are let go when it ends, or at app exit if it hangs
+    state.shutdown.on_shutdown("runner_input", {
+        let auto = Arc::downgrade(&run.automation);
+        move || {
+            if let Some(auto) = auto.upgrade() {
+                auto.release_all();
+            }
+        }
+    });
+    let run_history = state.run_history.clone();
+    // Any run replaces the checkpoint on disk, so an interrupted run can no longer be resumed
+    state.interrupted_run.lock().unwrap().take();
     let mut events = vec![];
-    mon.start(&mut events);
+    run.start(options.variables, options.resume_from, &mut events);
+    record_progress(&notifications, &run.mon);
+    if let Some(storage) = &state.secure_storage {
+        for (credential, reason) in storage.key_warnings().unwrap_or_default() {
+            events.push(Event::CredentialWarning { credential, reason });
+        }
+    }
     for e in events.drain(..) {
-        let _ = window.emit("loopautoma://event", &e);
+        emit_event(&emitter, &profile.id, &e);
     }
-
-    let handle = std::thread::spawn(move || {
-        let win = window;
-        // Small scheduler tick; Trigger decides whether to fire
-        loop {
-            if cancel_clone.load(Ordering::Relaxed) {
-                let evs = finalize_monitor_shutdown(&mut mon, panic_clone.load(Ordering::Relaxed));
-                for e in evs {
-                    let _ = win.emit("loopautoma://event", &e);
+    notifications.on_start();
+
+    let run_controls = controls.clone();
+    let handle = state.shutdown.spawn("runner", Some(controls.cancel.clone()), move |cancel| {
+        let win = emitter;
+        let mut hooks = UiRunHooks {
+            emitter: &win,
+            notifications: &notifications,
+            profile_id: &profile.id,
+        };
+        let mut details = run.run(&run_controls, &mut hooks);
+        let outcome = &details.record.outcome;
+        notifications.on_outcome(outcome);
+        let handoff = profile.handoff_for(outcome).cloned();
+        let succeeded = outcome.succeeded();
+        // Keep what the screen showed when a run failed
+        let screenshot = if succeeded == Some(false) { capture_full_screen_png() } else { None };
+        {
+            let mut run_history = run_history.lock().unwrap();
+            if let Some(png) = screenshot {
+                match run_history.archive_screenshot(&profile.id, started_at_ms, &png) {
+                    Ok(path) => details.screenshots.extend(path),
+                    Err(e) => warn!("Failed to archive run screenshot: {}", e),
                 }
-                break;
             }
-
-            if mon.started_at.is_none() {
-                break;
+            match run_history.record(details) {
+                Ok(run_id) => {
+                    let _ = win.emit("loopautoma://run_recorded", run_id);
+                }
+                Err(e) => warn!("Failed to record run: {}", e),
             }
+        }
 
-            let now = Instant::now();
-            let mut evs = vec![];
-            mon.tick(now, &regions, &*cap, &*auto, &mut evs);
-            for e in evs {
-                let _ = win.emit("loopautoma://event", &e);
-            }
-            if mon.started_at.is_none() {
-                break;
-            }
-            std::thread::sleep(Duration::from_millis(100));
+        // A cancelled runner has been replaced or stopped; it must not start anything
+        if let (Some(handoff), false) = (handoff, cancel.is_cancelled()) {
+            let on = if succeeded == Some(true) { "complete" } else { "failure" };
+            start_handoff(win, &profile.id, &handoff, on, &run.mon.context.variables);
         }
     });
 
     *state.runner.lock().unwrap() = Some(MonitorRunner {
-        cancel,
-        panic: panic_flag,
+        profile_id,
+        controls,
+        stepper,
         handle,
     });
     Ok(())
 }
 
-fn monitor_stop_impl(state: &tauri::State<AppState>, reason: StopReason) {
-    if let Some(r) = state.runner.lock().unwrap().take() {
-        if matches!(reason, StopReason::Panic) {
-            r.panic.store(true, Ordering::Relaxed);
-        }
-        r.cancel.store(true, Ordering::Relaxed);
+/// Reports a run's progress to the UI and the profile's notification channels
+struct UiRunHooks<'a, E> {
+    emitter: &'a E,
+    notifications: &'a notify::NotificationRouter,
+    profile_id: &'a str,
+}
+
+impl<E: Emitter<tauri::Wry>> run_loop::RunHooks for UiRunHooks<'_, E> {
+    fn on_event(&mut self, event: &Event) {
+        emit_runner_event(self.emitter, self.notifications, self.profile_id, event);
+    }
+
+    fn on_stopped(&mut self, event: &Event) {
+        emit_event(self.emitter, self.profile_id, event);
+    }
+
+    fn on_tick(&mut self, mon: &monitor::Monitor) {
+        record_progress(self.notifications, mon);
+    }
+}
+
+/// Start the follow-up profile of a finished run, copying the selected context variables
+fn start_handoff<E>(
+    emitter: E,
+    from: &str,
+    handoff: &ProfileHandoff,
+    on: &str,
+    variables: &HashMap<String, String>,
+) where
+    E: Manager<tauri::Wry> + Clone + Send + Sync + 'static,
+{
+    let variables = handoff
+        .pass_variables
+        .iter()
+        .filter_map(|name| variables.get(name).map(|v| (name.clone(), v.clone())))
+        .collect();
+    let _ = emitter.emit(
+        "loopautoma://event",
+        &Event::ProfileChained {
+            from: from.to_string(),
+            to: handoff.profile_id.clone(),
+            on: on.to_string(),
+        },
+    );
+    let app = emitter.app_handle().clone();
+    let state = app.state::<AppState>();
+    let options = RunOptions {
+        variables,
+        ..RunOptions::default()
+    };
+    if let Err(e) = start_profile_run(handoff.profile_id.clone(), emitter, &state, options) {
+        let _ = app.emit(
+            "loopautoma://event",
+            &Event::Error {
+                message: format!("Failed to start chained profile '{}': {}", handoff.profile_id, e),
+            },
+        );
+    }
+}
+
+/// Audio notifier for the runner thread, configured from persisted audio settings
+fn make_runner_audio_notifier(state: &tauri::State<AppState>) -> Option<Box<dyn audio::AudioNotifier>> {
+    let notifier = audio::create_audio_notifier().ok()?;
+    if let Some(storage) = &state.secure_storage {
+        notifier.set_enabled(storage.get_setting(&settings::AUDIO_ENABLED).unwrap_or(true));
+        let _ = notifier.set_volume(storage.get_setting(&settings::AUDIO_VOLUME).unwrap_or(0.5));
+        for (sound, path) in storage.get_setting(&settings::AUDIO_CUSTOM_SOUNDS).unwrap_or_default() {
+            notifier.set_custom_sound(sound, Some(std::path::PathBuf::from(path)));
+        }
+        notifier.set_sound_map(storage.get_setting(&settings::AUDIO_SOUND_MAP).unwrap_or_default());
+    }
+    Some(notifier)
+}
+
+/// Run a desktop notification points back to when clicked
+#[derive(Debug, Clone, Serialize)]
+struct NotificationClick {
+    profile_id: String,
+    started_at_ms: u64,
+    event: NotificationEvent,
+}
+
+/// Desktop notifications of one run; a click focuses the main window on the run's profile
+struct RunDesktopNotifier<E> {
+    emitter: E,
+    profile_id: String,
+    started_at_ms: u64,
+}
+
+impl<E> notify::Notifier for RunDesktopNotifier<E>
+where
+    E: Manager<tauri::Wry> + Clone + Send + Sync + 'static,
+{
+    fn channel(&self) -> NotificationChannel {
+        NotificationChannel::Desktop
+    }
+
+    fn notify(&self, notice: &notify::Notice) -> Result<(), String> {
+        let emitter = self.emitter.clone();
+        let click = NotificationClick {
+            profile_id: self.profile_id.clone(),
+            started_at_ms: self.started_at_ms,
+            event: notice.event,
+        };
+        let (title, body) = notice.message();
+        notify::post_desktop_notification(&title, &body, move || {
+            if let Some(main) = emitter.get_webview_window("main") {
+                let _ = main.unminimize();
+                let _ = main.show();
+                let _ = main.set_focus();
+            }
+            let _ = emitter.emit("loopautoma://notification_clicked", &click);
+        })
+    }
+}
+
+/// Webhook targets from settings; a thread takes the screenshot, then each
+/// target is delivered as a task on the shared runtime
+struct RunWebhookNotifier {
+    targets: Vec<webhooks::WebhookConfig>,
+    runtime: runtime::AsyncRuntime,
+    shutdown: Arc<shutdown::Shutdown>,
+}
+
+impl RunWebhookNotifier {
+    fn from_state(state: &tauri::State<AppState>) -> Self {
+        let targets = state
+            .secure_storage
+            .as_ref()
+            .and_then(|s| s.get_webhooks().ok())
+            .unwrap_or_default();
+        Self {
+            targets,
+            runtime: state.runtime,
+            shutdown: state.shutdown.clone(),
+        }
+    }
+}
+
+impl notify::Notifier for RunWebhookNotifier {
+    fn channel(&self) -> NotificationChannel {
+        NotificationChannel::Webhook
+    }
+
+    fn notify(&self, notice: &notify::Notice) -> Result<(), String> {
+        if self.targets.is_empty() {
+            return Ok(());
+        }
+        let (targets, runtime) = (self.targets.clone(), self.runtime);
+        let text = notice.text();
+        // Tracked, so a notice sent as the app quits is still delivered
+        self.shutdown.spawn("webhook", None, move |_| {
+            let screenshot = targets
+                .iter()
+                .any(|t| t.wants_screenshot())
+                .then(capture_full_screen_png)
+                .flatten();
+            for target in &targets {
+                let request = webhooks::build_request(target, &text, screenshot.as_deref(), &webhooks::new_boundary());
+                if let Err(e) = webhooks::send(&runtime, &request) {
+                    warn!("Webhook delivery failed: {}", e);
+                }
+            }
+        });
+        Ok(())
+    }
+}
+
+/// SMTP server from settings; each summary is mailed on its own thread
+struct RunEmailNotifier {
+    smtp: Option<email::SmtpConfig>,
+    shutdown: Arc<shutdown::Shutdown>,
+}
+
+impl RunEmailNotifier {
+    fn from_state(state: &tauri::State<AppState>) -> Self {
+        let smtp = state
+            .secure_storage
+            .as_ref()
+            .and_then(|s| s.get_smtp().ok())
+            .flatten();
+        Self {
+            smtp,
+            shutdown: state.shutdown.clone(),
+        }
+    }
+}
+
+impl notify::Notifier for RunEmailNotifier {
+    fn channel(&self) -> NotificationChannel {
+        NotificationChannel::Email
+    }
+
+    fn notify(&self, notice: &notify::Notice) -> Result<(), String> {
+        let Some(smtp) = self.smtp.clone() else {
+            return Ok(());
+        };
+        let summary = notice.summary();
+        self.shutdown.spawn("email", None, move |_| {
+            let screenshot = smtp.include_screenshot.then(capture_full_screen_png).flatten();
+            if let Err(e) = email::send(&smtp, &summary, screenshot.as_deref()) {
+                warn!("Email delivery failed: {}", e);
+            }
+        });
+        Ok(())
+    }
+}
+
+/// Hand the run's iteration count and the LLM's latest reason to email summaries
+fn record_progress(notifications: &notify::NotificationRouter, mon: &monitor::Monitor) {
+    notifications.record_progress(
+        mon.iterations,
+        mon.context.variables.get("task_complete_reason").map(String::as_str),
+    );
+}
+
+fn capture_full_screen_png() -> Option<Vec<u8>> {
+    let encoded = capture_full_screen().map_err(|e| warn!("Screenshot failed: {}", e)).ok()?;
+    Base64Standard.decode(encoded).ok()
+}
+
+/// Emit a runner event to the UI and to the profile's notification channels
+fn emit_runner_event<E: Emitter<tauri::Wry>>(
+    emitter: &E,
+    notifications: &notify::NotificationRouter,
+    profile_id: &str,
+    event: &Event,
+) {
+    notifications.on_event(event);
+    emit_event(emitter, profile_id, event);
+}
+
+/// Emit a runner event to the event log and, if it is a state transition, to `runner://state`
+fn emit_event<E: Emitter<tauri::Wry>>(emitter: &E, profile_id: &str, event: &Event) {
+    let _ = emitter.emit("loopautoma://event", event);
+    if let Some(update) = runner_state::RunnerStateUpdate::from_event(profile_id, history::now_ms(), event) {
+        let _ = emitter.emit(runner_state::CHANNEL, &update);
+    }
+}
+
+fn monitor_stop_impl(state: &tauri::State<AppState>, reason: StopReason) {
+    if let Some(r) = state.runner.lock().unwrap().take() {
+        if matches!(reason, StopReason::Panic) {
+            r.controls.panic.store(true, Ordering::Relaxed);
+        }
+        r.controls.cancel.cancel();
+        if let Some(stepper) = &r.stepper {
+            stepper.cancel(); // wake a runner held before an action
+        }
         // Detach: the loop will exit shortly; no need to await in command
     }
 }
 
 #[tauri::command]
-fn monitor_stop(state: tauri::State<AppState>) -> Result<(), String> {
+fn run_history_list(state: tauri::State<AppState>) -> Result<Vec<history::RunRecord>, Error> {
+    Ok(state.run_history.lock().unwrap().records())
+}
+
+/// Recorded runs, newest first, optionally of one profile and paged
+#[tauri::command]
+fn list_runs(query: Option<history::RunQuery>, state: tauri::State<AppState>) -> Result<Vec<history::RunRecord>, Error> {
+    Ok(state.run_history.lock().unwrap().list(&query.unwrap_or_default())?)
+}
+
+#[tauri::command]
+fn get_run_details(run_id: i64, state: tauri::State<AppState>) -> Result<history::RunDetails, Error> {
+    Ok(state
+        .run_history
+        .lock()
+        .unwrap()
+        .details(run_id)?
+        .ok_or_else(|| format!("Run {} not found", run_id))?)
+}
+
+/// Write a report of run `run_id` to `path`; `.html`/`.htm` paths get HTML, others Markdown
+#[tauri::command]
+fn export_run_report(run_id: i64, path: String, state: tauri::State<AppState>) -> Result<(), Error> {
+    let run = get_run_details(run_id, state)?;
+    let path = std::path::PathBuf::from(path);
+    let contents = report::render_report(&run, report::ReportFormat::from_path(&path));
+    Ok(std::fs::write(&path, contents).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?)
+}
+
+/// Statistics per profile over the recorded runs, optionally of one profile and of the last `days`
+#[tauri::command]
+fn get_metrics(profile_id: Option<String>, days: Option<u32>, state: tauri::State<AppState>) -> Result<Vec<metrics::ProfileMetrics>, Error> {
+    let since_ms = days.map(|days| history::now_ms().saturating_sub(days as u64 * 86_400_000));
+    let runs = state.run_history.lock().unwrap().runs_with_metrics(profile_id.as_deref(), since_ms)?;
+    Ok(metrics::aggregate(&runs))
+}
+
+/// Delete runs older than `older_than_days` and/or beyond the `keep_latest` most recent; returns how many went
+#[tauri::command]
+fn prune_runs(older_than_days: Option<u32>, keep_latest: Option<u32>, state: tauri::State<AppState>) -> Result<usize, Error> {
+    let ended_before_ms = older_than_days.map(|days| history::now_ms().saturating_sub(days as u64 * 86_400_000));
+    Ok(state.run_history.lock().unwrap().prune(history::PruneRule {
+        ended_before_ms,
+        keep_latest,
+    })?)
+}
+
+/// Checkpoint of a run interrupted by a crash or reboot, if it can still be resumed
+#[tauri::command]
+fn last_interrupted_run(state: tauri::State<AppState>) -> Result<Option<recovery::RunCheckpoint>, Error> {
+    Ok(state.interrupted_run.lock().unwrap().clone())
+}
+
+/// Restart the interrupted run with its iteration count and context variables restored
+#[tauri::command]
+fn resume_last_run(window: tauri::Window, state: tauri::State<AppState>) -> Result<String, Error> {
+    let checkpoint = state
+        .interrupted_run
+        .lock()
+        .unwrap()
+        .take()
+        .ok_or_else(|| "No interrupted run to resume".to_string())?;
+    let profile_id = checkpoint.profile_id.clone();
+    let options = RunOptions {
+        resume_from: Some(checkpoint),
+        ..RunOptions::default()
+    };
+    start_profile_run(profile_id.clone(), window, &state, options)?;
+    Ok(profile_id)
+}
+
+/// Forget the interrupted run
+#[tauri::command]
+fn discard_last_run(state: tauri::State<AppState>) -> Result<(), Error> {
+    if state.interrupted_run.lock().unwrap().take().is_some() {
+        state.checkpoints.clear();
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn monitor_stop(state: tauri::State<AppState>) -> Result<(), Error> {
     monitor_stop_impl(&state, StopReason::Graceful);
     Ok(())
 }
 
 #[tauri::command]
-fn monitor_panic_stop(state: tauri::State<AppState>) -> Result<(), String> {
+fn pause_profile(state: tauri::State<AppState>) -> Result<(), Error> {
+    Ok(set_runner_paused(&state, true)?)
+}
+
+#[tauri::command]
+fn resume_profile(state: tauri::State<AppState>) -> Result<(), Error> {
+    Ok(set_runner_paused(&state, false)?)
+}
+
+#[tauri::command]
+fn debug_step(state: tauri::State<AppState>) -> Result<(), Error> {
+    Ok(with_stepper(&state, |s| s.step())?)
+}
+
+#[tauri::command]
+fn debug_continue(state: tauri::State<AppState>) -> Result<(), Error> {
+    Ok(with_stepper(&state, |s| {
+        s.continue_run();
+        Ok(())
+    })?)
+}
+
+fn with_stepper(
+    state: &tauri::State<AppState>,
+    f: impl FnOnce(&debugger::StepController) -> Result<(), String>,
+) -> Result<(), String> {
+    match state.runner.lock().unwrap().as_ref().and_then(|r| r.stepper.as_deref()) {
+        Some(stepper) => f(stepper),
+        None => Err("No profile is running in debug mode".to_string()),
+    }
+}
+
+fn set_runner_paused(state: &tauri::State<AppState>, paused: bool) -> Result<(), String> {
+    match state.runner.lock().unwrap().as_ref() {
+        Some(r) if !r.handle.is_finished() => {
+            r.controls.pause.store(paused, Ordering::Relaxed);
+            if paused {
+                r.controls.interrupt.interrupt();
+            }
+            Ok(())
+        }
+        _ => Err("No profile is running".to_string()),
+    }
+}
+
+#[tauri::command]
+fn monitor_panic_stop(state: tauri::State<AppState>) -> Result<(), Error> {
     monitor_stop_impl(&state, StopReason::Panic);
     Ok(())
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    logging::init(logging::default_log_dir());
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_store::Builder::new().build())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .setup(|app| {
             let secure_storage = secure_storage::SecureStorage::new(app.handle())
-                .ok(); // Gracefully handle init failure
+                .ok() // Gracefully handle init failure
+                .map(|storage| {
+                    let app = app.handle().clone();
+                    storage.with_listener(move |change| {
+                        let _ = app.emit("loopautoma://settings", change);
+                    })
+                });
+            if let Some(level) = secure_storage.as_ref().and_then(|s| s.get_setting(&settings::LOG_LEVEL).ok()) {
+                if let Err(e) = logging::restore_level(&level) {
+                    warn!("Ignoring saved log level: {}", e);
+                }
+            }
             
             // Load profiles from disk on startup
-            let profiles = load_profiles_from_disk();
-            
+            let (profiles, profiles_encrypted) = load_profiles_from_disk();
+            if let Err(e) =
+                hotkey::register_profile_hotkeys(app.handle(), &profiles.profiles, hotkey_start_profile)
+            {
+                warn!("Failed to register profile hotkeys: {}", e);
+            }
+
+            let run_history = history::default_history_path()
+                .ok_or_else(|| "No config directory".to_string())
+                .and_then(|path| history::RunHistory::open(&path))
+                .unwrap_or_else(|e| {
+                    warn!("Run history unavailable, keeping this session's runs in memory: {}", e);
+                    history::RunHistory::default()
+                });
+            let checkpoints = recovery::default_checkpoint_path()
+                .map(recovery::CheckpointStore::new)
+                .unwrap_or_default();
+            let interrupted_run = checkpoints.load();
+            let quiet_hours = secure_storage
+                .as_ref()
+                .and_then(|s| s.get_setting(&settings::QUIET_HOURS).ok())
+                .map(notify::QuietPolicy::new)
+                .transpose()
+                .unwrap_or_else(|e| {
+                    warn!("Ignoring saved quiet hours: {}", e);
+                    None
+                })
+                .unwrap_or_default();
+
             app.manage(AppState {
                 profiles: Mutex::new(profiles),
                 runner: Mutex::new(None),
                 secure_storage,
+                run_history: Arc::new(Mutex::new(run_history)),
+                checkpoints: Arc::new(checkpoints),
+                interrupted_run: Mutex::new(interrupted_run),
+                profiles_encrypted: AtomicBool::new(profiles_encrypted),
+                last_sync: Mutex::new(None),
+                quiet_hours: Arc::new(Mutex::new(quiet_hours)),
+                region_previews: preview::PreviewStreams::default(),
+                region_picker: Mutex::new(None),
+                runner_status: Mutex::new(runner_state::RunnerStatus::default()),
+                remote_api: Mutex::new(None),
+                prometheus: Arc::new(prometheus::Metrics::default()),
+                metrics_exporter: Mutex::new(None),
+                voice: Mutex::new(None),
+                runtime: runtime::AsyncRuntime::shared()?,
+                shutdown: Arc::new(shutdown::Shutdown::default()),
+            });
+            let handle = app.handle().clone();
+            app.state::<AppState>().shutdown.on_shutdown("remote_api", move || {
+                // Dropping the server unblocks its thread
+                handle.state::<AppState>().remote_api.lock().unwrap().take();
+            });
+            let remote_config = app
+                .state::<AppState>()
+                .secure_storage
+                .as_ref()
+                .map(|s| s.get_setting(&settings::REMOTE_API))
+                .transpose()
+                .map(Option::flatten);
+            if let Err(e) = remote_config.and_then(|config| restart_remote_api(app.handle(), config.as_ref())) {
+                warn!("Remote API not started: {}", e);
+            }
+            let handle = app.handle().clone();
+            app.state::<AppState>().shutdown.on_shutdown("metrics_exporter", move || {
+                handle.state::<AppState>().metrics_exporter.lock().unwrap().take();
+            });
+            let metrics_port = app
+                .state::<AppState>()
+                .secure_storage
+                .as_ref()
+                .map(|s| s.get_setting(&settings::METRICS_PORT))
+                .transpose()
+                .map(Option::flatten);
+            if let Err(e) = metrics_port.and_then(|port| restart_metrics_exporter(&app.state::<AppState>(), port)) {
+                warn!("Metrics exporter not started: {}", e);
+            }
+            let handle = app.handle().clone();
+            app.state::<AppState>().shutdown.on_shutdown("voice", move || {
+                handle.state::<AppState>().voice.lock().unwrap().take();
+            });
+            if let Err(e) = voice_config(&app.state::<AppState>()).and_then(|config| restart_voice_commands(app.handle(), config.as_ref())) {
+                warn!("Voice commands not started: {}", e);
+            }
+            // Runs after the runner stopped, so its last spans are still sent
+            app.state::<AppState>().shutdown.on_shutdown("otlp_traces", || otel::tracer().stop());
+            let otlp_config = app
+                .state::<AppState>()
+                .secure_storage
+                .as_ref()
+                .map(|s| s.get_setting(&settings::OTLP_TRACES))
+                .transpose()
+                .map(Option::flatten);
+            if let Err(e) = otlp_config.and_then(|config| otel::tracer().start(config.as_ref())) {
+                warn!("Trace export not started: {}", e);
+            }
+            match app.state::<AppState>().secure_storage.as_ref().map(|s| s.get_setting(&settings::INPUT_CALIBRATION)) {
+                Some(Ok(transform)) => calibration::set_current(transform),
+                Some(Err(e)) => warn!("Pointer calibration not applied: {}", e),
+                None => {}
+            }
+            // Before any run, so profiles find their plugins
+            let enabled_plugins = app
+                .state::<AppState>()
+                .secure_storage
+                .as_ref()
+                .map(|s| s.get_setting(&settings::PLUGINS_ENABLED))
+                .transpose()
+                .unwrap_or_else(|e| {
+                    warn!("Plugins not loaded: {}", e);
+                    None
+                })
+                .unwrap_or_default();
+            if let Some(dir) = plugins::default_plugins_dir() {
+                plugins::registry().load_dir(&dir, &enabled_plugins);
+            }
+            if let Err(e) = tray::install(app.handle(), &tray_view(&app.state::<AppState>()), tray_command) {
+                warn!("Tray icon unavailable: {}", e);
+            }
+            let handle = app.handle().clone();
+            app.listen(runner_state::CHANNEL, move |event| {
+                if let Ok(update) = serde_json::from_str::<runner_state::RunnerStateUpdate>(event.payload()) {
+                    handle.state::<AppState>().runner_status.lock().unwrap().apply(&update);
+                    refresh_tray(&handle);
+                }
             });
+            let handle = app.handle().clone();
+            app.listen("loopautoma://run_recorded", move |_| refresh_tray(&handle));
+            spawn_profile_sync_scheduler(app.handle().clone());
             Ok(())
         })
+        .on_window_event(|window, event| {
+            // During a run, closing the main window hides it; the tray icon brings it back
+            if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                let running = window
+                    .state::<AppState>()
+                    .runner
+                    .lock()
+                    .unwrap()
+                    .as_ref()
+                    .is_some_and(|r| !r.handle.is_finished());
+                if window.label() == "main" && running && window.app_handle().tray_by_id(tray::TRAY_ID).is_some() {
+                    api.prevent_close();
+                    let _ = window.hide();
+                }
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             greet,
             profiles_load,
             profiles_save,
+            profiles_encryption_get,
+            profiles_encryption_set,
+            profile_sync_get,
+            profile_sync_set,
+            profile_sync_now,
+            profile_sync_status,
+            profile_export,
+            profile_import,
+            list_templates,
+            create_from_template,
+            validate_profile,
             monitor_start,
             monitor_stop,
             monitor_panic_stop,
+            pause_profile,
+            resume_profile,
+            debug_step,
+            debug_continue,
             window_info,
             window_position,
             region_picker_show,
             region_picker_complete,
             region_picker_cancel,
+            region_pick,
             region_capture_thumbnail,
+            region_anchor_create,
+            calibrate,
+            calibration_get,
+            calibration_reset,
+            enumerate_windows,
+            find_elements,
+            automation_capabilities,
+            start_region_preview,
+            stop_region_preview,
             action_recorder_show,
             action_recorder_close,
             action_recorder_complete,
@@ -521,29 +1797,122 @@ pub fn run() {
             delete_openai_key,
             get_openai_model,
             set_openai_model,
+            provider_list,
+            provider_set,
+            provider_delete,
             audio_test_intervention,
             audio_test_completed,
             audio_set_enabled,
             audio_get_enabled,
             audio_set_volume,
             audio_get_volume,
+            audio_get_custom_sounds,
+            audio_set_custom_sound,
+            audio_test_alert,
+            audio_get_sound_map,
+            audio_set_sound_map,
+            webhooks_get,
+            webhooks_set,
+            webhook_test,
+            quiet_hours_get,
+            quiet_hours_set,
+            log_level_get,
+            log_level_set,
+            tail_logs,
+            profile_secrets_list,
+            profile_secret_set,
+            profile_secret_delete,
+            env_credentials_detect,
+            import_env_credentials,
+            settings_defaults,
+            settings_snapshot,
+            settings_export,
+            settings_import,
+            smtp_get,
+            smtp_set,
+            smtp_test,
+            remote_api_get,
+            remote_api_set,
+            remote_api_status,
+            remote_api_generate_token,
+            metrics_exporter_get,
+            metrics_exporter_set,
+            metrics_exporter_status,
+            voice_commands_get,
+            voice_commands_set,
+            voice_commands_status,
+            otlp_traces_get,
+            otlp_traces_set,
+            otlp_traces_status,
+            plugins_list,
+            plugins_set_enabled,
+            run_history_list,
+            list_runs,
+            get_run_details,
+            export_run_report,
+            export_run_frames,
+            prune_runs,
+            get_metrics,
+            last_interrupted_run,
+            resume_last_run,
+            discard_last_run,
             app_quit
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                shutdown_app(app);
+            }
+        });
+}
+
+/// Stop every background thread and release held input before the process exits
+fn shutdown_app(app: &tauri::AppHandle) {
+    let state = app.state::<AppState>();
+    if let Some(stepper) = state.runner.lock().unwrap().as_ref().and_then(|r| r.stepper.clone()) {
+        stepper.cancel(); // wake a runner held before an action
+    }
+    let report = state.shutdown.run(shutdown::SHUTDOWN_TIMEOUT);
+    if report.timed_out.is_empty() {
+        info!("Background threads stopped");
+    } else {
+        warn!(
+            "Exiting while {} still running after {:?}",
+            report.timed_out.join(", "),
+            shutdown::SHUTDOWN_TIMEOUT
+        );
+    }
+}
+
+/// `loopautoma --mcp`: serve the Model Context Protocol on stdin/stdout instead of opening the app
+pub fn run_mcp_server() -> Result<(), String> {
+    logging::init(logging::default_log_dir());
+    let (profiles, _) = load_profiles_from_disk();
+    let run_history = history::default_history_path()
+        .ok_or_else(|| "No config directory".to_string())
+        .and_then(|path| history::RunHistory::open(&path))?;
+    let checkpoints = recovery::default_checkpoint_path()
+        .map(recovery::CheckpointStore::new)
+        .unwrap_or_default();
+    let metrics_port = match std::env::var("LOOPAUTOMA_METRICS_PORT") {
+        Ok(port) => Some(port.parse().map_err(|_| format!("LOOPAUTOMA_METRICS_PORT is not a port: {}", port))?),
+        Err(_) => None,
+    };
+    mcp::serve_stdio(profiles.profiles, run_history, checkpoints, metrics_port)
 }
 
 #[tauri::command]
-fn window_position(window: tauri::Window) -> Result<(i32, i32), String> {
-    window
+fn window_position(window: tauri::Window) -> Result<(i32, i32), Error> {
+    Ok(window
         .outer_position()
         .map(|p| (p.x as i32, p.y as i32))
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?)
 }
 
 // Window geometry helper providing outer position and scale factor (for HiDPI / multi-monitor)
 #[tauri::command]
-fn window_info(window: tauri::Window) -> Result<(i32, i32, f64), String> {
+fn window_info(window: tauri::Window) -> Result<(i32, i32, f64), Error> {
     let pos = window.outer_position().map_err(|e| e.to_string())?;
     let scale = window.scale_factor().map_err(|e| e.to_string())?;
     Ok((pos.x as i32, pos.y as i32, scale))
@@ -565,15 +1934,50 @@ struct RegionPickSubmission {
 struct RegionPickPayload {
     rect: Rect,
     thumbnail_png_base64: Option<String>,
+    /// The rectangle as a region, clipped to the display it was drawn on
+    picked: region_picker::PickedRegion,
 }
 
+/// Open the region overlay on a display (the primary one by default)
 #[tauri::command]
-fn region_picker_show(app: tauri::AppHandle) -> Result<(), String> {
+fn region_picker_show(
+    display_id: Option<u32>,
+    app: tauri::AppHandle,
+    state: tauri::State<AppState>,
+) -> Result<(), Error> {
+    Ok(open_region_picker(&app, &state, display_id, None)?)
+}
+
+/// Open the region overlay and wait until a rectangle is drawn; `None` if the picker is cancelled
+#[tauri::command(async)]
+fn region_pick(
+    display_id: Option<u32>,
+    app: tauri::AppHandle,
+    state: tauri::State<AppState>,
+) -> Result<Option<region_picker::PickedRegion>, String> {
+    let (reply, picked) = std::sync::mpsc::channel();
+    open_region_picker(&app, &state, display_id, Some(reply))?;
+    // A closed channel means the picker was replaced or the overlay went away
+    Ok(picked.recv().unwrap_or(None))
+}
+
+fn open_region_picker(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    display_id: Option<u32>,
+    reply: Option<std::sync::mpsc::Sender<Option<region_picker::PickedRegion>>>,
+) -> Result<(), String> {
     if let Some(win) = app.get_webview_window("region-overlay") {
         let _ = win.set_focus();
+        // Whoever waits on the open picker now waits on this request instead
+        if let (Some(session), Some(reply)) = (state.region_picker.lock().unwrap().as_mut(), reply) {
+            session.reply = Some(reply);
+        }
         return Ok(());
     }
-    
+
+    let display = region_picker::choose_display(&make_capture().displays().map_err(|e| e.to_string())?, display_id)?;
+
     // Hide main window first
     if let Some(main) = app.get_webview_window("main") {
         let _ = main.hide();
@@ -582,17 +1986,25 @@ fn region_picker_show(app: tauri::AppHandle) -> Result<(), String> {
     // Give time for window to minimize and desktop to redraw
     std::thread::sleep(std::time::Duration::from_millis(200));
     
-    // Capture full screen screenshot
-    let screenshot_base64 = capture_full_screen().map_err(|e| e.to_string())?;
+    // Capture the display the overlay covers
+    let screenshot_base64 = match capture_display(&display) {
+        Ok(png) => png,
+        Err(e) => {
+            restore_main_window(app);
+            return Err(e.to_string());
+        }
+    };
     
-    // Build overlay window with screenshot URL
+    // Build overlay window with screenshot URL, on the chosen display
     let screenshot_url = format!("data:image/png;base64,{}", screenshot_base64);
+    let scale = f64::from(display.scale_factor.max(0.1));
     tauri::WebviewWindowBuilder::new(
-        &app,
+        app,
         "region-overlay",
         tauri::WebviewUrl::App("index.html".into()),
     )
     .title("Select region")
+    .position(f64::from(display.x) / scale, f64::from(display.y) / scale)
     .fullscreen(true)
     .decorations(false)
     .always_on_top(true)
@@ -603,8 +2015,12 @@ fn region_picker_show(app: tauri::AppHandle) -> Result<(), String> {
         screenshot_url
     ))
     .build()
-    .map_err(|e| e.to_string())?;
-    
+    .map_err(|e| {
+        restore_main_window(app);
+        e.to_string()
+    })?;
+
+    *state.region_picker.lock().unwrap() = Some(region_picker::PickerSession { display, reply });
     Ok(())
 }
 
@@ -612,20 +2028,35 @@ fn region_picker_show(app: tauri::AppHandle) -> Result<(), String> {
 fn region_picker_complete(
     app: tauri::AppHandle,
     submission: RegionPickSubmission,
-) -> Result<(), String> {
+    state: tauri::State<AppState>,
+) -> Result<(), Error> {
     let rect = normalize_rect(&submission.start, &submission.end)
         .ok_or_else(|| "Region must have a non-zero area".to_string())?;
-    let preview = capture_thumbnail(&rect).map_err(|e| e.to_string())?;
+    let session = state.region_picker.lock().unwrap().take();
+    let display = match &session {
+        Some(session) => session.display.clone(),
+        None => region_picker::choose_display(&make_capture().displays().map_err(|e| e.to_string())?, None)?,
+    };
+    let picked = match region_picker::pick(rect, &display, format!("region-{:x}", history::now_ms())) {
+        Ok(picked) => picked,
+        Err(e) => {
+            // Keep the overlay open so the rectangle can be drawn again
+            *state.region_picker.lock().unwrap() = session;
+            return Err(e.into());
+        }
+    };
+    let preview = capture_thumbnail(&picked.region.rect).map_err(|e| e.to_string())?;
     let payload = RegionPickPayload {
-        rect,
+        rect: picked.region.rect,
         thumbnail_png_base64: preview,
+        picked: picked.clone(),
     };
     app.emit("loopautoma://region_pick_complete", &payload)
         .map_err(|e| e.to_string())?;
-    if let Some(main) = app.get_webview_window("main") {
-        let _ = main.show();
-        let _ = main.set_focus();
+    if let Some(reply) = session.and_then(|s| s.reply) {
+        let _ = reply.send(Some(picked));
     }
+    restore_main_window(&app);
     if let Some(overlay) = app.get_webview_window("region-overlay") {
         let _ = overlay.close();
     }
@@ -633,19 +2064,26 @@ fn region_picker_complete(
 }
 
 #[tauri::command]
-fn region_picker_cancel(app: tauri::AppHandle) -> Result<(), String> {
-    if let Some(main) = app.get_webview_window("main") {
-        let _ = main.show();
-        let _ = main.set_focus();
+fn region_picker_cancel(app: tauri::AppHandle, state: tauri::State<AppState>) -> Result<(), Error> {
+    if let Some(reply) = state.region_picker.lock().unwrap().take().and_then(|s| s.reply) {
+        let _ = reply.send(None);
     }
+    restore_main_window(&app);
     if let Some(overlay) = app.get_webview_window("region-overlay") {
         let _ = overlay.close();
     }
     Ok(())
 }
 
+fn restore_main_window(app: &tauri::AppHandle) {
+    if let Some(main) = app.get_webview_window("main") {
+        let _ = main.show();
+        let _ = main.set_focus();
+    }
+}
+
 #[tauri::command]
-fn action_recorder_close(app: tauri::AppHandle) -> Result<(), String> {
+fn action_recorder_close(app: tauri::AppHandle) -> Result<(), Error> {
     if let Some(main) = app.get_webview_window("main") {
         let _ = main.show();
         let _ = main.set_focus();
@@ -660,7 +2098,7 @@ fn action_recorder_close(app: tauri::AppHandle) -> Result<(), String> {
 fn action_recorder_complete(
     app: tauri::AppHandle,
     actions: Vec<serde_json::Value>,
-) -> Result<(), String> {
+) -> Result<(), Error> {
     // Emit actions to main window
     app.emit("loopautoma://action_recorder_complete", &actions)
         .map_err(|e| e.to_string())?;
@@ -680,7 +2118,7 @@ fn action_recorder_complete(
 }
 
 #[tauri::command]
-fn app_quit(app: tauri::AppHandle) -> Result<(), String> {
+fn app_quit(app: tauri::AppHandle) -> Result<(), Error> {
     if let Some(overlay) = app.get_webview_window("region-overlay") {
         let _ = overlay.close();
     }
@@ -695,8 +2133,128 @@ fn app_quit(app: tauri::AppHandle) -> Result<(), String> {
 }
 
 #[tauri::command]
-fn region_capture_thumbnail(rect: Rect) -> Result<Option<String>, String> {
-    capture_thumbnail(&rect).map_err(|e| e.to_string())
+fn region_capture_thumbnail(rect: Rect) -> Result<Option<String>, Error> {
+    Ok(capture_thumbnail(&rect).map_err(|e| e.to_string())?)
+}
+
+/// Anchor a region at `region` to what is on screen at `anchor` now
+#[tauri::command]
+fn region_anchor_create(region: Rect, anchor: Rect) -> Result<RegionAnchor, Error> {
+    Ok(anchor::create(region, anchor, make_backend_capture().as_ref()).map_err(|e| e.to_string())?)
+}
+
+/// Move the pointer over every display, check where it lands and save the correction for later moves
+#[tauri::command(async)]
+fn calibrate(state: tauri::State<AppState>) -> Result<calibration::CalibrationReport, String> {
+    let running = state.runner.lock().unwrap().as_ref().is_some_and(|r| !r.handle.is_finished());
+    if running {
+        return Err("Stop the running profile before calibrating the pointer".into());
+    }
+    let storage = state
+        .secure_storage
+        .as_ref()
+        .ok_or_else(|| "Secure storage not initialized".to_string())?;
+    let displays = make_backend_capture().displays().map_err(|e| e.to_string())?;
+    let report = calibration::calibrate(make_backend_automation().as_ref(), &displays, Duration::from_millis(50))
+        .map_err(|e| e.to_string())?;
+    storage.set_setting(&settings::INPUT_CALIBRATION, &report.transform)?;
+    calibration::set_current(report.transform);
+    Ok(report)
+}
+
+/// The pointer correction in use, if the last calibration found one
+#[tauri::command]
+fn calibration_get() -> Option<calibration::CursorTransform> {
+    calibration::current()
+}
+
+/// Drop the pointer correction; moves go where they are sent again
+#[tauri::command]
+fn calibration_reset(state: tauri::State<AppState>) -> Result<(), Error> {
+    if let Some(storage) = &state.secure_storage {
+        storage.set_setting(&settings::INPUT_CALIBRATION, &None)?;
+    }
+    calibration::set_current(None);
+    Ok(())
+}
+
+/// Which input backend runs will use and what it can do in this session
+#[tauri::command]
+fn automation_capabilities() -> AutomationCapabilities {
+    probe_automation()
+}
+
+/// Top-level windows with their title, owning process and screen rectangle
+#[tauri::command]
+fn enumerate_windows() -> Result<Vec<WindowInfo>, Error> {
+    Ok(make_window_enumerator().windows().map_err(|e| e.to_string())?)
+}
+
+/// Visible UI elements matching a role and/or name, with their screen rectangles
+#[tauri::command]
+fn find_elements(query: ElementQuery) -> Result<Vec<UiElement>, Error> {
+    query.validate()?;
+    Ok(make_element_finder().find(&query).map_err(|e| e.to_string())?)
+}
+
+/// Stream downscaled frames of a saved region as `loopautoma://region_preview` until stopped
+#[tauri::command]
+fn start_region_preview(
+    region_id: String,
+    fps: f64,
+    app: tauri::AppHandle,
+    state: tauri::State<AppState>,
+) -> Result<(), Error> {
+    let interval = preview::frame_interval(fps)?;
+    if preview::find_region(&state.profiles.lock().unwrap(), &region_id).is_none() {
+        return Err(format!("Region '{}' not found", region_id).into());
+    }
+    let stop = state.region_previews.start(&region_id);
+    state.shutdown.spawn("preview", None, move |token| {
+        let capture = make_capture();
+        let mut last_frame = None;
+        while !stop.load(Ordering::Relaxed) && !token.is_cancelled() {
+            let state = app.state::<AppState>();
+            // The region was deleted: nothing left to show
+            let Some(region) = preview::find_region(&state.profiles.lock().unwrap(), &region_id) else {
+                break;
+            };
+            match capture.capture_region(&region) {
+                Ok(frame) => {
+                    let fingerprint = (region.rect, frame_fingerprint(&frame));
+                    if last_frame != Some(fingerprint) {
+                        last_frame = Some(fingerprint);
+                        if let Some(png_base64) = encode_png_thumbnail(&frame) {
+                            let payload = preview::RegionPreviewFrame {
+                                region_id: region_id.clone(),
+                                rect: region.rect,
+                                png_base64,
+                                captured_at_ms: history::now_ms(),
+                            };
+                            let _ = app.emit(preview::CHANNEL, &payload);
+                        }
+                    }
+                }
+                Err(err) => warn!("Preview capture of '{}' failed: {}", region_id, err),
+            }
+            token.sleep(interval);
+        }
+        app.state::<AppState>().region_previews.finished(&region_id, &stop);
+    });
+    Ok(())
+}
+
+#[tauri::command]
+fn stop_region_preview(region_id: String, state: tauri::State<AppState>) -> Result<(), Error> {
+    state.region_previews.stop(&region_id);
+    Ok(())
+}
+
+fn frame_fingerprint(frame: &ScreenFrame) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    (frame.width, frame.height, &frame.bytes).hash(&mut hasher);
+    hasher.finish()
 }
 
 pub(crate) fn normalize_rect(start: &PickPoint, end: &PickPoint) -> Option<Rect> {
@@ -724,27 +2282,19 @@ pub(crate) fn normalize_rect(start: &PickPoint, end: &PickPoint) -> Option<Rect>
 }
 
 fn capture_full_screen() -> Result<String, BackendError> {
+    let displays = make_capture().displays()?;
+    let primary = region_picker::choose_display(&displays, None).map_err(|e| BackendError::new("capture", e))?;
+    capture_display(&primary)
+}
+
+/// Full-size PNG of one display, base64 encoded
+fn capture_display(display: &DisplayInfo) -> Result<String, BackendError> {
     let capture = make_capture();
-    
-    // Get primary display info
-    let displays = capture.displays()?;
-    let primary = displays.iter()
-        .find(|d| d.is_primary)
-        .or_else(|| displays.first())
-        .ok_or_else(|| BackendError::new("capture", "No displays found"))?;
-    
-    // Create a region covering the entire primary display
-    let rect = Rect {
-        x: primary.x.max(0) as u32,
-        y: primary.y.max(0) as u32,
-        width: primary.width,
-        height: primary.height,
-    };
-    
     let region = Region {
         id: "fullscreen".into(),
-        rect,
+        rect: region_picker::display_rect(display),
         name: None,
+        anchor: None,
     };
     
     let frame = capture.capture_region(&region)?;
@@ -775,11 +2325,12 @@ fn capture_thumbnail(rect: &Rect) -> Result<Option<String>, BackendError> {
         id: "region-thumbnail".into(),
         rect: *rect,
         name: None,
+        anchor: None,
     };
     match capture.capture_region(&region) {
         Ok(frame) => Ok(encode_png_thumbnail(&frame)),
         Err(err) => {
-            eprintln!("thumbnail capture failed: {err}");
+            warn!("Thumbnail capture failed: {err}");
             Ok(None)
         }
     }
@@ -816,7 +2367,7 @@ fn encode_png_thumbnail(frame: &ScreenFrame) -> Option<String> {
 }
 
 #[tauri::command]
-fn action_recorder_show(app: tauri::AppHandle) -> Result<(), String> {
+fn action_recorder_show(app: tauri::AppHandle) -> Result<(), Error> {
     // Check if Action Recorder window already exists
     if let Some(win) = app.get_webview_window("action-recorder") {
         let _ = win.set_focus();
@@ -860,93 +2411,595 @@ fn action_recorder_show(app: tauri::AppHandle) -> Result<(), String> {
 // ===== Secure Storage Commands =====
 
 #[tauri::command]
-fn get_openai_key_status(state: tauri::State<AppState>) -> Result<bool, String> {
+fn get_openai_key_status(state: tauri::State<AppState>) -> Result<bool, Error> {
     match &state.secure_storage {
-        Some(storage) => storage.has_openai_key(),
-        None => Err("Secure storage not initialized".to_string()),
+        Some(storage) => Ok(storage.has_openai_key()?),
+        None => Err("Secure storage not initialized".into()),
     }
 }
 
 #[tauri::command]
-fn set_openai_key(key: String, state: tauri::State<AppState>) -> Result<(), String> {
+fn set_openai_key(key: String, state: tauri::State<AppState>) -> Result<(), Error> {
     if key.trim().is_empty() {
-        return Err("API key cannot be empty".to_string());
+        return Err("API key cannot be empty".into());
+    }
+    match &state.secure_storage {
+        Some(storage) => Ok(storage.set_openai_key(&key)?),
+        None => Err("Secure storage not initialized".into()),
+    }
+}
+
+#[tauri::command]
+fn delete_openai_key(state: tauri::State<AppState>) -> Result<(), Error> {
+    match &state.secure_storage {
+        Some(storage) => Ok(storage.delete_openai_key()?),
+        None => Err("Secure storage not initialized".into()),
+    }
+}
+
+#[tauri::command]
+fn get_openai_model(state: tauri::State<AppState>) -> Result<Option<String>, Error> {
+    match &state.secure_storage {
+        Some(storage) => Ok(storage.get_setting(&settings::OPENAI_MODEL)?),
+        None => Err("Secure storage not initialized".into()),
+    }
+}
+
+#[tauri::command]
+fn set_openai_model(model: String, state: tauri::State<AppState>) -> Result<(), Error> {
+    match &state.secure_storage {
+        Some(storage) => Ok(storage.set_setting(&settings::OPENAI_MODEL, &Some(model))?),
+        None => Err("Secure storage not initialized".into()),
+    }
+}
+
+/// LLM providers with stored credentials; keys are reported as present, never returned
+#[tauri::command]
+fn provider_list(state: tauri::State<AppState>) -> Result<Vec<credentials::ProviderStatus>, Error> {
+    match &state.secure_storage {
+        Some(storage) => Ok(storage.list_providers()?),
+        None => Err("Secure storage not initialized".into()),
+    }
+}
+
+/// Add or update a provider; `id` is its kind, or `custom:<name>` for extra endpoints
+#[tauri::command]
+fn provider_set(
+    id: String,
+    credentials: credentials::ProviderCredentials,
+    state: tauri::State<AppState>,
+) -> Result<(), Error> {
+    match &state.secure_storage {
+        Some(storage) => Ok(storage.set_provider(&id, &credentials)?),
+        None => Err("Secure storage not initialized".into()),
+    }
+}
+
+#[tauri::command]
+fn provider_delete(id: String, state: tauri::State<AppState>) -> Result<(), Error> {
+    match &state.secure_storage {
+        Some(storage) => Ok(storage.delete_provider(&id)?),
+        None => Err("Secure storage not initialized".into()),
+    }
+}
+
+// Remote profile sync commands
+
+#[tauri::command]
+fn profile_sync_get(state: tauri::State<AppState>) -> Result<Option<sync::SyncConfig>, Error> {
+    match &state.secure_storage {
+        Some(storage) => Ok(storage.get_profile_sync()?),
+        None => Err("Secure storage not initialized".into()),
+    }
+}
+
+/// Save sync settings; `None` turns sync off
+#[tauri::command]
+fn profile_sync_set(config: Option<sync::SyncConfig>, state: tauri::State<AppState>) -> Result<(), Error> {
+    if let Some(config) = &config {
+        config.validate()?;
+    }
+    match &state.secure_storage {
+        Some(storage) => Ok(storage.set_profile_sync(config.as_ref())?),
+        None => Err("Secure storage not initialized".into()),
+    }
+}
+
+#[tauri::command]
+fn profile_sync_now(app: tauri::AppHandle) -> Result<sync::SyncReport, Error> {
+    Ok(run_profile_sync(&app)?)
+}
+
+#[tauri::command]
+fn profile_sync_status(state: tauri::State<AppState>) -> Option<sync::SyncStatus> {
+    state.last_sync.lock().unwrap().clone()
+}
+
+/// Pull, verify and merge the configured bundle; records the outcome for `profile_sync_status`
+fn run_profile_sync(app: &tauri::AppHandle) -> Result<sync::SyncReport, String> {
+    let state = app.state::<AppState>();
+    let result = (|| {
+        let config = state
+            .secure_storage
+            .as_ref()
+            .ok_or_else(|| "Secure storage not initialized".to_string())?
+            .get_profile_sync()?
+            .ok_or_else(|| "Profile sync is not configured".to_string())?;
+        let cache_dir = sync::default_cache_dir().ok_or_else(|| "Failed to get config directory".to_string())?;
+        let fetched = sync::fetch(&state.runtime, &config.source, &cache_dir)?;
+        let (sha256, remote) = sync::verify_and_parse(&fetched, &config)?;
+
+        let mut profiles = state.profiles.lock().unwrap().clone();
+        let report = sync::merge(&mut profiles.profiles, remote, sha256);
+        if report.changed() {
+            apply_profiles(profiles, app, &state)?;
+            let _ = app.emit("loopautoma://profiles_synced", &report);
+        }
+        Ok(report)
+    })();
+    *state.last_sync.lock().unwrap() = Some(sync::SyncStatus {
+        at_ms: history::now_ms(),
+        report: result.as_ref().ok().cloned(),
+        error: result.as_ref().err().cloned(),
+    });
+    result
+}
+
+/// Pull profiles every `interval_minutes`; settings are re-read each minute so changes apply without a restart
+fn spawn_profile_sync_scheduler(app: tauri::AppHandle) {
+    let shutdown = app.state::<AppState>().shutdown.clone();
+    shutdown.spawn("profile_sync", None, move |token| loop {
+        if token.sleep(Duration::from_secs(60)) {
+            break;
+        }
+        let state = app.state::<AppState>();
+        let interval_ms = match state.secure_storage.as_ref().map(|s| s.get_profile_sync()) {
+            Some(Ok(Some(config))) if config.interval_minutes > 0 => config.interval_minutes as u64 * 60_000,
+            _ => continue,
+        };
+        let last = state.last_sync.lock().unwrap().as_ref().map(|s| s.at_ms).unwrap_or(0);
+        if history::now_ms().saturating_sub(last) < interval_ms {
+            continue;
+        }
+        match run_profile_sync(&app) {
+            Ok(report) if report.changed() => info!(
+                "Added {:?}, updated {:?} from bundle {}",
+                report.added, report.updated, report.sha256
+            ),
+            Ok(_) => {}
+            Err(e) => warn!("Profile sync failed: {}", e),
+        }
+    });
+}
+
+/// Slack/Discord/Telegram targets that receive run notifications
+#[tauri::command]
+fn webhooks_get(state: tauri::State<AppState>) -> Result<Vec<webhooks::WebhookConfig>, Error> {
+    match &state.secure_storage {
+        Some(storage) => Ok(storage.get_webhooks()?),
+        None => Ok(Vec::new()),
+    }
+}
+
+#[tauri::command]
+fn webhooks_set(webhooks: Vec<webhooks::WebhookConfig>, state: tauri::State<AppState>) -> Result<(), Error> {
+    for w in &webhooks {
+        w.validate()?;
+    }
+    match &state.secure_storage {
+        Some(storage) => Ok(storage.set_webhooks(&webhooks)?),
+        None => Err("Secure storage not initialized".into()),
+    }
+}
+
+/// Send a test message to one target and report whether it was accepted
+#[tauri::command]
+fn webhook_test(webhook: webhooks::WebhookConfig, state: tauri::State<AppState>) -> Result<(), Error> {
+    webhook.validate()?;
+    let screenshot = webhook.wants_screenshot().then(capture_full_screen_png).flatten();
+    let request = webhooks::build_request(
+        &webhook,
+        "LoopAutoma: test notification",
+        screenshot.as_deref(),
+        &webhooks::new_boundary(),
+    );
+    Ok(webhooks::send(&state.runtime, &request)?)
+}
+
+/// Names of a profile's `{{secret:name}}` values; the values never leave secure storage
+#[tauri::command]
+fn profile_secrets_list(profile_id: String, state: tauri::State<AppState>) -> Result<Vec<String>, Error> {
+    match &state.secure_storage {
+        Some(storage) => Ok(storage.list_profile_secrets(&profile_id)?),
+        None => Ok(Vec::new()),
+    }
+}
+
+#[tauri::command]
+fn profile_secret_set(profile_id: String, name: String, value: String, state: tauri::State<AppState>) -> Result<(), Error> {
+    match &state.secure_storage {
+        Some(storage) => Ok(storage.set_profile_secret(&profile_id, &name, &value)?),
+        None => Err("Secure storage not initialized".into()),
+    }
+}
+
+#[tauri::command]
+fn profile_secret_delete(profile_id: String, name: String, state: tauri::State<AppState>) -> Result<(), Error> {
+    match &state.secure_storage {
+        Some(storage) => Ok(storage.delete_profile_secret(&profile_id, &name)?),
+        None => Err("Secure storage not initialized".into()),
+    }
+}
+
+/// API keys found in the environment, so they can be moved into secure storage
+#[tauri::command]
+fn env_credentials_detect(state: tauri::State<AppState>) -> Result<Vec<credentials::EnvKeyStatus>, Error> {
+    let storage = state.secure_storage.as_ref().ok_or("Secure storage not initialized")?;
+    Ok(storage.env_keys(&|var| std::env::var(var).ok()))
+}
+
+/// Store the keys of the given environment variables; stored keys take precedence from then on
+#[tauri::command]
+fn import_env_credentials(vars: Vec<String>, state: tauri::State<AppState>) -> Result<Vec<String>, Error> {
+    let storage = state.secure_storage.as_ref().ok_or("Secure storage not initialized")?;
+    Ok(storage.import_env_keys(&vars, &|var| std::env::var(var).ok())?)
+}
+
+/// Default of every typed setting, keyed by its namespaced key
+#[tauri::command]
+fn settings_defaults() -> BTreeMap<&'static str, serde_json::Value> {
+    settings::defaults()
+}
+
+/// Current settings for a window opening; later changes arrive as `loopautoma://settings`
+#[tauri::command]
+fn settings_snapshot(state: tauri::State<AppState>) -> Result<settings::SettingsSnapshot, Error> {
+    Ok(state.secure_storage.as_ref().ok_or("Secure storage not initialized")?.snapshot()?)
+}
+
+/// Write all settings except credentials to a JSON file; the file lists the secrets left out
+#[tauri::command]
+fn settings_export(path: String, state: tauri::State<AppState>) -> Result<(), Error> {
+    let storage = state.secure_storage.as_ref().ok_or("Secure storage not initialized")?;
+    let contents = serde_json::to_string_pretty(&storage.export_settings()?)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    Ok(std::fs::write(&path, contents).map_err(|e| format!("Failed to write {}: {}", path, e))?)
+}
+
+/// Apply a file written by `settings_export`; the report names secrets to enter again
+#[tauri::command]
+fn settings_import(path: String, state: tauri::State<AppState>) -> Result<settings::ImportReport, Error> {
+    let storage = state.secure_storage.as_ref().ok_or("Secure storage not initialized")?;
+    let contents = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let export: settings::SettingsExport =
+        serde_json::from_str(&contents).map_err(|e| format!("Invalid settings file {}: {}", path, e))?;
+    let report = storage.import_settings(&export)?;
+    *state.quiet_hours.lock().unwrap() = notify::QuietPolicy::new(storage.get_setting(&settings::QUIET_HOURS)?)?;
+    Ok(report)
+}
+
+/// Quiet hours for sounds and desktop notifications
+#[tauri::command]
+fn quiet_hours_get(state: tauri::State<AppState>) -> Result<notify::QuietHours, Error> {
+    Ok(state.quiet_hours.lock().unwrap().hours().clone())
+}
+
+/// Save the quiet hours; running profiles pick them up at their next notification
+#[tauri::command]
+fn quiet_hours_set(quiet_hours: notify::QuietHours, state: tauri::State<AppState>) -> Result<(), Error> {
+    let policy = notify::QuietPolicy::new(quiet_hours)?;
+    match &state.secure_storage {
+        Some(storage) => storage.set_setting(&settings::QUIET_HOURS, policy.hours())?,
+        None => return Err("Secure storage not initialized".into()),
+    }
+    *state.quiet_hours.lock().unwrap() = policy;
+    Ok(())
+}
+
+/// Current backend log level
+#[tauri::command]
+fn log_level_get() -> String {
+    logging::level()
+}
+
+/// Change the backend log level now and on later starts
+#[tauri::command]
+fn log_level_set(level: String, state: tauri::State<AppState>) -> Result<(), Error> {
+    logging::parse_level(&level)?;
+    match &state.secure_storage {
+        Some(storage) => storage.set_setting(&settings::LOG_LEVEL, &level.trim().to_ascii_lowercase())?,
+        None => return Err("Secure storage not initialized".into()),
+    }
+    Ok(logging::set_level(&level)?)
+}
+
+/// Newest backend log records for the log viewer, oldest first
+#[tauri::command]
+fn tail_logs(lines: Option<usize>, level: Option<String>) -> Result<Vec<logging::LogLine>, Error> {
+    let dir = logging::log_dir().ok_or("Log files are not being written")?;
+    Ok(logging::tail_logs(dir, lines.unwrap_or(200), level.as_deref())?)
+}
+
+/// Remote control API settings, if the API is enabled
+#[tauri::command]
+fn remote_api_get(state: tauri::State<AppState>) -> Result<Option<remote::RemoteApiConfig>, Error> {
+    match &state.secure_storage {
+        Some(storage) => Ok(storage.get_setting(&settings::REMOTE_API)?),
+        None => Ok(None),
     }
+}
+
+/// Save the remote API settings and restart the server with them; returns its URL
+#[tauri::command]
+fn remote_api_set(
+    config: Option<remote::RemoteApiConfig>,
+    app: tauri::AppHandle,
+    state: tauri::State<AppState>,
+) -> Result<Option<String>, Error> {
+    if let Some(config) = &config {
+        config.validate()?;
+    }
+    let storage = state
+        .secure_storage
+        .as_ref()
+        .ok_or_else(|| "Secure storage not initialized".to_string())?;
+    // Listen first so a port in use or a bad certificate rejects the save
+    let url = restart_remote_api(&app, config.as_ref())?;
+    storage.set_setting(&settings::REMOTE_API, &config)?;
+    Ok(url)
+}
+
+/// Prometheus exporter port, if it is enabled
+#[tauri::command]
+fn metrics_exporter_get(state: tauri::State<AppState>) -> Result<Option<u16>, Error> {
     match &state.secure_storage {
-        Some(storage) => storage.set_openai_key(&key),
-        None => Err("Secure storage not initialized".to_string()),
+        Some(storage) => Ok(storage.get_setting(&settings::METRICS_PORT)?),
+        None => Ok(None),
     }
 }
 
+/// Save the exporter port and restart the exporter on it; `None` turns it off. Returns its URL
+#[tauri::command]
+fn metrics_exporter_set(port: Option<u16>, state: tauri::State<AppState>) -> Result<Option<String>, Error> {
+    (settings::METRICS_PORT.check)(&port)?;
+    let storage = state
+        .secure_storage
+        .as_ref()
+        .ok_or_else(|| "Secure storage not initialized".to_string())?;
+    // Listen first so a port in use rejects the save
+    let url = restart_metrics_exporter(&state, port)?;
+    storage.set_setting(&settings::METRICS_PORT, &port)?;
+    Ok(url)
+}
+
+/// URL of the running metrics exporter, if it is enabled
+#[tauri::command]
+fn metrics_exporter_status(state: tauri::State<AppState>) -> Option<String> {
+    state.metrics_exporter.lock().unwrap().as_ref().map(|s| s.url.clone())
+}
+
+/// Voice command settings, if voice commands are enabled
+#[tauri::command]
+fn voice_commands_get(state: tauri::State<AppState>) -> Result<Option<voice::VoiceConfig>, Error> {
+    Ok(voice_config(&state)?)
+}
+
+/// Save the voice command settings and listen with them; `None` turns the microphone off.
+/// Returns the phrases listened for
+#[tauri::command]
+fn voice_commands_set(
+    config: Option<voice::VoiceConfig>,
+    app: tauri::AppHandle,
+    state: tauri::State<AppState>,
+) -> Result<Option<Vec<String>>, Error> {
+    (settings::VOICE_COMMANDS.check)(&config)?;
+    let storage = state
+        .secure_storage
+        .as_ref()
+        .ok_or_else(|| "Secure storage not initialized".to_string())?;
+    // Listen first so a missing model, microphone or word rejects the save
+    let phrases = restart_voice_commands(&app, config.as_ref())?;
+    storage.set_setting(&settings::VOICE_COMMANDS, &config)?;
+    Ok(phrases)
+}
+
+/// Phrases the voice listener is listening for, if it is listening
 #[tauri::command]
-fn delete_openai_key(state: tauri::State<AppState>) -> Result<(), String> {
+fn voice_commands_status(state: tauri::State<AppState>) -> Option<Vec<String>> {
+    state.voice.lock().unwrap().as_ref().map(|l| l.phrases.clone())
+}
+
+/// OTLP trace export settings, if traces are exported
+#[tauri::command]
+fn otlp_traces_get(state: tauri::State<AppState>) -> Result<Option<otel::OtlpConfig>, Error> {
     match &state.secure_storage {
-        Some(storage) => storage.delete_openai_key(),
-        None => Err("Secure storage not initialized".to_string()),
+        Some(storage) => Ok(storage.get_setting(&settings::OTLP_TRACES)?),
+        None => Ok(None),
     }
 }
 
+/// Save the trace export settings and restart the exporter with them; `None` stops it. Returns the traces URL
+#[tauri::command]
+fn otlp_traces_set(config: Option<otel::OtlpConfig>, state: tauri::State<AppState>) -> Result<Option<String>, Error> {
+    if let Some(config) = &config {
+        config.validate()?;
+    }
+    let storage = state
+        .secure_storage
+        .as_ref()
+        .ok_or_else(|| "Secure storage not initialized".to_string())?;
+    let url = otel::tracer().start(config.as_ref())?;
+    storage.set_setting(&settings::OTLP_TRACES, &config)?;
+    Ok(url)
+}
+
+/// Where traces go and how many were sent, while they are exported
+#[tauri::command]
+fn otlp_traces_status() -> Option<otel::ExportStatus> {
+    otel::tracer().status()
+}
+
+/// Plugins found in the plugins directory at startup
+#[tauri::command]
+fn plugins_list(state: tauri::State<AppState>) -> Result<Vec<plugins::PluginInfo>, Error> {
+    let enabled = match &state.secure_storage {
+        Some(storage) => storage.get_setting(&settings::PLUGINS_ENABLED)?,
+        None => Vec::new(),
+    };
+    Ok(plugins::registry().list(&enabled))
+}
+
+/// Enable or disable a plugin; takes effect at the next start
+#[tauri::command]
+fn plugins_set_enabled(id: String, enabled: bool, state: tauri::State<AppState>) -> Result<Vec<plugins::PluginInfo>, Error> {
+    let storage = state
+        .secure_storage
+        .as_ref()
+        .ok_or_else(|| "Secure storage not initialized".to_string())?;
+    let mut ids = storage.get_setting(&settings::PLUGINS_ENABLED)?;
+    ids.retain(|i| *i != id);
+    if enabled {
+        ids.push(id);
+    }
+    storage.set_setting(&settings::PLUGINS_ENABLED, &ids)?;
+    Ok(plugins::registry().list(&ids))
+}
+
+/// URL of the running remote API, if it is enabled
+#[tauri::command]
+fn remote_api_status(state: tauri::State<AppState>) -> Option<String> {
+    state.remote_api.lock().unwrap().as_ref().map(|s| s.url.clone())
+}
+
+#[tauri::command]
+fn remote_api_generate_token() -> String {
+    remote::generate_token()
+}
+
 #[tauri::command]
-fn get_openai_model(state: tauri::State<AppState>) -> Result<Option<String>, String> {
+fn smtp_get(state: tauri::State<AppState>) -> Result<Option<email::SmtpConfig>, Error> {
     match &state.secure_storage {
-        Some(storage) => storage.get_openai_model(),
-        None => Err("Secure storage not initialized".to_string()),
+        Some(storage) => Ok(storage.get_smtp()?),
+        None => Ok(None),
     }
 }
 
 #[tauri::command]
-fn set_openai_model(model: String, state: tauri::State<AppState>) -> Result<(), String> {
-    if model.trim().is_empty() {
-        return Err("Model cannot be empty".to_string());
+fn smtp_set(config: Option<email::SmtpConfig>, state: tauri::State<AppState>) -> Result<(), Error> {
+    if let Some(config) = &config {
+        config.validate()?;
     }
     match &state.secure_storage {
-        Some(storage) => storage.set_openai_model(&model),
-        None => Err("Secure storage not initialized".to_string()),
+        Some(storage) => Ok(storage.set_smtp(config.as_ref())?),
+        None => Err("Secure storage not initialized".into()),
     }
 }
 
+/// Mail a sample summary and report whether the server accepted it
+#[tauri::command]
+fn smtp_test(config: email::SmtpConfig) -> Result<(), Error> {
+    config.validate()?;
+    let summary = email::RunSummary {
+        profile_name: "Test".into(),
+        event: NotificationEvent::Completed,
+        detail: Some("test email from settings".into()),
+        iterations: 0,
+        duration_ms: 0,
+        last_llm_reason: None,
+    };
+    let screenshot = config.include_screenshot.then(capture_full_screen_png).flatten();
+    Ok(email::send(&config, &summary, screenshot.as_deref())?)
+}
+
 // Audio notification commands
 
 #[tauri::command]
-fn audio_test_intervention() -> Result<(), String> {
-    let notifier = audio::create_audio_notifier()?;
-    notifier.play_intervention_needed()
+fn audio_test_intervention(state: tauri::State<AppState>) -> Result<(), Error> {
+    let notifier = make_runner_audio_notifier(&state).ok_or_else(|| Error::Audio("Audio output unavailable".into()))?;
+    notifier.set_enabled(true);
+    notifier.play_sound(audio::AlertSound::Intervention)
+}
+
+#[tauri::command]
+fn audio_test_completed(state: tauri::State<AppState>) -> Result<(), Error> {
+    let notifier = make_runner_audio_notifier(&state).ok_or_else(|| Error::Audio("Audio output unavailable".into()))?;
+    notifier.set_enabled(true);
+    notifier.play_sound(audio::AlertSound::Completion)
 }
 
+/// Play what `event` would play during a run, with the saved sound map
 #[tauri::command]
-fn audio_test_completed() -> Result<(), String> {
-    let notifier = audio::create_audio_notifier()?;
-    notifier.play_profile_ended()
+fn audio_test_alert(event: audio::AlertEvent, state: tauri::State<AppState>) -> Result<(), Error> {
+    let notifier = make_runner_audio_notifier(&state).ok_or_else(|| Error::Audio("Audio output unavailable".into()))?;
+    notifier.set_enabled(true);
+    notifier.play(event)
+}
+
+/// Sound per alert event; events not listed play their default
+#[tauri::command]
+fn audio_get_sound_map(state: tauri::State<AppState>) -> Result<audio::SoundMap, Error> {
+    match &state.secure_storage {
+        Some(storage) => Ok(storage.get_setting(&settings::AUDIO_SOUND_MAP)?),
+        None => Ok(audio::SoundMap::new()),
+    }
+}
+
+#[tauri::command]
+fn audio_set_sound_map(map: audio::SoundMap, state: tauri::State<AppState>) -> Result<(), Error> {
+    match &state.secure_storage {
+        Some(storage) => Ok(storage.set_setting(&settings::AUDIO_SOUND_MAP, &map)?),
+        None => Err("Secure storage not initialized".into()),
+    }
+}
+
+/// Custom sound file per alert; alerts without one use the built-in sound
+#[tauri::command]
+fn audio_get_custom_sounds(state: tauri::State<AppState>) -> Result<HashMap<audio::AlertSound, String>, Error> {
+    match &state.secure_storage {
+        Some(storage) => Ok(storage.get_setting(&settings::AUDIO_CUSTOM_SOUNDS)?),
+        None => Ok(HashMap::new()),
+    }
+}
+
+#[tauri::command]
+fn audio_set_custom_sound(sound: audio::AlertSound, path: Option<String>, state: tauri::State<AppState>) -> Result<(), Error> {
+    if let Some(path) = &path {
+        audio::check_sound_file(std::path::Path::new(path))?;
+    }
+    match &state.secure_storage {
+        Some(storage) => Ok(storage.set_custom_sound(sound, path.as_deref())?),
+        None => Err("Secure storage not initialized".into()),
+    }
 }
 
 #[tauri::command]
-fn audio_set_enabled(enabled: bool, state: tauri::State<AppState>) -> Result<(), String> {
+fn audio_set_enabled(enabled: bool, state: tauri::State<AppState>) -> Result<(), Error> {
     match &state.secure_storage {
-        Some(storage) => storage.set_audio_enabled(enabled),
-        None => Err("Secure storage not initialized".to_string()),
+        Some(storage) => Ok(storage.set_setting(&settings::AUDIO_ENABLED, &enabled)?),
+        None => Err("Secure storage not initialized".into()),
     }
 }
 
 #[tauri::command]
-fn audio_get_enabled(state: tauri::State<AppState>) -> Result<bool, String> {
+fn audio_get_enabled(state: tauri::State<AppState>) -> Result<bool, Error> {
     match &state.secure_storage {
-        Some(storage) => storage.get_audio_enabled(),
-        None => Ok(true), // Default to enabled
+        Some(storage) => Ok(storage.get_setting(&settings::AUDIO_ENABLED)?),
+        None => Ok(settings::AUDIO_ENABLED.default_value()),
     }
 }
 
 #[tauri::command]
-fn audio_set_volume(volume: f32, state: tauri::State<AppState>) -> Result<(), String> {
+fn audio_set_volume(volume: f32, state: tauri::State<AppState>) -> Result<(), Error> {
     match &state.secure_storage {
-        Some(storage) => storage.set_audio_volume(volume),
-        None => Err("Secure storage not initialized".to_string()),
+        Some(storage) => Ok(storage.set_setting(&settings::AUDIO_VOLUME, &volume)?),
+        None => Err("Secure storage not initialized".into()),
     }
 }
 
 #[tauri::command]
-fn audio_get_volume(state: tauri::State<AppState>) -> Result<f32, String> {
+fn audio_get_volume(state: tauri::State<AppState>) -> Result<f32, Error> {
     match &state.secure_storage {
-        Some(storage) => storage.get_audio_volume(),
-        None => Ok(0.5), // Default to 50%
+        Some(storage) => Ok(storage.get_setting(&settings::AUDIO_VOLUME)?),
+        None => Ok(settings::AUDIO_VOLUME.default_value()),
     }
 }
@@ -1,6 +1,7 @@
 /// LLM client for generating prompts based on screen regions
-use crate::domain::{LLMPromptResponse, Region, ScreenCapture};
-use std::sync::Arc;
+use crate::domain::{Error, LLMPromptResponse, LlmUsage, Region, ScreenCapture};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 /// Trait for LLM clients to enable testing with mocks
 pub trait LLMClient: Send + Sync {
@@ -10,7 +11,130 @@ pub trait LLMClient: Send + Sync {
         region_images: Vec<Vec<u8>>, // PNG-encoded images
         system_prompt: Option<&str>,
         risk_guidance: &str,
-    ) -> Result<LLMPromptResponse, String>;
+    ) -> Result<LLMPromptResponse, Error>;
+
+    /// Whether this client answers without calling a provider
+    fn is_mock(&self) -> bool {
+        false
+    }
+
+    /// URL the client sends requests to; None when it calls nothing
+    fn endpoint(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Reports whether each call to the wrapped client worked, e.g. to track when an API key last worked
+pub struct TrackedClient {
+    pub inner: Arc<dyn LLMClient>,
+    pub on_result: Box<dyn Fn(bool) + Send + Sync>,
+}
+
+impl LLMClient for TrackedClient {
+    fn generate_prompt(
+        &self,
+        regions: &[Region],
+        region_images: Vec<Vec<u8>>,
+        system_prompt: Option<&str>,
+        risk_guidance: &str,
+    ) -> Result<LLMPromptResponse, Error> {
+        let result = self.inner.generate_prompt(regions, region_images, system_prompt, risk_guidance);
+        (self.on_result)(result.is_ok());
+        result
+    }
+
+    fn is_mock(&self) -> bool {
+        self.inner.is_mock()
+    }
+
+    fn endpoint(&self) -> Option<String> {
+        self.inner.endpoint()
+    }
+}
+
+/// LLM calls and token spend summed over a run
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct UsageTotals {
+    pub calls: u32,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    /// Estimated dollars; calls to models without a known price add nothing
+    pub cost_usd: f64,
+    /// Time spent waiting for answers
+    pub latency: Duration,
+}
+
+impl UsageTotals {
+    pub fn add(&mut self, latency: Duration, usage: Option<&LlmUsage>) {
+        self.calls += 1;
+        self.latency += latency;
+        if let Some(u) = usage {
+            self.prompt_tokens += u.prompt_tokens as u64;
+            self.completion_tokens += u.completion_tokens as u64;
+            self.cost_usd += u.cost_usd.unwrap_or(0.0);
+        }
+    }
+}
+
+/// Adds every call of the wrapped client, failed ones included, to `totals`
+pub struct MeteredClient {
+    pub inner: Arc<dyn LLMClient>,
+    pub totals: Arc<Mutex<UsageTotals>>,
+}
+
+impl LLMClient for MeteredClient {
+    fn generate_prompt(
+        &self,
+        regions: &[Region],
+        region_images: Vec<Vec<u8>>,
+        system_prompt: Option<&str>,
+        risk_guidance: &str,
+    ) -> Result<LLMPromptResponse, Error> {
+        let started = Instant::now();
+        let result = self.inner.generate_prompt(regions, region_images, system_prompt, risk_guidance);
+        let usage = result.as_ref().ok().and_then(|r| r.usage.as_ref());
+        self.totals.lock().unwrap().add(started.elapsed(), usage);
+        result
+    }
+
+    fn is_mock(&self) -> bool {
+        self.inner.is_mock()
+    }
+
+    fn endpoint(&self) -> Option<String> {
+        self.inner.endpoint()
+    }
+}
+
+/// Dollars per million prompt and completion tokens; more specific names first
+const MODEL_PRICES: &[(&str, f64, f64)] = &[
+    ("gpt-4o-mini", 0.15, 0.60),
+    ("gpt-4o", 2.50, 10.00),
+    ("gpt-4.1-nano", 0.10, 0.40),
+    ("gpt-4.1-mini", 0.40, 1.60),
+    ("gpt-4.1", 2.00, 8.00),
+    ("gpt-4-turbo", 10.00, 30.00),
+];
+
+/// Estimated price of a call, from the model's list price; None for unknown models
+pub fn estimate_cost_usd(model: &str, prompt_tokens: u32, completion_tokens: u32) -> Option<f64> {
+    let (_, input, output) = MODEL_PRICES.iter().find(|(prefix, ..)| model.starts_with(prefix))?;
+    Some((prompt_tokens as f64 * input + completion_tokens as f64 * output) / 1_000_000.0)
+}
+
+/// Where the OpenAI key of a run comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeySource {
+    Stored,
+    Environment,
+}
+
+/// The stored key if there is one, else the one from the environment; blank keys count as unset
+pub fn pick_api_key(stored: Option<String>, env: Option<String>) -> Option<(String, KeySource)> {
+    let usable = |key: Option<String>| key.map(|k| k.trim().to_string()).filter(|k| !k.is_empty());
+    usable(stored)
+        .map(|k| (k, KeySource::Stored))
+        .or_else(|| usable(env).map(|k| (k, KeySource::Environment)))
 }
 
 /// Mock LLM client for testing
@@ -47,22 +171,44 @@ impl LLMClient for MockLLMClient {
         _region_images: Vec<Vec<u8>>,
         _system_prompt: Option<&str>,
         _risk_guidance: &str,
-    ) -> Result<LLMPromptResponse, String> {
+    ) -> Result<LLMPromptResponse, Error> {
         Ok(self.mock_response.clone())
     }
+
+    fn is_mock(&self) -> bool {
+        true
+    }
+}
+
+/// Error for a provider answer with a non-success HTTP status: rate limits and
+/// server errors pass, anything else means the key, model or request is wrong
+#[cfg(feature = "llm-integration")]
+pub fn status_error(status: u16, body: &str) -> Error {
+    let message = format!("LLM provider returned HTTP {}: {}", status, body.trim());
+    if status == 429 || status >= 500 {
+        Error::Network(message)
+    } else {
+        Error::Config(message)
+    }
 }
 
 #[cfg(feature = "llm-integration")]
 mod real_client {
     use super::*;
+    use crate::runtime::AsyncRuntime;
     use serde::{Deserialize, Serialize};
     use std::env;
 
     /// OpenAI GPT-4 Vision client
+    ///
+    /// Requests run on the shared async runtime; the HTTP client is kept so
+    /// connections are reused across retries and monitor ticks.
     pub struct OpenAIClient {
         api_key: String,
         api_endpoint: String,
         model: String,
+        runtime: AsyncRuntime,
+        http: reqwest::Client,
     }
 
     #[derive(Serialize)]
@@ -96,6 +242,14 @@ mod real_client {
     #[derive(Deserialize)]
     struct OpenAIResponse {
         choices: Vec<Choice>,
+        #[serde(default)]
+        usage: Option<OpenAIUsage>,
+    }
+
+    #[derive(Deserialize)]
+    struct OpenAIUsage {
+        prompt_tokens: u32,
+        completion_tokens: u32,
     }
 
     #[derive(Deserialize)]
@@ -109,11 +263,7 @@ mod real_client {
     }
 
     impl OpenAIClient {
-        pub fn new(api_key: Option<String>, model: Option<String>) -> Result<Self, String> {
-            let api_key = api_key
-                .or_else(|| env::var("OPENAI_API_KEY").ok())
-                .ok_or("OpenAI API key not provided and OPENAI_API_KEY environment variable not set".to_string())?;
-
+        pub fn new(api_key: String, model: Option<String>) -> Result<Self, String> {
             let api_endpoint = env::var("OPENAI_API_ENDPOINT")
                 .unwrap_or_else(|_| "https://api.openai.com/v1/chat/completions".to_string());
 
@@ -125,6 +275,8 @@ mod real_client {
                 api_key,
                 api_endpoint,
                 model,
+                runtime: AsyncRuntime::shared()?,
+                http: reqwest::Client::new(),
             })
         }
 
@@ -152,7 +304,7 @@ mod real_client {
         }
         
         /// Parse LLM response with fallback keyword detection
-        fn parse_response(&self, content: &str) -> Result<LLMPromptResponse, String> {
+        fn parse_response(&self, content: &str) -> Result<LLMPromptResponse, Error> {
             // Extract JSON from potential markdown code blocks
             let json_str = if content.starts_with("```json") {
                 content
@@ -174,7 +326,7 @@ mod real_client {
             }
             
             // Fallback: keyword-based parsing
-            eprintln!("Warning: Failed to parse structured LLM response, using keyword fallback");
+            tracing::warn!("Failed to parse structured LLM response, using keyword fallback");
             
             let content_upper = content.to_uppercase();
             
@@ -221,7 +373,7 @@ mod real_client {
             region_images: Vec<Vec<u8>>,
             system_prompt: Option<&str>,
             risk_guidance: &str,
-        ) -> Result<LLMPromptResponse, String> {
+        ) -> Result<LLMPromptResponse, Error> {
             const MAX_RETRIES: usize = 3;
             
             // Build the base content with images
@@ -239,11 +391,10 @@ mod real_client {
                 });
             }
 
-            // Make the HTTP request synchronously using tokio runtime
-            let runtime = tokio::runtime::Runtime::new()
-                .map_err(|e| format!("Failed to create tokio runtime: {}", e))?;
-
-            let mut last_error = String::new();
+            // Callers are synchronous; wait on the shared runtime
+            let mut last_error = Error::Llm("no attempt made".to_string());
+            // Tokens of every answer, including ones rejected for bad JSON
+            let mut spent: Option<LlmUsage> = None;
             
             for attempt in 1..=MAX_RETRIES {
                 let request = OpenAIRequest {
@@ -256,37 +407,52 @@ mod real_client {
                     temperature: 0.7,
                 };
 
-                let response = runtime.block_on(async {
-                    let client = reqwest::Client::new();
-                    client
+                let response = self.runtime.block_on(async {
+                    let resp = self
+                        .http
                         .post(&self.api_endpoint)
                         .header("Authorization", format!("Bearer {}", self.api_key))
                         .header("Content-Type", "application/json")
                         .json(&request)
                         .send()
                         .await
-                        .map_err(|e| format!("HTTP request failed: {}", e))?
-                        .json::<OpenAIResponse>()
+                        .map_err(|e| Error::Network(format!("HTTP request failed: {}", e)))?;
+                    let status = resp.status();
+                    if !status.is_success() {
+                        let body = resp.text().await.unwrap_or_default();
+                        return Err(status_error(status.as_u16(), &body));
+                    }
+                    resp.json::<OpenAIResponse>()
                         .await
-                        .map_err(|e| format!("Failed to parse response: {}", e))
+                        .map_err(|e| Error::Llm(format!("Failed to parse response: {}", e)))
                 });
 
                 match response {
                     Ok(resp) => {
+                        if let Some(u) = &resp.usage {
+                            let total = spent.get_or_insert_with(LlmUsage::default);
+                            total.prompt_tokens += u.prompt_tokens;
+                            total.completion_tokens += u.completion_tokens;
+                        }
                         let response_content = resp
                             .choices
                             .first()
-                            .ok_or("No response from LLM")?
+                            .ok_or_else(|| Error::Llm("No response from LLM".to_string()))?
                             .message
                             .content
                             .trim();
 
                         // Try to parse response with fallback
                         match self.parse_response(response_content) {
-                            Ok(llm_response) => return Ok(llm_response),
+                            Ok(mut llm_response) => {
+                                llm_response.usage = spent.map(|u| LlmUsage {
+                                    cost_usd: estimate_cost_usd(&self.model, u.prompt_tokens, u.completion_tokens),
+                                    ..u
+                                });
+                                return Ok(llm_response);
+                            }
                             Err(e) => {
-                                last_error = e.clone();
-                                eprintln!("Attempt {}/{} failed: {}", attempt, MAX_RETRIES, e);
+                                tracing::warn!(attempt, max_attempts = MAX_RETRIES, code = e.code(), "LLM response rejected: {}", e);
                                 
                                 if attempt < MAX_RETRIES {
                                     // Add correction prompt for next attempt
@@ -298,12 +464,17 @@ mod real_client {
                                         ),
                                     });
                                 }
+                                last_error = e;
                             }
                         }
                     }
                     Err(e) => {
-                        last_error = e.clone();
-                        eprintln!("HTTP request attempt {}/{} failed: {}", attempt, MAX_RETRIES, e);
+                        tracing::warn!(attempt, max_attempts = MAX_RETRIES, code = e.code(), "LLM request failed: {}", e);
+                        // A rejected key or unknown model fails the same way every time
+                        if !e.is_retryable() {
+                            return Err(e);
+                        }
+                        last_error = e;
                         
                         if attempt < MAX_RETRIES {
                             std::thread::sleep(std::time::Duration::from_millis(500 * attempt as u64));
@@ -312,25 +483,38 @@ mod real_client {
                 }
             }
 
-            Err(format!(
-                "Failed after {} attempts. Last error: {}",
-                MAX_RETRIES, last_error
-            ))
+            Err(last_error.context(&format!("Failed after {} attempts. Last error", MAX_RETRIES)))
+        }
+
+        fn endpoint(&self) -> Option<String> {
+            Some(self.api_endpoint.clone())
         }
     }
 
     /// Factory function to create the appropriate LLM client
+    ///
+    /// The API key is the stored one (`api_key`), else `OPENAI_API_KEY`; without
+    /// either the mock client answers. The model is the stored one, else
+    /// `OPENAI_MODEL`, else gpt-4o.
     pub fn create_llm_client(api_key: Option<String>, model: Option<String>) -> Result<Arc<dyn LLMClient>, String> {
-        if env::var("LOOPAUTOMA_BACKEND").ok().as_deref() == Some("fake") {
+        if crate::fakes::selected() {
+            return Ok(Arc::new(MockLLMClient::new()));
+        }
+
+        let env_key = env::var("OPENAI_API_KEY").ok();
+        let Some((api_key, source)) = pick_api_key(api_key, env_key.clone()) else {
+            tracing::warn!("No OpenAI API key stored and OPENAI_API_KEY not set; using mock LLM client");
             return Ok(Arc::new(MockLLMClient::new()));
+        };
+        if source == KeySource::Stored && env_key.is_some_and(|k| k.trim() != api_key) {
+            tracing::info!("OPENAI_API_KEY differs from the stored key; using the stored key");
         }
 
         // Try to create OpenAI client
         match OpenAIClient::new(api_key, model) {
             Ok(client) => Ok(Arc::new(client)),
             Err(e) => {
-                eprintln!("Warning: Could not initialize OpenAI client: {}", e);
-                eprintln!("Falling back to mock LLM client");
+                tracing::warn!("Could not initialize OpenAI client, falling back to mock LLM client: {}", e);
                 Ok(Arc::new(MockLLMClient::new()))
             }
         }
@@ -360,24 +544,24 @@ Consider the user's risk threshold when choosing the safest viable prompt."#
 pub fn capture_region_images(
     regions: &[Region],
     capture: &dyn ScreenCapture,
-) -> Result<Vec<Vec<u8>>, String> {
+) -> Result<Vec<Vec<u8>>, Error> {
     let mut images = Vec::new();
 
     for region in regions {
         let frame = capture
             .capture_region(region)
-            .map_err(|e| format!("Failed to capture region '{}': {}", region.id, e))?;
+            .map_err(|e| e.context(&format!("Failed to capture region '{}'", region.id)))?;
 
         // Convert frame bytes to PNG
         let img = image::RgbaImage::from_raw(frame.width, frame.height, frame.bytes)
-            .ok_or_else(|| format!("Failed to create image from region '{}'", region.id))?;
+            .ok_or_else(|| Error::Action(format!("Failed to create image from region '{}'", region.id)))?;
 
         let mut png_bytes = Vec::new();
         img.write_to(
             &mut std::io::Cursor::new(&mut png_bytes),
             image::ImageFormat::Png,
         )
-        .map_err(|e| format!("Failed to encode PNG for region '{}': {}", region.id, e))?;
+        .map_err(|e| Error::Action(format!("Failed to encode PNG for region '{}': {}", region.id, e)))?;
 
         images.push(png_bytes);
     }
@@ -0,0 +1,221 @@
+/// Structured logging
+///
+/// Backend diagnostics go through `tracing`. `init` installs two outputs:
+/// readable lines on stderr and JSON lines in `logs/` under the config
+/// directory, one file per day with the oldest deleted past `MAX_LOG_FILES`.
+/// Runs, iterations and actions open spans (`run`, `iteration`, `action`), so
+/// each file record names the profile, iteration and action it came from.
+/// The same spans feed the trace export (see `otel`). Both outputs share one
+/// level filter. `set_level` changes it while the app runs, and the
+/// `logging.level` setting restores it on start. `tail_logs` reads the newest
+/// records back for the log viewer.
+///
+/// Records never carry typed text or OCR output, only their length.
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, Registry};
+
+/// Level names, least to most verbose
+pub const LEVELS: &[&str] = &["error", "warn", "info", "debug", "trace"];
+/// Level used until the saved setting is read; `LOOPAUTOMA_LOG` overrides it
+pub const DEFAULT_LEVEL: &str = "info";
+/// Daily files kept in the log directory
+pub const MAX_LOG_FILES: usize = 7;
+/// Most records `tail_logs` returns
+pub const MAX_TAIL_LINES: usize = 2000;
+
+const FILE_PREFIX: &str = "loopautoma";
+const FILE_SUFFIX: &str = "log";
+
+struct Logging {
+    level: reload::Handle<LevelFilter, Registry>,
+    dir: Option<PathBuf>,
+}
+
+static LOGGING: OnceLock<Logging> = OnceLock::new();
+
+/// `logs/` in the app's config directory, created if missing
+pub fn default_log_dir() -> Option<PathBuf> {
+    let dir = dirs::config_dir()?.join("loopautoma").join("logs");
+    fs::create_dir_all(&dir).ok()?;
+    Some(dir)
+}
+
+pub fn parse_level(level: &str) -> Result<LevelFilter, String> {
+    match level.trim().to_ascii_lowercase().as_str() {
+        "error" => Ok(LevelFilter::ERROR),
+        "warn" => Ok(LevelFilter::WARN),
+        "info" => Ok(LevelFilter::INFO),
+        "debug" => Ok(LevelFilter::DEBUG),
+        "trace" => Ok(LevelFilter::TRACE),
+        other => Err(format!("Unknown log level '{}'; expected one of {}", other, LEVELS.join(", "))),
+    }
+}
+
+/// Install the subscriber, writing files to `dir` when given. Later calls do nothing.
+pub fn init(dir: Option<PathBuf>) {
+    if LOGGING.get().is_some() {
+        return;
+    }
+    let level = std::env::var("LOOPAUTOMA_LOG")
+        .ok()
+        .and_then(|l| parse_level(&l).ok())
+        .unwrap_or(LevelFilter::INFO);
+    let (filter, handle) = reload::Layer::new(level);
+    let file = dir.as_deref().and_then(|dir| {
+        RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix(FILE_PREFIX)
+            .filename_suffix(FILE_SUFFIX)
+            .max_log_files(MAX_LOG_FILES)
+            .build(dir)
+            .map_err(|e| eprintln!("Log files disabled: {}", e))
+            .ok()
+    });
+    let dir = file.as_ref().and(dir);
+    let installed = tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer().with_writer(std::io::stderr))
+        .with(crate::otel::TraceLayer::new(crate::otel::tracer()))
+        .with(file.map(|file| {
+            fmt::layer()
+                .json()
+                .with_current_span(true)
+                .with_span_list(true)
+                .with_writer(file)
+        }))
+        .try_init();
+    if installed.is_ok() {
+        let _ = LOGGING.set(Logging { level: handle, dir });
+    }
+}
+
+/// Current level name; the default when logging is not installed
+pub fn level() -> String {
+    LOGGING
+        .get()
+        .and_then(|l| l.level.clone_current())
+        .map(|l| l.to_string().to_ascii_lowercase())
+        .unwrap_or_else(|| DEFAULT_LEVEL.to_string())
+}
+
+/// Apply the saved level unless `LOOPAUTOMA_LOG` chose one for this session
+pub fn restore_level(saved: &str) -> Result<(), String> {
+    if std::env::var_os("LOOPAUTOMA_LOG").is_some() {
+        return Ok(());
+    }
+    set_level(saved)
+}
+
+/// Change the level of both outputs
+pub fn set_level(level: &str) -> Result<(), String> {
+    let filter = parse_level(level)?;
+    let logging = LOGGING.get().ok_or("Logging not initialized")?;
+    logging
+        .level
+        .reload(filter)
+        .map_err(|e| format!("Failed to change log level: {}", e))
+}
+
+/// Directory the log files go to, if file output is on
+pub fn log_dir() -> Option<&'static Path> {
+    LOGGING.get().and_then(|l| l.dir.as_deref())
+}
+
+/// Span a record was written in, outermost first
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogSpan {
+    pub name: String,
+    #[serde(flatten)]
+    pub fields: BTreeMap<String, serde_json::Value>,
+}
+
+/// One record of a log file
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LogLine {
+    pub timestamp: String,
+    /// Lower-case level name
+    pub level: String,
+    /// Module that wrote the record
+    pub target: String,
+    pub message: String,
+    /// Fields of the record other than the message
+    pub fields: BTreeMap<String, serde_json::Value>,
+    pub spans: Vec<LogSpan>,
+}
+
+#[derive(Deserialize)]
+struct JsonRecord {
+    timestamp: String,
+    level: String,
+    target: String,
+    #[serde(default)]
+    fields: BTreeMap<String, serde_json::Value>,
+    #[serde(default)]
+    spans: Vec<LogSpan>,
+}
+
+/// Parse one JSON line as written by the file output
+pub fn parse_line(line: &str) -> Option<LogLine> {
+    let mut record: JsonRecord = serde_json::from_str(line).ok()?;
+    let message = match record.fields.remove("message") {
+        Some(serde_json::Value::String(m)) => m,
+        Some(other) => other.to_string(),
+        None => String::new(),
+    };
+    Some(LogLine {
+        timestamp: record.timestamp,
+        level: record.level.to_ascii_lowercase(),
+        target: record.target,
+        message,
+        fields: record.fields,
+        spans: record.spans,
+    })
+}
+
+/// Log files in `dir`, oldest first; the date in the name sorts them
+fn log_files(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("Failed to read log directory: {}", e))?;
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with(FILE_PREFIX) && n.ends_with(FILE_SUFFIX))
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// The newest `lines` records in `dir` at `min_level` or more severe, oldest first
+pub fn tail_logs(dir: &Path, lines: usize, min_level: Option<&str>) -> Result<Vec<LogLine>, String> {
+    let max = min_level.map(parse_level).transpose()?.unwrap_or(LevelFilter::TRACE);
+    let lines = lines.min(MAX_TAIL_LINES);
+    let mut tail = Vec::new();
+    for path in log_files(dir)?.iter().rev() {
+        if tail.len() >= lines {
+            break;
+        }
+        let file = fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        let records: Vec<LogLine> = BufReader::new(file)
+            .lines()
+            .map_while(Result::ok)
+            .filter_map(|l| parse_line(&l))
+            .filter(|l| parse_level(&l.level).is_ok_and(|level| level <= max))
+            .collect();
+        let keep = records.len().min(lines - tail.len());
+        // Newest file first, so older records go in front
+        tail.splice(0..0, records[records.len() - keep..].iter().cloned());
+    }
+    Ok(tail)
+}
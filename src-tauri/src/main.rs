@@ -2,5 +2,12 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    if std::env::args().any(|arg| arg == "--mcp") {
+        if let Err(e) = loopautoma_lib::run_mcp_server() {
+            eprintln!("[MCP] {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
     loopautoma_lib::run()
 }
@@ -0,0 +1,429 @@
+/// Model Context Protocol server
+///
+/// `loopautoma --mcp` speaks MCP (JSON-RPC 2.0, one message per line) on
+/// stdin/stdout, so AI agents such as Claude Desktop or IDE agents can drive
+/// the engine: capture a screen region, click, type, press keys, and start,
+/// stop or check on profiles. Input and capture go through the same
+/// `Automation`/`ScreenCapture` backends as profile actions; profiles run
+/// headless through a `RemoteControl` and are recorded in the run history.
+/// Stdout carries only protocol messages, so logs go to stderr.
+use std::io::{BufRead, Cursor, Write};
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+
+use base64::engine::general_purpose::STANDARD as Base64Standard;
+use base64::Engine as _;
+use image::{ImageOutputFormat, RgbaImage};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::credentials::profile_secret_entry;
+use crate::domain::{Automation, Event, MouseButton, Profile, Rect, Region, ScreenCapture, ScreenFrame, SecretLookup};
+use crate::history::{RunDetails, RunHistory, RunQuery, RunRecord};
+use crate::prometheus::Metrics;
+use crate::recovery::CheckpointStore;
+use crate::remote::{ProfileSummary, RemoteControl};
+use crate::run_loop::{ProfileRun, RunControls, RunHooks, RunSinks};
+use crate::runner_state::{RunnerStateUpdate, RunnerStatus};
+use crate::secure_storage::{KeyringSecrets, SecretStore};
+use crate::shutdown::ShutdownToken;
+
+/// Protocol revisions this server speaks, newest first; a client asking for another gets the newest
+pub const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// Tools with their JSON Schema inputs, as listed by `tools/list`
+pub fn tools() -> Value {
+    let point = json!({
+        "x": { "type": "integer", "minimum": 0, "description": "Screen x in physical pixels" },
+        "y": { "type": "integer", "minimum": 0, "description": "Screen y in physical pixels" },
+    });
+    let mut rect = point.clone();
+    rect["width"] = json!({ "type": "integer", "minimum": 1 });
+    rect["height"] = json!({ "type": "integer", "minimum": 1 });
+    let mut click = point;
+    click["button"] = json!({ "type": "string", "enum": ["Left", "Right", "Middle"], "default": "Left" });
+    let schema = |properties: Value, required: &[&str]| {
+        json!({ "type": "object", "properties": properties, "required": required })
+    };
+    json!([
+        {
+            "name": "capture_region",
+            "description": "Capture a rectangle of the screen as a PNG image",
+            "inputSchema": schema(rect, &["x", "y", "width", "height"]),
+        },
+        {
+            "name": "click",
+            "description": "Move the mouse to a screen position and click",
+            "inputSchema": schema(click, &["x", "y"]),
+        },
+        {
+            "name": "type_text",
+            "description": "Type text into the focused window",
+            "inputSchema": schema(json!({ "text": { "type": "string" } }), &["text"]),
+        },
+        {
+            "name": "press_key",
+            "description": "Press a key: Enter, Escape, Tab, Space, Backspace, or a single character",
+            "inputSchema": schema(json!({ "key": { "type": "string" } }), &["key"]),
+        },
+        {
+            "name": "list_profiles",
+            "description": "List the saved automation profiles",
+            "inputSchema": schema(json!({}), &[]),
+        },
+        {
+            "name": "run_profile",
+            "description": "Start a profile's monitor loop, stopping any run in progress",
+            "inputSchema": schema(json!({ "profile_id": { "type": "string" } }), &["profile_id"]),
+        },
+        {
+            "name": "stop_profile",
+            "description": "Stop the running profile",
+            "inputSchema": schema(json!({}), &[]),
+        },
+        {
+            "name": "get_run_status",
+            "description": "What the runner is doing (idle, running, paused, needs intervention) and the latest recorded run",
+            "inputSchema": schema(json!({}), &[]),
+        },
+    ])
+}
+
+#[derive(Deserialize)]
+struct ClickArgs {
+    x: u32,
+    y: u32,
+    #[serde(default = "left")]
+    button: MouseButton,
+}
+
+fn left() -> MouseButton {
+    MouseButton::Left
+}
+
+#[derive(Deserialize)]
+struct TextArgs {
+    text: String,
+}
+
+#[derive(Deserialize)]
+struct KeyArgs {
+    key: String,
+}
+
+#[derive(Deserialize)]
+struct ProfileArgs {
+    profile_id: String,
+}
+
+fn args<T: for<'de> Deserialize<'de>>(arguments: &Value) -> Result<T, String> {
+    serde_json::from_value(arguments.clone()).map_err(|e| format!("Invalid arguments: {}", e))
+}
+
+fn text(value: impl Into<String>) -> Value {
+    json!({ "type": "text", "text": value.into() })
+}
+
+fn encode_png(frame: &ScreenFrame) -> Result<String, String> {
+    let image = RgbaImage::from_vec(frame.width, frame.height, frame.bytes.clone())
+        .ok_or_else(|| "Captured frame has an unexpected size".to_string())?;
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), ImageOutputFormat::Png)
+        .map_err(|e| format!("PNG encoding failed: {}", e))?;
+    Ok(Base64Standard.encode(png))
+}
+
+pub struct McpServer<'a> {
+    pub capture: &'a dyn ScreenCapture,
+    pub automation: &'a dyn Automation,
+    pub runner: &'a dyn RemoteControl,
+}
+
+impl McpServer<'_> {
+    /// Run a tool; the content blocks of its result, or why it failed
+    fn call_tool(&self, name: &str, arguments: &Value) -> Option<Result<Vec<Value>, String>> {
+        let done = |result: Result<(), String>, message: &str| result.map(|()| vec![text(message)]);
+        Some(match name {
+            "capture_region" => args::<Rect>(arguments).and_then(|rect| {
+                if rect.width == 0 || rect.height == 0 {
+                    return Err("Width and height must be positive".into());
+                }
+                let region = Region {
+                    id: "mcp".into(),
+                    rect,
+                    name: None,
+                    anchor: None,
+                };
+                let frame = self.capture.capture_region(&region).map_err(|e| e.to_string())?;
+                Ok(vec![json!({ "type": "image", "data": encode_png(&frame)?, "mimeType": "image/png" })])
+            }),
+            "click" => args::<ClickArgs>(arguments).and_then(|a| {
+                self.automation.move_cursor(a.x, a.y)?;
+                done(self.automation.click(a.button).map_err(String::from), &format!("Clicked {:?} at ({}, {})", a.button, a.x, a.y))
+            }),
+            "type_text" => args::<TextArgs>(arguments).and_then(|a| done(self.automation.type_text(&a.text).map_err(String::from), "Typed")),
+            "press_key" => args::<KeyArgs>(arguments).and_then(|a| done(self.automation.key(&a.key).map_err(String::from), &format!("Pressed {}", a.key))),
+            "list_profiles" => Ok(vec![text(json!(self.runner.profiles()).to_string())]),
+            "run_profile" => args::<ProfileArgs>(arguments).and_then(|a| {
+                if !self.runner.profiles().iter().any(|p| p.id == a.profile_id) {
+                    return Err(format!("No profile '{}'", a.profile_id));
+                }
+                done(self.runner.start(&a.profile_id), &format!("Started {}", a.profile_id))
+            }),
+            "stop_profile" => {
+                self.runner.stop();
+                Ok(vec![text("Stopped")])
+            }
+            "get_run_status" => {
+                let latest = self.runner.runs(&RunQuery {
+                    limit: Some(1),
+                    ..Default::default()
+                });
+                latest.map(|runs| {
+                    let status = json!({ "status": self.runner.status(), "last_run": runs.first() });
+                    vec![text(status.to_string())]
+                })
+            }
+            _ => return None,
+        })
+    }
+
+    fn result(&self, method: &str, params: &Value) -> Result<Value, (i64, String)> {
+        match method {
+            "initialize" => {
+                let requested = params["protocolVersion"].as_str().unwrap_or_default();
+                let version = PROTOCOL_VERSIONS
+                    .iter()
+                    .find(|v| **v == requested)
+                    .unwrap_or(&PROTOCOL_VERSIONS[0]);
+                Ok(json!({
+                    "protocolVersion": version,
+                    "capabilities": { "tools": {} },
+                    "serverInfo": { "name": "loopautoma", "version": env!("CARGO_PKG_VERSION") },
+                }))
+            }
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": tools() })),
+            "tools/call" => {
+                let name = params["name"].as_str().unwrap_or_default();
+                let arguments = params.get("arguments").cloned().unwrap_or_else(|| json!({}));
+                match self.call_tool(name, &arguments) {
+                    Some(Ok(content)) => Ok(json!({ "content": content, "isError": false })),
+                    // Tool failures are results the agent can read and react to
+                    Some(Err(e)) => Ok(json!({ "content": [text(e)], "isError": true })),
+                    None => Err((INVALID_PARAMS, format!("Unknown tool '{}'", name))),
+                }
+            }
+            _ => Err((METHOD_NOT_FOUND, format!("Method '{}' not found", method))),
+        }
+    }
+
+    /// Answer one JSON-RPC message; notifications and responses get no answer
+    pub fn handle(&self, message: &Value) -> Option<Value> {
+        let id = message.get("id")?.clone();
+        let method = message["method"].as_str()?;
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        Some(match self.result(method, &params) {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } }),
+        })
+    }
+
+    /// Serve messages from `input` until it closes
+    pub fn serve(&self, input: impl BufRead, mut output: impl Write) -> std::io::Result<()> {
+        for line in input.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let reply = match serde_json::from_str::<Value>(&line) {
+                Ok(message) => self.handle(&message),
+                Err(e) => Some(json!({
+                    "jsonrpc": "2.0",
+                    "id": null,
+                    "error": { "code": PARSE_ERROR, "message": format!("Parse error: {}", e) },
+                })),
+            };
+            if let Some(reply) = reply {
+                writeln!(output, "{}", reply)?;
+                output.flush()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+struct HeadlessRun {
+    controls: RunControls,
+    handle: std::thread::JoinHandle<()>,
+}
+
+/// Follows the runner status from the events of a headless run
+struct StatusHooks<'a> {
+    status: &'a Mutex<RunnerStatus>,
+    profile_id: &'a str,
+}
+
+impl RunHooks for StatusHooks<'_> {
+    fn on_event(&mut self, event: &Event) {
+        if let Some(update) = RunnerStateUpdate::from_event(self.profile_id, crate::history::now_ms(), event) {
+            self.status.lock().unwrap().apply(&update);
+        }
+    }
+}
+
+/// Runs profiles without the app window, for the MCP server
+///
+/// One run at a time, like the app, through the same run loop: run windows,
+/// the connectivity probe, Prometheus metrics, checkpoints and the run history
+/// behave as in the app. Notifications and handoffs belong to the app and are
+/// left out. The OpenAI key comes from `OPENAI_API_KEY`, and profile secrets
+/// from the OS keyring.
+pub struct HeadlessRunner {
+    profiles: Vec<Profile>,
+    status: Arc<Mutex<RunnerStatus>>,
+    sinks: RunSinks,
+    run: Mutex<Option<HeadlessRun>>,
+}
+
+impl HeadlessRunner {
+    pub fn new(profiles: Vec<Profile>, history: RunHistory) -> Self {
+        Self {
+            profiles,
+            status: Arc::default(),
+            sinks: RunSinks {
+                history: Arc::new(Mutex::new(history)),
+                ..RunSinks::default()
+            },
+            run: Mutex::new(None),
+        }
+    }
+
+    /// Keep a crash-recovery checkpoint of each run in `checkpoints`
+    pub fn with_checkpoints(mut self, checkpoints: CheckpointStore) -> Self {
+        self.sinks.checkpoints = Arc::new(checkpoints);
+        self
+    }
+
+    /// Counters and histograms of the runs so far
+    pub fn metrics(&self) -> Arc<Metrics> {
+        self.sinks.metrics.clone()
+    }
+}
+
+impl RemoteControl for HeadlessRunner {
+    fn profiles(&self) -> Vec<ProfileSummary> {
+        self.profiles
+            .iter()
+            .map(|p| ProfileSummary {
+                id: p.id.clone(),
+                name: p.name.clone(),
+            })
+            .collect()
+    }
+
+    fn status(&self) -> RunnerStatus {
+        self.status.lock().unwrap().clone()
+    }
+
+    fn start(&self, profile_id: &str) -> Result<(), String> {
+        self.stop();
+        let profile = self
+            .profiles
+            .iter()
+            .find(|p| p.id == profile_id)
+            .ok_or_else(|| "profile not found".to_string())?;
+        let llm = crate::llm::create_llm_client(None, None).unwrap_or_else(|e| {
+            tracing::warn!("Failed to create LLM client: {}", e);
+            Arc::new(crate::llm::MockLLMClient::new())
+        });
+        let secrets = {
+            let profile_id = profile.id.clone();
+            SecretLookup::new(move |name| KeyringSecrets.get(&profile_secret_entry(&profile_id, name)))
+        };
+        let mut run = ProfileRun::prepare(profile, llm, secrets, None, self.sinks.clone())?;
+        let controls = RunControls::new(ShutdownToken::default());
+        let run_controls = controls.clone();
+        let (status, history) = (self.status.clone(), self.sinks.history.clone());
+        let handle = std::thread::spawn(move || {
+            let profile_id = run.profile.id.clone();
+            let mut hooks = StatusHooks {
+                status: &status,
+                profile_id: &profile_id,
+            };
+            let mut events = vec![];
+            run.start(std::iter::empty(), None, &mut events);
+            for e in &events {
+                hooks.on_event(e);
+            }
+            let details = run.run(&run_controls, &mut hooks);
+            *status.lock().unwrap() = RunnerStatus::Idle;
+            if let Err(e) = history.lock().unwrap().record(details) {
+                tracing::warn!("Failed to record run: {}", e);
+            }
+        });
+        *self.run.lock().unwrap() = Some(HeadlessRun { controls, handle });
+        Ok(())
+    }
+
+    fn stop(&self) {
+        if let Some(run) = self.run.lock().unwrap().take() {
+            run.controls.cancel.cancel();
+            // Wait, so the next run starts after this one is recorded
+            let _ = run.handle.join();
+        }
+    }
+
+    fn set_paused(&self, paused: bool) -> Result<(), String> {
+        match self.run.lock().unwrap().as_ref() {
+            Some(run) if !run.handle.is_finished() => {
+                run.controls.pause.store(paused, Ordering::Relaxed);
+                if paused {
+                    run.controls.interrupt.interrupt();
+                }
+                Ok(())
+            }
+            _ => Err("No profile is running".into()),
+        }
+    }
+
+    fn runs(&self, query: &RunQuery) -> Result<Vec<RunRecord>, String> {
+        self.sinks.history.lock().unwrap().list(query)
+    }
+
+    fn run_details(&self, run_id: i64) -> Result<Option<RunDetails>, String> {
+        self.sinks.history.lock().unwrap().details(run_id)
+    }
+}
+
+/// Serve MCP on stdin/stdout with the OS backends until the client disconnects
+///
+/// Runs keep their checkpoints in `checkpoints`; with `metrics_port` their metrics are served there.
+pub fn serve_stdio(
+    profiles: Vec<Profile>,
+    history: RunHistory,
+    checkpoints: CheckpointStore,
+    metrics_port: Option<u16>,
+) -> Result<(), String> {
+    let runner = HeadlessRunner::new(profiles, history).with_checkpoints(checkpoints);
+    let _exporter = metrics_port.and_then(|port| {
+        crate::prometheus::serve(port, runner.metrics())
+            .map_err(|e| tracing::warn!("{}", e))
+            .ok()
+    });
+    let capture = crate::make_capture();
+    let automation = crate::make_automation();
+    let server = McpServer {
+        capture: &*capture,
+        automation: &*automation,
+        runner: &runner,
+    };
+    let result = server.serve(std::io::stdin().lock(), std::io::stdout().lock());
+    runner.stop();
+    result.map_err(|e| format!("MCP connection failed: {}", e))
+}
@@ -0,0 +1,205 @@
+/// Per-profile run statistics
+///
+/// Each run measures how long its iterations, screen captures and LLM calls
+/// took, how many iterations succeeded and how often it needed the user
+/// (waiting for the network, a rejected risky prompt). The figures are stored
+/// with the run in the run history, and `get_metrics` folds them per profile
+/// into averages, success rates and the most common failure reasons.
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{BackendError, DisplayInfo, Event, Region, ScreenCapture, ScreenFrame};
+use crate::history::RunRecord;
+use crate::notify;
+
+/// Failure reasons listed per profile
+pub const MAX_FAILURE_REASONS: usize = 5;
+
+/// Timing and reliability figures of one run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunMetrics {
+    /// Iterations run in this session; a resumed run does not count earlier ones
+    pub iterations: u32,
+    pub successful_iterations: u32,
+    /// Time spent running action sequences
+    pub iteration_ms: f64,
+    pub captures: u32,
+    pub capture_ms: f64,
+    /// Time spent waiting for LLM answers, retries included
+    pub llm_ms: f64,
+    /// Times the run needed the user
+    pub interventions: u32,
+}
+
+impl RunMetrics {
+    /// Count the iteration or intervention an event reports; `iteration_time` is how long the iteration took
+    pub fn observe(&mut self, event: &Event, iteration_time: Duration) {
+        if let Event::IterationCompleted { success, .. } = event {
+            self.iterations += 1;
+            self.successful_iterations += u32::from(*success);
+            self.iteration_ms += iteration_time.as_secs_f64() * 1000.0;
+        }
+        if notify::classify_event(event).is_some() {
+            self.interventions += 1;
+        }
+    }
+}
+
+/// Number and total time of timed calls
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Timing {
+    pub count: u32,
+    pub total: Duration,
+}
+
+impl Timing {
+    fn time<T>(timing: &Mutex<Timing>, f: impl FnOnce() -> T) -> T {
+        let started = Instant::now();
+        let result = f();
+        let mut t = timing.lock().unwrap();
+        t.count += 1;
+        t.total += started.elapsed();
+        result
+    }
+}
+
+/// Times the region hashes and captures of the wrapped backend
+pub struct TimedCapture {
+    pub inner: Box<dyn ScreenCapture + Send + Sync>,
+    pub timing: Arc<Mutex<Timing>>,
+}
+
+impl ScreenCapture for TimedCapture {
+    fn hash_region(&self, region: &Region, downscale: u32) -> u64 {
+        Timing::time(&self.timing, || self.inner.hash_region(region, downscale))
+    }
+
+    fn capture_region(&self, region: &Region) -> Result<ScreenFrame, BackendError> {
+        Timing::time(&self.timing, || self.inner.capture_region(region))
+    }
+
+    fn capture_and_hash(&self, region: &Region, downscale: u32) -> Result<(ScreenFrame, u64), BackendError> {
+        Timing::time(&self.timing, || self.inner.capture_and_hash(region, downscale))
+    }
+
+    fn displays(&self) -> Result<Vec<DisplayInfo>, BackendError> {
+        self.inner.displays()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FailureReason {
+    pub reason: String,
+    pub runs: u32,
+}
+
+/// Statistics of one profile over its recorded runs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfileMetrics {
+    pub profile_id: String,
+    pub runs: u32,
+    pub succeeded_runs: u32,
+    pub failed_runs: u32,
+    /// Succeeded share of the runs that ended on their own; None if all were stopped by the user
+    pub success_rate: Option<f64>,
+    pub iterations: u32,
+    pub iteration_success_rate: Option<f64>,
+    pub avg_iteration_ms: Option<f64>,
+    pub avg_capture_ms: Option<f64>,
+    pub llm_calls: u32,
+    pub avg_llm_ms: Option<f64>,
+    pub llm_cost_usd: f64,
+    pub interventions_per_run: f64,
+    pub errors_per_run: f64,
+    /// Why runs failed, most common first
+    pub failure_reasons: Vec<FailureReason>,
+    pub last_run_at_ms: u64,
+}
+
+fn ratio(part: f64, whole: u32) -> Option<f64> {
+    (whole > 0).then(|| part / whole as f64)
+}
+
+/// Sums over the runs of one profile
+#[derive(Default)]
+struct Totals {
+    runs: u32,
+    succeeded: u32,
+    failed: u32,
+    metrics: RunMetrics,
+    llm_calls: u32,
+    llm_cost_usd: f64,
+    errors: u32,
+    failure_reasons: BTreeMap<String, u32>,
+    last_run_at_ms: u64,
+}
+
+impl Totals {
+    fn add(&mut self, r: &RunRecord, m: &RunMetrics) {
+        self.runs += 1;
+        match r.outcome.succeeded() {
+            Some(true) => self.succeeded += 1,
+            Some(false) => {
+                self.failed += 1;
+                let reason = notify::outcome_detail(&r.outcome).unwrap_or_else(|| "unknown".into());
+                *self.failure_reasons.entry(reason).or_default() += 1;
+            }
+            None => {}
+        }
+        self.metrics.iterations += m.iterations;
+        self.metrics.successful_iterations += m.successful_iterations;
+        self.metrics.iteration_ms += m.iteration_ms;
+        self.metrics.captures += m.captures;
+        self.metrics.capture_ms += m.capture_ms;
+        self.metrics.llm_ms += m.llm_ms;
+        self.metrics.interventions += m.interventions;
+        self.llm_calls += r.llm_calls;
+        self.llm_cost_usd += r.llm_cost_usd;
+        self.errors += r.error_count;
+        self.last_run_at_ms = self.last_run_at_ms.max(r.started_at_ms);
+    }
+
+    fn into_metrics(self, profile_id: String) -> ProfileMetrics {
+        let m = self.metrics;
+        let mut failure_reasons: Vec<FailureReason> = self
+            .failure_reasons
+            .into_iter()
+            .map(|(reason, runs)| FailureReason { reason, runs })
+            .collect();
+        failure_reasons.sort_by_key(|f| std::cmp::Reverse(f.runs));
+        failure_reasons.truncate(MAX_FAILURE_REASONS);
+        ProfileMetrics {
+            profile_id,
+            runs: self.runs,
+            succeeded_runs: self.succeeded,
+            failed_runs: self.failed,
+            success_rate: ratio(self.succeeded as f64, self.succeeded + self.failed),
+            iterations: m.iterations,
+            iteration_success_rate: ratio(m.successful_iterations as f64, m.iterations),
+            avg_iteration_ms: ratio(m.iteration_ms, m.iterations),
+            avg_capture_ms: ratio(m.capture_ms, m.captures),
+            llm_calls: self.llm_calls,
+            avg_llm_ms: ratio(m.llm_ms, self.llm_calls),
+            llm_cost_usd: self.llm_cost_usd,
+            interventions_per_run: ratio(m.interventions as f64, self.runs).unwrap_or(0.0),
+            errors_per_run: ratio(self.errors as f64, self.runs).unwrap_or(0.0),
+            failure_reasons,
+            last_run_at_ms: self.last_run_at_ms,
+        }
+    }
+}
+
+/// Fold recorded runs into statistics per profile, ordered by profile id
+pub fn aggregate(runs: &[(RunRecord, RunMetrics)]) -> Vec<ProfileMetrics> {
+    let mut by_profile: BTreeMap<&str, Totals> = BTreeMap::new();
+    for (r, m) in runs {
+        by_profile.entry(r.profile_id.as_str()).or_default().add(r, m);
+    }
+    by_profile
+        .into_iter()
+        .map(|(profile_id, totals)| totals.into_metrics(profile_id.to_string()))
+        .collect()
+}
@@ -0,0 +1,118 @@
+/// Profile schema versions and the migrations between them
+///
+/// Stored configs carry `schema_version`; files without it predate versioning and
+/// are treated as version 1. Migrations run on raw JSON before the typed parse, so
+/// formats the current structs can no longer read are still upgraded.
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Map, Value};
+
+/// Schema version written by this build
+pub const CURRENT_SCHEMA_VERSION: u32 = 2;
+
+type MigrationStep = fn(&mut Map<String, Value>) -> Result<(), String>;
+
+/// Each step upgrades a single profile from `version` to `version + 1`
+const STEPS: &[(u32, MigrationStep)] = &[(1, v1_to_v2)];
+
+/// Schema version of a stored config or exported profile
+pub fn schema_version(value: &Value) -> u32 {
+    value
+        .get("schema_version")
+        .and_then(Value::as_u64)
+        .map(|v| v as u32)
+        .unwrap_or(1)
+}
+
+/// Upgrade one profile object from schema version `from` to the current version
+pub fn migrate_profile(profile: &mut Value, from: u32) -> Result<(), String> {
+    check_supported(from)?;
+    let obj = profile
+        .as_object_mut()
+        .ok_or_else(|| "Profile must be a JSON object".to_string())?;
+    for (version, step) in STEPS {
+        if *version >= from {
+            step(obj)?;
+        }
+    }
+    Ok(())
+}
+
+/// Upgrade every profile of a stored config in place; returns the version it was stored with
+pub fn migrate_config(config: &mut Value) -> Result<u32, String> {
+    let from = schema_version(config);
+    check_supported(from)?;
+    if let Some(profiles) = config.get_mut("profiles").and_then(Value::as_array_mut) {
+        for p in profiles {
+            migrate_profile(p, from)?;
+        }
+    }
+    if let Some(obj) = config.as_object_mut() {
+        obj.insert("schema_version".into(), json!(CURRENT_SCHEMA_VERSION));
+    }
+    Ok(from)
+}
+
+fn check_supported(version: u32) -> Result<(), String> {
+    if version > CURRENT_SCHEMA_VERSION {
+        return Err(format!(
+            "Profile schema version {} is newer than this LoopAutoma supports ({})",
+            version, CURRENT_SCHEMA_VERSION
+        ));
+    }
+    Ok(())
+}
+
+/// Where the pre-migration copy of `path` is kept, e.g. `profiles.v1.bak.json`
+pub fn backup_path(path: &Path, from: u32) -> PathBuf {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("profiles");
+    path.with_file_name(format!("{}.v{}.bak.json", stem, from))
+}
+
+/// v2: count-based region conditions; clicks carry their own coordinates
+///
+/// v1 conditions waited `stable_ms` for a region to settle and v1 sequences moved
+/// the cursor with a separate `MoveCursor` before a coordinate-less `Click`.
+fn v1_to_v2(profile: &mut Map<String, Value>) -> Result<(), String> {
+    let interval_ms = profile
+        .get("trigger")
+        .and_then(|t| t.get("check_interval_sec"))
+        .and_then(Value::as_f64)
+        .filter(|s| *s > 0.0)
+        .map(|s| s * 1000.0);
+    if let Some(Value::Object(cond)) = profile.get_mut("condition") {
+        if !cond.contains_key("consecutive_checks") {
+            let checks = match (cond.get("stable_ms").and_then(Value::as_f64), interval_ms) {
+                (Some(stable), Some(interval)) => (stable / interval).ceil().max(1.0) as u64,
+                _ => 1,
+            };
+            cond.insert("consecutive_checks".into(), json!(checks));
+        }
+        cond.entry("expect_change").or_insert(json!(false));
+        cond.remove("stable_ms");
+        cond.remove("downscale");
+    }
+
+    if let Some(Value::Array(actions)) = profile.get_mut("actions") {
+        let mut cursor: Option<(Value, Value)> = None;
+        let mut migrated = Vec::with_capacity(actions.len());
+        for mut action in actions.drain(..) {
+            match action.get("type").and_then(Value::as_str) {
+                Some("MoveCursor") => {
+                    cursor = Some((action["x"].clone(), action["y"].clone()));
+                }
+                Some("Click") if action.get("x").is_none() => {
+                    let (x, y) = cursor
+                        .clone()
+                        .ok_or_else(|| "Click without coordinates and no preceding MoveCursor".to_string())?;
+                    action["x"] = x;
+                    action["y"] = y;
+                    migrated.push(action);
+                }
+                _ => migrated.push(action),
+            }
+        }
+        *actions = migrated;
+    }
+    Ok(())
+}
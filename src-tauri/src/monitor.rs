@@ -1,9 +1,15 @@
 use std::collections::VecDeque;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use crate::domain::{
-    ActionContext, ActionSequence, Condition, Event, Guardrails, MonitorState, Trigger,
+    ActionContext, ActionSequence, Condition, Event, Guardrails, MonitorState, RunOutcome,
+    Trigger,
 };
+use crate::network::ConnectivityProbe;
+
+/// How often connectivity is re-probed while waiting for the network
+pub const NETWORK_RETRY_INTERVAL: Duration = Duration::from_secs(5);
 
 pub struct Monitor<'a> {
     pub trigger: Box<dyn Trigger + Send + 'a>,
@@ -17,6 +23,26 @@ pub struct Monitor<'a> {
     pub context: ActionContext,
     /// Heartbeat: Last time an action made progress (used for stall detection)
     pub last_action_progress: Option<Instant>,
+    /// Optional connectivity probe consulted before LLM-dependent iterations
+    connectivity: Option<Arc<dyn ConnectivityProbe>>,
+    /// Set while paused in WaitingForNetwork; holds the time of the last probe
+    pub network_wait_probe_at: Option<Instant>,
+    /// When the current wait for the network began; that time does not count toward max_runtime
+    network_wait_since: Option<Instant>,
+    /// Action-sequence runs this session, successful or not (max_iterations budget)
+    pub iterations: u32,
+    /// LLM action failures in a row (max_consecutive_llm_failures budget)
+    pub consecutive_llm_failures: u32,
+    /// Why the monitor stopped itself; None while running or after a manual stop
+    pub outcome: Option<RunOutcome>,
+    /// Set while paused by the user; holds the time the pause began
+    pub paused_at: Option<Instant>,
+    /// State reported while paused: `Paused`, `OutsideRunWindow` or `ActionTimedOut`
+    pause_state: MonitorState,
+    /// Name of the last action that completed successfully (crash-recovery checkpoint)
+    pub last_successful_action: Option<String>,
+    /// Wall-clock time the last action sequence took
+    pub last_iteration_time: Duration,
 }
 
 impl<'a> Monitor<'a> {
@@ -37,7 +63,76 @@ impl<'a> Monitor<'a> {
             activation_log: VecDeque::new(),
             context: ActionContext::new(),
             last_action_progress: None,
+            connectivity: None,
+            network_wait_probe_at: None,
+            network_wait_since: None,
+            iterations: 0,
+            consecutive_llm_failures: 0,
+            outcome: None,
+            paused_at: None,
+            pause_state: MonitorState::Paused,
+            last_successful_action: None,
+            last_iteration_time: Duration::ZERO,
+        }
+    }
+
+    /// Attach a connectivity probe; only consulted if the action sequence needs the network
+    pub fn set_connectivity_probe(&mut self, probe: Arc<dyn ConnectivityProbe>) {
+        self.connectivity = Some(probe);
+    }
+
+    /// True while the monitor is paused waiting for network connectivity
+    pub fn is_waiting_for_network(&self) -> bool {
+        self.network_wait_probe_at.is_some()
+    }
+
+    /// True while paused by the user
+    pub fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+
+    /// State reported while paused; None while not paused
+    pub fn pause_state(&self) -> Option<MonitorState> {
+        self.is_paused().then_some(self.pause_state)
+    }
+
+    /// Hold all triggers until `resume`; context and counters are kept
+    pub fn pause(&mut self, now: Instant, events: &mut Vec<Event>) {
+        self.pause_as(now, MonitorState::Paused, events);
+    }
+
+    /// Pause reporting `state`; an already paused monitor only switches the reported state
+    pub fn pause_as(&mut self, now: Instant, state: MonitorState, events: &mut Vec<Event>) {
+        if self.started_at.is_none() || (self.is_paused() && self.pause_state == state) {
+            return;
+        }
+        self.paused_at.get_or_insert(now);
+        self.pause_state = state;
+        events.push(Event::MonitorStateChanged { state });
+    }
+
+    /// Resume a paused run; paused time counts toward neither max_runtime nor the heartbeat
+    /// (nor, when paused while offline, toward the network wait)
+    pub fn resume(&mut self, now: Instant, events: &mut Vec<Event>) {
+        let Some(paused_at) = self.paused_at.take() else {
+            return;
+        };
+        let paused_for = now.duration_since(paused_at);
+        if let Some(start) = self.started_at {
+            self.started_at = Some(start + paused_for);
+        }
+        if let Some(progress) = self.last_action_progress {
+            self.last_action_progress = Some(progress + paused_for);
+        }
+        if let Some(since) = self.network_wait_since {
+            self.network_wait_since = Some(since + paused_for);
         }
+        let state = if self.is_waiting_for_network() {
+            MonitorState::WaitingForNetwork
+        } else {
+            MonitorState::Running
+        };
+        events.push(Event::MonitorStateChanged { state });
     }
 
     pub fn start(&mut self, events: &mut Vec<Event>) {
@@ -47,6 +142,13 @@ impl<'a> Monitor<'a> {
         self.activation_log.clear();
         self.context = ActionContext::new(); // Reset context on start
         self.last_action_progress = None; // Reset heartbeat on start
+        self.network_wait_probe_at = None;
+        self.network_wait_since = None;
+        self.iterations = 0;
+        self.consecutive_llm_failures = 0;
+        self.outcome = None;
+        self.paused_at = None;
+        self.last_successful_action = None;
         events.push(Event::MonitorStateChanged {
             state: MonitorState::Running,
         });
@@ -54,6 +156,9 @@ impl<'a> Monitor<'a> {
     pub fn stop(&mut self, events: &mut Vec<Event>) {
         self.started_at = None;
         self.last_activation_at = None;
+        self.network_wait_probe_at = None;
+        self.network_wait_since = None;
+        self.paused_at = None;
         events.push(Event::MonitorStateChanged {
             state: MonitorState::Stopped,
         });
@@ -67,7 +172,7 @@ impl<'a> Monitor<'a> {
         automation: &dyn crate::domain::Automation,
         out_events: &mut Vec<Event>,
     ) {
-        if self.started_at.is_none() {
+        if self.started_at.is_none() || self.is_paused() {
             return;
         }
 
@@ -84,14 +189,34 @@ impl<'a> Monitor<'a> {
             0
         };
 
-        // guard: max runtime
+        // network wait: re-probe periodically, resume automatically once online.
+        // Checked before the runtime budget, which the time offline does not use up
+        if let Some(last_probe) = self.network_wait_probe_at {
+            if now.duration_since(last_probe) < NETWORK_RETRY_INTERVAL {
+                return;
+            }
+            if !self.is_online() {
+                self.network_wait_probe_at = Some(now);
+                return;
+            }
+            self.network_wait_probe_at = None;
+            if let (Some(start), Some(since)) = (self.started_at, self.network_wait_since.take()) {
+                self.started_at = Some(start + now.duration_since(since));
+            }
+            // Time spent offline is not a stall
+            if self.last_action_progress.is_some() {
+                self.last_action_progress = Some(now);
+            }
+            out_events.push(Event::MonitorStateChanged {
+                state: MonitorState::Running,
+            });
+        }
+
+        // budget: max runtime
         if let Some(start) = self.started_at {
             if let Some(max_rt) = self.guardrails.max_runtime {
                 if now.duration_since(start) > max_rt {
-                    out_events.push(Event::WatchdogTripped {
-                        reason: "max_runtime".into(),
-                    });
-                    self.stop(out_events);
+                    self.exceed_budget("max_runtime", max_rt.as_millis() as u64, out_events);
                     return;
                 }
             }
@@ -104,7 +229,12 @@ impl<'a> Monitor<'a> {
                     out_events.push(Event::WatchdogTripped {
                         reason: "heartbeat_stalled".into(),
                     });
-                    self.stop(out_events);
+                    self.end_run(
+                        RunOutcome::WatchdogTripped {
+                            reason: "heartbeat_stalled".into(),
+                        },
+                        out_events,
+                    );
                     return;
                 }
             }
@@ -132,7 +262,11 @@ impl<'a> Monitor<'a> {
             }
         }
 
-        let cond = self.condition.evaluate(now, regions, capture);
+        // Record the checked frames so the actions this change triggers can reuse them
+        let cond = match &self.actions.frame_cache {
+            Some(frames) => self.condition.evaluate(now, regions, &frames.recording(capture)),
+            None => self.condition.evaluate(now, regions, capture),
+        };
         out_events.push(Event::ConditionEvaluated { result: cond });
         out_events.push(Event::MonitorTick {
             next_check_ms,
@@ -154,9 +288,18 @@ impl<'a> Monitor<'a> {
         {
             if let Some(termination_reason) = self.check_ocr_termination(regions, capture) {
                 out_events.push(Event::WatchdogTripped {
-                    reason: termination_reason,
+                    reason: termination_reason.clone(),
                 });
-                self.stop(out_events);
+                let outcome = if termination_reason.starts_with("ocr_failure") {
+                    RunOutcome::Failed {
+                        reason: termination_reason,
+                    }
+                } else {
+                    RunOutcome::Completed {
+                        reason: termination_reason,
+                    }
+                };
+                self.end_run(outcome, out_events);
                 return;
             }
         }
@@ -179,15 +322,64 @@ impl<'a> Monitor<'a> {
             }
         }
 
+        // connectivity: park instead of failing LLM calls with HTTP errors
+        if self.actions.requires_network() && !self.is_online() {
+            self.network_wait_probe_at = Some(now);
+            self.network_wait_since = Some(now);
+            out_events.push(Event::MonitorStateChanged {
+                state: MonitorState::WaitingForNetwork,
+            });
+            return;
+        }
+
         // Touch heartbeat before running actions
         self.last_action_progress = Some(now);
         
-        let ok = self.actions.run(automation, &mut self.context, out_events);
-        if ok {
-            self.activations += 1;
-            self.last_activation_at = Some(now);
-            if self.guardrails.max_activations_per_hour.is_some() {
-                self.activation_log.push_back(now);
+        let iteration_started = Instant::now();
+        let first_event = out_events.len();
+        let result = {
+            let span = tracing::info_span!("iteration", n = self.iterations + 1, success = tracing::field::Empty).entered();
+            let result = self.actions.execute(automation, &mut self.context, out_events);
+            span.record("success", result.is_ok());
+            result
+        };
+        self.last_iteration_time = iteration_started.elapsed();
+        // Paused or stopped mid-sequence: not counted, the next trigger starts it over
+        if result.is_err() && self.context.cancel.is_cancelled() {
+            return;
+        }
+        let timed_out = out_events[first_event..].iter().any(
+            |e| matches!(e, Event::WatchdogTripped { reason } if reason == crate::watchdog::ACTION_TIMEOUT),
+        );
+        self.iterations += 1;
+        out_events.push(Event::IterationCompleted {
+            iteration: self.iterations,
+            success: result.is_ok(),
+        });
+        let succeeded = match result {
+            Ok(()) => self.actions.actions.len(),
+            Err(failed) => failed,
+        };
+        if let Some(last) = succeeded.checked_sub(1) {
+            self.last_successful_action = Some(self.actions.actions[last].name().to_string());
+        }
+        match result {
+            Ok(()) => {
+                self.activations += 1;
+                self.last_activation_at = Some(now);
+                if self.guardrails.max_activations_per_hour.is_some() {
+                    self.activation_log.push_back(now);
+                }
+                if self.actions.actions.iter().any(|a| a.uses_llm()) {
+                    self.consecutive_llm_failures = 0;
+                }
+            }
+            Err(failed) => {
+                if self.actions.actions[failed].uses_llm() {
+                    self.consecutive_llm_failures += 1;
+                } else if self.actions.actions[..failed].iter().any(|a| a.uses_llm()) {
+                    self.consecutive_llm_failures = 0;
+                }
             }
         }
         
@@ -195,9 +387,57 @@ impl<'a> Monitor<'a> {
         if self.context.is_termination_requested() {
             let reason = self.context.termination_reason.clone()
                 .unwrap_or_else(|| "termination_requested".to_string());
-            out_events.push(Event::WatchdogTripped { reason });
-            self.stop(out_events);
+            out_events.push(Event::WatchdogTripped {
+                reason: reason.clone(),
+            });
+            self.end_run(RunOutcome::Completed { reason }, out_events);
+            return;
+        }
+
+        // budgets: iterations and LLM failure streak
+        if let Some(max) = self.guardrails.max_consecutive_llm_failures {
+            if self.consecutive_llm_failures >= max {
+                self.exceed_budget("max_consecutive_llm_failures", max as u64, out_events);
+                return;
+            }
+        }
+        if let Some(max) = self.guardrails.max_iterations {
+            if self.iterations >= max {
+                self.exceed_budget("max_iterations", max as u64, out_events);
+                return;
+            }
         }
+
+        // An abandoned action may have left the desktop anywhere; hold for the user
+        if timed_out {
+            self.pause_as(now, MonitorState::ActionTimedOut, out_events);
+        }
+    }
+
+    /// Stop the run on its own accord, remembering why for run history
+    fn end_run(&mut self, outcome: RunOutcome, out_events: &mut Vec<Event>) {
+        self.outcome = Some(outcome);
+        self.stop(out_events);
+    }
+
+    fn exceed_budget(&mut self, budget: &str, limit: u64, out_events: &mut Vec<Event>) {
+        out_events.push(Event::BudgetExceeded {
+            budget: budget.into(),
+            limit,
+        });
+        self.end_run(
+            RunOutcome::BudgetExceeded {
+                budget: budget.into(),
+            },
+            out_events,
+        );
+    }
+
+    fn is_online(&self) -> bool {
+        self.connectivity
+            .as_ref()
+            .map(|probe| probe.is_online())
+            .unwrap_or(true)
     }
 
     /// Check OCR regions for termination patterns (success/failure keywords)
@@ -215,7 +455,7 @@ impl<'a> Monitor<'a> {
         let ocr = match crate::os::linux::LinuxOCR::new() {
             Ok(o) => o,
             Err(e) => {
-                eprintln!("[Monitor] Failed to initialize OCR: {}", e.message);
+                tracing::warn!("Failed to initialize OCR: {}", e.message);
                 return None;
             }
         };
@@ -225,7 +465,7 @@ impl<'a> Monitor<'a> {
             let region = match regions.iter().find(|r| &r.id == region_id) {
                 Some(r) => r,
                 None => {
-                    eprintln!("[Monitor] OCR region '{}' not found", region_id);
+                    tracing::warn!("OCR region '{}' not found", region_id);
                     continue;
                 }
             };
@@ -237,18 +477,15 @@ impl<'a> Monitor<'a> {
             let text = match ocr.extract_text_cached(region, region_hash) {
                 Ok(t) => t,
                 Err(e) => {
-                    eprintln!("[Monitor] OCR extraction failed for '{}': {}", region_id, e.message);
-                    println!("[OCR] Failed to extract text from region '{}' ({}): {}", 
+                    tracing::warn!("Failed to extract text from region '{}' ({}): {}",
                         region.name.as_deref().unwrap_or(&region_id), region_id, e.message);
                     continue;
                 }
             };
 
-            // Issue 7: Log OCR extraction with region name and extracted text
+            // Issue 7: log OCR extraction with region name; the text itself stays out of the logs
             let region_name = region.name.as_deref().unwrap_or(&region_id);
-            println!("[OCR] Region '{}' (ID: {}) extracted text: '{}'", 
-                region_name, region_id, 
-                if text.len() > 100 { format!("{}...", &text[..100]) } else { text.clone() });
+            tracing::debug!(chars = text.chars().count(), "Region '{}' (ID: {}) text extracted", region_name, region_id);
 
             let text_upper = text.to_uppercase();
 
@@ -0,0 +1,283 @@
+/// Network connectivity probing used to guard LLM-dependent iterations
+///
+/// The monitor consults a probe before running an action sequence that needs the
+/// network; when offline it parks in `MonitorState::WaitingForNetwork` instead of
+/// burning retries on HTTP errors. The probe connects to the host of the run's
+/// LLM client from a background thread and its answer is kept for `PROBE_TTL`,
+/// so a tick never waits on DNS or a TCP connect.
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::llm::LLMClient;
+
+/// How long a probe result is reused
+pub const PROBE_TTL: Duration = Duration::from_secs(5);
+
+/// Trait for connectivity checks to enable testing with fakes
+pub trait ConnectivityProbe: Send + Sync {
+    /// Returns true if the remote service is currently reachable
+    fn is_online(&self) -> bool;
+}
+
+/// Probe that opens a TCP connection to a host/port; `timeout` bounds the
+/// connects to all resolved addresses together
+pub struct TcpConnectivityProbe {
+    host: String,
+    port: u16,
+    timeout: Duration,
+}
+
+impl TcpConnectivityProbe {
+    pub fn new(host: impl Into<String>, port: u16, timeout: Duration) -> Self {
+        Self {
+            host: host.into(),
+            port,
+            timeout,
+        }
+    }
+
+    /// Probe the host of an http(s) endpoint; None if `url` is not one
+    pub fn for_endpoint(url: &str) -> Option<Self> {
+        let (host, port) = endpoint_host_port(url)?;
+        Some(Self::new(host, port, Duration::from_secs(3)))
+    }
+}
+
+impl ConnectivityProbe for TcpConnectivityProbe {
+    fn is_online(&self) -> bool {
+        let addrs = match (self.host.as_str(), self.port).to_socket_addrs() {
+            Ok(addrs) => addrs,
+            Err(_) => return false, // DNS failure counts as offline
+        };
+        let deadline = Instant::now() + self.timeout;
+        addrs.into_iter().any(|addr| {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            !remaining.is_zero() && TcpStream::connect_timeout(&addr, remaining).is_ok()
+        })
+    }
+}
+
+/// Reuses the answer of `inner` until it is `ttl` old, refreshing it on a
+/// background thread; online is assumed until the first answer arrives
+pub struct CachedProbe {
+    inner: Arc<dyn ConnectivityProbe>,
+    ttl: Duration,
+    state: Arc<Mutex<CachedState>>,
+}
+
+#[derive(Default)]
+struct CachedState {
+    last: Option<(Instant, bool)>,
+    probing: bool,
+}
+
+impl CachedProbe {
+    pub fn new(inner: Box<dyn ConnectivityProbe>, ttl: Duration) -> Self {
+        Self {
+            inner: Arc::from(inner),
+            ttl,
+            state: Arc::new(Mutex::new(CachedState::default())),
+        }
+    }
+}
+
+impl ConnectivityProbe for CachedProbe {
+    fn is_online(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let answer = state.last.map(|(_, online)| online).unwrap_or(true);
+        let fresh = matches!(state.last, Some((at, _)) if at.elapsed() < self.ttl);
+        if fresh || state.probing {
+            return answer;
+        }
+        // At most one probe in flight; the tick keeps the previous answer meanwhile
+        state.probing = true;
+        drop(state);
+        let inner = self.inner.clone();
+        let shared = self.state.clone();
+        let spawned = std::thread::Builder::new()
+            .name("connectivity-probe".into())
+            .spawn(move || {
+                let online = inner.is_online();
+                let mut state = shared.lock().unwrap();
+                state.last = Some((Instant::now(), online));
+                state.probing = false;
+            });
+        if let Err(e) = spawned {
+            tracing::warn!("Could not start connectivity probe: {}", e);
+            self.state.lock().unwrap().probing = false;
+        }
+        answer
+    }
+}
+
+/// Probe that always reports connectivity (fake backend, tests)
+pub struct AlwaysOnline;
+
+impl ConnectivityProbe for AlwaysOnline {
+    fn is_online(&self) -> bool {
+        true
+    }
+}
+
+/// Create the connectivity probe for a run using `llm`: the host it calls,
+/// or always online for the fake backend and clients that call nothing
+pub fn make_connectivity_probe(llm: &dyn LLMClient) -> Box<dyn ConnectivityProbe> {
+    if crate::fakes::selected() {
+        return Box::new(AlwaysOnline);
+    }
+    let Some(endpoint) = llm.endpoint() else {
+        return Box::new(AlwaysOnline);
+    };
+    match TcpConnectivityProbe::for_endpoint(&endpoint) {
+        Some(probe) => Box::new(CachedProbe::new(Box::new(probe), PROBE_TTL)),
+        None => {
+            tracing::warn!("LLM endpoint '{}' is not an http(s) URL; not probing connectivity", endpoint);
+            Box::new(AlwaysOnline)
+        }
+    }
+}
+
+/// Extract host and port from an http(s) URL, defaulting the port by scheme
+pub(crate) fn endpoint_host_port(url: &str) -> Option<(String, u16)> {
+    let (default_port, rest) = if let Some(rest) = url.strip_prefix("https://") {
+        (443, rest)
+    } else if let Some(rest) = url.strip_prefix("http://") {
+        (80, rest)
+    } else {
+        return None;
+    };
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let authority = authority.rsplit('@').next()?; // drop userinfo
+    if authority.is_empty() {
+        return None;
+    }
+    // Bracketed IPv6 literal, e.g. [::1]:8443
+    let (host, port) = if let Some(rest) = authority.strip_prefix('[') {
+        let (host, after) = rest.split_once(']')?;
+        (host, after.strip_prefix(':'))
+    } else {
+        match authority.split_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        }
+    };
+    let port = match port {
+        Some(p) => p.parse::<u16>().ok()?,
+        None => default_port,
+    };
+    Some((host.to_string(), port))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn endpoint_parsing_defaults_port_by_scheme() {
+        assert_eq!(
+            endpoint_host_port("https://api.openai.com/v1/chat/completions"),
+            Some(("api.openai.com".to_string(), 443))
+        );
+        assert_eq!(
+            endpoint_host_port("http://localhost/v1"),
+            Some(("localhost".to_string(), 80))
+        );
+    }
+
+    #[test]
+    fn endpoint_parsing_honours_explicit_port() {
+        assert_eq!(
+            endpoint_host_port("http://127.0.0.1:11434/api"),
+            Some(("127.0.0.1".to_string(), 11434))
+        );
+        assert_eq!(
+            endpoint_host_port("https://[::1]:8443"),
+            Some(("::1".to_string(), 8443))
+        );
+    }
+
+    #[test]
+    fn endpoint_parsing_rejects_unknown_schemes() {
+        assert_eq!(endpoint_host_port("ftp://example.com"), None);
+        assert_eq!(endpoint_host_port("https://"), None);
+    }
+
+    /// Offline, counting how often it was asked
+    struct Counting(Arc<AtomicUsize>);
+
+    impl ConnectivityProbe for Counting {
+        fn is_online(&self) -> bool {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            false
+        }
+    }
+
+    /// Poll `probe` until it reports offline, i.e. a background probe answered
+    fn await_offline(probe: &CachedProbe) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while probe.is_online() {
+            assert!(Instant::now() < deadline, "background probe never answered");
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn cached_probe_connects_once_per_ttl() {
+        let probes = Arc::new(AtomicUsize::new(0));
+        let cached = CachedProbe::new(Box::new(Counting(probes.clone())), Duration::from_secs(60));
+        assert!(cached.is_online(), "online is assumed until the first answer");
+        await_offline(&cached);
+        assert!(!cached.is_online() && !cached.is_online());
+        assert_eq!(probes.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn cached_probe_does_not_block_on_a_slow_probe() {
+        struct Slow;
+        impl ConnectivityProbe for Slow {
+            fn is_online(&self) -> bool {
+                std::thread::sleep(Duration::from_secs(2));
+                false
+            }
+        }
+        let cached = CachedProbe::new(Box::new(Slow), Duration::ZERO);
+        let started = Instant::now();
+        assert!(cached.is_online() && cached.is_online());
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn tcp_probe_caps_total_connect_time() {
+        // Nothing is left of a zero timeout, so no address is tried
+        let probe = TcpConnectivityProbe::new("127.0.0.1", 9, Duration::ZERO);
+        let started = Instant::now();
+        assert!(!probe.is_online());
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn probe_targets_the_llm_clients_endpoint() {
+        use crate::domain::{Error, LLMPromptResponse, Region};
+        struct Local;
+        impl LLMClient for Local {
+            fn generate_prompt(&self, _: &[Region], _: Vec<Vec<u8>>, _: Option<&str>, _: &str) -> Result<LLMPromptResponse, Error> {
+                Err(Error::Llm("unused".into()))
+            }
+            fn endpoint(&self) -> Option<String> {
+                Some("http://127.0.0.1:11434/v1/chat/completions".into())
+            }
+        }
+        let probe = TcpConnectivityProbe::for_endpoint(&Local.endpoint().unwrap()).unwrap();
+        assert_eq!((probe.host.as_str(), probe.port), ("127.0.0.1", 11434));
+        assert!(TcpConnectivityProbe::for_endpoint("unix:///run/llm.sock").is_none());
+        assert!(make_connectivity_probe(&crate::llm::MockLLMClient::new()).is_online(), "mock clients call nothing");
+    }
+
+    #[test]
+    fn tcp_probe_reports_offline_for_unresolvable_host() {
+        let probe = TcpConnectivityProbe::new("invalid.invalid", 443, Duration::from_millis(100));
+        assert!(!probe.is_online());
+    }
+}
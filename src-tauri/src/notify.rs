@@ -0,0 +1,438 @@
+/// Per-profile notification routing
+///
+/// Runner events and run outcomes are mapped to a small set of notification
+/// events. A profile's `notifications` map decides which channels fire for each;
+/// events it does not list fall back to the default routing, and an empty list
+/// silences an event.
+///
+/// Every channel is a `Notifier`. The `NotificationRouter` of a run holds one
+/// per configured channel and hands each notice to the notifiers whose channel
+/// the event is routed to, so a new channel only has to implement `Notifier`.
+///
+/// The Sound channel plays whatever the audio settings map the event to, so a
+/// budget stop can sound different from a failure keyword; see `audio`.
+///
+/// Webhook messages carry the reason a run ended; see `webhooks`. Emails carry a
+/// run summary built from the progress the runner records; see `email`.
+///
+/// Quiet hours (a weekly schedule, or do-not-disturb until turned off) hold back
+/// sounds and desktop popups; webhooks and email still go out. Events marked
+/// critical, the risk alarm by default, break through.
+///
+/// Desktop notifications go through `notify-rust`. Clicks are reported only by
+/// freedesktop notification servers, so click-through works on Linux; on macOS
+/// and Windows the notification is informational.
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::audio::{AlertEvent, AudioNotifier};
+use crate::domain::{Event, MonitorState, NotificationChannel, NotificationEvent, RunOutcome, RunWindow};
+use crate::email::RunSummary;
+use crate::history;
+use crate::schedule::{self, RunSchedule};
+
+pub type Routing = BTreeMap<NotificationEvent, Vec<NotificationChannel>>;
+
+/// Notification event raised by a runner event, if any
+pub fn classify_event(event: &Event) -> Option<NotificationEvent> {
+    match event {
+        Event::MonitorStateChanged {
+            state: MonitorState::WaitingForNetwork | MonitorState::ActionTimedOut,
+        } => Some(NotificationEvent::InterventionNeeded),
+        Event::Error { message } if message.contains("Risk threshold exceeded") => {
+            Some(NotificationEvent::RiskExceeded)
+        }
+        _ => None,
+    }
+}
+
+/// Notification event for how a run ended; user stops notify nobody
+pub fn classify_outcome(outcome: &RunOutcome) -> Option<NotificationEvent> {
+    match outcome.succeeded()? {
+        true => Some(NotificationEvent::Completed),
+        false => Some(NotificationEvent::Failed),
+    }
+}
+
+/// Sound event for a notification; budget stops get their own sound
+pub fn alert_for(event: NotificationEvent, outcome: Option<&RunOutcome>) -> AlertEvent {
+    match event {
+        NotificationEvent::Started => AlertEvent::RunStarted,
+        NotificationEvent::InterventionNeeded => AlertEvent::InterventionNeeded,
+        NotificationEvent::Completed => AlertEvent::ProfileEnded,
+        NotificationEvent::RiskExceeded => AlertEvent::RiskExceeded,
+        NotificationEvent::Failed => match outcome {
+            Some(RunOutcome::BudgetExceeded { .. }) => AlertEvent::BudgetExceeded,
+            _ => AlertEvent::Error,
+        },
+    }
+}
+
+/// Why a run ended, for messages that have room for it
+pub fn outcome_detail(outcome: &RunOutcome) -> Option<String> {
+    match outcome {
+        RunOutcome::Stopped => None,
+        RunOutcome::Completed { reason } | RunOutcome::Failed { reason } | RunOutcome::WatchdogTripped { reason } => {
+            Some(reason.clone())
+        }
+        RunOutcome::BudgetExceeded { budget } => Some(format!("budget {} exhausted", budget)),
+    }
+}
+
+/// Routing for profiles that configure nothing: every event goes to the
+/// configured webhooks, every event but a start beeps, all but failures and
+/// starts also post a desktop notification, and a run that ends or needs
+/// intervention is summarised by email
+pub fn default_channels(event: NotificationEvent) -> Vec<NotificationChannel> {
+    use NotificationChannel::*;
+    match event {
+        NotificationEvent::Started => vec![Webhook],
+        NotificationEvent::Failed => vec![Sound, Webhook, Email],
+        NotificationEvent::RiskExceeded => vec![Sound, Desktop, Webhook],
+        NotificationEvent::InterventionNeeded | NotificationEvent::Completed => vec![Sound, Desktop, Webhook, Email],
+    }
+}
+
+pub fn channels_for(routing: &Routing, event: NotificationEvent) -> Vec<NotificationChannel> {
+    routing
+        .get(&event)
+        .cloned()
+        .unwrap_or_else(|| default_channels(event))
+}
+
+/// Global quiet hours for sounds and desktop notifications
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuietHours {
+    /// Quiet until turned off, whatever the schedule says
+    #[serde(default)]
+    pub do_not_disturb: bool,
+    /// Local times that are quiet each week; same format as profile run windows
+    #[serde(default)]
+    pub windows: Vec<RunWindow>,
+    /// Record held-back notifications in the event log instead of dropping them
+    #[serde(default)]
+    pub log_suppressed: bool,
+    /// Events that sound and pop up even during quiet hours
+    #[serde(default = "default_critical")]
+    pub critical: Vec<NotificationEvent>,
+}
+
+fn default_critical() -> Vec<NotificationEvent> {
+    vec![NotificationEvent::RiskExceeded]
+}
+
+impl Default for QuietHours {
+    fn default() -> Self {
+        Self {
+            do_not_disturb: false,
+            windows: Vec::new(),
+            log_suppressed: false,
+            critical: default_critical(),
+        }
+    }
+}
+
+/// Quiet hours with their windows parsed, shared by the settings and every run
+#[derive(Debug, Clone, Default)]
+pub struct QuietPolicy {
+    hours: QuietHours,
+    schedule: RunSchedule,
+}
+
+impl QuietPolicy {
+    pub fn new(hours: QuietHours) -> Result<Self, String> {
+        let schedule = RunSchedule::parse_field("quiet_hours.windows", &hours.windows)?;
+        Ok(Self { hours, schedule })
+    }
+
+    pub fn hours(&self) -> &QuietHours {
+        &self.hours
+    }
+
+    pub fn is_quiet(&self, now: NaiveDateTime) -> bool {
+        self.hours.do_not_disturb || (!self.hours.windows.is_empty() && self.schedule.is_open(now))
+    }
+
+    /// Whether `event` loses its sound and desktop popup at `now`; `None` is a sound-only alert
+    pub fn holds_back(&self, event: Option<NotificationEvent>, now: NaiveDateTime) -> bool {
+        self.is_quiet(now) && !event.is_some_and(|e| self.hours.critical.contains(&e))
+    }
+}
+
+/// One notification as handed to the channels
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notice {
+    pub event: NotificationEvent,
+    /// Sound to play; budget stops sound different from other failures
+    pub alert: AlertEvent,
+    pub profile_name: String,
+    /// Why the run ended or failed, for channels that have room for it
+    pub detail: Option<String>,
+    pub iterations: u32,
+    pub duration_ms: u64,
+    pub last_llm_reason: Option<String>,
+}
+
+impl Notice {
+    /// Title and body of the desktop notification
+    pub fn message(&self) -> (String, String) {
+        desktop_message(self.event, &self.profile_name)
+    }
+
+    /// One-line chat message, with the detail when there is one
+    pub fn text(&self) -> String {
+        let (title, body) = self.message();
+        match &self.detail {
+            Some(detail) => format!("{}: {} ({})", title, body, detail),
+            None => format!("{}: {}", title, body),
+        }
+    }
+
+    /// Summary for email
+    pub fn summary(&self) -> RunSummary {
+        RunSummary {
+            profile_name: self.profile_name.clone(),
+            event: self.event,
+            detail: self.detail.clone(),
+            iterations: self.iterations,
+            duration_ms: self.duration_ms,
+            last_llm_reason: self.last_llm_reason.clone(),
+        }
+    }
+}
+
+/// A notification channel; the router decides which notices reach it
+pub trait Notifier: Send + Sync {
+    fn channel(&self) -> NotificationChannel;
+
+    fn notify(&self, notice: &Notice) -> Result<(), String>;
+
+    /// Sound-only alert that is not a routed event, such as a debugger step
+    fn alert(&self, _alert: AlertEvent) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Sound channel: plays the alert through the audio settings' sound map
+pub struct SoundNotifier(pub Arc<dyn AudioNotifier>);
+
+impl Notifier for SoundNotifier {
+    fn channel(&self) -> NotificationChannel {
+        NotificationChannel::Sound
+    }
+
+    fn notify(&self, notice: &Notice) -> Result<(), String> {
+        self.0.play(notice.alert).map_err(String::from)
+    }
+
+    fn alert(&self, alert: AlertEvent) -> Result<(), String> {
+        self.0.play(alert).map_err(String::from)
+    }
+}
+
+/// Title and body of the desktop notification for `event`
+pub fn desktop_message(event: NotificationEvent, profile_name: &str) -> (String, String) {
+    let body = match event {
+        NotificationEvent::Started => "The run started",
+        NotificationEvent::InterventionNeeded => "The run is waiting for you",
+        NotificationEvent::Completed => "The run completed",
+        NotificationEvent::Failed => "The run failed",
+        NotificationEvent::RiskExceeded => "An LLM prompt was rejected for exceeding the risk threshold",
+    };
+    (format!("LoopAutoma: {}", profile_name), body.to_string())
+}
+
+/// Post an OS notification; `on_click` runs if the user clicks it and the desktop reports clicks
+#[cfg(feature = "desktop-notifications")]
+pub fn post_desktop_notification(
+    title: &str,
+    body: &str,
+    on_click: impl FnOnce() + Send + 'static,
+) -> Result<(), String> {
+    let mut notification = notify_rust::Notification::new();
+    notification.appname("LoopAutoma").summary(title).body(body);
+    #[cfg(all(unix, not(target_os = "macos")))]
+    {
+        let handle = notification
+            .action("default", "Open")
+            .show()
+            .map_err(|e| format!("Failed to post desktop notification: {}", e))?;
+        std::thread::spawn(move || {
+            handle.wait_for_action(|action| {
+                if action == "default" {
+                    on_click();
+                }
+            })
+        });
+    }
+    #[cfg(not(all(unix, not(target_os = "macos"))))]
+    {
+        drop(on_click);
+        notification
+            .show()
+            .map_err(|e| format!("Failed to post desktop notification: {}", e))?;
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "desktop-notifications"))]
+pub fn post_desktop_notification(
+    _title: &str,
+    _body: &str,
+    _on_click: impl FnOnce() + Send + 'static,
+) -> Result<(), String> {
+    Err("This build cannot post desktop notifications".into())
+}
+
+/// Delivers a run's notifications to the channels its profile routes them to
+pub struct NotificationRouter {
+    routing: Routing,
+    profile_name: String,
+    notifiers: Vec<Arc<dyn Notifier>>,
+    progress: Mutex<RunProgress>,
+    quiet: Option<Quiet>,
+}
+
+/// Quiet hours as configured now, and where held-back notifications are logged
+struct Quiet {
+    policy: Arc<Mutex<QuietPolicy>>,
+    log: Box<dyn Fn(&Event) + Send + Sync>,
+}
+
+/// Run state for email summaries, as last recorded by the runner
+#[derive(Debug, Default)]
+struct RunProgress {
+    started_at_ms: u64,
+    iterations: u32,
+    last_llm_reason: Option<String>,
+}
+
+impl NotificationRouter {
+    /// Router for a run of `profile_name` that started at `started_at_ms`; it has no channels until `with` adds them
+    pub fn new(profile_name: String, routing: Routing, started_at_ms: u64) -> Self {
+        Self {
+            routing,
+            profile_name,
+            notifiers: Vec::new(),
+            progress: Mutex::new(RunProgress {
+                started_at_ms,
+                ..RunProgress::default()
+            }),
+            quiet: None,
+        }
+    }
+
+    /// Add a channel; several notifiers may share one
+    pub fn with(mut self, notifier: Arc<dyn Notifier>) -> Self {
+        self.notifiers.push(notifier);
+        self
+    }
+
+    /// Hold back sounds and desktop popups during quiet hours; `log` receives
+    /// `NotificationSuppressed` when the policy asks for log-only delivery.
+    /// The policy is read at each notification, so settings changes apply mid-run.
+    pub fn with_quiet_hours(
+        mut self,
+        policy: Arc<Mutex<QuietPolicy>>,
+        log: impl Fn(&Event) + Send + Sync + 'static,
+    ) -> Self {
+        self.quiet = Some(Quiet {
+            policy,
+            log: Box::new(log),
+        });
+        self
+    }
+
+    /// Update the iteration count and LLM reason reported by later summaries
+    pub fn record_progress(&self, iterations: u32, last_llm_reason: Option<&str>) {
+        let mut progress = self.progress.lock().unwrap();
+        progress.iterations = iterations;
+        if let Some(reason) = last_llm_reason {
+            progress.last_llm_reason = Some(reason.to_string());
+        }
+    }
+
+    pub fn on_start(&self) {
+        self.notify(NotificationEvent::Started, None);
+    }
+
+    pub fn on_event(&self, event: &Event) {
+        // The debugger waits for the user to approve each action; that only ever beeps
+        if let (Event::DebugPaused { .. }, false) = (event, self.holds_back(None)) {
+            self.alert(AlertEvent::ApprovalRequested);
+        }
+        if let Some(n) = classify_event(event) {
+            let detail = match event {
+                Event::Error { message } => Some(message.as_str()),
+                _ => None,
+            };
+            self.notify(n, detail);
+        }
+    }
+
+    pub fn on_outcome(&self, outcome: &RunOutcome) {
+        if let Some(n) = classify_outcome(outcome) {
+            self.deliver(n, alert_for(n, Some(outcome)), outcome_detail(outcome).as_deref());
+        }
+    }
+
+    /// Deliver `event` to its channels; `detail` (e.g. the completion reason) goes to webhooks and email
+    pub fn notify(&self, event: NotificationEvent, detail: Option<&str>) {
+        self.deliver(event, alert_for(event, None), detail);
+    }
+
+    fn deliver(&self, event: NotificationEvent, alert: AlertEvent, detail: Option<&str>) {
+        let mut channels = channels_for(&self.routing, event);
+        if self.holds_back(Some(event)) {
+            let held: Vec<_> = channels
+                .iter()
+                .copied()
+                .filter(|c| matches!(c, NotificationChannel::Sound | NotificationChannel::Desktop))
+                .collect();
+            channels.retain(|c| !held.contains(c));
+            if let Some(quiet) = self.quiet.as_ref().filter(|q| q.policy.lock().unwrap().hours.log_suppressed) {
+                if !held.is_empty() {
+                    (quiet.log)(&Event::NotificationSuppressed { event, channels: held });
+                }
+            }
+        }
+        let notice = self.notice(event, alert, detail);
+        for channel in channels {
+            for notifier in self.notifiers.iter().filter(|n| n.channel() == channel) {
+                if let Err(e) = notifier.notify(&notice) {
+                    tracing::warn!("Failed to deliver {:?} via {:?}: {}", event, channel, e);
+                }
+            }
+        }
+    }
+
+    fn notice(&self, event: NotificationEvent, alert: AlertEvent, detail: Option<&str>) -> Notice {
+        let progress = self.progress.lock().unwrap();
+        Notice {
+            event,
+            alert,
+            profile_name: self.profile_name.clone(),
+            detail: detail.map(str::to_string),
+            iterations: progress.iterations,
+            duration_ms: history::now_ms().saturating_sub(progress.started_at_ms),
+            last_llm_reason: progress.last_llm_reason.clone(),
+        }
+    }
+
+    fn holds_back(&self, event: Option<NotificationEvent>) -> bool {
+        self.quiet
+            .as_ref()
+            .is_some_and(|q| q.policy.lock().unwrap().holds_back(event, schedule::local_now()))
+    }
+
+    fn alert(&self, alert: AlertEvent) {
+        for notifier in &self.notifiers {
+            if let Err(e) = notifier.alert(alert) {
+                tracing::warn!("Failed to play {:?} alert: {}", alert, e);
+            }
+        }
+    }
+}
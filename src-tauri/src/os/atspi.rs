@@ -0,0 +1,211 @@
+/// UI element lookup on Linux through the AT-SPI accessibility bus
+///
+/// GTK, Qt, Firefox, Chromium and Electron apps publish their widgets on the
+/// AT-SPI bus, a separate D-Bus bus whose address the session bus hands out.
+/// `AtspiFinder` walks each application's tree, skipping hidden subtrees, and
+/// reports the matching elements with their screen extents. Under Wayland some
+/// toolkits only know window-relative coordinates, so rects may be offset.
+use zbus::blocking::{Connection, Proxy};
+use zbus::zvariant::{OwnedObjectPath, OwnedValue};
+
+use crate::domain::{
+    canonical_role, BackendError, ElementFinder, ElementQuery, UiElement, ELEMENT_SEARCH_MAX_DEPTH,
+    ELEMENT_SEARCH_MAX_NODES,
+};
+
+const REGISTRY: &str = "org.a11y.atspi.Registry";
+const ROOT_PATH: &str = "/org/a11y/atspi/accessible/root";
+const ACCESSIBLE: &str = "org.a11y.atspi.Accessible";
+const ACTION: &str = "org.a11y.atspi.Action";
+const COMPONENT: &str = "org.a11y.atspi.Component";
+const TEXT: &str = "org.a11y.atspi.Text";
+const PROPERTIES: &str = "org.freedesktop.DBus.Properties";
+/// `ATSPI_COORD_TYPE_SCREEN`
+const COORD_SCREEN: u32 = 0;
+/// `ATSPI_STATE_SHOWING`
+const STATE_SHOWING: u32 = 25;
+
+/// Bus name and object path of an accessible
+type Node = (String, OwnedObjectPath);
+
+fn bus_error(e: zbus::Error) -> BackendError {
+    BackendError::new("atspi_failed", e.to_string())
+}
+
+/// AT-SPI names editable text `text`; static text is `label`
+fn atspi_role(role_name: &str) -> String {
+    match role_name {
+        "text" => "text field".into(),
+        other => canonical_role(other),
+    }
+}
+
+struct Accessible<'a> {
+    conn: &'a Connection,
+    node: &'a Node,
+    proxy: Proxy<'a>,
+}
+
+impl<'a> Accessible<'a> {
+    fn new(conn: &'a Connection, node: &'a Node) -> zbus::Result<Self> {
+        let proxy = Proxy::new(conn, node.0.as_str(), node.1.as_str(), ACCESSIBLE)?;
+        Ok(Self { conn, node, proxy })
+    }
+
+    fn children(&self) -> zbus::Result<Vec<Node>> {
+        self.proxy.call("GetChildren", &())
+    }
+
+    fn role(&self) -> zbus::Result<String> {
+        self.proxy.call::<_, _, String>("GetRoleName", &()).map(|r| atspi_role(&r))
+    }
+
+    /// Read through `Properties.Get` so the proxy never sets up a property cache
+    fn name(&self) -> zbus::Result<String> {
+        let properties = Proxy::new(self.conn, self.node.0.as_str(), self.node.1.as_str(), PROPERTIES)?;
+        let value: OwnedValue = properties.call("Get", &(ACCESSIBLE, "Name"))?;
+        Ok(String::try_from(value).unwrap_or_default())
+    }
+
+    fn showing(&self) -> bool {
+        let states: Vec<u32> = self.proxy.call("GetState", &()).unwrap_or_default();
+        let word = (STATE_SHOWING / 32) as usize;
+        states.get(word).is_some_and(|bits| bits & (1 << (STATE_SHOWING % 32)) != 0)
+    }
+
+    /// Contents of the Text interface (entries, labels, documents)
+    fn text(&self) -> Option<String> {
+        let text = Proxy::new(self.conn, self.node.0.as_str(), self.node.1.as_str(), TEXT).ok()?;
+        let contents: String = text.call("GetText", &(0i32, -1i32)).ok()?;
+        (!contents.is_empty()).then_some(contents)
+    }
+
+    /// Run the first action of the Action interface, usually click/press/activate
+    fn do_default_action(&self) -> zbus::Result<bool> {
+        let action = Proxy::new(self.conn, self.node.0.as_str(), self.node.1.as_str(), ACTION)?;
+        action.call("DoAction", &(0i32,))
+    }
+
+    /// Screen rect, if the element has the Component interface and a size
+    fn extents(&self) -> Option<(i32, i32, u32, u32)> {
+        let component = Proxy::new(self.conn, self.node.0.as_str(), self.node.1.as_str(), COMPONENT).ok()?;
+        let (x, y, w, h): (i32, i32, i32, i32) = component.call("GetExtents", &(COORD_SCREEN,)).ok()?;
+        (w > 0 && h > 0).then_some((x, y, w as u32, h as u32))
+    }
+}
+
+pub struct AtspiFinder;
+
+impl AtspiFinder {
+    fn connect() -> Result<Connection, BackendError> {
+        let session = Connection::session().map_err(bus_error)?;
+        let launcher = Proxy::new(&session, "org.a11y.Bus", "/org/a11y/bus", "org.a11y.Bus").map_err(bus_error)?;
+        let address: String = launcher.call("GetAddress", &()).map_err(|e| {
+            BackendError::new(
+                "atspi_unavailable",
+                format!("the accessibility bus is not running (enable assistive technologies): {}", e),
+            )
+        })?;
+        zbus::blocking::connection::Builder::address(address.as_str())
+            .and_then(|builder| builder.build())
+            .map_err(bus_error)
+    }
+}
+
+impl AtspiFinder {
+    /// Walk the tree and hand every visible match to `visit` until it returns false
+    fn search(
+        &self,
+        query: &ElementQuery,
+        mut visit: impl FnMut(&Accessible, UiElement) -> Result<bool, BackendError>,
+    ) -> Result<(), BackendError> {
+        query.validate().map_err(|e| BackendError::new("invalid_query", e))?;
+        let conn = Self::connect()?;
+        let root: Node = (
+            REGISTRY.into(),
+            OwnedObjectPath::try_from(ROOT_PATH).map_err(|e| BackendError::new("atspi_failed", e.to_string()))?,
+        );
+        let apps = Accessible::new(&conn, &root).and_then(|a| a.children()).map_err(bus_error)?;
+        let mut visited = 0;
+        for app_node in &apps {
+            let Ok(app) = Accessible::new(&conn, app_node) else {
+                continue;
+            };
+            let app_name = app.name().unwrap_or_default();
+            if !query.matches_app(&app_name) {
+                continue;
+            }
+            let mut stack: Vec<(Node, usize)> =
+                app.children().unwrap_or_default().into_iter().rev().map(|n| (n, 1)).collect();
+            while let Some((node, depth)) = stack.pop() {
+                visited += 1;
+                if visited > ELEMENT_SEARCH_MAX_NODES {
+                    return Ok(());
+                }
+                // Apps exit and widgets disappear while we walk; skip what is gone
+                let Ok(element) = Accessible::new(&conn, &node) else {
+                    continue;
+                };
+                if !element.showing() {
+                    continue;
+                }
+                let role = element.role().unwrap_or_default();
+                // Only fetch the name when the role already fits
+                if query.matches_role(&role) {
+                    let name = element.name().unwrap_or_default();
+                    if query.matches_name(&name) {
+                        if let Some((x, y, width, height)) = element.extents() {
+                            let text = element.text().unwrap_or_else(|| name.clone());
+                            let found = UiElement {
+                                role,
+                                name,
+                                app: app_name.clone(),
+                                text,
+                                x,
+                                y,
+                                width,
+                                height,
+                            };
+                            if !visit(&element, found)? {
+                                return Ok(());
+                            }
+                        }
+                    }
+                }
+                if depth < ELEMENT_SEARCH_MAX_DEPTH {
+                    let children = element.children().unwrap_or_default();
+                    stack.extend(children.into_iter().rev().map(|n| (n, depth + 1)));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ElementFinder for AtspiFinder {
+    fn find(&self, query: &ElementQuery) -> Result<Vec<UiElement>, BackendError> {
+        let mut found = Vec::new();
+        self.search(query, |_, element| {
+            found.push(element);
+            Ok(true)
+        })?;
+        Ok(found)
+    }
+
+    fn invoke(&self, query: &ElementQuery) -> Result<UiElement, BackendError> {
+        let mut invoked = None;
+        self.search(query, |accessible, element| {
+            match accessible.do_default_action() {
+                Ok(true) => invoked = Some(element),
+                Ok(false) => {
+                    return Err(BackendError::new("atspi_action_failed", format!("{} refused its default action", query)))
+                }
+                Err(e) => {
+                    return Err(BackendError::new("atspi_no_action", format!("{} has no default action: {}", query, e)))
+                }
+            }
+            Ok(false)
+        })?;
+        invoked.ok_or_else(|| BackendError::new("element_not_found", format!("no {} found", query)))
+    }
+}
@@ -0,0 +1,137 @@
+/// Hash of every `downscale`-th RGBA pixel of `buf`, for change detection
+///
+/// Region conditions hash every watched region on every check, so at 10 Hz a
+/// 4K region means hashing 33 MB ten times a second. The pixels are read as
+/// 64-bit words spread over four independent lanes, which stay in registers
+/// and leave the compiler free to vectorize, and the lanes are folded once at
+/// the end. When `downscale` skips pixels, the sampled ones are gathered into
+/// a chunk first, so no path pays a call per pixel. The size and `downscale`
+/// are hashed too.
+///
+/// The hash tells frames apart within one process; it is not stable across
+/// versions and not meant to resist deliberate collisions.
+pub fn hash_rgba(buf: &[u8], width: u32, height: u32, downscale: u32) -> u64 {
+    let step = downscale.max(1) as usize;
+    let mut lanes = Lanes::new(mix(mix(mix(PRIME, width as u64), height as u64), step as u64));
+    // A trailing partial pixel is ignored
+    let pixels = &buf[..buf.len() / 4 * 4];
+    if step == 1 {
+        lanes.update(pixels);
+    } else {
+        let mut chunk = [0u8; CHUNK_PIXELS * 4];
+        let mut filled = 0;
+        for pixel in pixels.chunks_exact(4).step_by(step) {
+            chunk[filled..filled + 4].copy_from_slice(pixel);
+            filled += 4;
+            if filled == chunk.len() {
+                lanes.update(&chunk);
+                filled = 0;
+            }
+        }
+        lanes.update(&chunk[..filled]);
+    }
+    lanes.finish()
+}
+
+const PRIME: u64 = 0x9E37_79B9_7F4A_7C15;
+const LANES: usize = 4;
+/// Bytes consumed per round, one word per lane
+const BLOCK: usize = LANES * 8;
+/// Sampled pixels gathered before they are hashed; a multiple of `BLOCK` bytes
+const CHUNK_PIXELS: usize = 1024;
+
+fn mix(acc: u64, word: u64) -> u64 {
+    (acc ^ word).wrapping_mul(PRIME).rotate_left(29)
+}
+
+struct Lanes {
+    acc: [u64; LANES],
+    len: u64,
+}
+
+impl Lanes {
+    fn new(seed: u64) -> Self {
+        Self {
+            acc: [seed, seed ^ PRIME, seed.rotate_left(17), seed.rotate_left(41) ^ PRIME],
+            len: 0,
+        }
+    }
+
+    fn block(&mut self, block: &[u8]) {
+        for (lane, word) in self.acc.iter_mut().zip(block.chunks_exact(8)) {
+            *lane = mix(*lane, u64::from_le_bytes(word.try_into().unwrap()));
+        }
+    }
+
+    /// Feed `bytes`; every call but the last must pass a multiple of `BLOCK` bytes
+    fn update(&mut self, bytes: &[u8]) {
+        let mut blocks = bytes.chunks_exact(BLOCK);
+        for block in &mut blocks {
+            self.block(block);
+        }
+        let rest = blocks.remainder();
+        if !rest.is_empty() {
+            // The length folded in at the end tells the zero padding from zero pixels
+            let mut padded = [0u8; BLOCK];
+            padded[..rest.len()].copy_from_slice(rest);
+            self.block(&padded);
+        }
+        self.len += bytes.len() as u64;
+    }
+
+    fn finish(self) -> u64 {
+        let mut hash = self.acc.iter().fold(mix(PRIME, self.len), |h, lane| mix(h, *lane));
+        // Spread the last words' bits over the whole result
+        hash ^= hash >> 33;
+        hash = hash.wrapping_mul(0xFF51_AFD7_ED55_8CCD);
+        hash ^= hash >> 29;
+        hash
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hash_rgba, BLOCK, CHUNK_PIXELS};
+
+    fn pixels(count: usize) -> Vec<u8> {
+        (0..count * 4).map(|i| (i * 7 % 251) as u8).collect()
+    }
+
+    #[test]
+    fn any_changed_byte_changes_the_hash() {
+        // Not a whole number of blocks, so the padded tail is covered too
+        let frame = pixels(1001);
+        assert_ne!(frame.len() % BLOCK, 0);
+        let hash = hash_rgba(&frame, 7, 143, 1);
+        assert_eq!(hash, hash_rgba(&frame.clone(), 7, 143, 1));
+        for i in [0, 1, 31, 32, 2000, frame.len() - 1] {
+            let mut changed = frame.clone();
+            changed[i] ^= 1;
+            assert_ne!(hash_rgba(&changed, 7, 143, 1), hash, "byte {} was missed", i);
+        }
+    }
+
+    #[test]
+    fn size_and_downscale_are_part_of_the_hash() {
+        let frame = vec![0u8; 64];
+        assert_ne!(hash_rgba(&frame, 4, 4, 1), hash_rgba(&frame, 8, 2, 1));
+        assert_ne!(hash_rgba(&frame, 4, 4, 1), hash_rgba(&frame, 4, 4, 4));
+        assert_ne!(hash_rgba(&frame, 4, 4, 1), hash_rgba(&frame[..60], 4, 4, 1), "zero pixels differ from padding");
+        assert_eq!(hash_rgba(&frame, 4, 4, 0), hash_rgba(&frame, 4, 4, 1));
+    }
+
+    #[test]
+    fn downscale_only_reads_sampled_pixels() {
+        // Spans several chunks of sampled pixels
+        let frame = pixels(CHUNK_PIXELS * 3 * 3 + 5);
+        let hash = hash_rgba(&frame, 1, 1, 3);
+        let mut skipped = frame.clone();
+        skipped[(CHUNK_PIXELS * 3 + 1) * 4] ^= 1;
+        assert_eq!(hash_rgba(&skipped, 1, 1, 3), hash);
+        for pixel in [0, CHUNK_PIXELS * 3, CHUNK_PIXELS * 9 + 3] {
+            let mut sampled = frame.clone();
+            sampled[pixel * 4 + 2] ^= 1;
+            assert_ne!(hash_rgba(&sampled, 1, 1, 3), hash, "pixel {} was missed", pixel);
+        }
+    }
+}
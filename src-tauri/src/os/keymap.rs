@@ -0,0 +1,301 @@
+/// Character to key mapping for the active keyboard layout
+///
+/// XTest and ydotool inject key codes, not characters, so typing `@` means
+/// knowing which key and modifiers produce it on the user's layout: Shift+2 on
+/// US, AltGr+Q on German, AltGr+0 on French AZERTY. `LayoutMap` is built from
+/// an xkbcommon keymap (the live server keymap on X11, the configured layout
+/// on Wayland) and keeps the cheapest way to reach every keysym of the first
+/// layout group. Characters the layout only reaches through a dead key (`ê`
+/// on US International, `à` on German) are typed as dead key + base letter.
+use std::collections::HashMap;
+use std::process::Command;
+
+use xkbcommon::xkb::{self, Keycode};
+
+/// `XK_ISO_Level3_Shift`, the AltGr key
+pub const KEYSYM_LEVEL3_SHIFT: u32 = 0xfe03;
+/// `XK_Shift_L`
+pub const KEYSYM_SHIFT_L: u32 = 0xffe1;
+
+/// A key code and the modifiers to hold while pressing it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyStroke {
+    pub keycode: u32,
+    pub shift: bool,
+    /// AltGr (ISO_Level3_Shift)
+    pub level3: bool,
+}
+
+impl KeyStroke {
+    fn cost(&self) -> u8 {
+        self.shift as u8 + 2 * self.level3 as u8
+    }
+}
+
+/// Modifier bits of a keymap
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ModifierMasks {
+    pub shift: u32,
+    pub level3: u32,
+}
+
+#[derive(Debug, Default)]
+pub struct LayoutMap {
+    keys: HashMap<u32, KeyStroke>,
+    spare: Vec<u32>,
+}
+
+impl LayoutMap {
+    /// Record that `keysym` comes from `keycode` with `mods` held. Levels that
+    /// need other modifiers (Ctrl, Lock, NumLock) are skipped: holding them
+    /// would turn the character into a shortcut.
+    pub fn add(&mut self, keysym: u32, keycode: u32, mods: u32, masks: ModifierMasks) {
+        if mods & !(masks.shift | masks.level3) != 0 {
+            return;
+        }
+        let stroke = KeyStroke {
+            keycode,
+            shift: mods & masks.shift != 0,
+            level3: mods & masks.level3 != 0,
+        };
+        match self.keys.get(&keysym) {
+            Some(existing) if existing.cost() <= stroke.cost() => {}
+            _ => {
+                self.keys.insert(keysym, stroke);
+            }
+        }
+    }
+
+    /// Remember a key code without symbols, free for temporary remapping
+    pub fn add_spare(&mut self, keycode: u32) {
+        self.spare.push(keycode);
+    }
+
+    /// The highest unused key code; low codes are the ones keyboards send
+    pub fn spare(&self) -> Option<u32> {
+        self.spare.iter().copied().max()
+    }
+
+    pub fn get(&self, keysym: u32) -> Option<KeyStroke> {
+        self.keys.get(&keysym).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Strokes typing `ch`: its own key, else a dead key followed by the base letter
+    pub fn strokes(&self, ch: char) -> Option<Vec<KeyStroke>> {
+        if let Some(stroke) = self.get(char_keysym(ch)) {
+            return Some(vec![stroke]);
+        }
+        let (dead, base) = dead_key_for(ch)?;
+        Some(vec![self.get(dead)?, self.get(char_keysym(base))?])
+    }
+
+    /// Every keysym of the first layout group of `keymap`
+    pub fn from_xkb(keymap: &xkb::Keymap) -> Self {
+        let mod_mask = |name: &str| match keymap.mod_get_index(name) {
+            xkb::MOD_INVALID => 0,
+            index => 1 << index,
+        };
+        // AltGr sets the LevelThree virtual modifier, which keymaps bind to Mod5
+        let masks = ModifierMasks {
+            shift: mod_mask(xkb::MOD_NAME_SHIFT),
+            level3: mod_mask("Mod5") | mod_mask("LevelThree"),
+        };
+        let mut map = Self::default();
+        let mut level_masks = [0u32; 16];
+        for raw in keymap.min_keycode().raw()..=keymap.max_keycode().raw() {
+            let keycode = Keycode::new(raw);
+            let levels = if keymap.num_layouts_for_key(keycode) > 0 {
+                keymap.num_levels_for_key(keycode, 0)
+            } else {
+                0
+            };
+            let mut has_symbols = false;
+            for level in 0..levels {
+                let syms = keymap.key_get_syms_by_level(keycode, 0, level);
+                if syms.is_empty() {
+                    continue;
+                }
+                has_symbols = true;
+                let count = keymap.key_get_mods_for_level(keycode, 0, level, &mut level_masks);
+                for mods in &level_masks[..count.min(level_masks.len())] {
+                    for sym in syms {
+                        map.add(sym.raw(), raw, *mods, masks);
+                    }
+                }
+            }
+            // X11 key codes are 8..=255
+            if !has_symbols && raw <= 255 {
+                map.add_spare(raw);
+            }
+        }
+        map
+    }
+}
+
+/// Keysym of a character, legacy keysyms (`aogonek`) before Unicode ones
+pub fn char_keysym(ch: char) -> u32 {
+    xkb::utf32_to_keysym(ch as u32).raw()
+}
+
+/// Dead key and base letter composing `ch`, e.g. `ê` is dead_circumflex + `e`
+pub fn dead_key_for(ch: char) -> Option<(u32, char)> {
+    const DEAD_KEYS: [(u32, &str, &str); 6] = [
+        (0xfe50, "àèìòùÀÈÌÒÙ", "aeiouAEIOU"),
+        (0xfe51, "áéíóúýÁÉÍÓÚÝ", "aeiouyAEIOUY"),
+        (0xfe52, "âêîôûÂÊÎÔÛ", "aeiouAEIOU"),
+        (0xfe53, "ãñõÃÑÕ", "anoANO"),
+        (0xfe57, "äëïöüÿÄËÏÖÜ", "aeiouyAEIOU"),
+        (0xfe5b, "çÇ", "cC"),
+    ];
+    DEAD_KEYS.iter().find_map(|(dead, composed, bases)| {
+        let index = composed.chars().position(|c| c == ch)?;
+        bases.chars().nth(index).map(|base| (*dead, base))
+    })
+}
+
+/// XKB layout and variant, e.g. `de` + `nodeadkeys`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayoutName {
+    pub layout: String,
+    pub variant: String,
+}
+
+impl LayoutName {
+    fn new(layout: &str, variant: &str) -> Option<Self> {
+        let layout = layout.trim();
+        (!layout.is_empty()).then(|| Self {
+            layout: layout.into(),
+            variant: variant.trim().into(),
+        })
+    }
+}
+
+/// First XKB source of `gsettings get org.gnome.desktop.input-sources sources`,
+/// e.g. `[('xkb', 'de+nodeadkeys'), ('ibus', 'mozc')]`
+pub fn parse_gnome_sources(output: &str) -> Option<LayoutName> {
+    let start = output.find("('xkb', '")? + "('xkb', '".len();
+    let source = &output[start..];
+    let source = &source[..source.find('\'')?];
+    let (layout, variant) = source.split_once('+').unwrap_or((source, ""));
+    LayoutName::new(layout, variant)
+}
+
+/// First entries of `LayoutList` and `VariantList` in KDE's `kxkbrc`
+pub fn parse_kxkbrc(contents: &str) -> Option<LayoutName> {
+    let value = |key: &str| {
+        contents
+            .lines()
+            .find_map(|line| line.trim().strip_prefix(key)?.strip_prefix('='))
+            .and_then(|list| list.split(',').next())
+            .unwrap_or("")
+    };
+    LayoutName::new(value("LayoutList"), value("VariantList"))
+}
+
+/// First entries of `XKBLAYOUT` and `XKBVARIANT` in `/etc/default/keyboard`
+pub fn parse_default_keyboard(contents: &str) -> Option<LayoutName> {
+    let value = |key: &str| {
+        contents
+            .lines()
+            .find_map(|line| line.trim().strip_prefix(key)?.strip_prefix('='))
+            .map(|v| v.trim().trim_matches('"'))
+            .and_then(|list| list.split(',').next())
+            .unwrap_or("")
+    };
+    LayoutName::new(value("XKBLAYOUT"), value("XKBVARIANT"))
+}
+
+/// The layout the Wayland compositor uses, which clients cannot query. Checks
+/// `XKB_DEFAULT_LAYOUT`, GNOME, KDE and the system default in that order.
+pub fn detect_layout() -> LayoutName {
+    if let Ok(layout) = std::env::var("XKB_DEFAULT_LAYOUT") {
+        let variant = std::env::var("XKB_DEFAULT_VARIANT").unwrap_or_default();
+        if let Some(name) = LayoutName::new(layout.split(',').next().unwrap_or(""), variant.split(',').next().unwrap_or(""))
+        {
+            return name;
+        }
+    }
+    let gnome = Command::new("gsettings")
+        .args(["get", "org.gnome.desktop.input-sources", "sources"])
+        .output()
+        .ok()
+        .and_then(|out| parse_gnome_sources(&String::from_utf8_lossy(&out.stdout)));
+    let kde = || {
+        let config = std::env::var_os("XDG_CONFIG_HOME")
+            .map(std::path::PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| std::path::Path::new(&home).join(".config")))?;
+        parse_kxkbrc(&std::fs::read_to_string(config.join("kxkbrc")).ok()?)
+    };
+    let system = || parse_default_keyboard(&std::fs::read_to_string("/etc/default/keyboard").ok()?);
+    gnome.or_else(kde).or_else(system).unwrap_or(LayoutName {
+        layout: "us".into(),
+        variant: String::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MASKS: ModifierMasks = ModifierMasks { shift: 1, level3: 0x80 };
+
+    #[test]
+    fn layout_map_keeps_the_cheapest_plain_modifier_stroke() {
+        let mut map = LayoutMap::default();
+        // German `q` key: q, Q, @ (AltGr)
+        map.add('q' as u32, 24, 0, MASKS);
+        map.add('Q' as u32, 24, 1, MASKS);
+        map.add('@' as u32, 24, 0x80, MASKS);
+        // `@` reachable a second way with Shift+AltGr is not preferred
+        map.add('@' as u32, 11, 0x81, MASKS);
+        // Ctrl (0x4) levels are never used
+        map.add('x' as u32, 53, 0x4, MASKS);
+        assert_eq!(map.get('Q' as u32), Some(KeyStroke { keycode: 24, shift: true, level3: false }));
+        assert_eq!(map.get('@' as u32), Some(KeyStroke { keycode: 24, shift: false, level3: true }));
+        assert_eq!(map.get('x' as u32), None);
+        map.add_spare(200);
+        map.add_spare(250);
+        assert_eq!(map.spare(), Some(250));
+    }
+
+    #[test]
+    fn accented_letters_fall_back_to_dead_keys() {
+        let mut map = LayoutMap::default();
+        map.add('e' as u32, 26, 0, MASKS);
+        map.add(0xfe52, 34, 0, MASKS);
+        map.add(0xe9, 19, 0, MASKS);
+        assert_eq!(map.strokes('é').unwrap().len(), 1);
+        let circumflex = map.strokes('ê').unwrap();
+        assert_eq!(circumflex.iter().map(|s| s.keycode).collect::<Vec<_>>(), vec![34, 26]);
+        assert!(map.strokes('ñ').is_none());
+        assert_eq!(dead_key_for('Ü'), Some((0xfe57, 'U')));
+        assert_eq!(dead_key_for('x'), None);
+    }
+
+    #[test]
+    fn layout_detection_parses_desktop_settings() {
+        assert_eq!(
+            parse_gnome_sources("[('xkb', 'de+nodeadkeys'), ('ibus', 'mozc')]"),
+            Some(LayoutName { layout: "de".into(), variant: "nodeadkeys".into() })
+        );
+        assert_eq!(parse_gnome_sources("[('ibus', 'mozc'), ('xkb', 'fr')]").unwrap().layout, "fr");
+        assert_eq!(parse_gnome_sources("@a(ss) []"), None);
+        assert_eq!(
+            parse_kxkbrc("[Layout]\nLayoutList=ch,us\nVariantList=fr,\nUse=true\n"),
+            Some(LayoutName { layout: "ch".into(), variant: "fr".into() })
+        );
+        assert_eq!(
+            parse_default_keyboard("XKBMODEL=\"pc105\"\nXKBLAYOUT=\"fr,us\"\nXKBVARIANT=\"azerty\"\n"),
+            Some(LayoutName { layout: "fr".into(), variant: "azerty".into() })
+        );
+        assert_eq!(parse_default_keyboard("XKBMODEL=\"pc105\"\n"), None);
+    }
+}
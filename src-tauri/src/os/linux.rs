@@ -1,13 +1,11 @@
 #[cfg(feature = "os-linux-automation")]
-use crate::domain::{Automation, MouseButton};
-use crate::domain::{BackendError, DisplayInfo, Region, ScreenCapture, ScreenFrame};
+use crate::domain::{Automation, Error, MouseButton};
+use crate::domain::{BackendError, DisplayInfo, Region, ScreenCapture, ScreenFrame, WindowEnumerator, WindowInfo};
 
-#[cfg(feature = "os-linux-capture-xcap")]
-use ahash::AHasher;
-#[cfg(feature = "os-linux-automation")]
+#[cfg(feature = "ocr-integration")]
 use std::collections::HashMap;
-#[cfg(feature = "os-linux-capture-xcap")]
-use std::hash::{Hash, Hasher};
+#[cfg(feature = "os-linux-automation")]
+use std::sync::atomic::{AtomicU32, Ordering};
 #[cfg(feature = "os-linux-automation")]
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
@@ -22,9 +20,14 @@ use x11rb::{
     CURRENT_TIME,
 };
 #[cfg(feature = "os-linux-capture-xcap")]
-use xcap::Monitor;
+use xcap::{Monitor, Window};
+#[cfg(feature = "os-linux-automation")]
+use xkbcommon::xkb::{self, Context, Keysym};
+
+#[cfg(feature = "os-linux-capture-xcap")]
+use super::hash::hash_rgba;
 #[cfg(feature = "os-linux-automation")]
-use xkbcommon::xkb::{self, Context, Keycode, Keysym, ModMask};
+use super::keymap::{self, KeyStroke, LayoutMap, ModifierMasks, KEYSYM_LEVEL3_SHIFT, KEYSYM_SHIFT_L};
 
 pub struct LinuxCapture;
 impl ScreenCapture for LinuxCapture {
@@ -41,16 +44,7 @@ impl ScreenCapture for LinuxCapture {
                         return 0;
                     }
                     if let Ok(img) = mon.capture_region(x, y, w, h) {
-                        let buf = img.as_raw();
-                        let mut hasher = AHasher::default();
-                        (w, h, downscale).hash(&mut hasher);
-                        let step = (downscale.max(1) as usize) * 4;
-                        let mut i = 0usize;
-                        while i + 4 <= buf.len() {
-                            hasher.write(&buf[i..i + 4]);
-                            i += step;
-                        }
-                        return hasher.finish();
+                        return hash_rgba(img.as_raw(), w, h, downscale);
                     }
                 }
             }
@@ -101,6 +95,13 @@ impl ScreenCapture for LinuxCapture {
         }
     }
 
+    #[cfg(feature = "os-linux-capture-xcap")]
+    fn capture_and_hash(&self, region: &Region, downscale: u32) -> Result<(ScreenFrame, u64), BackendError> {
+        let frame = self.capture_region(region)?;
+        let hash = hash_rgba(&frame.bytes, frame.width, frame.height, downscale);
+        Ok((frame, hash))
+    }
+
     fn displays(&self) -> Result<Vec<DisplayInfo>, BackendError> {
         #[cfg(feature = "os-linux-capture-xcap")]
         {
@@ -118,58 +119,96 @@ impl ScreenCapture for LinuxCapture {
     }
 }
 
+/// XTest input over one long-lived X connection
+///
+/// Clones share the connection and keymap. The connection is reopened when
+/// the X server drops it (server restart, display switch), so a handle stays
+/// usable for the life of the process.
 #[cfg(feature = "os-linux-automation")]
+#[derive(Clone)]
 pub struct LinuxAutomation {
     conn: Arc<Mutex<XCBConnection>>,
-    root: xproto::Window,
-    keyboard: KeyboardLookup,
+    root: Arc<AtomicU32>,
+    keyboard: Arc<KeyboardLookup>,
 }
 
 #[cfg(feature = "os-linux-automation")]
 impl LinuxAutomation {
     pub fn new() -> Result<Self, BackendError> {
-        eprintln!("[LinuxAutomation] Initializing X11 automation...");
-        eprintln!("[LinuxAutomation] DISPLAY={:?}", std::env::var("DISPLAY"));
+        tracing::debug!(display = ?std::env::var("DISPLAY"), "Initializing X11 automation");
         
         let (conn, screen_idx) = open_xcb_connection()?;
-        eprintln!("[LinuxAutomation] X11 connection established, screen_idx={}", screen_idx);
+        tracing::debug!(screen_idx, "X11 connection established");
         
-        let root = conn
-            .setup()
-            .roots
-            .get(screen_idx)
-            .ok_or_else(|| BackendError::new("x11_screen_missing", "unable to read X11 screen"))?
-            .root;
-        eprintln!("[LinuxAutomation] Root window ID: {}", root);
+        let root = screen_root(&conn, screen_idx)?;
+        tracing::debug!(root, "Root window found");
         
-        eprintln!("[LinuxAutomation] Initializing keyboard lookup...");
         let keyboard = KeyboardLookup::from_connection(&conn)?;
-        eprintln!("[LinuxAutomation] Keyboard lookup initialized successfully with {} key mappings", keyboard.entries.len());
+        tracing::debug!(keys = keyboard.layout.len(), "Keyboard lookup initialized");
         
-        eprintln!("[LinuxAutomation] ✓ Initialization complete!");
         Ok(Self {
             conn: Arc::new(Mutex::new(conn)),
-            root,
-            keyboard,
+            root: Arc::new(AtomicU32::new(root)),
+            keyboard: Arc::new(keyboard),
         })
     }
 
+    /// The process-wide handle: the first call connects and reads the keymap,
+    /// later calls (every run, every MCP request) reuse them
+    pub fn shared() -> Result<Self, BackendError> {
+        static SHARED: Mutex<Option<LinuxAutomation>> = Mutex::new(None);
+        let mut shared = SHARED.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(automation) = shared.as_ref() {
+            return Ok(automation.clone());
+        }
+        let automation = Self::new()?;
+        *shared = Some(automation.clone());
+        Ok(automation)
+    }
+
+    fn root(&self) -> xproto::Window {
+        self.root.load(Ordering::Relaxed)
+    }
+
+    /// Replace a connection the X server has closed
+    fn reconnect(&self, conn: &mut XCBConnection, cause: &x11rb::errors::ConnectionError) -> Result<(), String> {
+        tracing::warn!("X11 connection lost ({}), reconnecting", cause);
+        let (fresh, screen_idx) =
+            open_xcb_connection().map_err(|e| format!("X11 connection lost ({}) and reconnect failed: {}", cause, e.message))?;
+        let root = screen_root(&fresh, screen_idx).map_err(|e| e.message)?;
+        *conn = fresh;
+        self.root.store(root, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Run `f` on the connection, reconnecting first if it is broken and
+    /// retrying once if it broke during `f`
     fn with_conn<T>(
         &self,
-        f: impl FnOnce(&mut XCBConnection) -> Result<T, String>,
+        f: impl Fn(&mut XCBConnection) -> Result<T, String>,
     ) -> Result<T, String> {
         let mut guard = self
             .conn
             .lock()
             .map_err(|_| "x11 connection lock poisoned".to_string())?;
-        f(&mut guard)
+        if let Some(cause) = guard.has_error() {
+            self.reconnect(&mut guard, &cause)?;
+        }
+        match f(&mut guard) {
+            Err(_) if guard.has_error().is_some() => {
+                let cause = guard.has_error().expect("checked above");
+                self.reconnect(&mut guard, &cause)?;
+                f(&mut guard)
+            }
+            result => result,
+        }
     }
 
     fn send_motion(&self, x: u32, y: u32) -> Result<(), String> {
         let xi = self.keyboard.clamp_coord(x);
         let yi = self.keyboard.clamp_coord(y);
         
-        eprintln!("[Automation] Moving cursor to ({}, {})", xi, yi);
+        tracing::trace!("Moving cursor to ({}, {})", xi, yi);
         
         self.with_conn(|conn| {
             // CRITICAL: XTest fake MOTION_NOTIFY doesn't actually move the cursor!
@@ -177,7 +216,7 @@ impl LinuxAutomation {
             // This is what xdotool and other automation tools do
             conn.warp_pointer(
                 x11rb::NONE,  // src_window (None = relative to root)
-                self.root,     // dst_window (warp to root coordinates)
+                self.root(),   // dst_window (warp to root coordinates)
                 0, 0,          // src_x, src_y (ignored when src_window is None)
                 0, 0,          // src_width, src_height (ignored)
                 xi, yi,        // dst_x, dst_y (target position)
@@ -190,17 +229,17 @@ impl LinuxAutomation {
             std::thread::sleep(std::time::Duration::from_millis(10));
             
             // Query actual cursor position to verify
-            match conn.query_pointer(self.root) {
+            match conn.query_pointer(self.root()) {
                 Ok(reply) => {
                     let reply = reply.reply().map_err(|e| format!("query_pointer reply failed: {}", e))?;
-                    eprintln!("[Automation] Cursor now at ({}, {}), target was ({}, {})", 
+                    tracing::trace!("Cursor now at ({}, {}), target was ({}, {})",
                              reply.root_x, reply.root_y, xi, yi);
                     if (reply.root_x as i32 - xi as i32).abs() > 5 || (reply.root_y as i32 - yi as i32).abs() > 5 {
                         return Err(format!("Cursor warp failed: ended at ({}, {}) instead of ({}, {})", 
                                          reply.root_x, reply.root_y, xi, yi));
                     }
                 }
-                Err(e) => eprintln!("[Automation] Warning: Could not verify cursor position: {}", e),
+                Err(e) => tracing::warn!("Could not verify cursor position: {}", e),
             }
             
             Ok(())
@@ -220,7 +259,7 @@ impl LinuxAutomation {
             MouseButton::Right => "Right",
         };
         
-        eprintln!("[Automation] Mouse {} button {}", button_name, if press { "DOWN" } else { "UP" });
+        tracing::trace!("Mouse {} button {}", button_name, if press { "DOWN" } else { "UP" });
         
         self.with_conn(|conn| {
             conn.xtest_fake_input(
@@ -231,7 +270,7 @@ impl LinuxAutomation {
                 },
                 detail,
                 CURRENT_TIME,
-                self.root,
+                self.root(),
                 0,
                 0,
                 0,
@@ -249,7 +288,7 @@ impl LinuxAutomation {
     }
 
     fn send_keycode(&self, keycode: u8, press: bool) -> Result<(), String> {
-        eprintln!("[Automation] Key {} keycode={}", if press { "DOWN" } else { "UP" }, keycode);
+        tracing::trace!("Key {} keycode={}", if press { "DOWN" } else { "UP" }, keycode);
         
         self.with_conn(|conn| {
             conn.xtest_fake_input(
@@ -260,7 +299,7 @@ impl LinuxAutomation {
                 },
                 keycode,
                 CURRENT_TIME,
-                self.root,
+                self.root(),
                 0,
                 0,
                 0,
@@ -278,28 +317,69 @@ impl LinuxAutomation {
     }
 
     fn send_keysym(&self, keysym: Keysym) -> Result<(), String> {
-        if let Some(entry) = self.keyboard.entries.get(&keysym.raw()) {
-            self.send_with_mods(entry)
-        } else {
-            Err(format!("keysym {:x} not mapped", keysym.raw()))
+        match self.keyboard.layout.get(keysym.raw()) {
+            Some(stroke) => self.send_stroke(stroke),
+            None => self.send_remapped(keysym.raw()),
         }
     }
 
-    fn send_with_mods(&self, entry: &KeyEntry) -> Result<(), String> {
-        let use_shift = entry.mods & self.keyboard.shift_mask != 0;
-        if use_shift {
-            if let Some(shift_keycode) = self.keyboard.shift_keycode {
-                self.send_keycode(shift_keycode, true)?;
-            }
+    /// Type a character with the keys the active layout uses for it
+    fn send_char(&self, ch: char) -> Result<(), String> {
+        match self.keyboard.layout.strokes(ch) {
+            Some(strokes) => strokes.into_iter().try_for_each(|stroke| self.send_stroke(stroke)),
+            None => self.send_remapped(keymap::char_keysym(ch)),
+        }
+    }
+
+    fn send_stroke(&self, stroke: KeyStroke) -> Result<(), String> {
+        self.press_stroke(stroke, true)?;
+        self.press_stroke(stroke, false)
+    }
+
+    /// Press the key with its modifiers held, or release it and then the modifiers
+    fn press_stroke(&self, stroke: KeyStroke, press: bool) -> Result<(), String> {
+        let mut modifiers = Vec::new();
+        if stroke.shift {
+            modifiers.push(self.keyboard.shift_keycode.ok_or("the keyboard layout has no Shift key")?);
+        }
+        if stroke.level3 {
+            modifiers.push(self.keyboard.level3_keycode.ok_or("the keyboard layout has no AltGr key")?);
         }
-        self.send_keycode(entry.keycode, true)?;
-        self.send_keycode(entry.keycode, false)?;
-        if use_shift {
-            if let Some(shift_keycode) = self.keyboard.shift_keycode {
-                self.send_keycode(shift_keycode, false)?;
+        if press {
+            for modifier in &modifiers {
+                self.send_keycode(*modifier, true)?;
+            }
+            self.send_keycode(stroke.keycode as u8, true)
+        } else {
+            self.send_keycode(stroke.keycode as u8, false)?;
+            for modifier in modifiers.iter().rev() {
+                self.send_keycode(*modifier, false)?;
             }
+            Ok(())
         }
-        Ok(())
+    }
+
+    /// Type a keysym the layout has no key for by binding it to a spare key code for one press
+    fn send_remapped(&self, keysym: u32) -> Result<(), String> {
+        let spare = self
+            .keyboard
+            .layout
+            .spare()
+            .ok_or_else(|| format!("keysym {:x} not mapped and no spare key code to remap", keysym))? as u8;
+        let bind = |sym: u32| {
+            self.with_conn(|conn| {
+                conn.change_keyboard_mapping(1, spare, 1, &[sym])
+                    .map_err(|e| format!("change_keyboard_mapping failed: {}", e))?;
+                conn.flush().map_err(|e| format!("flush failed: {}", e))
+            })
+        };
+        bind(keysym)?;
+        // Clients reload the mapping on MappingNotify; let that happen before the key arrives
+        std::thread::sleep(Duration::from_millis(20));
+        let typed = self.send_keycode(spare, true).and_then(|_| self.send_keycode(spare, false));
+        // NoSymbol
+        bind(0)?;
+        typed
     }
 
     fn key_from_str(&self, key: &str) -> Option<Keysym> {
@@ -316,21 +396,34 @@ impl LinuxAutomation {
             _ => None,
         }
     }
+
+    /// Key and modifiers to hold for `key`; held keys cannot use dead keys or remapping
+    fn held_stroke(&self, key: &str) -> Result<KeyStroke, Error> {
+        let keysym = self.key_from_str(key).ok_or_else(|| Error::Config(unsupported_key(key)))?;
+        self.keyboard.layout.get(keysym.raw()).ok_or_else(|| {
+            Error::Config(format!("keysym {:x} has no key on the active keyboard layout", keysym.raw()))
+        })
+    }
+}
+
+#[cfg(feature = "os-linux-automation")]
+fn unsupported_key(key: &str) -> String {
+    format!("unsupported key '{}': use Enter, Escape, Tab, Space, Backspace, or single characters", key)
 }
 
 #[cfg(feature = "os-linux-automation")]
 impl Automation for LinuxAutomation {
-    fn move_cursor(&self, x: u32, y: u32) -> Result<(), String> {
-        self.send_motion(x, y)
+    fn move_cursor(&self, x: u32, y: u32) -> Result<(), Error> {
+        self.send_motion(x, y).map_err(Error::Automation)
     }
 
-    fn click(&self, button: MouseButton) -> Result<(), String> {
+    fn click(&self, button: MouseButton) -> Result<(), Error> {
         self.mouse_down(button)?;
         self.mouse_up(button)
     }
 
-    fn type_text(&self, text: &str) -> Result<(), String> {
-        eprintln!("[Automation] Typing text: {:?} ({} chars)", text, text.len());
+    fn type_text(&self, text: &str) -> Result<(), Error> {
+        tracing::trace!(chars = text.chars().count(), "Typing text");
         
         let mut i = 0;
         let chars: Vec<char> = text.chars().collect();
@@ -341,7 +434,7 @@ impl Automation for LinuxAutomation {
             if chars[i] == '[' {
                 if let Some(end_pos) = text[i..].find(']') {
                     let key_name = &text[i+1..i+end_pos];
-                    eprintln!("[Automation] Pressing special key: [{}]", key_name);
+                    tracing::trace!("Pressing special key [{}]", key_name);
                     // Send the special key
                     self.key(key_name)?;
                     i += end_pos + 1;
@@ -351,12 +444,9 @@ impl Automation for LinuxAutomation {
             
             // Regular character
             if chars[i] == '\n' {
-                eprintln!("[Automation] Pressing Enter key");
                 self.key("Enter")?;
             } else {
-                let keysym = xkb::utf32_to_keysym(chars[i] as u32);
-                eprintln!("[Automation] Typing char '{}' (keysym={:x})", chars[i], keysym.raw());
-                self.send_keysym(keysym)?;
+                self.send_char(chars[i]).map_err(Error::Automation)?;
                 char_count += 1;
             }
             i += 1;
@@ -367,49 +457,69 @@ impl Automation for LinuxAutomation {
             }
         }
         
-        eprintln!("[Automation] Finished typing {} characters", char_count);
+        tracing::trace!("Finished typing {} characters", char_count);
         Ok(())
     }
 
-    fn key(&self, key: &str) -> Result<(), String> {
-        let keysym = self.key_from_str(key).ok_or_else(|| format!("unsupported key '{}': use Enter, Escape, Tab, Space, Backspace, or single characters", key))?;
-        self.send_keysym(keysym)
+    fn key(&self, key: &str) -> Result<(), Error> {
+        let keysym = self.key_from_str(key).ok_or_else(|| Error::Config(unsupported_key(key)))?;
+        self.send_keysym(keysym).map_err(Error::Automation)
     }
 
-    fn mouse_down(&self, button: MouseButton) -> Result<(), String> {
-        self.send_button(button, true)
+    fn mouse_down(&self, button: MouseButton) -> Result<(), Error> {
+        self.send_button(button, true).map_err(Error::Automation)
     }
 
-    fn mouse_up(&self, button: MouseButton) -> Result<(), String> {
-        self.send_button(button, false)
+    fn mouse_up(&self, button: MouseButton) -> Result<(), Error> {
+        self.send_button(button, false).map_err(Error::Automation)
     }
 
-    fn key_down(&self, key: &str) -> Result<(), String> {
-        let keysym = self.key_from_str(key).ok_or_else(|| format!("unsupported key '{}': use Enter, Escape, Tab, Space, Backspace, or single characters", key))?;
-        if let Some(entry) = self.keyboard.entries.get(&keysym.raw()) {
-            if entry.mods & self.keyboard.shift_mask != 0 {
-                if let Some(shift_keycode) = self.keyboard.shift_keycode {
-                    self.send_keycode(shift_keycode, true)?;
-                }
-            }
-            self.send_keycode(entry.keycode, true)
-        } else {
-            Err(format!("keysym {:x} not mapped", keysym.raw()))
-        }
+    fn key_down(&self, key: &str) -> Result<(), Error> {
+        let stroke = self.held_stroke(key)?;
+        self.press_stroke(stroke, true).map_err(Error::Automation)
     }
 
-    fn key_up(&self, key: &str) -> Result<(), String> {
-        let keysym = self.key_from_str(key).ok_or_else(|| format!("unsupported key '{}': use Enter, Escape, Tab, Space, Backspace, or single characters", key))?;
-        if let Some(entry) = self.keyboard.entries.get(&keysym.raw()) {
-            self.send_keycode(entry.keycode, false)?;
-            if entry.mods & self.keyboard.shift_mask != 0 {
-                if let Some(shift_keycode) = self.keyboard.shift_keycode {
-                    self.send_keycode(shift_keycode, false)?;
-                }
-            }
-            Ok(())
-        } else {
-            Err(format!("keysym {:x} not mapped", keysym.raw()))
+    fn key_up(&self, key: &str) -> Result<(), Error> {
+        let stroke = self.held_stroke(key)?;
+        self.press_stroke(stroke, false).map_err(Error::Automation)
+    }
+
+    fn cursor_position(&self) -> Result<Option<(i32, i32)>, Error> {
+        self.with_conn(|conn| {
+            let reply = conn
+                .query_pointer(self.root())
+                .map_err(|e| format!("query_pointer failed: {}", e))?
+                .reply()
+                .map_err(|e| format!("query_pointer reply failed: {}", e))?;
+            Ok(Some((reply.root_x as i32, reply.root_y as i32)))
+        })
+        .map_err(Error::Automation)
+    }
+}
+
+impl WindowEnumerator for LinuxCapture {
+    fn windows(&self) -> Result<Vec<WindowInfo>, BackendError> {
+        #[cfg(feature = "os-linux-capture-xcap")]
+        {
+            let windows = Window::all().map_err(|e| BackendError::new("linux_windows_failed", e.to_string()))?;
+            Ok(windows
+                .iter()
+                .map(|w| WindowInfo {
+                    id: w.id().map(u64::from).unwrap_or(0),
+                    title: w.title().unwrap_or_default(),
+                    process_name: w.app_name().unwrap_or_default(),
+                    pid: w.pid().unwrap_or(0),
+                    x: w.x().unwrap_or(0),
+                    y: w.y().unwrap_or(0),
+                    width: w.width().unwrap_or(0),
+                    height: w.height().unwrap_or(0),
+                    minimized: w.is_minimized().unwrap_or(false),
+                })
+                .collect())
+        }
+        #[cfg(not(feature = "os-linux-capture-xcap"))]
+        {
+            Err(BackendError::new("linux_windows_unavailable", "window listing requires os-linux-capture-xcap"))
         }
     }
 }
@@ -455,17 +565,11 @@ fn find_monitor<'a>(monitors: &'a [Monitor], region: &Region) -> Option<&'a Moni
 
 
 
-#[cfg(feature = "os-linux-automation")]
-struct KeyEntry {
-    keycode: u8,
-    mods: ModMask,
-}
-
 #[cfg(feature = "os-linux-automation")]
 struct KeyboardLookup {
-    entries: HashMap<u32, KeyEntry>,
-    shift_mask: ModMask,
+    layout: LayoutMap,
     shift_keycode: Option<u8>,
+    level3_keycode: Option<u8>,
 }
 
 #[cfg(feature = "os-linux-automation")]
@@ -474,12 +578,11 @@ impl KeyboardLookup {
         // Try XKB first, but fall back to static keymap if it fails
         match Self::from_xkb(conn) {
             Ok(lookup) => {
-                eprintln!("[XKB] Using live XKB keymap from X11");
+                tracing::debug!("Using live XKB keymap from X11");
                 Ok(lookup)
             }
             Err(e) => {
-                eprintln!("[XKB] Failed to get live keymap: {}", e);
-                eprintln!("[XKB] Falling back to static US QWERTY keymap");
+                tracing::warn!("Failed to get live keymap, falling back to static US QWERTY: {}", e);
                 Ok(Self::static_us_qwerty())
             }
         }
@@ -494,142 +597,54 @@ impl KeyboardLookup {
             device_id,
             xkb::KEYMAP_COMPILE_NO_FLAGS,
         );
-        let mut entries = HashMap::new();
-        let mut masks = [0u32; 16];
-        let min = keymap.min_keycode().raw();
-        let max = keymap.max_keycode().raw();
-        for raw_code in min..=max {
-            let keycode = Keycode::new(raw_code);
-            let layouts = keymap.num_layouts_for_key(keycode);
-            for layout in 0..layouts {
-                let levels = keymap.num_levels_for_key(keycode, layout);
-                for level in 0..levels {
-                    let syms = keymap.key_get_syms_by_level(keycode, layout, level);
-                    if syms.is_empty() {
-                        continue;
-                    }
-                    let mut mask_value = 0;
-                    let count = keymap.key_get_mods_for_level(keycode, layout, level, &mut masks);
-                    if count > 0 {
-                        mask_value = masks[0];
-                    }
-                    for sym in syms {
-                        entries.entry(sym.raw()).or_insert(KeyEntry {
-                            keycode: keycode.raw() as u8,
-                            mods: mask_value,
-                        });
-                    }
-                }
-            }
-        }
-        let shift_index = keymap.mod_get_index(xkb::MOD_NAME_SHIFT);
-        let shift_mask = if shift_index == xkb::MOD_INVALID {
-            0
-        } else {
-            1 << shift_index
-        };
-        let shift_keycode = entries
-            .get(&xkb::keysyms::KEY_Shift_L)
-            .map(|entry| entry.keycode)
-            .or_else(|| {
-                entries
-                    .get(&xkb::keysyms::KEY_Shift_R)
-                    .map(|entry| entry.keycode)
-            });
-        Ok(Self {
-            entries,
-            shift_mask,
+        Ok(Self::from_layout(LayoutMap::from_xkb(&keymap)))
+    }
+
+    fn from_layout(layout: LayoutMap) -> Self {
+        let keycode = |keysym: u32| layout.get(keysym).map(|stroke| stroke.keycode as u8);
+        let shift_keycode = keycode(KEYSYM_SHIFT_L).or_else(|| keycode(xkb::keysyms::KEY_Shift_R));
+        let level3_keycode = keycode(KEYSYM_LEVEL3_SHIFT);
+        Self {
+            layout,
             shift_keycode,
-        })
+            level3_keycode,
+        }
     }
     
     /// Fallback static keymap for US QWERTY layout
     /// Based on standard X11 keycodes (evdev offset +8)
     fn static_us_qwerty() -> Self {
         use xkb::keysyms::*;
-        let mut entries = HashMap::new();
-        
-        // Special keys (no shift)
-        entries.insert(KEY_Return.into(), KeyEntry { keycode: 36, mods: 0 });
-        entries.insert(KEY_Escape.into(), KeyEntry { keycode: 9, mods: 0 });
-        entries.insert(KEY_Tab.into(), KeyEntry { keycode: 23, mods: 0 });
-        entries.insert(KEY_space.into(), KeyEntry { keycode: 65, mods: 0 });
-        entries.insert(KEY_BackSpace.into(), KeyEntry { keycode: 22, mods: 0 });
-        
-        // Lowercase letters (no shift)
-        entries.insert(KEY_a.into(), KeyEntry { keycode: 38, mods: 0 });
-        entries.insert(KEY_b.into(), KeyEntry { keycode: 56, mods: 0 });
-        entries.insert(KEY_c.into(), KeyEntry { keycode: 54, mods: 0 });
-        entries.insert(KEY_d.into(), KeyEntry { keycode: 40, mods: 0 });
-        entries.insert(KEY_e.into(), KeyEntry { keycode: 26, mods: 0 });
-        entries.insert(KEY_f.into(), KeyEntry { keycode: 41, mods: 0 });
-        entries.insert(KEY_g.into(), KeyEntry { keycode: 42, mods: 0 });
-        entries.insert(KEY_h.into(), KeyEntry { keycode: 43, mods: 0 });
-        entries.insert(KEY_i.into(), KeyEntry { keycode: 31, mods: 0 });
-        entries.insert(KEY_j.into(), KeyEntry { keycode: 44, mods: 0 });
-        entries.insert(KEY_k.into(), KeyEntry { keycode: 45, mods: 0 });
-        entries.insert(KEY_l.into(), KeyEntry { keycode: 46, mods: 0 });
-        entries.insert(KEY_m.into(), KeyEntry { keycode: 58, mods: 0 });
-        entries.insert(KEY_n.into(), KeyEntry { keycode: 57, mods: 0 });
-        entries.insert(KEY_o.into(), KeyEntry { keycode: 32, mods: 0 });
-        entries.insert(KEY_p.into(), KeyEntry { keycode: 33, mods: 0 });
-        entries.insert(KEY_q.into(), KeyEntry { keycode: 24, mods: 0 });
-        entries.insert(KEY_r.into(), KeyEntry { keycode: 27, mods: 0 });
-        entries.insert(KEY_s.into(), KeyEntry { keycode: 39, mods: 0 });
-        entries.insert(KEY_t.into(), KeyEntry { keycode: 28, mods: 0 });
-        entries.insert(KEY_u.into(), KeyEntry { keycode: 30, mods: 0 });
-        entries.insert(KEY_v.into(), KeyEntry { keycode: 55, mods: 0 });
-        entries.insert(KEY_w.into(), KeyEntry { keycode: 25, mods: 0 });
-        entries.insert(KEY_x.into(), KeyEntry { keycode: 53, mods: 0 });
-        entries.insert(KEY_y.into(), KeyEntry { keycode: 29, mods: 0 });
-        entries.insert(KEY_z.into(), KeyEntry { keycode: 52, mods: 0 });
-        
-        // Uppercase letters (with shift bit set - bit 0)
-        let shift_mask = 1;
-        entries.insert(KEY_A.into(), KeyEntry { keycode: 38, mods: shift_mask });
-        entries.insert(KEY_B.into(), KeyEntry { keycode: 56, mods: shift_mask });
-        entries.insert(KEY_C.into(), KeyEntry { keycode: 54, mods: shift_mask });
-        entries.insert(KEY_D.into(), KeyEntry { keycode: 40, mods: shift_mask });
-        entries.insert(KEY_E.into(), KeyEntry { keycode: 26, mods: shift_mask });
-        entries.insert(KEY_F.into(), KeyEntry { keycode: 41, mods: shift_mask });
-        entries.insert(KEY_G.into(), KeyEntry { keycode: 42, mods: shift_mask });
-        entries.insert(KEY_H.into(), KeyEntry { keycode: 43, mods: shift_mask });
-        entries.insert(KEY_I.into(), KeyEntry { keycode: 31, mods: shift_mask });
-        entries.insert(KEY_J.into(), KeyEntry { keycode: 44, mods: shift_mask });
-        entries.insert(KEY_K.into(), KeyEntry { keycode: 45, mods: shift_mask });
-        entries.insert(KEY_L.into(), KeyEntry { keycode: 46, mods: shift_mask });
-        entries.insert(KEY_M.into(), KeyEntry { keycode: 58, mods: shift_mask });
-        entries.insert(KEY_N.into(), KeyEntry { keycode: 57, mods: shift_mask });
-        entries.insert(KEY_O.into(), KeyEntry { keycode: 32, mods: shift_mask });
-        entries.insert(KEY_P.into(), KeyEntry { keycode: 33, mods: shift_mask });
-        entries.insert(KEY_Q.into(), KeyEntry { keycode: 24, mods: shift_mask });
-        entries.insert(KEY_R.into(), KeyEntry { keycode: 27, mods: shift_mask });
-        entries.insert(KEY_S.into(), KeyEntry { keycode: 39, mods: shift_mask });
-        entries.insert(KEY_T.into(), KeyEntry { keycode: 28, mods: shift_mask });
-        entries.insert(KEY_U.into(), KeyEntry { keycode: 30, mods: shift_mask });
-        entries.insert(KEY_V.into(), KeyEntry { keycode: 55, mods: shift_mask });
-        entries.insert(KEY_W.into(), KeyEntry { keycode: 25, mods: shift_mask });
-        entries.insert(KEY_X.into(), KeyEntry { keycode: 53, mods: shift_mask });
-        entries.insert(KEY_Y.into(), KeyEntry { keycode: 29, mods: shift_mask });
-        entries.insert(KEY_Z.into(), KeyEntry { keycode: 52, mods: shift_mask });
-        
-        // Numbers (no shift)
-        entries.insert(KEY_0.into(), KeyEntry { keycode: 19, mods: 0 });
-        entries.insert(KEY_1.into(), KeyEntry { keycode: 10, mods: 0 });
-        entries.insert(KEY_2.into(), KeyEntry { keycode: 11, mods: 0 });
-        entries.insert(KEY_3.into(), KeyEntry { keycode: 12, mods: 0 });
-        entries.insert(KEY_4.into(), KeyEntry { keycode: 13, mods: 0 });
-        entries.insert(KEY_5.into(), KeyEntry { keycode: 14, mods: 0 });
-        entries.insert(KEY_6.into(), KeyEntry { keycode: 15, mods: 0 });
-        entries.insert(KEY_7.into(), KeyEntry { keycode: 16, mods: 0 });
-        entries.insert(KEY_8.into(), KeyEntry { keycode: 17, mods: 0 });
-        entries.insert(KEY_9.into(), KeyEntry { keycode: 18, mods: 0 });
-        
-        Self {
-            entries,
-            shift_mask,
-            shift_keycode: Some(50),  // Left Shift keycode
+        const LETTER_KEYCODES: [u32; 26] = [
+            38, 56, 54, 40, 26, 41, 42, 43, 31, 44, 45, 46, 58, 57, 32, 33, 24, 27, 39, 28, 30, 55, 25, 53, 29, 52,
+        ];
+        let masks = ModifierMasks { shift: 1, level3: 0 };
+        let mut layout = LayoutMap::default();
+
+        // Special keys and Left Shift
+        for (keysym, keycode) in [
+            (KEY_Return, 36),
+            (KEY_Escape, 9),
+            (KEY_Tab, 23),
+            (KEY_space, 65),
+            (KEY_BackSpace, 22),
+            (KEY_Shift_L, 50),
+        ] {
+            layout.add(keysym, keycode, 0, masks);
+        }
+
+        // Letters: lowercase plain, uppercase with Shift
+        for (letter, keycode) in ('a'..='z').zip(LETTER_KEYCODES) {
+            layout.add(letter as u32, keycode, 0, masks);
+            layout.add(letter.to_ascii_uppercase() as u32, keycode, masks.shift, masks);
+        }
+
+        // Digits 1-9 then 0
+        for (digit, keycode) in "1234567890".chars().zip(10..) {
+            layout.add(digit as u32, keycode, 0, masks);
         }
+
+        Self::from_layout(layout)
     }
 
     fn clamp_coord(&self, value: u32) -> i16 {
@@ -643,23 +658,33 @@ fn open_xcb_connection() -> Result<(XCBConnection, usize), BackendError> {
     XCBConnection::connect(None).map_err(|e| BackendError::new("x11_connect_failed", e.to_string()))
 }
 
+#[cfg(feature = "os-linux-automation")]
+fn screen_root(conn: &XCBConnection, screen_idx: usize) -> Result<xproto::Window, BackendError> {
+    conn.setup()
+        .roots
+        .get(screen_idx)
+        .map(|screen| screen.root)
+        .ok_or_else(|| BackendError::new("x11_screen_missing", "unable to read X11 screen"))
+}
+
 #[cfg(feature = "os-linux-automation")]
 fn core_keyboard_device_id(conn: &XCBConnection) -> Result<i32, BackendError> {
-    eprintln!("[XKB] Attempting to get core keyboard device ID...");
     let device_id = xkb::x11::get_core_keyboard_device_id(conn);
-    eprintln!("[XKB] get_core_keyboard_device_id returned: {}", device_id);
+    tracing::debug!(device_id, "Core keyboard device looked up");
     
     if device_id == -1 {
         // Try to get more context about the failure
-        eprintln!("[XKB] DISPLAY={:?}", std::env::var("DISPLAY"));
-        eprintln!("[XKB] XDG_SESSION_TYPE={:?}", std::env::var("XDG_SESSION_TYPE"));
+        tracing::debug!(
+            display = ?std::env::var("DISPLAY"),
+            session_type = ?std::env::var("XDG_SESSION_TYPE"),
+            "No core keyboard found"
+        );
         
         Err(BackendError::new(
             "x11_device_missing",
             "XKB could not find a core keyboard (is the app running in an X11 session with $DISPLAY set?).",
         ))
     } else {
-        eprintln!("[XKB] Successfully found core keyboard with device_id={}", device_id);
         Ok(device_id)
     }
 }
@@ -697,10 +722,10 @@ impl LinuxOCR {
 #[cfg(feature = "ocr-integration")]
 impl OCRCapture for LinuxOCR {
     fn extract_text(&self, region: &Region) -> Result<String, BackendError> {
-        eprintln!("[OCR] Extracting text from region '{}'", region.id);
+        tracing::debug!("Extracting text from region '{}'", region.id);
         
-        // Capture the region as an image
-        let capture = LinuxCapture;
+        // Capture the region as an image, where its anchor is now if it has one
+        let capture = crate::anchor::AnchoredCapture::new(Box::new(LinuxCapture));
         let frame = capture.capture_region(region)?;
         
         // Convert to image format
@@ -712,11 +737,11 @@ impl OCRCapture for LinuxOCR {
         img.save(&temp_path)
             .map_err(|e| BackendError::new("ocr_temp_save", e.to_string()))?;
         
-        // Perform OCR (blocking call - uni-ocr is async but we'll use blocking runtime)
-        let rt = tokio::runtime::Runtime::new()
-            .map_err(|e| BackendError::new("tokio_runtime", e.to_string()))?;
+        // uni-ocr is async; wait for it on the shared runtime
+        let runtime = crate::runtime::AsyncRuntime::shared()
+            .map_err(|e| BackendError::new("tokio_runtime", e))?;
         
-        let text = rt.block_on(async {
+        let text = runtime.block_on(async {
             let (text, _json, _confidence) = self.engine.recognize_file(&temp_path).await
                 .map_err(|e| BackendError::new("ocr_extraction_failed", e.to_string()))?;
             Ok::<String, BackendError>(text)
@@ -725,7 +750,7 @@ impl OCRCapture for LinuxOCR {
         // Clean up temp file
         let _ = std::fs::remove_file(&temp_path);
         
-        eprintln!("[OCR] Extracted text (length={}): {:?}", text.len(), text);
+        tracing::debug!(chars = text.chars().count(), "Text extracted");
         Ok(text)
     }
     
@@ -737,14 +762,14 @@ impl OCRCapture for LinuxOCR {
             
             if let Some((cached_text, cached_hash)) = cache.get(&region.id) {
                 if *cached_hash == region_hash {
-                    eprintln!("[OCR] Cache hit for region '{}' (hash={})", region.id, region_hash);
+                    tracing::trace!("OCR cache hit for region '{}' (hash={})", region.id, region_hash);
                     return Ok(cached_text.clone());
                 }
             }
         }
         
         // Cache miss or hash changed - extract text
-        eprintln!("[OCR] Cache miss for region '{}' (hash={})", region.id, region_hash);
+        tracing::trace!("OCR cache miss for region '{}' (hash={})", region.id, region_hash);
         let text = self.extract_text(region)?;
         
         // Update cache
@@ -1,9 +1,22 @@
+#[cfg(any(target_os = "macos", test))]
+use std::collections::HashMap;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use crate::domain::{
-    Automation, BackendError, DisplayInfo, MouseButton, Region, ScreenCapture, ScreenFrame,
+    Automation, BackendError, DisplayInfo, ElementFinder, ElementQuery, Error, MouseButton, Region,
+    ScreenCapture, ScreenFrame, UiElement, WindowEnumerator, WindowInfo,
 };
+#[cfg(target_os = "macos")]
+use crate::domain::{canonical_role, ELEMENT_SEARCH_MAX_DEPTH, ELEMENT_SEARCH_MAX_NODES};
 use screenshots::{display_info::DisplayInfo as RawDisplayInfo, Screen};
+#[cfg(target_os = "macos")]
+use core_graphics::event::{
+    CGEvent, CGEventFlags, CGEventTapLocation, CGEventType, CGMouseButton, ScrollEventUnit,
+};
+#[cfg(target_os = "macos")]
+use core_graphics::event_source::{CGEventSource, CGEventSourceStateID};
+#[cfg(target_os = "macos")]
+use core_graphics::geometry::CGPoint;
 
 pub struct MacCapture;
 
@@ -29,6 +42,12 @@ impl ScreenCapture for MacCapture {
         })
     }
 
+    fn capture_and_hash(&self, region: &Region, downscale: u32) -> Result<(ScreenFrame, u64), BackendError> {
+        let frame = self.capture_region(region)?;
+        let hash = hash_pixels(&frame.bytes, frame.width, frame.height, downscale);
+        Ok((frame, hash))
+    }
+
     fn displays(&self) -> Result<Vec<DisplayInfo>, BackendError> {
         let screens =
             Screen::all().map_err(|e| BackendError::new("mac_displays_failed", e.to_string()))?;
@@ -164,25 +183,661 @@ fn hash_pixels(bytes: &[u8], width: u32, height: u32, downscale: u32) -> u64 {
     hash
 }
 
-pub struct MacAutomation;
-impl Automation for MacAutomation {
-    fn move_cursor(&self, _x: u32, _y: u32) -> Result<(), String> {
+impl WindowEnumerator for MacCapture {
+    fn windows(&self) -> Result<Vec<WindowInfo>, BackendError> {
+        Err(BackendError::new("mac_windows_unsupported", "window listing is not implemented on macOS yet"))
+    }
+}
+
+/// The Accessibility (AX) API of ApplicationServices, which core-graphics does not wrap
+#[cfg(target_os = "macos")]
+mod ax {
+    use std::ffi::c_void;
+
+    use core_foundation::array::{CFArray, CFArrayGetTypeID, CFArrayRef};
+    use core_foundation::base::{CFType, CFTypeRef, TCFType};
+    use core_foundation::dictionary::{CFDictionary, CFDictionaryRef};
+    use core_foundation::number::CFNumber;
+    use core_foundation::string::{CFString, CFStringRef};
+    use core_graphics::geometry::{CGPoint, CGSize};
+    use core_graphics::window::{
+        copy_window_info, kCGNullWindowID, kCGWindowLayer, kCGWindowListExcludeDesktopElements,
+        kCGWindowListOptionOnScreenOnly, kCGWindowOwnerName, kCGWindowOwnerPID,
+    };
+
+    const AX_VALUE_CG_POINT: u32 = 1;
+    const AX_VALUE_CG_SIZE: u32 = 2;
+
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        fn AXIsProcessTrusted() -> bool;
+        fn AXUIElementCreateApplication(pid: i32) -> CFTypeRef;
+        fn AXUIElementCopyAttributeValue(element: CFTypeRef, attribute: CFStringRef, value: *mut CFTypeRef) -> i32;
+        fn AXValueGetValue(value: CFTypeRef, value_type: u32, out: *mut c_void) -> bool;
+        fn AXUIElementPerformAction(element: CFTypeRef, action: CFStringRef) -> i32;
+    }
+
+    /// Whether the user granted this app Accessibility access
+    pub fn trusted() -> bool {
+        unsafe { AXIsProcessTrusted() }
+    }
+
+    /// Pid and name of every app with a normal window on screen, in front-to-back order
+    pub fn applications() -> Vec<(i32, String)> {
+        let Some(windows) = copy_window_info(
+            kCGWindowListOptionOnScreenOnly | kCGWindowListExcludeDesktopElements,
+            kCGNullWindowID,
+        ) else {
+            return Vec::new();
+        };
+        let key = |k: CFStringRef| unsafe { CFString::wrap_under_get_rule(k) };
+        let (pid_key, name_key, layer_key) =
+            unsafe { (key(kCGWindowOwnerPID), key(kCGWindowOwnerName), key(kCGWindowLayer)) };
+        let mut apps: Vec<(i32, String)> = Vec::new();
+        for raw in windows.iter() {
+            let info: CFDictionary<CFString, CFType> =
+                unsafe { CFDictionary::wrap_under_get_rule(*raw as CFDictionaryRef) };
+            let number = |k: &CFString| info.find(k).and_then(|v| v.downcast::<CFNumber>()).and_then(|n| n.to_i32());
+            // Layer 0 holds app windows; menus, the Dock and overlays sit above it
+            if number(&layer_key) != Some(0) {
+                continue;
+            }
+            let Some(pid) = number(&pid_key) else {
+                continue;
+            };
+            if apps.iter().any(|(seen, _)| *seen == pid) {
+                continue;
+            }
+            let name = info
+                .find(&name_key)
+                .and_then(|v| v.downcast::<CFString>())
+                .map(|s| s.to_string())
+                .unwrap_or_default();
+            apps.push((pid, name));
+        }
+        apps
+    }
+
+    /// An AXUIElement
+    pub struct Element(CFType);
+
+    impl Element {
+        pub fn application(pid: i32) -> Option<Self> {
+            let raw = unsafe { AXUIElementCreateApplication(pid) };
+            (!raw.is_null()).then(|| Self(unsafe { CFType::wrap_under_create_rule(raw) }))
+        }
+
+        fn attribute(&self, name: &'static str) -> Option<CFType> {
+            let name = CFString::from_static_string(name);
+            let mut value: CFTypeRef = std::ptr::null();
+            let status = unsafe { AXUIElementCopyAttributeValue(self.0.as_CFTypeRef(), name.as_concrete_TypeRef(), &mut value) };
+            (status == 0 && !value.is_null()).then(|| unsafe { CFType::wrap_under_create_rule(value) })
+        }
+
+        pub fn string(&self, name: &'static str) -> String {
+            self.attribute(name)
+                .and_then(|v| v.downcast::<CFString>())
+                .map(|s| s.to_string())
+                .unwrap_or_default()
+        }
+
+        pub fn children(&self) -> Vec<Element> {
+            let Some(value) = self.attribute("AXChildren") else {
+                return Vec::new();
+            };
+            if value.type_of() != unsafe { CFArrayGetTypeID() } {
+                return Vec::new();
+            }
+            let array: CFArray<CFType> = unsafe { CFArray::wrap_under_get_rule(value.as_CFTypeRef() as CFArrayRef) };
+            array.iter().map(|child| Element(child.clone())).collect()
+        }
+
+        /// `AXPress`, what VoiceOver does for buttons, checkboxes and menu items
+        pub fn press(&self) -> bool {
+            let action = CFString::from_static_string("AXPress");
+            unsafe { AXUIElementPerformAction(self.0.as_CFTypeRef(), action.as_concrete_TypeRef()) == 0 }
+        }
+
+        /// Frame in global display coordinates (points, origin top-left of the main display)
+        pub fn frame(&self) -> Option<(CGPoint, CGSize)> {
+            let mut origin = CGPoint::new(0.0, 0.0);
+            let mut size = CGSize::new(0.0, 0.0);
+            let position = self.attribute("AXPosition")?;
+            let extent = self.attribute("AXSize")?;
+            let read = unsafe {
+                AXValueGetValue(position.as_CFTypeRef(), AX_VALUE_CG_POINT, &mut origin as *mut CGPoint as *mut c_void)
+                    && AXValueGetValue(extent.as_CFTypeRef(), AX_VALUE_CG_SIZE, &mut size as *mut CGSize as *mut c_void)
+            };
+            read.then_some((origin, size))
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl MacCapture {
+    /// Walk the AX trees of on-screen apps and hand every match to `visit` until it returns false
+    fn search_elements(
+        &self,
+        query: &ElementQuery,
+        mut visit: impl FnMut(&ax::Element, UiElement) -> Result<bool, BackendError>,
+    ) -> Result<(), BackendError> {
+        query.validate().map_err(|e| BackendError::new("invalid_query", e))?;
+        if !ax::trusted() {
+            return Err(BackendError::new(
+                "mac_accessibility_denied",
+                "allow LoopAutoma in System Settings > Privacy & Security > Accessibility",
+            ));
+        }
+        let mut visited = 0;
+        for (pid, app) in ax::applications() {
+            if !query.matches_app(&app) {
+                continue;
+            }
+            let Some(root) = ax::Element::application(pid) else {
+                continue;
+            };
+            let mut stack = vec![(root, 0usize)];
+            while let Some((element, depth)) = stack.pop() {
+                visited += 1;
+                if visited > ELEMENT_SEARCH_MAX_NODES {
+                    return Ok(());
+                }
+                let role = canonical_role(&element.string("AXRole"));
+                if depth > 0 && query.matches_role(&role) {
+                    // Icon-only buttons carry their label in AXDescription, static text in AXValue
+                    let name = ["AXTitle", "AXDescription", "AXValue"]
+                        .into_iter()
+                        .map(|attribute| element.string(attribute))
+                        .find(|s| !s.trim().is_empty())
+                        .unwrap_or_default();
+                    if query.matches_name(&name) {
+                        if let Some((origin, size)) = element.frame().filter(|(_, s)| s.width >= 1.0 && s.height >= 1.0) {
+                            let value = element.string("AXValue");
+                            let found = UiElement {
+                                role,
+                                text: if value.is_empty() { name.clone() } else { value },
+                                name,
+                                app: app.clone(),
+                                x: origin.x.round() as i32,
+                                y: origin.y.round() as i32,
+                                width: size.width.round() as u32,
+                                height: size.height.round() as u32,
+                            };
+                            if !visit(&element, found)? {
+                                return Ok(());
+                            }
+                        }
+                    }
+                }
+                if depth < ELEMENT_SEARCH_MAX_DEPTH {
+                    stack.extend(element.children().into_iter().rev().map(|child| (child, depth + 1)));
+                }
+            }
+        }
         Ok(())
     }
-    fn click(&self, _button: MouseButton) -> Result<(), String> {
+}
+
+#[cfg(target_os = "macos")]
+impl ElementFinder for MacCapture {
+    fn find(&self, query: &ElementQuery) -> Result<Vec<UiElement>, BackendError> {
+        let mut found = Vec::new();
+        self.search_elements(query, |_, element| {
+            found.push(element);
+            Ok(true)
+        })?;
+        Ok(found)
+    }
+
+    fn invoke(&self, query: &ElementQuery) -> Result<UiElement, BackendError> {
+        let mut invoked = None;
+        self.search_elements(query, |element, found| {
+            if !element.press() {
+                return Err(BackendError::new("mac_ax_no_press", format!("{} cannot be pressed", query)));
+            }
+            invoked = Some(found);
+            Ok(false)
+        })?;
+        invoked.ok_or_else(|| BackendError::new("element_not_found", format!("no {} found", query)))
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+impl ElementFinder for MacCapture {
+    fn find(&self, _query: &ElementQuery) -> Result<Vec<UiElement>, BackendError> {
+        Err(BackendError::new("mac_ax_unsupported", "element lookup requires building on macOS"))
+    }
+}
+
+/// Modifier keys held for a combination like `Cmd+Shift+T`
+#[cfg(any(target_os = "macos", test))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Modifiers {
+    command: bool,
+    shift: bool,
+    option: bool,
+    control: bool,
+}
+
+#[cfg(any(target_os = "macos", test))]
+impl Modifiers {
+    /// Virtual keycodes of the held modifiers, in press order
+    fn keycodes(&self) -> Vec<u16> {
+        [
+            (self.control, KVK_CONTROL),
+            (self.option, KVK_OPTION),
+            (self.shift, KVK_SHIFT),
+            (self.command, KVK_COMMAND),
+        ]
+        .into_iter()
+        .filter_map(|(held, code)| held.then_some(code))
+        .collect()
+    }
+}
+
+#[cfg(any(target_os = "macos", test))]
+const KVK_COMMAND: u16 = 0x37;
+#[cfg(any(target_os = "macos", test))]
+const KVK_SHIFT: u16 = 0x38;
+#[cfg(any(target_os = "macos", test))]
+const KVK_OPTION: u16 = 0x3A;
+#[cfg(any(target_os = "macos", test))]
+const KVK_CONTROL: u16 = 0x3B;
+
+#[cfg(any(target_os = "macos", test))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MacKey {
+    /// Virtual keycode (`kVK_*`)
+    Code(u16),
+    /// A character typed as Unicode, whatever the keyboard layout
+    Char(char),
+}
+
+#[cfg(any(target_os = "macos", test))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct KeyCombo {
+    modifiers: Modifiers,
+    key: MacKey,
+}
+
+/// Keycode of a named key, e.g. `Enter`, `PageDown`, `F5`
+#[cfg(any(target_os = "macos", test))]
+fn named_keycode(name: &str) -> Option<u16> {
+    const FUNCTION_KEYS: [u16; 12] = [0x7A, 0x78, 0x63, 0x76, 0x60, 0x61, 0x62, 0x64, 0x65, 0x6D, 0x67, 0x6F];
+    let lower = name.to_lowercase();
+    if let Some(n) = lower.strip_prefix('f').and_then(|n| n.parse::<usize>().ok()) {
+        return FUNCTION_KEYS.get(n.checked_sub(1)?).copied();
+    }
+    Some(match lower.as_str() {
+        "enter" | "return" => 0x24,
+        "tab" => 0x30,
+        "space" => 0x31,
+        "backspace" => 0x33,
+        "escape" | "esc" => 0x35,
+        "delete" | "del" => 0x75,
+        "home" => 0x73,
+        "end" => 0x77,
+        "pageup" => 0x74,
+        "pagedown" => 0x79,
+        "left" | "arrowleft" => 0x7B,
+        "right" | "arrowright" => 0x7C,
+        "down" | "arrowdown" => 0x7D,
+        "up" | "arrowup" => 0x7E,
+        _ => return None,
+    })
+}
+
+/// Keycode of a character on the US (ANSI) layout, for shortcuts
+#[cfg(any(target_os = "macos", test))]
+fn ansi_keycode(ch: char) -> Option<u16> {
+    const KEYS: &str = "asdfhgzxcv\0bqweryt123465=97-80]ou[ip\0lj'k;\\,/nm.";
+    let ch = ch.to_ascii_lowercase();
+    if ch == '`' {
+        return Some(0x32);
+    }
+    KEYS.chars().position(|k| k == ch && k != '\0').map(|i| i as u16)
+}
+
+/// Character to keycode table of a keyboard layout, given the character each
+/// keycode types without modifiers. The lowest keycode wins, so the main rows
+/// beat the keypad.
+#[cfg(any(target_os = "macos", test))]
+fn layout_table(translate: impl Fn(u16) -> Option<char>) -> HashMap<char, u16> {
+    let mut table = HashMap::new();
+    for code in 0..0x80u16 {
+        if let Some(ch) = translate(code).filter(|ch| !ch.is_control()) {
+            table.entry(ch).or_insert(code);
+        }
+    }
+    table
+}
+
+/// Parse `Enter`, `a` or a combination such as `Cmd+Shift+T` or `Ctrl++`
+///
+/// A lone character is typed as Unicode. With modifiers, it is sent as the key
+/// that types it on `layout` (Cmd+Z is a different key on AZERTY), else at its
+/// US layout position; apps match shortcuts against the character.
+#[cfg(any(target_os = "macos", test))]
+fn parse_key(raw: &str, layout: Option<&HashMap<char, u16>>) -> Result<KeyCombo, String> {
+    if raw.is_empty() {
+        return Err("key cannot be empty".into());
+    }
+    let (modifier_part, key) = match raw.strip_suffix("++") {
+        Some(mods) => (Some(mods), "+"),
+        None => match raw.rsplit_once('+') {
+            Some((mods, key)) if !key.is_empty() => (Some(mods), key),
+            _ => (None, raw),
+        },
+    };
+    let mut modifiers = Modifiers::default();
+    for name in modifier_part.into_iter().flat_map(|m| m.split('+')) {
+        match name.trim().to_lowercase().as_str() {
+            "cmd" | "command" | "meta" | "super" => modifiers.command = true,
+            "shift" => modifiers.shift = true,
+            "alt" | "option" | "opt" => modifiers.option = true,
+            "ctrl" | "control" => modifiers.control = true,
+            other => return Err(format!("unsupported modifier '{}': use Cmd, Shift, Option/Alt or Ctrl", other)),
+        }
+    }
+    if let Some(code) = named_keycode(key) {
+        return Ok(KeyCombo { modifiers, key: MacKey::Code(code) });
+    }
+    let mut chars = key.chars();
+    let (Some(ch), None) = (chars.next(), chars.next()) else {
+        return Err(format!(
+            "unsupported key '{}': use a named key (Enter, Escape, Tab, arrows, F1-F12, …) or a single character",
+            key
+        ));
+    };
+    if modifier_part.is_none() {
+        return Ok(KeyCombo { modifiers, key: MacKey::Char(ch) });
+    }
+    let base = ch.to_lowercase().next().unwrap_or(ch);
+    let code = layout
+        .and_then(|table| table.get(&base).copied())
+        .or_else(|| ansi_keycode(ch))
+        .ok_or_else(|| format!("no key for '{}' in a shortcut", ch))?;
+    Ok(KeyCombo { modifiers, key: MacKey::Code(code) })
+}
+
+/// The active keyboard layout, read with `UCKeyTranslate`
+#[cfg(target_os = "macos")]
+mod keyboard_layout {
+    use std::collections::HashMap;
+    use std::ffi::c_void;
+
+    use core_foundation::base::{CFRelease, CFTypeRef};
+    use core_foundation::data::{CFDataGetBytePtr, CFDataRef};
+    use core_foundation::string::CFStringRef;
+
+    const KEY_ACTION_DISPLAY: u16 = 3;
+    const NO_DEAD_KEYS: u32 = 1;
+
+    #[link(name = "Carbon", kind = "framework")]
+    extern "C" {
+        static kTISPropertyUnicodeKeyLayoutData: CFStringRef;
+        fn TISCopyCurrentKeyboardLayoutInputSource() -> CFTypeRef;
+        fn TISGetInputSourceProperty(source: CFTypeRef, key: CFStringRef) -> *const c_void;
+        fn LMGetKbdType() -> u8;
+        #[allow(clippy::too_many_arguments)]
+        fn UCKeyTranslate(
+            layout: *const c_void,
+            key_code: u16,
+            key_action: u16,
+            modifier_key_state: u32,
+            keyboard_type: u32,
+            key_translate_options: u32,
+            dead_key_state: *mut u32,
+            max_string_length: usize,
+            actual_string_length: *mut usize,
+            unicode_string: *mut u16,
+        ) -> i32;
+    }
+
+    /// Keycode of each character the current layout types without modifiers
+    pub fn current() -> Option<HashMap<char, u16>> {
+        unsafe {
+            let source = TISCopyCurrentKeyboardLayoutInputSource();
+            if source.is_null() {
+                return None;
+            }
+            let data = TISGetInputSourceProperty(source, kTISPropertyUnicodeKeyLayoutData) as CFDataRef;
+            let table = (!data.is_null()).then(|| {
+                let layout = CFDataGetBytePtr(data) as *const c_void;
+                let keyboard_type = LMGetKbdType() as u32;
+                super::layout_table(|code| {
+                    let (mut dead_keys, mut len, mut buf) = (0u32, 0usize, [0u16; 4]);
+                    let status = UCKeyTranslate(
+                        layout,
+                        code,
+                        KEY_ACTION_DISPLAY,
+                        0,
+                        keyboard_type,
+                        NO_DEAD_KEYS,
+                        &mut dead_keys,
+                        buf.len(),
+                        &mut len,
+                        buf.as_mut_ptr(),
+                    );
+                    if status != 0 || len != 1 {
+                        return None;
+                    }
+                    char::from_u32(buf[0] as u32)
+                })
+            });
+            CFRelease(source);
+            table
+        }
+    }
+}
+
+/// Mouse and keyboard input through Quartz events (CGEvent)
+///
+/// Coordinates are global display points, as used by `MacCapture`. Posting
+/// events needs the Accessibility permission.
+#[derive(Default)]
+pub struct MacAutomation {
+    /// Button held by `mouse_down`, so moves in between are sent as drags
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    held: std::sync::Mutex<Option<MouseButton>>,
+}
+
+#[cfg(target_os = "macos")]
+impl MacAutomation {
+    fn source() -> Result<CGEventSource, String> {
+        CGEventSource::new(CGEventSourceStateID::HIDSystemState)
+            .map_err(|_| "CGEventSource unavailable; allow LoopAutoma under System Settings → Privacy & Security → Accessibility".to_string())
+    }
+
+    fn post(event: Result<CGEvent, ()>, what: &str) -> Result<(), String> {
+        let event = event.map_err(|_| format!("failed to create {} event", what))?;
+        event.post(CGEventTapLocation::HID);
         Ok(())
     }
-    fn type_text(&self, _text: &str) -> Result<(), String> {
+
+    fn cursor() -> Result<CGPoint, String> {
+        CGEvent::new(Self::source()?)
+            .map(|e| e.location())
+            .map_err(|_| "failed to read the cursor position".to_string())
+    }
+
+    fn send_button(button: MouseButton, down: bool) -> Result<(), String> {
+        let (event_type, cg_button) = match (button, down) {
+            (MouseButton::Left, true) => (CGEventType::LeftMouseDown, CGMouseButton::Left),
+            (MouseButton::Left, false) => (CGEventType::LeftMouseUp, CGMouseButton::Left),
+            (MouseButton::Right, true) => (CGEventType::RightMouseDown, CGMouseButton::Right),
+            (MouseButton::Right, false) => (CGEventType::RightMouseUp, CGMouseButton::Right),
+            (MouseButton::Middle, true) => (CGEventType::OtherMouseDown, CGMouseButton::Center),
+            (MouseButton::Middle, false) => (CGEventType::OtherMouseUp, CGMouseButton::Center),
+        };
+        let at = Self::cursor()?;
+        Self::post(CGEvent::new_mouse_event(Self::source()?, event_type, at, cg_button), "mouse button")
+    }
+
+    fn send_keycode(code: u16, down: bool, flags: CGEventFlags) -> Result<(), String> {
+        let event = CGEvent::new_keyboard_event(Self::source()?, code, down);
+        if let Ok(event) = &event {
+            event.set_flags(flags);
+        }
+        Self::post(event, "key")
+    }
+
+    fn send_char(ch: char, down: bool) -> Result<(), String> {
+        let event = CGEvent::new_keyboard_event(Self::source()?, 0, down);
+        if let Ok(event) = &event {
+            event.set_string(&ch.to_string());
+        }
+        Self::post(event, "key")
+    }
+
+    fn flags(modifiers: &Modifiers) -> CGEventFlags {
+        let mut flags = CGEventFlags::CGEventFlagNull;
+        for (held, flag) in [
+            (modifiers.command, CGEventFlags::CGEventFlagCommand),
+            (modifiers.shift, CGEventFlags::CGEventFlagShift),
+            (modifiers.option, CGEventFlags::CGEventFlagAlternate),
+            (modifiers.control, CGEventFlags::CGEventFlagControl),
+        ] {
+            if held {
+                flags |= flag;
+            }
+        }
+        flags
+    }
+
+    /// Parse against the layout active now; users switch layouts at runtime
+    fn parse(key: &str) -> Result<KeyCombo, String> {
+        parse_key(key, keyboard_layout::current().as_ref())
+    }
+
+    /// Press or release a combination: modifiers go down first and up last
+    fn send_combo(combo: &KeyCombo, down: bool) -> Result<(), String> {
+        let flags = Self::flags(&combo.modifiers);
+        let modifiers = combo.modifiers.keycodes();
+        if down {
+            for code in &modifiers {
+                Self::send_keycode(*code, true, flags)?;
+            }
+        }
+        match combo.key {
+            MacKey::Code(code) => Self::send_keycode(code, down, flags)?,
+            MacKey::Char(ch) => Self::send_char(ch, down)?,
+        }
+        if !down {
+            for code in modifiers.iter().rev() {
+                Self::send_keycode(*code, false, CGEventFlags::CGEventFlagNull)?;
+            }
+        }
         Ok(())
     }
-    fn key(&self, _key: &str) -> Result<(), String> {
+
+    /// Scroll by whole lines; positive `dy` scrolls up, positive `dx` left
+    pub fn scroll(&self, dx: i32, dy: i32) -> Result<(), String> {
+        Self::post(
+            CGEvent::new_scroll_event(Self::source()?, ScrollEventUnit::LINE, 2, dy, dx, 0),
+            "scroll",
+        )
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl Automation for MacAutomation {
+    fn move_cursor(&self, x: u32, y: u32) -> Result<(), Error> {
+        let at = CGPoint::new(x as f64, y as f64);
+        let (event_type, button) = match *self.held.lock().unwrap() {
+            Some(MouseButton::Left) => (CGEventType::LeftMouseDragged, CGMouseButton::Left),
+            Some(MouseButton::Right) => (CGEventType::RightMouseDragged, CGMouseButton::Right),
+            Some(MouseButton::Middle) => (CGEventType::OtherMouseDragged, CGMouseButton::Center),
+            None => (CGEventType::MouseMoved, CGMouseButton::Left),
+        };
+        let source = Self::source().map_err(Error::Automation)?;
+        Self::post(CGEvent::new_mouse_event(source, event_type, at, button), "mouse move").map_err(Error::Automation)
+    }
+
+    fn click(&self, button: MouseButton) -> Result<(), Error> {
+        self.mouse_down(button)?;
+        self.mouse_up(button)
+    }
+
+    fn type_text(&self, text: &str) -> Result<(), Error> {
+        for ch in text.chars() {
+            match ch {
+                '\r' => {}
+                '\n' => self.key("Enter")?,
+                '\t' => self.key("Tab")?,
+                _ => {
+                    Self::send_char(ch, true).map_err(Error::Automation)?;
+                    Self::send_char(ch, false).map_err(Error::Automation)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn key(&self, key: &str) -> Result<(), Error> {
+        let combo = Self::parse(key).map_err(Error::Config)?;
+        Self::send_combo(&combo, true).map_err(Error::Automation)?;
+        Self::send_combo(&combo, false).map_err(Error::Automation)
+    }
+
+    fn mouse_down(&self, button: MouseButton) -> Result<(), Error> {
+        Self::send_button(button, true).map_err(Error::Automation)?;
+        *self.held.lock().unwrap() = Some(button);
         Ok(())
     }
+
+    fn mouse_up(&self, button: MouseButton) -> Result<(), Error> {
+        self.held.lock().unwrap().take();
+        Self::send_button(button, false).map_err(Error::Automation)
+    }
+
+    fn key_down(&self, key: &str) -> Result<(), Error> {
+        Self::send_combo(&Self::parse(key).map_err(Error::Config)?, true).map_err(Error::Automation)
+    }
+
+    fn key_up(&self, key: &str) -> Result<(), Error> {
+        Self::send_combo(&Self::parse(key).map_err(Error::Config)?, false).map_err(Error::Automation)
+    }
+
+    fn cursor_position(&self) -> Result<Option<(i32, i32)>, Error> {
+        let at = Self::cursor().map_err(Error::Automation)?;
+        Ok(Some((at.x.round() as i32, at.y.round() as i32)))
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+impl MacAutomation {
+    fn unsupported<T>() -> Result<T, String> {
+        Err("macOS automation backend requires building on macOS; run on a macOS target to enable CGEvent input.".into())
+    }
+
+    pub fn scroll(&self, _dx: i32, _dy: i32) -> Result<(), String> {
+        Self::unsupported()
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+impl Automation for MacAutomation {
+    fn move_cursor(&self, _x: u32, _y: u32) -> Result<(), Error> {
+        Self::unsupported().map_err(Error::Config)
+    }
+
+    fn click(&self, _button: MouseButton) -> Result<(), Error> {
+        Self::unsupported().map_err(Error::Config)
+    }
+
+    fn type_text(&self, _text: &str) -> Result<(), Error> {
+        Self::unsupported().map_err(Error::Config)
+    }
+
+    fn key(&self, _key: &str) -> Result<(), Error> {
+        Self::unsupported().map_err(Error::Config)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::hash_pixels;
+    use super::{ansi_keycode, hash_pixels, layout_table, parse_key, KeyCombo, MacKey, Modifiers};
 
     #[test]
     fn hash_pixels_changes_with_content() {
@@ -207,4 +862,67 @@ mod tests {
         let buf = vec![1u8; 16];
         assert_eq!(hash_pixels(&buf, 0, 4, 1), 0);
     }
+
+    #[test]
+    fn parse_named_and_plain_keys() {
+        assert_eq!(parse_key("Enter", None).unwrap().key, MacKey::Code(0x24));
+        assert_eq!(parse_key("esc", None).unwrap().key, MacKey::Code(0x35));
+        assert_eq!(parse_key("F12", None).unwrap().key, MacKey::Code(0x6F));
+        assert_eq!(parse_key("ArrowUp", None).unwrap().key, MacKey::Code(0x7E));
+        // Plain characters are typed as Unicode so the layout does not matter
+        assert_eq!(
+            parse_key("é", None).unwrap(),
+            KeyCombo { modifiers: Modifiers::default(), key: MacKey::Char('é') }
+        );
+        assert!(parse_key("", None).is_err());
+        assert!(parse_key("F13", None).is_err());
+        assert!(parse_key("Banana", None).is_err());
+    }
+
+    #[test]
+    fn parse_combinations_with_modifiers() {
+        let copy = parse_key("Cmd+C", None).unwrap();
+        assert!(copy.modifiers.command && !copy.modifiers.shift);
+        assert_eq!(copy.key, MacKey::Code(0x08));
+        let reopen = parse_key("cmd+shift+t", None).unwrap();
+        assert_eq!(reopen.modifiers.keycodes(), vec![0x38, 0x37]);
+        assert_eq!(reopen.key, MacKey::Code(0x11));
+        let zoom_out = parse_key("Cmd+-", None).unwrap();
+        assert_eq!(zoom_out.key, MacKey::Code(0x1B));
+        assert_eq!(parse_key("+", None).unwrap().key, MacKey::Char('+'));
+        // `+` needs Shift on the US layout: spell such shortcuts with their base key
+        assert!(parse_key("Cmd++", None).is_err());
+        assert!(parse_key("Hyper+A", None).is_err());
+        assert!(parse_key("Cmd+é", None).is_err());
+    }
+
+    #[test]
+    fn shortcuts_use_the_key_of_the_active_layout() {
+        // AZERTY swaps A/Q and Z/W and types `é` on the 2 key
+        let azerty = layout_table(|code| match code {
+            0x00 => Some('q'),
+            0x06 => Some('w'),
+            0x0C => Some('a'),
+            0x0D => Some('z'),
+            0x13 => Some('é'),
+            0x24 => Some('\r'),
+            _ => None,
+        });
+        assert_eq!(azerty.len(), 5);
+        assert_eq!(parse_key("Cmd+Z", Some(&azerty)).unwrap().key, MacKey::Code(0x0D));
+        assert_eq!(parse_key("Cmd+a", Some(&azerty)).unwrap().key, MacKey::Code(0x0C));
+        assert_eq!(parse_key("Cmd+é", Some(&azerty)).unwrap().key, MacKey::Code(0x13));
+        // Characters missing from the layout fall back to the US position
+        assert_eq!(parse_key("Cmd+C", Some(&azerty)).unwrap().key, MacKey::Code(0x08));
+    }
+
+    #[test]
+    fn ansi_keycodes_follow_the_us_layout() {
+        assert_eq!(ansi_keycode('a'), Some(0x00));
+        assert_eq!(ansi_keycode('Q'), Some(0x0C));
+        assert_eq!(ansi_keycode('0'), Some(0x1D));
+        assert_eq!(ansi_keycode('/'), Some(0x2C));
+        assert_eq!(ansi_keycode('`'), Some(0x32));
+        assert_eq!(ansi_keycode('\0'), None);
+    }
 }
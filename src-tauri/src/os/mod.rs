@@ -1,6 +1,13 @@
+#[cfg(feature = "os-linux-accessibility")]
+pub mod atspi;
+pub mod hash;
+#[cfg(feature = "os-linux-automation")]
+pub mod keymap;
 #[cfg(any(feature = "os-linux-capture-xcap", feature = "os-linux-automation"))]
 pub mod linux;
 #[cfg(feature = "os-macos")]
 pub mod macos;
+#[cfg(feature = "os-linux-automation")]
+pub mod wayland;
 #[cfg(feature = "os-windows")]
 pub mod windows;
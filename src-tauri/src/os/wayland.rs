@@ -0,0 +1,469 @@
+/// Input injection for Wayland sessions through ydotool
+///
+/// Wayland compositors do not accept XTest input for native windows, so on a
+/// Wayland session input goes through `ydotool`, which writes to a uinput
+/// device via its `ydotoold` daemon and works under every compositor. Keys are
+/// sent as Linux evdev keycodes. The uinput device goes through the
+/// compositor's keymap, so characters are looked up in the configured layout
+/// (see `keymap::detect_layout`); `ydotool type`, which assumes US QWERTY, is
+/// only the fallback when that layout cannot be compiled.
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use xkbcommon::xkb;
+
+use super::keymap::{self, KeyStroke, LayoutMap};
+use crate::domain::{Automation, AutomationCapabilities, Error, MouseButton};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionType {
+    X11,
+    Wayland,
+    Unknown,
+}
+
+impl SessionType {
+    /// From `XDG_SESSION_TYPE`, or which display variables are set
+    pub fn from_env(xdg_session_type: Option<&str>, wayland_display: Option<&str>, display: Option<&str>) -> Self {
+        match xdg_session_type.map(str::to_lowercase).as_deref() {
+            Some("wayland") => Self::Wayland,
+            Some("x11") => Self::X11,
+            _ if wayland_display.is_some_and(|d| !d.is_empty()) => Self::Wayland,
+            _ if display.is_some_and(|d| !d.is_empty()) => Self::X11,
+            _ => Self::Unknown,
+        }
+    }
+
+    pub fn detect() -> Self {
+        let var = |name| std::env::var(name).ok();
+        Self::from_env(
+            var("XDG_SESSION_TYPE").as_deref(),
+            var("WAYLAND_DISPLAY").as_deref(),
+            var("DISPLAY").as_deref(),
+        )
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::X11 => "x11",
+            Self::Wayland => "wayland",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+const KEY_LEFTCTRL: u16 = 29;
+const KEY_LEFTSHIFT: u16 = 42;
+const KEY_LEFTALT: u16 = 56;
+const KEY_LEFTMETA: u16 = 125;
+/// AltGr
+const KEY_RIGHTALT: u16 = 100;
+/// XKB key codes are evdev codes plus 8
+const XKB_EVDEV_OFFSET: u32 = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyPress {
+    /// Evdev keycodes pressed in order, modifiers first and the key itself last
+    Codes(Vec<u16>),
+    /// A lone character, typed as Unicode
+    Char(char),
+}
+
+/// Evdev keycode of a named key, e.g. `Enter`, `PageDown`, `F5`
+fn named_keycode(name: &str) -> Option<u16> {
+    const FUNCTION_KEYS: [u16; 12] = [59, 60, 61, 62, 63, 64, 65, 66, 67, 68, 87, 88];
+    let lower = name.to_lowercase();
+    if let Some(n) = lower.strip_prefix('f').and_then(|n| n.parse::<usize>().ok()) {
+        return FUNCTION_KEYS.get(n.checked_sub(1)?).copied();
+    }
+    Some(match lower.as_str() {
+        "enter" | "return" => 28,
+        "escape" | "esc" => 1,
+        "tab" => 15,
+        "space" => 57,
+        "backspace" => 14,
+        "delete" | "del" => 111,
+        "home" => 102,
+        "end" => 107,
+        "pageup" => 104,
+        "pagedown" => 109,
+        "up" | "arrowup" => 103,
+        "left" | "arrowleft" => 105,
+        "right" | "arrowright" => 106,
+        "down" | "arrowdown" => 108,
+        _ => return None,
+    })
+}
+
+/// Evdev keycode of a character's key on the US layout, for shortcuts
+fn char_keycode(ch: char) -> Option<u16> {
+    const ROWS: [(&str, u16); 5] = [
+        ("1234567890-=", 2),
+        ("qwertyuiop[]", 16),
+        ("asdfghjkl;'`", 30),
+        ("\\zxcvbnm,./", 43),
+        (" ", 57),
+    ];
+    let ch = ch.to_ascii_lowercase();
+    ROWS.iter()
+        .find_map(|(keys, first)| keys.chars().position(|k| k == ch).map(|i| first + i as u16))
+}
+
+/// Evdev codes pressing a layout key stroke, modifiers first
+pub fn stroke_codes(stroke: KeyStroke) -> Vec<u16> {
+    let mut codes = Vec::new();
+    if stroke.shift {
+        codes.push(KEY_LEFTSHIFT);
+    }
+    if stroke.level3 {
+        codes.push(KEY_RIGHTALT);
+    }
+    codes.push(stroke.keycode.saturating_sub(XKB_EVDEV_OFFSET) as u16);
+    codes
+}
+
+/// `ydotool key` arguments typing `text` on `layout`, or None if a character has no key
+pub fn text_key_events(text: &str, layout: &LayoutMap) -> Option<Vec<String>> {
+    let mut events = Vec::new();
+    for ch in text.chars() {
+        let ch = if ch == '\n' { '\r' } else { ch };
+        for stroke in layout.strokes(ch)? {
+            let codes = stroke_codes(stroke);
+            events.extend(key_events(&codes, true));
+            events.extend(key_events(&codes, false));
+        }
+    }
+    Some(events)
+}
+
+/// Parse `Enter`, `a` or a combination such as `Ctrl+Shift+T`. Shortcut
+/// characters use their key on `layout` (Ctrl+Z is a different key on AZERTY),
+/// else their US QWERTY position.
+pub fn parse_key(raw: &str, layout: Option<&LayoutMap>) -> Result<KeyPress, String> {
+    if raw.is_empty() {
+        return Err("key cannot be empty".into());
+    }
+    let (modifier_part, key) = match raw.rsplit_once('+') {
+        Some((mods, key)) if !key.is_empty() => (Some(mods), key),
+        _ => (None, raw),
+    };
+    let mut codes = Vec::new();
+    for name in modifier_part.into_iter().flat_map(|m| m.split('+')) {
+        codes.push(match name.trim().to_lowercase().as_str() {
+            "ctrl" | "control" => KEY_LEFTCTRL,
+            "shift" => KEY_LEFTSHIFT,
+            "alt" => KEY_LEFTALT,
+            "super" | "meta" | "win" | "cmd" => KEY_LEFTMETA,
+            other => return Err(format!("unsupported modifier '{}': use Ctrl, Shift, Alt or Super", other)),
+        });
+    }
+    let code = match named_keycode(key) {
+        Some(code) => code,
+        None => {
+            let mut chars = key.chars();
+            let (Some(ch), None) = (chars.next(), chars.next()) else {
+                return Err(format!(
+                    "unsupported key '{}': use a named key (Enter, Escape, Tab, arrows, F1-F12, …) or a single character",
+                    key
+                ));
+            };
+            if modifier_part.is_none() {
+                return Ok(KeyPress::Char(ch));
+            }
+            let on_layout = layout
+                .and_then(|l| l.get(keymap::char_keysym(ch.to_ascii_lowercase())))
+                .filter(|stroke| !stroke.level3)
+                .map(|stroke| stroke.keycode.saturating_sub(XKB_EVDEV_OFFSET) as u16);
+            on_layout
+                .or_else(|| char_keycode(ch))
+                .ok_or_else(|| format!("no key for '{}' in a shortcut", ch))?
+        }
+    };
+    codes.push(code);
+    Ok(KeyPress::Codes(codes))
+}
+
+/// `ydotool key` arguments pressing (`down`) or releasing the codes; releases go in reverse
+pub fn key_events(codes: &[u16], down: bool) -> Vec<String> {
+    if down {
+        codes.iter().map(|c| format!("{}:1", c)).collect()
+    } else {
+        codes.iter().rev().map(|c| format!("{}:0", c)).collect()
+    }
+}
+
+/// `ydotool click` code: button id, plus 0x40 to press and 0x80 to release
+pub fn click_code(button: MouseButton, down: bool, up: bool) -> String {
+    let id = match button {
+        MouseButton::Left => 0x00,
+        MouseButton::Right => 0x01,
+        MouseButton::Middle => 0x02,
+    };
+    let code = id | if down { 0x40 } else { 0 } | if up { 0x80 } else { 0 };
+    format!("0x{:02X}", code)
+}
+
+fn find_in_path(binary: &str) -> Option<PathBuf> {
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .map(|dir| dir.join(binary))
+        .find(|path| path.is_file())
+}
+
+/// Where `ydotoold` listens: `YDOTOOL_SOCKET`, else its defaults
+fn ydotool_socket() -> Option<PathBuf> {
+    if let Some(socket) = std::env::var_os("YDOTOOL_SOCKET") {
+        return Some(PathBuf::from(socket));
+    }
+    let runtime = std::env::var_os("XDG_RUNTIME_DIR").map(|d| Path::new(&d).join(".ydotool_socket"));
+    runtime
+        .into_iter()
+        .chain([PathBuf::from("/tmp/.ydotool_socket")])
+        .find(|path| path.exists())
+}
+
+/// Key codes of the configured layout, or None when xkbcommon cannot compile it
+fn load_layout() -> Option<LayoutMap> {
+    let name = keymap::detect_layout();
+    let context = xkb::Context::new(xkb::CONTEXT_NO_FLAGS);
+    let compiled = xkb::Keymap::new_from_names(
+        &context,
+        "",
+        "",
+        &name.layout,
+        &name.variant,
+        None,
+        xkb::KEYMAP_COMPILE_NO_FLAGS,
+    )?;
+    Some(LayoutMap::from_xkb(&compiled))
+}
+
+/// Input through the `ydotool` client and its `ydotoold` daemon
+pub struct YdotoolAutomation {
+    binary: PathBuf,
+    layout: Option<LayoutMap>,
+}
+
+impl YdotoolAutomation {
+    pub fn new() -> Result<Self, String> {
+        let binary = find_in_path("ydotool").ok_or("ydotool is not installed")?;
+        if ydotool_socket().is_none() {
+            return Err("ydotoold is not running (no ydotool socket)".into());
+        }
+        Ok(Self {
+            binary,
+            layout: load_layout(),
+        })
+    }
+
+    fn run(&self, args: &[&str]) -> Result<(), String> {
+        let output = Command::new(&self.binary)
+            .args(args)
+            .output()
+            .map_err(|e| format!("failed to run ydotool: {}", e))?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(format!(
+                "ydotool {} failed: {}",
+                args.first().unwrap_or(&""),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))
+        }
+    }
+
+    fn run_key_events(&self, events: &[String]) -> Result<(), String> {
+        let mut args = vec!["key", "--key-delay", "5"];
+        args.extend(events.iter().map(String::as_str));
+        self.run(&args)
+    }
+
+    fn press_codes(&self, codes: &[u16], down: bool, up: bool) -> Result<(), String> {
+        let mut events = Vec::new();
+        if down {
+            events.extend(key_events(codes, true));
+        }
+        if up {
+            events.extend(key_events(codes, false));
+        }
+        self.run_key_events(&events)
+    }
+
+    fn send_key(&self, key: &str, down: bool, up: bool) -> Result<(), Error> {
+        match parse_key(key, self.layout.as_ref()).map_err(Error::Config)? {
+            KeyPress::Codes(codes) => self.press_codes(&codes, down, up).map_err(Error::Automation),
+            KeyPress::Char(ch) => {
+                let stroke = self.layout.as_ref().and_then(|l| l.strokes(ch)).filter(|s| s.len() == 1);
+                match stroke {
+                    Some(stroke) => self.press_codes(&stroke_codes(stroke[0]), down, up).map_err(Error::Automation),
+                    // Dead-key and Unicode characters have no separate press and release
+                    None if down => self.type_text(&ch.to_string()),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+impl Automation for YdotoolAutomation {
+    fn move_cursor(&self, x: u32, y: u32) -> Result<(), Error> {
+        self.run(&["mousemove", "--absolute", "-x", &x.to_string(), "-y", &y.to_string()]).map_err(Error::Automation)
+    }
+
+    fn click(&self, button: MouseButton) -> Result<(), Error> {
+        self.run(&["click", &click_code(button, true, true)]).map_err(Error::Automation)
+    }
+
+    fn type_text(&self, text: &str) -> Result<(), Error> {
+        match self.layout.as_ref().and_then(|layout| text_key_events(text, layout)) {
+            Some(events) => self.run_key_events(&events),
+            None => self.run(&["type", "--key-delay", "5", "--", text]),
+        }
+        .map_err(Error::Automation)
+    }
+
+    fn key(&self, key: &str) -> Result<(), Error> {
+        self.send_key(key, true, true)
+    }
+
+    fn mouse_down(&self, button: MouseButton) -> Result<(), Error> {
+        self.run(&["click", &click_code(button, true, false)]).map_err(Error::Automation)
+    }
+
+    fn mouse_up(&self, button: MouseButton) -> Result<(), Error> {
+        self.run(&["click", &click_code(button, false, true)]).map_err(Error::Automation)
+    }
+
+    fn key_down(&self, key: &str) -> Result<(), Error> {
+        self.send_key(key, true, false)
+    }
+
+    fn key_up(&self, key: &str) -> Result<(), Error> {
+        self.send_key(key, false, true)
+    }
+}
+
+/// What input works in this Linux session, and with which backend
+pub fn probe(session: SessionType, x11_display: bool, ydotool: Result<(), String>) -> AutomationCapabilities {
+    let capabilities = |backend: &str, available: bool, hint: Option<String>| AutomationCapabilities {
+        session: session.name().into(),
+        backend: backend.into(),
+        mouse: available,
+        keyboard: available,
+        text: available,
+        hint,
+    };
+    match (session, ydotool) {
+        (SessionType::Wayland, Ok(())) => capabilities(
+            "ydotool",
+            true,
+            Some("Cursor positions are exact only with pointer acceleration turned off (flat profile).".into()),
+        ),
+        (SessionType::Wayland, Err(e)) if x11_display => capabilities(
+            "xtest",
+            true,
+            Some(format!(
+                "Wayland session without ydotool ({}): only X11 (XWayland) windows receive input. Install ydotool and start ydotoold to control all windows.",
+                e
+            )),
+        ),
+        (SessionType::Wayland, Err(e)) => capabilities(
+            "none",
+            false,
+            Some(format!("No input backend: {}. Install ydotool and start ydotoold.", e)),
+        ),
+        (_, _) if x11_display => capabilities("xtest", true, None),
+        (_, _) => capabilities("none", false, Some("No X11 display (DISPLAY is not set)".into())),
+    }
+}
+
+/// Probe the current session
+pub fn probe_session() -> AutomationCapabilities {
+    let x11_display = std::env::var("DISPLAY").is_ok_and(|d| !d.is_empty());
+    let session = SessionType::detect();
+    let ydotool = match session {
+        SessionType::Wayland => YdotoolAutomation::new().map(|_| ()),
+        _ => Err("not a Wayland session".into()),
+    };
+    probe(session, x11_display, ydotool)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{click_code, key_events, parse_key, probe, text_key_events, KeyPress, SessionType};
+    use crate::domain::MouseButton;
+    use crate::os::keymap::{LayoutMap, ModifierMasks};
+
+    /// A few keys of the French AZERTY layout, in XKB key codes
+    fn azerty() -> LayoutMap {
+        let masks = ModifierMasks { shift: 1, level3: 0x80 };
+        let mut layout = LayoutMap::default();
+        layout.add('a' as u32, 24, 0, masks);
+        layout.add('z' as u32, 25, 0, masks);
+        layout.add('Z' as u32, 25, 1, masks);
+        layout.add('@' as u32, 19, 0x80, masks);
+        layout.add(0xfe52, 34, 0, masks);
+        layout.add('e' as u32, 26, 0, masks);
+        layout
+    }
+
+    #[test]
+    fn session_type_prefers_xdg_then_display_variables() {
+        assert_eq!(SessionType::from_env(Some("wayland"), None, Some(":0")), SessionType::Wayland);
+        assert_eq!(SessionType::from_env(Some("X11"), Some("wayland-0"), None), SessionType::X11);
+        assert_eq!(SessionType::from_env(Some("tty"), Some("wayland-0"), Some(":0")), SessionType::Wayland);
+        assert_eq!(SessionType::from_env(None, Some(""), Some(":1")), SessionType::X11);
+        assert_eq!(SessionType::from_env(None, None, None), SessionType::Unknown);
+    }
+
+    #[test]
+    fn keys_map_to_evdev_codes() {
+        assert_eq!(parse_key("Enter", None).unwrap(), KeyPress::Codes(vec![28]));
+        assert_eq!(parse_key("F11", None).unwrap(), KeyPress::Codes(vec![87]));
+        assert_eq!(parse_key("ctrl+shift+t", None).unwrap(), KeyPress::Codes(vec![29, 42, 20]));
+        assert_eq!(parse_key("Super+/", None).unwrap(), KeyPress::Codes(vec![125, 53]));
+        assert_eq!(parse_key("ß", None).unwrap(), KeyPress::Char('ß'));
+        assert_eq!(parse_key("+", None).unwrap(), KeyPress::Char('+'));
+        assert!(parse_key("Hyper+A", None).is_err());
+        assert!(parse_key("Ctrl+ß", None).is_err());
+        assert!(parse_key("Banana", None).is_err());
+    }
+
+    #[test]
+    fn keys_and_text_follow_the_configured_layout() {
+        let layout = azerty();
+        assert_eq!(parse_key("Ctrl+Z", Some(&layout)).unwrap(), KeyPress::Codes(vec![29, 17]));
+        assert_eq!(parse_key("Ctrl+Q", Some(&layout)).unwrap(), KeyPress::Codes(vec![29, 16]));
+        assert_eq!(
+            text_key_events("Z@ê", &layout).unwrap(),
+            vec!["42:1", "17:1", "17:0", "42:0", "100:1", "11:1", "11:0", "100:0", "26:1", "26:0", "18:1", "18:0"]
+        );
+        assert_eq!(text_key_events("€", &layout), None);
+    }
+
+    #[test]
+    fn ydotool_arguments() {
+        assert_eq!(key_events(&[29, 46], true), vec!["29:1", "46:1"]);
+        assert_eq!(key_events(&[29, 46], false), vec!["46:0", "29:0"]);
+        assert_eq!(click_code(MouseButton::Left, true, true), "0xC0");
+        assert_eq!(click_code(MouseButton::Right, true, false), "0x41");
+        assert_eq!(click_code(MouseButton::Middle, false, true), "0x82");
+    }
+
+    #[test]
+    fn probe_reports_the_backend_for_the_session() {
+        let wayland = probe(SessionType::Wayland, true, Ok(()));
+        assert_eq!((wayland.session.as_str(), wayland.backend.as_str()), ("wayland", "ydotool"));
+        assert!(wayland.mouse && wayland.keyboard && wayland.text);
+
+        let xwayland_only = probe(SessionType::Wayland, true, Err("ydotool is not installed".into()));
+        assert_eq!(xwayland_only.backend, "xtest");
+        assert!(xwayland_only.hint.unwrap().contains("XWayland"));
+
+        let none = probe(SessionType::Wayland, false, Err("ydotool is not installed".into()));
+        assert_eq!(none.backend, "none");
+        assert!(!none.mouse && !none.keyboard && !none.text);
+
+        let x11 = probe(SessionType::X11, true, Err("not a Wayland session".into()));
+        assert_eq!((x11.backend.as_str(), x11.hint), ("xtest", None));
+    }
+}
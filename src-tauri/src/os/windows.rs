@@ -1,8 +1,11 @@
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use crate::domain::{
-    Automation, BackendError, DisplayInfo, MouseButton, Region, ScreenCapture, ScreenFrame,
+    Automation, BackendError, DisplayInfo, ElementFinder, ElementQuery, Error, MouseButton, Region,
+    ScreenCapture, ScreenFrame, UiElement, WindowEnumerator, WindowInfo,
 };
+#[cfg(target_os = "windows")]
+use crate::domain::{canonical_role, ELEMENT_SEARCH_MAX_DEPTH, ELEMENT_SEARCH_MAX_NODES};
 use screenshots::{display_info::DisplayInfo as RawDisplayInfo, Screen};
 #[cfg(target_os = "windows")]
 use std::mem::size_of;
@@ -10,13 +13,32 @@ use std::mem::size_of;
 use windows::core::Error as WinError;
 #[cfg(target_os = "windows")]
 use windows::Win32::UI::Input::KeyboardAndMouse::{
-    SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, INPUT_MOUSE, KEYBDINPUT, KEYBD_EVENT_FLAGS,
-    KEYEVENTF_KEYUP, KEYEVENTF_UNICODE, MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP,
-    MOUSEEVENTF_MIDDLEDOWN, MOUSEEVENTF_MIDDLEUP, MOUSEEVENTF_RIGHTDOWN, MOUSEEVENTF_RIGHTUP,
-    MOUSEINPUT, MOUSE_EVENT_FLAGS, VIRTUAL_KEY, VK_BACK, VK_ESCAPE, VK_RETURN, VK_SPACE, VK_TAB,
+    GetKeyboardLayout, SendInput, VkKeyScanExW, INPUT, INPUT_0, INPUT_KEYBOARD, INPUT_MOUSE,
+    KEYBDINPUT, KEYBD_EVENT_FLAGS, KEYEVENTF_KEYUP, KEYEVENTF_UNICODE, MOUSEEVENTF_LEFTDOWN,
+    MOUSEEVENTF_LEFTUP, MOUSEEVENTF_MIDDLEDOWN, MOUSEEVENTF_MIDDLEUP, MOUSEEVENTF_RIGHTDOWN,
+    MOUSEEVENTF_RIGHTUP, MOUSEINPUT, MOUSE_EVENT_FLAGS, VIRTUAL_KEY, VK_BACK, VK_CONTROL,
+    VK_ESCAPE, VK_MENU, VK_RETURN, VK_SHIFT, VK_SPACE, VK_TAB,
+};
+#[cfg(target_os = "windows")]
+use windows::Win32::Foundation::{CloseHandle, BOOL, HWND, LPARAM, POINT, RECT};
+#[cfg(target_os = "windows")]
+use windows::Win32::System::Threading::{
+    OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
 };
 #[cfg(target_os = "windows")]
-use windows::Win32::UI::WindowsAndMessaging::SetCursorPos;
+use windows::Win32::System::Com::{
+    CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_MULTITHREADED,
+};
+#[cfg(target_os = "windows")]
+use windows::Win32::UI::Accessibility::{
+    CUIAutomation, IUIAutomation, IUIAutomationElement, IUIAutomationInvokePattern,
+    IUIAutomationTreeWalker, IUIAutomationValuePattern, UIA_InvokePatternId, UIA_ValuePatternId,
+};
+#[cfg(target_os = "windows")]
+use windows::Win32::UI::WindowsAndMessaging::{
+    EnumWindows, GetCursorPos, GetForegroundWindow, GetWindowRect, GetWindowTextLengthW, GetWindowTextW,
+    GetWindowThreadProcessId, IsIconic, IsWindowVisible, SetCursorPos,
+};
 
 pub struct WinCapture;
 impl ScreenCapture for WinCapture {
@@ -41,6 +63,12 @@ impl ScreenCapture for WinCapture {
         })
     }
 
+    fn capture_and_hash(&self, region: &Region, downscale: u32) -> Result<(ScreenFrame, u64), BackendError> {
+        let frame = self.capture_region(region)?;
+        let hash = hash_pixels(&frame.bytes, frame.width, frame.height, downscale);
+        Ok((frame, hash))
+    }
+
     fn displays(&self) -> Result<Vec<DisplayInfo>, BackendError> {
         let screens =
             Screen::all().map_err(|e| BackendError::new("win_displays_failed", e.to_string()))?;
@@ -176,6 +204,252 @@ fn hash_pixels(bytes: &[u8], width: u32, height: u32, downscale: u32) -> u64 {
     hash
 }
 
+#[cfg(target_os = "windows")]
+unsafe extern "system" fn collect_window(hwnd: HWND, lparam: LPARAM) -> BOOL {
+    let handles = &mut *(lparam.0 as *mut Vec<HWND>);
+    handles.push(hwnd);
+    BOOL(1)
+}
+
+#[cfg(target_os = "windows")]
+fn window_title(hwnd: HWND) -> String {
+    unsafe {
+        let len = GetWindowTextLengthW(hwnd);
+        if len <= 0 {
+            return String::new();
+        }
+        let mut buf = vec![0u16; len as usize + 1];
+        let copied = GetWindowTextW(hwnd, &mut buf);
+        String::from_utf16_lossy(&buf[..copied.max(0) as usize])
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn process_name(pid: u32) -> String {
+    unsafe {
+        let Ok(handle) = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) else {
+            return String::new();
+        };
+        let mut buf = vec![0u16; 1024];
+        let mut len = buf.len() as u32;
+        let ok = QueryFullProcessImageNameW(
+            handle,
+            PROCESS_NAME_WIN32,
+            windows::core::PWSTR(buf.as_mut_ptr()),
+            &mut len,
+        )
+        .is_ok();
+        let _ = CloseHandle(handle);
+        if ok {
+            process_name_from_path(&String::from_utf16_lossy(&buf[..len as usize]))
+        } else {
+            String::new()
+        }
+    }
+}
+
+/// Executable name without directory or `.exe`, e.g. `notepad` for `C:\Windows\notepad.exe`
+#[cfg(any(target_os = "windows", test))]
+fn process_name_from_path(path: &str) -> String {
+    let file = path.rsplit(['\\', '/']).next().unwrap_or(path);
+    match file.len().checked_sub(4) {
+        Some(stem) if file.is_char_boundary(stem) && file[stem..].eq_ignore_ascii_case(".exe") => {
+            file[..stem].to_string()
+        }
+        _ => file.to_string(),
+    }
+}
+
+#[cfg(target_os = "windows")]
+impl WindowEnumerator for WinCapture {
+    fn windows(&self) -> Result<Vec<WindowInfo>, BackendError> {
+        let mut handles: Vec<HWND> = Vec::new();
+        unsafe {
+            EnumWindows(
+                Some(collect_window),
+                LPARAM(&mut handles as *mut Vec<HWND> as isize),
+            )
+        }
+        .map_err(|e| BackendError::new("win_windows_failed", e.to_string()))?;
+        let mut windows = Vec::new();
+        for hwnd in handles {
+            if !unsafe { IsWindowVisible(hwnd) }.as_bool() {
+                continue;
+            }
+            let title = window_title(hwnd);
+            if title.is_empty() {
+                continue;
+            }
+            let mut pid = 0u32;
+            unsafe { GetWindowThreadProcessId(hwnd, Some(&mut pid as *mut u32)) };
+            let mut rect = RECT::default();
+            if unsafe { GetWindowRect(hwnd, &mut rect) }.is_err() {
+                continue;
+            }
+            windows.push(WindowInfo {
+                id: hwnd.0 as usize as u64,
+                title,
+                process_name: process_name(pid),
+                pid,
+                x: rect.left,
+                y: rect.top,
+                width: (rect.right - rect.left).max(0) as u32,
+                height: (rect.bottom - rect.top).max(0) as u32,
+                minimized: unsafe { IsIconic(hwnd) }.as_bool(),
+            });
+        }
+        Ok(windows)
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+impl WindowEnumerator for WinCapture {
+    fn windows(&self) -> Result<Vec<WindowInfo>, BackendError> {
+        Err(BackendError::new(
+            "win_windows_unsupported",
+            "window listing requires building on Windows",
+        ))
+    }
+}
+
+/// Role name of a `UIA_*ControlTypeId`, in the order of the ids starting at 50000
+#[cfg(any(target_os = "windows", test))]
+fn uia_role(control_type: i32) -> &'static str {
+    const ROLES: [&str; 39] = [
+        "button", "calendar", "check box", "combo box", "edit", "hyperlink", "image",
+        "list item", "list", "menu", "menu bar", "menu item", "progress bar", "radio button",
+        "scroll bar", "slider", "spinner", "status bar", "tab list", "tab item", "text",
+        "tool bar", "tool tip", "tree", "tree item", "custom", "group", "thumb", "data grid",
+        "data item", "document", "split button", "window", "pane", "header", "header item",
+        "table", "title bar", "separator",
+    ];
+    control_type
+        .checked_sub(50000)
+        .and_then(|i| usize::try_from(i).ok())
+        .and_then(|i| ROLES.get(i))
+        .copied()
+        .unwrap_or("unknown")
+}
+
+#[cfg(target_os = "windows")]
+fn uia_children(walker: &IUIAutomationTreeWalker, parent: &IUIAutomationElement) -> Vec<IUIAutomationElement> {
+    let mut children = Vec::new();
+    let mut next = unsafe { walker.GetFirstChildElement(parent) }.ok();
+    while let Some(child) = next {
+        next = unsafe { walker.GetNextSiblingElement(&child) }.ok();
+        children.push(child);
+    }
+    children
+}
+
+/// Value of text fields and similar, through the Value pattern
+#[cfg(target_os = "windows")]
+fn uia_value(element: &IUIAutomationElement) -> Option<String> {
+    let pattern: IUIAutomationValuePattern = unsafe { element.GetCurrentPatternAs(UIA_ValuePatternId) }.ok()?;
+    let value = unsafe { pattern.CurrentValue() }.ok()?.to_string();
+    (!value.is_empty()).then_some(value)
+}
+
+#[cfg(target_os = "windows")]
+impl WinCapture {
+    /// Walk the control view and hand every visible match to `visit` until it returns false
+    fn search_elements(
+        &self,
+        query: &ElementQuery,
+        mut visit: impl FnMut(&IUIAutomationElement, UiElement) -> Result<bool, BackendError>,
+    ) -> Result<(), BackendError> {
+        query.validate().map_err(|e| BackendError::new("invalid_query", e))?;
+        let uia_error = |e: WinError| BackendError::new("win_uia_failed", e.to_string());
+        // UI Automation is COM; an already initialised apartment on this thread is fine
+        let _ = unsafe { CoInitializeEx(None, COINIT_MULTITHREADED) };
+        let automation: IUIAutomation =
+            unsafe { CoCreateInstance(&CUIAutomation, None, CLSCTX_INPROC_SERVER) }.map_err(uia_error)?;
+        let walker = unsafe { automation.ControlViewWalker() }.map_err(uia_error)?;
+        let root = unsafe { automation.GetRootElement() }.map_err(uia_error)?;
+        let mut visited = 0;
+        // Children of the desktop are the top-level windows
+        for window in uia_children(&walker, &root) {
+            let app = unsafe { window.CurrentProcessId() }
+                .map(|pid| process_name(pid as u32))
+                .unwrap_or_default();
+            if !query.matches_app(&app) {
+                continue;
+            }
+            let mut stack = vec![(window, 0usize)];
+            while let Some((element, depth)) = stack.pop() {
+                visited += 1;
+                if visited > ELEMENT_SEARCH_MAX_NODES {
+                    return Ok(());
+                }
+                if unsafe { element.CurrentIsOffscreen() }.is_ok_and(|offscreen| offscreen.as_bool()) {
+                    continue;
+                }
+                let control_type = unsafe { element.CurrentControlType() }.map(|t| t.0).unwrap_or_default();
+                let role = canonical_role(uia_role(control_type));
+                if query.matches_role(&role) {
+                    let name = unsafe { element.CurrentName() }.map(|n| n.to_string()).unwrap_or_default();
+                    let rect = unsafe { element.CurrentBoundingRectangle() }.unwrap_or_default();
+                    let (width, height) = (rect.right - rect.left, rect.bottom - rect.top);
+                    if query.matches_name(&name) && width > 0 && height > 0 {
+                        let found = UiElement {
+                            role,
+                            text: uia_value(&element).unwrap_or_else(|| name.clone()),
+                            name,
+                            app: app.clone(),
+                            x: rect.left,
+                            y: rect.top,
+                            width: width as u32,
+                            height: height as u32,
+                        };
+                        if !visit(&element, found)? {
+                            return Ok(());
+                        }
+                    }
+                }
+                if depth < ELEMENT_SEARCH_MAX_DEPTH {
+                    let children = uia_children(&walker, &element);
+                    stack.extend(children.into_iter().rev().map(|child| (child, depth + 1)));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "windows")]
+impl ElementFinder for WinCapture {
+    fn find(&self, query: &ElementQuery) -> Result<Vec<UiElement>, BackendError> {
+        let mut found = Vec::new();
+        self.search_elements(query, |_, element| {
+            found.push(element);
+            Ok(true)
+        })?;
+        Ok(found)
+    }
+
+    fn invoke(&self, query: &ElementQuery) -> Result<UiElement, BackendError> {
+        let mut invoked = None;
+        self.search_elements(query, |element, found| {
+            let pattern: IUIAutomationInvokePattern = unsafe { element.GetCurrentPatternAs(UIA_InvokePatternId) }
+                .map_err(|_| BackendError::new("win_uia_no_invoke", format!("{} cannot be invoked", query)))?;
+            unsafe { pattern.Invoke() }.map_err(|e| BackendError::new("win_uia_failed", e.to_string()))?;
+            invoked = Some(found);
+            Ok(false)
+        })?;
+        invoked.ok_or_else(|| BackendError::new("element_not_found", format!("no {} found", query)))
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+impl ElementFinder for WinCapture {
+    fn find(&self, _query: &ElementQuery) -> Result<Vec<UiElement>, BackendError> {
+        Err(BackendError::new(
+            "win_uia_unsupported",
+            "element lookup requires building on Windows",
+        ))
+    }
+}
+
 #[cfg(any(target_os = "windows", test))]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum NamedKey {
@@ -222,6 +496,32 @@ fn classify_key(raw: &str) -> Result<KeySpec, String> {
     ))
 }
 
+/// Virtual key of a character on a keyboard layout, with the modifiers it needs
+#[cfg(any(target_os = "windows", test))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct LayoutKey {
+    vk: u16,
+    shift: bool,
+    ctrl: bool,
+    alt: bool,
+}
+
+/// Decode `VkKeyScanExW`: virtual key in the low byte, shift state in the high
+/// byte (1 Shift, 2 Ctrl, 4 Alt; AltGr is Ctrl+Alt). -1 means no key.
+#[cfg(any(target_os = "windows", test))]
+fn decode_vk_scan(scan: i16) -> Option<LayoutKey> {
+    let [vk, state] = (scan as u16).to_le_bytes();
+    if scan == -1 || vk == 0xff {
+        return None;
+    }
+    Some(LayoutKey {
+        vk: vk as u16,
+        shift: state & 1 != 0,
+        ctrl: state & 2 != 0,
+        alt: state & 4 != 0,
+    })
+}
+
 pub struct WinAutomation;
 
 #[cfg(target_os = "windows")]
@@ -341,6 +641,49 @@ impl WinAutomation {
         Ok(())
     }
 
+    /// The key producing `ch` on the foreground window's layout; keyboard
+    /// layouts are per thread, so the target app's may differ from ours
+    fn layout_key(ch: char) -> Option<LayoutKey> {
+        let mut buf = [0u16; 2];
+        let [unit] = ch.encode_utf16(&mut buf) else {
+            return None;
+        };
+        unsafe {
+            let thread = GetWindowThreadProcessId(GetForegroundWindow(), None);
+            decode_vk_scan(VkKeyScanExW(*unit, GetKeyboardLayout(thread)))
+        }
+    }
+
+    /// Press a layout key with its modifiers held, or release it and then the modifiers
+    fn send_layout_key(key: LayoutKey, key_up: bool) -> Result<(), String> {
+        let modifiers: Vec<VIRTUAL_KEY> = [(key.shift, VK_SHIFT), (key.ctrl, VK_CONTROL), (key.alt, VK_MENU)]
+            .into_iter()
+            .filter_map(|(held, vk)| held.then_some(vk))
+            .collect();
+        let mut inputs: Vec<INPUT> = if key_up {
+            std::iter::once(VIRTUAL_KEY(key.vk))
+                .chain(modifiers.into_iter().rev())
+                .map(|vk| Self::key_input(vk, true))
+                .collect()
+        } else {
+            modifiers
+                .into_iter()
+                .chain(std::iter::once(VIRTUAL_KEY(key.vk)))
+                .map(|vk| Self::key_input(vk, false))
+                .collect()
+        };
+        Self::dispatch(&mut inputs)
+    }
+
+    /// Characters go through their virtual key so shortcuts held with
+    /// `key_down` reach the app; characters without a key are sent as Unicode
+    fn send_char(ch: char, key_up: bool) -> Result<(), String> {
+        match Self::layout_key(ch) {
+            Some(key) => Self::send_layout_key(key, key_up),
+            None => Self::send_unicode_for_char(ch, key_up),
+        }
+    }
+
     fn tap_unicode_char(ch: char) -> Result<(), String> {
         let mut buf = [0u16; 2];
         let units = ch.encode_utf16(&mut buf);
@@ -357,16 +700,16 @@ impl WinAutomation {
 
 #[cfg(target_os = "windows")]
 impl Automation for WinAutomation {
-    fn move_cursor(&self, x: u32, y: u32) -> Result<(), String> {
-        Self::set_cursor_pos(x, y)
+    fn move_cursor(&self, x: u32, y: u32) -> Result<(), Error> {
+        Self::set_cursor_pos(x, y).map_err(Error::Automation)
     }
 
-    fn click(&self, button: MouseButton) -> Result<(), String> {
+    fn click(&self, button: MouseButton) -> Result<(), Error> {
         self.mouse_down(button)?;
         self.mouse_up(button)
     }
 
-    fn type_text(&self, text: &str) -> Result<(), String> {
+    fn type_text(&self, text: &str) -> Result<(), Error> {
         for ch in text.chars() {
             match ch {
                 '\r' => {}
@@ -376,50 +719,53 @@ impl Automation for WinAutomation {
                 '\t' => {
                     self.key("Tab")?;
                 }
-                _ => Self::tap_unicode_char(ch)?,
+                _ => Self::tap_unicode_char(ch).map_err(Error::Automation)?,
             }
         }
         Ok(())
     }
 
-    fn key(&self, key: &str) -> Result<(), String> {
-        let spec = classify_key(key)?;
+    fn key(&self, key: &str) -> Result<(), Error> {
+        let spec = classify_key(key).map_err(Error::Config)?;
         match spec {
-            KeySpec::Named(named) => {
-                Self::send_named_key(named, false)?;
-                Self::send_named_key(named, true)
-            }
-            KeySpec::Char(ch) => {
-                Self::send_unicode_for_char(ch, false)?;
-                Self::send_unicode_for_char(ch, true)
-            }
+            KeySpec::Named(named) => Self::send_named_key(named, false).and_then(|()| Self::send_named_key(named, true)),
+            KeySpec::Char(ch) => Self::send_char(ch, false).and_then(|()| Self::send_char(ch, true)),
         }
+        .map_err(Error::Automation)
     }
 
-    fn mouse_down(&self, button: MouseButton) -> Result<(), String> {
+    fn mouse_down(&self, button: MouseButton) -> Result<(), Error> {
         let (down, _) = Self::button_flags(button);
-        Self::send_mouse_flag(down)
+        Self::send_mouse_flag(down).map_err(Error::Automation)
     }
 
-    fn mouse_up(&self, button: MouseButton) -> Result<(), String> {
+    fn mouse_up(&self, button: MouseButton) -> Result<(), Error> {
         let (_, up) = Self::button_flags(button);
-        Self::send_mouse_flag(up)
+        Self::send_mouse_flag(up).map_err(Error::Automation)
     }
 
-    fn key_down(&self, key: &str) -> Result<(), String> {
-        let spec = classify_key(key)?;
+    fn key_down(&self, key: &str) -> Result<(), Error> {
+        let spec = classify_key(key).map_err(Error::Config)?;
         match spec {
             KeySpec::Named(named) => Self::send_named_key(named, false),
-            KeySpec::Char(ch) => Self::send_unicode_for_char(ch, false),
+            KeySpec::Char(ch) => Self::send_char(ch, false),
         }
+        .map_err(Error::Automation)
     }
 
-    fn key_up(&self, key: &str) -> Result<(), String> {
-        let spec = classify_key(key)?;
+    fn key_up(&self, key: &str) -> Result<(), Error> {
+        let spec = classify_key(key).map_err(Error::Config)?;
         match spec {
             KeySpec::Named(named) => Self::send_named_key(named, true),
-            KeySpec::Char(ch) => Self::send_unicode_for_char(ch, true),
+            KeySpec::Char(ch) => Self::send_char(ch, true),
         }
+        .map_err(Error::Automation)
+    }
+
+    fn cursor_position(&self) -> Result<Option<(i32, i32)>, Error> {
+        let mut at = POINT::default();
+        unsafe { GetCursorPos(&mut at) }.map_err(|e| Error::Automation(format!("GetCursorPos failed: {}", e)))?;
+        Ok(Some((at.x, at.y)))
     }
 }
 
@@ -432,42 +778,45 @@ impl WinAutomation {
 
 #[cfg(not(target_os = "windows"))]
 impl Automation for WinAutomation {
-    fn move_cursor(&self, _x: u32, _y: u32) -> Result<(), String> {
-        Self::unsupported()
+    fn move_cursor(&self, _x: u32, _y: u32) -> Result<(), Error> {
+        Self::unsupported().map_err(Error::Config)
     }
 
-    fn click(&self, _button: MouseButton) -> Result<(), String> {
-        Self::unsupported()
+    fn click(&self, _button: MouseButton) -> Result<(), Error> {
+        Self::unsupported().map_err(Error::Config)
     }
 
-    fn type_text(&self, _text: &str) -> Result<(), String> {
-        Self::unsupported()
+    fn type_text(&self, _text: &str) -> Result<(), Error> {
+        Self::unsupported().map_err(Error::Config)
     }
 
-    fn key(&self, _key: &str) -> Result<(), String> {
-        Self::unsupported()
+    fn key(&self, _key: &str) -> Result<(), Error> {
+        Self::unsupported().map_err(Error::Config)
     }
 
-    fn mouse_down(&self, _button: MouseButton) -> Result<(), String> {
-        Self::unsupported()
+    fn mouse_down(&self, _button: MouseButton) -> Result<(), Error> {
+        Self::unsupported().map_err(Error::Config)
     }
 
-    fn mouse_up(&self, _button: MouseButton) -> Result<(), String> {
-        Self::unsupported()
+    fn mouse_up(&self, _button: MouseButton) -> Result<(), Error> {
+        Self::unsupported().map_err(Error::Config)
     }
 
-    fn key_down(&self, _key: &str) -> Result<(), String> {
-        Self::unsupported()
+    fn key_down(&self, _key: &str) -> Result<(), Error> {
+        Self::unsupported().map_err(Error::Config)
     }
 
-    fn key_up(&self, _key: &str) -> Result<(), String> {
-        Self::unsupported()
+    fn key_up(&self, _key: &str) -> Result<(), Error> {
+        Self::unsupported().map_err(Error::Config)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{classify_key, hash_pixels, KeySpec, NamedKey};
+    use super::{
+        classify_key, decode_vk_scan, hash_pixels, process_name_from_path, uia_role, KeySpec, LayoutKey, NamedKey,
+    };
+    use crate::domain::canonical_role;
 
     #[test]
     fn hash_pixels_changes_with_content() {
@@ -520,4 +869,32 @@ mod tests {
         assert!(classify_key("").is_err());
         assert!(classify_key("ShiftLeft").is_err());
     }
+
+    #[test]
+    fn vk_scan_results_decode_to_key_and_modifiers() {
+        let key = |vk, shift, ctrl, alt| Some(LayoutKey { vk, shift, ctrl, alt });
+        assert_eq!(decode_vk_scan(0x0041), key(0x41, false, false, false));
+        // `A` needs Shift; `@` on a German layout is AltGr+Q
+        assert_eq!(decode_vk_scan(0x0141), key(0x41, true, false, false));
+        assert_eq!(decode_vk_scan(0x0651), key(0x51, false, true, true));
+        assert_eq!(decode_vk_scan(-1), None);
+    }
+
+    #[test]
+    fn process_name_strips_directory_and_exe() {
+        assert_eq!(process_name_from_path(r"C:\Windows\System32\notepad.exe"), "notepad");
+        assert_eq!(process_name_from_path(r"C:\Tools\Code.EXE"), "Code");
+        assert_eq!(process_name_from_path("/usr/bin/wine"), "wine");
+        assert_eq!(process_name_from_path(""), "");
+    }
+
+    #[test]
+    fn uia_control_types_map_to_shared_roles() {
+        assert_eq!(uia_role(50000), "button");
+        assert_eq!(canonical_role(uia_role(50004)), "text field");
+        assert_eq!(canonical_role(uia_role(50019)), "tab");
+        assert_eq!(uia_role(50038), "separator");
+        assert_eq!(uia_role(50039), "unknown");
+        assert_eq!(uia_role(0), "unknown");
+    }
 }
@@ -0,0 +1,548 @@
+/// OpenTelemetry traces of automation runs
+///
+/// Runs executed on many machines are easier to compare in a tracing backend
+/// than in each machine's run history. With an OTLP endpoint configured, each
+/// run becomes a trace: the `run` span is its root, with spans for the
+/// iterations, the actions they ran and the LLM calls below it. These are the
+/// spans the log records are written in, so `TraceLayer` builds the trace from
+/// the same `tracing` data. Span fields become attributes (profile, iteration,
+/// action, region IDs, risk, success, outcome) and warnings and errors
+/// recorded in a span become its events.
+///
+/// Finished spans are queued and an exporter thread posts them in batches as
+/// OTLP/HTTP JSON to `<endpoint>/v1/traces`, with the configured headers. The
+/// headers usually carry the backend's API key, so the settings live in secure
+/// storage. A full queue drops spans rather than slowing a run down. The spans
+/// are `info` spans: with the log level below `info`, runs are not traced.
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+/// Spans that make up a trace; other spans are left to the logs
+pub const TRACED_SPANS: &[&str] = &["run", "iteration", "action", "llm_call"];
+/// Finished spans waiting for the exporter; more are dropped
+pub const MAX_QUEUED: usize = 4096;
+/// Most spans sent in one request
+#[cfg(feature = "otlp-traces")]
+pub const MAX_BATCH: usize = 512;
+/// Longest a finished span waits to be sent
+#[cfg(feature = "otlp-traces")]
+pub const FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+/// How long stopping the exporter waits for the last batch to be sent
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(3);
+#[cfg(feature = "otlp-traces")]
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Where traces are sent
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OtlpConfig {
+    /// Base URL of the collector's OTLP/HTTP receiver, e.g. `http://collector:4318`
+    pub endpoint: String,
+    /// Sent with every request, e.g. the backend's API key
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+}
+
+impl OtlpConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.endpoint.starts_with("http://") && !self.endpoint.starts_with("https://") {
+            return Err(format!("OTLP endpoint must be an http:// or https:// URL, got '{}'", self.endpoint));
+        }
+        for (name, value) in &self.headers {
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+                return Err(format!("Invalid header name '{}'", name));
+            }
+            if value.chars().any(|c| c.is_control()) {
+                return Err(format!("Header {} contains control characters", name));
+            }
+        }
+        Ok(())
+    }
+
+    /// `/v1/traces` under the endpoint, unless the endpoint names it already
+    pub fn traces_url(&self) -> String {
+        let base = self.endpoint.trim_end_matches('/');
+        if base.ends_with("/v1/traces") {
+            base.to_string()
+        } else {
+            format!("{}/v1/traces", base)
+        }
+    }
+}
+
+/// What the exporter has done since it was started
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExportStatus {
+    pub url: String,
+    /// Spans the collector accepted
+    pub exported: u64,
+    /// Spans lost to a full queue or a failed request
+    pub dropped: u64,
+    /// Why the last request failed, until one succeeds
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttributeValue {
+    String(String),
+    Int(i64),
+    Double(f64),
+    Bool(bool),
+}
+
+impl AttributeValue {
+    fn otlp(&self) -> serde_json::Value {
+        match self {
+            AttributeValue::String(s) => json!({ "stringValue": s }),
+            // 64-bit integers are strings in the JSON encoding of protobuf
+            AttributeValue::Int(i) => json!({ "intValue": i.to_string() }),
+            AttributeValue::Double(d) => json!({ "doubleValue": d }),
+            AttributeValue::Bool(b) => json!({ "boolValue": b }),
+        }
+    }
+}
+
+pub type SpanAttributes = Vec<(&'static str, AttributeValue)>;
+
+/// A warning or error written while a span was open
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpanEvent {
+    pub at: SystemTime,
+    pub message: String,
+    pub attributes: SpanAttributes,
+}
+
+/// A traced span, finished once `end` is set
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpanRecord {
+    pub trace_id: u128,
+    pub span_id: u64,
+    pub parent_span_id: Option<u64>,
+    pub name: &'static str,
+    pub start: SystemTime,
+    pub end: SystemTime,
+    /// The span's fields by name; recording a field again replaces it
+    pub attributes: SpanAttributes,
+    pub events: Vec<SpanEvent>,
+}
+
+impl SpanRecord {
+    pub fn attribute(&self, name: &str) -> Option<&AttributeValue> {
+        self.attributes.iter().find(|(n, _)| *n == name).map(|(_, v)| v)
+    }
+}
+
+/// Collects fields into attributes
+struct Fields<'a>(&'a mut SpanAttributes);
+
+impl Fields<'_> {
+    fn set(&mut self, field: &Field, value: AttributeValue) {
+        match self.0.iter_mut().find(|(n, _)| *n == field.name()) {
+            Some((_, v)) => *v = value,
+            None => self.0.push((field.name(), value)),
+        }
+    }
+}
+
+impl Visit for Fields<'_> {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.set(field, AttributeValue::Double(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.set(field, AttributeValue::Int(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.set(field, AttributeValue::Int(value.min(i64::MAX as u64) as i64));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.set(field, AttributeValue::Bool(value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.set(field, AttributeValue::String(value.to_string()));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.set(field, AttributeValue::String(format!("{:?}", value)));
+    }
+}
+
+/// Counters shared with the exporter thread
+#[derive(Default)]
+struct Stats {
+    exported: AtomicU64,
+    dropped: AtomicU64,
+    last_error: Mutex<Option<String>>,
+}
+
+struct Exporter {
+    url: String,
+    stats: Arc<Stats>,
+    /// Signalled when the thread has sent its last batch
+    done: mpsc::Receiver<()>,
+}
+
+/// Hands finished spans to the exporter, while one is running
+#[derive(Default)]
+pub struct Tracer {
+    queue: Mutex<Option<(SyncSender<SpanRecord>, Arc<Stats>)>>,
+    exporter: Mutex<Option<Exporter>>,
+}
+
+impl Tracer {
+    /// Whether spans are traced at all
+    pub fn is_enabled(&self) -> bool {
+        self.queue.lock().unwrap().is_some()
+    }
+
+    /// Send finished spans to `queue` from now on, without exporting them
+    #[cfg(test)]
+    pub fn connect(&self, queue: SyncSender<SpanRecord>) {
+        *self.queue.lock().unwrap() = Some((queue, Arc::default()));
+    }
+
+    fn submit(&self, span: SpanRecord) {
+        if let Some((queue, stats)) = self.queue.lock().unwrap().as_ref() {
+            if let Err(TrySendError::Full(_)) = queue.try_send(span) {
+                stats.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Export to `config` from now on, or stop exporting; returns the traces URL
+    ///
+    /// The exporter running until now is stopped once the new one started.
+    pub fn start(&self, config: Option<&OtlpConfig>) -> Result<Option<String>, String> {
+        let Some(config) = config else {
+            self.stop();
+            return Ok(None);
+        };
+        config.validate()?;
+        let (queue, spans) = mpsc::sync_channel(MAX_QUEUED);
+        let exporter = spawn_exporter(config, spans, Arc::default())?;
+        self.stop();
+        let url = exporter.url.clone();
+        *self.queue.lock().unwrap() = Some((queue, exporter.stats.clone()));
+        *self.exporter.lock().unwrap() = Some(exporter);
+        Ok(Some(url))
+    }
+
+    /// Stop tracing and give the exporter `FLUSH_TIMEOUT` to send what is queued
+    pub fn stop(&self) {
+        self.queue.lock().unwrap().take();
+        let exporter = self.exporter.lock().unwrap().take();
+        if let Some(exporter) = exporter {
+            let _ = exporter.done.recv_timeout(FLUSH_TIMEOUT);
+        }
+    }
+
+    /// The running exporter's URL and counters
+    pub fn status(&self) -> Option<ExportStatus> {
+        let exporter = self.exporter.lock().unwrap();
+        exporter.as_ref().map(|e| ExportStatus {
+            url: e.url.clone(),
+            exported: e.stats.exported.load(Ordering::Relaxed),
+            dropped: e.stats.dropped.load(Ordering::Relaxed),
+            last_error: e.stats.last_error.lock().unwrap().clone(),
+        })
+    }
+}
+
+/// The process's tracer, fed by the layer `logging::init` installs
+pub fn tracer() -> Arc<Tracer> {
+    static TRACER: OnceLock<Arc<Tracer>> = OnceLock::new();
+    TRACER.get_or_init(Default::default).clone()
+}
+
+/// Records the traced spans and hands them to a `Tracer` when they close
+pub struct TraceLayer {
+    tracer: Arc<Tracer>,
+}
+
+impl TraceLayer {
+    pub fn new(tracer: Arc<Tracer>) -> Self {
+        Self { tracer }
+    }
+}
+
+impl<S> Layer<S> for TraceLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let name = attrs.metadata().name();
+        if !TRACED_SPANS.contains(&name) || !self.tracer.is_enabled() {
+            return;
+        }
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        // The nearest traced ancestor; the watchdog's worker threads enter the action span, so this holds across them
+        let parent = span
+            .scope()
+            .skip(1)
+            .find_map(|s| s.extensions().get::<SpanRecord>().map(|p| (p.trace_id, p.span_id)));
+        let now = SystemTime::now();
+        let mut record = SpanRecord {
+            trace_id: parent.map_or_else(new_trace_id, |(trace_id, _)| trace_id),
+            span_id: new_span_id(),
+            parent_span_id: parent.map(|(_, span_id)| span_id),
+            name,
+            start: now,
+            end: now,
+            attributes: Vec::new(),
+            events: Vec::new(),
+        };
+        attrs.record(&mut Fields(&mut record.attributes));
+        span.extensions_mut().insert(record);
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(record) = span.extensions_mut().get_mut::<SpanRecord>() {
+                values.record(&mut Fields(&mut record.attributes));
+            }
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        if *event.metadata().level() > Level::WARN {
+            return;
+        }
+        let Some(span) = ctx.event_span(event) else {
+            return;
+        };
+        for s in span.scope() {
+            let mut extensions = s.extensions_mut();
+            if let Some(record) = extensions.get_mut::<SpanRecord>() {
+                let mut attributes = Vec::new();
+                event.record(&mut Fields(&mut attributes));
+                let message = match attributes.iter().position(|(n, _)| *n == "message") {
+                    Some(i) => match attributes.remove(i).1 {
+                        AttributeValue::String(m) => m,
+                        other => format!("{:?}", other),
+                    },
+                    None => String::new(),
+                };
+                attributes.push(("level", AttributeValue::String(event.metadata().level().to_string())));
+                record.events.push(SpanEvent {
+                    at: SystemTime::now(),
+                    message,
+                    attributes,
+                });
+                return;
+            }
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let record = span.extensions_mut().remove::<SpanRecord>();
+        if let Some(mut record) = record {
+            record.end = SystemTime::now();
+            self.tracer.submit(record);
+        }
+    }
+}
+
+fn new_trace_id() -> u128 {
+    use aes_gcm::aead::rand_core::RngCore;
+    let mut bytes = [0u8; 16];
+    aes_gcm::aead::OsRng.fill_bytes(&mut bytes);
+    // All zeroes is the invalid id
+    u128::from_le_bytes(bytes).max(1)
+}
+
+fn new_span_id() -> u64 {
+    use aes_gcm::aead::rand_core::RngCore;
+    aes_gcm::aead::OsRng.next_u64().max(1)
+}
+
+/// What every exported span is about: this app on this host
+pub fn resource() -> SpanAttributes {
+    vec![
+        ("service.name", AttributeValue::String("loopautoma".into())),
+        ("service.version", AttributeValue::String(env!("CARGO_PKG_VERSION").into())),
+        ("host.name", AttributeValue::String(host_name())),
+        ("os.type", AttributeValue::String(std::env::consts::OS.into())),
+    ]
+}
+
+fn host_name() -> String {
+    std::env::var("HOSTNAME")
+        .or_else(|_| std::env::var("COMPUTERNAME"))
+        .ok()
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+        .map(|h| h.trim().to_string())
+        .filter(|h| !h.is_empty())
+        .unwrap_or_else(|| "unknown".into())
+}
+
+/// Attribute name of a span field: namespaced, with the iteration's `n` spelled out
+fn attribute_key(span: &str, field: &str) -> String {
+    match (span, field) {
+        ("iteration", "n") => "loopautoma.iteration".to_string(),
+        _ => format!("loopautoma.{}", field),
+    }
+}
+
+fn unix_nanos(at: SystemTime) -> String {
+    at.duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos().to_string()
+}
+
+fn key_values<'a>(attributes: impl Iterator<Item = (String, &'a AttributeValue)>) -> Vec<serde_json::Value> {
+    attributes.map(|(key, value)| json!({ "key": key, "value": value.otlp() })).collect()
+}
+
+/// OK when the span recorded `success = true`, an error with its `error` field when `false`
+fn status(span: &SpanRecord) -> serde_json::Value {
+    match span.attribute("success") {
+        Some(AttributeValue::Bool(true)) => json!({ "code": 1 }),
+        Some(AttributeValue::Bool(false)) => match span.attribute("error") {
+            Some(AttributeValue::String(message)) => json!({ "code": 2, "message": message }),
+            _ => json!({ "code": 2 }),
+        },
+        _ => json!({}),
+    }
+}
+
+/// The body of an OTLP/HTTP JSON export request
+pub fn otlp_json(resource: &SpanAttributes, spans: &[SpanRecord]) -> serde_json::Value {
+    let spans: Vec<serde_json::Value> = spans
+        .iter()
+        .map(|span| {
+            let mut otlp = json!({
+                "traceId": format!("{:032x}", span.trace_id),
+                "spanId": format!("{:016x}", span.span_id),
+                "name": span.name,
+                // SPAN_KIND_INTERNAL
+                "kind": 1,
+                "startTimeUnixNano": unix_nanos(span.start),
+                "endTimeUnixNano": unix_nanos(span.end),
+                "attributes": key_values(span.attributes.iter().map(|(k, v)| (attribute_key(span.name, k), v))),
+                "events": span.events.iter().map(|e| json!({
+                    "timeUnixNano": unix_nanos(e.at),
+                    "name": e.message,
+                    "attributes": key_values(e.attributes.iter().map(|(k, v)| (k.to_string(), v))),
+                })).collect::<Vec<_>>(),
+                "status": status(span),
+            });
+            if let Some(parent) = span.parent_span_id {
+                otlp["parentSpanId"] = json!(format!("{:016x}", parent));
+            }
+            otlp
+        })
+        .collect();
+    json!({
+        "resourceSpans": [{
+            "resource": { "attributes": key_values(resource.iter().map(|(k, v)| (k.to_string(), v))) },
+            "scopeSpans": [{
+                "scope": { "name": "loopautoma", "version": env!("CARGO_PKG_VERSION") },
+                "spans": spans,
+            }],
+        }],
+    })
+}
+
+/// Start the thread that sends the spans of `spans` to `config` in batches
+#[cfg(feature = "otlp-traces")]
+fn spawn_exporter(config: &OtlpConfig, spans: mpsc::Receiver<SpanRecord>, stats: Arc<Stats>) -> Result<Exporter, String> {
+    use std::sync::mpsc::RecvTimeoutError;
+    use std::time::Instant;
+
+    let runtime = crate::runtime::AsyncRuntime::shared()?;
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let url = config.traces_url();
+    let headers = config.headers.clone();
+    let (finished, done) = mpsc::channel();
+    let target = url.clone();
+    let exported = stats.clone();
+    std::thread::Builder::new()
+        .name("loopautoma-otlp".into())
+        .spawn(move || {
+            let resource = resource();
+            let mut batch = Vec::new();
+            let mut deadline = Instant::now() + FLUSH_INTERVAL;
+            loop {
+                let closed = match spans.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                    Ok(span) => {
+                        batch.push(span);
+                        false
+                    }
+                    Err(RecvTimeoutError::Timeout) => false,
+                    // Stopped, and every queued span was received
+                    Err(RecvTimeoutError::Disconnected) => true,
+                };
+                let due = Instant::now() >= deadline;
+                if !batch.is_empty() && (closed || due || batch.len() >= MAX_BATCH) {
+                    let body = otlp_json(&resource, &batch).to_string().into_bytes();
+                    match runtime.block_on(post(&client, &target, &headers, body)) {
+                        Ok(()) => {
+                            stats.exported.fetch_add(batch.len() as u64, Ordering::Relaxed);
+                            stats.last_error.lock().unwrap().take();
+                        }
+                        Err(e) => {
+                            tracing::warn!("Trace export failed: {}", e);
+                            stats.dropped.fetch_add(batch.len() as u64, Ordering::Relaxed);
+                            *stats.last_error.lock().unwrap() = Some(e);
+                        }
+                    }
+                    batch.clear();
+                }
+                if closed {
+                    break;
+                }
+                if due {
+                    deadline = Instant::now() + FLUSH_INTERVAL;
+                }
+            }
+            let _ = finished.send(());
+        })
+        .map_err(|e| format!("Failed to start the trace exporter: {}", e))?;
+    Ok(Exporter { url, stats: exported, done })
+}
+
+#[cfg(feature = "otlp-traces")]
+async fn post(client: &reqwest::Client, url: &str, headers: &BTreeMap<String, String>, body: Vec<u8>) -> Result<(), String> {
+    let mut request = client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(body);
+    for (name, value) in headers {
+        request = request.header(name.as_str(), value.as_str());
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("OTLP request failed: {}", e.without_url()))?;
+    let status = response.status();
+    if status.is_success() {
+        Ok(())
+    } else {
+        let detail = response.text().await.unwrap_or_default();
+        Err(format!("OTLP collector rejected spans with {}: {}", status, detail.trim()))
+    }
+}
+
+#[cfg(not(feature = "otlp-traces"))]
+fn spawn_exporter(_config: &OtlpConfig, _spans: mpsc::Receiver<SpanRecord>, _stats: Arc<Stats>) -> Result<Exporter, String> {
+    Err("This build has no trace exporter (feature 'otlp-traces')".into())
+}
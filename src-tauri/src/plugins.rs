@@ -0,0 +1,632 @@
+/// Third-party actions, triggers and notifiers
+///
+/// Niche integrations live in plugins rather than in the core crate. A plugin
+/// is a folder in `plugins/` under the config directory holding a
+/// `plugin.json` manifest and a native library. The manifest names the
+/// plugin, the actions, triggers and notifiers it registers and the
+/// capabilities its actions need. Manifests are read at startup without
+/// running any plugin code. Only the plugins enabled in the `plugins.enabled`
+/// setting are loaded. Enabling one is a decision about running third-party
+/// code, so settings imports do not carry that list.
+///
+/// The library exports `loopautoma_plugin_v1`, returning a `PluginApi`. Every
+/// request from the app and every answer is a JSON string, so plugins can be
+/// written in any language with a C ABI:
+///
+/// - `{"call": "action", "kind", "config", "variables"}` answered with
+///   `{"variables": {...}}` to set context variables
+/// - `{"call": "trigger", "kind", "config"}` answered with `{"fire": bool}`,
+///   asked once per `check_interval_sec`
+/// - `{"call": "notify", "kind", "notice": {...}}` for the `plugin` channel
+///
+/// Any answer may be `{"error": "..."}`. While it handles a request, a plugin
+/// may call back through the `HostApi` it is handed: `log` always, input
+/// (`move_cursor`, `click`, `type_text`, `key`) and `capture_region` only from
+/// an action and only with the `input` and `capture` capabilities. Input goes
+/// through the run's automation, so pausing or stopping refuses it and the
+/// watchdog supervises plugin actions like any other. The host API is the only
+/// access plugins are granted, but a native library runs with the app's
+/// rights: it is not a sandbox, so enable only plugins you trust.
+use std::collections::BTreeMap;
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::panic::AssertUnwindSafe;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+
+use base64::Engine as _;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::domain::{Action, ActionContext, Automation, Error, MouseButton, NotificationChannel, Region, ScreenCapture, Trigger};
+use crate::notify::{Notice, Notifier};
+use crate::shutdown::Shutdown;
+use crate::trigger::IntervalTrigger;
+
+/// Version of the ABI below; plugins built for another are not loaded
+pub const API_VERSION: u32 = 1;
+/// Symbol the library exports: `extern "C" fn() -> *const PluginApi`
+#[cfg(feature = "plugins")]
+pub const ENTRY_POINT: &str = "loopautoma_plugin_v1";
+pub const MANIFEST_FILE: &str = "plugin.json";
+
+/// Callbacks handed to the plugin with every request
+#[repr(C)]
+pub struct HostApi {
+    pub ctx: *mut c_void,
+    /// Run a JSON host request; the answer is freed with `free`
+    pub call: unsafe extern "C" fn(ctx: *mut c_void, request: *const c_char) -> *mut c_char,
+    pub free: unsafe extern "C" fn(text: *mut c_char),
+}
+
+/// What `loopautoma_plugin_v1` returns; must stay valid while the library is loaded
+#[repr(C)]
+pub struct PluginApi {
+    pub api_version: u32,
+    /// Handle a JSON request; the answer is freed with `free`. May be called from several threads
+    pub invoke: unsafe extern "C" fn(host: *const HostApi, request: *const c_char) -> *mut c_char,
+    pub free: unsafe extern "C" fn(text: *mut c_char),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Capability {
+    /// Move the mouse, click, type and press keys from actions
+    Input,
+    /// Capture the profile's regions from actions
+    Capture,
+}
+
+/// An action, trigger or notifier a plugin registers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Component {
+    pub kind: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+/// `plugin.json`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginManifest {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    pub api_version: u32,
+    /// File name of the library in the plugin's folder
+    pub library: String,
+    #[serde(default)]
+    pub capabilities: Vec<Capability>,
+    #[serde(default)]
+    pub actions: Vec<Component>,
+    #[serde(default)]
+    pub triggers: Vec<Component>,
+    #[serde(default)]
+    pub notifiers: Vec<Component>,
+}
+
+impl PluginManifest {
+    pub fn validate(&self) -> Result<(), String> {
+        let id_ok = |c: char| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_';
+        if self.id.is_empty() || !self.id.chars().all(id_ok) {
+            return Err(format!("Plugin id '{}' must be lower-case letters, digits, '-' or '_'", self.id));
+        }
+        if self.api_version != API_VERSION {
+            return Err(format!(
+                "Plugin {} is built for plugin API {}; this app supports {}",
+                self.id, self.api_version, API_VERSION
+            ));
+        }
+        // The library must be in the plugin's own folder
+        if self.library.is_empty() || self.library.contains(['/', '\\']) || self.library.starts_with('.') {
+            return Err(format!("Plugin {} names an invalid library file '{}'", self.id, self.library));
+        }
+        Ok(())
+    }
+
+    pub fn has_action(&self, kind: &str) -> bool {
+        self.actions.iter().any(|c| c.kind == kind)
+    }
+
+    pub fn has_trigger(&self, kind: &str) -> bool {
+        self.triggers.iter().any(|c| c.kind == kind)
+    }
+}
+
+/// A plugin found in the plugins directory, as the settings list it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PluginInfo {
+    /// Manifest id, or the folder name when the manifest cannot be read
+    pub id: String,
+    pub dir: String,
+    pub manifest: Option<PluginManifest>,
+    pub enabled: bool,
+    pub loaded: bool,
+    /// Why the plugin is not loaded although enabled
+    pub error: Option<String>,
+}
+
+/// `plugins/` in the app's config directory
+pub fn default_plugins_dir() -> Option<PathBuf> {
+    Some(dirs::config_dir()?.join("loopautoma").join("plugins"))
+}
+
+/// The manifest of every folder in `dir`, by folder; a missing directory has none
+pub fn discover(dir: &Path) -> Vec<(PathBuf, Result<PluginManifest, String>)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut folders: Vec<PathBuf> = entries.filter_map(|e| e.ok().map(|e| e.path())).filter(|p| p.is_dir()).collect();
+    folders.sort();
+    folders
+        .into_iter()
+        .map(|folder| {
+            let path = folder.join(MANIFEST_FILE);
+            let manifest = std::fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+                .and_then(|text| {
+                    serde_json::from_str::<PluginManifest>(&text).map_err(|e| format!("Invalid {}: {}", path.display(), e))
+                })
+                .and_then(|manifest| manifest.validate().map(|_| manifest));
+            (folder, manifest)
+        })
+        .collect()
+}
+
+/// A plugin whose library is loaded; kept until the app exits
+pub struct LoadedPlugin {
+    pub manifest: PluginManifest,
+    api: *const PluginApi,
+    /// Keeps `api` valid
+    #[cfg(feature = "plugins")]
+    _library: Option<libloading::Library>,
+}
+
+// The ABI requires `invoke` to be callable from any thread
+unsafe impl Send for LoadedPlugin {}
+unsafe impl Sync for LoadedPlugin {}
+
+impl LoadedPlugin {
+    /// A plugin whose API is part of this process
+    #[cfg(test)]
+    pub fn from_api(manifest: PluginManifest, api: &'static PluginApi) -> Result<Self, String> {
+        if api.api_version != API_VERSION {
+            return Err(format!("Plugin {} reports plugin API {}; expected {}", manifest.id, api.api_version, API_VERSION));
+        }
+        Ok(Self {
+            manifest,
+            api,
+            #[cfg(feature = "plugins")]
+            _library: None,
+        })
+    }
+
+    /// Load the library of `manifest` from `dir`
+    #[cfg(feature = "plugins")]
+    pub fn load(dir: &Path, manifest: PluginManifest) -> Result<Self, String> {
+        let path = dir.join(&manifest.library);
+        // Loading runs the library's initialisers; only enabled plugins get here
+        let library = unsafe { libloading::Library::new(&path) }
+            .map_err(|e| format!("Failed to load {}: {}", path.display(), e))?;
+        let api = unsafe {
+            let entry = library
+                .get::<unsafe extern "C" fn() -> *const PluginApi>(ENTRY_POINT.as_bytes())
+                .map_err(|e| format!("{} does not export {}: {}", path.display(), ENTRY_POINT, e))?;
+            entry()
+        };
+        if api.is_null() {
+            return Err(format!("{} returned no plugin API", path.display()));
+        }
+        let version = unsafe { (*api).api_version };
+        if version != API_VERSION {
+            return Err(format!("Plugin {} reports plugin API {}; expected {}", manifest.id, version, API_VERSION));
+        }
+        Ok(Self {
+            manifest,
+            api,
+            _library: Some(library),
+        })
+    }
+
+    #[cfg(not(feature = "plugins"))]
+    pub fn load(_dir: &Path, manifest: PluginManifest) -> Result<Self, String> {
+        Err(format!("Plugin {} not loaded: this build has no plugin support (feature 'plugins')", manifest.id))
+    }
+
+    /// Send `request` to the plugin; an `error` in its answer is returned as the error
+    pub fn invoke(&self, request: &serde_json::Value, session: &HostSession) -> Result<serde_json::Value, String> {
+        let id = &self.manifest.id;
+        let request = CString::new(request.to_string()).map_err(|_| "Plugin request contains a NUL byte".to_string())?;
+        let host = HostApi {
+            ctx: session as *const HostSession as *mut c_void,
+            call: host_call,
+            free: free_text,
+        };
+        let answer = unsafe {
+            let api = &*self.api;
+            let answer = (api.invoke)(&host, request.as_ptr());
+            if answer.is_null() {
+                return Err(format!("Plugin {} gave no answer", id));
+            }
+            let text = CStr::from_ptr(answer).to_string_lossy().into_owned();
+            (api.free)(answer);
+            text
+        };
+        let answer: serde_json::Value =
+            serde_json::from_str(&answer).map_err(|e| format!("Plugin {} answered with invalid JSON: {}", id, e))?;
+        match answer.get("error") {
+            Some(serde_json::Value::String(e)) => Err(format!("Plugin {}: {}", id, e)),
+            Some(e) if !e.is_null() => Err(format!("Plugin {}: {}", id, e)),
+            _ => Ok(answer),
+        }
+    }
+}
+
+/// What a plugin may use while it handles one request
+pub struct HostSession<'a> {
+    plugin_id: &'a str,
+    capabilities: &'a [Capability],
+    automation: Option<&'a dyn Automation>,
+    capture: Option<(&'a dyn ScreenCapture, &'a [Region])>,
+}
+
+impl<'a> HostSession<'a> {
+    /// Logging only, as triggers and notifiers get it
+    pub fn new(manifest: &'a PluginManifest) -> Self {
+        Self {
+            plugin_id: &manifest.id,
+            capabilities: &manifest.capabilities,
+            automation: None,
+            capture: None,
+        }
+    }
+
+    /// Also the input and capture of an action, as far as the plugin's capabilities allow
+    pub fn for_action(
+        manifest: &'a PluginManifest,
+        automation: &'a dyn Automation,
+        capture: &'a dyn ScreenCapture,
+        regions: &'a [Region],
+    ) -> Self {
+        Self {
+            automation: Some(automation),
+            capture: Some((capture, regions)),
+            ..Self::new(manifest)
+        }
+    }
+
+    fn granted(&self, capability: Capability) -> Result<(), String> {
+        if self.capabilities.contains(&capability) {
+            Ok(())
+        } else {
+            Err(format!("plugin {} was not granted the {:?} capability", self.plugin_id, capability).to_lowercase())
+        }
+    }
+
+    fn input(&self) -> Result<&'a dyn Automation, String> {
+        self.granted(Capability::Input)?;
+        self.automation.ok_or_else(|| "input is only available to plugin actions".to_string())
+    }
+
+    /// Run one host request
+    pub fn handle(&self, request: &str) -> Result<serde_json::Value, String> {
+        let request: HostRequest = serde_json::from_str(request).map_err(|e| format!("invalid host request: {}", e))?;
+        let done = |r: Result<(), Error>| r.map(|_| json!({})).map_err(|e| e.to_string());
+        match request {
+            HostRequest::Log { level, message } => {
+                match level.as_str() {
+                    "error" => tracing::error!(plugin = self.plugin_id, "{}", message),
+                    "warn" => tracing::warn!(plugin = self.plugin_id, "{}", message),
+                    "debug" => tracing::debug!(plugin = self.plugin_id, "{}", message),
+                    _ => tracing::info!(plugin = self.plugin_id, "{}", message),
+                }
+                Ok(json!({}))
+            }
+            HostRequest::MoveCursor { x, y } => done(self.input()?.move_cursor(x, y)),
+            HostRequest::Click { button } => done(self.input()?.click(button)),
+            HostRequest::TypeText { text } => done(self.input()?.type_text(&text)),
+            HostRequest::Key { key } => done(self.input()?.key(&key)),
+            HostRequest::CaptureRegion { region_id } => {
+                self.granted(Capability::Capture)?;
+                let (capture, regions) = self.capture.ok_or_else(|| "capture is only available to plugin actions".to_string())?;
+                let region = regions
+                    .iter()
+                    .find(|r| r.id == region_id)
+                    .ok_or_else(|| format!("no region '{}' in this profile", region_id))?;
+                let frame = capture.capture_region(region).map_err(|e| e.to_string())?;
+                let png = crate::timelapse::archive_png(&frame, u32::MAX).ok_or("failed to encode the capture")?;
+                Ok(json!({
+                    "width": frame.width,
+                    "height": frame.height,
+                    "png_base64": base64::engine::general_purpose::STANDARD.encode(png),
+                }))
+            }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum HostRequest {
+    Log {
+        #[serde(default)]
+        level: String,
+        message: String,
+    },
+    MoveCursor {
+        x: u32,
+        y: u32,
+    },
+    Click {
+        button: MouseButton,
+    },
+    TypeText {
+        text: String,
+    },
+    Key {
+        key: String,
+    },
+    CaptureRegion {
+        region_id: String,
+    },
+}
+
+unsafe extern "C" fn host_call(ctx: *mut c_void, request: *const c_char) -> *mut c_char {
+    let answer = if ctx.is_null() || request.is_null() {
+        Err("missing host request".to_string())
+    } else {
+        let session = &*(ctx as *const HostSession);
+        let request = CStr::from_ptr(request).to_string_lossy();
+        // A panic must not unwind into the plugin
+        std::panic::catch_unwind(AssertUnwindSafe(|| session.handle(&request)))
+            .unwrap_or_else(|_| Err("host request panicked".to_string()))
+    };
+    let answer = answer.unwrap_or_else(|e| json!({ "error": e }));
+    CString::new(answer.to_string()).unwrap_or_default().into_raw()
+}
+
+unsafe extern "C" fn free_text(text: *mut c_char) {
+    if !text.is_null() {
+        drop(CString::from_raw(text));
+    }
+}
+
+/// Plugins found at startup, and the loaded ones by id
+#[derive(Default)]
+pub struct PluginRegistry {
+    found: RwLock<Vec<PluginInfo>>,
+    loaded: RwLock<BTreeMap<String, Arc<LoadedPlugin>>>,
+}
+
+impl PluginRegistry {
+    /// Find the plugins in `dir` and load the `enabled` ones; a failed plugin is listed with its error
+    pub fn load_dir(&self, dir: &Path, enabled: &[String]) {
+        let mut found = Vec::new();
+        for (folder, manifest) in discover(dir) {
+            let folder_name = folder.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+            let mut info = PluginInfo {
+                id: manifest.as_ref().map(|m| m.id.clone()).unwrap_or(folder_name),
+                dir: folder.display().to_string(),
+                manifest: manifest.as_ref().ok().cloned(),
+                enabled: false,
+                loaded: false,
+                error: manifest.as_ref().err().cloned(),
+            };
+            info.enabled = enabled.contains(&info.id);
+            if let (true, Ok(manifest)) = (info.enabled, manifest) {
+                if self.get(&manifest.id).is_some() {
+                    info.error = Some(format!("Another plugin has the id {}", manifest.id));
+                } else {
+                    match LoadedPlugin::load(&folder, manifest) {
+                        Ok(plugin) => {
+                            tracing::info!("Loaded plugin {} {}", plugin.manifest.id, plugin.manifest.version);
+                            info.loaded = true;
+                            self.register(Arc::new(plugin));
+                        }
+                        Err(e) => {
+                            tracing::warn!("{}", e);
+                            info.error = Some(e);
+                        }
+                    }
+                }
+            }
+            found.push(info);
+        }
+        *self.found.write().unwrap() = found;
+    }
+
+    pub fn register(&self, plugin: Arc<LoadedPlugin>) {
+        self.loaded.write().unwrap().insert(plugin.manifest.id.clone(), plugin);
+    }
+
+    pub fn get(&self, id: &str) -> Option<Arc<LoadedPlugin>> {
+        self.loaded.read().unwrap().get(id).cloned()
+    }
+
+    pub fn loaded(&self) -> Vec<Arc<LoadedPlugin>> {
+        self.loaded.read().unwrap().values().cloned().collect()
+    }
+
+    /// Whether a loaded plugin registers the action `kind`
+    pub fn check_action(&self, plugin: &str, kind: &str) -> Result<(), String> {
+        self.check(plugin, kind, "action", PluginManifest::has_action)
+    }
+
+    /// Whether a loaded plugin registers the trigger `kind`
+    pub fn check_trigger(&self, plugin: &str, kind: &str) -> Result<(), String> {
+        self.check(plugin, kind, "trigger", PluginManifest::has_trigger)
+    }
+
+    fn check(&self, plugin: &str, kind: &str, what: &str, has: fn(&PluginManifest, &str) -> bool) -> Result<(), String> {
+        let loaded = self
+            .get(plugin)
+            .ok_or_else(|| format!("Plugin '{}' is not loaded; enable it under Settings → Plugins and restart", plugin))?;
+        if has(&loaded.manifest, kind) {
+            Ok(())
+        } else {
+            Err(format!("Plugin '{}' has no {} '{}'", plugin, what, kind))
+        }
+    }
+
+    /// The plugins found at startup, with `enabled` as currently saved
+    pub fn list(&self, enabled: &[String]) -> Vec<PluginInfo> {
+        let mut found = self.found.read().unwrap().clone();
+        for info in &mut found {
+            info.enabled = enabled.contains(&info.id);
+        }
+        found
+    }
+}
+
+/// The process's plugins, loaded once at startup
+pub fn registry() -> &'static PluginRegistry {
+    static REGISTRY: OnceLock<PluginRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(PluginRegistry::default)
+}
+
+/// A profile's plugin action
+pub struct PluginAction {
+    pub plugin_id: String,
+    pub kind: String,
+    pub config: serde_json::Value,
+    /// `None` when the plugin is not loaded; the action then fails when it runs
+    pub plugin: Option<Arc<LoadedPlugin>>,
+    pub capture: Arc<dyn ScreenCapture + Send + Sync>,
+    pub regions: Vec<Region>,
+}
+
+impl Action for PluginAction {
+    fn name(&self) -> &'static str {
+        "Plugin"
+    }
+
+    fn execute(&self, automation: &dyn Automation, context: &mut ActionContext) -> Result<(), Error> {
+        let plugin = self
+            .plugin
+            .as_ref()
+            .ok_or_else(|| Error::Action(format!("Plugin {} is not loaded", self.plugin_id)))?;
+        if !plugin.manifest.has_action(&self.kind) {
+            return Err(Error::Action(format!("Plugin {} has no action '{}'", self.plugin_id, self.kind)));
+        }
+        // Secrets stay with the app; plugins see the run's variables only
+        let request = json!({
+            "call": "action",
+            "kind": self.kind,
+            "config": self.config,
+            "variables": context.variables,
+        });
+        let session = HostSession::for_action(&plugin.manifest, automation, &*self.capture, &self.regions);
+        let answer = plugin.invoke(&request, &session).map_err(Error::Action)?;
+        if let Some(variables) = answer.get("variables").and_then(|v| v.as_object()) {
+            for (name, value) in variables {
+                match value {
+                    serde_json::Value::String(s) => context.set(name, s.clone()),
+                    other => context.set(name, other.to_string()),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn debug_params(&self, _context: &ActionContext) -> serde_json::Value {
+        json!({ "plugin": self.plugin_id, "kind": self.kind, "config": self.config })
+    }
+}
+
+/// Fires when the plugin says so, asked at most once per interval
+pub struct PluginTrigger {
+    pub interval: IntervalTrigger,
+    pub plugin_id: String,
+    pub kind: String,
+    pub config: serde_json::Value,
+    pub plugin: Option<Arc<LoadedPlugin>>,
+    /// Failures are logged once per this long, not at every check
+    last_warning: Option<Instant>,
+}
+
+const TRIGGER_WARNING_INTERVAL: Duration = Duration::from_secs(60);
+
+impl PluginTrigger {
+    pub fn new(interval: Duration, config: &crate::domain::PluginTriggerConfig, plugin: Option<Arc<LoadedPlugin>>) -> Self {
+        Self {
+            interval: IntervalTrigger::new(interval),
+            plugin_id: config.plugin.clone(),
+            kind: config.kind.clone(),
+            config: config.config.clone(),
+            plugin,
+            last_warning: None,
+        }
+    }
+
+    fn ask(&self) -> Result<bool, String> {
+        let plugin = self.plugin.as_ref().ok_or_else(|| format!("Plugin {} is not loaded", self.plugin_id))?;
+        if !plugin.manifest.has_trigger(&self.kind) {
+            return Err(format!("Plugin {} has no trigger '{}'", self.plugin_id, self.kind));
+        }
+        let request = json!({ "call": "trigger", "kind": self.kind, "config": self.config });
+        let answer = plugin.invoke(&request, &HostSession::new(&plugin.manifest))?;
+        Ok(answer.get("fire").and_then(|f| f.as_bool()).unwrap_or(false))
+    }
+}
+
+impl Trigger for PluginTrigger {
+    fn should_fire(&mut self, now: Instant) -> bool {
+        if !self.interval.should_fire(now) {
+            return false;
+        }
+        match self.ask() {
+            Ok(fire) => fire,
+            Err(e) => {
+                if self.last_warning.is_none_or(|at| now.duration_since(at) >= TRIGGER_WARNING_INTERVAL) {
+                    tracing::warn!("Plugin trigger failed: {}", e);
+                    self.last_warning = Some(now);
+                }
+                false
+            }
+        }
+    }
+
+    fn time_until_next_ms(&self, now: Instant) -> u64 {
+        self.interval.time_until_next_ms(now)
+    }
+}
+
+/// The `plugin` channel: every notifier of every loaded plugin, each notice on its own thread
+pub struct PluginNotifier {
+    pub plugins: Vec<Arc<LoadedPlugin>>,
+    pub shutdown: Arc<Shutdown>,
+}
+
+impl Notifier for PluginNotifier {
+    fn channel(&self) -> NotificationChannel {
+        NotificationChannel::Plugin
+    }
+
+    fn notify(&self, notice: &Notice) -> Result<(), String> {
+        let plugins: Vec<Arc<LoadedPlugin>> = self.plugins.iter().filter(|p| !p.manifest.notifiers.is_empty()).cloned().collect();
+        if plugins.is_empty() {
+            return Ok(());
+        }
+        let (title, body) = notice.message();
+        let notice = json!({
+            "event": notice.event,
+            "profile_name": notice.profile_name,
+            "title": title,
+            "body": body,
+            "detail": notice.detail,
+            "iterations": notice.iterations,
+            "duration_ms": notice.duration_ms,
+        });
+        // Tracked, so a notice sent as the app quits is still delivered
+        self.shutdown.spawn("plugin-notifier", None, move |_| {
+            for plugin in &plugins {
+                for notifier in &plugin.manifest.notifiers {
+                    let request = json!({ "call": "notify", "kind": notifier.kind, "notice": notice });
+                    if let Err(e) = plugin.invoke(&request, &HostSession::new(&plugin.manifest)) {
+                        tracing::warn!("Plugin notification failed: {}", e);
+                    }
+                }
+            }
+        });
+        Ok(())
+    }
+}
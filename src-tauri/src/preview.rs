@@ -0,0 +1,85 @@
+/// Live region previews
+///
+/// `start_region_preview` streams PNG frames of a saved region, downscaled like
+/// thumbnails, as `loopautoma://region_preview` until `stop_region_preview`.
+/// The rectangle is looked up again for every frame, so a region that is
+/// redefined and saved while its preview runs is followed without restarting
+/// the stream. Frames that did not change since the last one are not sent.
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{Rect, Region};
+use crate::ProfilesConfig;
+
+pub const CHANNEL: &str = "loopautoma://region_preview";
+
+/// Fastest preview rate; capturing more often only loads the desktop
+pub const MAX_FPS: f64 = 10.0;
+
+/// Time between frames at `fps`, capped at `MAX_FPS`
+pub fn frame_interval(fps: f64) -> Result<Duration, String> {
+    if !fps.is_finite() || fps <= 0.0 {
+        return Err(format!("Preview rate must be above 0 fps, got {}", fps));
+    }
+    Ok(Duration::from_secs_f64(1.0 / fps.min(MAX_FPS)))
+}
+
+/// First region with this id in any profile
+pub fn find_region(config: &ProfilesConfig, region_id: &str) -> Option<Region> {
+    config
+        .profiles
+        .iter()
+        .flat_map(|p| p.regions.iter())
+        .find(|r| r.id == region_id)
+        .cloned()
+}
+
+/// Payload of `loopautoma://region_preview`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegionPreviewFrame {
+    pub region_id: String,
+    /// Rectangle the frame was captured from
+    pub rect: Rect,
+    pub png_base64: String,
+    pub captured_at_ms: u64,
+}
+
+/// Preview streams by region id; each stream runs until its flag is set
+#[derive(Default)]
+pub struct PreviewStreams {
+    streams: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+impl PreviewStreams {
+    /// Register a stream for `region_id`, stopping the one it replaces; returns its stop flag
+    pub fn start(&self, region_id: &str) -> Arc<AtomicBool> {
+        let stop = Arc::new(AtomicBool::new(false));
+        if let Some(old) = self.streams.lock().unwrap().insert(region_id.to_string(), stop.clone()) {
+            old.store(true, Ordering::Relaxed);
+        }
+        stop
+    }
+
+    /// Stop the stream of `region_id`; false if none was running
+    pub fn stop(&self, region_id: &str) -> bool {
+        match self.streams.lock().unwrap().remove(region_id) {
+            Some(stop) => {
+                stop.store(true, Ordering::Relaxed);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Forget a stream that ended on its own, unless a newer one replaced it
+    pub fn finished(&self, region_id: &str, stop: &Arc<AtomicBool>) {
+        let mut streams = self.streams.lock().unwrap();
+        if streams.get(region_id).is_some_and(|s| Arc::ptr_eq(s, stop)) {
+            streams.remove(region_id);
+        }
+    }
+}
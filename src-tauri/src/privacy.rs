@@ -0,0 +1,379 @@
+/// Masking of personal data in captured frames
+///
+/// A profile with `privacy` has every frame its actions capture read by OCR
+/// with word boxes before an LLM or a plugin sees it, and the same goes for its
+/// frame archive. Words (and runs of words on one line) that look like an email
+/// address, a payment card number or an API key are painted over. Text from
+/// local OCR is redacted the same way before it goes into an LLM prompt.
+///
+/// Word boxes come from the `tesseract` command line tool, found on the `PATH`
+/// or at `LOOPAUTOMA_TESSERACT`. The filter fails closed: when OCR cannot run,
+/// the capture fails rather than passing on unmasked pixels. What was masked is
+/// counted per region and kind, never recorded itself, and ends up in the run's
+/// history entry.
+use std::collections::BTreeMap;
+use std::io::{Cursor, Write};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use image::{ImageOutputFormat, RgbaImage};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{
+    BackendError, DisplayInfo, Error, LLMPromptResponse, PiiKind, PrivacyConfig, Rect, Region, ScreenCapture, ScreenFrame,
+};
+use crate::llm::LLMClient;
+
+/// Pixels masked around each word box, so glyph edges do not survive
+const MASK_PADDING: u32 = 2;
+
+/// Where redacted OCR text is counted in the report
+pub const PROMPT_SOURCE: &str = "llm_prompt";
+
+/// A word OCR found, with its box in frame pixels
+#[derive(Debug, Clone, PartialEq)]
+pub struct OcrWord {
+    pub text: String,
+    pub rect: Rect,
+    /// Block, paragraph and line number; words of one line share it
+    pub line: (u32, u32, u32),
+}
+
+/// Reads words and their boxes from a frame
+pub trait WordReader: Send + Sync {
+    fn words(&self, frame: &ScreenFrame) -> Result<Vec<OcrWord>, BackendError>;
+}
+
+/// `tesseract` run on each frame, reading its TSV output
+pub struct TesseractReader {
+    pub binary: String,
+}
+
+impl TesseractReader {
+    pub fn from_env() -> Self {
+        Self {
+            binary: std::env::var("LOOPAUTOMA_TESSERACT").unwrap_or_else(|_| "tesseract".to_string()),
+        }
+    }
+}
+
+impl WordReader for TesseractReader {
+    fn words(&self, frame: &ScreenFrame) -> Result<Vec<OcrWord>, BackendError> {
+        let unavailable = |message: String| BackendError::new("privacy_ocr_failed", message);
+        let image = RgbaImage::from_raw(frame.width, frame.height, frame.bytes.clone())
+            .ok_or_else(|| unavailable("Captured frame has an unexpected size".into()))?;
+        let mut png = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut png), ImageOutputFormat::Png)
+            .map_err(|e| unavailable(format!("PNG encoding failed: {}", e)))?;
+        let mut child = Command::new(&self.binary)
+            .args(["stdin", "stdout", "tsv"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => unavailable(format!(
+                    "{} was not found; install tesseract or set LOOPAUTOMA_TESSERACT for the privacy filter",
+                    self.binary
+                )),
+                _ => unavailable(format!("Failed to run {}: {}", self.binary, e)),
+            })?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(&png)
+                .map_err(|e| unavailable(format!("Failed to pass the frame to {}: {}", self.binary, e)))?;
+        }
+        let output = child
+            .wait_with_output()
+            .map_err(|e| unavailable(format!("Failed to run {}: {}", self.binary, e)))?;
+        if !output.status.success() {
+            return Err(unavailable(format!(
+                "tesseract failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(parse_tsv(&String::from_utf8_lossy(&output.stdout)))
+    }
+}
+
+/// Words of tesseract's TSV output (level 5 rows with text)
+pub fn parse_tsv(tsv: &str) -> Vec<OcrWord> {
+    tsv.lines()
+        .skip(1)
+        .filter_map(|row| {
+            let cols: Vec<&str> = row.splitn(12, '\t').collect();
+            if cols.len() < 12 || cols[0] != "5" || cols[11].trim().is_empty() {
+                return None;
+            }
+            let n = |i: usize| cols[i].parse::<u32>().ok();
+            Some(OcrWord {
+                text: cols[11].trim().to_string(),
+                rect: Rect {
+                    x: n(6)?,
+                    y: n(7)?,
+                    width: n(8)?,
+                    height: n(9)?,
+                },
+                line: (n(2)?, n(3)?, n(4)?),
+            })
+        })
+        .collect()
+}
+
+fn email() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}").unwrap())
+}
+
+fn provider_key() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(concat!(
+            r"^(sk-[A-Za-z0-9_-]{16,}|gh[pousr]_[A-Za-z0-9]{30,}|github_pat_[A-Za-z0-9_]{30,}",
+            r"|xox[abprs]-[A-Za-z0-9-]{10,}|AKIA[0-9A-Z]{16}|AIza[0-9A-Za-z_-]{35})$"
+        ))
+        .unwrap()
+    })
+}
+
+/// A word without the punctuation text puts around it
+fn trimmed(word: &str) -> &str {
+    word.trim_matches(|c: char| "\"'`()[]{}<>,;".contains(c)).trim_end_matches(['.', ':'])
+}
+
+fn is_api_key(word: &str) -> bool {
+    let word = trimmed(word);
+    // `TOKEN=…` and `key: …` show the value with its name
+    let value = word.rsplit(['=', ':']).next().unwrap_or(word);
+    [word, value].iter().any(|w| {
+        provider_key().is_match(w)
+            || (w.len() >= 24
+                && w.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+                && w.chars().any(|c| c.is_ascii_lowercase())
+                && w.chars().any(|c| c.is_ascii_uppercase())
+                && w.chars().any(|c| c.is_ascii_digit()))
+    })
+}
+
+/// Digits of a word made of digit groups (`4111`, `4111-1111`), else `None`
+fn card_digits(word: &str) -> Option<String> {
+    let word = trimmed(word);
+    let valid = !word.is_empty()
+        && word.chars().all(|c| c.is_ascii_digit() || c == '-')
+        && word.chars().next().is_some_and(|c| c.is_ascii_digit());
+    valid.then(|| word.chars().filter(char::is_ascii_digit).collect())
+}
+
+fn luhn(digits: &str) -> bool {
+    let sum: u32 = digits
+        .chars()
+        .rev()
+        .filter_map(|c| c.to_digit(10))
+        .enumerate()
+        .map(|(i, d)| match (i % 2, d * 2) {
+            (1, doubled) if doubled > 9 => doubled - 9,
+            (1, doubled) => doubled,
+            _ => d,
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+/// Runs of `words` holding personal data of `kinds`, as inclusive word index ranges
+///
+/// `same_line(a, b)` tells whether two words are on one line; card numbers may
+/// span several words of a line.
+pub fn detect(words: &[&str], same_line: impl Fn(usize, usize) -> bool, kinds: &[PiiKind]) -> Vec<(PiiKind, usize, usize)> {
+    let mut found = Vec::new();
+    let mut i = 0;
+    while i < words.len() {
+        if kinds.contains(&PiiKind::CreditCard) {
+            let mut digits = String::new();
+            let mut end = None;
+            for (j, word) in words.iter().enumerate().skip(i) {
+                let Some(d) = card_digits(word).filter(|_| same_line(i, j)) else { break };
+                digits.push_str(&d);
+                if digits.len() > 19 {
+                    break;
+                }
+                if digits.len() >= 13 && luhn(&digits) {
+                    end = Some(j);
+                }
+            }
+            if let Some(end) = end {
+                found.push((PiiKind::CreditCard, i, end));
+                i = end + 1;
+                continue;
+            }
+        }
+        if kinds.contains(&PiiKind::Email) && email().is_match(words[i]) {
+            found.push((PiiKind::Email, i, i));
+        } else if kinds.contains(&PiiKind::ApiKey) && is_api_key(words[i]) {
+            found.push((PiiKind::ApiKey, i, i));
+        }
+        i += 1;
+    }
+    found
+}
+
+/// Masked items per source (a region id or `PROMPT_SOURCE`) and kind
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MaskedPii {
+    pub source: String,
+    pub kind: PiiKind,
+    pub count: u32,
+}
+
+/// Masks personal data in frames and redacts it in text, counting what it hid
+pub struct PrivacyFilter {
+    kinds: Vec<PiiKind>,
+    reader: Box<dyn WordReader>,
+    masked: Mutex<BTreeMap<(String, PiiKind), u32>>,
+}
+
+impl PrivacyFilter {
+    pub fn new(config: &PrivacyConfig, reader: Box<dyn WordReader>) -> Self {
+        Self {
+            kinds: config.detectors.clone(),
+            reader,
+            masked: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    fn count(&self, source: &str, kind: PiiKind) {
+        *self.masked.lock().unwrap().entry((source.to_string(), kind)).or_default() += 1;
+    }
+
+    /// Paint over personal data in `frame`, a capture of `region`
+    pub fn mask(&self, region: &Region, mut frame: ScreenFrame) -> Result<ScreenFrame, BackendError> {
+        if self.kinds.is_empty() {
+            return Ok(frame);
+        }
+        let words = self.reader.words(&frame)?;
+        let texts: Vec<&str> = words.iter().map(|w| w.text.as_str()).collect();
+        for (kind, first, last) in detect(&texts, |a, b| words[a].line == words[b].line, &self.kinds) {
+            for word in &words[first..=last] {
+                fill(&mut frame, &word.rect);
+            }
+            self.count(&region.id, kind);
+        }
+        Ok(frame)
+    }
+
+    /// `text` with personal data replaced by its kind, e.g. `[email]`
+    pub fn redact(&self, text: &str) -> String {
+        // Words with their byte range and line
+        let mut words = Vec::new();
+        let mut line = 0;
+        let mut start = None;
+        for (i, c) in text.char_indices().chain(std::iter::once((text.len(), ' '))) {
+            if c.is_whitespace() {
+                if let Some(s) = start.take() {
+                    words.push((s, i, line));
+                }
+                if c == '\n' {
+                    line += 1;
+                }
+            } else if start.is_none() {
+                start = Some(i);
+            }
+        }
+        let texts: Vec<&str> = words.iter().map(|&(s, e, _)| &text[s..e]).collect();
+        let mut redacted = String::with_capacity(text.len());
+        let mut copied = 0;
+        for (kind, first, last) in detect(&texts, |a, b| words[a].2 == words[b].2, &self.kinds) {
+            redacted.push_str(&text[copied..words[first].0]);
+            redacted.push_str(label(kind));
+            copied = words[last].1;
+            self.count(PROMPT_SOURCE, kind);
+        }
+        redacted.push_str(&text[copied..]);
+        redacted
+    }
+
+    /// What was masked since the last call
+    pub fn take_report(&self) -> Vec<MaskedPii> {
+        std::mem::take(&mut *self.masked.lock().unwrap())
+            .into_iter()
+            .map(|((source, kind), count)| MaskedPii { source, kind, count })
+            .collect()
+    }
+}
+
+fn label(kind: PiiKind) -> &'static str {
+    match kind {
+        PiiKind::Email => "[email]",
+        PiiKind::CreditCard => "[card number]",
+        PiiKind::ApiKey => "[api key]",
+    }
+}
+
+/// Paint `rect`, grown by `MASK_PADDING`, black
+fn fill(frame: &mut ScreenFrame, rect: &Rect) {
+    let x0 = rect.x.saturating_sub(MASK_PADDING).min(frame.width);
+    let y0 = rect.y.saturating_sub(MASK_PADDING).min(frame.height);
+    let x1 = (rect.x + rect.width + MASK_PADDING).min(frame.width);
+    let y1 = (rect.y + rect.height + MASK_PADDING).min(frame.height);
+    for y in y0..y1 {
+        let row = (y * frame.stride) as usize;
+        for x in x0..x1 {
+            let i = row + x as usize * 4;
+            frame.bytes[i..i + 4].copy_from_slice(&[0, 0, 0, 255]);
+        }
+    }
+}
+
+/// Captures through the wrapped backend and masks every frame it hands out
+pub struct MaskingCapture {
+    pub inner: Arc<dyn ScreenCapture + Send + Sync>,
+    pub filter: Arc<PrivacyFilter>,
+}
+
+impl ScreenCapture for MaskingCapture {
+    fn hash_region(&self, region: &Region, downscale: u32) -> u64 {
+        self.inner.hash_region(region, downscale)
+    }
+
+    fn capture_region(&self, region: &Region) -> Result<ScreenFrame, BackendError> {
+        self.filter.mask(region, self.inner.capture_region(region)?)
+    }
+
+    fn capture_and_hash(&self, region: &Region, downscale: u32) -> Result<(ScreenFrame, u64), BackendError> {
+        let (frame, hash) = self.inner.capture_and_hash(region, downscale)?;
+        Ok((self.filter.mask(region, frame)?, hash))
+    }
+
+    fn displays(&self) -> Result<Vec<DisplayInfo>, BackendError> {
+        self.inner.displays()
+    }
+}
+
+/// Redacts the system prompt, where local OCR text goes, before the wrapped client sees it
+pub struct RedactingClient {
+    pub inner: Arc<dyn LLMClient>,
+    pub filter: Arc<PrivacyFilter>,
+}
+
+impl LLMClient for RedactingClient {
+    fn generate_prompt(
+        &self,
+        regions: &[Region],
+        region_images: Vec<Vec<u8>>,
+        system_prompt: Option<&str>,
+        risk_guidance: &str,
+    ) -> Result<LLMPromptResponse, Error> {
+        let redacted = system_prompt.map(|p| self.filter.redact(p));
+        self.inner
+            .generate_prompt(regions, region_images, redacted.as_deref(), risk_guidance)
+    }
+
+    fn is_mock(&self) -> bool {
+        self.inner.is_mock()
+    }
+
+    fn endpoint(&self) -> Option<String> {
+        self.inner.endpoint()
+    }
+}
@@ -0,0 +1,245 @@
+/// Optional encryption of the stored profiles file
+///
+/// Profiles hold prompts, window titles and shell commands, so the file can be
+/// sealed with AES-256-GCM. The key is generated on first use and kept in the OS
+/// keyring, never next to the file. An encrypted file is a small JSON envelope,
+/// which lets loading recognise it without any other setting.
+///
+/// The same envelope seals credentials in the settings store; see
+/// `secure_storage`. Their key comes from the keyring as well, or is derived
+/// from the machine's identity when there is no keyring.
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::engine::general_purpose::STANDARD as Base64Standard;
+use base64::Engine as _;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+pub const CIPHER: &str = "aes-256-gcm";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Envelope {
+    encrypted: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Where an encryption key lives
+pub trait KeyStore: Send + Sync {
+    fn load_key(&self) -> Result<Option<[u8; 32]>, String>;
+    fn store_key(&self, key: &[u8; 32]) -> Result<(), String>;
+}
+
+/// Key held by the OS keyring (Keychain, Credential Manager, Secret Service)
+pub struct KeyringKeyStore {
+    #[cfg_attr(not(feature = "os-keyring"), allow(dead_code))]
+    user: &'static str,
+    what: &'static str,
+}
+
+impl KeyringKeyStore {
+    /// Key of the profiles file
+    pub const PROFILES: Self = Self {
+        user: "profiles-encryption-key",
+        what: "profile",
+    };
+    /// Key of the credentials in the settings store
+    pub const SETTINGS: Self = Self {
+        user: "settings-encryption-key",
+        what: "settings",
+    };
+}
+
+#[cfg(feature = "os-keyring")]
+impl KeyringKeyStore {
+    const SERVICE: &'static str = "loopautoma";
+
+    fn entry(&self) -> Result<keyring::Entry, String> {
+        keyring::Entry::new(Self::SERVICE, self.user).map_err(|e| format!("OS keyring unavailable: {}", e))
+    }
+}
+
+#[cfg(feature = "os-keyring")]
+impl KeyStore for KeyringKeyStore {
+    fn load_key(&self) -> Result<Option<[u8; 32]>, String> {
+        match self.entry()?.get_password() {
+            Ok(encoded) => decode_key(&encoded)
+                .map(Some)
+                .ok_or_else(|| format!("The {} key in the OS keyring is malformed", self.what)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(format!("Failed to read the {} key from the OS keyring: {}", self.what, e)),
+        }
+    }
+
+    fn store_key(&self, key: &[u8; 32]) -> Result<(), String> {
+        self.entry()?
+            .set_password(&Base64Standard.encode(key))
+            .map_err(|e| format!("Failed to store the {} key in the OS keyring: {}", self.what, e))
+    }
+}
+
+#[cfg(not(feature = "os-keyring"))]
+impl KeyStore for KeyringKeyStore {
+    fn load_key(&self) -> Result<Option<[u8; 32]>, String> {
+        Err(format!("The {} key needs OS keyring support, which this build lacks", self.what))
+    }
+
+    fn store_key(&self, _key: &[u8; 32]) -> Result<(), String> {
+        Err(format!("The {} key needs OS keyring support, which this build lacks", self.what))
+    }
+}
+
+/// Key derived from this machine's ID and the user name, for systems without a keyring.
+/// Copying the store elsewhere does not reveal it, but anyone who can run code as this user can derive it.
+pub struct MachineKeyStore;
+
+impl KeyStore for MachineKeyStore {
+    fn load_key(&self) -> Result<Option<[u8; 32]>, String> {
+        let user = std::env::var("USER").or_else(|_| std::env::var("USERNAME")).unwrap_or_default();
+        Ok(Some(derive_machine_key(&machine_id()?, &user)))
+    }
+
+    fn store_key(&self, _key: &[u8; 32]) -> Result<(), String> {
+        Err("The machine key is derived, not stored".into())
+    }
+}
+
+pub fn derive_machine_key(machine_id: &str, user: &str) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for part in ["loopautoma-settings-v1", machine_id.trim(), user] {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    hasher.finalize().into()
+}
+
+/// Stable ID the OS assigns to this installation
+#[cfg(target_os = "linux")]
+fn machine_id() -> Result<String, String> {
+    ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+        .iter()
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .find(|id| !id.trim().is_empty())
+        .ok_or_else(|| "Cannot read /etc/machine-id to derive the settings key".to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn machine_id() -> Result<String, String> {
+    let output = std::process::Command::new("ioreg")
+        .args(["-rd1", "-c", "IOPlatformExpertDevice"])
+        .output()
+        .map_err(|e| format!("Cannot run ioreg to derive the settings key: {}", e))?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find(|line| line.contains("IOPlatformUUID"))
+        .and_then(|line| line.rsplit('"').nth(1))
+        .map(str::to_string)
+        .ok_or_else(|| "ioreg reported no IOPlatformUUID to derive the settings key".to_string())
+}
+
+#[cfg(target_os = "windows")]
+fn machine_id() -> Result<String, String> {
+    let output = std::process::Command::new("reg")
+        .args(["query", r"HKLM\SOFTWARE\Microsoft\Cryptography", "/v", "MachineGuid"])
+        .output()
+        .map_err(|e| format!("Cannot query MachineGuid to derive the settings key: {}", e))?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find(|line| line.contains("MachineGuid"))
+        .and_then(|line| line.split_whitespace().last())
+        .map(str::to_string)
+        .ok_or_else(|| "The registry has no MachineGuid to derive the settings key".to_string())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn machine_id() -> Result<String, String> {
+    Err("Cannot identify this machine to derive the settings key".into())
+}
+
+/// In-memory key store for tests
+#[cfg(test)]
+pub struct StaticKeyStore(pub std::sync::Mutex<Option<[u8; 32]>>);
+
+#[cfg(test)]
+impl KeyStore for StaticKeyStore {
+    fn load_key(&self) -> Result<Option<[u8; 32]>, String> {
+        Ok(*self.0.lock().unwrap())
+    }
+
+    fn store_key(&self, key: &[u8; 32]) -> Result<(), String> {
+        *self.0.lock().unwrap() = Some(*key);
+        Ok(())
+    }
+}
+
+#[cfg_attr(not(feature = "os-keyring"), allow(dead_code))]
+fn decode_key(encoded: &str) -> Option<[u8; 32]> {
+    Base64Standard
+        .decode(encoded.trim())
+        .ok()
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+}
+
+/// Whether `contents` is an envelope written by `seal`
+pub fn is_sealed(contents: &str) -> bool {
+    serde_json::from_str::<Envelope>(contents).is_ok_and(|e| e.encrypted == CIPHER)
+}
+
+/// Encrypt `plaintext`, creating the key on first use
+pub fn seal(plaintext: &str, keys: &dyn KeyStore) -> Result<String, String> {
+    seal_as(plaintext, keys, "profiles")
+}
+
+/// Plaintext of a profiles file and whether it was encrypted; plain files pass through unchanged
+pub fn open(contents: &str, keys: &dyn KeyStore) -> Result<(String, bool), String> {
+    open_as(contents, keys, "profiles")
+}
+
+/// `seal` for other contents; `what` names them in errors
+pub fn seal_as(plaintext: &str, keys: &dyn KeyStore, what: &str) -> Result<String, String> {
+    let key = match keys.load_key()? {
+        Some(key) => key,
+        None => {
+            let key: [u8; 32] = Aes256Gcm::generate_key(OsRng).into();
+            keys.store_key(&key)?;
+            key
+        }
+    };
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext.as_bytes())
+        .map_err(|_| format!("Failed to encrypt {}", what))?;
+    let envelope = Envelope {
+        encrypted: CIPHER.into(),
+        nonce: Base64Standard.encode(nonce),
+        ciphertext: Base64Standard.encode(ciphertext),
+    };
+    serde_json::to_string_pretty(&envelope).map_err(|e| format!("Failed to serialize encrypted {}: {}", what, e))
+}
+
+/// `open` for other contents; `what` names them in errors
+pub fn open_as(contents: &str, keys: &dyn KeyStore, what: &str) -> Result<(String, bool), String> {
+    let envelope = match serde_json::from_str::<Envelope>(contents) {
+        Ok(envelope) if envelope.encrypted == CIPHER => envelope,
+        Ok(envelope) => return Err(format!("Unsupported encryption '{}' of {}", envelope.encrypted, what)),
+        Err(_) => return Ok((contents.to_string(), false)),
+    };
+    let key = keys
+        .load_key()?
+        .ok_or_else(|| format!("Encrypted {} cannot be opened: their key is missing from the OS keyring", what))?;
+    let nonce = Base64Standard
+        .decode(&envelope.nonce)
+        .ok()
+        .filter(|n| n.len() == 12)
+        .ok_or_else(|| format!("Encrypted {} have a malformed nonce", what))?;
+    let ciphertext = Base64Standard
+        .decode(&envelope.ciphertext)
+        .map_err(|_| format!("Encrypted {} are malformed", what))?;
+    let plaintext = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key))
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+        .map_err(|_| format!("Failed to decrypt {}: wrong key or corrupted file", what))?;
+    String::from_utf8(plaintext)
+        .map(|text| (text, true))
+        .map_err(|_| format!("Decrypted {} are not valid UTF-8", what))
+}
@@ -0,0 +1,113 @@
+/// Profile import/export as standalone JSON or YAML files
+///
+/// An exported file holds one profile (regions, trigger, condition, actions with
+/// their LLM settings, guardrails) so it can be shared between machines or kept
+/// in a dotfiles repo. The format follows the file extension. Files carry the
+/// profile `schema_version` so older exports are migrated on import.
+use std::collections::HashSet;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::domain::{ActionConfig, Profile};
+use crate::migration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfileFormat {
+    Json,
+    Yaml,
+}
+
+impl ProfileFormat {
+    /// `.yaml`/`.yml` files are YAML; anything else is JSON
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase) {
+            Some(ext) if ext == "yaml" || ext == "yml" => ProfileFormat::Yaml,
+            _ => ProfileFormat::Json,
+        }
+    }
+}
+
+pub fn export_profile(profile: &Profile, format: ProfileFormat) -> Result<String, String> {
+    let mut value = serde_json::to_value(profile).map_err(|e| format!("Failed to serialize profile: {}", e))?;
+    if let Value::Object(obj) = &mut value {
+        obj.insert("schema_version".into(), migration::CURRENT_SCHEMA_VERSION.into());
+    }
+    match format {
+        ProfileFormat::Json => serde_json::to_string_pretty(&value).map_err(|e| e.to_string()),
+        ProfileFormat::Yaml => serde_yaml::to_string(&value).map_err(|e| e.to_string()),
+    }
+    .map_err(|e| format!("Failed to serialize profile: {}", e))
+}
+
+/// Parse an exported profile, migrate it to the current schema and reject one that could not run as-is
+pub fn import_profile(contents: &str, format: ProfileFormat) -> Result<Profile, String> {
+    let mut value: Value = match format {
+        ProfileFormat::Json => serde_json::from_str(contents).map_err(|e| e.to_string()),
+        ProfileFormat::Yaml => serde_yaml::from_str(contents).map_err(|e| e.to_string()),
+    }
+    .map_err(|e| format!("Invalid profile file: {}", e))?;
+    let from = migration::schema_version(&value);
+    migration::migrate_profile(&mut value, from)?;
+    let profile: Profile =
+        serde_json::from_value(value).map_err(|e| format!("Invalid profile file: {}", e))?;
+    check_importable(&profile)?;
+    Ok(profile)
+}
+
+pub fn check_importable(p: &Profile) -> Result<(), String> {
+    if p.id.trim().is_empty() {
+        return Err("Profile id must not be empty".into());
+    }
+    if p.name.trim().is_empty() {
+        return Err("Profile name must not be empty".into());
+    }
+    if !p.trigger.check_interval_sec.is_finite() || p.trigger.check_interval_sec <= 0.0 {
+        return Err(format!(
+            "Trigger interval must be positive, got {}",
+            p.trigger.check_interval_sec
+        ));
+    }
+    let mut region_ids = HashSet::new();
+    for r in &p.regions {
+        if !region_ids.insert(r.id.as_str()) {
+            return Err(format!("Duplicate region id '{}'", r.id));
+        }
+    }
+    for action in &p.actions {
+        let referenced = match action {
+            ActionConfig::LLMPromptGeneration {
+                region_ids: ids,
+                risk_threshold,
+                ..
+            } => {
+                if !(0.0..=1.0).contains(risk_threshold) {
+                    return Err(format!("Risk threshold must be between 0 and 1, got {}", risk_threshold));
+                }
+                ids
+            }
+            ActionConfig::TerminationCheck { ocr_region_ids, .. } => ocr_region_ids,
+            ActionConfig::ClickElement { element, .. } | ActionConfig::ReadElementText { element, .. } => {
+                element.validate()?;
+                continue;
+            }
+            _ => continue,
+        };
+        if let Some(missing) = referenced.iter().find(|id| !region_ids.contains(id.as_str())) {
+            return Err(format!("Action references unknown region '{}'", missing));
+        }
+    }
+    Ok(())
+}
+
+/// `id`, or `id-2`, `id-3`, ... when a profile with that id already exists
+pub fn unique_profile_id(id: &str, existing: &[Profile]) -> String {
+    let taken = |candidate: &str| existing.iter().any(|p| p.id == candidate);
+    if !taken(id) {
+        return id.to_string();
+    }
+    (2..)
+        .map(|n| format!("{}-{}", id, n))
+        .find(|candidate| !taken(candidate))
+        .expect("unbounded suffix search")
+}
@@ -0,0 +1,378 @@
+/// Prometheus metrics of the runs in this process
+///
+/// Run statistics in the history are per run and read in the app; a host that
+/// runs automation for days is better watched from the dashboards it already
+/// has. `Metrics` keeps counters and histograms of iterations, action
+/// failures, interventions, LLM calls with their latency, tokens and cost, and
+/// screen capture latency, labelled by profile. The runner feeds it events,
+/// and the LLM client and capture backend of a run are wrapped in
+/// `ObservedClient` and `ObservedCapture` to time each call. Values start at
+/// zero when the app starts, as Prometheus expects of counters.
+///
+/// With the `prometheus-metrics` feature and a port set in the settings, the
+/// metrics are served in the text exposition format at
+/// `http://127.0.0.1:<port>/metrics`. The endpoint listens on localhost only
+/// and needs no token; scrape it from an agent on the same host.
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::domain::{BackendError, DisplayInfo, Error, Event, LLMPromptResponse, Region, RunOutcome, ScreenCapture, ScreenFrame};
+use crate::llm::LLMClient;
+use crate::notify;
+
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+enum Kind {
+    Counter,
+    /// Upper bounds of the buckets, ascending; `+Inf` is implied
+    Histogram(&'static [f64]),
+}
+
+struct Family {
+    name: &'static str,
+    help: &'static str,
+    kind: Kind,
+}
+
+const ITERATIONS: Family = Family {
+    name: "loopautoma_iterations_total",
+    help: "Iterations of the action sequence, by result",
+    kind: Kind::Counter,
+};
+const ITERATION_SECONDS: Family = Family {
+    name: "loopautoma_iteration_duration_seconds",
+    help: "Time an iteration of the action sequence took",
+    kind: Kind::Histogram(&[0.1, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 300.0]),
+};
+const ACTION_FAILURES: Family = Family {
+    name: "loopautoma_action_failures_total",
+    help: "Actions that failed, by action",
+    kind: Kind::Counter,
+};
+const INTERVENTIONS: Family = Family {
+    name: "loopautoma_interventions_total",
+    help: "Times a run needed the user, by reason",
+    kind: Kind::Counter,
+};
+const RUNS: Family = Family {
+    name: "loopautoma_runs_total",
+    help: "Finished runs, by outcome",
+    kind: Kind::Counter,
+};
+const LLM_CALLS: Family = Family {
+    name: "loopautoma_llm_calls_total",
+    help: "LLM calls, by result",
+    kind: Kind::Counter,
+};
+const LLM_SECONDS: Family = Family {
+    name: "loopautoma_llm_latency_seconds",
+    help: "Time waiting for an LLM answer, retries included",
+    kind: Kind::Histogram(&[0.25, 0.5, 1.0, 2.0, 5.0, 10.0, 30.0, 60.0]),
+};
+const LLM_TOKENS: Family = Family {
+    name: "loopautoma_llm_tokens_total",
+    help: "Tokens of LLM calls, by kind",
+    kind: Kind::Counter,
+};
+const LLM_COST: Family = Family {
+    name: "loopautoma_llm_cost_usd_total",
+    help: "Estimated dollars spent on LLM calls; models without a known price add nothing",
+    kind: Kind::Counter,
+};
+const CAPTURE_SECONDS: Family = Family {
+    name: "loopautoma_capture_latency_seconds",
+    help: "Time to capture or hash a screen region",
+    kind: Kind::Histogram(&[0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0]),
+};
+
+/// In the order they are rendered
+const FAMILIES: &[&Family] = &[
+    &RUNS,
+    &ITERATIONS,
+    &ITERATION_SECONDS,
+    &ACTION_FAILURES,
+    &INTERVENTIONS,
+    &LLM_CALLS,
+    &LLM_SECONDS,
+    &LLM_TOKENS,
+    &LLM_COST,
+    &CAPTURE_SECONDS,
+];
+
+type Labels = Vec<(&'static str, String)>;
+
+#[derive(Default)]
+struct Histogram {
+    /// Observations per bucket, not cumulative
+    buckets: Vec<u64>,
+    count: u64,
+    sum: f64,
+}
+
+#[derive(Default)]
+struct Series {
+    counters: BTreeMap<(&'static str, Labels), f64>,
+    histograms: BTreeMap<(&'static str, Labels), Histogram>,
+}
+
+/// Counters and histograms of every run since the app started
+#[derive(Default)]
+pub struct Metrics {
+    series: Mutex<Series>,
+}
+
+fn labels(pairs: &[(&'static str, &str)]) -> Labels {
+    pairs.iter().map(|(k, v)| (*k, v.to_string())).collect()
+}
+
+impl Metrics {
+    fn count(&self, family: &Family, pairs: &[(&'static str, &str)], by: f64) {
+        *self.series.lock().unwrap().counters.entry((family.name, labels(pairs))).or_default() += by;
+    }
+
+    fn observe(&self, family: &Family, pairs: &[(&'static str, &str)], value: f64) {
+        let Kind::Histogram(bounds) = family.kind else {
+            return;
+        };
+        let mut series = self.series.lock().unwrap();
+        let h = series.histograms.entry((family.name, labels(pairs))).or_default();
+        h.buckets.resize(bounds.len(), 0);
+        if let Some(i) = bounds.iter().position(|bound| value <= *bound) {
+            h.buckets[i] += 1;
+        }
+        h.count += 1;
+        h.sum += value;
+    }
+
+    /// Count what a runner event reports; `iteration_time` is how long the iteration took
+    pub fn observe_event(&self, profile_id: &str, event: &Event, iteration_time: Duration) {
+        let profile = ("profile", profile_id);
+        match event {
+            Event::IterationCompleted { success, .. } => {
+                let result = if *success { "success" } else { "failure" };
+                self.count(&ITERATIONS, &[profile, ("result", result)], 1.0);
+                self.observe(&ITERATION_SECONDS, &[profile], iteration_time.as_secs_f64());
+            }
+            Event::ActionCompleted { action, success: false } => {
+                self.count(&ACTION_FAILURES, &[profile, ("action", action)], 1.0);
+            }
+            _ => {}
+        }
+        if let Some(reason) = notify::classify_event(event) {
+            let reason = serde_json::to_value(reason).ok();
+            self.count(&INTERVENTIONS, &[profile, ("reason", reason.as_ref().and_then(|r| r.as_str()).unwrap_or("unknown"))], 1.0);
+        }
+    }
+
+    pub fn observe_run(&self, profile_id: &str, outcome: &RunOutcome) {
+        self.count(&RUNS, &[("profile", profile_id), ("outcome", outcome.kind())], 1.0);
+    }
+
+    fn observe_llm_call(&self, profile_id: &str, latency: Duration, result: &Result<LLMPromptResponse, Error>) {
+        let profile = ("profile", profile_id);
+        let outcome = if result.is_ok() { "success" } else { "failure" };
+        self.count(&LLM_CALLS, &[profile, ("result", outcome)], 1.0);
+        self.observe(&LLM_SECONDS, &[profile], latency.as_secs_f64());
+        if let Some(usage) = result.as_ref().ok().and_then(|r| r.usage.as_ref()) {
+            self.count(&LLM_TOKENS, &[profile, ("kind", "prompt")], usage.prompt_tokens as f64);
+            self.count(&LLM_TOKENS, &[profile, ("kind", "completion")], usage.completion_tokens as f64);
+            self.count(&LLM_COST, &[profile], usage.cost_usd.unwrap_or(0.0));
+        }
+    }
+
+    /// Every series in the Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let series = self.series.lock().unwrap();
+        let mut out = String::new();
+        for family in FAMILIES {
+            let kind = match family.kind {
+                Kind::Counter => "counter",
+                Kind::Histogram(_) => "histogram",
+            };
+            out.push_str(&format!("# HELP {} {}\n# TYPE {} {}\n", family.name, family.help, family.name, kind));
+            match family.kind {
+                Kind::Counter => {
+                    for ((_, l), value) in series.counters.iter().filter(|((name, _), _)| *name == family.name) {
+                        out.push_str(&format!("{}{} {}\n", family.name, render_labels(l, None), value));
+                    }
+                }
+                Kind::Histogram(bounds) => {
+                    for ((_, l), h) in series.histograms.iter().filter(|((name, _), _)| *name == family.name) {
+                        let mut cumulative = 0;
+                        for (bound, n) in bounds.iter().zip(&h.buckets) {
+                            cumulative += n;
+                            let le = bound.to_string();
+                            out.push_str(&format!("{}_bucket{} {}\n", family.name, render_labels(l, Some(&le)), cumulative));
+                        }
+                        out.push_str(&format!("{}_bucket{} {}\n", family.name, render_labels(l, Some("+Inf")), h.count));
+                        out.push_str(&format!("{}_sum{} {}\n", family.name, render_labels(l, None), h.sum));
+                        out.push_str(&format!("{}_count{} {}\n", family.name, render_labels(l, None), h.count));
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+fn render_labels(labels: &Labels, le: Option<&str>) -> String {
+    let mut pairs: Vec<String> = labels.iter().map(|(k, v)| format!("{}=\"{}\"", k, escape_label(v))).collect();
+    if let Some(le) = le {
+        pairs.push(format!("le=\"{}\"", le));
+    }
+    if pairs.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", pairs.join(","))
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// Times every call of the wrapped client for the metrics of `profile_id`
+pub struct ObservedClient {
+    pub inner: Arc<dyn LLMClient>,
+    pub metrics: Arc<Metrics>,
+    pub profile_id: String,
+}
+
+impl LLMClient for ObservedClient {
+    fn generate_prompt(
+        &self,
+        regions: &[Region],
+        region_images: Vec<Vec<u8>>,
+        system_prompt: Option<&str>,
+        risk_guidance: &str,
+    ) -> Result<LLMPromptResponse, Error> {
+        let started = Instant::now();
+        let result = self.inner.generate_prompt(regions, region_images, system_prompt, risk_guidance);
+        self.metrics.observe_llm_call(&self.profile_id, started.elapsed(), &result);
+        result
+    }
+
+    fn is_mock(&self) -> bool {
+        self.inner.is_mock()
+    }
+
+    fn endpoint(&self) -> Option<String> {
+        self.inner.endpoint()
+    }
+}
+
+/// Times the region hashes and captures of the wrapped backend for the metrics of `profile_id`
+pub struct ObservedCapture {
+    pub inner: Box<dyn ScreenCapture + Send + Sync>,
+    pub metrics: Arc<Metrics>,
+    pub profile_id: String,
+}
+
+impl ObservedCapture {
+    fn time<T>(&self, f: impl FnOnce() -> T) -> T {
+        let started = Instant::now();
+        let result = f();
+        self.metrics
+            .observe(&CAPTURE_SECONDS, &[("profile", &self.profile_id)], started.elapsed().as_secs_f64());
+        result
+    }
+}
+
+impl ScreenCapture for ObservedCapture {
+    fn hash_region(&self, region: &Region, downscale: u32) -> u64 {
+        self.time(|| self.inner.hash_region(region, downscale))
+    }
+
+    fn capture_region(&self, region: &Region) -> Result<ScreenFrame, BackendError> {
+        self.time(|| self.inner.capture_region(region))
+    }
+
+    fn capture_and_hash(&self, region: &Region, downscale: u32) -> Result<(ScreenFrame, u64), BackendError> {
+        self.time(|| self.inner.capture_and_hash(region, downscale))
+    }
+
+    fn displays(&self) -> Result<Vec<DisplayInfo>, BackendError> {
+        self.inner.displays()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricsResponse {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+/// Answer one scrape; only `GET /metrics` is served
+pub fn handle(metrics: &Metrics, method: &str, url: &str) -> MetricsResponse {
+    let path = url.split_once('?').map_or(url, |(path, _)| path);
+    let (status, body) = match (method, path) {
+        ("GET", "/metrics") => {
+            return MetricsResponse {
+                status: 200,
+                content_type: CONTENT_TYPE,
+                body: metrics.render(),
+            }
+        }
+        (_, "/metrics") => (405, "Use GET\n"),
+        _ => (404, "Not found; metrics are at /metrics\n"),
+    };
+    MetricsResponse {
+        status,
+        content_type: "text/plain; charset=utf-8",
+        body: body.to_string(),
+    }
+}
+
+/// URL the exporter serves on `port`
+pub fn metrics_url(port: u16) -> String {
+    format!("http://127.0.0.1:{}/metrics", port)
+}
+
+/// A listening exporter; dropping it stops the server
+pub struct MetricsServer {
+    #[cfg(feature = "prometheus-metrics")]
+    server: Arc<tiny_http::Server>,
+    pub url: String,
+}
+
+#[cfg(feature = "prometheus-metrics")]
+impl Drop for MetricsServer {
+    fn drop(&mut self) {
+        self.server.unblock();
+    }
+}
+
+/// Serve `metrics` on 127.0.0.1:`port` from a background thread
+#[cfg(feature = "prometheus-metrics")]
+pub fn serve(port: u16, metrics: Arc<Metrics>) -> Result<MetricsServer, String> {
+    let address = format!("127.0.0.1:{}", port);
+    let server = tiny_http::Server::http(&address)
+        .map_err(|e| format!("Metrics exporter failed to listen on {}: {}", address, e))?;
+    let server = Arc::new(server);
+    let listener = server.clone();
+    std::thread::spawn(move || {
+        // `recv` fails once the server is unblocked
+        while let Ok(request) = listener.recv() {
+            let response = handle(&metrics, request.method().as_str(), request.url());
+            let content_type = tiny_http::Header::from_bytes("Content-Type", response.content_type)
+                .expect("static header is valid");
+            let reply = tiny_http::Response::from_string(response.body)
+                .with_status_code(response.status)
+                .with_header(content_type);
+            if let Err(e) = request.respond(reply) {
+                tracing::warn!("Failed to answer a metrics scrape: {}", e);
+            }
+        }
+    });
+    Ok(MetricsServer {
+        server,
+        url: metrics_url(port),
+    })
+}
+
+#[cfg(not(feature = "prometheus-metrics"))]
+pub fn serve(_port: u16, _metrics: Arc<Metrics>) -> Result<MetricsServer, String> {
+    Err("This build has no metrics exporter (feature 'prometheus-metrics')".into())
+}
@@ -0,0 +1,74 @@
+/// Crash recovery: a checkpoint of the active run, persisted as JSON
+///
+/// The runner rewrites the checkpoint after every iteration and removes it when the
+/// run ends. A checkpoint still on disk at startup belongs to a run that was cut
+/// short by a crash or reboot and can be resumed with `resume_last_run`.
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunCheckpoint {
+    pub profile_id: String,
+    pub started_at_ms: u64,
+    pub saved_at_ms: u64,
+    /// Action-sequence runs completed so far (counts towards max_iterations)
+    pub iterations: u32,
+    pub variables: HashMap<String, String>,
+    /// Name of the last action that completed successfully
+    #[serde(default)]
+    pub last_successful_action: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct CheckpointStore {
+    path: Option<PathBuf>,
+}
+
+impl CheckpointStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path: Some(path) }
+    }
+
+    /// The checkpoint on disk; a missing or unreadable file means there is nothing to resume
+    pub fn load(&self) -> Option<RunCheckpoint> {
+        let contents = std::fs::read_to_string(self.path.as_ref()?).ok()?;
+        serde_json::from_str(&contents).ok()
+    }
+
+    /// Persist via a temporary file so a crash mid-write never leaves a torn checkpoint
+    pub fn save(&self, checkpoint: &RunCheckpoint) -> Result<(), String> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let json = serde_json::to_string_pretty(checkpoint)
+            .map_err(|e| format!("Failed to serialize run checkpoint: {}", e))?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, json).map_err(|e| format!("Failed to write run checkpoint: {}", e))?;
+        std::fs::rename(&tmp, path).map_err(|e| format!("Failed to write run checkpoint: {}", e))
+    }
+
+    /// Remove the checkpoint of a finished run, leaving one written by a newer run alone
+    pub fn clear_run(&self, profile_id: &str, started_at_ms: u64) {
+        let ours = self
+            .load()
+            .is_some_and(|c| c.profile_id == profile_id && c.started_at_ms == started_at_ms);
+        if ours {
+            self.clear();
+        }
+    }
+
+    pub fn clear(&self) {
+        if let Some(path) = &self.path {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// Default location: `<config dir>/loopautoma/run_checkpoint.json`
+pub fn default_checkpoint_path() -> Option<PathBuf> {
+    let app_dir = dirs::config_dir()?.join("loopautoma");
+    std::fs::create_dir_all(&app_dir).ok()?;
+    Some(app_dir.join("run_checkpoint.json"))
+}
@@ -0,0 +1,84 @@
+/// Interactive on-screen region picker
+///
+/// The picker captures one display, shows the capture in a full-screen overlay
+/// on that display, and turns the rectangle drawn there into a `Region`. The
+/// overlay reports corners in physical desktop pixels (its CSS pixels scaled by
+/// the display's DPI factor), so the rectangle is clipped to the display it was
+/// drawn on and matches what `ScreenCapture` reads at any scale.
+///
+/// `region_pick` waits for the drawing and returns the region; the older
+/// `region_picker_show` flow only reports it as `loopautoma://region_pick_complete`.
+use std::sync::mpsc::Sender;
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{DisplayInfo, Rect, Region};
+
+/// The display to pick on: `id` if given, else the primary one
+pub fn choose_display(displays: &[DisplayInfo], id: Option<u32>) -> Result<DisplayInfo, String> {
+    let display = match id {
+        Some(id) => displays.iter().find(|d| d.id == id).ok_or_else(|| format!("Display {} not found", id))?,
+        None => displays
+            .iter()
+            .find(|d| d.is_primary)
+            .or_else(|| displays.first())
+            .ok_or("No displays found")?,
+    };
+    Ok(display.clone())
+}
+
+/// The part of `display` covered by the desktop coordinates regions can use
+pub fn display_rect(display: &DisplayInfo) -> Rect {
+    let clip = |origin: i32, len: u32| (origin.max(0) as u32, (origin as i64 + len as i64).max(0) as u32);
+    let (x, right) = clip(display.x, display.width);
+    let (y, bottom) = clip(display.y, display.height);
+    Rect {
+        x,
+        y,
+        width: right.saturating_sub(x),
+        height: bottom.saturating_sub(y),
+    }
+}
+
+/// A region drawn in the picker
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PickedRegion {
+    pub region: Region,
+    pub display_id: u32,
+    /// DPI factor of the display; the rectangle is already in physical pixels
+    pub scale_factor: f32,
+}
+
+/// Clip `rect` to `display` and name it `id`
+pub fn pick(rect: Rect, display: &DisplayInfo, id: String) -> Result<PickedRegion, String> {
+    let bounds = display_rect(display);
+    let x = rect.x.max(bounds.x);
+    let y = rect.y.max(bounds.y);
+    let right = (rect.x + rect.width).min(bounds.x + bounds.width);
+    let bottom = (rect.y + rect.height).min(bounds.y + bounds.height);
+    if right <= x || bottom <= y {
+        return Err(format!("Region is outside display {}", display.id));
+    }
+    Ok(PickedRegion {
+        region: Region {
+            id,
+            rect: Rect {
+                x,
+                y,
+                width: right - x,
+                height: bottom - y,
+            },
+            name: None,
+            anchor: None,
+        },
+        display_id: display.id,
+        scale_factor: display.scale_factor,
+    })
+}
+
+/// The open picker: the display it shows and who waits for its result
+pub struct PickerSession {
+    pub display: DisplayInfo,
+    /// Set by `region_pick`; receives `None` when the picker is cancelled
+    pub reply: Option<Sender<Option<PickedRegion>>>,
+}
@@ -0,0 +1,439 @@
+/// Remote control HTTP API
+///
+/// An optional JSON API to check on and control runs from another machine or
+/// a phone: list profiles, start, stop, pause or resume a run, read the runner
+/// status and the run history. Every request must carry the configured token as
+/// `Authorization: Bearer <token>`. By default the server listens on 127.0.0.1
+/// only; listening on the LAN requires a TLS certificate so the token never
+/// crosses the network in clear text.
+///
+/// Routes (all under `/api`):
+/// - `GET /profiles`, `GET /status`
+/// - `POST /profiles/<id>/start`, `POST /stop`, `POST /pause`, `POST /resume`
+/// - `GET /runs?profile_id=&limit=&offset=`, `GET /runs/<id>`
+///
+/// Requests are answered one at a time, so one client must not be able to hold
+/// up the others, e.g. a stop sent while someone streams an endless body. The
+/// token and the announced body size are checked before any of the body is
+/// read, and refused requests close their connection. A refused request whose
+/// body tiny_http already holds is answered on the accept thread. Dropping one
+/// with a larger or streamed body reads out what the client still sends, so
+/// those go to `DRAIN_THREADS` fixed threads. When `DRAIN_QUEUE` of them are
+/// already waiting, the accept thread answers the next one itself, so a flood
+/// slows the API down instead of piling up threads. Accepted bodies are at most
+/// `MAX_BODY_BYTES`, which tiny_http reads in full on the connection's thread,
+/// and connections time out after `READ_TIMEOUT` without data.
+use std::sync::Arc;
+#[cfg(feature = "remote-control")]
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::history::{RunDetails, RunQuery, RunRecord};
+use crate::runner_state::RunnerStatus;
+
+pub const DEFAULT_PORT: u16 = 8787;
+
+/// Shortest accepted token; generated tokens are 43 characters
+pub const MIN_TOKEN_LEN: usize = 24;
+
+/// Largest request body accepted; no route reads one
+pub const MAX_BODY_BYTES: usize = 1024;
+
+/// How long a connection may send nothing before it is closed
+#[cfg(feature = "remote-control")]
+pub const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Threads answering refused requests whose body is read out when they are dropped
+#[cfg(feature = "remote-control")]
+const DRAIN_THREADS: usize = 2;
+
+/// Refused requests waiting for a drain thread; further ones are answered on the accept thread
+#[cfg(feature = "remote-control")]
+const DRAIN_QUEUE: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TlsFiles {
+    /// PEM certificate chain
+    pub cert_path: String,
+    /// PEM private key (PKCS#8 or RSA)
+    pub key_path: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemoteApiConfig {
+    /// Listen on all interfaces instead of 127.0.0.1; requires `tls`
+    #[serde(default)]
+    pub lan: bool,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    /// Bearer token every request must send
+    pub token: String,
+    #[serde(default)]
+    pub tls: Option<TlsFiles>,
+}
+
+fn default_port() -> u16 {
+    DEFAULT_PORT
+}
+
+impl RemoteApiConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.port == 0 {
+            return Err("Remote API port is required".into());
+        }
+        if self.token.trim().len() < MIN_TOKEN_LEN {
+            return Err(format!("Remote API token must have at least {} characters", MIN_TOKEN_LEN));
+        }
+        match &self.tls {
+            Some(tls) if tls.cert_path.trim().is_empty() || tls.key_path.trim().is_empty() => {
+                Err("TLS needs a certificate and a private key file".into())
+            }
+            None if self.lan => Err("Listening on the LAN requires a TLS certificate".into()),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn address(&self) -> String {
+        let host = if self.lan { "0.0.0.0" } else { "127.0.0.1" };
+        format!("{}:{}", host, self.port)
+    }
+
+    /// Base URL as seen from this machine
+    pub fn url(&self) -> String {
+        let scheme = if self.tls.is_some() { "https" } else { "http" };
+        format!("{}://{}/api", scheme, self.address().replace("0.0.0.0", "localhost"))
+    }
+}
+
+/// A random token for a new configuration, URL-safe base64 of 32 bytes
+pub fn generate_token() -> String {
+    use aes_gcm::aead::rand_core::RngCore;
+    use aes_gcm::aead::OsRng;
+    use base64::Engine as _;
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfileSummary {
+    pub id: String,
+    pub name: String,
+}
+
+/// What the API can do; implemented over the app's command layer
+pub trait RemoteControl: Send + Sync {
+    fn profiles(&self) -> Vec<ProfileSummary>;
+    fn status(&self) -> RunnerStatus;
+    fn start(&self, profile_id: &str) -> Result<(), String>;
+    fn stop(&self);
+    fn set_paused(&self, paused: bool) -> Result<(), String>;
+    fn runs(&self, query: &RunQuery) -> Result<Vec<RunRecord>, String>;
+    fn run_details(&self, run_id: i64) -> Result<Option<RunDetails>, String>;
+}
+
+/// The parts of an HTTP request the API looks at
+pub struct ApiRequest<'a> {
+    pub method: &'a str,
+    /// Path and query string
+    pub url: &'a str,
+    pub authorization: Option<&'a str>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiResponse {
+    pub status: u16,
+    pub body: Value,
+}
+
+impl ApiResponse {
+    fn ok(body: impl Serialize) -> Self {
+        Self {
+            status: 200,
+            body: json!(body),
+        }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: json!({ "error": message.into() }),
+        }
+    }
+
+    fn done(result: Result<(), String>) -> Self {
+        match result {
+            Ok(()) => Self::ok(json!({ "ok": true })),
+            Err(e) => Self::error(409, e),
+        }
+    }
+}
+
+/// Compare without returning early, so response time does not reveal how much of a guess matched
+fn token_matches(expected: &str, authorization: Option<&str>) -> bool {
+    let Some(given) = authorization.and_then(|h| h.strip_prefix("Bearer ")) else {
+        return false;
+    };
+    let (a, b) = (expected.as_bytes(), given.trim().as_bytes());
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| std::str::from_utf8(h).ok());
+        match (bytes[i], hex.and_then(|h| u8::from_str_radix(h, 16).ok())) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (b'+', _) => {
+                out.push(b' ');
+                i += 1;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn parse_query(query: &str) -> Result<RunQuery, String> {
+    let mut q = RunQuery::default();
+    for pair in query.split('&').filter(|p| !p.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let value = percent_decode(value);
+        let number = || value.parse::<u32>().map_err(|_| format!("Invalid {} '{}'", key, value));
+        match key {
+            "profile_id" => q.profile_id = Some(value.clone()),
+            "limit" => q.limit = Some(number()?),
+            "offset" => q.offset = Some(number()?),
+            _ => {}
+        }
+    }
+    Ok(q)
+}
+
+/// How a request announced its body
+#[cfg_attr(not(feature = "remote-control"), allow(dead_code))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodySize {
+    Length(usize),
+    /// Chunked, or sent only after `Expect: 100-continue`: the size is not known up front
+    Streamed,
+}
+
+#[cfg_attr(not(feature = "remote-control"), allow(dead_code))]
+impl BodySize {
+    /// Whether tiny_http read the whole body with the headers, so dropping the request does not wait on the client
+    pub fn is_buffered(self) -> bool {
+        matches!(self, BodySize::Length(length) if length <= MAX_BODY_BYTES)
+    }
+}
+
+/// Refuse a request from its headers alone, before any of its body is read
+#[cfg_attr(not(feature = "remote-control"), allow(dead_code))]
+pub fn refuse_early(token: &str, authorization: Option<&str>, body: BodySize) -> Option<ApiResponse> {
+    if !token_matches(token, authorization) {
+        return Some(ApiResponse::error(401, "Missing or wrong bearer token"));
+    }
+    match body {
+        BodySize::Length(length) if length <= MAX_BODY_BYTES => None,
+        _ => Some(ApiResponse::error(
+            413,
+            format!("Request bodies are limited to {} bytes with a Content-Length", MAX_BODY_BYTES),
+        )),
+    }
+}
+
+/// Answer one request
+pub fn handle(api: &dyn RemoteControl, token: &str, req: &ApiRequest) -> ApiResponse {
+    if !token_matches(token, req.authorization) {
+        return ApiResponse::error(401, "Missing or wrong bearer token");
+    }
+    let (path, query) = req.url.split_once('?').unwrap_or((req.url, ""));
+    let segments: Vec<String> = path.trim_matches('/').split('/').map(percent_decode).collect();
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+    match (req.method, segments.as_slice()) {
+        ("GET", ["api", "profiles"]) => ApiResponse::ok(api.profiles()),
+        ("GET", ["api", "status"]) => ApiResponse::ok(api.status()),
+        ("POST", ["api", "profiles", id, "start"]) => {
+            if !api.profiles().iter().any(|p| p.id == *id) {
+                return ApiResponse::error(404, format!("No profile '{}'", id));
+            }
+            ApiResponse::done(api.start(id))
+        }
+        ("POST", ["api", "stop"]) => {
+            api.stop();
+            ApiResponse::done(Ok(()))
+        }
+        ("POST", ["api", "pause"]) => ApiResponse::done(api.set_paused(true)),
+        ("POST", ["api", "resume"]) => ApiResponse::done(api.set_paused(false)),
+        ("GET", ["api", "runs"]) => match parse_query(query).and_then(|q| api.runs(&q)) {
+            Ok(runs) => ApiResponse::ok(runs),
+            Err(e) => ApiResponse::error(400, e),
+        },
+        ("GET", ["api", "runs", id]) => match id.parse::<i64>() {
+            Ok(id) => match api.run_details(id) {
+                Ok(Some(run)) => ApiResponse::ok(run),
+                Ok(None) => ApiResponse::error(404, format!("No run {}", id)),
+                Err(e) => ApiResponse::error(500, e),
+            },
+            Err(_) => ApiResponse::error(400, format!("Invalid run id '{}'", id)),
+        },
+        (_, ["api", "profiles"] | ["api", "status"] | ["api", "runs", ..]) => ApiResponse::error(405, "Use GET"),
+        (_, ["api", "stop"] | ["api", "pause"] | ["api", "resume"] | ["api", "profiles", _, "start"]) => {
+            ApiResponse::error(405, "Use POST")
+        }
+        _ => ApiResponse::error(404, "Not found"),
+    }
+}
+
+/// A listening server; dropping it stops the server
+pub struct RemoteServer {
+    #[cfg(feature = "remote-control")]
+    server: Arc<tiny_http::Server>,
+    pub url: String,
+}
+
+#[cfg(feature = "remote-control")]
+impl Drop for RemoteServer {
+    fn drop(&mut self) {
+        self.server.unblock();
+    }
+}
+
+/// Start serving `api` on a background thread
+#[cfg(feature = "remote-control")]
+pub fn serve(config: &RemoteApiConfig, api: Arc<dyn RemoteControl>) -> Result<RemoteServer, String> {
+    config.validate()?;
+    let ssl = match &config.tls {
+        Some(tls) => {
+            let read = |path: &str| std::fs::read(path).map_err(|e| format!("Failed to read '{}': {}", path, e));
+            Some(tiny_http::SslConfig {
+                certificate: read(&tls.cert_path)?,
+                private_key: read(&tls.key_path)?,
+            })
+        }
+        None => None,
+    };
+    let server = listen(&config.address())
+        .map_err(|e| e.to_string())
+        .and_then(|listener| tiny_http::Server::from_listener(listener, ssl).map_err(|e| e.to_string()))
+        .map_err(|e| format!("Remote API failed to listen on {}: {}", config.address(), e))?;
+    let server = Arc::new(server);
+    let listener = server.clone();
+    let token = config.token.trim().to_string();
+    let (drain, refused) = std::sync::mpsc::sync_channel::<(tiny_http::Request, ApiResponse)>(DRAIN_QUEUE);
+    let refused = Arc::new(std::sync::Mutex::new(refused));
+    for _ in 0..DRAIN_THREADS {
+        let refused = refused.clone();
+        // Ends when the accept thread does and the queue is empty
+        std::thread::spawn(move || loop {
+            let next = refused.lock().unwrap().recv();
+            match next {
+                Ok((request, response)) => respond(request, &response, true),
+                Err(_) => break,
+            }
+        });
+    }
+    std::thread::spawn(move || {
+        use std::io::Read;
+        use std::sync::mpsc::TrySendError;
+
+        // `recv` fails once the server is unblocked
+        while let Ok(mut request) = listener.recv() {
+            let method = request.method().as_str().to_string();
+            let url = request.url().to_string();
+            let authorization = request
+                .headers()
+                .iter()
+                .find(|h| h.field.equiv("Authorization"))
+                .map(|h| h.value.as_str().to_string());
+            let streamed = request
+                .headers()
+                .iter()
+                .any(|h| h.field.equiv("Transfer-Encoding") || h.field.equiv("Expect"));
+            let body = match request.body_length() {
+                Some(length) if !streamed => BodySize::Length(length),
+                None if !streamed => BodySize::Length(0),
+                _ => BodySize::Streamed,
+            };
+            if let Some(response) = refuse_early(&token, authorization.as_deref(), body) {
+                if body.is_buffered() {
+                    respond(request, &response, true);
+                    continue;
+                }
+                // Dropping the request reads the rest of its body, which the client may never send
+                if let Err(TrySendError::Full((request, response)) | TrySendError::Disconnected((request, response))) =
+                    drain.try_send((request, response))
+                {
+                    tracing::warn!("Too many refused requests pending; answering {} {} in line", method, url);
+                    respond(request, &response, true);
+                }
+                continue;
+            }
+            // Bodies are not used; drain them so keep-alive connections stay in sync. tiny_http
+            // has read bodies this small already, so this does not wait on the client
+            let _ = std::io::copy(&mut request.as_reader().take(MAX_BODY_BYTES as u64), &mut std::io::sink());
+            let response = handle(
+                api.as_ref(),
+                &token,
+                &ApiRequest {
+                    method: &method,
+                    url: &url,
+                    authorization: authorization.as_deref(),
+                },
+            );
+            respond(request, &response, false);
+        }
+    });
+    Ok(RemoteServer {
+        server,
+        url: config.url(),
+    })
+}
+
+/// Bind `address` with `READ_TIMEOUT`, which accepted connections inherit
+#[cfg(feature = "remote-control")]
+fn listen(address: &str) -> std::io::Result<std::net::TcpListener> {
+    use socket2::{Domain, Protocol, Socket, Type};
+
+    let address: std::net::SocketAddr = address
+        .parse()
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+    let socket = Socket::new(Domain::for_address(address), Type::STREAM, Some(Protocol::TCP))?;
+    #[cfg(unix)]
+    socket.set_reuse_address(true)?;
+    socket.set_read_timeout(Some(READ_TIMEOUT))?;
+    socket.bind(&address.into())?;
+    socket.listen(128)?;
+    Ok(socket.into())
+}
+
+/// Send `response` as JSON, closing the connection after it if `close`
+#[cfg(feature = "remote-control")]
+fn respond(request: tiny_http::Request, response: &ApiResponse, close: bool) {
+    let (method, url) = (request.method().to_string(), request.url().to_string());
+    let content_type = tiny_http::Header::from_bytes("Content-Type", "application/json").expect("static header is valid");
+    let mut reply = tiny_http::Response::from_string(response.body.to_string())
+        .with_status_code(response.status)
+        .with_header(content_type);
+    if close {
+        reply.add_header(tiny_http::Header::from_bytes("Connection", "close").expect("static header is valid"));
+    }
+    if let Err(e) = request.respond(reply) {
+        tracing::warn!("Failed to answer {} {}: {}", method, url, e);
+    }
+}
+
+#[cfg(not(feature = "remote-control"))]
+pub fn serve(_config: &RemoteApiConfig, _api: Arc<dyn RemoteControl>) -> Result<RemoteServer, String> {
+    Err("This build has no remote control API (feature 'remote-control')".into())
+}
@@ -0,0 +1,292 @@
+/// Shareable reports of recorded runs, as Markdown or a standalone HTML page
+///
+/// A report covers one run from the history store: a summary with the outcome
+/// and LLM spend, a timeline of its iterations with the actions they ran and
+/// the prompts and risks the model answered with, the errors it reported, what
+/// the privacy filter masked and its archived screenshots. HTML reports embed the screenshots, so the file
+/// can be attached or handed on by itself; Markdown reports link to them by
+/// path. The format follows the file extension, like profile exports.
+use std::path::Path;
+
+use base64::Engine as _;
+use chrono::TimeZone;
+
+use crate::domain::{PiiKind, RunOutcome};
+use crate::history::{RunDetails, RunIteration, MAX_RUN_ITERATIONS};
+use crate::privacy::MaskedPii;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    /// `.html`/`.htm` files are HTML; anything else is Markdown
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase) {
+            Some(ext) if ext == "html" || ext == "htm" => ReportFormat::Html,
+            _ => ReportFormat::Markdown,
+        }
+    }
+}
+
+/// The report of `run` in `format`; HTML reads the screenshots to embed them
+pub fn render_report(run: &RunDetails, format: ReportFormat) -> String {
+    let report = Report::of(run);
+    match format {
+        ReportFormat::Markdown => report.markdown(),
+        ReportFormat::Html => report.html(),
+    }
+}
+
+const TIMELINE_COLUMNS: [&str; 6] = ["#", "Started", "Took", "Result", "Actions", "LLM"];
+
+/// The report's text, formatted but not yet escaped
+struct Report<'a> {
+    title: String,
+    summary: Vec<(&'static str, String)>,
+    /// Cells of each iteration, with whether it succeeded
+    timeline: Vec<(bool, [String; 6])>,
+    /// Said below the timeline when iterations are missing from it
+    timeline_note: Option<String>,
+    errors: Vec<String>,
+    screenshots: &'a [String],
+}
+
+impl<'a> Report<'a> {
+    fn of(run: &'a RunDetails) -> Self {
+        let r = &run.record;
+        let successful = run.timeline.iter().filter(|it| it.success).count();
+        let mut summary = vec![
+            ("Profile", r.profile_id.clone()),
+            ("Started", local_time(r.started_at_ms, "%Y-%m-%d %H:%M:%S %:z")),
+            ("Ended", local_time(r.ended_at_ms, "%Y-%m-%d %H:%M:%S %:z")),
+            ("Duration", seconds(r.ended_at_ms.saturating_sub(r.started_at_ms))),
+            ("Outcome", describe_outcome(&r.outcome)),
+            ("Iterations", r.iterations.to_string()),
+            (
+                "LLM calls",
+                format!(
+                    "{} ({} prompt / {} completion tokens, ${:.4})",
+                    r.llm_calls, run.llm_prompt_tokens, run.llm_completion_tokens, r.llm_cost_usd
+                ),
+            ),
+            ("Errors", r.error_count.to_string()),
+        ];
+        if !run.masked.is_empty() {
+            summary.push(("Masked", describe_masked(&run.masked)));
+        }
+        if !run.timeline.is_empty() {
+            summary.insert(6, ("Successful iterations", format!("{} of {}", successful, run.timeline.len())));
+        }
+        let recorded = run.timeline.len();
+        let timeline_note = if recorded == 0 {
+            Some("No iterations were recorded for this run.".to_string())
+        } else if (r.iterations as usize) > recorded {
+            Some(format!(
+                "Only the first {} of {} iterations are recorded (at most {} per run; a resumed run starts counting where it left off).",
+                recorded, r.iterations, MAX_RUN_ITERATIONS
+            ))
+        } else {
+            None
+        };
+        let mut errors: Vec<String> = run
+            .errors
+            .iter()
+            .map(|e| format!("{} {}", local_time(e.at_ms, "%H:%M:%S"), e.message))
+            .collect();
+        if (r.error_count as usize) > errors.len() {
+            errors.push(format!("… and {} more", r.error_count as usize - errors.len()));
+        }
+        Self {
+            title: format!("Run report: {}", r.profile_id),
+            summary,
+            timeline: run.timeline.iter().map(|it| (it.success, timeline_row(it))).collect(),
+            timeline_note,
+            errors,
+            screenshots: &run.screenshots,
+        }
+    }
+
+    fn markdown(&self) -> String {
+        let cell = |text: &str| text.replace('|', "\\|").replace('\n', " ");
+        let mut out = format!("# {}\n\n| | |\n|---|---|\n", self.title);
+        for (name, value) in &self.summary {
+            out.push_str(&format!("| {} | {} |\n", name, cell(value)));
+        }
+        out.push_str("\n## Timeline\n\n");
+        if !self.timeline.is_empty() {
+            out.push_str(&format!("| {} |\n|{}\n", TIMELINE_COLUMNS.join(" | "), "---|".repeat(TIMELINE_COLUMNS.len())));
+            for (_, row) in &self.timeline {
+                let cells: Vec<String> = row.iter().map(|c| cell(c)).collect();
+                out.push_str(&format!("| {} |\n", cells.join(" | ")));
+            }
+            out.push('\n');
+        }
+        if let Some(note) = &self.timeline_note {
+            out.push_str(&format!("_{}_\n\n", note));
+        }
+        if !self.errors.is_empty() {
+            out.push_str("## Errors\n\n");
+            for e in &self.errors {
+                out.push_str(&format!("- {}\n", e.replace('\n', " ")));
+            }
+            out.push('\n');
+        }
+        if !self.screenshots.is_empty() {
+            out.push_str("## Screenshots\n\n");
+            for (i, path) in self.screenshots.iter().enumerate() {
+                out.push_str(&format!("![Screenshot {}](<{}>)\n", i + 1, path));
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    fn html(&self) -> String {
+        let mut out = format!(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n<h1>{}</h1>\n<table>\n",
+            escape_html(&self.title),
+            STYLE,
+            escape_html(&self.title)
+        );
+        for (name, value) in &self.summary {
+            out.push_str(&format!("<tr><th>{}</th><td>{}</td></tr>\n", name, escape_html(value)));
+        }
+        out.push_str("</table>\n<h2>Timeline</h2>\n");
+        if !self.timeline.is_empty() {
+            out.push_str("<table>\n<tr>");
+            for column in TIMELINE_COLUMNS {
+                out.push_str(&format!("<th>{}</th>", column));
+            }
+            out.push_str("</tr>\n");
+            for (success, row) in &self.timeline {
+                out.push_str(if *success { "<tr>" } else { "<tr class=\"failed\">" });
+                for c in row {
+                    out.push_str(&format!("<td>{}</td>", escape_html(c)));
+                }
+                out.push_str("</tr>\n");
+            }
+            out.push_str("</table>\n");
+        }
+        if let Some(note) = &self.timeline_note {
+            out.push_str(&format!("<p><em>{}</em></p>\n", escape_html(note)));
+        }
+        if !self.errors.is_empty() {
+            out.push_str("<h2>Errors</h2>\n<ul>\n");
+            for e in &self.errors {
+                out.push_str(&format!("<li>{}</li>\n", escape_html(e)));
+            }
+            out.push_str("</ul>\n");
+        }
+        if !self.screenshots.is_empty() {
+            out.push_str("<h2>Screenshots</h2>\n");
+            for path in self.screenshots {
+                match std::fs::read(path) {
+                    Ok(png) => out.push_str(&format!(
+                        "<figure><img alt=\"Screenshot\" src=\"data:image/png;base64,{}\"><figcaption>{}</figcaption></figure>\n",
+                        base64::engine::general_purpose::STANDARD.encode(png),
+                        escape_html(path)
+                    )),
+                    Err(_) => out.push_str(&format!("<p>Screenshot missing: {}</p>\n", escape_html(path))),
+                }
+            }
+        }
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+}
+
+const STYLE: &str = "body{font-family:sans-serif;margin:2em;color:#222}\
+table{border-collapse:collapse;margin-bottom:1em}\
+th,td{border:1px solid #ccc;padding:4px 8px;text-align:left;vertical-align:top}\
+tr.failed td{background:#fdecea}\
+img{max-width:100%;border:1px solid #ccc}";
+
+fn timeline_row(it: &RunIteration) -> [String; 6] {
+    let actions = it
+        .actions
+        .iter()
+        .map(|a| format!("{} {}", a.action, if a.success { "✓" } else { "✗" }))
+        .collect::<Vec<_>>()
+        .join(" → ");
+    let llm = it
+        .llm_calls
+        .iter()
+        .map(|call| {
+            let risk = call.risk.map(|r| format!("risk {:.2}", r));
+            match (&call.prompt, risk) {
+                (Some(prompt), Some(risk)) => format!("{}: “{}”", risk, prompt),
+                (Some(prompt), None) => format!("“{}”", prompt),
+                (None, Some(risk)) => risk,
+                (None, None) if call.success => "answered".to_string(),
+                (None, None) => "failed".to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("; ");
+    [
+        it.iteration.to_string(),
+        local_time(it.started_at_ms, "%H:%M:%S"),
+        seconds(it.ended_at_ms.saturating_sub(it.started_at_ms)),
+        if it.success { "✓" } else { "✗" }.to_string(),
+        actions,
+        llm,
+    ]
+}
+
+/// How a run ended, as the run history panel words it
+fn describe_outcome(outcome: &RunOutcome) -> String {
+    match outcome {
+        RunOutcome::Stopped => "Stopped".to_string(),
+        RunOutcome::Completed { reason } => format!("Completed: {}", reason),
+        RunOutcome::BudgetExceeded { budget } => format!("Budget exceeded: {}", budget),
+        RunOutcome::WatchdogTripped { reason } => format!("Watchdog: {}", reason),
+        RunOutcome::Failed { reason } => format!("Failed: {}", reason),
+    }
+}
+
+/// E.g. `2 emails in inbox, 1 API key in llm_prompt`
+fn describe_masked(masked: &[MaskedPii]) -> String {
+    let kind = |kind: PiiKind, count: u32| match (kind, count) {
+        (PiiKind::Email, 1) => "email",
+        (PiiKind::Email, _) => "emails",
+        (PiiKind::CreditCard, 1) => "card number",
+        (PiiKind::CreditCard, _) => "card numbers",
+        (PiiKind::ApiKey, 1) => "API key",
+        (PiiKind::ApiKey, _) => "API keys",
+    };
+    masked
+        .iter()
+        .map(|m| format!("{} {} in {}", m.count, kind(m.kind, m.count), m.source))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn local_time(ms: u64, format: &str) -> String {
+    chrono::Local
+        .timestamp_millis_opt(ms as i64)
+        .single()
+        .map(|t| t.format(format).to_string())
+        .unwrap_or_default()
+}
+
+fn seconds(ms: u64) -> String {
+    format!("{:.1} s", ms as f64 / 1000.0)
+}
+
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
@@ -0,0 +1,369 @@
+/// The run of one profile: building its monitor and ticking it until it ends
+///
+/// The app's runner and the headless MCP runner both go through here, so a
+/// profile behaves the same whoever starts it: its secrets, run windows and
+/// connectivity probe, the Prometheus metrics, crash checkpoints, frame
+/// archive and the run history record. What the caller does with each event
+/// (emit it to the UI and notify, or follow the runner status) goes through
+/// `RunHooks`; storing the finished record is left to the caller too.
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tracing::{info, info_span, warn};
+
+use crate::domain::{
+    Automation, Event, FrameArchiveConfig, MonitorState, Profile, Region, RunOutcome, ScreenCapture, SecretLookup,
+};
+use crate::history::{self, RunDetails, RunHistory, RunRecord};
+use crate::llm::{LLMClient, MeteredClient, UsageTotals};
+use crate::metrics::{TimedCapture, Timing};
+use crate::monitor::Monitor;
+use crate::prometheus::{Metrics, ObservedCapture, ObservedClient};
+use crate::recovery::{CheckpointStore, RunCheckpoint};
+use crate::schedule::{self, RunSchedule};
+use crate::shutdown::{ReleasingAutomation, ShutdownToken};
+use crate::watchdog::{ActionInterrupt, ActionWatchdog};
+use crate::{network, privacy, timelapse};
+
+/// Time between scheduler ticks; the trigger decides whether one fires
+const TICK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Where runs report and persist, shared by every run of the process
+#[derive(Clone, Default)]
+pub struct RunSinks {
+    pub metrics: Arc<Metrics>,
+    pub checkpoints: Arc<CheckpointStore>,
+    pub history: Arc<Mutex<RunHistory>>,
+}
+
+/// Flags the owner of a run uses to steer it from another thread
+#[derive(Clone)]
+pub struct RunControls {
+    /// Stops the run; cancelled by stop or at app exit
+    pub cancel: ShutdownToken,
+    /// Requested pause state, applied between ticks
+    pub pause: Arc<AtomicBool>,
+    /// Set by a panic stop, reported as a tripped watchdog
+    pub panic: Arc<AtomicBool>,
+    /// Cancels the action in flight when pausing
+    pub interrupt: ActionInterrupt,
+}
+
+impl RunControls {
+    pub fn new(cancel: ShutdownToken) -> Self {
+        Self {
+            interrupt: ActionInterrupt::new(cancel.clone()),
+            cancel,
+            pause: Arc::default(),
+            panic: Arc::default(),
+        }
+    }
+}
+
+/// What the owner of a run does with its progress
+pub trait RunHooks {
+    /// An event of the run, after it is counted in the history record and metrics
+    fn on_event(&mut self, event: &Event);
+
+    /// An event of a run being stopped from outside
+    fn on_stopped(&mut self, event: &Event) {
+        self.on_event(event);
+    }
+
+    /// After each tick, before its events
+    fn on_tick(&mut self, _mon: &Monitor) {}
+}
+
+/// A profile's monitor with the backends and bookkeeping of one run
+pub struct ProfileRun {
+    /// The profile with its templates expanded
+    pub profile: Profile,
+    pub mon: Monitor<'static>,
+    /// Releases input a stopped run leaves held
+    pub automation: Arc<ReleasingAutomation<dyn Automation + Send + Sync>>,
+    pub started_at_ms: u64,
+    regions: Vec<Region>,
+    schedule: RunSchedule,
+    capture: TimedCapture,
+    capture_timing: Arc<Mutex<Timing>>,
+    usage: Arc<Mutex<UsageTotals>>,
+    sinks: RunSinks,
+}
+
+impl ProfileRun {
+    /// Build the monitor of `profile`; `llm` is metered and observed for this run
+    pub fn prepare(
+        profile: &Profile,
+        llm: Arc<dyn LLMClient>,
+        secrets: SecretLookup,
+        resume_from: Option<&RunCheckpoint>,
+        sinks: RunSinks,
+    ) -> Result<Self, String> {
+        let profile = crate::expand::expand_profile(profile)?;
+        let schedule = RunSchedule::parse(&profile.run_windows)?;
+        let usage = Arc::new(Mutex::new(UsageTotals::default()));
+        let llm: Arc<dyn LLMClient> = Arc::new(MeteredClient {
+            inner: Arc::new(ObservedClient {
+                inner: llm,
+                metrics: sinks.metrics.clone(),
+                profile_id: profile.id.clone(),
+            }),
+            totals: usage.clone(),
+        });
+        let probe = network::make_connectivity_probe(llm.as_ref());
+        let (mut mon, regions) = crate::build_monitor_with_llm(&profile, llm);
+        mon.context.secrets = secrets;
+        mon.set_connectivity_probe(Arc::from(probe));
+
+        // backends: OS adapters by default; set LOOPAUTOMA_BACKEND=fake to force fakes
+        let capture_timing = Arc::new(Mutex::new(Timing::default()));
+        let capture = TimedCapture {
+            inner: Box::new(ObservedCapture {
+                inner: crate::make_capture(),
+                metrics: sinks.metrics.clone(),
+                profile_id: profile.id.clone(),
+            }),
+            timing: capture_timing.clone(),
+        };
+        let automation = Arc::new(ReleasingAutomation::new(crate::make_automation()));
+        mon.actions.watchdog = Some(ActionWatchdog::new(mon.guardrails.action_timeout, automation.clone()));
+        let started_at_ms = resume_from.map_or_else(history::now_ms, |cp| cp.started_at_ms);
+        Ok(Self {
+            profile,
+            mon,
+            automation,
+            started_at_ms,
+            regions,
+            schedule,
+            capture,
+            capture_timing,
+            usage,
+            sinks,
+        })
+    }
+
+    /// Start the monitor, continuing from `resume_from` if given, and write the first checkpoint
+    ///
+    /// A run started outside its run windows is queued until one opens.
+    pub fn start(
+        &mut self,
+        variables: impl IntoIterator<Item = (String, String)>,
+        resume_from: Option<RunCheckpoint>,
+        events: &mut Vec<Event>,
+    ) {
+        let mon = &mut self.mon;
+        mon.start(events);
+        mon.context.variables.extend(variables);
+        if let Some(cp) = resume_from {
+            mon.iterations = cp.iterations;
+            mon.context.variables.extend(cp.variables);
+            mon.last_successful_action = cp.last_successful_action;
+        }
+        self.save_checkpoint();
+        if !self.schedule.is_open(schedule::local_now()) {
+            self.mon.pause_as(Instant::now(), MonitorState::OutsideRunWindow, events);
+        }
+    }
+
+    /// Tick until the run ends or is stopped; the record is complete but not yet stored
+    pub fn run(&mut self, controls: &RunControls, hooks: &mut dyn RunHooks) -> RunDetails {
+        let profile_id = self.profile.id.clone();
+        let span = info_span!(
+            "run",
+            profile = %profile_id,
+            outcome = tracing::field::Empty,
+            success = tracing::field::Empty,
+            iterations = tracing::field::Empty
+        )
+        .entered();
+        let mut checkpointed_iterations = self.mon.iterations;
+        let mut archived_iterations = 0;
+        let mut run = RunDetails::new(RunRecord {
+            id: 0,
+            profile_id: profile_id.clone(),
+            started_at_ms: self.started_at_ms,
+            ended_at_ms: self.started_at_ms,
+            iterations: 0,
+            outcome: RunOutcome::Stopped,
+            llm_calls: 0,
+            llm_cost_usd: 0.0,
+            error_count: 0,
+        });
+        loop {
+            let mon = &mut self.mon;
+            if controls.cancel.is_cancelled() {
+                let evs = crate::finalize_monitor_shutdown(mon, controls.panic.load(Ordering::Relaxed));
+                for e in evs {
+                    run.observe(history::now_ms(), &e);
+                    self.sinks.metrics.observe_event(&profile_id, &e, mon.last_iteration_time);
+                    hooks.on_stopped(&e);
+                }
+                break;
+            }
+
+            if mon.started_at.is_none() {
+                break;
+            }
+
+            let now = Instant::now();
+            let mut evs = vec![];
+            // Pause/resume between ticks; a pause during a tick cancels the action in flight.
+            // A user pause is reported over a closed run window
+            let hold = if controls.pause.load(Ordering::Relaxed) {
+                Some(MonitorState::Paused)
+            } else if !self.schedule.is_open(schedule::local_now()) {
+                Some(MonitorState::OutsideRunWindow)
+            } else {
+                None
+            };
+            match hold {
+                // A timed-out action stays reported as such until the user resumes
+                Some(_) if mon.pause_state() == Some(MonitorState::ActionTimedOut) => {}
+                Some(state) => mon.pause_as(now, state, &mut evs),
+                None if mon.is_paused() => mon.resume(now, &mut evs),
+                None => {}
+            }
+            mon.context.cancel = controls.interrupt.token();
+            mon.tick(now, &self.regions, &self.capture, &*self.automation, &mut evs);
+            if mon.context.cancel.is_cancelled() {
+                let released = self.automation.release_all();
+                if released > 0 {
+                    info!("Released {} key(s) or button(s) held by a cancelled action", released);
+                }
+            }
+            // The watchdog's hold lasts until the user resumes, like a pause
+            if mon.pause_state() == Some(MonitorState::ActionTimedOut) {
+                controls.pause.store(true, Ordering::Relaxed);
+            }
+            if let Some(archive) = self.profile.frame_archive {
+                for e in &evs {
+                    if let (Event::IterationCompleted { iteration, .. }, true) = (e, archived_iterations < archive.max_frames) {
+                        archived_iterations += 1;
+                        // Past the timed capture, so the archive does not count towards the run's capture metrics
+                        run.frames.extend(archive_region_frames(
+                            &*self.capture.inner,
+                            mon.actions.privacy.as_deref(),
+                            &self.regions,
+                            archive,
+                            &self.sinks.history,
+                            &profile_id,
+                            self.started_at_ms,
+                            *iteration,
+                        ));
+                    }
+                }
+            }
+            if let Some(filter) = &mon.actions.privacy {
+                run.add_masked(filter.take_report());
+            }
+            hooks.on_tick(mon);
+            for e in evs {
+                run.observe(history::now_ms(), &e);
+                run.metrics.observe(&e, mon.last_iteration_time);
+                self.sinks.metrics.observe_event(&profile_id, &e, mon.last_iteration_time);
+                hooks.on_event(&e);
+            }
+            if mon.iterations != checkpointed_iterations {
+                checkpointed_iterations = mon.iterations;
+                self.save_checkpoint();
+            }
+            if self.mon.started_at.is_none() {
+                break;
+            }
+            controls.cancel.sleep(TICK_INTERVAL);
+        }
+        let released = self.automation.release_all();
+        if released > 0 {
+            info!("Released {} key(s) or button(s) left held", released);
+        }
+
+        let outcome = if controls.panic.load(Ordering::Relaxed) {
+            RunOutcome::WatchdogTripped {
+                reason: "panic_stop".into(),
+            }
+        } else {
+            self.mon.outcome.take().unwrap_or(RunOutcome::Stopped)
+        };
+        // The run ended here rather than in a crash: nothing to recover
+        self.sinks.checkpoints.clear_run(&profile_id, self.started_at_ms);
+        self.sinks.metrics.observe_run(&profile_id, &outcome);
+        span.record("outcome", outcome.kind());
+        span.record("iterations", self.mon.iterations);
+        if let Some(succeeded) = outcome.succeeded() {
+            span.record("success", succeeded);
+        }
+        let usage = *self.usage.lock().unwrap();
+        run.record.ended_at_ms = history::now_ms();
+        run.record.iterations = self.mon.iterations;
+        run.record.outcome = outcome;
+        run.record.llm_calls = usage.calls;
+        run.record.llm_cost_usd = usage.cost_usd;
+        run.llm_prompt_tokens = usage.prompt_tokens;
+        run.llm_completion_tokens = usage.completion_tokens;
+        let captures = *self.capture_timing.lock().unwrap();
+        run.metrics.captures = captures.count;
+        run.metrics.capture_ms = captures.total.as_secs_f64() * 1000.0;
+        run.metrics.llm_ms = usage.latency.as_secs_f64() * 1000.0;
+        run
+    }
+
+    /// Write the crash-recovery checkpoint of the run (best effort)
+    fn save_checkpoint(&self) {
+        let checkpoint = RunCheckpoint {
+            profile_id: self.profile.id.clone(),
+            started_at_ms: self.started_at_ms,
+            saved_at_ms: history::now_ms(),
+            iterations: self.mon.iterations,
+            variables: self.mon.context.variables.clone(),
+            last_successful_action: self.mon.last_successful_action.clone(),
+        };
+        if let Err(e) = self.sinks.checkpoints.save(&checkpoint) {
+            warn!("Failed to save run checkpoint: {}", e);
+        }
+    }
+}
+
+/// Capture every region into the run's frame archive; regions that fail (or cannot be masked) are skipped
+#[allow(clippy::too_many_arguments)]
+fn archive_region_frames(
+    capture: &dyn ScreenCapture,
+    privacy: Option<&privacy::PrivacyFilter>,
+    regions: &[Region],
+    archive: FrameArchiveConfig,
+    run_history: &Mutex<RunHistory>,
+    profile_id: &str,
+    started_at_ms: u64,
+    iteration: u32,
+) -> Vec<history::RunFrame> {
+    let mut frames = Vec::new();
+    for region in regions {
+        let captured = capture.capture_region(region).and_then(|frame| match privacy {
+            Some(filter) => filter.mask(region, frame),
+            None => Ok(frame),
+        });
+        let png = match captured {
+            Ok(frame) => timelapse::archive_png(&frame, archive.max_size),
+            Err(e) => {
+                warn!("Failed to capture region {} for the frame archive: {}", region.id, e);
+                continue;
+            }
+        };
+        let Some(png) = png else { continue };
+        match run_history
+            .lock()
+            .unwrap()
+            .archive_frame(profile_id, started_at_ms, &region.id, iteration, &png)
+        {
+            Ok(Some(path)) => frames.push(history::RunFrame {
+                region_id: region.id.clone(),
+                iteration,
+                at_ms: history::now_ms(),
+                path,
+            }),
+            Ok(None) => {}
+            Err(e) => warn!("Failed to archive frame of region {}: {}", region.id, e),
+        }
+    }
+    frames
+}
@@ -0,0 +1,161 @@
+/// Runner state transitions for a live activity feed
+///
+/// `loopautoma://event` carries every runner event, ticks included, for the
+/// event log. `runner://state` carries only the transitions a feed renders:
+/// trigger fired, action and LLM call started or finished, iteration done,
+/// run state changes and errors. Each is stamped with the profile and the
+/// time it happened, so the UI needs no parsing or guessing from log text.
+///
+/// `RunnerStatus` folds the transitions into what the current run is doing,
+/// for the tray icon and the remote control API.
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{Event, MonitorState};
+
+pub const CHANNEL: &str = "runner://state";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Transition {
+    RunState {
+        state: MonitorState,
+    },
+    TriggerFired,
+    ActionStarted {
+        action: String,
+    },
+    ActionFinished {
+        action: String,
+        success: bool,
+    },
+    LlmCallStarted {
+        action: String,
+    },
+    LlmCallFinished {
+        action: String,
+        success: bool,
+        risk: Option<f64>,
+    },
+    IterationCompleted {
+        iteration: u32,
+        success: bool,
+    },
+    /// A guardrail or budget is ending the run
+    Guardrail {
+        reason: String,
+    },
+    Error {
+        message: String,
+    },
+}
+
+impl Transition {
+    /// The transition an event reports; ticks, debug pauses and notices are not transitions
+    pub fn from_event(event: &Event) -> Option<Self> {
+        Some(match event {
+            Event::MonitorStateChanged { state } => Self::RunState { state: *state },
+            Event::TriggerFired => Self::TriggerFired,
+            Event::ActionStarted { action } => Self::ActionStarted { action: action.clone() },
+            Event::ActionCompleted { action, success } => Self::ActionFinished {
+                action: action.clone(),
+                success: *success,
+            },
+            Event::LlmCallStarted { action } => Self::LlmCallStarted { action: action.clone() },
+            Event::LlmCallFinished { action, success, risk, .. } => Self::LlmCallFinished {
+                action: action.clone(),
+                success: *success,
+                risk: *risk,
+            },
+            Event::IterationCompleted { iteration, success } => Self::IterationCompleted {
+                iteration: *iteration,
+                success: *success,
+            },
+            Event::WatchdogTripped { reason } | Event::TerminationCheckTriggered { reason } => {
+                Self::Guardrail { reason: reason.clone() }
+            }
+            Event::BudgetExceeded { budget, .. } => Self::Guardrail { reason: budget.clone() },
+            Event::Error { message } => Self::Error { message: message.clone() },
+            _ => return None,
+        })
+    }
+}
+
+/// Payload of `runner://state`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunnerStateUpdate {
+    pub profile_id: String,
+    pub at_ms: u64,
+    #[serde(flatten)]
+    pub transition: Transition,
+}
+
+impl RunnerStateUpdate {
+    pub fn from_event(profile_id: &str, at_ms: u64, event: &Event) -> Option<Self> {
+        Transition::from_event(event).map(|transition| Self {
+            profile_id: profile_id.to_string(),
+            at_ms,
+            transition,
+        })
+    }
+}
+
+/// What the current run is doing, followed from its transitions for the tray and the remote API
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum RunnerStatus {
+    #[default]
+    Idle,
+    Running {
+        profile_id: String,
+    },
+    /// Paused by the user or held outside the profile's run window
+    Paused {
+        profile_id: String,
+    },
+    NeedsIntervention {
+        profile_id: String,
+        reason: String,
+    },
+}
+
+impl RunnerStatus {
+    /// Follow a runner state transition
+    pub fn apply(&mut self, update: &RunnerStateUpdate) {
+        let profile_id = update.profile_id.clone();
+        *self = match &update.transition {
+            Transition::RunState { state } => match state {
+                MonitorState::Running => Self::Running { profile_id },
+                MonitorState::Paused | MonitorState::OutsideRunWindow => Self::Paused { profile_id },
+                MonitorState::WaitingForNetwork => Self::NeedsIntervention {
+                    profile_id,
+                    reason: "waiting for the network".into(),
+                },
+                MonitorState::ActionTimedOut => Self::NeedsIntervention {
+                    profile_id,
+                    reason: "an action timed out".into(),
+                },
+                MonitorState::Stopped => Self::Idle,
+                MonitorState::Stopping => return,
+            },
+            Transition::Error { message } if message.contains("Risk threshold exceeded") => Self::NeedsIntervention {
+                profile_id,
+                reason: "a risky prompt was rejected".into(),
+            },
+            // The run got past whatever needed the user
+            Transition::IterationCompleted { success: true, .. } if matches!(self, Self::NeedsIntervention { .. }) => {
+                Self::Running { profile_id }
+            }
+            _ => return,
+        };
+    }
+
+    /// Profile of the current run
+    pub fn profile_id(&self) -> Option<&str> {
+        match self {
+            Self::Idle => None,
+            Self::Running { profile_id } | Self::Paused { profile_id } | Self::NeedsIntervention { profile_id, .. } => {
+                Some(profile_id)
+            }
+        }
+    }
+}
@@ -0,0 +1,58 @@
+/// The backend's one tokio runtime
+///
+/// The LLM client, webhooks, profile sync and OCR are async (reqwest,
+/// uni-ocr) but are called from plain threads: the monitor loop, notifier
+/// threads and Tauri commands. They block on this shared runtime instead of
+/// building and tearing down a runtime per call. `AppState` keeps a handle;
+/// code without app state (MCP server, headless runs, the LLM client factory)
+/// gets the same runtime from `AsyncRuntime::shared`.
+#[cfg(feature = "tokio")]
+use std::future::Future;
+#[cfg(feature = "tokio")]
+use std::sync::OnceLock;
+
+#[cfg(feature = "tokio")]
+use tokio::runtime::{Builder, Runtime};
+
+/// Worker threads; the work is waiting on the network, not computing
+#[cfg(feature = "tokio")]
+const WORKER_THREADS: usize = 2;
+
+/// Handle to the shared runtime; builds without async features carry nothing
+#[derive(Clone, Copy)]
+pub struct AsyncRuntime {
+    #[cfg(feature = "tokio")]
+    runtime: &'static Runtime,
+}
+
+impl AsyncRuntime {
+    /// The process-wide runtime, started on first use
+    #[cfg(feature = "tokio")]
+    pub fn shared() -> Result<Self, String> {
+        static SHARED: OnceLock<Result<Runtime, String>> = OnceLock::new();
+        let runtime = SHARED.get_or_init(|| {
+            Builder::new_multi_thread()
+                .worker_threads(WORKER_THREADS)
+                .thread_name("loopautoma-async")
+                .enable_all()
+                .build()
+                .map_err(|e| format!("Failed to create tokio runtime: {}", e))
+        });
+        match runtime {
+            Ok(runtime) => Ok(Self { runtime }),
+            Err(e) => Err(e.clone()),
+        }
+    }
+
+    #[cfg(not(feature = "tokio"))]
+    pub fn shared() -> Result<Self, String> {
+        Ok(Self {})
+    }
+
+    /// Run `future` on the runtime and wait for it. Several threads may wait at
+    /// once; calling this from a task already on the runtime panics.
+    #[cfg(feature = "tokio")]
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+}
@@ -0,0 +1,93 @@
+/// Allowed-hours run windows
+///
+/// A profile with run windows only acts inside them. A run started or handed off
+/// outside every window is queued (held paused) until one opens, and a running
+/// profile is paused between ticks when its window closes, so an in-flight action
+/// always completes. Times are the machine's local wall-clock time. The same
+/// windows describe notification quiet hours.
+use chrono::{Datelike, NaiveDateTime, NaiveTime};
+
+use crate::domain::{RunWindow, Weekday};
+
+/// Run windows parsed once at run start
+#[derive(Debug, Clone, Default)]
+pub struct RunSchedule {
+    windows: Vec<ParsedWindow>,
+}
+
+#[derive(Debug, Clone)]
+struct ParsedWindow {
+    days: Vec<chrono::Weekday>,
+    start: NaiveTime,
+    end: NaiveTime,
+}
+
+impl ParsedWindow {
+    fn opens_on(&self, day: chrono::Weekday) -> bool {
+        self.days.is_empty() || self.days.contains(&day)
+    }
+
+    fn contains(&self, now: NaiveDateTime) -> bool {
+        let (today, time) = (now.weekday(), now.time());
+        if self.start < self.end {
+            self.opens_on(today) && time >= self.start && time < self.end
+        } else if self.start > self.end {
+            (self.opens_on(today) && time >= self.start) || (self.opens_on(today.pred()) && time < self.end)
+        } else {
+            self.opens_on(today)
+        }
+    }
+}
+
+impl RunSchedule {
+    pub fn parse(windows: &[RunWindow]) -> Result<Self, String> {
+        Self::parse_field("run_windows", windows)
+    }
+
+    /// Parse windows stored under `field`, which prefixes error messages
+    pub fn parse_field(field: &str, windows: &[RunWindow]) -> Result<Self, String> {
+        let windows = windows
+            .iter()
+            .enumerate()
+            .map(|(i, w)| {
+                let time = |name: &str, value: &str| {
+                    parse_time(value).map_err(|e| format!("{}[{}].{}: {}", field, i, name, e))
+                };
+                Ok(ParsedWindow {
+                    days: w.days.iter().map(|d| to_chrono(*d)).collect(),
+                    start: time("start", &w.start)?,
+                    end: time("end", &w.end)?,
+                })
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(Self { windows })
+    }
+
+    /// True when `now` falls inside any window; a schedule without windows is always open
+    pub fn is_open(&self, now: NaiveDateTime) -> bool {
+        self.windows.is_empty() || self.windows.iter().any(|w| w.contains(now))
+    }
+}
+
+/// Parse a `HH:MM` time of day
+pub fn parse_time(value: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M")
+        .map_err(|_| format!("'{}' is not a time of day; use HH:MM, e.g. 22:00", value))
+}
+
+/// Current local wall-clock time
+pub fn local_now() -> NaiveDateTime {
+    chrono::Local::now().naive_local()
+}
+
+fn to_chrono(day: Weekday) -> chrono::Weekday {
+    match day {
+        Weekday::Mon => chrono::Weekday::Mon,
+        Weekday::Tue => chrono::Weekday::Tue,
+        Weekday::Wed => chrono::Weekday::Wed,
+        Weekday::Thu => chrono::Weekday::Thu,
+        Weekday::Fri => chrono::Weekday::Fri,
+        Weekday::Sat => chrono::Weekday::Sat,
+        Weekday::Sun => chrono::Weekday::Sun,
+    }
+}
@@ -1,126 +1,797 @@
 /// Secure storage abstraction for sensitive data (API keys, etc.)
-/// Uses OS keyring: macOS Keychain, Windows Credential Manager, Linux Secret Service/KWallet
+///
+/// API keys live in the OS keyring (macOS Keychain, Windows Credential Manager,
+/// Linux Secret Service/KWallet) when one answers at startup; keys left in the
+/// store file by earlier versions are moved there then. Without a keyring they
+/// stay in the `secure.bin` store, which holds all other settings too.
+///
+/// Entries holding credentials are sealed with AES-256-GCM inside the store, so
+/// the file never shows them as plain JSON. Their key is kept in the keyring, or
+/// derived from the machine's identity when there is none; which of the two is
+/// recorded in the store so a later session opens them with the same key.
+/// Plain entries left by earlier versions are sealed at startup.
+use serde::{de::DeserializeOwned, Serialize};
 use tauri_plugin_store::{Store, StoreExt};
 use std::sync::Arc;
 
+use crate::credentials::{
+    self, EnvKeyStatus, KeyMetadata, KeyMetadataMap, ProfileSecretIndex, ProviderCredentials, ProviderKind, ProviderStatus, Providers,
+};
+use crate::history::now_ms;
+use crate::profile_crypto::{self, KeyStore, KeyringKeyStore, MachineKeyStore};
+use crate::settings::{self, AnySetting, ImportReport, Setting, SettingChange, SettingsExport, SettingsSnapshot};
+
 const OPENAI_KEY_ENTRY: &str = "openai_api_key";
-const OPENAI_MODEL_ENTRY: &str = "openai_model";
-const AUDIO_ENABLED_ENTRY: &str = "audio_enabled";
-const AUDIO_VOLUME_ENTRY: &str = "audio_volume";
+const PROFILE_SYNC_ENTRY: &str = "profile_sync";
+const WEBHOOKS_ENTRY: &str = "webhooks";
+const SMTP_ENTRY: &str = "smtp";
+const SETTINGS_KEY_ENTRY: &str = "settings_key";
+const PROVIDERS_ENTRY: &str = "providers";
+const KEY_METADATA_ENTRY: &str = "key_metadata";
+const PROFILE_SECRETS_ENTRY: &str = "profile_secrets";
+
+/// Entries sealed in the store because they hold credentials, besides every `*_api_key` and profile secret
+const SENSITIVE_ENTRIES: &[&str] = &[OPENAI_KEY_ENTRY, WEBHOOKS_ENTRY, SMTP_ENTRY, PROFILE_SYNC_ENTRY, PROVIDERS_ENTRY];
+
+fn is_sensitive(name: &str) -> bool {
+    SENSITIVE_ENTRIES.contains(&name) || name.ends_with("_api_key") || name.starts_with(credentials::PROFILE_SECRET_PREFIX)
+}
+
+/// Where API keys are kept
+pub trait SecretStore: Send + Sync {
+    fn get(&self, name: &str) -> Result<Option<String>, String>;
+    fn set(&self, name: &str, value: &str) -> Result<(), String>;
+    fn delete(&self, name: &str) -> Result<(), String>;
+}
+
+/// API keys in the OS keyring, one entry per key under the app's service name
+pub struct KeyringSecrets;
+
+#[cfg(feature = "os-keyring")]
+impl KeyringSecrets {
+    const SERVICE: &'static str = "loopautoma";
+
+    fn entry(name: &str) -> Result<keyring::Entry, String> {
+        keyring::Entry::new(Self::SERVICE, name).map_err(|e| format!("OS keyring unavailable: {}", e))
+    }
+
+    /// Whether a keyring answers; a missing entry still counts as an answer
+    pub fn probe() -> Result<(), String> {
+        match Self::entry(OPENAI_KEY_ENTRY)?.get_password() {
+            Ok(_) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(format!("OS keyring unavailable: {}", e)),
+        }
+    }
+}
+
+#[cfg(feature = "os-keyring")]
+impl SecretStore for KeyringSecrets {
+    fn get(&self, name: &str) -> Result<Option<String>, String> {
+        match Self::entry(name)?.get_password() {
+            Ok(value) => Ok(Some(value)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(format!("Failed to read {} from the OS keyring: {}", name, e)),
+        }
+    }
+
+    fn set(&self, name: &str, value: &str) -> Result<(), String> {
+        Self::entry(name)?
+            .set_password(value)
+            .map_err(|e| format!("Failed to store {} in the OS keyring: {}", name, e))
+    }
+
+    fn delete(&self, name: &str) -> Result<(), String> {
+        match Self::entry(name)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(format!("Failed to delete {} from the OS keyring: {}", name, e)),
+        }
+    }
+}
+
+#[cfg(not(feature = "os-keyring"))]
+impl KeyringSecrets {
+    pub fn probe() -> Result<(), String> {
+        Err("This build lacks OS keyring support".into())
+    }
+}
+
+#[cfg(not(feature = "os-keyring"))]
+impl SecretStore for KeyringSecrets {
+    fn get(&self, _name: &str) -> Result<Option<String>, String> {
+        Err("This build lacks OS keyring support".into())
+    }
+
+    fn set(&self, _name: &str, _value: &str) -> Result<(), String> {
+        Err("This build lacks OS keyring support".into())
+    }
+
+    fn delete(&self, _name: &str) -> Result<(), String> {
+        Err("This build lacks OS keyring support".into())
+    }
+}
+
+/// Store value sealing `value`: the encrypted envelope as a JSON string
+pub fn seal_value(value: &serde_json::Value, keys: &dyn KeyStore) -> Result<serde_json::Value, String> {
+    profile_crypto::seal_as(&value.to_string(), keys, "settings").map(serde_json::Value::String)
+}
+
+/// Value stored by `seal_value`; plain values from earlier versions pass through
+pub fn open_value(value: serde_json::Value, keys: &dyn KeyStore) -> Result<serde_json::Value, String> {
+    match value.as_str() {
+        Some(text) if profile_crypto::is_sealed(text) => {
+            let (plaintext, _) = profile_crypto::open_as(text, keys, "settings")?;
+            serde_json::from_str(&plaintext).map_err(|e| format!("Decrypted settings are not valid JSON: {}", e))
+        }
+        _ => Ok(value),
+    }
+}
+
+/// Entry `name` of `store`, decrypted if sealed
+fn read_entry<R: tauri::Runtime>(
+    store: &Store<R>,
+    keys: &dyn KeyStore,
+    name: &str,
+) -> Result<Option<serde_json::Value>, String> {
+    store
+        .get(name)
+        .map(|value| open_value(value, keys).map_err(|e| format!("{}: {}", name, e)))
+        .transpose()
+}
+
+/// Put entry `name` in `store`, sealed when it holds credentials; the caller saves
+fn write_entry<R: tauri::Runtime>(
+    store: &Store<R>,
+    keys: &dyn KeyStore,
+    name: &str,
+    value: serde_json::Value,
+) -> Result<(), String> {
+    let value = if is_sensitive(name) {
+        seal_value(&value, keys).map_err(|e| format!("{}: {}", name, e))?
+    } else {
+        value
+    };
+    store.set(name, value);
+    Ok(())
+}
+
+/// API keys in the store file, for systems without a keyring
+struct StoreSecrets<R: tauri::Runtime> {
+    store: Arc<Store<R>>,
+    keys: Arc<dyn KeyStore>,
+}
+
+impl<R: tauri::Runtime> SecretStore for StoreSecrets<R> {
+    fn get(&self, name: &str) -> Result<Option<String>, String> {
+        match read_entry(&self.store, self.keys.as_ref(), name)? {
+            Some(value) => Ok(Some(value.as_str().ok_or("Invalid key format in storage")?.to_string())),
+            None => Ok(None),
+        }
+    }
+
+    fn set(&self, name: &str, value: &str) -> Result<(), String> {
+        write_entry(&self.store, self.keys.as_ref(), name, serde_json::json!(value))?;
+        self.store.save().map_err(|e| format!("Failed to save key to storage: {}", e))
+    }
+
+    fn delete(&self, name: &str) -> Result<(), String> {
+        self.store.delete(name);
+        self.store.save().map_err(|e| format!("Failed to save after delete: {}", e))
+    }
+}
+
+/// In-memory secrets for tests; `failing` makes every write fail like a locked keyring
+#[cfg(test)]
+#[derive(Default)]
+pub struct MemorySecrets {
+    pub values: std::sync::Mutex<std::collections::HashMap<String, String>>,
+    pub failing: bool,
+}
+
+#[cfg(test)]
+impl SecretStore for MemorySecrets {
+    fn get(&self, name: &str) -> Result<Option<String>, String> {
+        Ok(self.values.lock().unwrap().get(name).cloned())
+    }
+
+    fn set(&self, name: &str, value: &str) -> Result<(), String> {
+        if self.failing {
+            return Err("keyring locked".into());
+        }
+        self.values.lock().unwrap().insert(name.into(), value.into());
+        Ok(())
+    }
+
+    fn delete(&self, name: &str) -> Result<(), String> {
+        self.values.lock().unwrap().remove(name);
+        Ok(())
+    }
+}
+
+/// Move the `names` found in `from` into `to`, returning how many moved.
+/// A key is removed from `from` only once `to` returns it intact.
+pub fn migrate_secrets(from: &dyn SecretStore, to: &dyn SecretStore, names: &[&str]) -> Result<usize, String> {
+    let mut moved = 0;
+    for name in names {
+        let Some(value) = from.get(name)? else {
+            continue;
+        };
+        to.set(name, &value)?;
+        if to.get(name)?.as_deref() != Some(value.as_str()) {
+            return Err(format!("The OS keyring did not keep {}", name));
+        }
+        from.delete(name)?;
+        moved += 1;
+    }
+    Ok(moved)
+}
 
 pub struct SecureStorage<R: tauri::Runtime> {
     store: Arc<Store<R>>,
+    keys: Arc<dyn KeyStore>,
+    secrets: Box<dyn SecretStore>,
+    listener: Option<Box<ChangeListener>>,
 }
 
+type ChangeListener = dyn Fn(&SettingChange) + Send + Sync;
+
 impl<R: tauri::Runtime> SecureStorage<R> {
     pub fn new(app_handle: &tauri::AppHandle<R>) -> Result<Self, String> {
         let store = app_handle.store("secure.bin")
             .map_err(|e| format!("Failed to initialize secure storage: {}", e))?;
+        let keyring = KeyringSecrets::probe();
+        let keys = Self::settings_keys(&store, keyring.is_ok());
+        match Self::seal_plain_entries(&store, keys.as_ref()) {
+            Ok(0) => {}
+            Ok(sealed) => tracing::info!("Encrypted {} stored credential(s)", sealed),
+            Err(e) => tracing::warn!("Failed to encrypt stored credentials: {}", e),
+        }
+        let secrets = Self::choose_secret_store(&store, &keys, keyring);
         
         Ok(Self {
             store,
+            keys,
+            secrets,
+            listener: None,
         })
     }
 
+    /// Call `listener` after each saved change
+    pub fn with_listener(mut self, listener: impl Fn(&SettingChange) + Send + Sync + 'static) -> Self {
+        self.listener = Some(Box::new(listener));
+        self
+    }
+
+    fn changed(&self, key: impl Into<String>, removed: bool) {
+        if let Some(listener) = &self.listener {
+            listener(&SettingChange { key: key.into(), removed });
+        }
+    }
+
+    /// Key sealing credentials in the store; the first session picks the keyring if it has one
+    fn settings_keys(store: &Store<R>, keyring_available: bool) -> Arc<dyn KeyStore> {
+        let recorded = store.get(SETTINGS_KEY_ENTRY).and_then(|v| v.as_str().map(str::to_string));
+        let use_keyring = match recorded.as_deref() {
+            Some(source) => source == "keyring",
+            None => {
+                let source = if keyring_available { "keyring" } else { "machine" };
+                store.set(SETTINGS_KEY_ENTRY, serde_json::json!(source));
+                keyring_available
+            }
+        };
+        if use_keyring {
+            Arc::new(KeyringKeyStore::SETTINGS)
+        } else {
+            Arc::new(MachineKeyStore)
+        }
+    }
+
+    /// Seal credentials that earlier versions stored as plain JSON, returning how many
+    fn seal_plain_entries(store: &Store<R>, keys: &dyn KeyStore) -> Result<usize, String> {
+        let mut sealed = 0;
+        for name in SENSITIVE_ENTRIES {
+            let Some(value) = store.get(name) else {
+                continue;
+            };
+            if !value.as_str().is_some_and(profile_crypto::is_sealed) {
+                write_entry(store, keys, name, value)?;
+                sealed += 1;
+            }
+        }
+        store.save().map_err(|e| format!("Failed to save encrypted settings: {}", e))?;
+        Ok(sealed)
+    }
+
+    /// The OS keyring once existing keys have moved into it, else the store file
+    fn choose_secret_store(
+        store: &Arc<Store<R>>,
+        keys: &Arc<dyn KeyStore>,
+        keyring: Result<(), String>,
+    ) -> Box<dyn SecretStore> {
+        let file = StoreSecrets {
+            store: store.clone(),
+            keys: keys.clone(),
+        };
+        if let Err(e) = keyring {
+            tracing::warn!("{}; keeping API keys in the store file", e);
+            return Box::new(file);
+        }
+        // Keys kept in the OS keyring when one is available
+        let mut names = vec![OPENAI_KEY_ENTRY.to_string()];
+        if let Ok(Some(providers)) = read_entry(store, keys.as_ref(), PROVIDERS_ENTRY) {
+            let providers: Providers = serde_json::from_value(providers).unwrap_or_default();
+            names.extend(providers.keys().map(|id| credentials::key_entry(id)));
+        }
+        if let Some(index) = store.get(PROFILE_SECRETS_ENTRY) {
+            let index: ProfileSecretIndex = serde_json::from_value(index).unwrap_or_default();
+            for (profile_id, secrets) in &index {
+                names.extend(secrets.iter().map(|name| credentials::profile_secret_entry(profile_id, name)));
+            }
+        }
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+        match migrate_secrets(&file, &KeyringSecrets, &names) {
+            Ok(0) => Box::new(KeyringSecrets),
+            Ok(moved) => {
+                tracing::info!("Moved {} API key(s) to the OS keyring", moved);
+                Box::new(KeyringSecrets)
+            }
+            Err(e) => {
+                tracing::warn!("{}; keeping API keys in the store file", e);
+                Box::new(file)
+            }
+        }
+    }
+
+    fn read(&self, name: &str) -> Result<Option<serde_json::Value>, String> {
+        read_entry(&self.store, self.keys.as_ref(), name)
+    }
+
+    fn write(&self, name: &str, value: serde_json::Value) -> Result<(), String> {
+        write_entry(&self.store, self.keys.as_ref(), name, value)
+    }
+
     /// Get OpenAI API key from secure storage
     /// Returns None if key is not set
     pub fn get_openai_key(&self) -> Result<Option<String>, String> {
-        match self.store.get(OPENAI_KEY_ENTRY) {
-            Some(value) => {
-                let key = value.as_str()
-                    .ok_or("Invalid key format in storage")?
-                    .to_string();
-                Ok(Some(key))
-            }
-            None => Ok(None)
-        }
+        self.secrets.get(OPENAI_KEY_ENTRY)
     }
 
     /// Set OpenAI API key in secure storage
     pub fn set_openai_key(&self, key: &str) -> Result<(), String> {
-        self.store.set(OPENAI_KEY_ENTRY, serde_json::json!(key));
-        self.store.save()
-            .map_err(|e| format!("Failed to save key to storage: {}", e))?;
-        
+        self.secrets.set(OPENAI_KEY_ENTRY, key)?;
+        self.key_set(OPENAI_KEY_ENTRY)?;
+        self.changed(settings::credential_key(OPENAI_KEY_ENTRY), false);
         Ok(())
     }
 
     /// Delete OpenAI API key from secure storage
     pub fn delete_openai_key(&self) -> Result<(), String> {
-        self.store.delete(OPENAI_KEY_ENTRY);
-        self.store.save()
-            .map_err(|e| format!("Failed to save after delete: {}", e))?;
-        
+        self.secrets.delete(OPENAI_KEY_ENTRY)?;
+        self.update_key_metadata(|keys| {
+            keys.remove(OPENAI_KEY_ENTRY);
+        })?;
+        self.changed(settings::credential_key(OPENAI_KEY_ENTRY), true);
         Ok(())
     }
 
     /// Check if OpenAI API key exists (without revealing it)
     pub fn has_openai_key(&self) -> Result<bool, String> {
-        Ok(self.store.get(OPENAI_KEY_ENTRY).is_some())
+        Ok(self.secrets.get(OPENAI_KEY_ENTRY)?.is_some())
+    }
+
+    /// Providers configured in settings, without their keys
+    pub fn get_providers(&self) -> Result<Providers, String> {
+        match self.read(PROVIDERS_ENTRY)? {
+            Some(value) => serde_json::from_value(value)
+                .map_err(|e| format!("Invalid providers format in storage: {}", e)),
+            None => Ok(Providers::new()),
+        }
+    }
+
+    /// Configured providers and whether each has a key, never the key itself
+    pub fn list_providers(&self) -> Result<Vec<ProviderStatus>, String> {
+        let providers = self.get_providers()?;
+        let mut list = credentials::statuses(&providers, |id| {
+            matches!(self.secrets.get(&credentials::key_entry(id)), Ok(Some(_)))
+        });
+        if let Some(openai) = list.iter_mut().find(|p| p.kind == ProviderKind::OpenAi) {
+            openai.model = self.get_setting(&settings::OPENAI_MODEL)?;
+        }
+        let keys = self.key_metadata()?;
+        for provider in list.iter_mut().filter(|p| p.has_key) {
+            provider.key = keys.get(&credentials::key_entry(&provider.id)).copied();
+        }
+        Ok(list)
+    }
+
+    /// API key of provider `id`
+    pub fn get_provider_key(&self, id: &str) -> Result<Option<String>, String> {
+        self.secrets.get(&credentials::key_entry(id))
+    }
+
+    /// Add or update provider `id`; a missing key keeps the stored one.
+    /// OpenAI's model stays in the setting the model selector uses.
+    pub fn set_provider(&self, id: &str, credentials: &ProviderCredentials) -> Result<(), String> {
+        credentials.validate(id, self.get_provider_key(id)?.is_some())?;
+        if let Some(key) = &credentials.api_key {
+            self.secrets.set(&credentials::key_entry(id), key.trim())?;
+            self.key_set(&credentials::key_entry(id))?;
+            self.changed(settings::credential_key(&credentials::key_entry(id)), false);
+        }
+        let mut settings = credentials.settings();
+        if settings.kind == ProviderKind::OpenAi {
+            if let Some(model) = settings.model.take() {
+                self.set_setting(&settings::OPENAI_MODEL, &Some(model))?;
+            }
+        }
+        let mut providers = self.get_providers()?;
+        providers.insert(id.to_string(), settings);
+        self.write(PROVIDERS_ENTRY, serde_json::json!(providers))?;
+        self.store.save()
+            .map_err(|e| format!("Failed to save providers: {}", e))?;
+        self.changed(settings::PROVIDERS_KEY, false);
+        Ok(())
     }
 
-    /// Get preferred OpenAI model
-    /// Returns None if not set (defaults to gpt-4o in client)
-    pub fn get_openai_model(&self) -> Result<Option<String>, String> {
-        match self.store.get(OPENAI_MODEL_ENTRY) {
-            Some(value) => {
-                let model = value.as_str()
-                    .ok_or("Invalid model format in storage")?
-                    .to_string();
-                Ok(Some(model))
+    /// Forget provider `id` and its key
+    pub fn delete_provider(&self, id: &str) -> Result<(), String> {
+        self.secrets.delete(&credentials::key_entry(id))?;
+        self.update_key_metadata(|keys| {
+            keys.remove(&credentials::key_entry(id));
+        })?;
+        self.changed(settings::credential_key(&credentials::key_entry(id)), true);
+        let mut providers = self.get_providers()?;
+        if providers.remove(id).is_some() {
+            self.write(PROVIDERS_ENTRY, serde_json::json!(providers))?;
+            self.store.save()
+                .map_err(|e| format!("Failed to save providers: {}", e))?;
+            self.changed(settings::PROVIDERS_KEY, false);
+        }
+        Ok(())
+    }
+
+    /// Provider keys found in the environment, and whether a stored key already overrides them
+    pub fn env_keys(&self, env: &dyn Fn(&str) -> Option<String>) -> Vec<EnvKeyStatus> {
+        credentials::detect_env_keys(env, &|id| matches!(self.secrets.get(&credentials::key_entry(id)), Ok(Some(_))))
+    }
+
+    /// Store the keys of the environment variables `vars`; returns the variables imported
+    pub fn import_env_keys(&self, vars: &[String], env: &dyn Fn(&str) -> Option<String>) -> Result<Vec<String>, String> {
+        let found = self.env_keys(env);
+        let mut imported = Vec::new();
+        for var in vars {
+            let status = found
+                .iter()
+                .find(|s| &s.var == var)
+                .ok_or_else(|| format!("{} is not set or not a known API key variable", var))?;
+            if let Some(problem) = &status.problem {
+                return Err(format!("Cannot import {}: {}", var, problem));
+            }
+            let credential = credentials::ENV_CREDENTIALS.iter().find(|c| c.var == var).expect("detected variable");
+            let key = env(var).unwrap_or_default().trim().to_string();
+            if credential.kind == ProviderKind::OpenAi {
+                self.set_openai_key(&key)?;
+            } else {
+                let existing = self.get_providers()?.remove(&status.provider);
+                let base_url = existing
+                    .as_ref()
+                    .and_then(|p| p.base_url.clone())
+                    .or_else(|| credential.base_url_var.and_then(env));
+                self.set_provider(
+                    &status.provider,
+                    &ProviderCredentials {
+                        kind: credential.kind,
+                        api_key: Some(key),
+                        base_url,
+                        model: existing.and_then(|p| p.model),
+                    },
+                )?;
             }
-            None => Ok(None)
+            imported.push(var.clone());
         }
+        Ok(imported)
     }
 
-    /// Set preferred OpenAI model
-    pub fn set_openai_model(&self, model: &str) -> Result<(), String> {
-        self.store.set(OPENAI_MODEL_ENTRY, serde_json::json!(model));
+    fn profile_secret_index(&self) -> Result<ProfileSecretIndex, String> {
+        match self.store.get(PROFILE_SECRETS_ENTRY) {
+            Some(value) => serde_json::from_value(value)
+                .map_err(|e| format!("Invalid profile_secrets format in storage: {}", e)),
+            None => Ok(ProfileSecretIndex::new()),
+        }
+    }
+
+    fn save_profile_secret_index(&self, index: &ProfileSecretIndex) -> Result<(), String> {
+        self.store.set(PROFILE_SECRETS_ENTRY, serde_json::json!(index));
         self.store.save()
-            .map_err(|e| format!("Failed to save model to storage: {}", e))?;
-        
+            .map_err(|e| format!("Failed to save profile secrets: {}", e))
+    }
+
+    /// Names of the secrets stored for a profile, never their values
+    pub fn list_profile_secrets(&self, profile_id: &str) -> Result<Vec<String>, String> {
+        Ok(self.profile_secret_index()?.remove(profile_id).unwrap_or_default().into_iter().collect())
+    }
+
+    /// Value of `{{secret:name}}` for a profile
+    pub fn get_profile_secret(&self, profile_id: &str, name: &str) -> Result<Option<String>, String> {
+        self.secrets.get(&credentials::profile_secret_entry(profile_id, name))
+    }
+
+    pub fn set_profile_secret(&self, profile_id: &str, name: &str, value: &str) -> Result<(), String> {
+        credentials::check_secret_name(name)?;
+        if value.is_empty() {
+            return Err("Secret value cannot be empty".to_string());
+        }
+        self.secrets.set(&credentials::profile_secret_entry(profile_id, name), value)?;
+        let mut index = self.profile_secret_index()?;
+        index.entry(profile_id.to_string()).or_default().insert(name.to_string());
+        self.save_profile_secret_index(&index)?;
+        self.changed(settings::PROFILE_SECRETS_KEY, false);
         Ok(())
     }
 
-    /// Get audio enabled setting
-    pub fn get_audio_enabled(&self) -> Result<bool, String> {
-        match self.store.get(AUDIO_ENABLED_ENTRY) {
-            Some(value) => {
-                value.as_bool()
-                    .ok_or("Invalid audio_enabled format in storage".to_string())
+    pub fn delete_profile_secret(&self, profile_id: &str, name: &str) -> Result<(), String> {
+        self.secrets.delete(&credentials::profile_secret_entry(profile_id, name))?;
+        let mut index = self.profile_secret_index()?;
+        if let Some(names) = index.get_mut(profile_id) {
+            names.remove(name);
+            if names.is_empty() {
+                index.remove(profile_id);
             }
-            None => Ok(true) // Default to enabled
+        }
+        self.save_profile_secret_index(&index)?;
+        self.changed(settings::PROFILE_SECRETS_KEY, true);
+        Ok(())
+    }
+
+    /// When each stored key was set and last worked
+    pub fn key_metadata(&self) -> Result<KeyMetadataMap, String> {
+        match self.store.get(KEY_METADATA_ENTRY) {
+            Some(value) => serde_json::from_value(value)
+                .map_err(|e| format!("Invalid key_metadata format in storage: {}", e)),
+            None => Ok(KeyMetadataMap::new()),
         }
     }
 
-    /// Set audio enabled setting
-    pub fn set_audio_enabled(&self, enabled: bool) -> Result<(), String> {
-        self.store.set(AUDIO_ENABLED_ENTRY, serde_json::json!(enabled));
+    fn update_key_metadata(&self, update: impl FnOnce(&mut KeyMetadataMap)) -> Result<(), String> {
+        let mut keys = self.key_metadata()?;
+        update(&mut keys);
+        self.store.set(KEY_METADATA_ENTRY, serde_json::json!(keys));
         self.store.save()
-            .map_err(|e| format!("Failed to save audio setting: {}", e))?;
+            .map_err(|e| format!("Failed to save key metadata: {}", e))
+    }
+
+    fn key_set(&self, entry: &str) -> Result<(), String> {
+        self.update_key_metadata(|keys| {
+            keys.insert(entry.to_string(), KeyMetadata::new(now_ms()));
+        })
+    }
+
+    /// Note whether the key in secret `entry` just worked
+    pub fn record_key_use(&self, entry: &str, ok: bool) -> Result<(), String> {
+        let now = now_ms();
+        self.update_key_metadata(|keys| {
+            keys.entry(entry.to_string())
+                .or_insert_with(|| KeyMetadata::new(now))
+                .record_use(ok, now);
+        })
+    }
+
+    /// Stored keys that stopped working or are due for rotation, with the reason
+    pub fn key_warnings(&self) -> Result<Vec<(String, String)>, String> {
+        let policy = self.get_setting(&settings::KEY_AGE)?;
+        let now = now_ms();
+        let mut warnings = Vec::new();
+        for (entry, metadata) in self.key_metadata()? {
+            if let Some(reason) = metadata.warning(&policy, now) {
+                if self.secrets.get(&entry)?.is_some() {
+                    warnings.push((entry, reason));
+                }
+            }
+        }
+        Ok(warnings)
+    }
+
+    /// Typed setting, its legacy entry when not yet saved under its key, or its default
+    pub fn get_setting<T: DeserializeOwned>(&self, setting: &Setting<T>) -> Result<T, String> {
+        settings::resolve(setting, self.stored_setting(setting.key, setting.legacy)?)
+    }
+
+    /// Stored JSON of a setting, decrypted if sealed
+    fn stored_setting(&self, key: &str, legacy: Option<&str>) -> Result<Option<serde_json::Value>, String> {
+        match self.store.get(key) {
+            Some(_) => self.read(key),
+            None => legacy.map_or(Ok(None), |name| self.read(name)),
+        }
+    }
+
+    /// Put a checked setting under its key, sealed when it holds credentials, dropping its legacy entry; the caller saves
+    fn put_setting(&self, setting: &dyn AnySetting, value: serde_json::Value) -> Result<(), String> {
+        let value = if setting.secret().is_empty() {
+            value
+        } else {
+            seal_value(&value, self.keys.as_ref()).map_err(|e| format!("{}: {}", setting.key(), e))?
+        };
+        self.store.set(setting.key(), value);
+        if let Some(legacy) = setting.legacy() {
+            self.store.delete(legacy);
+        }
         Ok(())
     }
 
-    /// Get audio volume (0.0 to 1.0)
-    pub fn get_audio_volume(&self) -> Result<f32, String> {
-        match self.store.get(AUDIO_VOLUME_ENTRY) {
-            Some(value) => {
-                value.as_f64()
-                    .ok_or("Invalid audio_volume format in storage".to_string())
-                    .map(|v| v as f32)
+    /// Check and save a typed setting under its namespaced key, dropping its legacy entry
+    pub fn set_setting<T: Serialize + DeserializeOwned>(&self, setting: &Setting<T>, value: &T) -> Result<(), String> {
+        debug_assert!(settings::is_namespaced(setting.key), "{} is not namespaced", setting.key);
+        (setting.check)(value)?;
+        let value = serde_json::to_value(value)
+            .map_err(|e| format!("Failed to serialize {}: {}", setting.key, e))?;
+        self.put_setting(setting, value)?;
+        self.store.save()
+            .map_err(|e| format!("Failed to save {}: {}", setting.key, e))?;
+        self.changed(setting.key, false);
+        Ok(())
+    }
+
+    /// Every typed setting, the providers and which credentials are set, without any secret value
+    pub fn snapshot(&self) -> Result<SettingsSnapshot, String> {
+        let export = self.export_settings()?;
+        let mut settings = export.settings;
+        settings.remove(settings::PROVIDERS_KEY);
+        Ok(SettingsSnapshot {
+            settings,
+            providers: self.list_providers()?,
+            secrets: export.secrets,
+        })
+    }
+
+    /// Every typed setting and the providers without their keys, for another machine
+    pub fn export_settings(&self) -> Result<SettingsExport, String> {
+        let mut values = std::collections::BTreeMap::new();
+        for setting in settings::REGISTRY {
+            let mut value = self.stored_setting(setting.key(), setting.legacy())?.unwrap_or_else(|| setting.default_json());
+            settings::strip_secrets(*setting, &mut value);
+            values.insert(setting.key().to_string(), value);
+        }
+        let providers = self.get_providers()?;
+        values.insert(settings::PROVIDERS_KEY.to_string(), serde_json::json!(providers));
+        Ok(SettingsExport::new(values, self.secret_names(&providers)?))
+    }
+
+    /// Secrets set here, by the name an export lists them under: the entry, or the key of a typed setting
+    fn secret_names(&self, providers: &Providers) -> Result<Vec<String>, String> {
+        let mut names = Vec::new();
+        let ids = std::iter::once(ProviderKind::OpenAi.id()).chain(providers.keys().map(String::as_str));
+        for id in ids {
+            let entry = credentials::key_entry(id);
+            if !names.contains(&entry) && self.secrets.get(&entry)?.is_some() {
+                names.push(entry);
             }
-            None => Ok(0.5) // Default to 50%
         }
+        for entry in [WEBHOOKS_ENTRY, SMTP_ENTRY, PROFILE_SYNC_ENTRY] {
+            if matches!(self.read(entry)?, Some(value) if value != serde_json::json!([])) {
+                names.push(entry.to_string());
+            }
+        }
+        for setting in settings::REGISTRY.iter().filter(|s| !s.secret().is_empty()) {
+            if let Some(mut value) = self.stored_setting(setting.key(), setting.legacy())? {
+                if settings::strip_secrets(*setting, &mut value) {
+                    names.push(setting.key().to_string());
+                }
+            }
+        }
+        Ok(names)
     }
 
-    /// Set audio volume (0.0 to 1.0)
-    pub fn set_audio_volume(&self, volume: f32) -> Result<(), String> {
-        if !(0.0..=1.0).contains(&volume) {
-            return Err("Volume must be between 0.0 and 1.0".to_string());
+    /// Save the settings of an export once all of them pass their checks.
+    /// Imported providers are added to the configured ones and keep any key already stored here;
+    /// settings whose credentials were left out keep the ones stored here, or are skipped until re-entered.
+    pub fn import_settings(&self, export: &SettingsExport) -> Result<ImportReport, String> {
+        let mut plan = export.plan()?;
+        for (setting, mut value) in std::mem::take(&mut plan.needs_secrets) {
+            let Some(mut stored) = self.stored_setting(setting.key(), setting.legacy())? else {
+                continue;
+            };
+            let local = stored.clone();
+            if settings::strip_secrets(setting, &mut stored) {
+                settings::restore_secrets(setting, &mut value, &local);
+                setting.check_json(&value)?;
+                plan.values.push((setting, value));
+            }
+        }
+        let mut report = ImportReport { ignored: plan.ignored, ..Default::default() };
+        for (setting, value) in plan.values {
+            self.put_setting(setting, value)?;
+            report.imported.push(setting.key().to_string());
+        }
+        let mut providers = self.get_providers()?;
+        if let Some(imported) = plan.providers {
+            providers.extend(imported);
+            self.write(PROVIDERS_ENTRY, serde_json::json!(providers))?;
+            report.imported.push(settings::PROVIDERS_KEY.to_string());
+        }
+        self.store.save()
+            .map_err(|e| format!("Failed to save imported settings: {}", e))?;
+        for key in &report.imported {
+            self.changed(key.clone(), false);
+        }
+        let present = self.secret_names(&providers)?;
+        report.reenter = export.secrets.iter().filter(|name| !present.contains(name)).cloned().collect();
+        Ok(report)
+    }
+
+    /// Set or clear the custom sound file for one alert
+    pub fn set_custom_sound(&self, sound: crate::audio::AlertSound, path: Option<&str>) -> Result<(), String> {
+        let mut sounds = self.get_setting(&settings::AUDIO_CUSTOM_SOUNDS)?;
+        match path {
+            Some(path) => sounds.insert(sound, path.to_string()),
+            None => sounds.remove(&sound),
+        };
+        self.set_setting(&settings::AUDIO_CUSTOM_SOUNDS, &sounds)
+    }
+
+    /// Get webhook notification targets; their URLs and tokens are credentials
+    pub fn get_webhooks(&self) -> Result<Vec<crate::webhooks::WebhookConfig>, String> {
+        match self.read(WEBHOOKS_ENTRY)? {
+            Some(value) => serde_json::from_value(value)
+                .map_err(|e| format!("Invalid webhooks format in storage: {}", e)),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Replace the webhook notification targets
+    pub fn set_webhooks(&self, webhooks: &[crate::webhooks::WebhookConfig]) -> Result<(), String> {
+        self.write(WEBHOOKS_ENTRY, serde_json::json!(webhooks))?;
+        self.store.save()
+            .map_err(|e| format!("Failed to save webhooks: {}", e))?;
+        self.changed(settings::WEBHOOKS_KEY, false);
+        Ok(())
+    }
+
+    /// Get the SMTP server for email notifications, password included
+    /// Returns None if email is not configured
+    pub fn get_smtp(&self) -> Result<Option<crate::email::SmtpConfig>, String> {
+        match self.read(SMTP_ENTRY)? {
+            Some(value) => serde_json::from_value(value)
+                .map(Some)
+                .map_err(|e| format!("Invalid smtp format in storage: {}", e)),
+            None => Ok(None),
+        }
+    }
+
+    /// Set or clear the SMTP server for email notifications
+    pub fn set_smtp(&self, config: Option<&crate::email::SmtpConfig>) -> Result<(), String> {
+        match config {
+            Some(config) => self.write(SMTP_ENTRY, serde_json::json!(config))?,
+            None => {
+                self.store.delete(SMTP_ENTRY);
+            }
+        }
+        self.store.save()
+            .map_err(|e| format!("Failed to save SMTP settings: {}", e))?;
+        self.changed(settings::SMTP_KEY, config.is_none());
+        Ok(())
+    }
+
+    /// Get remote profile sync settings
+    /// Returns None if sync is not configured
+    pub fn get_profile_sync(&self) -> Result<Option<crate::sync::SyncConfig>, String> {
+        match self.read(PROFILE_SYNC_ENTRY)? {
+            Some(value) => serde_json::from_value(value)
+                .map(Some)
+                .map_err(|e| format!("Invalid profile_sync format in storage: {}", e)),
+            None => Ok(None),
+        }
+    }
+
+    /// Set or clear remote profile sync settings
+    pub fn set_profile_sync(&self, config: Option<&crate::sync::SyncConfig>) -> Result<(), String> {
+        match config {
+            Some(config) => self.write(PROFILE_SYNC_ENTRY, serde_json::json!(config))?,
+            None => {
+                self.store.delete(PROFILE_SYNC_ENTRY);
+            }
         }
-        self.store.set(AUDIO_VOLUME_ENTRY, serde_json::json!(volume));
         self.store.save()
-            .map_err(|e| format!("Failed to save volume: {}", e))?;
+            .map_err(|e| format!("Failed to save sync settings: {}", e))?;
+        self.changed(settings::PROFILE_SYNC_KEY, config.is_none());
         Ok(())
     }
 }
@@ -0,0 +1,413 @@
+/// Typed settings kept in secure storage
+///
+/// Each setting is a `Setting<T>` constant naming its key, its default and an
+/// optional check; `SecureStorage::get_setting` and `set_setting` do the JSON
+/// conversion, so a new setting is one constant here plus a line in
+/// `defaults()`. Keys are namespaced as `<area>.<name>`. Settings that existed
+/// before the scheme name their old flat entry, which is still read until the
+/// setting is next saved.
+///
+/// Settings can be exported to a JSON file and imported on another machine.
+/// Credentials never go into the file: it lists which secrets were left out so
+/// the import can ask for them again. A setting whose value holds credentials
+/// names those fields in `secret`; it is stored sealed, exported without them
+/// and listed under its key.
+///
+/// Every change saved through `SecureStorage` is reported as a `SettingChange`,
+/// which the app emits as `loopautoma://settings` so open windows and the tray
+/// stay in sync. Changes name the setting key, or one of the keys below for
+/// entries that are not typed settings; secret values are never included.
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::audio::{AlertSound, SoundMap};
+use crate::calibration::CursorTransform;
+use crate::credentials::{self, KeyAgePolicy, Providers};
+use crate::notify::{QuietHours, QuietPolicy};
+use crate::otel::OtlpConfig;
+use crate::remote::RemoteApiConfig;
+use crate::voice::VoiceConfig;
+
+pub struct Setting<T> {
+    /// Namespaced key, e.g. `audio.volume`
+    pub key: &'static str,
+    /// Flat entry used before keys were namespaced
+    pub legacy: Option<&'static str>,
+    pub default: fn() -> T,
+    /// Fields of the value that are credentials. A setting with any is stored
+    /// sealed, and exports and snapshots leave these fields out.
+    pub secret: &'static [&'static str],
+    /// Rejects values that must not be saved
+    pub check: fn(&T) -> Result<(), String>,
+}
+
+impl<T> Setting<T> {
+    pub fn default_value(&self) -> T {
+        (self.default)()
+    }
+}
+
+/// A setting of any type, handled as JSON
+pub trait AnySetting: Sync {
+    fn key(&self) -> &'static str;
+    fn legacy(&self) -> Option<&'static str>;
+    fn default_json(&self) -> serde_json::Value;
+    fn secret(&self) -> &'static [&'static str];
+    /// Parse and check a JSON value as this setting would be saved
+    fn check_json(&self, value: &serde_json::Value) -> Result<(), String>;
+}
+
+impl<T: Serialize + DeserializeOwned> AnySetting for Setting<T> {
+    fn key(&self) -> &'static str {
+        self.key
+    }
+
+    fn legacy(&self) -> Option<&'static str> {
+        self.legacy
+    }
+
+    fn default_json(&self) -> serde_json::Value {
+        serde_json::json!(self.default_value())
+    }
+
+    fn secret(&self) -> &'static [&'static str] {
+        self.secret
+    }
+
+    fn check_json(&self, value: &serde_json::Value) -> Result<(), String> {
+        let value: T = serde_json::from_value(value.clone()).map_err(|e| format!("Invalid {}: {}", self.key, e))?;
+        (self.check)(&value).map_err(|e| format!("{}: {}", self.key, e))
+    }
+}
+
+fn any<T>(_: &T) -> Result<(), String> {
+    Ok(())
+}
+
+pub const AUDIO_ENABLED: Setting<bool> = Setting {
+    key: "audio.enabled",
+    legacy: Some("audio_enabled"),
+    default: || true,
+    secret: &[],
+    check: any,
+};
+
+pub const AUDIO_VOLUME: Setting<f32> = Setting {
+    key: "audio.volume",
+    legacy: Some("audio_volume"),
+    default: || 0.5,
+    secret: &[],
+    check: |volume| {
+        if (0.0..=1.0).contains(volume) {
+            Ok(())
+        } else {
+            Err("Volume must be between 0.0 and 1.0".to_string())
+        }
+    },
+};
+
+/// Custom sound file paths, keyed by alert
+pub const AUDIO_CUSTOM_SOUNDS: Setting<HashMap<AlertSound, String>> = Setting {
+    key: "audio.custom_sounds",
+    legacy: Some("custom_sounds"),
+    default: HashMap::new,
+    secret: &[],
+    check: any,
+};
+
+/// Sound played per alert event; unlisted events use their default
+pub const AUDIO_SOUND_MAP: Setting<SoundMap> = Setting {
+    key: "audio.sound_map",
+    legacy: Some("sound_map"),
+    default: SoundMap::new,
+    secret: &[],
+    check: any,
+};
+
+/// Preferred OpenAI model; `None` leaves the choice to the client
+pub const OPENAI_MODEL: Setting<Option<String>> = Setting {
+    key: "llm.openai_model",
+    legacy: Some("openai_model"),
+    default: || None,
+    secret: &[],
+    check: |model| match model {
+        Some(model) if model.trim().is_empty() => Err("Model cannot be empty".to_string()),
+        _ => Ok(()),
+    },
+};
+
+pub const QUIET_HOURS: Setting<QuietHours> = Setting {
+    key: "notify.quiet_hours",
+    legacy: Some("quiet_hours"),
+    default: QuietHours::default,
+    secret: &[],
+    check: |hours| QuietPolicy::new(hours.clone()).map(|_| ()),
+};
+
+/// Log level restored on start, one of `logging::LEVELS`
+pub const LOG_LEVEL: Setting<String> = Setting {
+    key: "logging.level",
+    legacy: None,
+    default: || crate::logging::DEFAULT_LEVEL.to_string(),
+    secret: &[],
+    check: |level| crate::logging::parse_level(level).map(|_| ()),
+};
+
+/// Value of `setting` from its stored JSON, or its default when nothing is stored
+pub fn resolve<T: DeserializeOwned>(setting: &Setting<T>, stored: Option<serde_json::Value>) -> Result<T, String> {
+    match stored {
+        Some(value) => serde_json::from_value(value)
+            .map_err(|e| format!("Invalid {} format in storage: {}", setting.key, e)),
+        None => Ok(setting.default_value()),
+    }
+}
+
+/// When stored API keys count as failing or old
+pub const KEY_AGE: Setting<KeyAgePolicy> = Setting {
+    key: "credentials.key_age",
+    legacy: None,
+    default: KeyAgePolicy::default,
+    secret: &[],
+    check: any,
+};
+
+/// Port of the Prometheus metrics endpoint on 127.0.0.1; `None` keeps it off
+pub const METRICS_PORT: Setting<Option<u16>> = Setting {
+    key: "metrics.prometheus_port",
+    legacy: None,
+    default: || None,
+    secret: &[],
+    check: |port| match port {
+        Some(0) => Err("Port must be between 1 and 65535".to_string()),
+        _ => Ok(()),
+    },
+};
+
+/// Ids of the plugins loaded at startup
+///
+/// Not in `REGISTRY`: loading a plugin runs its code, so importing settings
+/// must not switch one on.
+pub const PLUGINS_ENABLED: Setting<Vec<String>> = Setting {
+    key: "plugins.enabled",
+    legacy: None,
+    default: Vec::new,
+    secret: &[],
+    check: any,
+};
+
+/// Correction for pointer moves found by the last calibration; `None` moves uncorrected
+///
+/// Not in `REGISTRY`: it describes this machine's displays, so it is not
+/// exported to another one.
+pub const INPUT_CALIBRATION: Setting<Option<CursorTransform>> = Setting {
+    key: "input.calibration",
+    legacy: None,
+    default: || None,
+    secret: &[],
+    check: |transform| transform.as_ref().map_or(Ok(()), CursorTransform::check),
+};
+
+/// Vosk model and wake word for voice commands; `None` keeps the microphone off
+///
+/// Not in `REGISTRY`: the model directory is a path on this machine.
+pub const VOICE_COMMANDS: Setting<Option<VoiceConfig>> = Setting {
+    key: "voice.commands",
+    legacy: None,
+    default: || None,
+    secret: &[],
+    check: |config| config.as_ref().map_or(Ok(()), VoiceConfig::validate),
+};
+
+/// Where traces are exported; `None` keeps tracing off. The headers usually hold an API key.
+pub const OTLP_TRACES: Setting<Option<OtlpConfig>> = Setting {
+    key: "telemetry.otlp",
+    legacy: Some("otlp_traces"),
+    default: || None,
+    secret: &["headers"],
+    check: |config| config.as_ref().map_or(Ok(()), OtlpConfig::validate),
+};
+
+/// Remote control API; `None` keeps it off. The token lets anyone who has it start runs.
+pub const REMOTE_API: Setting<Option<RemoteApiConfig>> = Setting {
+    key: "remote.api",
+    legacy: Some("remote_api"),
+    default: || None,
+    secret: &["token"],
+    check: |config| config.as_ref().map_or(Ok(()), RemoteApiConfig::validate),
+};
+
+/// Every typed setting
+pub const REGISTRY: &[&dyn AnySetting] = &[
+    &AUDIO_ENABLED,
+    &AUDIO_VOLUME,
+    &AUDIO_CUSTOM_SOUNDS,
+    &AUDIO_SOUND_MAP,
+    &OPENAI_MODEL,
+    &QUIET_HOURS,
+    &KEY_AGE,
+    &LOG_LEVEL,
+    &METRICS_PORT,
+    &REMOTE_API,
+    &OTLP_TRACES,
+];
+
+/// Every typed setting with its default
+pub fn defaults() -> BTreeMap<&'static str, serde_json::Value> {
+    REGISTRY.iter().map(|s| (s.key(), s.default_json())).collect()
+}
+
+/// Whether `key` follows the `<area>.<name>` scheme
+pub fn is_namespaced(key: &str) -> bool {
+    let segment = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    matches!(key.split_once('.'), Some((area, name)) if segment(area) && segment(name))
+}
+
+/// Take the credential fields of `setting` out of `value`, returning whether any was set
+pub fn strip_secrets(setting: &dyn AnySetting, value: &mut serde_json::Value) -> bool {
+    let Some(fields) = value.as_object_mut() else {
+        return false;
+    };
+    let mut set = false;
+    for name in setting.secret() {
+        set |= fields.remove(*name).is_some_and(|v| !is_empty(&v));
+    }
+    set
+}
+
+/// Put the credential fields of `setting` stored in `from` back into `value`
+pub fn restore_secrets(setting: &dyn AnySetting, value: &mut serde_json::Value, from: &serde_json::Value) {
+    let Some(fields) = value.as_object_mut() else {
+        return;
+    };
+    for name in setting.secret() {
+        if let Some(secret) = from.get(*name) {
+            fields.insert(name.to_string(), secret.clone());
+        }
+    }
+}
+
+fn is_empty(value: &serde_json::Value) -> bool {
+    match value {
+        serde_json::Value::Null => true,
+        serde_json::Value::String(s) => s.is_empty(),
+        serde_json::Value::Array(a) => a.is_empty(),
+        serde_json::Value::Object(o) => o.is_empty(),
+        _ => false,
+    }
+}
+
+/// Change keys of entries that are not typed settings
+pub const WEBHOOKS_KEY: &str = "notify.webhooks";
+pub const SMTP_KEY: &str = "notify.smtp";
+pub const PROFILE_SYNC_KEY: &str = "sync.profiles";
+pub const PROFILE_SECRETS_KEY: &str = "profiles.secrets";
+
+/// Change key of the secret entry holding an API key, e.g. `credentials.openai_api_key`
+pub fn credential_key(entry: &str) -> String {
+    format!("credentials.{}", entry)
+}
+
+/// Payload of `loopautoma://settings`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SettingChange {
+    pub key: String,
+    /// The entry was deleted rather than set
+    #[serde(default)]
+    pub removed: bool,
+}
+
+/// Current settings as returned by `settings_snapshot`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SettingsSnapshot {
+    /// Every typed setting, stored or default
+    pub settings: BTreeMap<String, serde_json::Value>,
+    pub providers: Vec<credentials::ProviderStatus>,
+    /// Names of the credentials that are set, never their values
+    pub secrets: Vec<String>,
+}
+
+pub const EXPORT_FORMAT: &str = "loopautoma-settings";
+pub const EXPORT_VERSION: u32 = 1;
+
+/// Export key of the configured LLM providers; their API keys are listed as secrets
+pub const PROVIDERS_KEY: &str = "llm.providers";
+
+/// Settings file written by `settings_export`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SettingsExport {
+    pub format: String,
+    pub version: u32,
+    pub settings: BTreeMap<String, serde_json::Value>,
+    /// Secrets set on the exporting machine and left out of the file, e.g. `anthropic_api_key`
+    #[serde(default)]
+    pub secrets: Vec<String>,
+}
+
+impl SettingsExport {
+    pub fn new(settings: BTreeMap<String, serde_json::Value>, secrets: Vec<String>) -> Self {
+        Self {
+            format: EXPORT_FORMAT.to_string(),
+            version: EXPORT_VERSION,
+            settings,
+            secrets,
+        }
+    }
+
+    /// Check every value before anything is saved; keys this version does not know are skipped
+    pub fn plan(&self) -> Result<ImportPlan, String> {
+        if self.format != EXPORT_FORMAT {
+            return Err(format!("Not a loopautoma settings file (format '{}')", self.format));
+        }
+        if self.version > EXPORT_VERSION {
+            return Err(format!(
+                "Settings file version {} is newer than this app supports ({})",
+                self.version, EXPORT_VERSION
+            ));
+        }
+        let mut plan = ImportPlan::default();
+        for (key, value) in &self.settings {
+            if key == PROVIDERS_KEY {
+                let providers: Providers =
+                    serde_json::from_value(value.clone()).map_err(|e| format!("Invalid {}: {}", key, e))?;
+                for (id, settings) in &providers {
+                    if credentials::kind_of(id)? != settings.kind {
+                        return Err(format!("Provider '{}' is not of kind '{}'", id, settings.kind.id()));
+                    }
+                }
+                plan.providers = Some(providers);
+            } else if let Some(setting) = REGISTRY.iter().find(|s| s.key() == key) {
+                if self.secrets.contains(key) && !setting.secret().is_empty() {
+                    plan.needs_secrets.push((*setting, value.clone()));
+                } else {
+                    setting.check_json(value)?;
+                    plan.values.push((*setting, value.clone()));
+                }
+            } else {
+                plan.ignored.push(key.clone());
+            }
+        }
+        Ok(plan)
+    }
+}
+
+/// Checked contents of a settings file, ready to save
+#[derive(Default)]
+pub struct ImportPlan {
+    pub values: Vec<(&'static dyn AnySetting, serde_json::Value)>,
+    /// Values whose credentials were left out of the file, checked once the
+    /// ones stored here are put back
+    pub needs_secrets: Vec<(&'static dyn AnySetting, serde_json::Value)>,
+    pub providers: Option<Providers>,
+    pub ignored: Vec<String>,
+}
+
+/// Outcome of `settings_import`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportReport {
+    pub imported: Vec<String>,
+    /// Keys from a newer version, left alone
+    pub ignored: Vec<String>,
+    /// Secrets left out of the file that are not set here yet
+    pub reenter: Vec<String>,
+}
@@ -0,0 +1,359 @@
+/// Coordinated shutdown of background threads
+///
+/// The runner loop, region previews, the profile sync scheduler and the
+/// notification senders run on their own threads. They are started through
+/// `Shutdown::spawn`, which hands each one a `ShutdownToken` and tracks it
+/// until it returns. Stopping a profile cancels only the runner's token.
+/// Exiting the app calls `Shutdown::run`. It cancels every token, waits for
+/// the tracked threads up to a deadline, then runs the registered hooks, such
+/// as releasing held keys and closing the remote API. Threads still running at
+/// the deadline are named in the report and left to the process exit.
+///
+/// Threads check their token between steps and sleep with `ShutdownToken::sleep`,
+/// which returns early on cancellation. An action in progress is abandoned by
+/// its supervisor (see `watchdog`) on cancellation or when it overruns, which
+/// cancels the action's own child token; `CancellableAutomation` refuses the
+/// input it tries to send afterwards.
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::domain::{Automation, Error, MouseButton};
+
+/// How long app exit waits for tracked threads
+pub const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Longest a sleeping thread takes to notice that a parent token was cancelled
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+struct TokenState {
+    cancelled: AtomicBool,
+    parent: Option<Arc<TokenState>>,
+    lock: Mutex<()>,
+    wake: Condvar,
+}
+
+impl TokenState {
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed) || self.parent.as_ref().is_some_and(|p| p.is_cancelled())
+    }
+}
+
+/// Tells a thread to stop; cancelling a token also cancels its children
+#[derive(Clone)]
+pub struct ShutdownToken(Arc<TokenState>);
+
+impl Default for ShutdownToken {
+    fn default() -> Self {
+        Self::with_parent(None)
+    }
+}
+
+impl std::fmt::Debug for ShutdownToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ShutdownToken").field("cancelled", &self.is_cancelled()).finish()
+    }
+}
+
+impl ShutdownToken {
+    fn with_parent(parent: Option<Arc<TokenState>>) -> Self {
+        Self(Arc::new(TokenState {
+            cancelled: AtomicBool::new(false),
+            parent,
+            lock: Mutex::new(()),
+            wake: Condvar::new(),
+        }))
+    }
+
+    /// A token cancelled with this one or on its own
+    pub fn child(&self) -> Self {
+        Self::with_parent(Some(self.0.clone()))
+    }
+
+    pub fn cancel(&self) {
+        self.0.cancelled.store(true, Ordering::Relaxed);
+        let _guard = self.0.lock.lock().unwrap();
+        self.0.wake.notify_all();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.is_cancelled()
+    }
+
+    /// Sleep for `duration` or until cancelled; true if cancelled
+    pub fn sleep(&self, duration: Duration) -> bool {
+        let deadline = Instant::now() + duration;
+        let mut guard = self.0.lock.lock().unwrap();
+        loop {
+            if self.is_cancelled() {
+                return true;
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return false;
+            }
+            guard = self.0.wake.wait_timeout(guard, (deadline - now).min(POLL_INTERVAL)).unwrap().0;
+        }
+    }
+}
+
+type Hook = Box<dyn FnOnce() + Send>;
+
+/// Tracked threads by id, with their names
+#[derive(Default)]
+struct Running {
+    threads: Mutex<BTreeMap<u64, String>>,
+    done: Condvar,
+}
+
+/// Removes its thread from `Running` when the thread ends, by return or panic
+struct Finished {
+    running: Arc<Running>,
+    id: u64,
+}
+
+impl Drop for Finished {
+    fn drop(&mut self) {
+        self.running.threads.lock().unwrap().remove(&self.id);
+        self.running.done.notify_all();
+    }
+}
+
+/// What `Shutdown::run` stopped and what it gave up on
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ShutdownReport {
+    /// Threads still running at the deadline
+    pub timed_out: Vec<String>,
+    /// Hooks that ran
+    pub hooks: Vec<String>,
+}
+
+/// The process's background threads and the cleanup to run at exit
+#[derive(Default)]
+pub struct Shutdown {
+    token: ShutdownToken,
+    running: Arc<Running>,
+    next_id: AtomicU64,
+    /// By name, so re-registering a hook replaces it; run in name order
+    hooks: Mutex<BTreeMap<String, Hook>>,
+}
+
+impl Shutdown {
+    /// Cancelled when the app exits
+    pub fn token(&self) -> ShutdownToken {
+        self.token.clone()
+    }
+
+    /// Start a tracked thread named `name`; it gets `token`, or a child of the app token
+    pub fn spawn<T, F>(&self, name: &str, token: Option<ShutdownToken>, f: F) -> JoinHandle<T>
+    where
+        F: FnOnce(ShutdownToken) -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let token = token.unwrap_or_else(|| self.token.child());
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.running.threads.lock().unwrap().insert(id, name.to_string());
+        let finished = Finished {
+            running: self.running.clone(),
+            id,
+        };
+        std::thread::Builder::new()
+            .name(format!("loopautoma-{}", name))
+            .spawn(move || {
+                let _finished = finished;
+                f(token)
+            })
+            .expect("failed to spawn thread")
+    }
+
+    /// Run `hook` at exit after the threads stopped; replaces a hook of the same name
+    pub fn on_shutdown(&self, name: &str, hook: impl FnOnce() + Send + 'static) {
+        self.hooks.lock().unwrap().insert(name.to_string(), Box::new(hook));
+    }
+
+    /// Cancel every token, wait up to `timeout` for the threads, then run the hooks
+    pub fn run(&self, timeout: Duration) -> ShutdownReport {
+        self.token.cancel();
+        let deadline = Instant::now() + timeout;
+        let mut threads = self.running.threads.lock().unwrap();
+        while !threads.is_empty() {
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            threads = self.running.done.wait_timeout(threads, deadline - now).unwrap().0;
+        }
+        let timed_out: Vec<String> = threads.values().cloned().collect();
+        drop(threads);
+        let hooks = std::mem::take(&mut *self.hooks.lock().unwrap());
+        let mut report = ShutdownReport {
+            timed_out,
+            hooks: Vec::new(),
+        };
+        for (name, hook) in hooks {
+            hook();
+            report.hooks.push(name);
+        }
+        report
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Held {
+    Key(String),
+    Button(MouseButton),
+}
+
+/// Automation that remembers keys and buttons pressed and not yet released
+///
+/// A run stopped between a `key_down` and its `key_up` would leave the key
+/// held on the desktop; `release_all` lets them go.
+pub struct ReleasingAutomation<A: ?Sized> {
+    inner: Box<A>,
+    held: Mutex<Vec<Held>>,
+}
+
+impl<A: Automation + ?Sized> ReleasingAutomation<A> {
+    pub fn new(inner: Box<A>) -> Self {
+        Self {
+            inner,
+            held: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn forget(&self, input: &Held) {
+        let mut held = self.held.lock().unwrap();
+        if let Some(i) = held.iter().rposition(|h| h == input) {
+            held.remove(i);
+        }
+    }
+
+    /// Release everything still held, last pressed first; returns how many were released
+    pub fn release_all(&self) -> usize {
+        let held = std::mem::take(&mut *self.held.lock().unwrap());
+        for input in held.iter().rev() {
+            let released = match input {
+                Held::Key(key) => self.inner.key_up(key),
+                Held::Button(button) => self.inner.mouse_up(*button),
+            };
+            if let Err(e) = released {
+                tracing::warn!("Failed to release {:?}: {}", input, e);
+            }
+        }
+        held.len()
+    }
+}
+
+impl<A: Automation + ?Sized> Automation for ReleasingAutomation<A> {
+    fn move_cursor(&self, x: u32, y: u32) -> Result<(), Error> {
+        self.inner.move_cursor(x, y)
+    }
+
+    fn click(&self, button: MouseButton) -> Result<(), Error> {
+        self.inner.click(button)
+    }
+
+    fn type_text(&self, text: &str) -> Result<(), Error> {
+        self.inner.type_text(text)
+    }
+
+    fn key(&self, key: &str) -> Result<(), Error> {
+        self.inner.key(key)
+    }
+
+    fn mouse_down(&self, button: MouseButton) -> Result<(), Error> {
+        self.inner.mouse_down(button)?;
+        self.held.lock().unwrap().push(Held::Button(button));
+        Ok(())
+    }
+
+    fn mouse_up(&self, button: MouseButton) -> Result<(), Error> {
+        self.forget(&Held::Button(button));
+        self.inner.mouse_up(button)
+    }
+
+    fn key_down(&self, key: &str) -> Result<(), Error> {
+        self.inner.key_down(key)?;
+        self.held.lock().unwrap().push(Held::Key(key.to_string()));
+        Ok(())
+    }
+
+    fn key_up(&self, key: &str) -> Result<(), Error> {
+        self.forget(&Held::Key(key.to_string()));
+        self.inner.key_up(key)
+    }
+
+    fn cursor_position(&self) -> Result<Option<(i32, i32)>, Error> {
+        self.inner.cursor_position()
+    }
+}
+
+/// Automation that refuses new input once `cancel` is cancelled
+///
+/// An abandoned action may still be running. It must not press anything after
+/// the run was paused or stopped, but it may still let go of what it holds.
+pub struct CancellableAutomation<'a> {
+    inner: &'a dyn Automation,
+    cancel: &'a ShutdownToken,
+}
+
+impl<'a> CancellableAutomation<'a> {
+    pub fn new(inner: &'a dyn Automation, cancel: &'a ShutdownToken) -> Self {
+        Self { inner, cancel }
+    }
+
+    fn check(&self) -> Result<(), Error> {
+        if self.cancel.is_cancelled() {
+            return Err(Error::Cancelled("input refused: the action was cancelled".into()));
+        }
+        Ok(())
+    }
+}
+
+impl Automation for CancellableAutomation<'_> {
+    fn move_cursor(&self, x: u32, y: u32) -> Result<(), Error> {
+        self.check()?;
+        self.inner.move_cursor(x, y)
+    }
+
+    fn click(&self, button: MouseButton) -> Result<(), Error> {
+        self.check()?;
+        self.inner.click(button)
+    }
+
+    fn type_text(&self, text: &str) -> Result<(), Error> {
+        self.check()?;
+        self.inner.type_text(text)
+    }
+
+    fn key(&self, key: &str) -> Result<(), Error> {
+        self.check()?;
+        self.inner.key(key)
+    }
+
+    fn mouse_down(&self, button: MouseButton) -> Result<(), Error> {
+        self.check()?;
+        self.inner.mouse_down(button)
+    }
+
+    fn mouse_up(&self, button: MouseButton) -> Result<(), Error> {
+        self.inner.mouse_up(button)
+    }
+
+    fn key_down(&self, key: &str) -> Result<(), Error> {
+        self.check()?;
+        self.inner.key_down(key)
+    }
+
+    fn key_up(&self, key: &str) -> Result<(), Error> {
+        self.inner.key_up(key)
+    }
+
+    fn cursor_position(&self) -> Result<Option<(i32, i32)>, Error> {
+        self.inner.cursor_position()
+    }
+}
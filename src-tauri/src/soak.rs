@@ -103,10 +103,13 @@ fn build_profile(config: &SoakConfig) -> Profile {
                 height: 400,
             },
             name: Some("Soak".into()),
+            anchor: None,
         }],
         trigger: TriggerConfig {
             r#type: "IntervalTrigger".into(),
             check_interval_sec: config.check_interval_sec,
+            hotkey: None,
+            plugin: None,
         },
         condition: ConditionConfig {
             r#type: "RegionCondition".into(),
@@ -124,14 +127,25 @@ fn build_profile(config: &SoakConfig) -> Profile {
         guardrails: Some(GuardrailsConfig {
             max_runtime_ms: Some(config.max_runtime_ms),
             max_activations_per_hour: Some((3_600_000u64 / config.cooldown_ms.max(1)).max(1) as u32),
+            max_iterations: None,
+            max_consecutive_llm_failures: None,
             cooldown_ms: config.cooldown_ms,
             heartbeat_timeout_ms: None,
+            action_timeout_ms: None,
             ocr_mode: crate::domain::OcrMode::default(),
             success_keywords: Vec::new(),
             failure_keywords: Vec::new(),
             ocr_termination_pattern: None,
             ocr_region_ids: Vec::new(),
         }),
+        on_complete: None,
+        on_failure: None,
+        env_allowlist: vec![],
+        notifications: Default::default(),
+        run_windows: vec![],
+        frame_archive: None,
+        privacy: None,
+        voice: None,
     }
 }
 
@@ -139,6 +153,7 @@ fn process_events(report: &mut SoakReport, events: Vec<Event>) {
     for event in events {
         match event {
             Event::WatchdogTripped { reason } => report.guardrail_trips.push(reason),
+            Event::BudgetExceeded { budget, .. } => report.guardrail_trips.push(budget),
             Event::Error { message } => report.error_events.push(message),
             Event::ActionCompleted { success, .. } if !success => report.action_failures += 1,
             _ => {}
@@ -0,0 +1,380 @@
+/// Remote profile sync from an HTTPS URL or a git repository
+///
+/// A team publishes a profile bundle (a `profiles.json`-style config or a single
+/// exported profile, JSON or YAML) and every machine pulls it on a schedule. The
+/// bundle must match a SHA-256 pinned in the sync settings, or carry a detached
+/// Ed25519 signature (`<bundle>.sig`) made with the key whose public half is
+/// configured; on a mismatch nothing is changed. Anything published next to the
+/// bundle can be replaced with it, so a checksum from the same place proves nothing.
+/// Synced profiles replace local ones with the same id; local-only profiles stay.
+///
+/// Downloads and git commands have deadlines, so a stalled server fails the
+/// sync instead of hanging the scheduled sync thread.
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+use std::time::{Duration, Instant};
+
+use base64::engine::general_purpose::STANDARD as Base64Standard;
+use base64::Engine as _;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::runtime::AsyncRuntime;
+use sha2::{Digest, Sha256};
+
+use crate::domain::Profile;
+use crate::migration;
+use crate::profile_io::{self, ProfileFormat};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum SyncSource {
+    /// Bundle served over HTTPS
+    Url { url: String },
+    /// Bundle at `path` inside a git repository; any URL `git clone` accepts
+    Git {
+        repo: String,
+        #[serde(default)]
+        branch: Option<String>,
+        path: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncConfig {
+    pub source: SyncSource,
+    /// Minutes between scheduled pulls; 0 syncs only on demand
+    #[serde(default)]
+    pub interval_minutes: u32,
+    /// Expected SHA-256 of the bundle
+    #[serde(default)]
+    pub sha256: Option<String>,
+    /// Ed25519 public key (base64 of the raw key or its DER/PEM form) that
+    /// `<bundle>.sig` must verify against
+    #[serde(default)]
+    pub public_key: Option<String>,
+}
+
+/// Longest a download may wait to connect, and then for more data
+#[cfg_attr(not(feature = "remote-sync"), allow(dead_code))]
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+#[cfg_attr(not(feature = "remote-sync"), allow(dead_code))]
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+/// Longest a `git clone` or `git fetch` may run before it is killed
+const GIT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// DER header of an Ed25519 public key, as `openssl pkey -pubout -outform DER` writes it
+const ED25519_SPKI_PREFIX: [u8; 12] = [0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00];
+
+impl SyncConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        match &self.source {
+            SyncSource::Url { url } => {
+                if !url.starts_with("https://") {
+                    return Err(format!("Sync URL must use https://, got '{}'", url));
+                }
+            }
+            SyncSource::Git { repo, branch, path } => {
+                if repo.trim().is_empty() || path.trim().is_empty() {
+                    return Err("Git sync needs a repository and a file path".into());
+                }
+                // git would take it for an option
+                if branch.as_deref().is_some_and(|b| b.trim_start().starts_with('-')) {
+                    return Err(format!("Branch '{}' must not start with '-'", branch.as_deref().unwrap_or_default()));
+                }
+                let escapes = |c: Component| matches!(c, Component::ParentDir | Component::RootDir | Component::Prefix(_));
+                if Path::new(path).components().any(escapes) {
+                    return Err(format!("Bundle path '{}' must stay inside the repository", path));
+                }
+            }
+        }
+        if let Some(hash) = &self.sha256 {
+            parse_sha256(hash)?;
+        }
+        if let Some(key) = &self.public_key {
+            parse_public_key(key)?;
+        }
+        if self.sha256.is_none() && self.public_key.is_none() {
+            return Err("Pin the bundle's SHA-256 or set the public key that signs it".into());
+        }
+        Ok(())
+    }
+}
+
+/// A downloaded bundle and the signature published alongside it, if any
+#[derive(Debug, Clone)]
+pub struct Fetched {
+    /// File name of the bundle; its extension selects JSON or YAML
+    pub name: String,
+    pub bytes: Vec<u8>,
+    /// Contents of `<bundle>.sig`: a base64 Ed25519 signature of `bytes`
+    pub signature: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncReport {
+    pub sha256: String,
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+}
+
+impl SyncReport {
+    pub fn changed(&self) -> bool {
+        !self.added.is_empty() || !self.updated.is_empty()
+    }
+}
+
+/// Outcome of the most recent sync attempt
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncStatus {
+    pub at_ms: u64,
+    pub report: Option<SyncReport>,
+    pub error: Option<String>,
+}
+
+pub fn fetch(runtime: &AsyncRuntime, source: &SyncSource, cache_dir: &Path) -> Result<Fetched, String> {
+    match source {
+        SyncSource::Url { url } => fetch_url(runtime, url),
+        SyncSource::Git { repo, branch, path } => fetch_git(repo, branch.as_deref(), path, cache_dir),
+    }
+}
+
+#[cfg(feature = "remote-sync")]
+fn fetch_url(runtime: &AsyncRuntime, url: &str) -> Result<Fetched, String> {
+    let client = reqwest::Client::builder()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .read_timeout(READ_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let client = &client;
+    let get = |url: String| async move {
+        let response = client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to download {}: {}", url, e))?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let response = response
+            .error_for_status()
+            .map_err(|e| format!("Failed to download {}: {}", url, e))?;
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| format!("Failed to download {}: {}", url, e))?;
+        Ok::<_, String>(Some(bytes.to_vec()))
+    };
+    runtime.block_on(async {
+        let bytes = get(url.to_string())
+            .await?
+            .ok_or_else(|| format!("Failed to download {}: not found", url))?;
+        let signature = get(format!("{}.sig", url)).await?;
+        Ok(Fetched {
+            name: url.rsplit('/').next().unwrap_or(url).to_string(),
+            bytes,
+            signature: signature.map(|b| String::from_utf8_lossy(&b).into_owned()),
+        })
+    })
+}
+
+#[cfg(not(feature = "remote-sync"))]
+fn fetch_url(_runtime: &AsyncRuntime, _url: &str) -> Result<Fetched, String> {
+    Err("This build cannot sync profiles over HTTPS".into())
+}
+
+/// Shallow-clone the repository once, then fetch and reset to the branch tip on later pulls
+fn fetch_git(repo: &str, branch: Option<&str>, path: &str, cache_dir: &Path) -> Result<Fetched, String> {
+    let checkout = cache_dir.join(&sha256_hex(repo.as_bytes())[..16]);
+    let git = |args: &[&str], dir: Option<&Path>| -> Result<(), String> {
+        let mut cmd = Command::new("git");
+        if let Some(dir) = dir {
+            cmd.arg("-C").arg(dir);
+        }
+        // Fail instead of waiting for a password nobody will type
+        cmd.env("GIT_TERMINAL_PROMPT", "0").args(args);
+        let command = format!("git {}", args.first().copied().unwrap_or_default());
+        let (status, stderr) = run_with_timeout(cmd, GIT_TIMEOUT).map_err(|e| format!("{}: {}", command, e))?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(format!("{} failed: {}", command, stderr.trim()))
+        }
+    };
+
+    if checkout.join(".git").is_dir() {
+        git(&["fetch", "--depth", "1", "--", "origin", branch.unwrap_or("HEAD")], Some(&checkout))?;
+        git(&["reset", "--hard", "FETCH_HEAD"], Some(&checkout))?;
+    } else {
+        std::fs::create_dir_all(cache_dir).map_err(|e| format!("Failed to create sync cache: {}", e))?;
+        let target = checkout.to_string_lossy().into_owned();
+        let mut args = vec!["clone", "--depth", "1"];
+        if let Some(branch) = branch {
+            args.extend(["--branch", branch]);
+        }
+        args.extend(["--", repo, target.as_str()]);
+        git(&args, None)?;
+    }
+
+    let file = checkout.join(path);
+    let bytes = std::fs::read(&file).map_err(|e| format!("Failed to read {} from {}: {}", path, repo, e))?;
+    let signature = std::fs::read_to_string(checkout.join(format!("{}.sig", path))).ok();
+    Ok(Fetched {
+        name: path.to_string(),
+        bytes,
+        signature,
+    })
+}
+
+/// Run `cmd` to completion, killing it once `timeout` has passed; returns its status and stderr
+pub fn run_with_timeout(mut cmd: Command, timeout: Duration) -> Result<(ExitStatus, String), String> {
+    let mut child = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run: {}", e))?;
+    let mut stderr = child.stderr.take();
+    // Read as it comes, so a chatty command cannot fill the pipe and stall
+    let reader = std::thread::spawn(move || {
+        let mut text = String::new();
+        if let Some(stderr) = stderr.as_mut() {
+            let _ = stderr.read_to_string(&mut text);
+        }
+        text
+    });
+    let deadline = Instant::now() + timeout;
+    loop {
+        match child.try_wait().map_err(|e| format!("Failed to wait: {}", e))? {
+            Some(status) => return Ok((status, reader.join().unwrap_or_default())),
+            None if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("no answer after {} s", timeout.as_secs()));
+            }
+            None => std::thread::sleep(Duration::from_millis(50)),
+        }
+    }
+}
+
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Accepts a bare hex digest or a `sha256sum` line (`<hex>  <file>`)
+fn parse_sha256(text: &str) -> Result<String, String> {
+    let hex = text.split_whitespace().next().unwrap_or_default().to_ascii_lowercase();
+    if hex.len() == 64 && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(hex)
+    } else {
+        Err(format!("'{}' is not a SHA-256 checksum", text.trim()))
+    }
+}
+
+/// Ed25519 public key from base64 of the raw 32 bytes, or of its DER form with or without PEM armor
+fn parse_public_key(text: &str) -> Result<[u8; 32], String> {
+    let base64: String = text.lines().filter(|l| !l.starts_with("-----")).map(str::trim).collect();
+    let der = Base64Standard
+        .decode(base64)
+        .map_err(|_| "The sync public key is not base64".to_string())?;
+    let raw = match der.strip_prefix(&ED25519_SPKI_PREFIX[..]) {
+        Some(raw) => raw,
+        None => &der[..],
+    };
+    raw.try_into()
+        .map_err(|_| "The sync public key is not an Ed25519 key".to_string())
+}
+
+/// Check `signature` (base64) of `bytes` against `public_key`
+fn verify_signature(bytes: &[u8], signature: &str, public_key: &str) -> Result<(), String> {
+    let key = parse_public_key(public_key)?;
+    let signature = Base64Standard
+        .decode(signature.trim())
+        .map_err(|_| "The signature is not base64".to_string())?;
+    ring::signature::UnparsedPublicKey::new(&ring::signature::ED25519, key)
+        .verify(bytes, &signature)
+        .map_err(|_| "The signature does not match the configured public key".to_string())
+}
+
+/// Check the bundle against the pinned checksum and the signing key, whichever are
+/// configured, and parse its profiles
+pub fn verify_and_parse(fetched: &Fetched, config: &SyncConfig) -> Result<(String, Vec<Profile>), String> {
+    if config.sha256.is_none() && config.public_key.is_none() {
+        return Err(format!(
+            "Nothing to verify {} against: pin its SHA-256 or set the public key that signs it",
+            fetched.name
+        ));
+    }
+    let actual = sha256_hex(&fetched.bytes);
+    if let Some(pinned) = &config.sha256 {
+        let expected = parse_sha256(pinned)?;
+        if actual != expected {
+            return Err(format!(
+                "Checksum mismatch for {}: expected {}, got {}",
+                fetched.name, expected, actual
+            ));
+        }
+    }
+    if let Some(key) = &config.public_key {
+        let signature = fetched
+            .signature
+            .as_deref()
+            .ok_or_else(|| format!("No signature for {}: publish {}.sig", fetched.name, fetched.name))?;
+        verify_signature(&fetched.bytes, signature, key).map_err(|e| format!("{}: {}", fetched.name, e))?;
+    }
+    let text = std::str::from_utf8(&fetched.bytes).map_err(|_| format!("{} is not UTF-8 text", fetched.name))?;
+    Ok((actual, parse_bundle(text, ProfileFormat::from_path(Path::new(&fetched.name)))?))
+}
+
+/// A whole profiles config or a single exported profile
+pub fn parse_bundle(text: &str, format: ProfileFormat) -> Result<Vec<Profile>, String> {
+    let mut value: Value = match format {
+        ProfileFormat::Json => serde_json::from_str(text).map_err(|e| e.to_string()),
+        ProfileFormat::Yaml => serde_yaml::from_str(text).map_err(|e| e.to_string()),
+    }
+    .map_err(|e| format!("Invalid profile bundle: {}", e))?;
+    if value.get("profiles").is_none() {
+        return profile_io::import_profile(text, format).map(|p| vec![p]);
+    }
+
+    migration::migrate_config(&mut value)?;
+    let profiles: Vec<Profile> = serde_json::from_value(value["profiles"].take())
+        .map_err(|e| format!("Invalid profile bundle: {}", e))?;
+    let mut ids = std::collections::HashSet::new();
+    for p in &profiles {
+        profile_io::check_importable(p).map_err(|e| format!("Profile '{}': {}", p.id, e))?;
+        if !ids.insert(p.id.as_str()) {
+            return Err(format!("Duplicate profile id '{}' in bundle", p.id));
+        }
+    }
+    Ok(profiles)
+}
+
+/// Insert or replace the remote profiles; local-only profiles are left alone
+pub fn merge(local: &mut Vec<Profile>, remote: Vec<Profile>, sha256: String) -> SyncReport {
+    let mut report = SyncReport {
+        sha256,
+        ..SyncReport::default()
+    };
+    for profile in remote {
+        match local.iter_mut().find(|p| p.id == profile.id) {
+            Some(existing) if *existing == profile => {}
+            Some(existing) => {
+                report.updated.push(profile.id.clone());
+                *existing = profile;
+            }
+            None => {
+                report.added.push(profile.id.clone());
+                local.push(profile);
+            }
+        }
+    }
+    report
+}
+
+/// Default location for git checkouts: `<config dir>/loopautoma/sync`
+pub fn default_cache_dir() -> Option<PathBuf> {
+    Some(dirs::config_dir()?.join("loopautoma").join("sync"))
+}
@@ -0,0 +1,167 @@
+/// Built-in profile templates embedded in the binary
+///
+/// A template is a profile skeleton whose values may be `{{key}}` placeholders for
+/// its parameters. A string that is exactly one placeholder takes the parameter's
+/// value with its JSON type, so numbers and keyword lists stay typed. Regions are
+/// declared by id and name only; their rectangles come from the user.
+use std::collections::HashMap;
+
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::domain::{Profile, Rect, Region};
+use crate::profile_io;
+
+const TEMPLATE_SOURCES: &[&str] = &[
+    include_str!("../templates/keep-agent-going.json"),
+    include_str!("../templates/build-failure-alert.json"),
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplateRegion {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplateParameter {
+    pub key: String,
+    pub label: String,
+    /// Used when the caller supplies no value; also tells the UI the expected type
+    pub default: Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfileTemplate {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub regions: Vec<TemplateRegion>,
+    pub parameters: Vec<TemplateParameter>,
+    /// Profile skeleton without id, name and regions; not sent to the UI
+    #[serde(default, skip_serializing)]
+    pub profile: Value,
+}
+
+/// What the user fills in when creating a profile from a template
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TemplateInput {
+    /// Profile name; defaults to the template name
+    pub name: Option<String>,
+    /// Rectangle for every template region, keyed by region id
+    pub regions: HashMap<String, Rect>,
+    /// Parameter values, keyed by parameter key
+    pub values: HashMap<String, Value>,
+}
+
+pub fn builtin_templates() -> Vec<ProfileTemplate> {
+    TEMPLATE_SOURCES
+        .iter()
+        .map(|src| serde_json::from_str(src).expect("embedded template is valid JSON"))
+        .collect()
+}
+
+pub fn find_template(id: &str) -> Result<ProfileTemplate, String> {
+    builtin_templates()
+        .into_iter()
+        .find(|t| t.id == id)
+        .ok_or_else(|| format!("Unknown template '{}'", id))
+}
+
+/// Build a new profile from `template`; its id is made unique among `existing`
+pub fn instantiate(template: &ProfileTemplate, input: &TemplateInput, existing: &[Profile]) -> Result<Profile, String> {
+    if let Some(key) = input
+        .values
+        .keys()
+        .find(|k| !template.parameters.iter().any(|p| &p.key == *k))
+    {
+        return Err(format!("Template '{}' has no parameter '{}'", template.id, key));
+    }
+    let values: HashMap<&str, &Value> = template
+        .parameters
+        .iter()
+        .map(|p| (p.key.as_str(), input.values.get(&p.key).unwrap_or(&p.default)))
+        .collect();
+
+    let mut regions = Vec::with_capacity(template.regions.len());
+    for r in &template.regions {
+        let rect = input
+            .regions
+            .get(&r.id)
+            .ok_or_else(|| format!("Select a screen region for '{}'", r.name))?;
+        regions.push(Region {
+            id: r.id.clone(),
+            rect: *rect,
+            name: Some(r.name.clone()),
+            anchor: None,
+        });
+    }
+
+    let mut value = template.profile.clone();
+    let placeholder = Regex::new(r"\{\{([A-Za-z0-9_]+)\}\}").expect("valid regex");
+    fill_placeholders(&mut value, &placeholder, &values)?;
+    let obj = value
+        .as_object_mut()
+        .ok_or_else(|| format!("Template '{}' profile must be a JSON object", template.id))?;
+    let name = input
+        .name
+        .as_deref()
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .unwrap_or(&template.name);
+    obj.insert("id".into(), profile_io::unique_profile_id(&template.id, existing).into());
+    obj.insert("name".into(), name.into());
+    obj.insert(
+        "regions".into(),
+        serde_json::to_value(&regions).map_err(|e| e.to_string())?,
+    );
+
+    let profile: Profile = serde_json::from_value(value)
+        .map_err(|e| format!("Invalid value for template '{}': {}", template.id, e))?;
+    profile_io::check_importable(&profile)?;
+    Ok(profile)
+}
+
+fn fill_placeholders(value: &mut Value, re: &Regex, values: &HashMap<&str, &Value>) -> Result<(), String> {
+    let lookup = |key: &str| {
+        values
+            .get(key)
+            .copied()
+            .ok_or_else(|| format!("Template placeholder '{{{{{}}}}}' has no parameter", key))
+    };
+    match value {
+        Value::String(s) => {
+            if let Some(whole) = re.captures(s).filter(|c| c[0].len() == s.len()) {
+                *value = lookup(&whole[1])?.clone();
+                return Ok(());
+            }
+            let mut error = None;
+            let filled = re.replace_all(s, |c: &Captures| match lookup(&c[1]) {
+                Ok(Value::String(v)) => v.clone(),
+                Ok(v) => v.to_string(),
+                Err(e) => {
+                    error.get_or_insert(e);
+                    String::new()
+                }
+            });
+            if let Some(e) = error {
+                return Err(e);
+            }
+            *s = filled.into_owned();
+        }
+        Value::Array(items) => {
+            for item in items {
+                fill_placeholders(item, re, values)?;
+            }
+        }
+        Value::Object(obj) => {
+            for item in obj.values_mut() {
+                fill_placeholders(item, re, values)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
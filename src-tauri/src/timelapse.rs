@@ -0,0 +1,226 @@
+/// Animated exports of the region frames a run archived
+///
+/// A profile with a `frame_archive` keeps a small PNG of every region after
+/// each iteration, in a folder of the run's own in the screenshot archive.
+/// Exporting assembles the frames of one region into an animated GIF, or into
+/// an MP4 through `ffmpeg`, with the iteration and the time it was taken
+/// burned into each frame. The format follows the file extension, like
+/// reports and profile exports.
+///
+/// GIFs are encoded in process. MP4s need the `ffmpeg` binary, found on the
+/// `PATH` or at `LOOPAUTOMA_FFMPEG`; without it the export fails and says so.
+use std::io::{BufWriter, Cursor};
+use std::path::Path;
+use std::process::Command;
+
+use chrono::TimeZone;
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::imageops::FilterType;
+use image::{Delay, DynamicImage, Frame, ImageOutputFormat, Rgba, RgbaImage};
+
+use crate::domain::ScreenFrame;
+use crate::history::RunFrame;
+
+/// How long each frame is shown
+pub const FRAME_DELAY_MS: u32 = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnimationFormat {
+    Gif,
+    Mp4,
+}
+
+impl AnimationFormat {
+    /// `.mp4` files are MP4; anything else is a GIF
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase) {
+            Some(ext) if ext == "mp4" => AnimationFormat::Mp4,
+            _ => AnimationFormat::Gif,
+        }
+    }
+}
+
+/// `frame` as a PNG no larger than `max_size` on either side
+pub fn archive_png(frame: &ScreenFrame, max_size: u32) -> Option<Vec<u8>> {
+    let image = RgbaImage::from_vec(frame.width, frame.height, frame.bytes.clone())?;
+    let mut image = DynamicImage::ImageRgba8(image);
+    let max_size = max_size.max(1);
+    if frame.width > max_size || frame.height > max_size {
+        image = image.resize(max_size, max_size, FilterType::Triangle);
+    }
+    let mut png = Vec::new();
+    image.write_to(&mut Cursor::new(&mut png), ImageOutputFormat::Png).ok()?;
+    Some(png)
+}
+
+/// Write the frames of `region_id` to `path`; returns how many were written
+///
+/// `region_id` may be left out when the run archived a single region.
+pub fn export_animation(frames: &[RunFrame], region_id: Option<&str>, path: &Path) -> Result<usize, String> {
+    let mut regions: Vec<&str> = frames.iter().map(|f| f.region_id.as_str()).collect();
+    regions.sort_unstable();
+    regions.dedup();
+    let region = match (region_id, regions.as_slice()) {
+        (Some(region), _) => region,
+        (None, []) => return Err("No frames were archived for this run".into()),
+        (None, [region]) => *region,
+        (None, _) => {
+            return Err(format!(
+                "This run archived frames of several regions ({}); choose one",
+                regions.join(", ")
+            ))
+        }
+    };
+    let images = load_frames(frames.iter().filter(|f| f.region_id == region));
+    if images.is_empty() {
+        return Err(format!("No frames of region {} were found", region));
+    }
+    match AnimationFormat::from_path(path) {
+        AnimationFormat::Gif => write_gif(&images, path)?,
+        AnimationFormat::Mp4 => write_mp4(&images, path, &ffmpeg_binary())?,
+    }
+    Ok(images.len())
+}
+
+/// The archived PNGs, labelled and scaled to the size of the first one; missing files are skipped
+fn load_frames<'a>(frames: impl Iterator<Item = &'a RunFrame>) -> Vec<RgbaImage> {
+    let mut images: Vec<RgbaImage> = Vec::new();
+    for frame in frames {
+        let mut image = match image::open(&frame.path) {
+            Ok(image) => image.to_rgba8(),
+            Err(e) => {
+                tracing::warn!("Skipping frame {}: {}", frame.path, e);
+                continue;
+            }
+        };
+        // A region resized mid-run would leave the animation with frames of two sizes
+        if let Some(first) = images.first() {
+            if image.dimensions() != first.dimensions() {
+                image = image::imageops::resize(&image, first.width(), first.height(), FilterType::Triangle);
+            }
+        }
+        burn_label(&mut image, &frame_label(frame));
+        images.push(image);
+    }
+    images
+}
+
+/// `#<iteration> <local time>`
+fn frame_label(frame: &RunFrame) -> String {
+    let at = chrono::Local
+        .timestamp_millis_opt(frame.at_ms as i64)
+        .single()
+        .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_default();
+    format!("#{} {}", frame.iteration, at)
+}
+
+pub fn write_gif(frames: &[RgbaImage], path: &Path) -> Result<(), String> {
+    let file = std::fs::File::create(path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    let mut encoder = GifEncoder::new_with_speed(BufWriter::new(file), 10);
+    encoder.set_repeat(Repeat::Infinite).map_err(|e| format!("Failed to write GIF: {}", e))?;
+    let delay = Delay::from_numer_denom_ms(FRAME_DELAY_MS, 1);
+    encoder
+        .encode_frames(frames.iter().map(|image| Frame::from_parts(image.clone(), 0, 0, delay)))
+        .map_err(|e| format!("Failed to write GIF: {}", e))
+}
+
+/// Encode `frames` as H.264 with `ffmpeg`, run as `binary`
+pub fn write_mp4(frames: &[RgbaImage], path: &Path, binary: &str) -> Result<(), String> {
+    let dir = std::env::temp_dir().join(format!("loopautoma-frames-{}-{}", std::process::id(), crate::history::now_ms()));
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let result = encode_mp4(frames, &dir, path, binary);
+    let _ = std::fs::remove_dir_all(&dir);
+    result
+}
+
+fn encode_mp4(frames: &[RgbaImage], dir: &Path, path: &Path, binary: &str) -> Result<(), String> {
+    for (i, image) in frames.iter().enumerate() {
+        image
+            .save(dir.join(format!("frame-{:05}.png", i)))
+            .map_err(|e| format!("Failed to write frame {}: {}", i, e))?;
+    }
+    let output = Command::new(binary)
+        .args(["-y", "-loglevel", "error", "-framerate"])
+        .arg(format!("{}/{}", 1000, FRAME_DELAY_MS))
+        .arg("-i")
+        .arg(dir.join("frame-%05d.png"))
+        // H.264 in yuv420p needs even sizes
+        .args(["-vf", "pad=ceil(iw/2)*2:ceil(ih/2)*2", "-c:v", "libx264", "-pix_fmt", "yuv420p"])
+        .arg(path)
+        .output()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => format!(
+                "{} was not found; install ffmpeg or set LOOPAUTOMA_FFMPEG to export MP4, or export a .gif instead",
+                binary
+            ),
+            _ => format!("Failed to run {}: {}", binary, e),
+        })?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!("ffmpeg failed: {}", String::from_utf8_lossy(&output.stderr).trim()))
+    }
+}
+
+fn ffmpeg_binary() -> String {
+    std::env::var("LOOPAUTOMA_FFMPEG").unwrap_or_else(|_| "ffmpeg".to_string())
+}
+
+const GLYPH_WIDTH: u32 = 5;
+const GLYPH_HEIGHT: u32 = 7;
+
+/// Rows of a 5x7 glyph, high bit on the left; characters without one are blank
+fn glyph(c: char) -> [u8; 7] {
+    match c {
+        '0' => [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+        '1' => [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        '2' => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+        '3' => [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+        '4' => [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+        '5' => [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+        '6' => [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+        '7' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+        '9' => [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+        ':' => [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00],
+        '-' => [0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00],
+        '.' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C],
+        '#' => [0x0A, 0x0A, 0x1F, 0x0A, 0x1F, 0x0A, 0x0A],
+        _ => [0; 7],
+    }
+}
+
+/// Draw `text` in white on a black box in the top left corner of `image`
+///
+/// The font is scaled with the image, so the label stays legible on large
+/// frames; whatever does not fit is cut off.
+pub fn burn_label(image: &mut RgbaImage, text: &str) {
+    let scale = (image.width() / 240).clamp(1, 4);
+    let pad = 2 * scale;
+    let advance = (GLYPH_WIDTH + 1) * scale;
+    let box_width = (pad * 2 + advance * text.chars().count() as u32).saturating_sub(scale);
+    let box_height = pad * 2 + GLYPH_HEIGHT * scale;
+    let (width, height) = image.dimensions();
+    for y in 0..box_height.min(height) {
+        for x in 0..box_width.min(width) {
+            image.put_pixel(x, y, Rgba([0, 0, 0, 255]));
+        }
+    }
+    for (i, c) in text.chars().enumerate() {
+        let left = pad + advance * i as u32;
+        for (row, bits) in glyph(c).iter().enumerate() {
+            for col in 0..GLYPH_WIDTH {
+                if bits & (1 << (GLYPH_WIDTH - 1 - col)) == 0 {
+                    continue;
+                }
+                let (x0, y0) = (left + col * scale, pad + row as u32 * scale);
+                for y in y0..(y0 + scale).min(height) {
+                    for x in x0..(x0 + scale).min(width) {
+                        image.put_pixel(x, y, Rgba([255, 255, 255, 255]));
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,191 @@
+/// System tray status and quick actions
+///
+/// The tray icon follows `runner://state`, so its tooltip and menu show whether
+/// a profile is running, paused or waiting for the user while the main window
+/// is closed. Its menu starts one of the recently run profiles and stops,
+/// pauses or resumes the current run through the same calls as the UI.
+use tauri::menu::{Menu, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::tray::TrayIconBuilder;
+
+use crate::domain::Profile;
+use crate::history::RunRecord;
+use crate::runner_state::RunnerStatus;
+
+pub const TRAY_ID: &str = "main";
+
+/// Profiles offered under "Start"
+pub const MAX_RECENT_PROFILES: usize = 5;
+
+/// A tray menu entry; its menu id round-trips through `id`/`parse`
+#[derive(Debug, Clone, PartialEq)]
+pub enum TrayCommand {
+    Start(String),
+    Stop,
+    Pause,
+    Resume,
+    ShowWindow,
+    Quit,
+}
+
+impl TrayCommand {
+    pub fn id(&self) -> String {
+        match self {
+            Self::Start(profile_id) => format!("start:{}", profile_id),
+            Self::Stop => "stop".into(),
+            Self::Pause => "pause".into(),
+            Self::Resume => "resume".into(),
+            Self::ShowWindow => "show".into(),
+            Self::Quit => "quit".into(),
+        }
+    }
+
+    pub fn parse(id: &str) -> Option<Self> {
+        if let Some(profile_id) = id.strip_prefix("start:") {
+            return Some(Self::Start(profile_id.to_string()));
+        }
+        Some(match id {
+            "stop" => Self::Stop,
+            "pause" => Self::Pause,
+            "resume" => Self::Resume,
+            "show" => Self::ShowWindow,
+            "quit" => Self::Quit,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrayItem {
+    pub command: TrayCommand,
+    pub label: String,
+    pub enabled: bool,
+}
+
+impl TrayItem {
+    fn new(command: TrayCommand, label: impl Into<String>, enabled: bool) -> Self {
+        Self {
+            command,
+            label: label.into(),
+            enabled,
+        }
+    }
+}
+
+/// Tooltip and menu of the tray icon for a status
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrayView {
+    pub tooltip: String,
+    /// First, disabled menu line
+    pub status: String,
+    /// Recently run profiles, most recent first
+    pub start: Vec<TrayItem>,
+    /// Stop, pause or resume, then window and quit
+    pub controls: Vec<TrayItem>,
+}
+
+impl TrayView {
+    /// `runs` are the recorded runs, newest first
+    pub fn new(status: &RunnerStatus, profiles: &[Profile], runs: &[RunRecord]) -> Self {
+        let name = |id: &str| {
+            profiles
+                .iter()
+                .find(|p| p.id == id)
+                .map_or_else(|| id.to_string(), |p| p.name.clone())
+        };
+        let status_line = match status {
+            RunnerStatus::Idle => "Idle".to_string(),
+            RunnerStatus::Running { profile_id } => format!("Running {}", name(profile_id)),
+            RunnerStatus::Paused { profile_id } => format!("Paused {}", name(profile_id)),
+            RunnerStatus::NeedsIntervention { profile_id, reason } => {
+                format!("{} needs you: {}", name(profile_id), reason)
+            }
+        };
+        let start = recent_profiles(profiles, runs)
+            .into_iter()
+            .map(|p| TrayItem::new(TrayCommand::Start(p.id.clone()), &p.name, status.profile_id() != Some(&p.id)))
+            .collect();
+        let active = status.profile_id().is_some();
+        let pause_or_resume = match status {
+            RunnerStatus::Paused { .. } => TrayItem::new(TrayCommand::Resume, "Resume", true),
+            _ => TrayItem::new(TrayCommand::Pause, "Pause", active),
+        };
+        Self {
+            tooltip: format!("loopautoma — {}", status_line),
+            status: status_line,
+            start,
+            controls: vec![
+                pause_or_resume,
+                TrayItem::new(TrayCommand::Stop, "Stop", active),
+                TrayItem::new(TrayCommand::ShowWindow, "Show window", true),
+                TrayItem::new(TrayCommand::Quit, "Quit", true),
+            ],
+        }
+    }
+}
+
+/// Profiles by their latest run, then the never-run ones in config order
+pub fn recent_profiles<'a>(profiles: &'a [Profile], runs: &[RunRecord]) -> Vec<&'a Profile> {
+    let mut recent: Vec<&Profile> = Vec::new();
+    let by_run = runs.iter().filter_map(|r| profiles.iter().find(|p| p.id == r.profile_id));
+    for p in by_run.chain(profiles) {
+        if recent.len() == MAX_RECENT_PROFILES {
+            break;
+        }
+        if !recent.iter().any(|q| q.id == p.id) {
+            recent.push(p);
+        }
+    }
+    recent
+}
+
+fn build_menu(app: &tauri::AppHandle, view: &TrayView) -> tauri::Result<Menu<tauri::Wry>> {
+    let item = |i: &TrayItem| MenuItem::with_id(app, i.command.id(), &i.label, i.enabled, None::<&str>);
+    let menu = Menu::new(app)?;
+    menu.append(&MenuItem::with_id(app, "status", &view.status, false, None::<&str>)?)?;
+    let start = Submenu::with_id(app, "start", "Start", !view.start.is_empty())?;
+    for i in &view.start {
+        start.append(&item(i)?)?;
+    }
+    menu.append(&start)?;
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+    for i in &view.controls {
+        if i.command == TrayCommand::ShowWindow {
+            menu.append(&PredefinedMenuItem::separator(app)?)?;
+        }
+        menu.append(&item(i)?)?;
+    }
+    Ok(menu)
+}
+
+/// Create the tray icon; `on_command` runs the menu entries
+pub fn install<F>(app: &tauri::AppHandle, view: &TrayView, on_command: F) -> Result<(), String>
+where
+    F: Fn(&tauri::AppHandle, TrayCommand) + Send + Sync + 'static,
+{
+    let menu = build_menu(app, view).map_err(|e| format!("Failed to build tray menu: {}", e))?;
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip(&view.tooltip)
+        .menu(&menu)
+        .on_menu_event(move |app, event| {
+            if let Some(command) = TrayCommand::parse(event.id().as_ref()) {
+                on_command(app, command);
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder
+        .build(app)
+        .map_err(|e| format!("Failed to create tray icon: {}", e))?;
+    Ok(())
+}
+
+/// Show a new view on the tray icon, if there is one
+pub fn update(app: &tauri::AppHandle, view: &TrayView) -> Result<(), String> {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return Ok(());
+    };
+    let menu = build_menu(app, view).map_err(|e| format!("Failed to build tray menu: {}", e))?;
+    tray.set_tooltip(Some(&view.tooltip)).map_err(|e| e.to_string())?;
+    tray.set_menu(Some(menu)).map_err(|e| e.to_string())
+}
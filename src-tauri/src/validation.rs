@@ -0,0 +1,317 @@
+/// Profile validation with actionable diagnostics
+///
+/// Errors describe problems that make a run fail or misbehave; warnings flag
+/// settings that are probably unintended. Each diagnostic names the offending
+/// field so the UI can point at it.
+use std::collections::HashSet;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::anchor;
+use crate::domain::{ActionConfig, DisplayInfo, Profile, Rect};
+use crate::expand;
+use crate::plugins;
+use crate::schedule;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// Path of the offending field, e.g. `actions[2].region_ids`
+    pub field: String,
+    pub message: String,
+}
+
+/// Key names accepted by `{Key:...}`; single characters are accepted as well
+pub const KNOWN_KEYS: &[&str] = &["Enter", "Escape", "Esc", "Tab", "Space", "Backspace"];
+
+const LLM_DEFAULT_VARIABLE: &str = "prompt";
+const ELEMENT_TEXT_DEFAULT_VARIABLE: &str = "element_text";
+const TERMINATION_CHECK_TYPES: &[&str] = &["context", "ocr", "ai_query"];
+
+pub fn is_known_key(key: &str) -> bool {
+    key.chars().count() == 1 || KNOWN_KEYS.iter().any(|k| k.eq_ignore_ascii_case(key))
+}
+
+struct Diagnostics(Vec<Diagnostic>);
+
+impl Diagnostics {
+    fn error(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        self.push(Severity::Error, field, message);
+    }
+    fn warning(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        self.push(Severity::Warning, field, message);
+    }
+    fn push(&mut self, severity: Severity, field: impl Into<String>, message: impl Into<String>) {
+        self.0.push(Diagnostic {
+            severity,
+            field: field.into(),
+            message: message.into(),
+        });
+    }
+}
+
+/// Check `profile` against the other saved profiles and the current display layout
+///
+/// `displays` may be empty when the layout is unknown; coordinates are then not checked.
+pub fn validate_profile(profile: &Profile, profiles: &[Profile], displays: &[DisplayInfo]) -> Vec<Diagnostic> {
+    let mut d = Diagnostics(Vec::new());
+
+    if profile.name.trim().is_empty() {
+        d.error("name", "Profile name is required");
+    }
+    if !profile.trigger.check_interval_sec.is_finite() || profile.trigger.check_interval_sec <= 0.0 {
+        d.error("trigger.check_interval_sec", "Trigger interval must be greater than 0 seconds");
+    }
+    if profile.condition.consecutive_checks == 0 {
+        d.error("condition.consecutive_checks", "Consecutive checks must be at least 1");
+    }
+
+    let mut region_ids = HashSet::new();
+    for (i, r) in profile.regions.iter().enumerate() {
+        let field = format!("regions[{}]", i);
+        if !region_ids.insert(r.id.as_str()) {
+            d.error(format!("{}.id", field), format!("Duplicate region id '{}'", r.id));
+        }
+        if r.rect.width == 0 || r.rect.height == 0 {
+            d.error(format!("{}.rect", field), format!("Region '{}' has zero width or height", r.id));
+        } else {
+            check_rect(&mut d, &format!("{}.rect", field), &r.rect, displays);
+        }
+        if let Some(a) = &r.anchor {
+            if let Err(e) = anchor::Template::decode(&a.template_png) {
+                d.error(format!("{}.anchor.template_png", field), e);
+            }
+            if !(a.threshold > 0.0 && a.threshold <= 1.0) {
+                d.error(format!("{}.anchor.threshold", field), "Anchor match threshold must be above 0 and at most 1");
+            } else if a.threshold < 0.5 {
+                d.warning(format!("{}.anchor.threshold", field), "Anchor thresholds below 0.5 match almost anything");
+            }
+            match &a.search {
+                Some(area) if area.width == 0 || area.height == 0 => {
+                    d.error(format!("{}.anchor.search", field), "Anchor search area has zero width or height");
+                }
+                Some(area) => check_rect(&mut d, &format!("{}.anchor.search", field), area, displays),
+                None => {}
+            }
+        }
+    }
+    let check_region_refs = |d: &mut Diagnostics, field: String, ids: &[String]| {
+        for id in ids.iter().filter(|id| !region_ids.contains(id.as_str())) {
+            d.error(field.clone(), format!("Unknown region '{}'; define it or remove the reference", id));
+        }
+    };
+
+    // Variables handed in by chained profiles count as defined from the start
+    let mut defined: HashSet<String> = profiles
+        .iter()
+        .flat_map(|p| [&p.on_complete, &p.on_failure])
+        .flatten()
+        .filter(|h| h.profile_id == profile.id)
+        .flat_map(|h| h.pass_variables.iter().cloned())
+        .collect();
+    let mut produced: HashSet<String> = HashSet::new();
+    for action in &profile.actions {
+        match action {
+            ActionConfig::LLMPromptGeneration { variable_name, .. } => {
+                produced.insert(variable_name.clone().unwrap_or_else(|| LLM_DEFAULT_VARIABLE.into()));
+            }
+            ActionConfig::ReadElementText { variable_name, .. } => {
+                produced.insert(variable_name.clone().unwrap_or_else(|| ELEMENT_TEXT_DEFAULT_VARIABLE.into()));
+            }
+            _ => {}
+        }
+    }
+
+    let var_re = Regex::new(r"\$([A-Za-z_][A-Za-z0-9_]*)").expect("valid regex");
+    for (i, action) in profile.actions.iter().enumerate() {
+        let field = format!("actions[{}]", i);
+        match action {
+            ActionConfig::Click { x, y, .. } => {
+                let point = Rect { x: *x, y: *y, width: 1, height: 1 };
+                if !displays.is_empty() && !inside_any(&point, displays) {
+                    d.error(format!("{}.x", field), format!("Click at ({}, {}) is outside every display", x, y));
+                }
+            }
+            ActionConfig::Type { text } => {
+                let trimmed = text.trim();
+                if let Some(key) = trimmed.strip_prefix("{Key:").and_then(|k| k.strip_suffix('}')) {
+                    if !is_known_key(key) {
+                        d.error(
+                            format!("{}.text", field),
+                            format!("Unknown key '{}'; use {} or a single character", key, KNOWN_KEYS.join(", ")),
+                        );
+                    }
+                } else if text.contains("{Key:") {
+                    d.warning(
+                        format!("{}.text", field),
+                        "Inline {Key:...} is typed literally; put the key in its own Type action",
+                    );
+                }
+                for name in var_re.captures_iter(text).map(|c| c[1].to_string()) {
+                    if defined.contains(&name) {
+                        continue;
+                    }
+                    if produced.contains(&name) {
+                        d.warning(
+                            format!("{}.text", field),
+                            format!("${} is only set by a later action; the first iteration types it literally", name),
+                        );
+                    } else {
+                        d.error(
+                            format!("{}.text", field),
+                            format!("${} is never set; add an LLM action with variable_name '{}' or pass it from a chained profile", name, name),
+                        );
+                    }
+                }
+            }
+            ActionConfig::ClickElement { element, .. } => {
+                if let Err(e) = element.validate() {
+                    d.error(format!("{}.element", field), e);
+                }
+            }
+            ActionConfig::ReadElementText { element, variable_name } => {
+                if let Err(e) = element.validate() {
+                    d.error(format!("{}.element", field), e);
+                }
+                defined.insert(variable_name.clone().unwrap_or_else(|| ELEMENT_TEXT_DEFAULT_VARIABLE.into()));
+            }
+            ActionConfig::Plugin { plugin, kind, .. } => {
+                if let Err(e) = plugins::registry().check_action(plugin, kind) {
+                    d.error(format!("{}.kind", field), e);
+                }
+            }
+            ActionConfig::LLMPromptGeneration {
+                region_ids,
+                risk_threshold,
+                variable_name,
+                ..
+            } => {
+                if region_ids.is_empty() {
+                    d.warning(format!("{}.region_ids", field), "LLM action captures no regions");
+                }
+                check_region_refs(&mut d, format!("{}.region_ids", field), region_ids);
+                if !(0.0..=1.0).contains(risk_threshold) {
+                    d.error(
+                        format!("{}.risk_threshold", field),
+                        format!("Risk threshold {} is outside 0.0–1.0", risk_threshold),
+                    );
+                }
+                defined.insert(variable_name.clone().unwrap_or_else(|| LLM_DEFAULT_VARIABLE.into()));
+            }
+            ActionConfig::TerminationCheck {
+                check_type,
+                context_vars,
+                ocr_region_ids,
+                ..
+            } => {
+                if !TERMINATION_CHECK_TYPES.contains(&check_type.as_str()) {
+                    d.error(
+                        format!("{}.check_type", field),
+                        format!("Unknown check type '{}'; use {}", check_type, TERMINATION_CHECK_TYPES.join(", ")),
+                    );
+                }
+                check_region_refs(&mut d, format!("{}.ocr_region_ids", field), ocr_region_ids);
+                for name in context_vars.iter().filter(|v| !defined.contains(*v) && !produced.contains(*v)) {
+                    d.warning(format!("{}.context_vars", field), format!("Context variable '{}' is never set", name));
+                }
+            }
+        }
+    }
+
+    if let Some(g) = &profile.guardrails {
+        check_region_refs(&mut d, "guardrails.ocr_region_ids".into(), &g.ocr_region_ids);
+    }
+
+    if let Some(t) = &profile.trigger.plugin {
+        if let Err(e) = plugins::registry().check_trigger(&t.plugin, &t.kind) {
+            d.error("trigger.plugin", e);
+        }
+    }
+
+    for (field, value) in expand::string_fields_mut(&mut profile.clone()) {
+        for name in expand::env_references(value) {
+            if !profile.env_allowlist.contains(&name) {
+                d.error(
+                    field.clone(),
+                    format!("${{{}}} is not allowed; add {} to env_allowlist", name, name),
+                );
+            }
+        }
+        let typed = field.starts_with("actions[") && field.ends_with("].text");
+        for name in expand::secret_references(value) {
+            if !typed {
+                d.error(
+                    field.clone(),
+                    format!("{{{{secret:{}}}}} is only filled in Type actions, never in prompts or guardrails", name),
+                );
+            }
+        }
+    }
+
+    for (i, w) in profile.run_windows.iter().enumerate() {
+        for (key, value) in [("start", &w.start), ("end", &w.end)] {
+            if let Err(e) = schedule::parse_time(value) {
+                d.error(format!("run_windows[{}].{}", i, key), e);
+            }
+        }
+    }
+
+    if let Some(archive) = &profile.frame_archive {
+        for (key, value) in [("max_size", archive.max_size), ("max_frames", archive.max_frames)] {
+            if value == 0 {
+                d.error(format!("frame_archive.{}", key), "Must be at least 1");
+            }
+        }
+    }
+    if profile.privacy.as_ref().is_some_and(|p| p.detectors.is_empty()) {
+        d.warning("privacy.detectors", "Privacy filter has no detectors and masks nothing");
+    }
+
+    for (key, handoff) in [("on_complete", &profile.on_complete), ("on_failure", &profile.on_failure)] {
+        if let Some(h) = handoff {
+            if !profiles.iter().any(|p| p.id == h.profile_id) {
+                d.error(format!("{}.profile_id", key), format!("Follow-up profile '{}' does not exist", h.profile_id));
+            }
+        }
+    }
+
+    d.0
+}
+
+fn check_rect(d: &mut Diagnostics, field: &str, rect: &Rect, displays: &[DisplayInfo]) {
+    if displays.is_empty() {
+        return;
+    }
+    let corner = |x: u32, y: u32| Rect { x, y, width: 1, height: 1 };
+    let top_left = inside_any(&corner(rect.x, rect.y), displays);
+    let bottom_right = inside_any(
+        &corner(
+            rect.x.saturating_add(rect.width - 1),
+            rect.y.saturating_add(rect.height - 1),
+        ),
+        displays,
+    );
+    match (top_left, bottom_right) {
+        (true, true) => {}
+        (false, false) => d.error(field, "Region lies outside every display"),
+        _ => d.warning(field, "Region extends beyond the display edge"),
+    }
+}
+
+fn inside_any(rect: &Rect, displays: &[DisplayInfo]) -> bool {
+    displays.iter().any(|disp| {
+        let (x, y) = (rect.x as i64, rect.y as i64);
+        x >= disp.x as i64
+            && y >= disp.y as i64
+            && x + rect.width as i64 <= disp.x as i64 + disp.width as i64
+            && y + rect.height as i64 <= disp.y as i64 + disp.height as i64
+    })
+}
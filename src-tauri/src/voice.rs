@@ -0,0 +1,273 @@
+/// Voice commands: start and stop profiles by saying a phrase
+///
+/// A profile's `voice` names the phrases that start and stop it, said after the
+/// wake word: with the default wake word, "loopautoma, continue" starts a
+/// profile whose start phrase is "continue". When that profile is already
+/// running and paused, e.g. waiting for someone to step in, its start phrase
+/// resumes it instead.
+///
+/// Speech is recognized locally with Vosk from the default microphone. The
+/// recognizer only knows the configured phrases (anything else is heard as
+/// unknown), so conversation nearby does not start runs and no audio leaves
+/// the machine. Vosk cannot learn words: the wake word and every phrase must
+/// be in the vocabulary of the model, which `listen` checks.
+///
+/// Listening needs the `voice-commands` feature, which links libvosk, and a
+/// model directory set in the settings (`voice.commands`).
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::Profile;
+
+pub const DEFAULT_WAKE_WORD: &str = "loopautoma";
+
+/// Where the speech model is and what to say before a phrase
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VoiceConfig {
+    /// Directory of an unpacked Vosk model, e.g. vosk-model-small-en-us-0.15
+    pub model_path: String,
+    #[serde(default = "VoiceConfig::default_wake_word")]
+    pub wake_word: String,
+}
+
+impl VoiceConfig {
+    fn default_wake_word() -> String {
+        DEFAULT_WAKE_WORD.to_string()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.model_path.trim().is_empty() {
+            return Err("Voice commands need the directory of a Vosk model".into());
+        }
+        if normalize(&self.wake_word).is_empty() {
+            return Err("The wake word must contain a word".into());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VoiceAction {
+    Start,
+    Stop,
+}
+
+/// A phrase and what it does
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VoiceCommand {
+    /// Normalized, without the wake word
+    pub phrase: String,
+    pub profile_id: String,
+    pub action: VoiceAction,
+}
+
+/// Lowercase words separated by single spaces, without punctuation
+pub fn normalize(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !(c.is_alphanumeric() || c == '\''))
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The phrases of `profiles`, rejecting a phrase used twice
+pub fn voice_commands(profiles: &[Profile]) -> Result<Vec<VoiceCommand>, String> {
+    let mut commands = Vec::new();
+    let mut owners: HashMap<String, &str> = HashMap::new();
+    for p in profiles {
+        let Some(voice) = &p.voice else { continue };
+        for (action, phrase) in [(VoiceAction::Start, &voice.start), (VoiceAction::Stop, &voice.stop)] {
+            let Some(phrase) = phrase.as_deref().filter(|s| !s.trim().is_empty()) else {
+                continue;
+            };
+            let normalized = normalize(phrase);
+            if normalized.is_empty() {
+                return Err(format!("Profile '{}' has a voice phrase without words: '{}'", p.name, phrase));
+            }
+            if let Some(other) = owners.insert(normalized.clone(), &p.name) {
+                return Err(format!("Voice phrase '{}' is used by both '{}' and '{}'", normalized, other, p.name));
+            }
+            commands.push(VoiceCommand {
+                phrase: normalized,
+                profile_id: p.id.clone(),
+                action,
+            });
+        }
+    }
+    Ok(commands)
+}
+
+/// What the recognizer listens for: each phrase after the wake word
+#[cfg_attr(not(feature = "voice-commands"), allow(dead_code))]
+pub fn grammar(wake_word: &str, commands: &[VoiceCommand]) -> Vec<String> {
+    let wake_word = normalize(wake_word);
+    commands.iter().map(|c| format!("{} {}", wake_word, c.phrase)).collect()
+}
+
+/// The command `heard` asks for, if it is the wake word and a known phrase
+#[cfg_attr(not(feature = "voice-commands"), allow(dead_code))]
+pub fn recognize<'a>(heard: &str, wake_word: &str, commands: &'a [VoiceCommand]) -> Option<&'a VoiceCommand> {
+    let heard = normalize(heard);
+    let phrase = heard.strip_prefix(&normalize(wake_word))?.strip_prefix(' ')?;
+    commands.iter().find(|c| c.phrase == phrase)
+}
+
+/// The microphone listener; listening stops when it is dropped
+pub struct VoiceListener {
+    #[cfg(feature = "voice-commands")]
+    stop: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    #[cfg(feature = "voice-commands")]
+    thread: Option<std::thread::JoinHandle<()>>,
+    /// What is listened for, wake word included
+    pub phrases: Vec<String>,
+}
+
+#[cfg(feature = "voice-commands")]
+impl Drop for VoiceListener {
+    fn drop(&mut self) {
+        self.stop.store(true, std::sync::atomic::Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Listen on the default microphone from a background thread, calling
+/// `on_command` for every command heard
+#[cfg(feature = "voice-commands")]
+pub fn listen<F>(config: &VoiceConfig, commands: Vec<VoiceCommand>, on_command: F) -> Result<VoiceListener, String>
+where
+    F: Fn(&VoiceCommand) + Send + 'static,
+{
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{mpsc, Arc};
+    use std::time::Duration;
+
+    config.validate()?;
+    vosk::set_log_level(vosk::LogLevel::Error);
+    let mut model = vosk::Model::new(config.model_path.as_str())
+        .ok_or_else(|| format!("No Vosk model could be loaded from {}", config.model_path))?;
+    let phrases = grammar(&config.wake_word, &commands);
+    for phrase in &phrases {
+        if let Some(word) = phrase.split(' ').find(|w| model.find_word(w).is_none()) {
+            return Err(format!("'{}' is not in the vocabulary of the Vosk model; choose words it knows", word));
+        }
+    }
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let (ready_tx, ready_rx) = mpsc::channel();
+    let thread = {
+        let stop = stop.clone();
+        let wake_word = config.wake_word.clone();
+        let mut grammar = phrases.clone();
+        grammar.push("[unk]".to_string());
+        std::thread::spawn(move || {
+            // The stream is not Send on every platform, so it lives on this thread
+            let (tx, rx) = mpsc::channel::<Vec<i16>>();
+            let started = open_microphone(tx).and_then(|(stream, sample_rate)| {
+                let recognizer = vosk::Recognizer::new_with_grammar(&model, sample_rate, &grammar)
+                    .ok_or_else(|| "Vosk could not create a recognizer for the model".to_string())?;
+                Ok((stream, recognizer))
+            });
+            let (_stream, mut recognizer) = match started {
+                Ok(started) => {
+                    let _ = ready_tx.send(Ok(()));
+                    started
+                }
+                Err(e) => {
+                    let _ = ready_tx.send(Err(e));
+                    return;
+                }
+            };
+            while !stop.load(Ordering::Relaxed) {
+                let samples = match rx.recv_timeout(Duration::from_millis(200)) {
+                    Ok(samples) => samples,
+                    Err(mpsc::RecvTimeoutError::Timeout) => continue,
+                    Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                };
+                if !matches!(recognizer.accept_waveform(&samples), Ok(vosk::DecodingState::Finalized)) {
+                    continue;
+                }
+                let Some(heard) = recognizer.result().single().map(|r| r.text.to_string()) else {
+                    continue;
+                };
+                if let Some(command) = recognize(&heard, &wake_word, &commands) {
+                    tracing::info!("Heard voice command '{}'", heard);
+                    on_command(command);
+                }
+            }
+        })
+    };
+    ready_rx
+        .recv()
+        .map_err(|_| "The voice listener stopped while starting".to_string())??;
+    Ok(VoiceListener {
+        stop,
+        thread: Some(thread),
+        phrases,
+    })
+}
+
+/// Capture the default input device as mono 16-bit samples into `tx`; returns the stream and its sample rate
+#[cfg(feature = "voice-commands")]
+fn open_microphone(tx: std::sync::mpsc::Sender<Vec<i16>>) -> Result<(cpal::Stream, f32), String> {
+    use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+
+    let device = cpal::default_host()
+        .default_input_device()
+        .ok_or_else(|| "No microphone found".to_string())?;
+    let supported = device
+        .default_input_config()
+        .map_err(|e| format!("Microphone unavailable: {}", e))?;
+    let channels = supported.channels() as usize;
+    let config = supported.config();
+    let on_error = |e: cpal::StreamError| tracing::warn!("Microphone stream error: {}", e);
+    let stream = match supported.sample_format() {
+        cpal::SampleFormat::F32 => device.build_input_stream(
+            &config,
+            move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                let _ = tx.send(downmix(data, channels, |s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16));
+            },
+            on_error,
+            None,
+        ),
+        cpal::SampleFormat::I16 => device.build_input_stream(
+            &config,
+            move |data: &[i16], _: &cpal::InputCallbackInfo| {
+                let _ = tx.send(downmix(data, channels, |s| s));
+            },
+            on_error,
+            None,
+        ),
+        cpal::SampleFormat::U16 => device.build_input_stream(
+            &config,
+            move |data: &[u16], _: &cpal::InputCallbackInfo| {
+                let _ = tx.send(downmix(data, channels, |s| (s as i32 - 32768) as i16));
+            },
+            on_error,
+            None,
+        ),
+        other => return Err(format!("Unsupported microphone sample format {:?}", other)),
+    }
+    .map_err(|e| format!("Microphone unavailable: {}", e))?;
+    stream.play().map_err(|e| format!("Microphone unavailable: {}", e))?;
+    Ok((stream, config.sample_rate.0 as f32))
+}
+
+/// Average the channels of interleaved `data` into mono 16-bit samples
+#[cfg(feature = "voice-commands")]
+fn downmix<T: Copy>(data: &[T], channels: usize, to_i16: impl Fn(T) -> i16) -> Vec<i16> {
+    data.chunks(channels.max(1))
+        .map(|frame| (frame.iter().map(|&s| to_i16(s) as i32).sum::<i32>() / frame.len() as i32) as i16)
+        .collect()
+}
+
+#[cfg(not(feature = "voice-commands"))]
+pub fn listen<F>(_config: &VoiceConfig, _commands: Vec<VoiceCommand>, _on_command: F) -> Result<VoiceListener, String>
+where
+    F: Fn(&VoiceCommand) + Send + 'static,
+{
+    Err("This build has no voice commands (feature 'voice-commands')".into())
+}
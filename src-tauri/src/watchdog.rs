@@ -0,0 +1,169 @@
+/// Supervised action execution: hung-action watchdog and cancellation
+///
+/// The heartbeat guardrail is checked between ticks, so it cannot catch an
+/// action that never returns: a `type_text` stuck on a grabbed keyboard or an
+/// LLM call on a dead connection freezes the tick it runs in. Pausing and
+/// stopping are applied between ticks too. So the runner attaches an
+/// `ActionWatchdog` to the action sequence. Each action then runs on a worker
+/// thread with its own copy of the context, and the sequence waits for
+/// whichever comes first: the action returns, `action_timeout` passes, or the
+/// context's `cancel` token is cancelled.
+///
+/// An action that overruns fails with `Error::Timeout`. Each worker gets a
+/// child of the context's token, and the watchdog cancels it when it gives up
+/// on the worker: the thread cannot be killed and runs until the action
+/// returns, but its input is refused from then on and whatever it did to the
+/// context is dropped. The sequence reports `WatchdogTripped { reason: "action_timeout" }`, and the
+/// monitor holds the run in `MonitorState::ActionTimedOut`, which asks for the
+/// user's intervention. Resuming carries on with the next trigger; stopping
+/// releases any input the abandoned action left held.
+///
+/// Stopping a run cancels the token, and pausing cancels the one handed out by
+/// `ActionInterrupt`. Actions that wait check the token and return early; the
+/// worker gets `CANCEL_GRACE` to do so before it is abandoned, and the action
+/// fails with `Error::Cancelled`. Whatever makes the watchdog give up on a
+/// worker, a timeout, a cancellation or a panic, its token is cancelled. The
+/// worker's input goes through a `CancellableAutomation` on that token, so it
+/// cannot press anything once abandoned, and the runner releases what it left
+/// held.
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::domain::{Action, ActionContext, Automation, Error};
+use crate::shutdown::{CancellableAutomation, ShutdownToken};
+
+/// `WatchdogTripped` reason for an action that overran its deadline
+pub const ACTION_TIMEOUT: &str = "action_timeout";
+
+/// How long a cancelled action may take to return before it is abandoned
+pub const CANCEL_GRACE: Duration = Duration::from_millis(500);
+
+/// Longest the sequence takes to notice a cancellation
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Runs actions on supervised workers, with an optional per-action deadline
+pub struct ActionWatchdog {
+    pub timeout: Option<Duration>,
+    /// What the workers send input through; the runner's own automation
+    automation: Arc<dyn Automation + Send + Sync>,
+}
+
+impl ActionWatchdog {
+    pub fn new(timeout: Option<Duration>, automation: Arc<dyn Automation + Send + Sync>) -> Self {
+        Self { timeout, automation }
+    }
+
+    /// Run `action` on a worker until it returns, times out or is cancelled
+    ///
+    /// The context is updated only if the action returns in time.
+    pub fn run(&self, action: Arc<dyn Action + Send + Sync>, context: &mut ActionContext) -> Result<(), Error> {
+        let name = action.name();
+        let (tx, rx) = mpsc::channel();
+        let automation = self.automation.clone();
+        let mut worker_context = context.clone();
+        // Cancelled with the run or a pause, and by the watchdog when it abandons the worker
+        let abandon = Abandon(Some(context.cancel.child()));
+        worker_context.cancel = abandon.token();
+        // Records the worker writes still belong to the run, iteration and action
+        let span = tracing::Span::current();
+        std::thread::Builder::new()
+            .name(format!("loopautoma-action-{}", name))
+            .spawn(move || {
+                let _span = span.entered();
+                let cancel = worker_context.cancel.clone();
+                let result = action.execute(&CancellableAutomation::new(&*automation, &cancel), &mut worker_context);
+                // The receiver is gone if the watchdog gave up on this action
+                let _ = tx.send((result, worker_context));
+            })
+            .map_err(|e| Error::Action(format!("Failed to start a worker for {}: {}", name, e)))?;
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+        loop {
+            let wait = match deadline {
+                Some(deadline) => deadline.saturating_duration_since(Instant::now()).min(POLL_INTERVAL),
+                None => POLL_INTERVAL,
+            };
+            match rx.recv_timeout(wait) {
+                Ok((result, updated)) => {
+                    abandon.disarm();
+                    // Keep the sequence's token, not the worker's child of it
+                    let cancel = context.cancel.clone();
+                    *context = updated;
+                    context.cancel = cancel;
+                    return result;
+                }
+                Err(RecvTimeoutError::Disconnected) => return Err(Error::Action(format!("{} panicked", name))),
+                Err(RecvTimeoutError::Timeout) => {}
+            }
+            if context.cancel.is_cancelled() {
+                // Give the action the chance to return on its own; its changes are dropped either way
+                let returned = rx.recv_timeout(CANCEL_GRACE).is_ok();
+                return Err(Error::Cancelled(if returned {
+                    format!("{} was cancelled", name)
+                } else {
+                    format!("{} was cancelled and abandoned", name)
+                }));
+            }
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                return Err(Error::Timeout(format!(
+                    "{} did not finish within {} ms",
+                    name,
+                    self.timeout.unwrap_or_default().as_millis()
+                )));
+            }
+        }
+    }
+}
+
+/// The worker's token, cancelled on drop unless the worker returned
+struct Abandon(Option<ShutdownToken>);
+
+impl Abandon {
+    fn token(&self) -> ShutdownToken {
+        self.0.clone().unwrap_or_default()
+    }
+
+    fn disarm(mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for Abandon {
+    fn drop(&mut self) {
+        if let Some(token) = self.0.take() {
+            token.cancel();
+        }
+    }
+}
+
+/// Hands out the tokens a pause cancels, one per tick
+///
+/// A pause may land while an action runs; `interrupt` cancels the token that
+/// action was given. The next `token` after that is a fresh one.
+#[derive(Clone)]
+pub struct ActionInterrupt {
+    /// The run's own token: stopping the run cancels every token handed out
+    run: ShutdownToken,
+    current: Arc<Mutex<ShutdownToken>>,
+}
+
+impl ActionInterrupt {
+    pub fn new(run: ShutdownToken) -> Self {
+        let current = Arc::new(Mutex::new(run.child()));
+        Self { run, current }
+    }
+
+    /// Cancel the token of the action in flight, if any
+    pub fn interrupt(&self) {
+        self.current.lock().unwrap().cancel();
+    }
+
+    /// The token for the next actions; cancelled by `interrupt` or with the run
+    pub fn token(&self) -> ShutdownToken {
+        let mut current = self.current.lock().unwrap();
+        if current.is_cancelled() {
+            *current = self.run.child();
+        }
+        current.clone()
+    }
+}
@@ -0,0 +1,203 @@
+/// Outbound webhook notifications to Slack, Discord and Telegram
+///
+/// Webhook URLs and bot tokens are credentials, so targets live in secure storage
+/// rather than in profiles. Requests are built here as plain bytes and delivered
+/// as tasks on the shared async runtime, so a slow or unreachable endpoint never
+/// holds up a run.
+/// Slack incoming webhooks cannot upload files; screenshots go to Discord and
+/// Telegram only.
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::runtime::AsyncRuntime;
+
+/// Telegram rejects photo captions longer than this
+const TELEGRAM_CAPTION_LIMIT: usize = 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum WebhookTarget {
+    /// Slack incoming webhook URL
+    Slack { url: String },
+    /// Discord channel webhook URL
+    Discord { url: String },
+    /// Bot token from @BotFather and the chat to post into
+    Telegram { token: String, chat_id: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    pub target: WebhookTarget,
+    /// Attach a screenshot of the primary display (Discord and Telegram)
+    #[serde(default)]
+    pub include_screenshot: bool,
+}
+
+impl WebhookConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        match &self.target {
+            WebhookTarget::Slack { url } | WebhookTarget::Discord { url } => {
+                if !url.starts_with("https://") {
+                    return Err(format!("Webhook URL must use https://, got '{}'", url));
+                }
+            }
+            WebhookTarget::Telegram { token, chat_id } => {
+                if token.trim().is_empty() || chat_id.trim().is_empty() {
+                    return Err("Telegram needs a bot token and a chat id".into());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether this target wants a screenshot and can deliver one
+    pub fn wants_screenshot(&self) -> bool {
+        self.include_screenshot && !matches!(self.target, WebhookTarget::Slack { .. })
+    }
+}
+
+/// A ready-to-send HTTP POST
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebhookRequest {
+    pub url: String,
+    pub content_type: String,
+    pub body: Vec<u8>,
+}
+
+impl WebhookRequest {
+    fn json(url: String, body: serde_json::Value) -> Self {
+        Self {
+            url,
+            content_type: "application/json".into(),
+            body: body.to_string().into_bytes(),
+        }
+    }
+}
+
+/// Build the request that posts `text` (and `screenshot`, a PNG, where supported) to `config`
+pub fn build_request(config: &WebhookConfig, text: &str, screenshot: Option<&[u8]>, boundary: &str) -> WebhookRequest {
+    let screenshot = screenshot.filter(|_| config.wants_screenshot());
+    match (&config.target, screenshot) {
+        (WebhookTarget::Slack { url }, _) => WebhookRequest::json(url.clone(), json!({ "text": text })),
+        (WebhookTarget::Discord { url }, None) => WebhookRequest::json(url.clone(), json!({ "content": text })),
+        (WebhookTarget::Discord { url }, Some(png)) => {
+            let payload = json!({ "content": text }).to_string();
+            multipart(
+                url.clone(),
+                boundary,
+                &[
+                    Part::text("payload_json", &payload),
+                    Part::file("files[0]", "screenshot.png", "image/png", png),
+                ],
+            )
+        }
+        (WebhookTarget::Telegram { token, chat_id }, None) => WebhookRequest::json(
+            format!("https://api.telegram.org/bot{}/sendMessage", token),
+            json!({ "chat_id": chat_id, "text": text }),
+        ),
+        (WebhookTarget::Telegram { token, chat_id }, Some(png)) => {
+            let caption: String = text.chars().take(TELEGRAM_CAPTION_LIMIT).collect();
+            multipart(
+                format!("https://api.telegram.org/bot{}/sendPhoto", token),
+                boundary,
+                &[
+                    Part::text("chat_id", chat_id),
+                    Part::text("caption", &caption),
+                    Part::file("photo", "screenshot.png", "image/png", png),
+                ],
+            )
+        }
+    }
+}
+
+struct Part<'a> {
+    name: &'a str,
+    file: Option<(&'a str, &'a str)>,
+    data: &'a [u8],
+}
+
+impl<'a> Part<'a> {
+    fn text(name: &'a str, value: &'a str) -> Self {
+        Self { name, file: None, data: value.as_bytes() }
+    }
+
+    fn file(name: &'a str, filename: &'a str, content_type: &'a str, data: &'a [u8]) -> Self {
+        Self {
+            name,
+            file: Some((filename, content_type)),
+            data,
+        }
+    }
+}
+
+/// `multipart/form-data` body; only reqwest's JSON feature is enabled, so it is assembled by hand
+fn multipart(url: String, boundary: &str, parts: &[Part]) -> WebhookRequest {
+    let mut body = Vec::new();
+    for part in parts {
+        body.extend_from_slice(format!("--{}\r\n", boundary).as_bytes());
+        match part.file {
+            Some((filename, content_type)) => body.extend_from_slice(
+                format!(
+                    "Content-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\nContent-Type: {}\r\n\r\n",
+                    part.name, filename, content_type
+                )
+                .as_bytes(),
+            ),
+            None => body.extend_from_slice(
+                format!("Content-Disposition: form-data; name=\"{}\"\r\n\r\n", part.name).as_bytes(),
+            ),
+        }
+        body.extend_from_slice(part.data);
+        body.extend_from_slice(b"\r\n");
+    }
+    body.extend_from_slice(format!("--{}--\r\n", boundary).as_bytes());
+    WebhookRequest {
+        url,
+        content_type: format!("multipart/form-data; boundary={}", boundary),
+        body,
+    }
+}
+
+/// Boundary that will not occur in a PNG or message by accident
+pub fn new_boundary() -> String {
+    format!("loopautoma-{:032x}", rand_u128())
+}
+
+fn rand_u128() -> u128 {
+    use std::hash::{BuildHasher, Hasher};
+    let state = std::collections::hash_map::RandomState::new();
+    let (mut a, mut b) = (state.build_hasher(), state.build_hasher());
+    a.write_u8(0);
+    b.write_u8(1);
+    ((a.finish() as u128) << 64) | b.finish() as u128
+}
+
+/// Send and wait for the endpoint's answer
+#[cfg(feature = "webhook-notifications")]
+pub fn send(runtime: &AsyncRuntime, request: &WebhookRequest) -> Result<(), String> {
+    runtime.block_on(deliver(request))
+}
+
+#[cfg(feature = "webhook-notifications")]
+async fn deliver(request: &WebhookRequest) -> Result<(), String> {
+    let response = reqwest::Client::new()
+        .post(&request.url)
+        .header(reqwest::header::CONTENT_TYPE, &request.content_type)
+        .body(request.body.clone())
+        .send()
+        .await
+        .map_err(|e| format!("Webhook request failed: {}", e.without_url()))?;
+    let status = response.status();
+    if status.is_success() {
+        Ok(())
+    } else {
+        let detail = response.text().await.unwrap_or_default();
+        Err(format!("Webhook rejected with {}: {}", status, detail.trim()))
+    }
+}
+
+#[cfg(not(feature = "webhook-notifications"))]
+pub fn send(_runtime: &AsyncRuntime, _request: &WebhookRequest) -> Result<(), String> {
+    Err("This build cannot send webhook notifications".into())
+}
+
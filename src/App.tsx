@@ -3,6 +3,9 @@ import reactLogo from "./assets/react.svg";
 import "./App.css";
 import { ProfileSelector } from "./components/ProfileSelector";
 import { EventLog } from "./components/EventLog";
+import { ActivityFeed } from "./components/ActivityFeed";
+import { RunHistoryPanel } from "./components/RunHistoryPanel";
+import { ProfileStats } from "./components/ProfileStats";
 import { ProfileEditor } from "./components/ProfileEditor";
 import { RecordingBar, toActions } from "./components/RecordingBar";
 import { RegionAuthoringPanel } from "./components/RegionAuthoringPanel";
@@ -13,13 +16,24 @@ import { SettingsPanel } from "./components/SettingsPanel";
 import { SettingsIcon } from "./components/Icons";
 
 import { useEventStream, useProfiles, useRunState } from "./store";
-import { normalizeProfilesConfig, Profile, ProfilesConfig } from "./types";
-import { monitorStart, monitorStop, profilesLoad, profilesSave, appQuit } from "./tauriBridge";
+import { subscribeEvent } from "./eventBridge";
+import { normalizeProfilesConfig, NotificationClick, Profile, ProfilesConfig, RunCheckpoint } from "./types";
+import { monitorStart, monitorStop, pauseProfile, resumeProfile, debugStep, debugContinue, lastInterruptedRun, resumeLastRun, discardLastRun, profilesLoad, profilesSave, appQuit } from "./tauriBridge";
 import logo from "../doc/img/logo.png";
 import { useEffectOnce } from "./hooks/useEffectOnce";
 import { registerBuiltins } from "./plugins/builtins";
 import { isDesktopEnvironment } from "./utils/runtime";
 import { AcceleratingNumberInput } from "./components/AcceleratingNumberInput";
+import { ProfileChainingEditor } from "./components/ProfileChainingEditor";
+import { ProfileNotificationsEditor } from "./components/ProfileNotificationsEditor";
+import { ProfileRunWindowsEditor } from "./components/ProfileRunWindowsEditor";
+import { ProfileFrameArchiveEditor } from "./components/ProfileFrameArchiveEditor";
+import { ProfilePrivacyEditor } from "./components/ProfilePrivacyEditor";
+import { ProfileVoiceEditor } from "./components/ProfileVoiceEditor";
+import { ProfileTransfer } from "./components/ProfileTransfer";
+import { ProfileSecrets } from "./components/ProfileSecrets";
+import { ProfileTemplates } from "./components/ProfileTemplates";
+import { ProfileDiagnostics } from "./components/ProfileDiagnostics";
 import { getCurrentWindow } from "@tauri-apps/api/window";
 
 const THEME_STORAGE_KEY = "loopautoma.theme";
@@ -66,7 +80,7 @@ export function App() {
 function MainWindow() {
   const { config, setConfig } = useProfiles();
   const { events, clear } = useEventStream();
-  const { runningProfileId, setRunningProfileId } = useRunState();
+  const { runningProfileId, setRunningProfileId, paused, outsideRunWindow, actionTimedOut } = useRunState();
   const [selectedId, setSelectedId] = useState<string | null>(null);
   const [showGraph, setShowGraph] = useState(true);
   const [theme, setTheme] = useState<ThemeChoice>("dark");
@@ -170,19 +184,63 @@ function MainWindow() {
     };
   }, [applyConfig, setConfig]);
 
+  const [debugMode, setDebugMode] = useState(false);
+
   const start = useCallback(async () => {
     if (!selectedId) return;
-    await monitorStart(selectedId);
+    await monitorStart(selectedId, debugMode);
     setRunningProfileId(selectedId);
-  }, [selectedId, setRunningProfileId]);
+  }, [selectedId, setRunningProfileId, debugMode]);
+
+  // The backend has already persisted the imported profile; refresh the cache and select it
+  const reloadAfterImport = useCallback(async (imported: Profile) => {
+    setConfig(normalizeProfilesConfig(await profilesLoad()));
+    setSelectedId(imported.id);
+  }, [setConfig]);
+
+  // A checkpoint left behind by a crash or reboot; offered for resumption once at startup
+  const [interruptedRun, setInterruptedRun] = useState<RunCheckpoint | null>(null);
+  useEffect(() => {
+    lastInterruptedRun().then(setInterruptedRun).catch(() => setInterruptedRun(null));
+  }, []);
+
+  // Clicking a desktop notification focuses the window; show the profile of that run
+  useEffect(() => {
+    let dispose: (() => void) | undefined;
+    subscribeEvent<NotificationClick>("loopautoma://notification_clicked", (click) => {
+      if (click?.profile_id) setSelectedId(click.profile_id);
+    }).then((off) => (dispose = off));
+    return () => dispose?.();
+  }, []);
+
+  const resumeInterrupted = useCallback(async () => {
+    const profileId = await resumeLastRun();
+    setInterruptedRun(null);
+    setSelectedId(profileId);
+    setRunningProfileId(profileId);
+  }, [setRunningProfileId]);
+
+  const discardInterrupted = useCallback(async () => {
+    await discardLastRun();
+    setInterruptedRun(null);
+  }, []);
 
   const stop = useCallback(async () => {
     await monitorStop();
     setRunningProfileId(null);
   }, [setRunningProfileId]);
 
+  const togglePause = useCallback(async () => {
+    await (paused ? resumeProfile() : pauseProfile());
+  }, [paused]);
+
   const selectedProfile = useMemo(() => profiles.find((p) => p.id === selectedId) ?? null, [profiles, selectedId]);
   const isRunning = Boolean(runningProfileId);
+  // Step-through debugging: the runner is held while the latest runtime event is a DebugPaused
+  const heldAtStep = useMemo(() => {
+    const last = [...events].reverse().find((e) => e.type !== "MonitorTick");
+    return isRunning && last?.type === "DebugPaused";
+  }, [events, isRunning]);
 
   // Keep ref in sync with selectedProfile
   useEffect(() => {
@@ -397,12 +455,31 @@ function MainWindow() {
               </div>
             </div>
             {isRunning && (
-              <span className="running-chip" aria-live="polite" title="Monitor is running">
-                Running
+              <span
+                className={`running-chip ${paused || outsideRunWindow ? "paused" : ""}`}
+                aria-live="polite"
+                title={actionTimedOut ? "An action overran its timeout; resume to carry on" : paused ? "Monitor is paused" : outsideRunWindow ? "Queued until the profile's run window opens" : "Monitor is running"}
+              >
+                {actionTimedOut ? "Action timed out" : paused ? "Paused" : outsideRunWindow ? "Waiting for run window" : "Running"}
               </span>
             )}
           </div>
 
+          {interruptedRun && !isRunning && (
+            <div className="alert interrupted-run" role="alert">
+              <span>
+                The last run of <strong>{profiles.find((p) => p.id === interruptedRun.profile_id)?.name ?? interruptedRun.profile_id}</strong>{" "}
+                was interrupted after {interruptedRun.iterations} iterations.
+              </span>
+              <button onClick={resumeInterrupted} title="Restart the loop with its iteration count and context variables restored">
+                Resume
+              </button>
+              <button className="ghost" onClick={discardInterrupted}>
+                Discard
+              </button>
+            </div>
+          )}
+
           <div className="monitor-toolbar" role="group" aria-label="Profile selection">
             <label className="sr-only" htmlFor="profile-select">
               Select profile
@@ -434,6 +511,30 @@ function MainWindow() {
                 </span>
                 {isRunning ? "Stop" : "Start"}
               </button>
+              {!isRunning && (
+                <label title="Hold before every action so you can inspect parameters and context variables">
+                  <input type="checkbox" checked={debugMode} onChange={(e) => setDebugMode(e.target.checked)} />
+                  Step-through
+                </label>
+              )}
+              {heldAtStep && (
+                <>
+                  <button onClick={() => debugStep()} title="Run the held action and stop before the next one">
+                    Step
+                  </button>
+                  <button onClick={() => debugContinue()} title="Leave step-through mode and run normally">
+                    Continue
+                  </button>
+                </>
+              )}
+              {isRunning && (
+                <button
+                  onClick={togglePause}
+                  title={paused ? "Resume the paused run" : "Pause after the current action; context is kept"}
+                >
+                  {paused ? "Resume" : "Pause"}
+                </button>
+              )}
 
             </div>
           </div>
@@ -490,9 +591,68 @@ function MainWindow() {
                   placeholder="unset"
                 />
               </label>
+              <label title="Stop the run after this many action-sequence iterations.">
+                Max iterations
+                <AcceleratingNumberInput
+                  min={1}
+                  value={selectedProfile.guardrails?.max_iterations ?? ""}
+                  onValueChange={async (nextValue) => {
+                    const parsed = nextValue === "" ? undefined : Number(nextValue);
+                    const next = parsed === undefined
+                      ? mergeGuardrails({}, { unset: ["max_iterations"] })
+                      : mergeGuardrails({ max_iterations: Math.max(1, parsed) });
+                    await updateProfile({ ...selectedProfile, guardrails: next });
+                  }}
+                  placeholder="unset"
+                />
+              </label>
+              <label title="Stop the run after this many LLM action failures in a row.">
+                Max LLM failures
+                <AcceleratingNumberInput
+                  min={1}
+                  value={selectedProfile.guardrails?.max_consecutive_llm_failures ?? ""}
+                  onValueChange={async (nextValue) => {
+                    const parsed = nextValue === "" ? undefined : Number(nextValue);
+                    const next = parsed === undefined
+                      ? mergeGuardrails({}, { unset: ["max_consecutive_llm_failures"] })
+                      : mergeGuardrails({ max_consecutive_llm_failures: Math.max(1, parsed) });
+                    await updateProfile({ ...selectedProfile, guardrails: next });
+                  }}
+                  placeholder="unset"
+                />
+              </label>
             </div>
           )}
 
+          {selectedProfile && <ProfileDiagnostics profile={selectedProfile} />}
+
+          {selectedProfile && (
+            <ProfileChainingEditor
+              profile={selectedProfile}
+              profiles={profiles}
+              onProfileChange={updateProfile}
+            />
+          )}
+
+          {selectedProfile && (
+            <ProfileNotificationsEditor profile={selectedProfile} onProfileChange={updateProfile} />
+          )}
+
+          {selectedProfile && (
+            <ProfileRunWindowsEditor profile={selectedProfile} onProfileChange={updateProfile} />
+          )}
+
+          {selectedProfile && (
+            <ProfileFrameArchiveEditor profile={selectedProfile} onProfileChange={updateProfile} />
+          )}
+
+          {selectedProfile && (
+            <ProfilePrivacyEditor profile={selectedProfile} onProfileChange={updateProfile} />
+          )}
+
+          {selectedProfile && (
+            <ProfileVoiceEditor profile={selectedProfile} onProfileChange={updateProfile} />
+          )}
 
         </article>
 
@@ -509,9 +669,24 @@ function MainWindow() {
               Clear log
             </button>
           </div>
+          <ActivityFeed />
           <EventLog events={events} />
         </article>
 
+        <article className="panel card run-history-panel" aria-label="Run history panel">
+          <div className="panel-header">
+            <div className="panel-title">
+              <span className="panel-icon" aria-hidden="true">🗂️</span>
+              <div>
+                <h3>Run history</h3>
+                <p className="muted">Finished runs with their outcome, LLM calls and cost.</p>
+              </div>
+            </div>
+          </div>
+          <ProfileStats />
+          <RunHistoryPanel />
+        </article>
+
         <article className="panel card recording-panel" aria-label="Recording bar">
           <div className="panel-header">
             <div className="panel-title">
@@ -567,6 +742,15 @@ function MainWindow() {
                 ),
               });
             }}
+            onRegionAnchor={async (regionId, anchor) => {
+              if (!selectedProfile) return;
+              await updateProfile({
+                ...selectedProfile,
+                regions: selectedProfile.regions.map((region) =>
+                  region.id === regionId ? { ...region, anchor: anchor ?? undefined } : region
+                ),
+              });
+            }}
           />
         </article>
 
@@ -602,6 +786,9 @@ function MainWindow() {
               </div>
             </div>
           </div>
+          <ProfileTemplates onCreated={reloadAfterImport} />
+          <ProfileTransfer selectedProfile={selectedProfile} onImported={reloadAfterImport} />
+          {selectedProfile && <ProfileSecrets profileId={selectedProfile.id} />}
           <ProfileEditor config={config} onChange={applyConfig} />
         </article>
       </section>
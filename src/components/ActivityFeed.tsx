@@ -0,0 +1,59 @@
+import { useEffect, useState } from "react";
+import { subscribeEvent } from "../eventBridge";
+import { RunnerStateUpdate } from "../types";
+
+const MAX_ENTRIES = 50;
+
+function describe(u: RunnerStateUpdate): string {
+  const mark = (ok: boolean) => (ok ? "✓" : "✗");
+  switch (u.type) {
+    case "RunState":
+      return `Run ${u.state}`;
+    case "TriggerFired":
+      return "Trigger fired";
+    case "ActionStarted":
+      return `▶ ${u.action}`;
+    case "ActionFinished":
+      return `${mark(u.success)} ${u.action}`;
+    case "LlmCallStarted":
+      return `🤖 ${u.action} calling LLM…`;
+    case "LlmCallFinished":
+      return `🤖 ${mark(u.success)} ${u.action}${u.risk === null ? "" : ` (risk ${u.risk.toFixed(2)})`}`;
+    case "IterationCompleted":
+      return `Iteration ${u.iteration} ${mark(u.success)}`;
+    case "Guardrail":
+      return `⚠️ ${u.reason}`;
+    case "Error":
+      return `❌ ${u.message}`;
+  }
+}
+
+/** Live feed of runner state transitions, newest first */
+export function ActivityFeed() {
+  const [entries, setEntries] = useState<RunnerStateUpdate[]>([]);
+
+  useEffect(() => {
+    let dispose: (() => void) | undefined;
+    subscribeEvent<RunnerStateUpdate>("runner://state", (update) => {
+      if (!update) return;
+      setEntries((prev) => [update, ...prev].slice(0, MAX_ENTRIES));
+    }).then((off) => (dispose = off));
+    return () => {
+      try {
+        dispose?.();
+      } catch { }
+    };
+  }, []);
+
+  if (entries.length === 0) return null;
+
+  return (
+    <ol aria-label="Activity feed" style={{ listStyle: "none", margin: "0 0 8px", padding: 0, maxHeight: 120, overflowY: "auto", fontSize: 11 }}>
+      {entries.map((u, i) => (
+        <li key={`${u.at_ms}-${i}`} title={u.profile_id}>
+          <span className="muted">{new Date(u.at_ms).toLocaleTimeString()}</span> {describe(u)}
+        </li>
+      ))}
+    </ol>
+  );
+}
@@ -0,0 +1,90 @@
+import { useEffect, useState } from "react";
+import { AlertEvent, AlertSound, SoundMap } from "../types";
+import { audioGetSoundMap, audioSetSoundMap, audioTestAlert } from "../tauriBridge";
+
+const EVENTS: { key: AlertEvent; label: string }[] = [
+  { key: "run_started", label: "Run started" },
+  { key: "intervention_needed", label: "Needs intervention" },
+  { key: "profile_ended", label: "Completed" },
+  { key: "risk_exceeded", label: "Risk exceeded" },
+  { key: "budget_exceeded", label: "Budget exceeded" },
+  { key: "error", label: "Failed" },
+  { key: "approval_requested", label: "Debugger step" },
+];
+
+// Sounds the backend plays for events the map does not list
+const DEFAULT_SOUNDS: Record<AlertEvent, AlertSound | null> = {
+  run_started: "completion",
+  intervention_needed: "intervention",
+  profile_ended: "completion",
+  risk_exceeded: "intervention",
+  budget_exceeded: "intervention",
+  error: "intervention",
+  approval_requested: null,
+};
+
+const SOUND_LABELS: Record<AlertSound, string> = { intervention: "Intervention", completion: "Completion" };
+
+const describe = (sound: AlertSound | null) => (sound ? SOUND_LABELS[sound] : "Silent");
+
+/** Which sound each alert event plays */
+export function AlertSoundMapSettings() {
+  const [map, setMap] = useState<SoundMap>({});
+  const [message, setMessage] = useState("");
+
+  useEffect(() => {
+    audioGetSoundMap().then(setMap).catch(() => { });
+  }, []);
+
+  const choose = async (event: AlertEvent, value: string) => {
+    const next = { ...map };
+    if (value === "default") {
+      delete next[event];
+    } else {
+      next[event] = value === "silent" ? null : (value as AlertSound);
+    }
+    try {
+      await audioSetSoundMap(next);
+      setMap(next);
+      setMessage("✓ Alert sounds saved");
+    } catch (error) {
+      setMessage(`Error: ${error}`);
+    }
+  };
+
+  const test = async (event: AlertEvent) => {
+    try {
+      await audioTestAlert(event);
+    } catch (error) {
+      setMessage(`Error: ${error}`);
+    }
+  };
+
+  const selected = (event: AlertEvent) => {
+    if (!(event in map)) return "default";
+    return map[event] ?? "silent";
+  };
+
+  return (
+    <div style={{ display: "flex", flexDirection: "column", gap: 8 }}>
+      <div style={{ fontSize: 13, opacity: 0.8 }}>Sound per alert. A profile's Notifications settings decide whether the sound plays at all.</div>
+      {EVENTS.map(({ key, label }) => (
+        <div key={key} style={{ display: "flex", alignItems: "center", gap: 8 }}>
+          <span style={{ width: 140 }}>{label}:</span>
+          <select aria-label={`${label} sound`} value={selected(key)} onChange={(e) => choose(key, e.target.value)} style={{ flex: 1 }}>
+            <option value="default">Default ({describe(DEFAULT_SOUNDS[key])})</option>
+            <option value="intervention">Intervention</option>
+            <option value="completion">Completion</option>
+            <option value="silent">Silent</option>
+          </select>
+          <button aria-label={`Test ${label} sound`} onClick={() => test(key)}>Test</button>
+        </div>
+      ))}
+      {message && (
+        <div role="status" style={{ fontSize: 13, color: message.startsWith("Error") ? "#d32f2f" : undefined }}>
+          {message}
+        </div>
+      )}
+    </div>
+  );
+}
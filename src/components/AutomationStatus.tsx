@@ -0,0 +1,53 @@
+import { useEffect, useState } from "react";
+import { AutomationCapabilities } from "../types";
+import { automationCapabilities } from "../tauriBridge";
+
+const BACKENDS: Record<string, string> = {
+  xtest: "X11 (XTest)",
+  ydotool: "ydotool (uinput)",
+  cgevent: "macOS Quartz events",
+  sendinput: "Windows SendInput",
+  fake: "Simulated",
+  none: "None",
+};
+
+/** Which input backend runs use in this session and what it can do */
+export function AutomationStatus() {
+  const [caps, setCaps] = useState<AutomationCapabilities | null>(null);
+  const [error, setError] = useState("");
+
+  useEffect(() => {
+    automationCapabilities()
+      .then(setCaps)
+      .catch((e) => setError(`Error: ${e}`));
+  }, []);
+
+  if (error) return <div role="status" style={{ fontSize: 13, color: "#d32f2f" }}>{error}</div>;
+  if (!caps) return <div style={{ fontSize: 13, opacity: 0.8 }}>Input automation is only available in the desktop app.</div>;
+
+  const features: [string, boolean][] = [
+    ["Mouse", caps.mouse],
+    ["Keyboard", caps.keyboard],
+    ["Text typing", caps.text],
+  ];
+  return (
+    <div style={{ display: "flex", flexDirection: "column", gap: 8 }}>
+      <div>
+        Backend: <strong>{BACKENDS[caps.backend] ?? caps.backend}</strong>
+        {caps.session !== "unknown" && <span style={{ opacity: 0.7 }}> · {caps.session} session</span>}
+      </div>
+      <ul aria-label="Automation features" style={{ margin: 0, paddingLeft: 20 }}>
+        {features.map(([name, ok]) => (
+          <li key={name}>
+            {ok ? "✓" : "✗"} {name}
+          </li>
+        ))}
+      </ul>
+      {caps.hint && (
+        <div role="status" style={{ fontSize: 13, color: caps.backend === "none" ? "#d32f2f" : undefined, opacity: caps.backend === "none" ? 1 : 0.8 }}>
+          {caps.hint}
+        </div>
+      )}
+    </div>
+  );
+}
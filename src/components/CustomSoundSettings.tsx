@@ -0,0 +1,56 @@
+import { useEffect, useState } from "react";
+import { AlertSound } from "../types";
+import { audioGetCustomSounds, audioSetCustomSound } from "../tauriBridge";
+
+const SOUNDS: { key: AlertSound; label: string }[] = [
+  { key: "intervention", label: "Intervention" },
+  { key: "completion", label: "Completion" },
+];
+
+/** Custom sound file per alert; an empty path plays the built-in sound */
+export function CustomSoundSettings() {
+  const [paths, setPaths] = useState<Partial<Record<AlertSound, string>>>({});
+  const [message, setMessage] = useState("");
+
+  useEffect(() => {
+    audioGetCustomSounds().then(setPaths).catch(() => { });
+  }, []);
+
+  const save = async (sound: AlertSound, path: string | null) => {
+    try {
+      await audioSetCustomSound(sound, path);
+      setPaths((current) => ({ ...current, [sound]: path ?? "" }));
+      setMessage(path ? `✓ Custom ${sound} sound saved` : `✓ Built-in ${sound} sound restored`);
+    } catch (error) {
+      setMessage(`Error: ${error}`);
+    }
+  };
+
+  return (
+    <div style={{ display: "flex", flexDirection: "column", gap: 8 }}>
+      <div style={{ fontSize: 13, opacity: 0.8 }}>
+        Custom sound files (WAV, OGG, MP3 or FLAC). Leave empty to use the built-in sounds.
+      </div>
+      {SOUNDS.map(({ key, label }) => (
+        <div key={key} style={{ display: "flex", alignItems: "center", gap: 8 }}>
+          <span style={{ width: 100 }}>{label}:</span>
+          <input
+            type="text"
+            aria-label={`${label} sound file`}
+            value={paths[key] ?? ""}
+            onChange={(e) => setPaths((current) => ({ ...current, [key]: e.target.value }))}
+            placeholder="Built-in sound"
+            style={{ flex: 1 }}
+          />
+          <button onClick={() => save(key, paths[key]?.trim() || null)}>Save</button>
+          <button onClick={() => save(key, null)} disabled={!paths[key]}>Reset</button>
+        </div>
+      ))}
+      {message && (
+        <div role="status" style={{ fontSize: 13, color: message.startsWith("Error") ? "#d32f2f" : undefined }}>
+          {message}
+        </div>
+      )}
+    </div>
+  );
+}
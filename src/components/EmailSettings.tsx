@@ -0,0 +1,113 @@
+import { useEffect, useState } from "react";
+import { SmtpConfig, SmtpSecurity } from "../types";
+import { smtpGet, smtpSet, smtpTest } from "../tauriBridge";
+
+const EMPTY: SmtpConfig = {
+  host: "",
+  port: 0,
+  security: "starttls",
+  username: "",
+  password: "",
+  from: "",
+  to: [],
+  include_screenshot: false,
+};
+
+/** SMTP server that mails a summary when a run ends or needs intervention */
+export function EmailSettings() {
+  const [enabled, setEnabled] = useState(false);
+  const [config, setConfig] = useState<SmtpConfig>(EMPTY);
+  const [recipients, setRecipients] = useState("");
+  const [message, setMessage] = useState("");
+
+  useEffect(() => {
+    smtpGet()
+      .then((saved) => {
+        if (!saved) return;
+        setEnabled(true);
+        setConfig(saved);
+        setRecipients(saved.to.join(", "));
+      })
+      .catch(() => { });
+  }, []);
+
+  const set = (patch: Partial<SmtpConfig>) => setConfig((current) => ({ ...current, ...patch }));
+
+  const toConfig = (): SmtpConfig => ({
+    ...config,
+    host: config.host.trim(),
+    from: config.from.trim(),
+    to: recipients.split(",").map((r) => r.trim()).filter(Boolean),
+  });
+
+  const save = async () => {
+    try {
+      await smtpSet(enabled ? toConfig() : null);
+      setMessage(enabled ? "✓ Email settings saved" : "✓ Email notifications turned off");
+    } catch (error) {
+      setMessage(`Error: ${error}`);
+    }
+  };
+
+  const test = async () => {
+    try {
+      await smtpTest(toConfig());
+      setMessage("✓ Test email sent");
+    } catch (error) {
+      setMessage(`Error: ${error}`);
+    }
+  };
+
+  return (
+    <div style={{ display: "flex", flexDirection: "column", gap: 10 }}>
+      <div style={{ fontSize: 13, opacity: 0.8 }}>
+        Mail a run summary (outcome, iterations, duration and the LLM's last reason) when a run ends or needs intervention.
+      </div>
+      <label style={{ display: "flex", alignItems: "center", gap: 12, cursor: "pointer" }}>
+        <input type="checkbox" checked={enabled} onChange={(e) => setEnabled(e.target.checked)} />
+        <span>Send email notifications</span>
+      </label>
+      {enabled && (
+        <>
+          <div style={{ display: "flex", gap: 8 }}>
+            <input type="text" aria-label="SMTP server" value={config.host} onChange={(e) => set({ host: e.target.value })} placeholder="smtp.example.com" style={{ flex: 1 }} />
+            <select aria-label="SMTP security" value={config.security} onChange={(e) => set({ security: e.target.value as SmtpSecurity })}>
+              <option value="starttls">STARTTLS</option>
+              <option value="tls">TLS</option>
+              <option value="none">None</option>
+            </select>
+            <input
+              type="number"
+              aria-label="SMTP port"
+              min={0}
+              max={65535}
+              value={config.port || ""}
+              onChange={(e) => set({ port: Math.max(0, Math.floor(Number(e.target.value) || 0)) })}
+              placeholder="Default"
+              style={{ width: 80 }}
+            />
+          </div>
+          <div style={{ display: "flex", gap: 8 }}>
+            <input type="text" aria-label="SMTP username" value={config.username} onChange={(e) => set({ username: e.target.value })} placeholder="Username (optional)" style={{ flex: 1 }} />
+            <input type="password" aria-label="SMTP password" value={config.password} onChange={(e) => set({ password: e.target.value })} placeholder="Password" style={{ flex: 1 }} />
+          </div>
+          <input type="text" aria-label="From address" value={config.from} onChange={(e) => set({ from: e.target.value })} placeholder="LoopAutoma <bot@example.com>" />
+          <input type="text" aria-label="Recipients" value={recipients} onChange={(e) => setRecipients(e.target.value)} placeholder="ops@example.com, me@example.com" />
+          <label style={{ display: "flex", alignItems: "center", gap: 6 }}>
+            <input type="checkbox" aria-label="Attach screenshot to email" checked={config.include_screenshot} onChange={(e) => set({ include_screenshot: e.target.checked })} />
+            Attach screenshot
+          </label>
+        </>
+      )}
+      <div style={{ display: "flex", gap: 8 }}>
+        <button onClick={save}>Save email settings</button>
+        <button onClick={test} disabled={!enabled}>Send test email</button>
+      </div>
+      {message && (
+        <div role="status" style={{ fontSize: 13, color: message.startsWith("Error") ? "#d32f2f" : undefined }}>
+          {message}
+        </div>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,58 @@
+import { useEffect, useState } from "react";
+import { detectEnvCredentials, importEnvCredentials } from "../tauriSecureStorage";
+import { EnvKeyStatus } from "../types";
+
+/** Offers to move API keys from environment variables into secure storage; renders nothing when none are set */
+export function EnvCredentialImport() {
+  const [found, setFound] = useState<EnvKeyStatus[]>([]);
+  const [status, setStatus] = useState<{ ok: boolean; message: string } | null>(null);
+
+  const refresh = () =>
+    detectEnvCredentials()
+      .then(setFound)
+      .catch(() => setFound([]));
+
+  useEffect(() => {
+    refresh();
+  }, []);
+
+  const importable = found.filter((k) => !k.stored && !k.problem).map((k) => k.var);
+
+  const importKeys = async () => {
+    try {
+      const imported = await importEnvCredentials(importable);
+      setStatus({ ok: true, message: `✓ Stored ${imported.join(", ")}; the stored keys are now used` });
+      await refresh();
+    } catch (e: any) {
+      setStatus({ ok: false, message: e?.message ?? String(e) });
+    }
+  };
+
+  if (found.length === 0) return null;
+
+  return (
+    <div style={{ display: "flex", flexDirection: "column", gap: 8, marginTop: 12 }}>
+      <div style={{ fontSize: 13, opacity: 0.8 }}>
+        API keys found in environment variables. Stored keys take precedence over the environment.
+      </div>
+      <ul aria-label="Environment API keys" style={{ margin: 0, paddingLeft: 18, fontSize: 13 }}>
+        {found.map((k) => (
+          <li key={k.var}>
+            <code>{k.var}</code> ({k.provider}){" "}
+            {k.problem ? `— ${k.problem}` : k.stored ? "— ignored, a stored key is used" : ""}
+          </li>
+        ))}
+      </ul>
+      <div>
+        <button onClick={importKeys} disabled={importable.length === 0}>
+          Import from environment
+        </button>
+      </div>
+      {status && (
+        <div role={status.ok ? "status" : "alert"} style={{ fontSize: 13, color: status.ok ? undefined : "#d32f2f" }}>
+          {status.message}
+        </div>
+      )}
+    </div>
+  );
+}
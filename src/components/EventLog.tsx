@@ -30,8 +30,28 @@ function formatEvent(e: Event): EventRow {
       return { time, name: "Action Completed", details: `${status} ${e.action.length > 40 ? `${e.action.substring(0, 40)}...` : e.action}`, fullDetails: `${status} ${e.action}` };
     case "MonitorStateChanged":
       return { time, name: "Monitor State", details: e.state };
+    case "LlmCallStarted":
+      return { time, name: "LLM Call", details: e.action };
+    case "LlmCallFinished": {
+      const risk = e.risk === null ? "" : ` risk=${e.risk}`;
+      return { time, name: "LLM Call Finished", details: `${e.success ? "✓" : "✗"} ${e.action}${risk}` };
+    }
+    case "IterationCompleted":
+      return { time, name: "Iteration Completed", details: `#${e.iteration} ${e.success ? "✓" : "✗"}` };
     case "WatchdogTripped":
       return { time, name: "⚠️ Watchdog", details: e.reason };
+    case "DebugPaused": {
+      const details = `#${e.index + 1} ${e.action} ${JSON.stringify(e.params ?? {})}`;
+      return { time, name: "⏸️ Debug Step", details: details.length > 60 ? `${details.substring(0, 60)}...` : details, fullDetails: `${details} vars=${JSON.stringify(e.variables)}` };
+    }
+    case "BudgetExceeded":
+      return { time, name: "⏹️ Budget Exceeded", details: `${e.budget} (limit ${e.limit})` };
+    case "ProfileChained":
+      return { time, name: "⛓️ Profile Chained", details: `${e.from} → ${e.to} (on ${e.on})` };
+    case "NotificationSuppressed":
+      return { time, name: "🔕 Quiet Hours", details: `${e.event}: held back ${e.channels.join(", ")}` };
+    case "CredentialWarning":
+      return { time, name: "🔑 API Key", details: `${e.credential} ${e.reason}` };
     case "Error":
       return { time, name: "❌ Error", details: e.message.length > 60 ? `${e.message.substring(0, 60)}...` : e.message, fullDetails: e.message };
     case "MonitorTick": {
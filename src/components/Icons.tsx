@@ -99,4 +99,24 @@ export function SettingsIcon({ size = 18, ...props }: IconProps) {
             <path d="M15.73 4.27l-0.86 0.86a8 8 0 0 1 0 13.74l0.86 0.86a9 9 0 0 0 0-15.46zM8.27 4.27l0.86 0.86a8 8 0 0 0 0 13.74l-0.86 0.86a9 9 0 0 1 0-15.46z" />
         </svg>
     );
-}
\ No newline at end of file
+}
+
+export function EyeIcon({ size = 18, ...props }: IconProps) {
+    return (
+        <svg viewBox="0 0 24 24" width={size} height={size} {...baseProps} {...props}>
+            <path d="M2 12s3.5-7 10-7 10 7 10 7-3.5 7-10 7-10-7-10-7z" />
+            <circle cx="12" cy="12" r="3" />
+        </svg>
+    );
+}
+
+export function AnchorIcon({ size = 18, ...props }: IconProps) {
+    return (
+        <svg viewBox="0 0 24 24" width={size} height={size} {...baseProps} {...props}>
+            <circle cx="12" cy="5" r="2" />
+            <line x1="12" y1="7" x2="12" y2="21" />
+            <line x1="8" y1="11" x2="16" y2="11" />
+            <path d="M4 14a8 8 0 0 0 16 0" />
+        </svg>
+    );
+}
@@ -0,0 +1,100 @@
+import { useCallback, useEffect, useState } from "react";
+import { LogLevel, LogLine } from "../types";
+import { logLevelGet, logLevelSet, tailLogs } from "../tauriBridge";
+
+const LEVELS: LogLevel[] = ["error", "warn", "info", "debug", "trace"];
+const TAIL_LINES = 200;
+
+const LEVEL_COLORS: Partial<Record<LogLevel, string>> = {
+  error: "#d32f2f",
+  warn: "#ed6c02",
+};
+
+/** Where a record came from, e.g. `run profile=p1 › action index=2 action=Click` */
+function spanContext(line: LogLine): string {
+  return line.spans
+    .map(({ name, ...fields }) =>
+      [name, ...Object.entries(fields).map(([k, v]) => `${k}=${typeof v === "string" ? v : JSON.stringify(v)}`)].join(" "),
+    )
+    .join(" › ");
+}
+
+/** Backend log level and the newest records of the log files */
+export function LogViewer() {
+  const [level, setLevel] = useState<LogLevel>("info");
+  const [filter, setFilter] = useState<LogLevel>("trace");
+  const [lines, setLines] = useState<LogLine[]>([]);
+  const [message, setMessage] = useState("");
+
+  const refresh = useCallback(async (shown: LogLevel) => {
+    try {
+      setLines(await tailLogs(TAIL_LINES, shown));
+      setMessage("");
+    } catch (error) {
+      setMessage(`Error: ${error}`);
+    }
+  }, []);
+
+  useEffect(() => {
+    logLevelGet()
+      .then((saved) => saved && setLevel(saved))
+      .catch(() => { });
+    refresh("trace");
+  }, [refresh]);
+
+  const changeLevel = async (next: LogLevel) => {
+    try {
+      await logLevelSet(next);
+      setLevel(next);
+      setMessage(`✓ Log level set to ${next}`);
+    } catch (error) {
+      setMessage(`Error: ${error}`);
+    }
+  };
+
+  const changeFilter = (next: LogLevel) => {
+    setFilter(next);
+    refresh(next);
+  };
+
+  return (
+    <div style={{ display: "flex", flexDirection: "column", gap: 10 }}>
+      <div style={{ display: "flex", alignItems: "center", gap: 12, flexWrap: "wrap" }}>
+        <label style={{ display: "flex", alignItems: "center", gap: 6 }}>
+          Log level
+          <select aria-label="Log level" value={level} onChange={(e) => changeLevel(e.target.value as LogLevel)}>
+            {LEVELS.map((l) => (
+              <option key={l} value={l}>{l}</option>
+            ))}
+          </select>
+        </label>
+        <label style={{ display: "flex", alignItems: "center", gap: 6 }}>
+          Show
+          <select aria-label="Show records" value={filter} onChange={(e) => changeFilter(e.target.value as LogLevel)}>
+            {LEVELS.map((l) => (
+              <option key={l} value={l}>{l === "trace" ? "everything" : `${l} and worse`}</option>
+            ))}
+          </select>
+        </label>
+        <button onClick={() => refresh(filter)}>Refresh</button>
+      </div>
+      {lines.length === 0 ? (
+        <p className="muted">No log records.</p>
+      ) : (
+        <ol aria-label="Log records" style={{ listStyle: "none", margin: 0, padding: 0, maxHeight: 320, overflowY: "auto", fontFamily: "monospace", fontSize: 12 }}>
+          {lines.map((line, i) => (
+            <li key={i} style={{ color: LEVEL_COLORS[line.level] }}>
+              <span style={{ opacity: 0.7 }}>{line.timestamp}</span> {line.level.toUpperCase()} {line.message}
+              {line.spans.length > 0 && <span style={{ opacity: 0.7 }}> [{spanContext(line)}]</span>}
+            </li>
+          ))}
+        </ol>
+      )}
+      {message && (
+        <div role="status" style={{ fontSize: 13, color: message.startsWith("Error") ? "#d32f2f" : undefined }}>
+          {message}
+        </div>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,67 @@
+import { useEffect, useState } from "react";
+import { metricsExporterGet, metricsExporterSet, metricsExporterStatus } from "../tauriBridge";
+
+// The port commonly used by Prometheus exporters
+const DEFAULT_PORT = 9464;
+
+/** Prometheus `/metrics` endpoint on localhost, for monitoring long-running hosts */
+export function MetricsExporterSettings() {
+  const [enabled, setEnabled] = useState(false);
+  const [port, setPort] = useState(DEFAULT_PORT);
+  const [url, setUrl] = useState<string | null>(null);
+  const [message, setMessage] = useState("");
+
+  useEffect(() => {
+    metricsExporterGet()
+      .then((saved) => {
+        if (saved === null) return;
+        setEnabled(true);
+        setPort(saved);
+      })
+      .catch(() => { });
+    metricsExporterStatus().then(setUrl).catch(() => { });
+  }, []);
+
+  const save = async () => {
+    try {
+      const saved = await metricsExporterSet(enabled ? port : null);
+      setUrl(saved);
+      setMessage(saved ? `✓ Serving ${saved}` : "✓ Metrics endpoint turned off");
+    } catch (error) {
+      setMessage(`Error: ${error}`);
+    }
+  };
+
+  return (
+    <div style={{ display: "flex", flexDirection: "column", gap: 10 }}>
+      <div style={{ fontSize: 13, opacity: 0.8 }}>
+        Counters and histograms of iterations, action failures, interventions, LLM calls (latency, tokens, cost) and screen capture
+        latency, per profile, in the Prometheus format. Listens on 127.0.0.1 only; builds need the <code>prometheus-metrics</code> feature.
+      </div>
+      <label style={{ display: "flex", alignItems: "center", gap: 12, cursor: "pointer" }}>
+        <input type="checkbox" checked={enabled} onChange={(e) => setEnabled(e.target.checked)} />
+        <span>Enable metrics endpoint</span>
+      </label>
+      {enabled && (
+        <input
+          type="number"
+          aria-label="Metrics port"
+          min={1}
+          max={65535}
+          value={port || ""}
+          onChange={(e) => setPort(Math.max(0, Math.floor(Number(e.target.value) || 0)))}
+          style={{ width: 90 }}
+        />
+      )}
+      <div style={{ display: "flex", gap: 8, alignItems: "center" }}>
+        <button onClick={save}>Save metrics settings</button>
+        {url && <code>{url}</code>}
+      </div>
+      {message && (
+        <div role="status" style={{ fontSize: 13, color: message.startsWith("Error") ? "#d32f2f" : undefined }}>
+          {message}
+        </div>
+      )}
+    </div>
+  );
+}
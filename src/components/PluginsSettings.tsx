@@ -0,0 +1,73 @@
+import { useEffect, useState } from "react";
+import { pluginsList, pluginsSetEnabled } from "../tauriBridge";
+import { PluginInfo } from "../types";
+
+function components(plugin: PluginInfo): string {
+  const m = plugin.manifest;
+  if (!m) return "";
+  const parts = [
+    ...m.actions.map((c) => `action ${c.kind}`),
+    ...m.triggers.map((c) => `trigger ${c.kind}`),
+    ...m.notifiers.map((c) => `notifier ${c.kind}`),
+  ];
+  return parts.join(", ");
+}
+
+/** Plugins found in the plugins directory; enabling one loads its library at the next start */
+export function PluginsSettings() {
+  const [plugins, setPlugins] = useState<PluginInfo[]>([]);
+  const [message, setMessage] = useState("");
+
+  useEffect(() => {
+    pluginsList().then(setPlugins).catch(() => { });
+  }, []);
+
+  const toggle = async (plugin: PluginInfo, enabled: boolean) => {
+    try {
+      setPlugins(await pluginsSetEnabled(plugin.id, enabled));
+      setMessage(`✓ ${plugin.manifest?.name ?? plugin.id} ${enabled ? "enabled" : "disabled"}; restart loopautoma to apply`);
+    } catch (error) {
+      setMessage(`Error: ${error}`);
+    }
+  };
+
+  return (
+    <div style={{ display: "flex", flexDirection: "column", gap: 10 }}>
+      <div style={{ fontSize: 13, opacity: 0.8 }}>
+        Actions, triggers and notifiers from folders in <code>plugins/</code> under the loopautoma config directory. Plugins are native
+        code running with the app's rights, not in a sandbox: enable only plugins you trust. Input and capture are granted as each
+        plugin's manifest asks.
+      </div>
+      {plugins.length === 0 && <div style={{ fontSize: 13 }}>No plugins found.</div>}
+      {plugins.map((plugin) => (
+        <div key={plugin.dir} style={{ display: "flex", flexDirection: "column", gap: 4 }}>
+          <label style={{ display: "flex", alignItems: "center", gap: 12, cursor: plugin.manifest ? "pointer" : undefined }}>
+            <input
+              type="checkbox"
+              aria-label={`Enable ${plugin.id}`}
+              checked={plugin.enabled}
+              disabled={!plugin.manifest}
+              onChange={(e) => toggle(plugin, e.target.checked)}
+            />
+            <span>
+              {plugin.manifest ? `${plugin.manifest.name} ${plugin.manifest.version}` : plugin.id}
+              {plugin.loaded && " (loaded)"}
+            </span>
+          </label>
+          {plugin.manifest && (
+            <div style={{ fontSize: 12, opacity: 0.8 }}>
+              {components(plugin)}
+              {plugin.manifest.capabilities.length > 0 && ` · may use ${plugin.manifest.capabilities.join(" and ")}`}
+            </div>
+          )}
+          {plugin.error && <div style={{ fontSize: 12, color: "#d32f2f" }}>{plugin.error}</div>}
+        </div>
+      ))}
+      {message && (
+        <div role="status" style={{ fontSize: 13, color: message.startsWith("Error") ? "#d32f2f" : undefined }}>
+          {message}
+        </div>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,74 @@
+import { useEffect, useState } from "react";
+import { calibrate, calibrationGet, calibrationReset } from "../tauriBridge";
+import { CalibrationReport, CursorTransform } from "../types";
+
+function describeTransform(t: CursorTransform): string {
+  const axis = (scale: number, offset: number) => `×${scale.toFixed(3)} ${offset < 0 ? "−" : "+"} ${Math.abs(Math.round(offset))} px`;
+  return `x ${axis(t.scale_x, t.offset_x)}, y ${axis(t.scale_y, t.offset_y)}`;
+}
+
+/** Check where pointer moves land on every display and correct later moves */
+export function PointerCalibration() {
+  const [transform, setTransform] = useState<CursorTransform | null>(null);
+  const [report, setReport] = useState<CalibrationReport | null>(null);
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState("");
+
+  useEffect(() => {
+    calibrationGet().then(setTransform).catch(() => { });
+  }, []);
+
+  const run = async () => {
+    setBusy(true);
+    setError("");
+    try {
+      const result = await calibrate();
+      setReport(result);
+      setTransform(result.transform);
+    } catch (e) {
+      setError(`Error: ${e}`);
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const reset = async () => {
+    try {
+      await calibrationReset();
+      setTransform(null);
+      setReport(null);
+    } catch (e) {
+      setError(`Error: ${e}`);
+    }
+  };
+
+  return (
+    <div style={{ display: "flex", flexDirection: "column", gap: 8, marginTop: 12 }}>
+      <div style={{ fontSize: 13, opacity: 0.8 }}>
+        Clicks landing off target? Calibration moves the pointer across every display for a moment and checks where it ends up.
+        Don't touch the mouse while it runs.
+      </div>
+      <div style={{ display: "flex", gap: 8 }}>
+        <button onClick={run} disabled={busy}>
+          {busy ? "Calibrating…" : "Calibrate pointer"}
+        </button>
+        {transform && <button onClick={reset}>Reset calibration</button>}
+      </div>
+      <div style={{ fontSize: 13 }}>
+        {transform ? `Pointer moves are corrected: ${describeTransform(transform)}` : "Pointer moves are not corrected."}
+      </div>
+      {report && (
+        <ul aria-label="Calibration findings" style={{ margin: 0, paddingLeft: 20, fontSize: 13 }}>
+          {report.findings.map((f) => (
+            <li key={f}>{f}</li>
+          ))}
+        </ul>
+      )}
+      {error && (
+        <div role="status" style={{ fontSize: 13, color: "#d32f2f" }}>
+          {error}
+        </div>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,74 @@
+import { Profile, ProfileHandoff } from "../types";
+
+interface ProfileChainingEditorProps {
+  profile: Profile;
+  profiles: Profile[];
+  onProfileChange: (profile: Profile) => void;
+}
+
+type HandoffKey = "on_complete" | "on_failure";
+
+const HANDOFFS: { key: HandoffKey; label: string; title: string }[] = [
+  { key: "on_complete", label: "On complete", title: "Profile to start when this run completes successfully." },
+  { key: "on_failure", label: "On failure", title: "Profile to start when this run fails (failure keyword, budget or watchdog)." },
+];
+
+export function ProfileChainingEditor({ profile, profiles, onProfileChange }: ProfileChainingEditorProps) {
+  const setHandoff = (key: HandoffKey, handoff: ProfileHandoff | undefined) => {
+    const next: Profile = { ...profile };
+    if (handoff) {
+      next[key] = handoff;
+    } else {
+      delete next[key];
+    }
+    onProfileChange(next);
+  };
+
+  return (
+    <div className="guardrail-controls" aria-label="Profile chaining">
+      <div className="guardrail-label">
+        <span className="panel-icon" aria-hidden="true">⛓️</span>
+        <strong>Chaining</strong>
+      </div>
+      {HANDOFFS.map(({ key, label, title }) => {
+        const handoff = profile[key];
+        return (
+          <div key={key} className="chaining-row">
+            <label title={title}>
+              {label}
+              <select
+                value={handoff?.profile_id ?? ""}
+                onChange={(e) => {
+                  const profileId = e.target.value;
+                  setHandoff(key, profileId ? { ...handoff, profile_id: profileId } : undefined);
+                }}
+              >
+                <option value="">None</option>
+                {profiles.map((p) => (
+                  <option key={p.id} value={p.id}>
+                    {p.name}
+                  </option>
+                ))}
+              </select>
+            </label>
+            {handoff && (
+              <label title="Comma-separated context variables copied into the follow-up run (applied when the field loses focus).">
+                Pass variables
+                <input
+                  key={`${profile.id}-${key}-${handoff.profile_id}`}
+                  type="text"
+                  defaultValue={(handoff.pass_variables ?? []).join(", ")}
+                  onBlur={(e) => {
+                    const names = e.target.value.split(",").map((s) => s.trim()).filter(Boolean);
+                    setHandoff(key, { profile_id: handoff.profile_id, ...(names.length ? { pass_variables: names } : {}) });
+                  }}
+                  placeholder="none"
+                />
+              </label>
+            )}
+          </div>
+        );
+      })}
+    </div>
+  );
+}
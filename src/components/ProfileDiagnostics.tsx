@@ -0,0 +1,49 @@
+import { useEffect, useState } from "react";
+import { Diagnostic, Profile } from "../types";
+import { validateProfile } from "../tauriBridge";
+
+type Props = {
+  profile: Profile;
+};
+
+/** Backend validation results for the selected profile, refreshed on demand */
+export function ProfileDiagnostics({ profile }: Props) {
+  const [diagnostics, setDiagnostics] = useState<Diagnostic[] | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  // Results belong to the profile they were computed for
+  useEffect(() => {
+    setDiagnostics(null);
+    setError(null);
+  }, [profile]);
+
+  const check = async () => {
+    try {
+      setDiagnostics(await validateProfile(profile));
+      setError(null);
+    } catch (e: any) {
+      setError(e?.message ?? String(e));
+    }
+  };
+
+  const errors = diagnostics?.filter((d) => d.severity === "error").length ?? 0;
+
+  return (
+    <div className="profile-diagnostics" aria-label="Profile validation">
+      <button onClick={check} title="Check region references, key names, variables, risk thresholds and coordinates">
+        Validate profile
+      </button>
+      {error && <span className="alert">{error}</span>}
+      {diagnostics && diagnostics.length === 0 && <span className="muted">No problems found.</span>}
+      {diagnostics && diagnostics.length > 0 && (
+        <ul className={errors ? "validation-errors" : "validation-warnings"} role="alert">
+          {diagnostics.map((d, i) => (
+            <li key={i} className={`diagnostic ${d.severity}`}>
+              <strong>{d.severity === "error" ? "Error" : "Warning"}</strong> <code>{d.field}</code>: {d.message}
+            </li>
+          ))}
+        </ul>
+      )}
+    </div>
+  );
+}
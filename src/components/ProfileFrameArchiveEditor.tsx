@@ -0,0 +1,53 @@
+import { FrameArchiveConfig, Profile } from "../types";
+
+interface ProfileFrameArchiveEditorProps {
+  profile: Profile;
+  onProfileChange: (profile: Profile) => void;
+}
+
+// What the backend fills in for fields a profile leaves out
+const DEFAULTS: FrameArchiveConfig = { max_size: 480, max_frames: 500 };
+
+export function ProfileFrameArchiveEditor({ profile, onProfileChange }: ProfileFrameArchiveEditorProps) {
+  const archive = profile.frame_archive;
+
+  const save = (next: FrameArchiveConfig | undefined) => {
+    const updated: Profile = { ...profile };
+    if (next) {
+      updated.frame_archive = next;
+    } else {
+      delete updated.frame_archive;
+    }
+    onProfileChange(updated);
+  };
+
+  const setCount = (key: keyof FrameArchiveConfig, value: string) => {
+    const n = Math.floor(Number(value));
+    if (archive && Number.isFinite(n) && n > 0) save({ ...archive, [key]: n });
+  };
+
+  return (
+    <div className="guardrail-controls" aria-label="Frame archive">
+      <div className="guardrail-label">
+        <span className="panel-icon" aria-hidden="true">🎞</span>
+        <strong>Frame archive</strong>
+      </div>
+      <label className="toggle" title="Keep a small image of every region after each iteration; export them from the run history as a GIF or MP4.">
+        <input type="checkbox" checked={!!archive} onChange={(e) => save(e.target.checked ? DEFAULTS : undefined)} />
+        Archive region frames after each iteration
+      </label>
+      {archive && (
+        <div className="notification-row">
+          <label>
+            Longest side (px)
+            <input type="number" min={16} value={archive.max_size} onChange={(e) => setCount("max_size", e.target.value)} />
+          </label>
+          <label>
+            Iterations per run
+            <input type="number" min={1} value={archive.max_frames} onChange={(e) => setCount("max_frames", e.target.value)} />
+          </label>
+        </div>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,80 @@
+import { NotificationChannel, NotificationEvent, Profile } from "../types";
+
+interface ProfileNotificationsEditorProps {
+  profile: Profile;
+  onProfileChange: (profile: Profile) => void;
+}
+
+const EVENTS: { key: NotificationEvent; label: string; title: string }[] = [
+  { key: "started", label: "Started", title: "A run began or was queued for its run window." },
+  { key: "intervention_needed", label: "Needs intervention", title: "The run is blocked and waiting for you (e.g. no network)." },
+  { key: "completed", label: "Completed", title: "A success check ended the run." },
+  { key: "failed", label: "Failed", title: "A failure keyword, budget or watchdog ended the run." },
+  { key: "risk_exceeded", label: "Risk exceeded", title: "An LLM prompt was rejected for exceeding the risk threshold." },
+];
+
+const CHANNELS: { key: NotificationChannel; label: string }[] = [
+  { key: "sound", label: "Sound" },
+  { key: "desktop", label: "Desktop" },
+  { key: "webhook", label: "Webhooks" },
+  { key: "email", label: "Email" },
+  { key: "plugin", label: "Plugins" },
+];
+
+// Routing the backend applies to events a profile does not list
+const defaultChannels = (event: NotificationEvent): NotificationChannel[] => {
+  if (event === "started") return ["webhook"];
+  if (event === "failed") return ["sound", "webhook", "email"];
+  if (event === "risk_exceeded") return ["sound", "desktop", "webhook"];
+  return ["sound", "desktop", "webhook", "email"];
+};
+
+export function ProfileNotificationsEditor({ profile, onProfileChange }: ProfileNotificationsEditorProps) {
+  const routing = profile.notifications ?? {};
+  const channelsFor = (event: NotificationEvent) => routing[event] ?? defaultChannels(event);
+
+  const toggle = (event: NotificationEvent, channel: NotificationChannel, on: boolean) => {
+    const current = channelsFor(event).filter((c) => c !== channel);
+    const channels = on ? [...current, channel] : current;
+    const next = { ...routing };
+    const defaults = defaultChannels(event);
+    const isDefault = channels.length === defaults.length && defaults.every((c) => channels.includes(c));
+    if (isDefault) {
+      delete next[event];
+    } else {
+      next[event] = channels;
+    }
+    const updated: Profile = { ...profile };
+    if (Object.keys(next).length) {
+      updated.notifications = next;
+    } else {
+      delete updated.notifications;
+    }
+    onProfileChange(updated);
+  };
+
+  return (
+    <div className="guardrail-controls" aria-label="Notifications">
+      <div className="guardrail-label">
+        <span className="panel-icon" aria-hidden="true">🔔</span>
+        <strong>Notifications</strong>
+      </div>
+      {EVENTS.map(({ key, label, title }) => (
+        <div key={key} className="notification-row" title={title}>
+          <span>{label}</span>
+          {CHANNELS.map((channel) => (
+            <label key={channel.key} className="toggle">
+              <input
+                type="checkbox"
+                aria-label={`${label}: ${channel.label}`}
+                checked={channelsFor(key).includes(channel.key)}
+                onChange={(e) => toggle(key, channel.key, e.target.checked)}
+              />
+              {channel.label}
+            </label>
+          ))}
+        </div>
+      ))}
+    </div>
+  );
+}
@@ -0,0 +1,62 @@
+import { PiiKind, PrivacyConfig, Profile } from "../types";
+
+interface ProfilePrivacyEditorProps {
+  profile: Profile;
+  onProfileChange: (profile: Profile) => void;
+}
+
+const DETECTORS: { kind: PiiKind; label: string }[] = [
+  { kind: "email", label: "Email addresses" },
+  { kind: "credit_card", label: "Card numbers" },
+  { kind: "api_key", label: "API keys and tokens" },
+];
+
+export function ProfilePrivacyEditor({ profile, onProfileChange }: ProfilePrivacyEditorProps) {
+  const privacy = profile.privacy;
+
+  const save = (next: PrivacyConfig | undefined) => {
+    const updated: Profile = { ...profile };
+    if (next) {
+      updated.privacy = next;
+    } else {
+      delete updated.privacy;
+    }
+    onProfileChange(updated);
+  };
+
+  const toggle = (kind: PiiKind, on: boolean) => {
+    if (!privacy) return;
+    const detectors = DETECTORS.map((d) => d.kind).filter((k) => (k === kind ? on : privacy.detectors.includes(k)));
+    save({ detectors });
+  };
+
+  return (
+    <div className="guardrail-controls" aria-label="Privacy filter">
+      <div className="guardrail-label">
+        <span className="panel-icon" aria-hidden="true">🕶</span>
+        <strong>Privacy filter</strong>
+      </div>
+      <label
+        className="toggle"
+        title="Read every capture with tesseract and black out what looks like personal data before it is archived or sent to an LLM. Captures fail while tesseract cannot run."
+      >
+        <input
+          type="checkbox"
+          checked={!!privacy}
+          onChange={(e) => save(e.target.checked ? { detectors: DETECTORS.map((d) => d.kind) } : undefined)}
+        />
+        Mask personal data in captures
+      </label>
+      {privacy && (
+        <div className="notification-row">
+          {DETECTORS.map((d) => (
+            <label key={d.kind} className="toggle">
+              <input type="checkbox" checked={privacy.detectors.includes(d.kind)} onChange={(e) => toggle(d.kind, e.target.checked)} />
+              {d.label}
+            </label>
+          ))}
+        </div>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,107 @@
+import { Profile, RunWindow, Weekday } from "../types";
+
+interface ProfileRunWindowsEditorProps {
+  profile: Profile;
+  onProfileChange: (profile: Profile) => void;
+}
+
+const DAYS: { key: Weekday; label: string }[] = [
+  { key: "mon", label: "Mo" },
+  { key: "tue", label: "Tu" },
+  { key: "wed", label: "We" },
+  { key: "thu", label: "Th" },
+  { key: "fri", label: "Fr" },
+  { key: "sat", label: "Sa" },
+  { key: "sun", label: "Su" },
+];
+
+const NEW_WINDOW: RunWindow = { days: ["mon", "tue", "wed", "thu", "fri"], start: "22:00", end: "06:00" };
+
+interface RunWindowListProps {
+  windows: RunWindow[];
+  onChange: (windows: RunWindow[]) => void;
+}
+
+/** Editable weekly time windows; shared by profile run windows and quiet hours */
+export function RunWindowList({ windows, onChange }: RunWindowListProps) {
+  const update = (index: number, patch: Partial<RunWindow>) =>
+    onChange(windows.map((w, i) => (i === index ? { ...w, ...patch } : w)));
+
+  const toggleDay = (index: number, day: Weekday, on: boolean) => {
+    const current = windows[index].days ?? [];
+    const days = DAYS.map((d) => d.key).filter((d) => (d === day ? on : current.includes(d)));
+    update(index, { days });
+  };
+
+  return (
+    <>
+      {windows.map((w, index) => (
+        <div key={index} className="run-window-row">
+          <div className="run-window-days">
+            {DAYS.map((d) => (
+              <label key={d.key} className="toggle">
+                <input
+                  type="checkbox"
+                  aria-label={`Window ${index + 1}: ${d.key}`}
+                  checked={(w.days ?? []).includes(d.key)}
+                  onChange={(e) => toggleDay(index, d.key, e.target.checked)}
+                />
+                {d.label}
+              </label>
+            ))}
+          </div>
+          <input
+            type="time"
+            aria-label={`Window ${index + 1} start`}
+            value={w.start}
+            onChange={(e) => update(index, { start: e.target.value })}
+          />
+          <span aria-hidden="true">–</span>
+          <input
+            type="time"
+            aria-label={`Window ${index + 1} end`}
+            value={w.end}
+            onChange={(e) => update(index, { end: e.target.value })}
+          />
+          <button
+            type="button"
+            className="ghost"
+            aria-label={`Remove window ${index + 1}`}
+            onClick={() => onChange(windows.filter((_, i) => i !== index))}
+          >
+            ✕
+          </button>
+        </div>
+      ))}
+      <button type="button" className="ghost" onClick={() => onChange([...windows, { ...NEW_WINDOW }])}>
+        Add window
+      </button>
+    </>
+  );
+}
+
+export function ProfileRunWindowsEditor({ profile, onProfileChange }: ProfileRunWindowsEditorProps) {
+  const windows = profile.run_windows ?? [];
+
+  const save = (next: RunWindow[]) => {
+    const updated: Profile = { ...profile };
+    if (next.length) {
+      updated.run_windows = next;
+    } else {
+      delete updated.run_windows;
+    }
+    onProfileChange(updated);
+  };
+
+  return (
+    <div className="guardrail-controls" aria-label="Run windows">
+      <div className="guardrail-label">
+        <span className="panel-icon" aria-hidden="true">🕙</span>
+        <strong>Run windows</strong>
+      </div>
+      {windows.length === 0 && <p className="muted">Runs at any time. Add a window to restrict it to certain hours.</p>}
+      <RunWindowList windows={windows} onChange={save} />
+      <p className="muted">No days ticked means every day. An end before the start closes the next morning.</p>
+    </div>
+  );
+}
@@ -0,0 +1,70 @@
+import { useEffect, useState } from "react";
+import { deleteProfileSecret, listProfileSecrets, setProfileSecret } from "../tauriSecureStorage";
+
+/** Named secrets of one profile, typed by Type actions as `{{secret:name}}`; values are never shown */
+export function ProfileSecrets({ profileId }: { profileId: string }) {
+  const [names, setNames] = useState<string[]>([]);
+  const [name, setName] = useState("");
+  const [value, setValue] = useState("");
+  const [status, setStatus] = useState<{ ok: boolean; message: string } | null>(null);
+
+  const refresh = () =>
+    listProfileSecrets(profileId)
+      .then(setNames)
+      .catch(() => setNames([]));
+
+  useEffect(() => {
+    setStatus(null);
+    refresh();
+  }, [profileId]);
+
+  const run = async (task: () => Promise<string>) => {
+    try {
+      setStatus({ ok: true, message: await task() });
+      await refresh();
+    } catch (e: any) {
+      setStatus({ ok: false, message: e?.message ?? String(e) });
+    }
+  };
+
+  const save = () =>
+    run(async () => {
+      await setProfileSecret(profileId, name.trim(), value);
+      setName("");
+      setValue("");
+      return `✓ Saved {{secret:${name.trim()}}}`;
+    });
+
+  const remove = (secret: string) =>
+    run(async () => {
+      await deleteProfileSecret(profileId, secret);
+      return `✓ Removed ${secret}`;
+    });
+
+  return (
+    <div className="profile-secrets" role="group" aria-label="Profile secrets">
+      {names.length > 0 && (
+        <ul aria-label="Stored secrets">
+          {names.map((secret) => (
+            <li key={secret}>
+              <code>{`{{secret:${secret}}}`}</code>{" "}
+              <button aria-label={`Remove secret ${secret}`} onClick={() => remove(secret)}>Remove</button>
+            </li>
+          ))}
+        </ul>
+      )}
+      <div className="profile-transfer">
+        <input type="text" aria-label="Secret name" value={name} onChange={(e) => setName(e.target.value)} placeholder="jira_token" />
+        <input type="password" aria-label="Secret value" value={value} onChange={(e) => setValue(e.target.value)} placeholder="Value" />
+        <button onClick={save} disabled={!name.trim() || !value} title="Stored in the OS keyring; Type actions fill it in when they run">
+          Save secret
+        </button>
+      </div>
+      {status && (
+        <span className={status.ok ? "muted" : "alert"} role={status.ok ? "status" : "alert"}>
+          {status.message}
+        </span>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,86 @@
+import { useCallback, useEffect, useState } from "react";
+import { getMetrics } from "../tauriBridge";
+import { subscribeEvent } from "../eventBridge";
+import { ProfileMetrics } from "../types";
+
+const percent = (rate: number | null) => (rate === null ? "–" : `${Math.round(rate * 100)}%`);
+const millis = (ms: number | null) => (ms === null ? "–" : ms >= 1000 ? `${(ms / 1000).toFixed(1)}s` : `${Math.round(ms)}ms`);
+
+/** Per-profile success rates and latencies, to spot flaky profiles and why they fail */
+export function ProfileStats() {
+  const [days, setDays] = useState<number | undefined>(30);
+  const [stats, setStats] = useState<ProfileMetrics[]>([]);
+  const [error, setError] = useState<string | null>(null);
+
+  const reload = useCallback(async () => {
+    try {
+      setStats(await getMetrics(undefined, days));
+      setError(null);
+    } catch (e: any) {
+      setError(e?.message ?? String(e));
+    }
+  }, [days]);
+
+  useEffect(() => {
+    reload();
+    let dispose: (() => void) | undefined;
+    subscribeEvent<number>("loopautoma://run_recorded", () => {
+      reload();
+    }).then((off) => (dispose = off));
+    return () => {
+      try {
+        dispose?.();
+      } catch { }
+    };
+  }, [reload]);
+
+  return (
+    <div className="profile-stats">
+      <label>
+        Statistics over{" "}
+        <select value={days ?? ""} onChange={(e) => setDays(e.target.value === "" ? undefined : Number(e.target.value))}>
+          <option value="7">7 days</option>
+          <option value="30">30 days</option>
+          <option value="">all runs</option>
+        </select>
+      </label>
+      {error && (
+        <div className="alert" role="alert">
+          {error}
+        </div>
+      )}
+      {stats.length > 0 && (
+        <table aria-label="Profile statistics">
+          <thead>
+            <tr>
+              <th>Profile</th>
+              <th title="Completed share of runs that ended on their own">Success</th>
+              <th>Iterations OK</th>
+              <th>Iteration</th>
+              <th>Capture</th>
+              <th>LLM</th>
+              <th title="Network waits and rejected risky prompts">Interventions / run</th>
+              <th>Top failure</th>
+            </tr>
+          </thead>
+          <tbody>
+            {stats.map((s) => (
+              <tr key={s.profile_id}>
+                <td>
+                  {s.profile_id} <span className="muted">({s.runs} runs)</span>
+                </td>
+                <td>{percent(s.success_rate)}</td>
+                <td>{percent(s.iteration_success_rate)}</td>
+                <td>{millis(s.avg_iteration_ms)}</td>
+                <td>{millis(s.avg_capture_ms)}</td>
+                <td>{millis(s.avg_llm_ms)}</td>
+                <td>{s.interventions_per_run.toFixed(1)}</td>
+                <td>{s.failure_reasons[0] ? `${s.failure_reasons[0].reason} (${s.failure_reasons[0].runs}×)` : "–"}</td>
+              </tr>
+            ))}
+          </tbody>
+        </table>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,135 @@
+import { useEffect, useState } from "react";
+import { SyncConfig, SyncStatus } from "../types";
+import { profileSyncGet, profileSyncNow, profileSyncSet, profileSyncStatus } from "../tauriBridge";
+
+type Kind = "off" | "url" | "git";
+
+function describeStatus(status: SyncStatus): string {
+  const when = new Date(status.at_ms).toLocaleString();
+  if (status.error) return `Last sync failed (${when}): ${status.error}`;
+  const r = status.report;
+  if (!r) return `Last sync: ${when}`;
+  const changes = r.added.length + r.updated.length;
+  return changes
+    ? `Last sync ${when}: ${r.added.length} added, ${r.updated.length} updated`
+    : `Last sync ${when}: up to date`;
+}
+
+/** Settings for pulling team-managed profiles from an HTTPS URL or git repository */
+export function ProfileSyncSettings() {
+  const [kind, setKind] = useState<Kind>("off");
+  const [url, setUrl] = useState("");
+  const [repo, setRepo] = useState("");
+  const [branch, setBranch] = useState("");
+  const [path, setPath] = useState("");
+  const [sha256, setSha256] = useState("");
+  const [publicKey, setPublicKey] = useState("");
+  const [intervalMinutes, setIntervalMinutes] = useState(60);
+  const [message, setMessage] = useState("");
+
+  useEffect(() => {
+    profileSyncGet()
+      .then((config) => {
+        if (!config) return;
+        setKind(config.source.kind);
+        if (config.source.kind === "url") {
+          setUrl(config.source.url);
+        } else {
+          setRepo(config.source.repo);
+          setBranch(config.source.branch ?? "");
+          setPath(config.source.path);
+        }
+        setSha256(config.sha256 ?? "");
+        setPublicKey(config.public_key ?? "");
+        setIntervalMinutes(config.interval_minutes);
+      })
+      .catch(() => { });
+    profileSyncStatus()
+      .then((status) => status && setMessage(describeStatus(status)))
+      .catch(() => { });
+  }, []);
+
+  const toConfig = (): SyncConfig | null => {
+    if (kind === "off") return null;
+    return {
+      source: kind === "url"
+        ? { kind: "url", url: url.trim() }
+        : { kind: "git", repo: repo.trim(), branch: branch.trim() || null, path: path.trim() },
+      interval_minutes: intervalMinutes,
+      sha256: sha256.trim() || null,
+      public_key: publicKey.trim() || null,
+    };
+  };
+
+  const save = async () => {
+    try {
+      await profileSyncSet(toConfig());
+      setMessage(kind === "off" ? "✓ Profile sync turned off" : "✓ Sync settings saved");
+    } catch (error) {
+      setMessage(`Error: ${error}`);
+    }
+  };
+
+  const syncNow = async () => {
+    try {
+      await profileSyncSet(toConfig());
+      const report = await profileSyncNow();
+      setMessage(describeStatus({ at_ms: Date.now(), report }));
+    } catch (error) {
+      setMessage(`Error: ${error}`);
+    }
+  };
+
+  return (
+    <div style={{ display: "flex", flexDirection: "column", gap: 10 }}>
+      <div style={{ fontSize: 13, opacity: 0.8 }}>
+        Pull profiles managed by your team. The bundle must match a pinned SHA-256, or carry a <code>.sig</code> file signed with the key whose public half you enter; synced profiles replace local ones with the same id.
+      </div>
+      <label style={{ display: "flex", alignItems: "center", gap: 12 }}>
+        <span style={{ width: 100 }}>Source:</span>
+        <select aria-label="Sync source" value={kind} onChange={(e) => setKind(e.target.value as Kind)}>
+          <option value="off">Off</option>
+          <option value="url">HTTPS URL</option>
+          <option value="git">Git repository</option>
+        </select>
+      </label>
+      {kind === "url" && (
+        <input type="text" aria-label="Bundle URL" value={url} onChange={(e) => setUrl(e.target.value)} placeholder="https://example.com/team/profiles.json" />
+      )}
+      {kind === "git" && (
+        <>
+          <input type="text" aria-label="Repository" value={repo} onChange={(e) => setRepo(e.target.value)} placeholder="git@github.com:team/automation.git" />
+          <input type="text" aria-label="Branch" value={branch} onChange={(e) => setBranch(e.target.value)} placeholder="Branch (default branch if empty)" />
+          <input type="text" aria-label="Bundle path" value={path} onChange={(e) => setPath(e.target.value)} placeholder="profiles/team.yaml" />
+        </>
+      )}
+      {kind !== "off" && (
+        <>
+          <input type="text" aria-label="Pinned SHA-256" value={sha256} onChange={(e) => setSha256(e.target.value)} placeholder="Pinned SHA-256" />
+          <input type="text" aria-label="Signing public key" value={publicKey} onChange={(e) => setPublicKey(e.target.value)} placeholder="Ed25519 public key (base64 or PEM)" />
+          <label style={{ display: "flex", alignItems: "center", gap: 12 }}>
+            <span style={{ width: 100 }}>Every:</span>
+            <input
+              type="number"
+              aria-label="Sync interval in minutes"
+              min={0}
+              value={intervalMinutes}
+              onChange={(e) => setIntervalMinutes(Math.max(0, Math.floor(Number(e.target.value) || 0)))}
+              style={{ width: 80 }}
+            />
+            <span style={{ fontSize: 12, opacity: 0.7 }}>minutes (0 = manual only)</span>
+          </label>
+        </>
+      )}
+      <div style={{ display: "flex", gap: 8 }}>
+        <button onClick={save}>Save sync settings</button>
+        <button onClick={syncNow} disabled={kind === "off"}>Sync now</button>
+      </div>
+      {message && (
+        <div role="status" style={{ fontSize: 13, color: message.startsWith("Error") || message.startsWith("Last sync failed") ? "#d32f2f" : undefined }}>
+          {message}
+        </div>
+      )}
+    </div>
+  );
+}
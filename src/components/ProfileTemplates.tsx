@@ -0,0 +1,127 @@
+import { useEffect, useState } from "react";
+import { Profile, ProfileTemplate, Rect, TemplateParameter } from "../types";
+import { createFromTemplate, listTemplates } from "../tauriBridge";
+
+type Props = {
+  onCreated: (profile: Profile) => void;
+};
+
+/** Text form of a parameter value; lists are edited as comma-separated text */
+function toText(value: unknown): string {
+  return Array.isArray(value) ? value.join(", ") : String(value ?? "");
+}
+
+/** Parse edited text back into the type of the parameter's default */
+function fromText(param: TemplateParameter, text: string): unknown {
+  if (Array.isArray(param.default)) {
+    return text.split(",").map((s) => s.trim()).filter(Boolean);
+  }
+  if (typeof param.default === "number") {
+    const n = Number(text);
+    if (text.trim() === "" || !Number.isFinite(n)) throw new Error(`${param.label} must be a number`);
+    return n;
+  }
+  return text;
+}
+
+function parseRect(name: string, text: string): Rect {
+  const parts = text.split(",").map((s) => Number(s.trim()));
+  if (parts.length !== 4 || parts.some((n) => !Number.isInteger(n) || n < 0)) {
+    throw new Error(`Enter “${name}” as x, y, width, height`);
+  }
+  const [x, y, width, height] = parts;
+  return { x, y, width, height };
+}
+
+/** Create a profile from one of the built-in templates */
+export function ProfileTemplates({ onCreated }: Props) {
+  const [templates, setTemplates] = useState<ProfileTemplate[]>([]);
+  const [templateId, setTemplateId] = useState("");
+  const [name, setName] = useState("");
+  const [values, setValues] = useState<Record<string, string>>({});
+  const [regions, setRegions] = useState<Record<string, string>>({});
+  const [status, setStatus] = useState<{ ok: boolean; message: string } | null>(null);
+
+  useEffect(() => {
+    listTemplates()
+      .then(setTemplates)
+      .catch(() => setTemplates([]));
+  }, []);
+
+  const template = templates.find((t) => t.id === templateId) ?? null;
+
+  const selectTemplate = (id: string) => {
+    const next = templates.find((t) => t.id === id);
+    setTemplateId(id);
+    setName(next?.name ?? "");
+    setValues(Object.fromEntries((next?.parameters ?? []).map((p) => [p.key, toText(p.default)])));
+    setRegions({});
+    setStatus(null);
+  };
+
+  const create = async () => {
+    if (!template) return;
+    try {
+      const profile = await createFromTemplate(template.id, {
+        name,
+        regions: Object.fromEntries(template.regions.map((r) => [r.id, parseRect(r.name, regions[r.id] ?? "")])),
+        values: Object.fromEntries(template.parameters.map((p) => [p.key, fromText(p, values[p.key] ?? "")])),
+      });
+      onCreated(profile);
+      setStatus({ ok: true, message: `Created “${profile.name}”` });
+    } catch (e: any) {
+      setStatus({ ok: false, message: e?.message ?? String(e) });
+    }
+  };
+
+  if (templates.length === 0) return null;
+
+  return (
+    <div className="profile-templates" role="group" aria-label="Profile templates">
+      <select aria-label="Template" value={templateId} onChange={(e) => selectTemplate(e.target.value)}>
+        <option value="">New profile from template…</option>
+        {templates.map((t) => (
+          <option key={t.id} value={t.id}>
+            {t.name}
+          </option>
+        ))}
+      </select>
+      {template && (
+        <>
+          <p className="muted">{template.description}</p>
+          <label>
+            Profile name
+            <input type="text" value={name} onChange={(e) => setName(e.target.value)} />
+          </label>
+          {template.regions.map((r) => (
+            <label key={r.id}>
+              {r.name}
+              <input
+                type="text"
+                value={regions[r.id] ?? ""}
+                onChange={(e) => setRegions({ ...regions, [r.id]: e.target.value })}
+                placeholder="x, y, width, height"
+              />
+            </label>
+          ))}
+          {template.parameters.map((p) => (
+            <label key={p.key}>
+              {p.label}
+              <input
+                type="text"
+                value={values[p.key] ?? ""}
+                onChange={(e) => setValues({ ...values, [p.key]: e.target.value })}
+              />
+            </label>
+          ))}
+          <button onClick={create}>Create profile</button>
+        </>
+      )}
+      {status && (
+        <span className={status.ok ? "muted" : "alert"} role={status.ok ? "status" : "alert"}>
+          {status.message}
+        </span>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,59 @@
+import { useState } from "react";
+import { Profile } from "../types";
+import { profileExport, profileImport } from "../tauriBridge";
+
+type Props = {
+  selectedProfile: Profile | null;
+  onImported: (profile: Profile) => void;
+};
+
+/** Share single profiles between machines as standalone JSON/YAML files */
+export function ProfileTransfer({ selectedProfile, onImported }: Props) {
+  const [path, setPath] = useState("");
+  const [status, setStatus] = useState<{ ok: boolean; message: string } | null>(null);
+
+  const run = async (task: () => Promise<string>) => {
+    try {
+      setStatus({ ok: true, message: await task() });
+    } catch (e: any) {
+      setStatus({ ok: false, message: e?.message ?? String(e) });
+    }
+  };
+
+  const exportSelected = () =>
+    run(async () => {
+      if (!selectedProfile) throw new Error("Select a profile to export");
+      await profileExport(selectedProfile.id, path.trim());
+      return `Exported “${selectedProfile.name}”`;
+    });
+
+  const importFile = () =>
+    run(async () => {
+      const imported = await profileImport(path.trim());
+      onImported(imported);
+      return `Imported “${imported.name}”`;
+    });
+
+  return (
+    <div className="profile-transfer" role="group" aria-label="Profile import and export">
+      <input
+        type="text"
+        aria-label="Profile file path"
+        value={path}
+        onChange={(e) => setPath(e.target.value)}
+        placeholder="/path/to/profile.yaml"
+      />
+      <button onClick={exportSelected} disabled={!path.trim() || !selectedProfile} title="Write the selected profile to this file (.yaml/.yml for YAML, otherwise JSON)">
+        Export
+      </button>
+      <button onClick={importFile} disabled={!path.trim()} title="Validate and add the profile stored in this file">
+        Import
+      </button>
+      {status && (
+        <span className={status.ok ? "muted" : "alert"} role={status.ok ? "status" : "alert"}>
+          {status.message}
+        </span>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,48 @@
+import { Profile, VoicePhrases } from "../types";
+
+interface ProfileVoiceEditorProps {
+  profile: Profile;
+  onProfileChange: (profile: Profile) => void;
+}
+
+export function ProfileVoiceEditor({ profile, onProfileChange }: ProfileVoiceEditorProps) {
+  const voice = profile.voice ?? {};
+
+  const setPhrase = (key: keyof VoicePhrases, value: string) => {
+    const next: VoicePhrases = { ...voice };
+    if (value.trim()) {
+      next[key] = value;
+    } else {
+      delete next[key];
+    }
+    const updated: Profile = { ...profile };
+    if (next.start || next.stop) {
+      updated.voice = next;
+    } else {
+      delete updated.voice;
+    }
+    onProfileChange(updated);
+  };
+
+  return (
+    <div className="guardrail-controls" aria-label="Voice commands">
+      <div className="guardrail-label">
+        <span className="panel-icon" aria-hidden="true">🎙</span>
+        <strong>Voice commands</strong>
+      </div>
+      <div
+        className="notification-row"
+        title="Said after the wake word set under Settings → Voice Commands. Start also resumes the profile when it is paused."
+      >
+        <label>
+          Start phrase
+          <input type="text" placeholder="continue" value={voice.start ?? ""} onChange={(e) => setPhrase("start", e.target.value)} />
+        </label>
+        <label>
+          Stop phrase
+          <input type="text" placeholder="stop" value={voice.stop ?? ""} onChange={(e) => setPhrase("stop", e.target.value)} />
+        </label>
+      </div>
+    </div>
+  );
+}
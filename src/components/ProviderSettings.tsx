@@ -0,0 +1,118 @@
+import { useEffect, useState } from "react";
+import { KeyMetadata, ProviderKind, ProviderStatus } from "../types";
+import { deleteProvider, listProviders, setProvider } from "../tauriSecureStorage";
+
+const KINDS: { kind: ProviderKind; label: string; needsKey: boolean; needsUrl: boolean }[] = [
+  { kind: "openai", label: "OpenAI", needsKey: true, needsUrl: false },
+  { kind: "anthropic", label: "Anthropic", needsKey: true, needsUrl: false },
+  { kind: "gemini", label: "Gemini", needsKey: true, needsUrl: false },
+  { kind: "azure", label: "Azure OpenAI", needsKey: true, needsUrl: true },
+  { kind: "ollama", label: "Ollama", needsKey: false, needsUrl: true },
+  { kind: "custom", label: "Custom endpoint", needsKey: true, needsUrl: true },
+];
+
+const info = (kind: ProviderKind) => KINDS.find((k) => k.kind === kind)!;
+
+const ago = (ms: number) => {
+  const days = Math.floor((Date.now() - ms) / (24 * 60 * 60 * 1000));
+  return days === 0 ? "today" : days === 1 ? "1 day ago" : `${days} days ago`;
+};
+
+const keyAge = (key: KeyMetadata) =>
+  `set ${ago(key.set_at_ms)}${key.last_used_ms ? `, last worked ${ago(key.last_used_ms)}` : ""}`;
+
+/** Credentials for LLM providers; keys go to the OS keyring and are never shown again */
+export function ProviderSettings() {
+  const [providers, setProviders] = useState<ProviderStatus[]>([]);
+  const [kind, setKind] = useState<ProviderKind>("anthropic");
+  const [name, setName] = useState("");
+  const [apiKey, setApiKey] = useState("");
+  const [baseUrl, setBaseUrl] = useState("");
+  const [model, setModel] = useState("");
+  const [message, setMessage] = useState("");
+
+  const refresh = () =>
+    listProviders()
+      .then(setProviders)
+      .catch(() => { });
+
+  useEffect(() => {
+    refresh();
+  }, []);
+
+  const save = async () => {
+    const id = kind === "custom" ? `custom:${name.trim()}` : kind;
+    try {
+      await setProvider(id, {
+        kind,
+        ...(apiKey ? { api_key: apiKey } : {}),
+        base_url: baseUrl.trim() || null,
+        model: model.trim() || null,
+      });
+      setApiKey("");
+      setMessage(`✓ ${info(kind).label} saved`);
+      await refresh();
+    } catch (error) {
+      setMessage(`Error: ${error}`);
+    }
+  };
+
+  const remove = async (id: string) => {
+    try {
+      await deleteProvider(id);
+      setMessage(`✓ Removed ${id}`);
+      await refresh();
+    } catch (error) {
+      setMessage(`Error: ${error}`);
+    }
+  };
+
+  const { needsKey, needsUrl } = info(kind);
+
+  return (
+    <div style={{ display: "flex", flexDirection: "column", gap: 10 }}>
+      <div style={{ fontSize: 13, opacity: 0.8 }}>
+        Credentials for other LLM providers. Saving again without a key keeps the stored one.
+      </div>
+      {providers.length === 0 ? (
+        <p className="muted">No providers configured.</p>
+      ) : (
+        <ul aria-label="Configured providers" style={{ margin: 0, paddingLeft: 18 }}>
+          {providers.map((p) => (
+            <li key={p.id} style={{ display: "flex", alignItems: "center", gap: 8 }}>
+              <strong>{p.id}</strong>
+              {p.model && <span>{p.model}</span>}
+              {p.base_url && <span style={{ fontFamily: "monospace", opacity: 0.7 }}>{p.base_url}</span>}
+              <span>{p.has_key ? "✓ key stored" : "no key"}</span>
+              {p.key && <span style={{ fontSize: 12, opacity: 0.7 }}>{keyAge(p.key)}</span>}
+              <button aria-label={`Remove ${p.id}`} onClick={() => remove(p.id)}>Remove</button>
+            </li>
+          ))}
+        </ul>
+      )}
+      <div style={{ display: "flex", gap: 8, flexWrap: "wrap" }}>
+        <select aria-label="Provider" value={kind} onChange={(e) => setKind(e.target.value as ProviderKind)}>
+          {KINDS.map((k) => (
+            <option key={k.kind} value={k.kind}>{k.label}</option>
+          ))}
+        </select>
+        {kind === "custom" && (
+          <input type="text" aria-label="Endpoint name" value={name} onChange={(e) => setName(e.target.value)} placeholder="lm-studio" />
+        )}
+        {needsKey && (
+          <input type="password" aria-label="Provider API key" value={apiKey} onChange={(e) => setApiKey(e.target.value)} placeholder="API key" style={{ fontFamily: "monospace" }} />
+        )}
+        {needsUrl && (
+          <input type="text" aria-label="Base URL" value={baseUrl} onChange={(e) => setBaseUrl(e.target.value)} placeholder="http://localhost:11434" style={{ flex: 1 }} />
+        )}
+        <input type="text" aria-label="Model" value={model} onChange={(e) => setModel(e.target.value)} placeholder="Model (optional)" />
+        <button onClick={save}>Save provider</button>
+      </div>
+      {message && (
+        <div role="status" style={{ fontSize: 13, color: message.startsWith("Error") ? "#d32f2f" : undefined }}>
+          {message}
+        </div>
+      )}
+    </div>
+  );
+}
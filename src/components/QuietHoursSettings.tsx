@@ -0,0 +1,99 @@
+import { useEffect, useState } from "react";
+import { NotificationEvent, QuietHours } from "../types";
+import { quietHoursGet, quietHoursSet } from "../tauriBridge";
+import { RunWindowList } from "./ProfileRunWindowsEditor";
+
+const DEFAULT_QUIET_HOURS: QuietHours = {
+  do_not_disturb: false,
+  windows: [],
+  log_suppressed: false,
+  critical: ["risk_exceeded"],
+};
+
+const CRITICAL_EVENTS: { key: NotificationEvent; label: string }[] = [
+  { key: "risk_exceeded", label: "Risk exceeded" },
+  { key: "intervention_needed", label: "Needs intervention" },
+  { key: "failed", label: "Failed" },
+];
+
+/** Do-not-disturb and weekly quiet hours for sounds and desktop notifications */
+export function QuietHoursSettings() {
+  const [quietHours, setQuietHours] = useState<QuietHours>(DEFAULT_QUIET_HOURS);
+  const [message, setMessage] = useState("");
+
+  useEffect(() => {
+    quietHoursGet()
+      .then((saved) => saved && setQuietHours(saved))
+      .catch(() => { });
+  }, []);
+
+  const save = async (next: QuietHours, confirmation: string) => {
+    try {
+      await quietHoursSet(next);
+      setQuietHours(next);
+      setMessage(confirmation);
+    } catch (error) {
+      setMessage(`Error: ${error}`);
+    }
+  };
+
+  const toggleCritical = (event: NotificationEvent, on: boolean) => {
+    const critical = CRITICAL_EVENTS.map((c) => c.key).filter((k) => (k === event ? on : quietHours.critical.includes(k)));
+    setQuietHours({ ...quietHours, critical });
+  };
+
+  return (
+    <div style={{ display: "flex", flexDirection: "column", gap: 10 }}>
+      <div style={{ fontSize: 13, opacity: 0.8 }}>
+        Hold back sounds and desktop notifications. Webhooks and email are still sent.
+      </div>
+      <label style={{ display: "flex", alignItems: "center", gap: 12, cursor: "pointer" }}>
+        <input
+          type="checkbox"
+          checked={quietHours.do_not_disturb}
+          onChange={(e) =>
+            save(
+              { ...quietHours, do_not_disturb: e.target.checked },
+              e.target.checked ? "✓ Do not disturb is on" : "✓ Do not disturb is off",
+            )
+          }
+        />
+        <span>Do not disturb</span>
+      </label>
+      <div className="guardrail-controls" aria-label="Quiet hours">
+        {quietHours.windows.length === 0 && <p className="muted">No scheduled quiet hours.</p>}
+        <RunWindowList windows={quietHours.windows} onChange={(windows) => setQuietHours({ ...quietHours, windows })} />
+      </div>
+      <div style={{ display: "flex", alignItems: "center", gap: 12, flexWrap: "wrap" }}>
+        <span>Always notify:</span>
+        {CRITICAL_EVENTS.map(({ key, label }) => (
+          <label key={key} style={{ display: "flex", alignItems: "center", gap: 6 }}>
+            <input
+              type="checkbox"
+              aria-label={`Always notify: ${label}`}
+              checked={quietHours.critical.includes(key)}
+              onChange={(e) => toggleCritical(key, e.target.checked)}
+            />
+            {label}
+          </label>
+        ))}
+      </div>
+      <label style={{ display: "flex", alignItems: "center", gap: 6 }}>
+        <input
+          type="checkbox"
+          checked={quietHours.log_suppressed}
+          onChange={(e) => setQuietHours({ ...quietHours, log_suppressed: e.target.checked })}
+        />
+        Log held-back notifications in the event log
+      </label>
+      <div style={{ display: "flex", gap: 8 }}>
+        <button onClick={() => save(quietHours, "✓ Quiet hours saved")}>Save quiet hours</button>
+      </div>
+      {message && (
+        <div role="status" style={{ fontSize: 13, color: message.startsWith("Error") ? "#d32f2f" : undefined }}>
+          {message}
+        </div>
+      )}
+    </div>
+  );
+}
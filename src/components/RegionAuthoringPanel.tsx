@@ -1,14 +1,18 @@
 import { useCallback, useEffect, useState, useRef } from "react";
-import { captureRegionThumbnail, regionPickerShow } from "../tauriBridge";
-import { Rect, Region } from "../types";
+import { captureRegionThumbnail, enumerateWindows, regionAnchorCreate, regionPickerShow, startRegionPreview, stopRegionPreview } from "../tauriBridge";
+import { PickedRegion, Rect, Region, RegionAnchor, RegionPreviewFrame, WindowInfo } from "../types";
 import { subscribeEvent } from "../eventBridge";
-import { MouseIcon, RefreshIcon, TrashIcon, PlusIcon } from "./Icons";
+import { AnchorIcon, EyeIcon, MouseIcon, RefreshIcon, TrashIcon, PlusIcon } from "./Icons";
 
 type RegionPickEventPayload = {
   rect: Rect;
   thumbnail_png_base64?: string | null;
+  picked?: PickedRegion;
 };
 
+const describeDisplay = (picked?: PickedRegion) =>
+  picked ? ` on display ${picked.display_id}${picked.scale_factor !== 1 ? ` (${picked.scale_factor}× scale)` : ""}` : "";
+
 export type RegionDraft = { rect: Rect; id?: string; name?: string };
 
 type RegionAuthoringPanelProps = {
@@ -17,14 +21,26 @@ type RegionAuthoringPanelProps = {
   onRegionAdd?: (draft: RegionDraft) => Promise<void> | void;
   onRegionRemove?: (regionId: string) => Promise<void> | void;
   onRegionUpdate?: (regionId: string, newRect: Rect) => Promise<void> | void;
+  onRegionAnchor?: (regionId: string, anchor: RegionAnchor | null) => Promise<void> | void;
 };
 
+const PREVIEW_FPS = 2;
+
+// Part of a window's rectangle at non-negative desktop coordinates
+const windowRect = (w: WindowInfo): Rect => {
+  const x = Math.max(0, w.x);
+  const y = Math.max(0, w.y);
+  return { x, y, width: Math.max(0, w.width - (x - w.x)), height: Math.max(0, w.height - (y - w.y)) };
+};
+
+const signed = (n: number) => (n >= 0 ? `+${n}` : `${n}`);
+
 const toDataUrl = (value?: string | null): string | null => {
   if (!value) return null;
   return value.startsWith("data:") ? value : `data:image/png;base64,${value}`;
 };
 
-export function RegionAuthoringPanel({ regions, disabled, onRegionAdd, onRegionRemove, onRegionUpdate }: RegionAuthoringPanelProps) {
+export function RegionAuthoringPanel({ regions, disabled, onRegionAdd, onRegionRemove, onRegionUpdate, onRegionAnchor }: RegionAuthoringPanelProps) {
   const regionCount = regions?.length ?? 0;
   const [pending, setPending] = useState<{ rect: Rect; thumbnail?: string | null } | null>(null);
   const [pendingId, setPendingId] = useState("");
@@ -34,8 +50,13 @@ export function RegionAuthoringPanel({ regions, disabled, onRegionAdd, onRegionR
   const [error, setError] = useState<string | null>(null);
   const [thumbnails, setThumbnails] = useState<Record<string, string | null>>({});
   const [thumbLoading, setThumbLoading] = useState<string | null>(null);
+  const [liveRegionId, setLiveRegionId] = useState<string | null>(null);
+  const [windows, setWindows] = useState<WindowInfo[]>([]);
+  const liveRegionIdRef = useRef<string | null>(null);
   // Use ref to avoid re-subscribing when redefining ID changes
   const redefiningRegionIdRef = useRef<string | null>(null);
+  // Region whose anchor is being picked
+  const anchoringRegionRef = useRef<Region | null>(null);
   const idCounterRef = useRef(0);
 
   const nextRegionId = useCallback(() => {
@@ -49,6 +70,24 @@ export function RegionAuthoringPanel({ regions, disabled, onRegionAdd, onRegionR
       if (!payload) return;
       setOverlayActive(false);
 
+      // The picked area becomes the anchor of an existing region
+      const anchoring = anchoringRegionRef.current;
+      if (anchoring && onRegionAnchor) {
+        anchoringRegionRef.current = null;
+        regionAnchorCreate(anchoring.rect, payload.rect)
+          .then((anchor) => onRegionAnchor(anchoring.id, anchor))
+          .then(() => {
+            setStatus("Region anchored — it now follows the picked area on screen.");
+            setError(null);
+          })
+          .catch((err) => {
+            const msg = err instanceof Error ? err.message : String(err);
+            setError(msg || "Unable to anchor region");
+            setStatus(null);
+          });
+        return;
+      }
+
       // Check if we're redefining an existing region
       const redefiningId = redefiningRegionIdRef.current;
       if (redefiningId && onRegionUpdate) {
@@ -57,7 +96,7 @@ export function RegionAuthoringPanel({ regions, disabled, onRegionAdd, onRegionR
           setThumbnails((prev) => ({ ...prev, [redefiningId]: payload.thumbnail_png_base64 ?? null }));
         }
         redefiningRegionIdRef.current = null;
-        setStatus("Region redefined successfully.");
+        setStatus(`Region redefined successfully${describeDisplay(payload.picked)}.`);
         setError(null);
         return;
       }
@@ -69,7 +108,7 @@ export function RegionAuthoringPanel({ regions, disabled, onRegionAdd, onRegionR
       });
       setPendingId(nextRegionId());
       setPendingName(`Region ${regionCount + 1}`);
-      setStatus("Region captured — review details below.");
+      setStatus(`Region captured${describeDisplay(payload.picked)} — review details below.`);
       setError(null);
     }).then((off) => (dispose = off));
     return () => {
@@ -79,7 +118,86 @@ export function RegionAuthoringPanel({ regions, disabled, onRegionAdd, onRegionR
         // ignore
       }
     };
-  }, [regionCount, onRegionUpdate, nextRegionId]);
+  }, [regionCount, onRegionUpdate, onRegionAnchor, nextRegionId]);
+
+  // Live preview frames replace the region's thumbnail as they arrive
+  useEffect(() => {
+    let dispose: (() => void) | undefined;
+    subscribeEvent<RegionPreviewFrame>("loopautoma://region_preview", (frame) => {
+      if (!frame) return;
+      setThumbnails((prev) => ({ ...prev, [frame.region_id]: frame.png_base64 }));
+    }).then((off) => (dispose = off));
+    return () => {
+      try {
+        dispose?.();
+      } catch {
+        // ignore
+      }
+      if (liveRegionIdRef.current) {
+        stopRegionPreview(liveRegionIdRef.current).catch(() => undefined);
+      }
+    };
+  }, []);
+
+  useEffect(() => {
+    if (disabled) return;
+    let cancelled = false;
+    enumerateWindows()
+      .then((list) => {
+        if (cancelled) return;
+        setWindows(
+          list.filter((w) => {
+            const rect = windowRect(w);
+            return !w.minimized && rect.width > 0 && rect.height > 0;
+          })
+        );
+      })
+      .catch((err) => console.warn("window listing failed", err));
+    return () => {
+      cancelled = true;
+    };
+  }, [disabled]);
+
+  const regionFromWindow = useCallback(
+    async (windowId: string) => {
+      const win = windows.find((w) => String(w.id) === windowId);
+      if (!win) return;
+      const rect = windowRect(win);
+      setPending({ rect, thumbnail: null });
+      setPendingId(nextRegionId());
+      setPendingName(win.title);
+      setStatus(`Region taken from window “${win.title}” (${win.process_name}) — review details below.`);
+      setError(null);
+      try {
+        const thumbnail = await captureRegionThumbnail(rect);
+        setPending((current) => (current?.rect === rect ? { rect, thumbnail } : current));
+      } catch (err) {
+        console.warn("thumbnail capture failed", err);
+      }
+    },
+    [windows, nextRegionId]
+  );
+
+  const toggleLivePreview = useCallback(async (regionId: string) => {
+    const current = liveRegionIdRef.current;
+    setError(null);
+    try {
+      if (current) {
+        await stopRegionPreview(current);
+      }
+      const next = current === regionId ? null : regionId;
+      if (next) {
+        await startRegionPreview(next, PREVIEW_FPS);
+      }
+      liveRegionIdRef.current = next;
+      setLiveRegionId(next);
+    } catch (err) {
+      const msg = err instanceof Error ? err.message : String(err);
+      setError(msg || "Unable to start live preview");
+      liveRegionIdRef.current = null;
+      setLiveRegionId(null);
+    }
+  }, []);
 
   const launchOverlay = useCallback(async () => {
     if (disabled) {
@@ -191,6 +309,23 @@ export function RegionAuthoringPanel({ regions, disabled, onRegionAdd, onRegionR
     }
   }, []);
 
+  const anchorRegion = useCallback(async (region: Region) => {
+    anchoringRegionRef.current = region;
+    setError(null);
+    setStatus("Opening overlay to pick the anchor…");
+    try {
+      await regionPickerShow();
+      setOverlayActive(true);
+      setStatus("Overlay active — drag over a label or icon near the region; the region will follow it.");
+    } catch (err) {
+      const msg = err instanceof Error ? err.message : String(err);
+      setError(msg || "Unable to open overlay");
+      setStatus(null);
+      setOverlayActive(false);
+      anchoringRegionRef.current = null;
+    }
+  }, []);
+
   useEffect(() => {
     if (!regions || regions.length === 0) {
       if (Object.keys(thumbnails).length > 0) {
@@ -235,6 +370,22 @@ export function RegionAuthoringPanel({ regions, disabled, onRegionAdd, onRegionR
           <MouseIcon size={20} />
           <span className="sr-only">Define watch region</span>
         </button>
+        {windows.length > 0 && (
+          <select
+            aria-label="From window"
+            value=""
+            onChange={(e) => regionFromWindow(e.target.value)}
+            disabled={disabled}
+            title="Use the rectangle of an open window as the region"
+          >
+            <option value="">From window…</option>
+            {windows.map((w) => (
+              <option key={w.id} value={String(w.id)}>
+                {w.title} — {w.process_name}
+              </option>
+            ))}
+          </select>
+        )}
         <div className="region-overlay-hint">
           {overlayActive
             ? "Overlay active — drag with the left mouse button, then release to capture the region and return to LoopAutoma."
@@ -308,6 +459,11 @@ export function RegionAuthoringPanel({ regions, disabled, onRegionAdd, onRegionR
                     <span>
                       ({region.rect.x}, {region.rect.y}) · {region.rect.width}×{region.rect.height}
                     </span>
+                    {region.anchor && (
+                      <span className="region-anchor">
+                        Anchored at {signed(region.anchor.offset_x)}, {signed(region.anchor.offset_y)} from its template
+                      </span>
+                    )}
                     <div className="region-controls">
                       <button
                         type="button"
@@ -320,6 +476,17 @@ export function RegionAuthoringPanel({ regions, disabled, onRegionAdd, onRegionR
                         <RefreshIcon size={16} />
                         <span className="sr-only">Refresh thumbnail</span>
                       </button>
+                      <button
+                        type="button"
+                        className={`icon-button${liveRegionId === region.id ? " accent" : ""}`}
+                        onClick={() => toggleLivePreview(region.id)}
+                        aria-pressed={liveRegionId === region.id}
+                        title={liveRegionId === region.id ? "Stop live preview" : "Show what this region sees, live"}
+                        aria-label="Live preview"
+                      >
+                        <EyeIcon size={16} />
+                        <span className="sr-only">Live preview</span>
+                      </button>
                       {onRegionUpdate && (
                         <button
                           type="button"
@@ -333,6 +500,20 @@ export function RegionAuthoringPanel({ regions, disabled, onRegionAdd, onRegionR
                           <span className="sr-only">Redefine</span>
                         </button>
                       )}
+                      {onRegionAnchor && (
+                        <button
+                          type="button"
+                          className={`icon-button${region.anchor ? " accent" : ""}`}
+                          onClick={() => (region.anchor ? onRegionAnchor(region.id, null) : anchorRegion(region))}
+                          disabled={overlayActive}
+                          aria-pressed={!!region.anchor}
+                          title={region.anchor ? "Remove anchor; use fixed coordinates again" : "Anchor to a label or icon so the region follows it on screen"}
+                          aria-label="Anchor region"
+                        >
+                          <AnchorIcon size={16} />
+                          <span className="sr-only">Anchor</span>
+                        </button>
+                      )}
                       {onRegionRemove && (
                         <button
                           type="button"
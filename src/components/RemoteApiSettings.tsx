@@ -0,0 +1,103 @@
+import { useEffect, useState } from "react";
+import { RemoteApiConfig } from "../types";
+import { remoteApiGenerateToken, remoteApiGet, remoteApiSet, remoteApiStatus } from "../tauriBridge";
+
+const EMPTY: RemoteApiConfig = { lan: false, port: 8787, token: "", tls: null };
+
+/** HTTP API to check on and control runs from another machine or a phone */
+export function RemoteApiSettings() {
+  const [enabled, setEnabled] = useState(false);
+  const [config, setConfig] = useState<RemoteApiConfig>(EMPTY);
+  const [url, setUrl] = useState<string | null>(null);
+  const [message, setMessage] = useState("");
+
+  useEffect(() => {
+    remoteApiGet()
+      .then((saved) => {
+        if (!saved) return;
+        setEnabled(true);
+        setConfig(saved);
+      })
+      .catch(() => { });
+    remoteApiStatus().then(setUrl).catch(() => { });
+  }, []);
+
+  const set = (patch: Partial<RemoteApiConfig>) => setConfig((current) => ({ ...current, ...patch }));
+  const tls = config.tls ?? { cert_path: "", key_path: "" };
+  const useTls = config.lan || config.tls !== null;
+
+  const generate = async () => {
+    try {
+      set({ token: await remoteApiGenerateToken() });
+    } catch (error) {
+      setMessage(`Error: ${error}`);
+    }
+  };
+
+  const save = async () => {
+    try {
+      const saved = await remoteApiSet(enabled ? { ...config, token: config.token.trim(), tls: useTls ? tls : null } : null);
+      setUrl(saved);
+      setMessage(saved ? `✓ Listening on ${saved}` : "✓ Remote API turned off");
+    } catch (error) {
+      setMessage(`Error: ${error}`);
+    }
+  };
+
+  return (
+    <div style={{ display: "flex", flexDirection: "column", gap: 10 }}>
+      <div style={{ fontSize: 13, opacity: 0.8 }}>
+        List profiles, start, stop, pause or resume runs, and read the status and run history over HTTP. Every request sends the token as
+        <code> Authorization: Bearer &lt;token&gt;</code>.
+      </div>
+      <label style={{ display: "flex", alignItems: "center", gap: 12, cursor: "pointer" }}>
+        <input type="checkbox" checked={enabled} onChange={(e) => setEnabled(e.target.checked)} />
+        <span>Enable remote API</span>
+      </label>
+      {enabled && (
+        <>
+          <div style={{ display: "flex", gap: 8, alignItems: "center" }}>
+            <label style={{ display: "flex", alignItems: "center", gap: 6 }}>
+              <input type="checkbox" aria-label="Allow LAN access" checked={config.lan} onChange={(e) => set({ lan: e.target.checked })} />
+              Allow access from the LAN (requires TLS)
+            </label>
+            <input
+              type="number"
+              aria-label="Remote API port"
+              min={1}
+              max={65535}
+              value={config.port || ""}
+              onChange={(e) => set({ port: Math.max(0, Math.floor(Number(e.target.value) || 0)) })}
+              style={{ width: 90 }}
+            />
+          </div>
+          <div style={{ display: "flex", gap: 8 }}>
+            <input type="password" aria-label="Remote API token" value={config.token} onChange={(e) => set({ token: e.target.value })} placeholder="Token" style={{ flex: 1 }} />
+            <button onClick={generate}>Generate token</button>
+          </div>
+          {!config.lan && (
+            <label style={{ display: "flex", alignItems: "center", gap: 6 }}>
+              <input type="checkbox" aria-label="Use TLS" checked={config.tls !== null} onChange={(e) => set({ tls: e.target.checked ? tls : null })} />
+              Use TLS
+            </label>
+          )}
+          {useTls && (
+            <div style={{ display: "flex", gap: 8 }}>
+              <input type="text" aria-label="TLS certificate file" value={tls.cert_path} onChange={(e) => set({ tls: { ...tls, cert_path: e.target.value } })} placeholder="/path/to/cert.pem" style={{ flex: 1 }} />
+              <input type="text" aria-label="TLS key file" value={tls.key_path} onChange={(e) => set({ tls: { ...tls, key_path: e.target.value } })} placeholder="/path/to/key.pem" style={{ flex: 1 }} />
+            </div>
+          )}
+        </>
+      )}
+      <div style={{ display: "flex", gap: 8, alignItems: "center" }}>
+        <button onClick={save}>Save remote API settings</button>
+        {url && <code>{url}</code>}
+      </div>
+      {message && (
+        <div role="status" style={{ fontSize: 13, color: message.startsWith("Error") ? "#d32f2f" : undefined }}>
+          {message}
+        </div>
+      )}
+    </div>
+  );
+}
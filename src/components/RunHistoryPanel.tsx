@@ -0,0 +1,228 @@
+import { useCallback, useEffect, useState } from "react";
+import { exportRunFrames, exportRunReport, getRunDetails, listRuns, pruneRuns } from "../tauriBridge";
+import { subscribeEvent } from "../eventBridge";
+import { MaskedPii, PiiKind, RunDetails, RunOutcome, RunRecord } from "../types";
+
+const PAGE_SIZE = 50;
+
+function describeOutcome(o: RunOutcome): string {
+  switch (o.type) {
+    case "Stopped":
+      return "Stopped";
+    case "Completed":
+      return `Completed: ${o.reason}`;
+    case "BudgetExceeded":
+      return `Budget exceeded: ${o.budget}`;
+    case "WatchdogTripped":
+      return `Watchdog: ${o.reason}`;
+    case "Failed":
+      return `Failed: ${o.reason}`;
+  }
+}
+
+const PII_NAMES: Record<PiiKind, [string, string]> = {
+  email: ["email", "emails"],
+  credit_card: ["card number", "card numbers"],
+  api_key: ["API key", "API keys"],
+};
+
+// Same wording as the run report, e.g. "2 emails in inbox, 1 API key in llm_prompt"
+const describeMasked = (masked: MaskedPii[]) =>
+  masked.map((m) => `${m.count} ${PII_NAMES[m.kind][m.count === 1 ? 0 : 1]} in ${m.source}`).join(", ");
+
+const formatCost = (usd: number) => `$${usd.toFixed(usd < 1 ? 4 : 2)}`;
+
+const parseCount = (value: string) => (value.trim() === "" ? undefined : Math.max(0, Math.floor(Number(value))));
+
+/** Recorded runs with their outcome and LLM spend; select one for its errors and screenshots or to export a report or animation */
+export function RunHistoryPanel() {
+  const [runs, setRuns] = useState<RunRecord[]>([]);
+  const [selected, setSelected] = useState<RunDetails | null>(null);
+  const [olderThanDays, setOlderThanDays] = useState("30");
+  const [keepLatest, setKeepLatest] = useState("");
+  const [reportPath, setReportPath] = useState("");
+  const [animationPath, setAnimationPath] = useState("");
+  const [animationRegion, setAnimationRegion] = useState("");
+  const [status, setStatus] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const reload = useCallback(async () => {
+    try {
+      setRuns(await listRuns({ limit: PAGE_SIZE }));
+      setError(null);
+    } catch (e: any) {
+      setError(e?.message ?? String(e));
+    }
+  }, []);
+
+  useEffect(() => {
+    reload();
+    let dispose: (() => void) | undefined;
+    subscribeEvent<number>("loopautoma://run_recorded", () => {
+      reload();
+    }).then((off) => (dispose = off));
+    return () => {
+      try {
+        dispose?.();
+      } catch { }
+    };
+  }, [reload]);
+
+  const select = async (run: RunRecord) => {
+    if (selected?.id === run.id) {
+      setSelected(null);
+      return;
+    }
+    try {
+      setSelected(await getRunDetails(run.id));
+      setError(null);
+    } catch (e: any) {
+      setError(e?.message ?? String(e));
+    }
+  };
+
+  const prune = async () => {
+    const days = parseCount(olderThanDays);
+    const keep = parseCount(keepLatest);
+    if (days === undefined && keep === undefined) {
+      setError("Enter an age in days or a number of runs to keep.");
+      return;
+    }
+    try {
+      const removed = await pruneRuns(days, keep);
+      setStatus(`Removed ${removed} run${removed === 1 ? "" : "s"}.`);
+      setSelected(null);
+      await reload();
+    } catch (e: any) {
+      setError(e?.message ?? String(e));
+    }
+  };
+
+  const exportAnimation = async (runId: number, regionId: string) => {
+    try {
+      const written = await exportRunFrames(runId, regionId, animationPath.trim());
+      setStatus(`Animation of ${written} frame${written === 1 ? "" : "s"} written to ${animationPath.trim()}.`);
+      setError(null);
+    } catch (e: any) {
+      setError(e?.message ?? String(e));
+    }
+  };
+
+  const frameRegions = [...new Set(selected?.frames.map((f) => f.region_id) ?? [])];
+  const selectedRegion = frameRegions.includes(animationRegion) ? animationRegion : frameRegions[0];
+
+  const exportReport = async (runId: number) => {
+    try {
+      await exportRunReport(runId, reportPath.trim());
+      setStatus(`Report written to ${reportPath.trim()}.`);
+      setError(null);
+    } catch (e: any) {
+      setError(e?.message ?? String(e));
+    }
+  };
+
+  return (
+    <div className="run-history" aria-label="Run history">
+      <div className="run-history-prune">
+        <label>
+          Older than (days)
+          <input type="number" min={0} value={olderThanDays} onChange={(e) => setOlderThanDays(e.target.value)} />
+        </label>
+        <label>
+          Keep latest
+          <input type="number" min={0} value={keepLatest} placeholder="all" onChange={(e) => setKeepLatest(e.target.value)} />
+        </label>
+        <button onClick={prune} title="Delete matching runs with their archived screenshots">
+          Prune
+        </button>
+      </div>
+      {status && <p className="muted">{status}</p>}
+      {error && (
+        <div className="alert" role="alert">
+          {error}
+        </div>
+      )}
+      {runs.length === 0 ? (
+        <p className="muted">No runs recorded yet.</p>
+      ) : (
+        <ul className="run-history-list">
+          {runs.map((run) => (
+            <li key={run.id}>
+              <button className="ghost" onClick={() => select(run)} aria-expanded={selected?.id === run.id}>
+                <span className="muted">{new Date(run.started_at_ms).toLocaleString()}</span> {run.profile_id} —{" "}
+                {describeOutcome(run.outcome)} · {run.iterations} iterations · {run.llm_calls} LLM calls ({formatCost(run.llm_cost_usd)})
+                {run.error_count > 0 && ` · ${run.error_count} errors`}
+              </button>
+              {selected?.id === run.id && (
+                <div className="run-history-details">
+                  <p className="muted">
+                    Ran {Math.round((selected.ended_at_ms - selected.started_at_ms) / 1000)}s ·{" "}
+                    {selected.llm_prompt_tokens} prompt / {selected.llm_completion_tokens} completion tokens
+                  </p>
+                  {selected.errors.length > 0 && (
+                    <ul aria-label="Run errors">
+                      {selected.errors.map((e, i) => (
+                        <li key={i}>
+                          <span className="muted">{new Date(e.at_ms).toLocaleTimeString()}</span> {e.message}
+                        </li>
+                      ))}
+                    </ul>
+                  )}
+                  {selected.masked.length > 0 && <p className="muted">Masked {describeMasked(selected.masked)}</p>}
+                  {selected.screenshots.map((path) => (
+                    <code key={path}>{path}</code>
+                  ))}
+                  <div className="run-history-report">
+                    <input
+                      type="text"
+                      aria-label="Report file path"
+                      value={reportPath}
+                      onChange={(e) => setReportPath(e.target.value)}
+                      placeholder="/path/to/report.html"
+                    />
+                    <button
+                      onClick={() => exportReport(selected.id)}
+                      disabled={!reportPath.trim()}
+                      title="Write the run's timeline, LLM prompts, errors and screenshots to this file (.html for a standalone page, otherwise Markdown)"
+                    >
+                      Export report
+                    </button>
+                  </div>
+                  {selectedRegion !== undefined && (
+                    <div className="run-history-report">
+                      <select
+                        aria-label="Animation region"
+                        value={selectedRegion}
+                        onChange={(e) => setAnimationRegion(e.target.value)}
+                      >
+                        {frameRegions.map((id) => (
+                          <option key={id} value={id}>
+                            {id}
+                          </option>
+                        ))}
+                      </select>
+                      <input
+                        type="text"
+                        aria-label="Animation file path"
+                        value={animationPath}
+                        onChange={(e) => setAnimationPath(e.target.value)}
+                        placeholder="/path/to/run.gif"
+                      />
+                      <button
+                        onClick={() => exportAnimation(selected.id, selectedRegion)}
+                        disabled={!animationPath.trim()}
+                        title="Assemble the region's archived frames, labelled with iteration and time (.mp4 needs ffmpeg, otherwise a GIF)"
+                      >
+                        Export animation
+                      </button>
+                    </div>
+                  )}
+                </div>
+              )}
+            </li>
+          ))}
+        </ul>
+      )}
+    </div>
+  );
+}
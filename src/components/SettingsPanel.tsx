@@ -1,7 +1,26 @@
 import { useState, useEffect } from "react";
 import { getOpenAIKeyStatus, setOpenAIKey, deleteOpenAIKey, getOpenAIModel, setOpenAIModel } from "../tauriSecureStorage";
-import { audioTestIntervention, audioTestCompleted, audioSetEnabled, audioGetEnabled, audioSetVolume, audioGetVolume } from "../tauriBridge";
+import { audioTestIntervention, audioTestCompleted, audioSetEnabled, audioGetEnabled, audioSetVolume, audioGetVolume, profilesEncryptionGet, profilesEncryptionSet } from "../tauriBridge";
+import { subscribeEvent } from "../eventBridge";
+import { SettingChange } from "../types";
+import { ProfileSyncSettings } from "./ProfileSyncSettings";
+import { WebhookSettings } from "./WebhookSettings";
+import { EmailSettings } from "./EmailSettings";
+import { RemoteApiSettings } from "./RemoteApiSettings";
+import { MetricsExporterSettings } from "./MetricsExporterSettings";
+import { TraceExportSettings } from "./TraceExportSettings";
+import { PluginsSettings } from "./PluginsSettings";
+import { VoiceCommandSettings } from "./VoiceCommandSettings";
+import { PointerCalibration } from "./PointerCalibration";
+import { AutomationStatus } from "./AutomationStatus";
+import { CustomSoundSettings } from "./CustomSoundSettings";
+import { AlertSoundMapSettings } from "./AlertSoundMapSettings";
+import { QuietHoursSettings } from "./QuietHoursSettings";
+import { LogViewer } from "./LogViewer";
 import { ModelSelector } from "./ModelSelector";
+import { ProviderSettings } from "./ProviderSettings";
+import { EnvCredentialImport } from "./EnvCredentialImport";
+import { SettingsTransfer } from "./SettingsTransfer";
 
 interface SettingsPanelProps {
     isOpen: boolean;
@@ -32,12 +51,32 @@ export function SettingsPanel({
     const [audioVolume, setAudioVolumeState] = useState(0.5);
     const [audioStatusMessage, setAudioStatusMessage] = useState("");
 
+    // Profile storage state
+    const [profilesEncrypted, setProfilesEncrypted] = useState(false);
+    const [storageStatusMessage, setStorageStatusMessage] = useState("");
+
     useEffect(() => {
         if (isOpen) {
             loadSettings();
         }
     }, [isOpen]);
 
+    // Reload when settings change elsewhere, e.g. in another window or the tray
+    useEffect(() => {
+        if (!isOpen) return;
+        let dispose: (() => void) | undefined;
+        subscribeEvent<SettingChange>("loopautoma://settings", () => {
+            loadSettings();
+        }).then((off) => (dispose = off));
+        return () => {
+            try {
+                dispose?.();
+            } catch {
+                // ignore
+            }
+        };
+    }, [isOpen]);
+
     async function loadSettings() {
         try {
             const keyStatus = await getOpenAIKeyStatus();
@@ -52,6 +91,8 @@ export function SettingsPanel({
             
             const volume = await audioGetVolume();
             setAudioVolumeState(volume);
+
+            setProfilesEncrypted(await profilesEncryptionGet());
         } catch (error) {
             console.error("Failed to load settings:", error);
         }
@@ -315,6 +356,10 @@ export function SettingsPanel({
                                 ✅ Test Completion Sound
                             </button>
                         </div>
+
+                        <CustomSoundSettings />
+
+                        <AlertSoundMapSettings />
                         
                         {audioStatusMessage && (
                             <div
@@ -338,8 +383,125 @@ export function SettingsPanel({
                     </div>
                 </section>
 
+                {/* Profile Storage Section */}
+                <section style={{ marginBottom: 32 }}>
+                    <h3 style={{ fontSize: 18, marginBottom: 16, borderBottom: `1px solid ${theme === "dark" ? "#444" : "#ddd"}`, paddingBottom: 8 }}>
+                        Profile Storage
+                    </h3>
+                    <div style={{ display: "flex", flexDirection: "column", gap: 12 }}>
+                        <div style={{ fontSize: 13, opacity: 0.8 }}>
+                            Encrypt profiles.json with a key kept in your OS keyring. Prompts, window titles and commands are then unreadable without your login.
+                        </div>
+                        <label style={{ display: "flex", alignItems: "center", gap: 12 }}>
+                            <input
+                                type="checkbox"
+                                checked={profilesEncrypted}
+                                onChange={async (e) => {
+                                    const enabled = e.target.checked;
+                                    try {
+                                        await profilesEncryptionSet(enabled);
+                                        setProfilesEncrypted(enabled);
+                                        setStorageStatusMessage(`✓ Profiles are now stored ${enabled ? "encrypted" : "as plain JSON"}`);
+                                        setTimeout(() => setStorageStatusMessage(""), 3000);
+                                    } catch (error) {
+                                        setStorageStatusMessage(`Error: ${error}`);
+                                    }
+                                }}
+                                style={{ cursor: "pointer" }}
+                            />
+                            <span>Encrypt profiles on disk</span>
+                        </label>
+                        {storageStatusMessage && (
+                            <div style={{ fontSize: 13, color: storageStatusMessage.startsWith("✓") ? "#4caf50" : "#d32f2f" }}>
+                                {storageStatusMessage}
+                            </div>
+                        )}
+                    </div>
+                </section>
+
+                {/* Remote Profile Sync Section */}
+                <section style={{ marginBottom: 32 }}>
+                    <h3 style={{ fontSize: 18, marginBottom: 16, borderBottom: `1px solid ${theme === "dark" ? "#444" : "#ddd"}`, paddingBottom: 8 }}>
+                        Profile Sync
+                    </h3>
+                    <ProfileSyncSettings />
+                </section>
+
+                {/* Quiet Hours Section */}
+                <section style={{ marginBottom: 32 }}>
+                    <h3 style={{ fontSize: 18, marginBottom: 16, borderBottom: `1px solid ${theme === "dark" ? "#444" : "#ddd"}`, paddingBottom: 8 }}>
+                        Quiet Hours
+                    </h3>
+                    <QuietHoursSettings />
+                </section>
+
+                {/* Webhook Notifications Section */}
+                <section style={{ marginBottom: 32 }}>
+                    <h3 style={{ fontSize: 18, marginBottom: 16, borderBottom: `1px solid ${theme === "dark" ? "#444" : "#ddd"}`, paddingBottom: 8 }}>
+                        Webhook Notifications
+                    </h3>
+                    <WebhookSettings />
+                </section>
+
+                {/* Email Notifications Section */}
+                <section style={{ marginBottom: 32 }}>
+                    <h3 style={{ fontSize: 18, marginBottom: 16, borderBottom: `1px solid ${theme === "dark" ? "#444" : "#ddd"}`, paddingBottom: 8 }}>
+                        Email Notifications
+                    </h3>
+                    <EmailSettings />
+                </section>
+
+                {/* Remote API Section */}
+                <section style={{ marginBottom: 32 }}>
+                    <h3 style={{ fontSize: 18, marginBottom: 16, borderBottom: `1px solid ${theme === "dark" ? "#444" : "#ddd"}`, paddingBottom: 8 }}>
+                        Remote API
+                    </h3>
+                    <RemoteApiSettings />
+                </section>
+
+                {/* Metrics Endpoint Section */}
+                <section style={{ marginBottom: 32 }}>
+                    <h3 style={{ fontSize: 18, marginBottom: 16, borderBottom: `1px solid ${theme === "dark" ? "#444" : "#ddd"}`, paddingBottom: 8 }}>
+                        Metrics Endpoint
+                    </h3>
+                    <MetricsExporterSettings />
+                </section>
+
+                {/* Trace Export Section */}
+                <section style={{ marginBottom: 32 }}>
+                    <h3 style={{ fontSize: 18, marginBottom: 16, borderBottom: `1px solid ${theme === "dark" ? "#444" : "#ddd"}`, paddingBottom: 8 }}>
+                        Trace Export
+                    </h3>
+                    <TraceExportSettings />
+                </section>
+
+                {/* Plugins Section */}
+                <section style={{ marginBottom: 32 }}>
+                    <h3 style={{ fontSize: 18, marginBottom: 16, borderBottom: `1px solid ${theme === "dark" ? "#444" : "#ddd"}`, paddingBottom: 8 }}>
+                        Plugins
+                    </h3>
+                    <PluginsSettings />
+                </section>
+
+                {/* Voice Commands Section */}
+                <section style={{ marginBottom: 32 }}>
+                    <h3 style={{ fontSize: 18, marginBottom: 16, borderBottom: `1px solid ${theme === "dark" ? "#444" : "#ddd"}`, paddingBottom: 8 }}>
+                        Voice Commands
+                    </h3>
+                    <VoiceCommandSettings />
+                </section>
+
+                {/* Input Automation Section */}
+                <section style={{ marginBottom: 32 }}>
+                    <h3 style={{ fontSize: 18, marginBottom: 16, borderBottom: `1px solid ${theme === "dark" ? "#444" : "#ddd"}`, paddingBottom: 8 }}>
+                        Input Automation
+                    </h3>
+                    <AutomationStatus />
+                    <PointerCalibration />
+                </section>
+
                 {/* OpenAI API Key Section */}
-                <section>
+                <section style={{ marginBottom: 32 }}>
                     <h3 style={{ fontSize: 18, marginBottom: 16, borderBottom: `1px solid ${theme === "dark" ? "#444" : "#ddd"}`, paddingBottom: 8 }}>
                         OpenAI Integration
                     </h3>
@@ -478,6 +640,31 @@ export function SettingsPanel({
                         />
                     </div>
                 </section>
+
+                {/* Other LLM Providers Section */}
+                <section style={{ marginBottom: 32 }}>
+                    <h3 style={{ fontSize: 18, marginBottom: 16, borderBottom: `1px solid ${theme === "dark" ? "#444" : "#ddd"}`, paddingBottom: 8 }}>
+                        Other LLM Providers
+                    </h3>
+                    <ProviderSettings />
+                    <EnvCredentialImport />
+                </section>
+
+                {/* Logs Section */}
+                <section style={{ marginBottom: 32 }}>
+                    <h3 style={{ fontSize: 18, marginBottom: 16, borderBottom: `1px solid ${theme === "dark" ? "#444" : "#ddd"}`, paddingBottom: 8 }}>
+                        Logs
+                    </h3>
+                    <LogViewer />
+                </section>
+
+                {/* Settings Transfer Section */}
+                <section>
+                    <h3 style={{ fontSize: 18, marginBottom: 16, borderBottom: `1px solid ${theme === "dark" ? "#444" : "#ddd"}`, paddingBottom: 8 }}>
+                        Export &amp; Import Settings
+                    </h3>
+                    <SettingsTransfer />
+                </section>
             </div>
         </div>
     );
@@ -0,0 +1,67 @@
+import { useState } from "react";
+import { settingsExport, settingsImport } from "../tauriBridge";
+
+/** Move settings between machines as a JSON file; credentials stay behind and are named for re-entry */
+export function SettingsTransfer() {
+  const [path, setPath] = useState("");
+  const [status, setStatus] = useState<{ ok: boolean; message: string } | null>(null);
+  const [reenter, setReenter] = useState<string[]>([]);
+
+  const run = async (task: () => Promise<string>) => {
+    try {
+      setStatus({ ok: true, message: await task() });
+    } catch (e: any) {
+      setStatus({ ok: false, message: e?.message ?? String(e) });
+    }
+  };
+
+  const exportFile = () =>
+    run(async () => {
+      await settingsExport(path.trim());
+      setReenter([]);
+      return "✓ Settings exported without credentials";
+    });
+
+  const importFile = () =>
+    run(async () => {
+      const report = await settingsImport(path.trim());
+      setReenter(report.reenter);
+      const ignored = report.ignored.length ? ` (skipped unknown: ${report.ignored.join(", ")})` : "";
+      return `✓ Imported ${report.imported.length} settings${ignored}`;
+    });
+
+  return (
+    <div style={{ display: "flex", flexDirection: "column", gap: 10 }}>
+      <div style={{ fontSize: 13, opacity: 0.8 }}>
+        API keys, webhook URLs and logins are never written to the file.
+      </div>
+      <div style={{ display: "flex", gap: 8, flexWrap: "wrap" }}>
+        <input
+          type="text"
+          aria-label="Settings file path"
+          value={path}
+          onChange={(e) => setPath(e.target.value)}
+          placeholder="/path/to/loopautoma-settings.json"
+          style={{ flex: 1 }}
+        />
+        <button onClick={exportFile} disabled={!path.trim()}>Export settings</button>
+        <button onClick={importFile} disabled={!path.trim()}>Import settings</button>
+      </div>
+      {status && (
+        <div role={status.ok ? "status" : "alert"} style={{ fontSize: 13, color: status.ok ? undefined : "#d32f2f" }}>
+          {status.message}
+        </div>
+      )}
+      {reenter.length > 0 && (
+        <div style={{ fontSize: 13 }}>
+          Enter these again, they were not copied:
+          <ul aria-label="Secrets to re-enter" style={{ margin: "4px 0 0", paddingLeft: 18 }}>
+            {reenter.map((name) => (
+              <li key={name} style={{ fontFamily: "monospace" }}>{name}</li>
+            ))}
+          </ul>
+        </div>
+      )}
+    </div>
+  );
+}
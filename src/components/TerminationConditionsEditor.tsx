@@ -155,7 +155,7 @@ export function TerminationConditionsEditor({
       <div className="termination-section">
         <h4 className="termination-subsection-title">Timeout Settings</h4>
         <div className="form-group">
-          <label htmlFor="action-timeout" title="Maximum time (in milliseconds) allowed for each action to complete. Automation stops if exceeded.">
+          <label htmlFor="action-timeout" title="Maximum time (in milliseconds) allowed for each action to complete. An action that overruns is abandoned and the run is held until you resume it.">
             Action Timeout (ms)
             <span className="help-text">(max time per action)</span>
           </label>
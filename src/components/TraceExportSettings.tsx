@@ -0,0 +1,102 @@
+import { useEffect, useState } from "react";
+import { otlpTracesGet, otlpTracesSet, otlpTracesStatus } from "../tauriBridge";
+import { OtlpExportStatus } from "../types";
+
+// The standard OTLP/HTTP port on a local collector
+const DEFAULT_ENDPOINT = "http://localhost:4318";
+
+/** `Name: value` lines to a header map; blank lines are skipped */
+export function parseHeaders(text: string): Record<string, string> {
+  const headers: Record<string, string> = {};
+  for (const line of text.split("\n")) {
+    if (!line.trim()) continue;
+    const colon = line.indexOf(":");
+    if (colon <= 0) throw new Error(`Header line "${line.trim()}" needs the form Name: value`);
+    headers[line.slice(0, colon).trim()] = line.slice(colon + 1).trim();
+  }
+  return headers;
+}
+
+function formatHeaders(headers: Record<string, string>): string {
+  return Object.entries(headers)
+    .map(([name, value]) => `${name}: ${value}`)
+    .join("\n");
+}
+
+/** OpenTelemetry export of runs as traces, for analysing a fleet of machines in one tracing backend */
+export function TraceExportSettings() {
+  const [enabled, setEnabled] = useState(false);
+  const [endpoint, setEndpoint] = useState(DEFAULT_ENDPOINT);
+  const [headers, setHeaders] = useState("");
+  const [status, setStatus] = useState<OtlpExportStatus | null>(null);
+  const [message, setMessage] = useState("");
+
+  useEffect(() => {
+    otlpTracesGet()
+      .then((saved) => {
+        if (!saved) return;
+        setEnabled(true);
+        setEndpoint(saved.endpoint);
+        setHeaders(formatHeaders(saved.headers));
+      })
+      .catch(() => { });
+    otlpTracesStatus().then(setStatus).catch(() => { });
+  }, []);
+
+  const save = async () => {
+    try {
+      const url = await otlpTracesSet(enabled ? { endpoint: endpoint.trim(), headers: parseHeaders(headers) } : null);
+      setStatus(await otlpTracesStatus().catch(() => null));
+      setMessage(url ? `✓ Sending traces to ${url}` : "✓ Trace export turned off");
+    } catch (error) {
+      setMessage(`Error: ${error instanceof Error ? error.message : error}`);
+    }
+  };
+
+  return (
+    <div style={{ display: "flex", flexDirection: "column", gap: 10 }}>
+      <div style={{ fontSize: 13, opacity: 0.8 }}>
+        Each run is sent as a trace, with a span per iteration, action and LLM call carrying the profile, region IDs, risk and
+        outcome, over OTLP/HTTP to a collector or tracing backend. Runs are traced while the log level is info or more verbose;
+        builds need the <code>otlp-traces</code> feature.
+      </div>
+      <label style={{ display: "flex", alignItems: "center", gap: 12, cursor: "pointer" }}>
+        <input type="checkbox" checked={enabled} onChange={(e) => setEnabled(e.target.checked)} />
+        <span>Export traces</span>
+      </label>
+      {enabled && (
+        <>
+          <input
+            type="url"
+            aria-label="OTLP endpoint"
+            placeholder={DEFAULT_ENDPOINT}
+            value={endpoint}
+            onChange={(e) => setEndpoint(e.target.value)}
+          />
+          <textarea
+            aria-label="OTLP headers"
+            placeholder="x-api-key: …"
+            rows={3}
+            value={headers}
+            onChange={(e) => setHeaders(e.target.value)}
+            style={{ fontFamily: "monospace" }}
+          />
+        </>
+      )}
+      <div style={{ display: "flex", gap: 8, alignItems: "center" }}>
+        <button onClick={save}>Save trace settings</button>
+        {status && (
+          <span style={{ fontSize: 13 }}>
+            {status.exported} span(s) sent, {status.dropped} dropped
+            {status.last_error && <span style={{ color: "#d32f2f" }}> — {status.last_error}</span>}
+          </span>
+        )}
+      </div>
+      {message && (
+        <div role="status" style={{ fontSize: 13, color: message.startsWith("Error") ? "#d32f2f" : undefined }}>
+          {message}
+        </div>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,78 @@
+import { useEffect, useState } from "react";
+import { voiceCommandsGet, voiceCommandsSet, voiceCommandsStatus } from "../tauriBridge";
+
+const DEFAULT_WAKE_WORD = "loopautoma";
+
+/** Microphone listener that starts and stops profiles by their voice phrases */
+export function VoiceCommandSettings() {
+  const [enabled, setEnabled] = useState(false);
+  const [modelPath, setModelPath] = useState("");
+  const [wakeWord, setWakeWord] = useState(DEFAULT_WAKE_WORD);
+  const [phrases, setPhrases] = useState<string[] | null>(null);
+  const [message, setMessage] = useState("");
+
+  useEffect(() => {
+    voiceCommandsGet()
+      .then((saved) => {
+        if (!saved) return;
+        setEnabled(true);
+        setModelPath(saved.model_path);
+        setWakeWord(saved.wake_word ?? DEFAULT_WAKE_WORD);
+      })
+      .catch(() => { });
+    voiceCommandsStatus().then(setPhrases).catch(() => { });
+  }, []);
+
+  const save = async () => {
+    try {
+      const heard = await voiceCommandsSet(enabled ? { model_path: modelPath.trim(), wake_word: wakeWord.trim() || DEFAULT_WAKE_WORD } : null);
+      setPhrases(heard);
+      if (!heard) setMessage("✓ Voice commands turned off");
+      else if (heard.length === 0) setMessage("✓ Listening; no profile has a voice phrase yet");
+      else setMessage(`✓ Listening for ${heard.length} phrase${heard.length === 1 ? "" : "s"}`);
+    } catch (error) {
+      setMessage(`Error: ${error}`);
+    }
+  };
+
+  return (
+    <div style={{ display: "flex", flexDirection: "column", gap: 10 }}>
+      <div style={{ fontSize: 13, opacity: 0.8 }}>
+        Say the wake word and a profile's phrase, e.g. "loopautoma, continue", to start or stop it. Speech is recognized on this
+        machine with a <a href="https://alphacephei.com/vosk/models" target="_blank" rel="noopener noreferrer" style={{ color: "#2196f3" }}>Vosk model</a>; the wake word and
+        phrases must be words the model knows. Builds need the <code>voice-commands</code> feature.
+      </div>
+      <label style={{ display: "flex", alignItems: "center", gap: 12, cursor: "pointer" }}>
+        <input type="checkbox" checked={enabled} onChange={(e) => setEnabled(e.target.checked)} />
+        <span>Enable voice commands</span>
+      </label>
+      {enabled && (
+        <>
+          <input
+            type="text"
+            aria-label="Vosk model directory"
+            placeholder="/path/to/vosk-model-small-en-us-0.15"
+            value={modelPath}
+            onChange={(e) => setModelPath(e.target.value)}
+          />
+          <input type="text" aria-label="Wake word" value={wakeWord} onChange={(e) => setWakeWord(e.target.value)} style={{ width: 160 }} />
+        </>
+      )}
+      <div style={{ display: "flex", gap: 8, alignItems: "center" }}>
+        <button onClick={save}>Save voice settings</button>
+      </div>
+      {phrases && phrases.length > 0 && (
+        <ul aria-label="Voice phrases" style={{ margin: 0, fontSize: 13 }}>
+          {phrases.map((p) => (
+            <li key={p}>{p}</li>
+          ))}
+        </ul>
+      )}
+      {message && (
+        <div role="status" style={{ fontSize: 13, color: message.startsWith("Error") ? "#d32f2f" : undefined }}>
+          {message}
+        </div>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,101 @@
+import { useEffect, useState } from "react";
+import { WebhookConfig, WebhookTarget } from "../types";
+import { webhooksGet, webhooksSet, webhookTest } from "../tauriBridge";
+
+type Kind = WebhookTarget["kind"];
+
+const KIND_LABELS: Record<Kind, string> = { slack: "Slack", discord: "Discord", telegram: "Telegram" };
+
+function emptyTarget(kind: Kind): WebhookTarget {
+  return kind === "telegram" ? { kind, token: "", chat_id: "" } : { kind, url: "" };
+}
+
+/** Slack/Discord/Telegram targets that receive run notifications */
+export function WebhookSettings() {
+  const [webhooks, setWebhooks] = useState<WebhookConfig[]>([]);
+  const [message, setMessage] = useState("");
+
+  useEffect(() => {
+    webhooksGet().then(setWebhooks).catch(() => { });
+  }, []);
+
+  const update = (index: number, patch: Partial<WebhookConfig>) =>
+    setWebhooks((current) => current.map((w, i) => (i === index ? { ...w, ...patch } : w)));
+
+  const setTarget = (index: number, patch: Partial<{ url: string; token: string; chat_id: string }>) =>
+    setWebhooks((current) =>
+      current.map((w, i) => (i === index ? { ...w, target: { ...w.target, ...patch } as WebhookTarget } : w)),
+    );
+
+  const save = async () => {
+    try {
+      await webhooksSet(webhooks);
+      setMessage("✓ Webhooks saved");
+    } catch (error) {
+      setMessage(`Error: ${error}`);
+    }
+  };
+
+  const test = async (webhook: WebhookConfig) => {
+    try {
+      await webhookTest(webhook);
+      setMessage(`✓ Test message sent to ${KIND_LABELS[webhook.target.kind]}`);
+    } catch (error) {
+      setMessage(`Error: ${error}`);
+    }
+  };
+
+  return (
+    <div style={{ display: "flex", flexDirection: "column", gap: 10 }}>
+      <div style={{ fontSize: 13, opacity: 0.8 }}>
+        Send run starts, completions, failures and alerts to chat. Which events are sent is set per profile under <strong>Notifications</strong>.
+      </div>
+      {webhooks.map((w, index) => (
+        <div key={index} style={{ display: "flex", flexDirection: "column", gap: 6 }}>
+          <div style={{ display: "flex", alignItems: "center", gap: 8 }}>
+            <select
+              aria-label={`Webhook ${index + 1} service`}
+              value={w.target.kind}
+              onChange={(e) => update(index, { target: emptyTarget(e.target.value as Kind) })}
+            >
+              {(Object.keys(KIND_LABELS) as Kind[]).map((k) => (
+                <option key={k} value={k}>{KIND_LABELS[k]}</option>
+              ))}
+            </select>
+            {w.target.kind === "telegram" ? (
+              <>
+                <input type="password" aria-label={`Webhook ${index + 1} bot token`} value={w.target.token} onChange={(e) => setTarget(index, { token: e.target.value })} placeholder="Bot token" style={{ flex: 1 }} />
+                <input type="text" aria-label={`Webhook ${index + 1} chat id`} value={w.target.chat_id} onChange={(e) => setTarget(index, { chat_id: e.target.value })} placeholder="Chat id" style={{ width: 110 }} />
+              </>
+            ) : (
+              <input type="password" aria-label={`Webhook ${index + 1} URL`} value={w.target.url} onChange={(e) => setTarget(index, { url: e.target.value })} placeholder="https://…" style={{ flex: 1 }} />
+            )}
+          </div>
+          <div style={{ display: "flex", alignItems: "center", gap: 8 }}>
+            <label style={{ display: "flex", alignItems: "center", gap: 6, opacity: w.target.kind === "slack" ? 0.5 : 1 }}>
+              <input
+                type="checkbox"
+                aria-label={`Webhook ${index + 1} screenshot`}
+                checked={!!w.include_screenshot}
+                disabled={w.target.kind === "slack"}
+                onChange={(e) => update(index, { include_screenshot: e.target.checked })}
+              />
+              Attach screenshot
+            </label>
+            <button onClick={() => test(w)}>Send test</button>
+            <button onClick={() => setWebhooks((current) => current.filter((_, i) => i !== index))}>Remove</button>
+          </div>
+        </div>
+      ))}
+      <div style={{ display: "flex", gap: 8 }}>
+        <button onClick={() => setWebhooks((current) => [...current, { target: emptyTarget("slack") }])}>Add webhook</button>
+        <button onClick={save}>Save webhooks</button>
+      </div>
+      {message && (
+        <div role="status" style={{ fontSize: 13, color: message.startsWith("Error") ? "#d32f2f" : undefined }}>
+          {message}
+        </div>
+      )}
+    </div>
+  );
+}
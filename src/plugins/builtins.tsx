@@ -1,5 +1,6 @@
 import { useState, useEffect } from "react";
-import { ActionConfig, MouseButton } from "../types";
+import { ActionConfig, ElementQuery, MouseButton } from "../types";
+import { findElements } from "../tauriBridge";
 import { SPECIAL_KEYS, formatInlineKeyToken } from "../utils/specialKeys";
 import { AcceleratingNumberInput } from "../components/AcceleratingNumberInput";
 import { KeyboardReferenceOverlay } from "../components/KeyboardReferenceOverlay";
@@ -13,28 +14,49 @@ import {
   registerTriggerEditor,
 } from "./registry";
 
-// Trigger: IntervalTrigger
+// Trigger: IntervalTrigger / HotkeyTrigger
+// Setting a launch hotkey turns the profile into a HotkeyTrigger; the interval still paces the loop once started.
 function IntervalTriggerEditor({ value, onChange }: TriggerEditorProps) {
   return (
-    <label
-      title="How often the loop evaluates the trigger/condition (seconds)"
-      style={{ display: "flex", alignItems: "center", gap: 6 }}
-    >
-      <span>Check interval (s)</span>
-      <AcceleratingNumberInput
-        min={0.1}
-        value={value.check_interval_sec}
-        onValueChange={(next) =>
-          onChange({
-            ...value,
-            type: "IntervalTrigger",
-            check_interval_sec: next === "" ? 0 : Number(next),
-          })
-        }
-        inputMode="decimal"
-        containerStyle={{ width: 170 }}
-      />
-    </label>
+    <>
+      <label
+        title="How often the loop evaluates the trigger/condition (seconds)"
+        style={{ display: "flex", alignItems: "center", gap: 6 }}
+      >
+        <span>Check interval (s)</span>
+        <AcceleratingNumberInput
+          min={0.1}
+          value={value.check_interval_sec}
+          onValueChange={(next) =>
+            onChange({
+              ...value,
+              check_interval_sec: next === "" ? 0 : Number(next),
+            })
+          }
+          inputMode="decimal"
+          containerStyle={{ width: 170 }}
+        />
+      </label>
+      <label
+        title="Global hotkey that starts this profile from anywhere, e.g. Ctrl+Alt+D"
+        style={{ display: "flex", alignItems: "center", gap: 6 }}
+      >
+        <span>Launch hotkey</span>
+        <input
+          value={value.hotkey ?? ""}
+          placeholder="none"
+          onChange={(e) => {
+            const hotkey = e.target.value;
+            onChange({
+              ...value,
+              type: hotkey.trim() ? "HotkeyTrigger" : "IntervalTrigger",
+              hotkey: hotkey || undefined,
+            });
+          }}
+          style={{ width: 140 }}
+        />
+      </label>
+    </>
   );
 }
 
@@ -174,6 +196,103 @@ function TypeEditor({ value, onChange }: ActionEditorProps) {
   );
 }
 
+// Actions: ClickElement, ReadElementText (selector looked up in the accessibility tree at run time)
+function ElementQueryFields({ value, onChange }: { value: ElementQuery; onChange: (next: ElementQuery) => void }) {
+  const [result, setResult] = useState("");
+  const field = (key: keyof ElementQuery, label: string, placeholder: string, title: string) => (
+    <label title={title} style={{ display: "flex", alignItems: "center", gap: 6 }}>
+      <span>{label}</span>
+      <input
+        value={value[key] ?? ""}
+        placeholder={placeholder}
+        onChange={(e) => onChange({ ...value, [key]: e.target.value || null })}
+        style={{ width: 120 }}
+      />
+    </label>
+  );
+  const test = async () => {
+    try {
+      const found = await findElements(value);
+      setResult(found.length === 0 ? "No match" : `${found.length} match${found.length === 1 ? "" : "es"}, first at (${found[0].x}, ${found[0].y})`);
+    } catch (error) {
+      setResult(`Error: ${error}`);
+    }
+  };
+  return (
+    <>
+      {field("role", "Role", "button", "Element role: button, text field, check box, link, tab, ...")}
+      {field("name", "Name", "Run", "Accessible name (label) of the element, case-insensitive")}
+      {field("app", "App", "any", "Only look in applications whose name contains this")}
+      <button type="button" className="ghost" onClick={test} title="Look the element up now">
+        Find
+      </button>
+      {result && <span role="status" style={{ fontSize: 12, opacity: 0.8 }}>{result}</span>}
+    </>
+  );
+}
+
+function ClickElementEditor({ value, onChange }: ActionEditorProps) {
+  const v = value as Extract<ActionConfig, { type: "ClickElement" }>;
+  return (
+    <>
+      <MouseIcon size={16} style={{ flexShrink: 0, opacity: 0.7 }} />
+      <ElementQueryFields value={v.element} onChange={(element) => onChange({ ...v, element })} />
+      <label title="Mouse button to click">
+        Button
+        <select
+          value={v.button}
+          onChange={(e) => onChange({ ...v, button: e.target.value as MouseButton })}
+          style={{ marginLeft: 6 }}
+        >
+          <option value="Left">Left</option>
+          <option value="Right">Right</option>
+          <option value="Middle">Middle</option>
+        </select>
+      </label>
+      <label title="Press the element through the accessibility API instead of moving the mouse" style={{ display: "flex", alignItems: "center", gap: 6 }}>
+        <input type="checkbox" checked={v.invoke ?? false} onChange={(e) => onChange({ ...v, invoke: e.target.checked })} />
+        Invoke without mouse
+      </label>
+    </>
+  );
+}
+
+function ReadElementTextEditor({ value, onChange }: ActionEditorProps) {
+  const v = value as Extract<ActionConfig, { type: "ReadElementText" }>;
+  return (
+    <>
+      <SparklesIcon size={16} style={{ flexShrink: 0, opacity: 0.7 }} />
+      <ElementQueryFields value={v.element} onChange={(element) => onChange({ ...v, element })} />
+      <label title="Variable that receives the element's text" style={{ display: "flex", alignItems: "center", gap: 6 }}>
+        <span>Store in $</span>
+        <input
+          value={v.variable_name ?? ""}
+          placeholder="element_text"
+          onChange={(e) => onChange({ ...v, variable_name: e.target.value || null })}
+          style={{ width: 120 }}
+        />
+      </label>
+    </>
+  );
+}
+
+// Action: Plugin (a kind registered by a third-party plugin; its config is edited in the JSON editor)
+function PluginActionEditor({ value, onChange }: ActionEditorProps) {
+  const v = value as Extract<ActionConfig, { type: "Plugin" }>;
+  return (
+    <>
+      <label title="Id of the plugin, as listed under Settings → Plugins" style={{ display: "flex", alignItems: "center", gap: 6 }}>
+        <span>Plugin</span>
+        <input value={v.plugin} onChange={(e) => onChange({ ...v, plugin: e.target.value })} style={{ width: 120 }} />
+      </label>
+      <label title="Action the plugin registers" style={{ display: "flex", alignItems: "center", gap: 6 }}>
+        <span>Action</span>
+        <input value={v.kind} onChange={(e) => onChange({ ...v, kind: e.target.value })} style={{ width: 140 }} />
+      </label>
+    </>
+  );
+}
+
 function LLMPromptGenerationEditor({ value, onChange }: ActionEditorProps) {
   const v = value as Extract<ActionConfig, { type: "LLMPromptGeneration" }>;
   const [hasApiKey, setHasApiKey] = useState<boolean | null>(null);
@@ -286,8 +405,12 @@ function LLMPromptGenerationEditor({ value, onChange }: ActionEditorProps) {
 
 export function registerBuiltins() {
   registerTriggerEditor("IntervalTrigger", IntervalTriggerEditor);
+  registerTriggerEditor("HotkeyTrigger", IntervalTriggerEditor);
   registerConditionEditor("RegionCondition", RegionConditionEditor);
   registerActionEditor("Click", ClickEditor);
   registerActionEditor("Type", TypeEditor);
+  registerActionEditor("ClickElement", ClickElementEditor);
+  registerActionEditor("ReadElementText", ReadElementTextEditor);
   registerActionEditor("LLMPromptGeneration", LLMPromptGenerationEditor);
+  registerActionEditor("Plugin", PluginActionEditor);
 }
@@ -1,9 +1,24 @@
 import { useCallback, useEffect, useState } from "react";
-import { Event as RuntimeEvent, ProfilesConfig } from "./types";
+import { Event as RuntimeEvent, ProfilesConfig, normalizeProfilesConfig } from "./types";
 import { subscribeEvent } from "./eventBridge";
+import { profilesLoad } from "./tauriBridge";
 
 export function useProfiles() {
   const [config, setConfig] = useState<ProfilesConfig | null>(null);
+  // Remote profile sync merges into the backend config; reload it when that happens
+  useEffect(() => {
+    let dispose: (() => void) | undefined;
+    subscribeEvent("loopautoma://profiles_synced", () => {
+      profilesLoad()
+        .then((cfg) => setConfig(normalizeProfilesConfig(cfg)))
+        .catch(() => { });
+    }).then((off) => (dispose = off));
+    return () => {
+      try {
+        dispose?.();
+      } catch { }
+    };
+  }, []);
   return { config, setConfig };
 }
 
@@ -29,18 +44,41 @@ export function useEventStream() {
 
 export function useRunState() {
   const [runningProfileId, setRunningProfileId] = useState<string | null>(null);
+  const [paused, setPaused] = useState(false);
+  const [outsideRunWindow, setOutsideRunWindow] = useState(false);
+  // Held after an action overran its timeout; resumed like a pause
+  const [actionTimedOut, setActionTimedOut] = useState(false);
   useEffect(() => {
     let dispose: (() => void) | undefined;
     subscribeEvent<RuntimeEvent>("loopautoma://event", (payload) => {
-      if (payload?.type === "MonitorStateChanged" && (payload as any).state !== "Running") {
+      const state = payload?.type === "MonitorStateChanged" ? (payload as any).state : undefined;
+      if (state !== undefined) {
+        setPaused(state === "Paused" || state === "ActionTimedOut");
+        setOutsideRunWindow(state === "OutsideRunWindow");
+        setActionTimedOut(state === "ActionTimedOut");
+      }
+      // A paused run or one waiting for the network is still active; only terminal states clear it
+      if (state === "Stopped" || state === "Stopping") {
         setRunningProfileId(null);
       }
+      // A finished run handed off to its follow-up profile
+      if (payload?.type === "ProfileChained") {
+        setRunningProfileId(payload.to);
+      }
     }).then((off) => (dispose = off));
+    // Profiles launched by a global hotkey, the tray menu, the remote API or a voice command start without going through the UI
+    const disposeStarted: Array<() => void> = [];
+    for (const channel of ["loopautoma://hotkey_profile_started", "loopautoma://tray_profile_started", "loopautoma://remote_profile_started", "loopautoma://voice_profile_started"]) {
+      subscribeEvent<string>(channel, (profileId) => {
+        if (profileId) setRunningProfileId(profileId);
+      }).then((off) => disposeStarted.push(off));
+    }
     return () => {
       try {
         dispose?.();
+        disposeStarted.forEach((off) => off());
       } catch { }
     };
   }, []);
-  return { runningProfileId, setRunningProfileId };
+  return { runningProfileId, setRunningProfileId, paused, outsideRunWindow, actionTimedOut };
 }
@@ -1,5 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
-import { ProfilesConfig, Rect, defaultProfilesConfig, normalizeProfilesConfig } from "./types";
+import { AlertEvent, AlertSound, AutomationCapabilities, CalibrationReport, CommandError, CursorTransform, Diagnostic, ElementQuery, LogLevel, LogLine, OtlpConfig, OtlpExportStatus, PickedRegion, PluginInfo, Profile, ProfileMetrics, ProfilesConfig, ProfileTemplate, QuietHours, Rect, RegionAnchor, RemoteApiConfig, RunCheckpoint, RunDetails, RunQuery, RunRecord, SyncConfig, SyncReport, SmtpConfig, SettingsImportReport, SettingsSnapshot, SoundMap, SyncStatus, TemplateInput, UiElement, VoiceConfig, WebhookConfig, WindowInfo, defaultProfilesConfig, normalizeProfilesConfig } from "./types";
 import { BLANK_PNG_BASE64 } from "./testConstants";
 import { getTestHarness, isDesktopEnvironment } from "./utils/runtime";
 
@@ -10,7 +10,15 @@ async function callInvoke<T = unknown>(cmd: string, args?: any): Promise<T> {
   if (harness?.invoke) {
     return harness.invoke(cmd, args);
   }
-  return invoke(cmd, args);
+  try {
+    return await invoke(cmd, args);
+  } catch (error) {
+    // Commands fail with `{ code, message }`; keep the message readable in `${error}`
+    if (error && typeof error === "object" && "code" in error && "message" in error) {
+      throw new CommandError(String(error.code), String(error.message));
+    }
+    throw error;
+  }
 }
 
 export async function profilesLoad(): Promise<ProfilesConfig> {
@@ -49,9 +57,49 @@ export async function profilesSave(config: ProfilesConfig): Promise<void> {
   }
 }
 
-export async function monitorStart(profileId: string): Promise<void> {
+/** Check a profile (saved or draft) for broken references, unknown keys and off-screen coordinates */
+export async function validateProfile(profile: Profile): Promise<Diagnostic[]> {
+  if (!isDesktopMode()) return [];
+  return (await callInvoke("validate_profile", { profile })) as Diagnostic[];
+}
+
+/** Export one profile to a file; `.yaml`/`.yml` paths are written as YAML, others as JSON */
+export async function profileExport(profileId: string, path: string): Promise<void> {
+  if (!isDesktopMode()) throw new Error("Profile export is only available in the desktop app");
+  await callInvoke("profile_export", { profileId, path });
+}
+
+/** Validate and add a profile file; resolves to the imported profile (its id may be suffixed) */
+export async function profileImport(path: string): Promise<Profile> {
+  if (!isDesktopMode()) throw new Error("Profile import is only available in the desktop app");
+  return (await callInvoke("profile_import", { path })) as Profile;
+}
+
+/** Built-in profile templates with the regions and parameters each one needs */
+export async function listTemplates(): Promise<ProfileTemplate[]> {
+  if (!isDesktopMode()) return [];
+  return (await callInvoke("list_templates")) as ProfileTemplate[];
+}
+
+/** Create and save a profile from a template; resolves to the new profile (its id may be suffixed) */
+export async function createFromTemplate(templateId: string, input: TemplateInput): Promise<Profile> {
+  if (!isDesktopMode()) throw new Error("Templates are only available in the desktop app");
+  return (await callInvoke("create_from_template", { templateId, input })) as Profile;
+}
+
+export async function monitorStart(profileId: string, debug = false): Promise<void> {
+  if (!isDesktopMode()) return; // no-op in web preview
+  await callInvoke("monitor_start", { profileId, debug });
+}
+
+export async function debugStep(): Promise<void> {
+  if (!isDesktopMode()) return; // no-op in web preview
+  await callInvoke("debug_step");
+}
+
+export async function debugContinue(): Promise<void> {
   if (!isDesktopMode()) return; // no-op in web preview
-  await callInvoke("monitor_start", { profileId });
+  await callInvoke("debug_continue");
 }
 
 export async function monitorStop(): Promise<void> {
@@ -59,6 +107,16 @@ export async function monitorStop(): Promise<void> {
   await callInvoke("monitor_stop");
 }
 
+export async function pauseProfile(): Promise<void> {
+  if (!isDesktopMode()) return; // no-op in web preview
+  await callInvoke("pause_profile");
+}
+
+export async function resumeProfile(): Promise<void> {
+  if (!isDesktopMode()) return; // no-op in web preview
+  await callInvoke("resume_profile");
+}
+
 export async function windowPosition(): Promise<{ x: number; y: number }> {
   if (isDesktopMode()) {
     const [x, y] = (await callInvoke("window_position")) as [number, number];
@@ -77,9 +135,16 @@ export async function windowInfo(): Promise<{ x: number; y: number; scale: numbe
 
 export type RegionPickPoint = { x: number; y: number };
 
-export async function regionPickerShow(): Promise<void> {
+/** Open the region overlay on a display (the primary one by default); the result arrives as loopautoma://region_pick_complete */
+export async function regionPickerShow(displayId?: number): Promise<void> {
   if (!isDesktopMode()) throw new Error("Region overlay requires desktop mode. Please run the Tauri app instead of the web preview.");
-  await callInvoke("region_picker_show");
+  await callInvoke("region_picker_show", { displayId: displayId ?? null });
+}
+
+/** Open the region overlay and resolve to the drawn region, or null if the picker was cancelled */
+export async function regionPick(displayId?: number): Promise<PickedRegion | null> {
+  if (!isDesktopMode()) throw new Error("Region overlay requires desktop mode. Please run the Tauri app instead of the web preview.");
+  return (await callInvoke("region_pick", { displayId: displayId ?? null })) as PickedRegion | null;
 }
 
 export async function regionPickerComplete(start: RegionPickPoint, end: RegionPickPoint): Promise<void> {
@@ -97,11 +162,64 @@ export async function appQuit(): Promise<void> {
   await callInvoke("app_quit");
 }
 
+/** Top-level windows with title, process and geometry; empty in the web preview */
+export async function enumerateWindows(): Promise<WindowInfo[]> {
+  if (!isDesktopMode()) return [];
+  return (await callInvoke("enumerate_windows")) as WindowInfo[];
+}
+
+/** Visible UI elements matching a role and/or name; empty in the web preview */
+export async function findElements(query: ElementQuery): Promise<UiElement[]> {
+  if (!isDesktopMode()) return [];
+  return (await callInvoke("find_elements", { query })) as UiElement[];
+}
+
+/** The input backend runs will use and what it can do; null in the web preview */
+export async function automationCapabilities(): Promise<AutomationCapabilities | null> {
+  if (!isDesktopMode()) return null;
+  return (await callInvoke("automation_capabilities")) as AutomationCapabilities;
+}
+
+/** Move the pointer over every display, check where it lands and save the correction for later moves */
+export async function calibrate(): Promise<CalibrationReport> {
+  if (!isDesktopMode()) throw new Error("Calibration is only available in the desktop app");
+  return (await callInvoke("calibrate")) as CalibrationReport;
+}
+
+/** The pointer correction in use, if any */
+export async function calibrationGet(): Promise<CursorTransform | null> {
+  if (!isDesktopMode()) return null;
+  return ((await callInvoke("calibration_get")) as CursorTransform | null) ?? null;
+}
+
+/** Drop the pointer correction */
+export async function calibrationReset(): Promise<void> {
+  if (!isDesktopMode()) return;
+  await callInvoke("calibration_reset");
+}
+
 export async function captureRegionThumbnail(rect: Rect): Promise<string | null> {
   if (!isDesktopMode()) return BLANK_PNG_BASE64;
   return (await callInvoke("region_capture_thumbnail", { rect })) as string | null;
 }
 
+/** Anchor the region at `region` to what is on screen at `anchor` now */
+export async function regionAnchorCreate(region: Rect, anchor: Rect): Promise<RegionAnchor> {
+  if (!isDesktopMode()) throw new Error("Anchors require desktop mode. Please run the Tauri app instead of the web preview.");
+  return (await callInvoke("region_anchor_create", { region, anchor })) as RegionAnchor;
+}
+
+/** Stream frames of a saved region as loopautoma://region_preview until stopped; capped at 10 fps */
+export async function startRegionPreview(regionId: string, fps: number): Promise<void> {
+  if (!isDesktopMode()) throw new Error("Live preview requires desktop mode. Please run the Tauri app instead of the web preview.");
+  await callInvoke("start_region_preview", { regionId, fps });
+}
+
+export async function stopRegionPreview(regionId: string): Promise<void> {
+  if (!isDesktopMode()) return;
+  await callInvoke("stop_region_preview", { regionId });
+}
+
 export async function actionRecorderShow(): Promise<void> {
   if (!isDesktopMode()) {
     throw new Error("Action Recorder requires desktop mode. Please run the Tauri app instead of the web preview.");
@@ -130,6 +248,243 @@ export async function audioTestCompleted(): Promise<void> {
   await callInvoke("audio_test_completed");
 }
 
+export async function audioGetCustomSounds(): Promise<Partial<Record<AlertSound, string>>> {
+  if (!isDesktopMode()) return {};
+  return ((await callInvoke("audio_get_custom_sounds")) as Partial<Record<AlertSound, string>>) ?? {};
+}
+
+/** Play the sound `event` is mapped to, as a run would */
+export async function audioTestAlert(event: AlertEvent): Promise<void> {
+  if (!isDesktopMode()) return; // no-op in web preview
+  await callInvoke("audio_test_alert", { event });
+}
+
+export async function audioGetSoundMap(): Promise<SoundMap> {
+  if (!isDesktopMode()) return {};
+  return ((await callInvoke("audio_get_sound_map")) as SoundMap) ?? {};
+}
+
+/** Replace the sound played per alert event */
+export async function audioSetSoundMap(map: SoundMap): Promise<void> {
+  if (!isDesktopMode()) throw new Error("Sound settings are only available in the desktop app");
+  await callInvoke("audio_set_sound_map", { map });
+}
+
+/** Play the WAV/OGG/MP3/FLAC file at `path` for `sound`; `null` restores the built-in sound */
+export async function audioSetCustomSound(sound: AlertSound, path: string | null): Promise<void> {
+  if (!isDesktopMode()) throw new Error("Custom sounds are only available in the desktop app");
+  await callInvoke("audio_set_custom_sound", { sound, path });
+}
+
+export async function webhooksGet(): Promise<WebhookConfig[]> {
+  if (!isDesktopMode()) return [];
+  return ((await callInvoke("webhooks_get")) as WebhookConfig[]) ?? [];
+}
+
+/** Replace the webhook targets that receive run notifications */
+export async function webhooksSet(webhooks: WebhookConfig[]): Promise<void> {
+  if (!isDesktopMode()) throw new Error("Webhooks are only available in the desktop app");
+  await callInvoke("webhooks_set", { webhooks });
+}
+
+/** Send a test message to one target; rejects with the endpoint's error */
+export async function webhookTest(webhook: WebhookConfig): Promise<void> {
+  if (!isDesktopMode()) throw new Error("Webhooks are only available in the desktop app");
+  await callInvoke("webhook_test", { webhook });
+}
+
+export async function quietHoursGet(): Promise<QuietHours | null> {
+  if (!isDesktopMode()) return null;
+  return ((await callInvoke("quiet_hours_get")) as QuietHours | null) ?? null;
+}
+
+/** Default of every typed setting, keyed by its namespaced key such as `audio.volume` */
+export async function settingsDefaults(): Promise<Record<string, unknown>> {
+  if (!isDesktopMode()) return {};
+  return (await callInvoke("settings_defaults")) as Record<string, unknown>;
+}
+
+/** Current settings for a window that just opened; changes then arrive as `loopautoma://settings` */
+export async function settingsSnapshot(): Promise<SettingsSnapshot | null> {
+  if (!isDesktopMode()) return null;
+  return (await callInvoke("settings_snapshot")) as SettingsSnapshot;
+}
+
+/** Write all settings except credentials to a JSON file */
+export async function settingsExport(path: string): Promise<void> {
+  if (!isDesktopMode()) throw new Error("Settings export is only available in the desktop app");
+  await callInvoke("settings_export", { path });
+}
+
+/** Apply a settings file; the report names secrets that must be entered again */
+export async function settingsImport(path: string): Promise<SettingsImportReport> {
+  if (!isDesktopMode()) throw new Error("Settings import is only available in the desktop app");
+  return (await callInvoke("settings_import", { path })) as SettingsImportReport;
+}
+
+/** Save the quiet hours; running profiles apply them from their next notification */
+export async function quietHoursSet(quietHours: QuietHours): Promise<void> {
+  if (!isDesktopMode()) throw new Error("Quiet hours are only available in the desktop app");
+  await callInvoke("quiet_hours_set", { quietHours });
+}
+
+/** Current backend log level, e.g. `info` */
+export async function logLevelGet(): Promise<LogLevel | null> {
+  if (!isDesktopMode()) return null;
+  return ((await callInvoke("log_level_get")) as LogLevel | null) ?? null;
+}
+
+/** Change the backend log level now and on later starts */
+export async function logLevelSet(level: LogLevel): Promise<void> {
+  if (!isDesktopMode()) throw new Error("Logs are only available in the desktop app");
+  await callInvoke("log_level_set", { level });
+}
+
+/** Newest backend log records, oldest first; `level` keeps only that level and more severe */
+export async function tailLogs(lines?: number, level?: LogLevel): Promise<LogLine[]> {
+  if (!isDesktopMode()) return [];
+  return ((await callInvoke("tail_logs", { lines, level })) as LogLine[] | null) ?? [];
+}
+
+export async function smtpGet(): Promise<SmtpConfig | null> {
+  if (!isDesktopMode()) return null;
+  return ((await callInvoke("smtp_get")) as SmtpConfig | null) ?? null;
+}
+
+/** Save the SMTP server for email summaries; `null` turns email off */
+export async function smtpSet(config: SmtpConfig | null): Promise<void> {
+  if (!isDesktopMode()) throw new Error("Email notifications are only available in the desktop app");
+  await callInvoke("smtp_set", { config });
+}
+
+/** Mail a sample run summary; rejects with the server's error */
+export async function smtpTest(config: SmtpConfig): Promise<void> {
+  if (!isDesktopMode()) throw new Error("Email notifications are only available in the desktop app");
+  await callInvoke("smtp_test", { config });
+}
+
+export async function remoteApiGet(): Promise<RemoteApiConfig | null> {
+  if (!isDesktopMode()) return null;
+  return ((await callInvoke("remote_api_get")) as RemoteApiConfig | null) ?? null;
+}
+
+/** Save the remote API settings and restart its server; resolves to its URL, `null` when turned off */
+export async function remoteApiSet(config: RemoteApiConfig | null): Promise<string | null> {
+  if (!isDesktopMode()) throw new Error("The remote API is only available in the desktop app");
+  return ((await callInvoke("remote_api_set", { config })) as string | null) ?? null;
+}
+
+/** URL of the running remote API, if enabled */
+export async function remoteApiStatus(): Promise<string | null> {
+  if (!isDesktopMode()) return null;
+  return ((await callInvoke("remote_api_status")) as string | null) ?? null;
+}
+
+export async function remoteApiGenerateToken(): Promise<string> {
+  if (!isDesktopMode()) throw new Error("The remote API is only available in the desktop app");
+  return (await callInvoke("remote_api_generate_token")) as string;
+}
+
+/** Port of the Prometheus metrics endpoint on 127.0.0.1, `null` when it is off */
+export async function metricsExporterGet(): Promise<number | null> {
+  if (!isDesktopMode()) return null;
+  return ((await callInvoke("metrics_exporter_get")) as number | null) ?? null;
+}
+
+/** Save the metrics endpoint port (`null` turns it off) and restart it; resolves to its URL */
+export async function metricsExporterSet(port: number | null): Promise<string | null> {
+  if (!isDesktopMode()) throw new Error("The metrics endpoint is only available in the desktop app");
+  return ((await callInvoke("metrics_exporter_set", { port })) as string | null) ?? null;
+}
+
+/** URL of the running metrics endpoint, if enabled */
+export async function metricsExporterStatus(): Promise<string | null> {
+  if (!isDesktopMode()) return null;
+  return ((await callInvoke("metrics_exporter_status")) as string | null) ?? null;
+}
+
+/** Vosk model and wake word for voice commands, `null` when they are off */
+export async function voiceCommandsGet(): Promise<VoiceConfig | null> {
+  if (!isDesktopMode()) return null;
+  return ((await callInvoke("voice_commands_get")) as VoiceConfig | null) ?? null;
+}
+
+/** Save the voice command settings (`null` turns the microphone off) and listen with them; resolves to the phrases listened for */
+export async function voiceCommandsSet(config: VoiceConfig | null): Promise<string[] | null> {
+  if (!isDesktopMode()) throw new Error("Voice commands are only available in the desktop app");
+  return ((await callInvoke("voice_commands_set", { config })) as string[] | null) ?? null;
+}
+
+/** Phrases the voice listener is listening for, if it is listening */
+export async function voiceCommandsStatus(): Promise<string[] | null> {
+  if (!isDesktopMode()) return null;
+  return ((await callInvoke("voice_commands_status")) as string[] | null) ?? null;
+}
+
+/** Where OpenTelemetry traces of runs are sent, `null` when they are not exported */
+export async function otlpTracesGet(): Promise<OtlpConfig | null> {
+  if (!isDesktopMode()) return null;
+  return ((await callInvoke("otlp_traces_get")) as OtlpConfig | null) ?? null;
+}
+
+/** Save the trace export settings (`null` stops exporting) and restart the exporter; resolves to the traces URL */
+export async function otlpTracesSet(config: OtlpConfig | null): Promise<string | null> {
+  if (!isDesktopMode()) throw new Error("Trace export is only available in the desktop app");
+  return ((await callInvoke("otlp_traces_set", { config })) as string | null) ?? null;
+}
+
+/** Where traces go and how many were sent, while they are exported */
+export async function otlpTracesStatus(): Promise<OtlpExportStatus | null> {
+  if (!isDesktopMode()) return null;
+  return ((await callInvoke("otlp_traces_status")) as OtlpExportStatus | null) ?? null;
+}
+
+/** Plugins found in the plugins directory at startup */
+export async function pluginsList(): Promise<PluginInfo[]> {
+  if (!isDesktopMode()) return [];
+  return ((await callInvoke("plugins_list")) as PluginInfo[] | null) ?? [];
+}
+
+/** Enable or disable a plugin from the next start; resolves to the updated list */
+export async function pluginsSetEnabled(id: string, enabled: boolean): Promise<PluginInfo[]> {
+  if (!isDesktopMode()) throw new Error("Plugins are only available in the desktop app");
+  return ((await callInvoke("plugins_set_enabled", { id, enabled })) as PluginInfo[] | null) ?? [];
+}
+
+/** Whether profiles.json is encrypted with the key held in the OS keyring */
+export async function profilesEncryptionGet(): Promise<boolean> {
+  if (!isDesktopMode()) return false;
+  return (await callInvoke("profiles_encryption_get")) as boolean;
+}
+
+/** Encrypt or decrypt profiles.json; the file is rewritten immediately */
+export async function profilesEncryptionSet(enabled: boolean): Promise<void> {
+  if (!isDesktopMode()) throw new Error("Profile encryption is only available in the desktop app");
+  await callInvoke("profiles_encryption_set", { enabled });
+}
+
+export async function profileSyncGet(): Promise<SyncConfig | null> {
+  if (!isDesktopMode()) return null;
+  return ((await callInvoke("profile_sync_get")) as SyncConfig | null) ?? null;
+}
+
+/** Save remote sync settings; `null` turns sync off */
+export async function profileSyncSet(config: SyncConfig | null): Promise<void> {
+  if (!isDesktopMode()) throw new Error("Profile sync is only available in the desktop app");
+  await callInvoke("profile_sync_set", { config });
+}
+
+/** Pull, verify and merge the configured bundle now */
+export async function profileSyncNow(): Promise<SyncReport> {
+  if (!isDesktopMode()) throw new Error("Profile sync is only available in the desktop app");
+  return (await callInvoke("profile_sync_now")) as SyncReport;
+}
+
+export async function profileSyncStatus(): Promise<SyncStatus | null> {
+  if (!isDesktopMode()) return null;
+  return ((await callInvoke("profile_sync_status")) as SyncStatus | null) ?? null;
+}
+
 export async function audioSetEnabled(enabled: boolean): Promise<void> {
   if (!isDesktopMode()) return; // no-op in web preview
   await callInvoke("audio_set_enabled", { enabled });
@@ -150,3 +505,57 @@ export async function audioGetVolume(): Promise<number> {
   return (await callInvoke("audio_get_volume")) as number;
 }
 
+export async function runHistoryList(): Promise<RunRecord[]> {
+  if (!isDesktopMode()) return [];
+  return (await callInvoke("run_history_list")) as RunRecord[];
+}
+
+/** Recorded runs, newest first */
+export async function listRuns(query?: RunQuery): Promise<RunRecord[]> {
+  if (!isDesktopMode()) return [];
+  return (await callInvoke("list_runs", { query: query ?? null })) as RunRecord[];
+}
+
+export async function getRunDetails(runId: number): Promise<RunDetails> {
+  if (!isDesktopMode()) throw new Error("Run history requires desktop mode.");
+  return (await callInvoke("get_run_details", { runId })) as RunDetails;
+}
+
+/** Write a report of a recorded run; `.html`/`.htm` paths get a standalone HTML page, others Markdown */
+export async function exportRunReport(runId: number, path: string): Promise<void> {
+  if (!isDesktopMode()) throw new Error("Run reports require desktop mode.");
+  await callInvoke("export_run_report", { runId, path });
+}
+
+/** Animate the archived frames of a run's region; `.mp4` paths need ffmpeg, others get a GIF. Returns the frame count */
+export async function exportRunFrames(runId: number, regionId: string | undefined, path: string): Promise<number> {
+  if (!isDesktopMode()) throw new Error("Run animations require desktop mode.");
+  return (await callInvoke("export_run_frames", { runId, regionId: regionId ?? null, path })) as number;
+}
+
+/** Statistics per profile over recorded runs, optionally of one profile and the last `days` */
+export async function getMetrics(profileId?: string, days?: number): Promise<ProfileMetrics[]> {
+  if (!isDesktopMode()) return [];
+  return (await callInvoke("get_metrics", { profileId: profileId ?? null, days: days ?? null })) as ProfileMetrics[];
+}
+
+/** Delete runs older than `olderThanDays` and/or all but the `keepLatest` newest; resolves to how many were removed */
+export async function pruneRuns(olderThanDays?: number, keepLatest?: number): Promise<number> {
+  if (!isDesktopMode()) return 0;
+  return (await callInvoke("prune_runs", { olderThanDays: olderThanDays ?? null, keepLatest: keepLatest ?? null })) as number;
+}
+
+export async function lastInterruptedRun(): Promise<RunCheckpoint | null> {
+  if (!isDesktopMode()) return null;
+  return ((await callInvoke("last_interrupted_run")) as RunCheckpoint | null) ?? null;
+}
+
+/** Resume the interrupted run; resolves to the id of the profile that was restarted */
+export async function resumeLastRun(): Promise<string> {
+  return (await callInvoke("resume_last_run")) as string;
+}
+
+export async function discardLastRun(): Promise<void> {
+  if (!isDesktopMode()) return;
+  await callInvoke("discard_last_run");
+}
@@ -1,5 +1,6 @@
 // Tauri commands for secure API key and model management
 import { invoke } from "@tauri-apps/api/core";
+import { EnvKeyStatus, ProviderCredentials, ProviderStatus } from "./types";
 
 export async function getOpenAIKeyStatus(): Promise<boolean> {
     return await invoke<boolean>("get_openai_key_status");
@@ -20,3 +21,40 @@ export async function getOpenAIModel(): Promise<string | null> {
 export async function setOpenAIModel(model: string): Promise<void> {
     await invoke("set_openai_model", { model });
 }
+
+/** Configured LLM providers; keys are only reported as present */
+export async function listProviders(): Promise<ProviderStatus[]> {
+    return await invoke<ProviderStatus[]>("provider_list");
+}
+
+/** Add or update a provider; `id` is its kind or `custom:<name>` */
+export async function setProvider(id: string, credentials: ProviderCredentials): Promise<void> {
+    await invoke("provider_set", { id, credentials });
+}
+
+export async function deleteProvider(id: string): Promise<void> {
+    await invoke("provider_delete", { id });
+}
+
+/** Names of a profile's `{{secret:name}}` values; values are never returned */
+export async function listProfileSecrets(profileId: string): Promise<string[]> {
+    return await invoke<string[]>("profile_secrets_list", { profileId });
+}
+
+export async function setProfileSecret(profileId: string, name: string, value: string): Promise<void> {
+    await invoke("profile_secret_set", { profileId, name, value });
+}
+
+export async function deleteProfileSecret(profileId: string, name: string): Promise<void> {
+    await invoke("profile_secret_delete", { profileId, name });
+}
+
+/** API keys set as environment variables; values are never returned */
+export async function detectEnvCredentials(): Promise<EnvKeyStatus[]> {
+    return await invoke<EnvKeyStatus[]>("env_credentials_detect");
+}
+
+/** Store the keys of the given variables; returns the variables imported */
+export async function importEnvCredentials(vars: string[]): Promise<string[]> {
+    return await invoke<string[]>("import_env_credentials", { vars });
+}
@@ -1,8 +1,116 @@
 // Shared model types mirroring Rust models (doc/architecture.md)
 export type Rect = { x: number; y: number; width: number; height: number };
-export type Region = { id: string; rect: Rect; name?: string };
 
-export type TriggerConfig = { type: string; check_interval_sec: number };
+/** A failed command; `code` is stable, e.g. "network_failed" or "config_invalid" */
+export class CommandError extends Error {
+  constructor(public readonly code: string, message: string) {
+    super(message);
+    this.name = "CommandError";
+  }
+
+  toString(): string {
+    return this.message;
+  }
+}
+export type Region = { id: string; rect: Rect; name?: string; anchor?: RegionAnchor };
+
+// Positions a region relative to where a template image matches on screen, resolved at capture time
+export type RegionAnchor = {
+  template_png: string; // base64 PNG
+  offset_x: number;
+  offset_y: number;
+  search?: Rect; // defaults to the display holding the region
+  threshold: number; // lowest match score, 0..1
+};
+
+// A region drawn in the on-screen picker; the rect is in physical pixels of that display
+export type PickedRegion = {
+  region: Region;
+  display_id: number;
+  scale_factor: number;
+};
+
+// Top-level desktop window as listed by enumerate_windows
+export type WindowInfo = {
+  id: number;
+  title: string;
+  process_name: string;
+  pid: number;
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+  minimized: boolean;
+};
+
+/** Which UI elements findElements looks for; needs a role or a name */
+export type ElementQuery = {
+  /** button, text field, check box, link, ... (platform role names work too) */
+  role?: string | null;
+  /** Accessible name, compared case-insensitively */
+  name?: string | null;
+  /** Only search applications whose name contains this */
+  app?: string | null;
+};
+
+/** An element of the accessibility tree with its screen rectangle */
+export type UiElement = {
+  role: string;
+  name: string;
+  app: string;
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+};
+
+/** Which input the automation backend can send in this session */
+export type AutomationCapabilities = {
+  /** x11, wayland, unknown, or the OS (macos, windows) */
+  session: string;
+  /** xtest, ydotool, cgevent, sendinput, fake, or none */
+  backend: string;
+  mouse: boolean;
+  keyboard: boolean;
+  text: boolean;
+  /** What limits the backend or how to enable it */
+  hint: string | null;
+};
+
+/** Correction for pointer moves: a target is sent as target * scale + offset per axis */
+export type CursorTransform = {
+  scale_x: number;
+  scale_y: number;
+  offset_x: number;
+  offset_y: number;
+};
+
+/** Returned by calibrate */
+export type CalibrationReport = {
+  samples: { display_id: number; target_x: number; target_y: number; landed_x: number; landed_y: number }[];
+  /** Null when the pointer landed where it was sent */
+  transform: CursorTransform | null;
+  /** Largest distance in pixels between a corrected move and its target */
+  residual_px: number;
+  findings: string[];
+};
+
+// Payload of loopautoma://region_preview
+export type RegionPreviewFrame = {
+  region_id: string;
+  rect: Rect;
+  png_base64: string;
+  captured_at_ms: number;
+};
+
+export type TriggerConfig = {
+  type: string;
+  check_interval_sec: number;
+  hotkey?: string;
+  /** Fire only when this plugin trigger says so, asked once per interval */
+  plugin?: PluginTriggerConfig;
+};
+export type PluginTriggerConfig = { plugin: string; kind: string; config?: unknown };
 export type ConditionConfig = {
   type: string;
   consecutive_checks: number;
@@ -13,18 +121,27 @@ export type MouseButton = "Left" | "Right" | "Middle";
 export type ActionConfig =
   | { type: "Click"; x: number; y: number; button: MouseButton }
   | { type: "Type"; text: string }
+  /** Looks the element up in the accessibility tree when the action runs; invoke uses its default action instead of the mouse */
+  | { type: "ClickElement"; element: ElementQuery; button: MouseButton; invoke?: boolean }
+  /** Stores the element's text or value in $element_text (or variable_name) */
+  | { type: "ReadElementText"; element: ElementQuery; variable_name?: string | null }
   | {
     type: "LLMPromptGeneration";
     region_ids: string[];
     risk_threshold: number;
     system_prompt?: string;
     variable_name?: string;
-  };
+  }
+  /** An action a plugin registers; `config` is passed to the plugin as is */
+  | { type: "Plugin"; plugin: string; kind: string; config?: unknown };
 
 export type GuardrailsConfig = {
   max_runtime_ms?: number;
   max_activations_per_hour?: number;
   cooldown_ms: number;
+  // Per-profile budgets: the run stops with a BudgetExceeded outcome when reached
+  max_iterations?: number;
+  max_consecutive_llm_failures?: number;
   // Intelligent termination fields (Phase 7)
   heartbeat_timeout_ms?: number;
   ocr_mode?: "none" | "local" | "vision";
@@ -44,14 +161,263 @@ export type Profile = {
   condition: ConditionConfig;
   actions: ActionConfig[];
   guardrails?: GuardrailsConfig;
+  // Follow-up profiles started when a run completes or fails
+  on_complete?: ProfileHandoff;
+  on_failure?: ProfileHandoff;
+  // Environment variables that `${NAME}` references in profile strings may read
+  env_allowlist?: string[];
+  // Channels per notification event; unlisted events use the default (sound), [] silences
+  notifications?: Partial<Record<NotificationEvent, NotificationChannel[]>>;
+  /** Local times the profile may run; absent or empty means any time */
+  run_windows?: RunWindow[];
+  /** Keep a frame of every region after each iteration, for animated exports */
+  frame_archive?: FrameArchiveConfig;
+  /** Mask personal data in captures before they are archived or sent to an LLM */
+  privacy?: PrivacyConfig;
+  /** Phrases that start and stop the profile, said after the wake word */
+  voice?: VoicePhrases;
+};
+
+export interface FrameArchiveConfig {
+  /** Longest side of an archived frame in pixels */
+  max_size: number;
+  /** Iterations archived per run */
+  max_frames: number;
+}
+
+export type PiiKind = "email" | "credit_card" | "api_key";
+
+export interface PrivacyConfig {
+  /** What to mask; the backend fills in all kinds when left out */
+  detectors: PiiKind[];
+}
+
+export interface VoicePhrases {
+  /** Starts the profile, or resumes it when it is running and paused */
+  start?: string;
+  /** Stops the profile when it is running */
+  stop?: string;
+}
+
+// Vosk model directory and wake word for voice commands; the backend defaults the wake word to "loopautoma"
+export interface VoiceConfig {
+  model_path: string;
+  wake_word?: string;
+}
+
+export type NotificationEvent = "started" | "intervention_needed" | "completed" | "failed" | "risk_exceeded";
+export type NotificationChannel = "sound" | "desktop" | "webhook" | "email" | "plugin";
+
+// Slack/Discord/Telegram target; URLs and tokens are kept in secure storage
+export type WebhookTarget =
+  | { kind: "slack"; url: string }
+  | { kind: "discord"; url: string }
+  | { kind: "telegram"; token: string; chat_id: string };
+
+export type WebhookConfig = {
+  target: WebhookTarget;
+  /** Attach a screenshot of the primary display (Discord and Telegram) */
+  include_screenshot?: boolean;
+};
+
+// Global quiet hours: sounds and desktop notifications are held back, webhooks and email still go out
+export type QuietHours = {
+  do_not_disturb: boolean;
+  /** Weekly quiet times; same format as profile run windows */
+  windows: RunWindow[];
+  /** Record held-back notifications in the event log */
+  log_suppressed: boolean;
+  /** Events that break through quiet hours */
+  critical: NotificationEvent[];
+};
+
+export type LogLevel = "error" | "warn" | "info" | "debug" | "trace";
+
+// Span a backend log record was written in (`run`, `iteration`, `action`) with its fields
+export type LogSpan = {
+  name: string;
+  [field: string]: unknown;
+};
+
+// One record of the backend's JSON log files
+export type LogLine = {
+  timestamp: string;
+  level: LogLevel;
+  /** Rust module that wrote the record */
+  target: string;
+  message: string;
+  fields: Record<string, unknown>;
+  /** Outermost first */
+  spans: LogSpan[];
+};
+
+export type SmtpSecurity = "starttls" | "tls" | "none";
+
+// SMTP server for email run summaries; kept in secure storage with its password
+export type SmtpConfig = {
+  host: string;
+  /** 0 uses the default port of `security` (587, 465 or 25) */
+  port: number;
+  security: SmtpSecurity;
+  /** Empty sends without authentication */
+  username: string;
+  password: string;
+  from: string;
+  to: string[];
+  /** Attach a screenshot of the primary display */
+  include_screenshot: boolean;
+};
+
+// Remote control HTTP API; kept in secure storage with its token
+export type RemoteApiConfig = {
+  /** Listen on all interfaces instead of 127.0.0.1; requires `tls` */
+  lan: boolean;
+  port: number;
+  /** Bearer token every request must send */
+  token: string;
+  /** PEM certificate chain and private key files */
+  tls: { cert_path: string; key_path: string } | null;
+};
+
+// Where OpenTelemetry traces of runs are sent; kept in secure storage
+export type OtlpConfig = {
+  /** Base URL of the collector's OTLP/HTTP receiver, e.g. http://collector:4318 */
+  endpoint: string;
+  /** Sent with every request, e.g. the backend's API key */
+  headers: Record<string, string>;
+};
+
+export type OtlpExportStatus = {
+  url: string;
+  exported: number;
+  dropped: number;
+  last_error: string | null;
+};
+
+export type PluginCapability = "input" | "capture";
+export type PluginComponent = { kind: string; description: string };
+
+// plugin.json of a plugin in the plugins directory
+export type PluginManifest = {
+  id: string;
+  name: string;
+  version: string;
+  api_version: number;
+  library: string;
+  capabilities: PluginCapability[];
+  actions: PluginComponent[];
+  triggers: PluginComponent[];
+  notifiers: PluginComponent[];
+};
+
+export type PluginInfo = {
+  id: string;
+  dir: string;
+  /** `null` when plugin.json could not be read */
+  manifest: PluginManifest | null;
+  /** Load at the next start */
+  enabled: boolean;
+  loaded: boolean;
+  error: string | null;
+};
+
+export type ProviderKind = "openai" | "anthropic" | "gemini" | "azure" | "ollama" | "custom";
+
+// Credentials entered for an LLM provider; the key is written to secure storage and never read back
+export type ProviderCredentials = {
+  kind: ProviderKind;
+  /** Omit to keep the stored key */
+  api_key?: string;
+  base_url: string | null;
+  model: string | null;
+};
+
+// Payload of loopautoma://settings, sent after any stored setting changes
+export type SettingChange = {
+  /** Namespaced key such as `audio.volume`, or `credentials.<entry>` for an API key */
+  key: string;
+  removed: boolean;
+};
+
+// Current settings from settings_snapshot; credentials are only named
+export type SettingsSnapshot = {
+  settings: Record<string, unknown>;
+  providers: ProviderStatus[];
+  secrets: string[];
+};
+
+// Outcome of settings_import
+export type SettingsImportReport = {
+  /** Namespaced keys applied, e.g. `audio.volume` */
+  imported: string[];
+  /** Keys from a newer version, left alone */
+  ignored: string[];
+  /** Secrets left out of the file and not set on this machine, e.g. `anthropic_api_key` */
+  reenter: string[];
+};
+
+// A configured LLM provider as listed by provider_list
+export type ProviderStatus = {
+  /** The kind, or `custom:<name>` for extra endpoints */
+  id: string;
+  kind: ProviderKind;
+  base_url: string | null;
+  model: string | null;
+  has_key: boolean;
+  /** When the key was set and last worked, if known */
+  key: KeyMetadata | null;
+};
+
+// When a stored API key was set and used (epoch ms)
+export type KeyMetadata = {
+  set_at_ms: number;
+  last_used_ms: number | null;
+  last_failed_ms: number | null;
+};
+
+// An API key found in an environment variable, as listed by env_credentials_detect
+export type EnvKeyStatus = {
+  var: string;
+  provider: string;
+  /** A stored key exists and is used instead of the variable */
+  stored: boolean;
+  /** Why the key cannot be imported, e.g. a missing endpoint variable */
+  problem: string | null;
+};
+
+// Payload of loopautoma://notification_clicked
+export type NotificationClick = {
+  profile_id: string;
+  started_at_ms: number;
+  event: NotificationEvent;
+};
+
+export type ProfileHandoff = {
+  profile_id: string;
+  // Context variables copied into the follow-up run
+  pass_variables?: string[];
 };
 
 export type ProfilesConfig = {
   version?: number;
+  // Profile format version; set by the backend, which migrates older files on load
+  schema_version?: number;
   profiles: Profile[];
 };
 
-export type MonitorState = "Stopped" | "Running" | "Stopping";
+export type MonitorState = "Stopped" | "Running" | "Stopping" | "WaitingForNetwork" | "Paused" | "OutsideRunWindow" | "ActionTimedOut";
+
+export type Weekday = "mon" | "tue" | "wed" | "thu" | "fri" | "sat" | "sun";
+
+/** Daily local-time span; an `end` before `start` closes the next morning */
+export interface RunWindow {
+  /** Days the window opens on; absent or empty means every day */
+  days?: Weekday[];
+  /** "HH:MM" */
+  start: string;
+  /** "HH:MM" */
+  end: string;
+}
 
 export type Event =
   | { type: "TriggerFired" }
@@ -59,10 +425,206 @@ export type Event =
   | { type: "ActionStarted"; action: string }
   | { type: "ActionCompleted"; action: string; success: boolean }
   | { type: "MonitorStateChanged"; state: MonitorState }
+  | { type: "LlmCallStarted"; action: string }
+  | { type: "LlmCallFinished"; action: string; success: boolean; risk: number | null; prompt: string | null }
+  | { type: "IterationCompleted"; iteration: number; success: boolean }
   | { type: "WatchdogTripped"; reason: string }
+  | { type: "DebugPaused"; index: number; action: string; params: unknown; variables: Record<string, string> }
+  | { type: "BudgetExceeded"; budget: string; limit: number }
+  | { type: "ProfileChained"; from: string; to: string; on: "complete" | "failure" }
   | { type: "Error"; message: string }
+  | { type: "NotificationSuppressed"; event: NotificationEvent; channels: NotificationChannel[] }
+  | { type: "CredentialWarning"; credential: string; reason: string }
   | { type: "MonitorTick"; next_check_ms: number; cooldown_remaining_ms: number; condition_met: boolean };
 
+// A runner state transition (runner://state)
+export type RunnerTransition =
+  | { type: "RunState"; state: MonitorState }
+  | { type: "TriggerFired" }
+  | { type: "ActionStarted"; action: string }
+  | { type: "ActionFinished"; action: string; success: boolean }
+  | { type: "LlmCallStarted"; action: string }
+  | { type: "LlmCallFinished"; action: string; success: boolean; risk: number | null }
+  | { type: "IterationCompleted"; iteration: number; success: boolean }
+  | { type: "Guardrail"; reason: string }
+  | { type: "Error"; message: string };
+
+// Payload of runner://state
+export type RunnerStateUpdate = RunnerTransition & {
+  profile_id: string;
+  at_ms: number;
+};
+
+export type RunOutcome =
+  | { type: "Stopped" }
+  | { type: "Completed"; reason: string }
+  | { type: "BudgetExceeded"; budget: string }
+  | { type: "WatchdogTripped"; reason: string }
+  | { type: "Failed"; reason: string };
+
+export type RunRecord = {
+  id: number;
+  profile_id: string;
+  started_at_ms: number;
+  ended_at_ms: number;
+  iterations: number;
+  outcome: RunOutcome;
+  llm_calls: number;
+  // Estimated from the model's list price
+  llm_cost_usd: number;
+  error_count: number;
+};
+
+// Returned by get_run_details
+// One iteration of a recorded run, for the run timeline and reports
+export type RunIteration = {
+  iteration: number;
+  started_at_ms: number;
+  ended_at_ms: number;
+  success: boolean;
+  actions: { action: string; success: boolean }[];
+  llm_calls: { action: string; success: boolean; risk: number | null; prompt: string | null }[];
+};
+
+export type RunDetails = RunRecord & {
+  llm_prompt_tokens: number;
+  llm_completion_tokens: number;
+  errors: { at_ms: number; message: string }[];
+  // Paths of screenshots archived for the run
+  screenshots: string[];
+  // The first 200 iterations
+  timeline: RunIteration[];
+  // Region frames archived after each iteration, when the profile keeps them
+  frames: RunFrame[];
+  // What the privacy filter masked, per region (or "llm_prompt" for OCR text) and kind
+  masked: MaskedPii[];
+};
+
+export type MaskedPii = {
+  source: string;
+  kind: PiiKind;
+  count: number;
+};
+
+export type RunFrame = {
+  region_id: string;
+  iteration: number;
+  at_ms: number;
+  path: string;
+};
+
+// Per-profile statistics returned by get_metrics
+export type ProfileMetrics = {
+  profile_id: string;
+  runs: number;
+  succeeded_runs: number;
+  failed_runs: number;
+  // Null when every run was stopped by the user
+  success_rate: number | null;
+  iterations: number;
+  iteration_success_rate: number | null;
+  avg_iteration_ms: number | null;
+  avg_capture_ms: number | null;
+  llm_calls: number;
+  avg_llm_ms: number | null;
+  llm_cost_usd: number;
+  interventions_per_run: number;
+  errors_per_run: number;
+  // Most common first
+  failure_reasons: { reason: string; runs: number }[];
+  last_run_at_ms: number;
+};
+
+export type RunQuery = {
+  profile_id?: string;
+  limit?: number;
+  offset?: number;
+};
+
+// Finding from the backend validate_profile command
+export type Diagnostic = {
+  severity: "error" | "warning";
+  // Path of the offending field, e.g. "actions[2].region_ids"
+  field: string;
+  message: string;
+};
+
+export type TemplateParameter = {
+  key: string;
+  label: string;
+  // Default value; its JSON type is the type the backend expects
+  default: unknown;
+};
+
+// Built-in profile template returned by list_templates
+export type ProfileTemplate = {
+  id: string;
+  name: string;
+  description: string;
+  regions: { id: string; name: string }[];
+  parameters: TemplateParameter[];
+};
+
+export type TemplateInput = {
+  name?: string;
+  // Rectangle for every template region, keyed by region id
+  regions: Record<string, Rect>;
+  values: Record<string, unknown>;
+};
+
+// Where remote profile sync pulls its bundle from
+export type SyncSource =
+  | { kind: "url"; url: string }
+  | { kind: "git"; repo: string; branch?: string | null; path: string };
+
+export type SyncConfig = {
+  source: SyncSource;
+  // Minutes between scheduled pulls; 0 syncs only on demand
+  interval_minutes: number;
+  // Pinned SHA-256 of the bundle
+  sha256?: string | null;
+  // Ed25519 public key (base64, raw or PEM) that `<bundle>.sig` must verify against; this or `sha256` is required
+  public_key?: string | null;
+};
+
+export type SyncReport = {
+  sha256: string;
+  added: string[];
+  updated: string[];
+};
+
+export type SyncStatus = {
+  at_ms: number;
+  report?: SyncReport | null;
+  error?: string | null;
+};
+
+// Alerts that play a sound; each can use a custom sound file instead of the built-in one
+export type AlertSound = "intervention" | "completion";
+
+// Runner situations that can play a sound
+export type AlertEvent =
+  | "run_started"
+  | "intervention_needed"
+  | "profile_ended"
+  | "risk_exceeded"
+  | "budget_exceeded"
+  | "error"
+  | "approval_requested";
+
+// Sound per alert event; null silences it, unlisted events use their default
+export type SoundMap = Partial<Record<AlertEvent, AlertSound | null>>;
+
+// Checkpoint of a run cut short by a crash or reboot
+export type RunCheckpoint = {
+  profile_id: string;
+  started_at_ms: number;
+  saved_at_ms: number;
+  iterations: number;
+  variables: Record<string, string>;
+  last_successful_action?: string | null;
+};
+
 export type DisplayInfo = {
   id: number;
   name?: string | null;
@@ -0,0 +1,34 @@
+import { describe, it, expect } from "vitest";
+import { render, screen, act } from "@testing-library/react";
+import "@testing-library/jest-dom/vitest";
+import { ActivityFeed } from "../src/components/ActivityFeed";
+import { emitTestEvent } from "../src/eventBridge";
+
+describe("ActivityFeed", () => {
+  it("renders nothing until the runner reports a transition", () => {
+    const { container } = render(<ActivityFeed />);
+    expect(container).toBeEmptyDOMElement();
+  });
+
+  it("lists transitions newest first with the LLM risk", async () => {
+    render(<ActivityFeed />);
+    await act(async () => {
+      emitTestEvent("runner://state", { profile_id: "agent", at_ms: 1000, type: "LlmCallStarted", action: "LLMPromptGeneration" });
+      emitTestEvent("runner://state", {
+        profile_id: "agent",
+        at_ms: 2000,
+        type: "LlmCallFinished",
+        action: "LLMPromptGeneration",
+        success: false,
+        risk: 0.9,
+      });
+      emitTestEvent("runner://state", { profile_id: "agent", at_ms: 3000, type: "IterationCompleted", iteration: 4, success: false });
+    });
+
+    const items = (await screen.findByRole("list", { name: "Activity feed" })).querySelectorAll("li");
+    expect(items).toHaveLength(3);
+    expect(items[0]).toHaveTextContent("Iteration 4 ✗");
+    expect(items[1]).toHaveTextContent("✗ LLMPromptGeneration (risk 0.90)");
+    expect(items[2]).toHaveTextContent("calling LLM");
+  });
+});
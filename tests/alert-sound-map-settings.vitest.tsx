@@ -0,0 +1,45 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { render, screen, fireEvent, waitFor } from "@testing-library/react";
+import "@testing-library/jest-dom/vitest";
+import { AlertSoundMapSettings } from "../src/components/AlertSoundMapSettings";
+
+const bridge = vi.hoisted(() => ({
+  audioGetSoundMap: vi.fn(),
+  audioSetSoundMap: vi.fn(),
+  audioTestAlert: vi.fn(),
+}));
+vi.mock("../src/tauriBridge", () => bridge);
+
+describe("AlertSoundMapSettings", () => {
+  beforeEach(() => {
+    bridge.audioGetSoundMap.mockReset().mockResolvedValue({});
+    bridge.audioSetSoundMap.mockReset().mockResolvedValue(undefined);
+    bridge.audioTestAlert.mockReset().mockResolvedValue(undefined);
+  });
+
+  it("shows defaults and saves an override per event", async () => {
+    render(<AlertSoundMapSettings />);
+    const risk = screen.getByLabelText("Risk exceeded sound");
+    expect(risk).toHaveValue("default");
+    expect(screen.getByRole("option", { name: "Default (Silent)" })).toBeInTheDocument();
+
+    fireEvent.change(risk, { target: { value: "completion" } });
+    await waitFor(() => expect(bridge.audioSetSoundMap).toHaveBeenCalledWith({ risk_exceeded: "completion" }));
+    expect(await screen.findByRole("status")).toHaveTextContent("Alert sounds saved");
+  });
+
+  it("silences an event with null and restores the default by dropping it", async () => {
+    bridge.audioGetSoundMap.mockResolvedValue({ error: null, budget_exceeded: "completion" });
+    render(<AlertSoundMapSettings />);
+    await waitFor(() => expect(screen.getByLabelText("Failed sound")).toHaveValue("silent"));
+
+    fireEvent.change(screen.getByLabelText("Budget exceeded sound"), { target: { value: "default" } });
+    await waitFor(() => expect(bridge.audioSetSoundMap).toHaveBeenCalledWith({ error: null }));
+  });
+
+  it("previews the mapped sound of an event", () => {
+    render(<AlertSoundMapSettings />);
+    fireEvent.click(screen.getByRole("button", { name: "Test Needs intervention sound" }));
+    expect(bridge.audioTestAlert).toHaveBeenCalledWith("intervention_needed");
+  });
+});
@@ -0,0 +1,52 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { render, screen } from "@testing-library/react";
+import "@testing-library/jest-dom/vitest";
+import { AutomationStatus } from "../src/components/AutomationStatus";
+
+const bridge = vi.hoisted(() => ({
+  automationCapabilities: vi.fn(),
+}));
+vi.mock("../src/tauriBridge", () => bridge);
+
+describe("AutomationStatus", () => {
+  beforeEach(() => {
+    bridge.automationCapabilities.mockReset();
+  });
+
+  it("shows the Wayland backend and its hint", async () => {
+    bridge.automationCapabilities.mockResolvedValue({
+      session: "wayland",
+      backend: "ydotool",
+      mouse: true,
+      keyboard: true,
+      text: true,
+      hint: "Cursor positions are exact only with pointer acceleration turned off (flat profile).",
+    });
+    render(<AutomationStatus />);
+    expect(await screen.findByText("ydotool (uinput)")).toBeInTheDocument();
+    expect(screen.getByText(/wayland session/)).toBeInTheDocument();
+    expect(screen.getByText(/✓ Mouse/)).toBeInTheDocument();
+    expect(screen.getByRole("status")).toHaveTextContent("pointer acceleration");
+  });
+
+  it("marks missing features when no backend works", async () => {
+    bridge.automationCapabilities.mockResolvedValue({
+      session: "wayland",
+      backend: "none",
+      mouse: false,
+      keyboard: false,
+      text: false,
+      hint: "No input backend: ydotool is not installed. Install ydotool and start ydotoold.",
+    });
+    render(<AutomationStatus />);
+    expect(await screen.findByText("None")).toBeInTheDocument();
+    expect(screen.getByText(/✗ Keyboard/)).toBeInTheDocument();
+    expect(screen.getByRole("status")).toHaveTextContent("Install ydotool");
+  });
+
+  it("explains that the web preview has no input backend", async () => {
+    bridge.automationCapabilities.mockResolvedValue(null);
+    render(<AutomationStatus />);
+    expect(await screen.findByText(/only available in the desktop app/)).toBeInTheDocument();
+  });
+});
@@ -0,0 +1,36 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { render, screen, fireEvent, waitFor } from "@testing-library/react";
+import "@testing-library/jest-dom/vitest";
+import { CustomSoundSettings } from "../src/components/CustomSoundSettings";
+
+const bridge = vi.hoisted(() => ({
+  audioGetCustomSounds: vi.fn(),
+  audioSetCustomSound: vi.fn(),
+}));
+vi.mock("../src/tauriBridge", () => bridge);
+
+describe("CustomSoundSettings", () => {
+  beforeEach(() => {
+    bridge.audioGetCustomSounds.mockReset().mockResolvedValue({ completion: "/home/me/done.ogg" });
+    bridge.audioSetCustomSound.mockReset().mockResolvedValue(undefined);
+  });
+
+  it("loads saved paths and restores the built-in sound", async () => {
+    render(<CustomSoundSettings />);
+    expect(await screen.findByLabelText("Completion sound file")).toHaveValue("/home/me/done.ogg");
+
+    fireEvent.click(screen.getAllByRole("button", { name: "Reset" })[1]);
+    await waitFor(() => expect(bridge.audioSetCustomSound).toHaveBeenCalledWith("completion", null));
+    expect(await screen.findByRole("status")).toHaveTextContent("Built-in completion sound restored");
+  });
+
+  it("saves a custom file and shows rejected formats", async () => {
+    bridge.audioSetCustomSound.mockRejectedValue("Unsupported sound file '/tmp/a.txt'; use wav, ogg, mp3, flac");
+    render(<CustomSoundSettings />);
+    fireEvent.change(screen.getByLabelText("Intervention sound file"), { target: { value: " /tmp/a.txt " } });
+    fireEvent.click(screen.getAllByRole("button", { name: "Save" })[0]);
+
+    await waitFor(() => expect(bridge.audioSetCustomSound).toHaveBeenCalledWith("intervention", "/tmp/a.txt"));
+    expect(await screen.findByRole("status")).toHaveTextContent("Unsupported sound file");
+  });
+});
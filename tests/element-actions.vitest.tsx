@@ -0,0 +1,65 @@
+import { describe, it, expect, vi, beforeAll, beforeEach } from "vitest";
+import { render, screen, fireEvent, within } from "@testing-library/react";
+import "@testing-library/jest-dom/vitest";
+import { useState } from "react";
+import { GraphComposer } from "../src/components/GraphComposer";
+import { ActionConfig, Profile, defaultPresetProfile } from "../src/types";
+import { registerBuiltins } from "../src/plugins/builtins";
+
+const bridge = vi.hoisted(() => ({
+  findElements: vi.fn(),
+}));
+vi.mock("../src/tauriBridge", () => bridge);
+
+beforeAll(() => registerBuiltins());
+
+function renderWith(actions: ActionConfig[]) {
+  let latest: Profile | null = null;
+  const Wrapper = () => {
+    const [p, setP] = useState<Profile>(() => ({ ...defaultPresetProfile(), actions }));
+    latest = p;
+    return <GraphComposer profile={p} onChange={setP} />;
+  };
+  render(<Wrapper />);
+  return () => latest!;
+}
+
+describe("Element actions", () => {
+  beforeEach(() => {
+    bridge.findElements.mockReset().mockResolvedValue([
+      { role: "button", name: "Run", app: "Editor", text: "Run", x: 120, y: 100, width: 80, height: 28 },
+    ]);
+  });
+
+  it("edits the selector of a ClickElement action and finds it", async () => {
+    const profile = renderWith([{ type: "ClickElement", element: { role: "button", name: "Run" }, button: "Left" }]);
+    const row = within(screen.getAllByRole("listitem")[0]);
+    fireEvent.change(row.getByPlaceholderText("any"), { target: { value: "Editor" } });
+    fireEvent.click(row.getByLabelText("Invoke without mouse"));
+    expect(profile().actions[0]).toEqual({
+      type: "ClickElement",
+      element: { role: "button", name: "Run", app: "Editor" },
+      button: "Left",
+      invoke: true,
+    });
+
+    fireEvent.click(row.getByRole("button", { name: "Find" }));
+    expect(await row.findByRole("status")).toHaveTextContent("1 match, first at (120, 100)");
+    expect(bridge.findElements).toHaveBeenCalledWith({ role: "button", name: "Run", app: "Editor" });
+  });
+
+  it("sets the variable of a ReadElementText action and reports lookup errors", async () => {
+    bridge.findElements.mockRejectedValueOnce("atspi_unavailable: the accessibility bus is not running");
+    const profile = renderWith([{ type: "ReadElementText", element: { role: "text field", name: "Status" } }]);
+    const row = within(screen.getAllByRole("listitem")[0]);
+    fireEvent.change(row.getByPlaceholderText("element_text"), { target: { value: "status" } });
+    expect(profile().actions[0]).toEqual({
+      type: "ReadElementText",
+      element: { role: "text field", name: "Status" },
+      variable_name: "status",
+    });
+
+    fireEvent.click(row.getByRole("button", { name: "Find" }));
+    expect(await row.findByRole("status")).toHaveTextContent("accessibility bus is not running");
+  });
+});
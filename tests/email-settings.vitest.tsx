@@ -0,0 +1,70 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { render, screen, fireEvent, waitFor } from "@testing-library/react";
+import "@testing-library/jest-dom/vitest";
+import { EmailSettings } from "../src/components/EmailSettings";
+
+const bridge = vi.hoisted(() => ({
+  smtpGet: vi.fn(),
+  smtpSet: vi.fn(),
+  smtpTest: vi.fn(),
+}));
+vi.mock("../src/tauriBridge", () => bridge);
+
+describe("EmailSettings", () => {
+  beforeEach(() => {
+    bridge.smtpGet.mockReset().mockResolvedValue(null);
+    bridge.smtpSet.mockReset().mockResolvedValue(undefined);
+    bridge.smtpTest.mockReset().mockResolvedValue(undefined);
+  });
+
+  it("saves an SMTP server with several recipients", async () => {
+    render(<EmailSettings />);
+    fireEvent.click(screen.getByLabelText("Send email notifications"));
+    fireEvent.change(screen.getByLabelText("SMTP server"), { target: { value: " smtp.example.com " } });
+    fireEvent.change(screen.getByLabelText("SMTP security"), { target: { value: "tls" } });
+    fireEvent.change(screen.getByLabelText("SMTP username"), { target: { value: "bot" } });
+    fireEvent.change(screen.getByLabelText("SMTP password"), { target: { value: "secret" } });
+    fireEvent.change(screen.getByLabelText("From address"), { target: { value: "bot@example.com" } });
+    fireEvent.change(screen.getByLabelText("Recipients"), { target: { value: "ops@example.com, me@example.com," } });
+    fireEvent.click(screen.getByLabelText("Attach screenshot to email"));
+    fireEvent.click(screen.getByRole("button", { name: "Save email settings" }));
+
+    await waitFor(() =>
+      expect(bridge.smtpSet).toHaveBeenCalledWith({
+        host: "smtp.example.com",
+        port: 0,
+        security: "tls",
+        username: "bot",
+        password: "secret",
+        from: "bot@example.com",
+        to: ["ops@example.com", "me@example.com"],
+        include_screenshot: true,
+      }),
+    );
+    expect(await screen.findByRole("status")).toHaveTextContent("Email settings saved");
+  });
+
+  it("loads saved settings, reports test failures and turns email off", async () => {
+    bridge.smtpGet.mockResolvedValue({
+      host: "mail.example.com",
+      port: 2525,
+      security: "starttls",
+      username: "",
+      password: "",
+      from: "bot@example.com",
+      to: ["ops@example.com"],
+      include_screenshot: false,
+    });
+    bridge.smtpTest.mockRejectedValue("Failed to send email: connection refused");
+    render(<EmailSettings />);
+
+    expect(await screen.findByLabelText("SMTP port")).toHaveValue(2525);
+    expect(screen.getByLabelText("Recipients")).toHaveValue("ops@example.com");
+    fireEvent.click(screen.getByRole("button", { name: "Send test email" }));
+    expect(await screen.findByRole("status")).toHaveTextContent("connection refused");
+
+    fireEvent.click(screen.getByLabelText("Send email notifications"));
+    fireEvent.click(screen.getByRole("button", { name: "Save email settings" }));
+    await waitFor(() => expect(bridge.smtpSet).toHaveBeenCalledWith(null));
+  });
+});
@@ -0,0 +1,51 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { render, screen, fireEvent, waitFor } from "@testing-library/react";
+import "@testing-library/jest-dom/vitest";
+import { EnvCredentialImport } from "../src/components/EnvCredentialImport";
+
+const storage = vi.hoisted(() => ({
+  detectEnvCredentials: vi.fn(),
+  importEnvCredentials: vi.fn(),
+}));
+vi.mock("../src/tauriSecureStorage", () => storage);
+
+describe("EnvCredentialImport", () => {
+  beforeEach(() => {
+    storage.detectEnvCredentials.mockReset().mockResolvedValue([]);
+    storage.importEnvCredentials.mockReset().mockResolvedValue([]);
+  });
+
+  it("renders nothing without environment keys", async () => {
+    const { container } = render(<EnvCredentialImport />);
+    await waitFor(() => expect(storage.detectEnvCredentials).toHaveBeenCalled());
+    expect(container).toBeEmptyDOMElement();
+  });
+
+  it("imports only keys that are not stored and have no problem", async () => {
+    storage.detectEnvCredentials.mockResolvedValueOnce([
+      { var: "OPENAI_API_KEY", provider: "openai", stored: true, problem: null },
+      { var: "ANTHROPIC_API_KEY", provider: "anthropic", stored: false, problem: null },
+      { var: "AZURE_OPENAI_API_KEY", provider: "azure", stored: false, problem: "AZURE_OPENAI_ENDPOINT is not set" },
+    ]);
+    storage.importEnvCredentials.mockResolvedValue(["ANTHROPIC_API_KEY"]);
+    render(<EnvCredentialImport />);
+
+    const list = await screen.findByRole("list", { name: "Environment API keys" });
+    expect(list).toHaveTextContent("ignored, a stored key is used");
+    expect(list).toHaveTextContent("AZURE_OPENAI_ENDPOINT is not set");
+
+    fireEvent.click(screen.getByRole("button", { name: "Import from environment" }));
+    await waitFor(() => expect(storage.importEnvCredentials).toHaveBeenCalledWith(["ANTHROPIC_API_KEY"]));
+    expect(await screen.findByRole("status")).toHaveTextContent("Stored ANTHROPIC_API_KEY");
+  });
+
+  it("shows why an import failed", async () => {
+    storage.detectEnvCredentials.mockResolvedValue([
+      { var: "GEMINI_API_KEY", provider: "gemini", stored: false, problem: null },
+    ]);
+    storage.importEnvCredentials.mockRejectedValue("Keyring unavailable");
+    render(<EnvCredentialImport />);
+    fireEvent.click(await screen.findByRole("button", { name: "Import from environment" }));
+    expect(await screen.findByRole("alert")).toHaveTextContent("Keyring unavailable");
+  });
+});
@@ -106,6 +106,34 @@ describe("EventLog", () => {
     expect(screen.getByText(/heartbeat_stalled/)).toBeInTheDocument();
   });
 
+  it("renders budget breaches with the budget name and limit", () => {
+    render(<EventLog events={[{ type: "BudgetExceeded", budget: "max_iterations", limit: 10 }]} />);
+
+    expect(screen.getByText(/Budget Exceeded/)).toBeInTheDocument();
+    expect(screen.getByText(/max_iterations \(limit 10\)/)).toBeInTheDocument();
+  });
+
+  it("renders a ProfileChained event with source and target profiles", () => {
+    render(<EventLog events={[{ type: "ProfileChained", from: "tests", to: "deploy", on: "complete" }]} />);
+
+    expect(screen.getByText(/Profile Chained/)).toBeInTheDocument();
+    expect(screen.getByText(/tests → deploy \(on complete\)/)).toBeInTheDocument();
+  });
+
+  it("renders notifications held back by quiet hours", () => {
+    render(<EventLog events={[{ type: "NotificationSuppressed", event: "completed", channels: ["sound", "desktop"] }]} />);
+
+    expect(screen.getByText(/Quiet Hours/)).toBeInTheDocument();
+    expect(screen.getByText(/completed: held back sound, desktop/)).toBeInTheDocument();
+  });
+
+  it("renders a warning about a stored API key", () => {
+    render(<EventLog events={[{ type: "CredentialWarning", credential: "openai_api_key", reason: "has not worked for 3 days; it may have been revoked" }]} />);
+
+    expect(screen.getByText(/API Key/)).toBeInTheDocument();
+    expect(screen.getByText(/openai_api_key has not worked for 3 days/)).toBeInTheDocument();
+  });
+
   it("keeps rows in the same order as supplied events", () => {
     const events: Event[] = [
       { type: "TriggerFired" },
@@ -47,4 +47,15 @@ describe("GraphComposer", () => {
     expect(screen.getByText(/change detected for/i)).toBeTruthy();
     expect(screen.getByText(/check\(s\)/i)).toBeTruthy();
   });
+
+  it("switches to a hotkey trigger when a launch hotkey is entered", () => {
+    let p = defaultPresetProfile();
+    const onChange = (next: any) => { p = next; };
+    render(<GraphComposer profile={p} onChange={onChange} />);
+
+    const hotkeyInput = screen.getByTitle(/Global hotkey/i).querySelector("input") as HTMLInputElement;
+    fireEvent.change(hotkeyInput, { target: { value: "Ctrl+Alt+D" } });
+    expect(p.trigger.type).toBe("HotkeyTrigger");
+    expect(p.trigger.hotkey).toBe("Ctrl+Alt+D");
+  });
 });
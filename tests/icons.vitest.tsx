@@ -10,6 +10,7 @@ import {
     PlusIcon,
     ScissorsIcon,
     SettingsIcon,
+    EyeIcon,
 } from "../src/components/Icons";
 
 describe("Icons", () => {
@@ -157,6 +158,7 @@ describe("Icons", () => {
                 PlusIcon,
                 ScissorsIcon,
                 SettingsIcon,
+                EyeIcon,
             ];
 
             icons.forEach((IconComponent) => {
@@ -184,6 +186,7 @@ describe("Icons", () => {
                 PlusIcon,
                 ScissorsIcon,
                 SettingsIcon,
+                EyeIcon,
             ];
 
             icons.forEach((IconComponent) => {
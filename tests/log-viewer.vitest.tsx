@@ -0,0 +1,59 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { render, screen, fireEvent, waitFor } from "@testing-library/react";
+import "@testing-library/jest-dom/vitest";
+import { LogViewer } from "../src/components/LogViewer";
+import type { LogLine } from "../src/types";
+
+const bridge = vi.hoisted(() => ({
+  logLevelGet: vi.fn(),
+  logLevelSet: vi.fn(),
+  tailLogs: vi.fn(),
+}));
+vi.mock("../src/tauriBridge", () => bridge);
+
+const failure: LogLine = {
+  timestamp: "2026-10-16T10:52:51.073385Z",
+  level: "warn",
+  target: "loopautoma_lib::domain",
+  message: "Action failed: no display",
+  fields: { code: "automation_failed" },
+  spans: [
+    { name: "run", profile: "p1" },
+    { name: "action", index: 2, action: "Click" },
+  ],
+};
+
+describe("LogViewer", () => {
+  beforeEach(() => {
+    bridge.logLevelGet.mockReset().mockResolvedValue("debug");
+    bridge.logLevelSet.mockReset().mockResolvedValue(undefined);
+    bridge.tailLogs.mockReset().mockResolvedValue([failure]);
+  });
+
+  it("shows the saved level and the newest records with their spans", async () => {
+    render(<LogViewer />);
+    expect(await screen.findByText(/Action failed: no display/)).toBeInTheDocument();
+    expect(screen.getByText(/run profile=p1 › action index=2 action=Click/)).toBeInTheDocument();
+    expect(screen.getByLabelText("Log level")).toHaveValue("debug");
+    expect(bridge.tailLogs).toHaveBeenCalledWith(200, "trace");
+  });
+
+  it("changes the level and filters records by severity", async () => {
+    render(<LogViewer />);
+    await screen.findByText(/Action failed/);
+    fireEvent.change(screen.getByLabelText("Log level"), { target: { value: "trace" } });
+    await waitFor(() => expect(bridge.logLevelSet).toHaveBeenCalledWith("trace"));
+    expect(await screen.findByRole("status")).toHaveTextContent("Log level set to trace");
+
+    bridge.tailLogs.mockResolvedValue([]);
+    fireEvent.change(screen.getByLabelText("Show records"), { target: { value: "error" } });
+    await waitFor(() => expect(bridge.tailLogs).toHaveBeenLastCalledWith(200, "error"));
+    expect(await screen.findByText("No log records.")).toBeInTheDocument();
+  });
+
+  it("reports errors from the backend", async () => {
+    bridge.tailLogs.mockRejectedValue("Log files are not being written");
+    render(<LogViewer />);
+    expect(await screen.findByRole("status")).toHaveTextContent("Log files are not being written");
+  });
+});
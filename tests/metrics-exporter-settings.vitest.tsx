@@ -0,0 +1,50 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { render, screen, fireEvent, waitFor } from "@testing-library/react";
+import "@testing-library/jest-dom/vitest";
+import { MetricsExporterSettings } from "../src/components/MetricsExporterSettings";
+
+const bridge = vi.hoisted(() => ({
+  metricsExporterGet: vi.fn(),
+  metricsExporterSet: vi.fn(),
+  metricsExporterStatus: vi.fn(),
+}));
+vi.mock("../src/tauriBridge", () => bridge);
+
+describe("MetricsExporterSettings", () => {
+  beforeEach(() => {
+    bridge.metricsExporterGet.mockReset().mockResolvedValue(null);
+    bridge.metricsExporterSet.mockReset().mockResolvedValue("http://127.0.0.1:9100/metrics");
+    bridge.metricsExporterStatus.mockReset().mockResolvedValue(null);
+  });
+
+  it("enables the endpoint on the entered port and shows its URL", async () => {
+    render(<MetricsExporterSettings />);
+    fireEvent.click(screen.getByLabelText("Enable metrics endpoint"));
+    fireEvent.change(screen.getByLabelText("Metrics port"), { target: { value: "9100" } });
+    fireEvent.click(screen.getByRole("button", { name: "Save metrics settings" }));
+
+    await waitFor(() => expect(bridge.metricsExporterSet).toHaveBeenCalledWith(9100));
+    expect(await screen.findByText("✓ Serving http://127.0.0.1:9100/metrics")).toBeInTheDocument();
+  });
+
+  it("loads the saved port and turns the endpoint off", async () => {
+    bridge.metricsExporterGet.mockResolvedValue(9464);
+    bridge.metricsExporterSet.mockResolvedValue(null);
+    render(<MetricsExporterSettings />);
+    await waitFor(() => expect(screen.getByLabelText("Metrics port")).toHaveValue(9464));
+    fireEvent.click(screen.getByLabelText("Enable metrics endpoint"));
+    fireEvent.click(screen.getByRole("button", { name: "Save metrics settings" }));
+
+    await waitFor(() => expect(bridge.metricsExporterSet).toHaveBeenCalledWith(null));
+    expect(await screen.findByText("✓ Metrics endpoint turned off")).toBeInTheDocument();
+  });
+
+  it("shows why the endpoint could not start", async () => {
+    bridge.metricsExporterSet.mockRejectedValue("This build has no metrics exporter (feature 'prometheus-metrics')");
+    render(<MetricsExporterSettings />);
+    fireEvent.click(screen.getByLabelText("Enable metrics endpoint"));
+    fireEvent.click(screen.getByRole("button", { name: "Save metrics settings" }));
+
+    expect(await screen.findByRole("status")).toHaveTextContent("feature 'prometheus-metrics'");
+  });
+});
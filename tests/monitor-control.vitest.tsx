@@ -7,6 +7,13 @@ const tauriBridgeMocks = vi.hoisted(() => ({
   profilesSave: vi.fn(),
   monitorStart: vi.fn(),
   monitorStop: vi.fn(),
+  pauseProfile: vi.fn(),
+  resumeProfile: vi.fn(),
+  debugStep: vi.fn(),
+  debugContinue: vi.fn(),
+  lastInterruptedRun: vi.fn(),
+  resumeLastRun: vi.fn(),
+  discardLastRun: vi.fn(),
   startInputRecording: vi.fn(),
   stopInputRecording: vi.fn(),
   windowPosition: vi.fn(),
@@ -16,6 +23,9 @@ const tauriBridgeMocks = vi.hoisted(() => ({
   regionPickerShow: vi.fn(),
   regionPickerComplete: vi.fn(),
   regionPickerCancel: vi.fn(),
+  enumerateWindows: vi.fn().mockResolvedValue([]),
+  listRuns: vi.fn().mockResolvedValue([]),
+  getMetrics: vi.fn().mockResolvedValue([]),
 }));
 
 vi.mock("../src/tauriBridge", () => tauriBridgeMocks);
@@ -24,6 +34,9 @@ const mockProfilesLoad = tauriBridgeMocks.profilesLoad;
 const mockProfilesSave = tauriBridgeMocks.profilesSave;
 const mockMonitorStart = tauriBridgeMocks.monitorStart;
 const mockMonitorStop = tauriBridgeMocks.monitorStop;
+const mockPauseProfile = tauriBridgeMocks.pauseProfile;
+const mockLastInterruptedRun = tauriBridgeMocks.lastInterruptedRun;
+const mockResumeLastRun = tauriBridgeMocks.resumeLastRun;
 const mockStartInputRecording = tauriBridgeMocks.startInputRecording;
 const mockStopInputRecording = tauriBridgeMocks.stopInputRecording;
 const mockWindowPosition = tauriBridgeMocks.windowPosition;
@@ -49,6 +62,7 @@ describe("Monitor control", () => {
     mockWindowInfo.mockReset().mockResolvedValue({ x: 0, y: 0, scale: 1 });
     mockAppQuit.mockReset().mockResolvedValue(undefined);
     mockCaptureRegionThumbnail.mockReset().mockResolvedValue(null);
+    mockLastInterruptedRun.mockReset().mockResolvedValue(null);
   });
 
   it("loads profiles on mount", async () => {
@@ -111,7 +125,29 @@ describe("Monitor control", () => {
     fireEvent.click(startBtn);
 
     await waitFor(() => {
-      expect(mockMonitorStart).toHaveBeenCalledWith("test-1");
+      expect(mockMonitorStart).toHaveBeenCalledWith("test-1", false);
+    });
+  });
+
+  it("starts monitor in step-through mode when the checkbox is ticked", async () => {
+    const testProfile = {
+      id: "test-1",
+      name: "Test",
+      regions: [],
+      trigger: { type: "IntervalTrigger", check_interval_sec: 60 },
+      condition: { type: "RegionCondition", stable_ms: 1000, downscale: 4 },
+      actions: [],
+    };
+    mockProfilesLoad.mockResolvedValue([testProfile]);
+
+    render(<App />);
+
+    const checkbox = await screen.findByRole("checkbox", { name: /Step-through/i });
+    fireEvent.click(checkbox);
+    fireEvent.click(screen.getByRole("button", { name: /^Start$/i }));
+
+    await waitFor(() => {
+      expect(mockMonitorStart).toHaveBeenCalledWith("test-1", true);
     });
   });
 
@@ -164,6 +200,58 @@ describe("Monitor control", () => {
     });
   });
 
+  it("pauses the running monitor when Pause button clicked", async () => {
+    const testProfile = {
+      id: "test-1",
+      name: "Test",
+      regions: [],
+      trigger: { type: "IntervalTrigger", check_interval_sec: 60 },
+      condition: { type: "RegionCondition", stable_ms: 1000, downscale: 4 },
+      actions: [],
+    };
+    mockProfilesLoad.mockResolvedValue([testProfile]);
+    mockPauseProfile.mockReset().mockResolvedValue(undefined);
+
+    render(<App />);
+
+    const startBtn = await screen.findByRole("button", { name: /^Start$/i });
+    fireEvent.click(startBtn);
+
+    const pauseBtn = await screen.findByRole("button", { name: /^Pause$/i });
+    fireEvent.click(pauseBtn);
+
+    await waitFor(() => expect(mockPauseProfile).toHaveBeenCalled());
+  });
+
+  it("offers to resume a run interrupted by a crash", async () => {
+    const testProfile = {
+      id: "test-1",
+      name: "Overnight",
+      regions: [],
+      trigger: { type: "IntervalTrigger", check_interval_sec: 60 },
+      condition: { type: "RegionCondition", stable_ms: 1000, downscale: 4 },
+      actions: [],
+    };
+    mockProfilesLoad.mockResolvedValue([testProfile]);
+    mockLastInterruptedRun.mockResolvedValue({
+      profile_id: "test-1",
+      started_at_ms: 1,
+      saved_at_ms: 2,
+      iterations: 12,
+      variables: {},
+    });
+    mockResumeLastRun.mockReset().mockResolvedValue("test-1");
+
+    render(<App />);
+
+    expect(await screen.findByText(/was interrupted after 12 iterations/)).toBeTruthy();
+    fireEvent.click(screen.getByRole("button", { name: /^Resume$/i }));
+
+    await waitFor(() => expect(mockResumeLastRun).toHaveBeenCalled());
+    await waitFor(() => expect(screen.getByText(/Running/)).toBeTruthy());
+    expect(screen.queryByText(/was interrupted/)).toBeNull();
+  });
+
   it("invokes appQuit when Quit button is clicked", async () => {
     const infoSpy = vi.spyOn(console, "info").mockImplementation(() => { });
     mockProfilesLoad.mockResolvedValue([]);
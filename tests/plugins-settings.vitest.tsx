@@ -0,0 +1,63 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { render, screen, fireEvent, waitFor } from "@testing-library/react";
+import "@testing-library/jest-dom/vitest";
+import { PluginsSettings } from "../src/components/PluginsSettings";
+import type { PluginInfo } from "../src/types";
+
+const bridge = vi.hoisted(() => ({
+  pluginsList: vi.fn(),
+  pluginsSetEnabled: vi.fn(),
+}));
+vi.mock("../src/tauriBridge", () => bridge);
+
+const jira: PluginInfo = {
+  id: "jira",
+  dir: "/home/me/.config/loopautoma/plugins/jira",
+  manifest: {
+    id: "jira",
+    name: "Jira",
+    version: "1.2.0",
+    api_version: 1,
+    library: "libjira.so",
+    capabilities: ["input"],
+    actions: [{ kind: "comment", description: "Comment on an issue" }],
+    triggers: [{ kind: "issue_assigned", description: "" }],
+    notifiers: [],
+  },
+  enabled: false,
+  loaded: false,
+  error: null,
+};
+
+const broken: PluginInfo = {
+  id: "broken",
+  dir: "/home/me/.config/loopautoma/plugins/broken",
+  manifest: null,
+  enabled: false,
+  loaded: false,
+  error: "Invalid plugin.json: EOF while parsing",
+};
+
+describe("PluginsSettings", () => {
+  beforeEach(() => {
+    bridge.pluginsList.mockReset().mockResolvedValue([jira, broken]);
+    bridge.pluginsSetEnabled.mockReset().mockResolvedValue([{ ...jira, enabled: true }, broken]);
+  });
+
+  it("lists plugins with what they register and the capabilities they use", async () => {
+    render(<PluginsSettings />);
+    expect(await screen.findByText("Jira 1.2.0")).toBeInTheDocument();
+    expect(screen.getByText(/action comment, trigger issue_assigned · may use input/)).toBeInTheDocument();
+    expect(screen.getByText("Invalid plugin.json: EOF while parsing")).toBeInTheDocument();
+    expect(screen.getByLabelText("Enable broken")).toBeDisabled();
+  });
+
+  it("enables a plugin for the next start", async () => {
+    render(<PluginsSettings />);
+    fireEvent.click(await screen.findByLabelText("Enable jira"));
+
+    await waitFor(() => expect(bridge.pluginsSetEnabled).toHaveBeenCalledWith("jira", true));
+    expect(await screen.findByRole("status")).toHaveTextContent("✓ Jira enabled; restart loopautoma to apply");
+    expect(screen.getByLabelText("Enable jira")).toBeChecked();
+  });
+});
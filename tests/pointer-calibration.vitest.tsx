@@ -0,0 +1,44 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { render, screen, fireEvent } from "@testing-library/react";
+import "@testing-library/jest-dom/vitest";
+import { PointerCalibration } from "../src/components/PointerCalibration";
+
+const bridge = vi.hoisted(() => ({
+  calibrate: vi.fn(),
+  calibrationGet: vi.fn(),
+  calibrationReset: vi.fn(),
+}));
+vi.mock("../src/tauriBridge", () => bridge);
+
+describe("PointerCalibration", () => {
+  beforeEach(() => {
+    bridge.calibrationGet.mockReset().mockResolvedValue(null);
+    bridge.calibrationReset.mockReset().mockResolvedValue(undefined);
+    bridge.calibrate.mockReset().mockResolvedValue({
+      samples: [],
+      transform: { scale_x: 0.8, scale_y: 0.8, offset_x: -80, offset_y: 0 },
+      residual_px: 0.4,
+      findings: ["The pointer lands at 1.25× / 1.25× the requested x / y: input and capture disagree on display scaling"],
+    });
+  });
+
+  it("calibrates and shows the correction with what was found", async () => {
+    render(<PointerCalibration />);
+    expect(await screen.findByText("Pointer moves are not corrected.")).toBeInTheDocument();
+
+    fireEvent.click(screen.getByRole("button", { name: "Calibrate pointer" }));
+    expect(await screen.findByText("Pointer moves are corrected: x ×0.800 − 80 px, y ×0.800 + 0 px")).toBeInTheDocument();
+    expect(screen.getByLabelText("Calibration findings")).toHaveTextContent("disagree on display scaling");
+
+    fireEvent.click(screen.getByRole("button", { name: "Reset calibration" }));
+    expect(await screen.findByText("Pointer moves are not corrected.")).toBeInTheDocument();
+    expect(bridge.calibrationReset).toHaveBeenCalled();
+  });
+
+  it("reports backends that cannot be calibrated", async () => {
+    bridge.calibrate.mockRejectedValue("calibration_unsupported: The input backend cannot report where the pointer is");
+    render(<PointerCalibration />);
+    fireEvent.click(screen.getByRole("button", { name: "Calibrate pointer" }));
+    expect(await screen.findByRole("status")).toHaveTextContent("cannot report where the pointer is");
+  });
+});
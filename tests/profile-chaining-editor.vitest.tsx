@@ -0,0 +1,40 @@
+import { describe, it, expect, vi } from "vitest";
+import { render, screen, fireEvent } from "@testing-library/react";
+import "@testing-library/jest-dom/vitest";
+import { ProfileChainingEditor } from "../src/components/ProfileChainingEditor";
+import { defaultPresetProfile, Profile } from "../src/types";
+
+describe("ProfileChainingEditor", () => {
+  const base: Profile = { ...defaultPresetProfile(), id: "tests", name: "Run tests" };
+  const deploy: Profile = { ...defaultPresetProfile(), id: "deploy", name: "Deploy docs" };
+
+  it("sets and clears the on-complete follow-up profile", () => {
+    const onChange = vi.fn();
+    const { rerender } = render(
+      <ProfileChainingEditor profile={base} profiles={[base, deploy]} onProfileChange={onChange} />
+    );
+
+    fireEvent.change(screen.getByLabelText("On complete"), { target: { value: "deploy" } });
+    const chained = onChange.mock.calls[0][0] as Profile;
+    expect(chained.on_complete).toEqual({ profile_id: "deploy" });
+    expect(chained.on_failure).toBeUndefined();
+
+    rerender(<ProfileChainingEditor profile={chained} profiles={[base, deploy]} onProfileChange={onChange} />);
+    fireEvent.change(screen.getByLabelText("On complete"), { target: { value: "" } });
+    expect("on_complete" in (onChange.mock.calls[1][0] as Profile)).toBe(false);
+  });
+
+  it("passes selected context variables to the follow-up run", () => {
+    const onChange = vi.fn();
+    const profile: Profile = { ...base, on_failure: { profile_id: "deploy" } };
+    render(<ProfileChainingEditor profile={profile} profiles={[base, deploy]} onProfileChange={onChange} />);
+
+    const input = screen.getByLabelText("Pass variables");
+    fireEvent.change(input, { target: { value: "ticket, , branch" } });
+    fireEvent.blur(input);
+    expect((onChange.mock.calls[0][0] as Profile).on_failure).toEqual({
+      profile_id: "deploy",
+      pass_variables: ["ticket", "branch"],
+    });
+  });
+});
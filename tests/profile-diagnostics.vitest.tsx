@@ -0,0 +1,37 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { render, screen, fireEvent } from "@testing-library/react";
+import "@testing-library/jest-dom/vitest";
+import { ProfileDiagnostics } from "../src/components/ProfileDiagnostics";
+import { defaultPresetProfile } from "../src/types";
+
+const bridge = vi.hoisted(() => ({ validateProfile: vi.fn() }));
+vi.mock("../src/tauriBridge", () => bridge);
+
+describe("ProfileDiagnostics", () => {
+  const profile = defaultPresetProfile();
+
+  beforeEach(() => {
+    bridge.validateProfile.mockReset();
+  });
+
+  it("lists errors and warnings with the offending field", async () => {
+    bridge.validateProfile.mockResolvedValue([
+      { severity: "error", field: "actions[1].text", message: "Unknown key 'ArrowUp'" },
+      { severity: "warning", field: "regions[0].rect", message: "Region extends beyond the display edge" },
+    ]);
+    render(<ProfileDiagnostics profile={profile} />);
+    fireEvent.click(screen.getByRole("button", { name: "Validate profile" }));
+
+    expect(await screen.findByText("actions[1].text")).toBeInTheDocument();
+    expect(screen.getByText(/Unknown key 'ArrowUp'/)).toBeInTheDocument();
+    expect(screen.getByText(/extends beyond the display edge/)).toBeInTheDocument();
+    expect(bridge.validateProfile).toHaveBeenCalledWith(profile);
+  });
+
+  it("reports a clean profile", async () => {
+    bridge.validateProfile.mockResolvedValue([]);
+    render(<ProfileDiagnostics profile={profile} />);
+    fireEvent.click(screen.getByRole("button", { name: "Validate profile" }));
+    expect(await screen.findByText("No problems found.")).toBeInTheDocument();
+  });
+});
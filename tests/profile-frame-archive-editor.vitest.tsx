@@ -0,0 +1,32 @@
+import { describe, it, expect, vi } from "vitest";
+import { render, screen, fireEvent } from "@testing-library/react";
+import "@testing-library/jest-dom/vitest";
+import { ProfileFrameArchiveEditor } from "../src/components/ProfileFrameArchiveEditor";
+import { defaultPresetProfile, Profile } from "../src/types";
+
+describe("ProfileFrameArchiveEditor", () => {
+  const base: Profile = { ...defaultPresetProfile(), id: "agent", name: "Agent" };
+
+  it("turns the archive on with the backend defaults", () => {
+    const onChange = vi.fn();
+    render(<ProfileFrameArchiveEditor profile={base} onProfileChange={onChange} />);
+
+    fireEvent.click(screen.getByLabelText("Archive region frames after each iteration"));
+    expect((onChange.mock.calls[0][0] as Profile).frame_archive).toEqual({ max_size: 480, max_frames: 500 });
+  });
+
+  it("edits the limits and drops the field when turned off", () => {
+    const onChange = vi.fn();
+    const profile: Profile = { ...base, frame_archive: { max_size: 480, max_frames: 500 } };
+    render(<ProfileFrameArchiveEditor profile={profile} onProfileChange={onChange} />);
+
+    fireEvent.change(screen.getByLabelText("Iterations per run"), { target: { value: "50" } });
+    expect((onChange.mock.calls[0][0] as Profile).frame_archive).toEqual({ max_size: 480, max_frames: 50 });
+
+    fireEvent.change(screen.getByLabelText("Longest side (px)"), { target: { value: "0" } });
+    expect(onChange).toHaveBeenCalledTimes(1);
+
+    fireEvent.click(screen.getByLabelText("Archive region frames after each iteration"));
+    expect("frame_archive" in (onChange.mock.calls[1][0] as Profile)).toBe(false);
+  });
+});
@@ -0,0 +1,58 @@
+import { describe, it, expect, vi } from "vitest";
+import { render, screen, fireEvent } from "@testing-library/react";
+import "@testing-library/jest-dom/vitest";
+import { ProfileNotificationsEditor } from "../src/components/ProfileNotificationsEditor";
+import { defaultPresetProfile, Profile } from "../src/types";
+
+describe("ProfileNotificationsEditor", () => {
+  const base: Profile = { ...defaultPresetProfile(), id: "overnight", name: "Overnight" };
+
+  it("shows the default routing and overrides it per event", () => {
+    const onChange = vi.fn();
+    render(<ProfileNotificationsEditor profile={base} onProfileChange={onChange} />);
+
+    const completed = screen.getByLabelText("Completed: Sound");
+    expect(completed).toBeChecked();
+    fireEvent.click(completed);
+    expect((onChange.mock.calls[0][0] as Profile).notifications).toEqual({ completed: ["desktop", "webhook", "email"] });
+  });
+
+  it("posts desktop notifications for everything but failures by default", () => {
+    const onChange = vi.fn();
+    render(<ProfileNotificationsEditor profile={base} onProfileChange={onChange} />);
+
+    expect(screen.getByLabelText("Needs intervention: Desktop")).toBeChecked();
+    expect(screen.getByLabelText("Risk exceeded: Desktop")).toBeChecked();
+    const failed = screen.getByLabelText("Failed: Desktop");
+    expect(failed).not.toBeChecked();
+    fireEvent.click(failed);
+    expect((onChange.mock.calls[0][0] as Profile).notifications).toEqual({ failed: ["sound", "webhook", "email", "desktop"] });
+  });
+
+  it("sends only run starts to webhooks by default", () => {
+    render(<ProfileNotificationsEditor profile={base} onProfileChange={vi.fn()} />);
+    expect(screen.getByLabelText("Started: Webhooks")).toBeChecked();
+    expect(screen.getByLabelText("Started: Sound")).not.toBeChecked();
+    expect(screen.getByLabelText("Failed: Webhooks")).toBeChecked();
+  });
+
+  it("emails a summary when a run ends or needs intervention", () => {
+    render(<ProfileNotificationsEditor profile={base} onProfileChange={vi.fn()} />);
+    expect(screen.getByLabelText("Completed: Email")).toBeChecked();
+    expect(screen.getByLabelText("Failed: Email")).toBeChecked();
+    expect(screen.getByLabelText("Needs intervention: Email")).toBeChecked();
+    expect(screen.getByLabelText("Started: Email")).not.toBeChecked();
+    expect(screen.getByLabelText("Risk exceeded: Email")).not.toBeChecked();
+  });
+
+  it("drops routes that match the default", () => {
+    const onChange = vi.fn();
+    const profile: Profile = { ...base, notifications: { failed: ["webhook", "email"] } };
+    render(<ProfileNotificationsEditor profile={profile} onProfileChange={onChange} />);
+
+    const failed = screen.getByLabelText("Failed: Sound");
+    expect(failed).not.toBeChecked();
+    fireEvent.click(failed);
+    expect("notifications" in (onChange.mock.calls[0][0] as Profile)).toBe(false);
+  });
+});
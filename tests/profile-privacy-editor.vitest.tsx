@@ -0,0 +1,34 @@
+import { describe, it, expect, vi } from "vitest";
+import { render, screen, fireEvent } from "@testing-library/react";
+import "@testing-library/jest-dom/vitest";
+import { ProfilePrivacyEditor } from "../src/components/ProfilePrivacyEditor";
+import { defaultPresetProfile, Profile } from "../src/types";
+
+describe("ProfilePrivacyEditor", () => {
+  const base: Profile = { ...defaultPresetProfile(), id: "agent", name: "Agent" };
+
+  it("turns the filter on with every detector", () => {
+    const onChange = vi.fn();
+    render(<ProfilePrivacyEditor profile={base} onProfileChange={onChange} />);
+
+    expect(screen.queryByLabelText("Card numbers")).not.toBeInTheDocument();
+    fireEvent.click(screen.getByLabelText("Mask personal data in captures"));
+    expect((onChange.mock.calls[0][0] as Profile).privacy).toEqual({ detectors: ["email", "credit_card", "api_key"] });
+  });
+
+  it("toggles single detectors and drops the field when turned off", () => {
+    const onChange = vi.fn();
+    const profile: Profile = { ...base, privacy: { detectors: ["email", "api_key"] } };
+    render(<ProfilePrivacyEditor profile={profile} onProfileChange={onChange} />);
+
+    expect(screen.getByLabelText("Card numbers")).not.toBeChecked();
+    fireEvent.click(screen.getByLabelText("Card numbers"));
+    expect((onChange.mock.calls[0][0] as Profile).privacy).toEqual({ detectors: ["email", "credit_card", "api_key"] });
+
+    fireEvent.click(screen.getByLabelText("Email addresses"));
+    expect((onChange.mock.calls[1][0] as Profile).privacy).toEqual({ detectors: ["api_key"] });
+
+    fireEvent.click(screen.getByLabelText("Mask personal data in captures"));
+    expect("privacy" in (onChange.mock.calls[2][0] as Profile)).toBe(false);
+  });
+});
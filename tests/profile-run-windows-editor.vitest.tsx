@@ -0,0 +1,34 @@
+import { describe, it, expect, vi } from "vitest";
+import { render, screen, fireEvent } from "@testing-library/react";
+import "@testing-library/jest-dom/vitest";
+import { ProfileRunWindowsEditor } from "../src/components/ProfileRunWindowsEditor";
+import { defaultPresetProfile, Profile } from "../src/types";
+
+describe("ProfileRunWindowsEditor", () => {
+  const base: Profile = { ...defaultPresetProfile(), id: "overnight", name: "Overnight" };
+
+  it("adds a weekday overnight window", () => {
+    const onChange = vi.fn();
+    render(<ProfileRunWindowsEditor profile={base} onProfileChange={onChange} />);
+
+    fireEvent.click(screen.getByRole("button", { name: "Add window" }));
+    expect((onChange.mock.calls[0][0] as Profile).run_windows).toEqual([
+      { days: ["mon", "tue", "wed", "thu", "fri"], start: "22:00", end: "06:00" },
+    ]);
+  });
+
+  it("edits days and times and drops the field when the last window is removed", () => {
+    const onChange = vi.fn();
+    const profile: Profile = { ...base, run_windows: [{ days: ["mon"], start: "09:00", end: "17:00" }] };
+    render(<ProfileRunWindowsEditor profile={profile} onProfileChange={onChange} />);
+
+    fireEvent.click(screen.getByLabelText("Window 1: sat"));
+    expect((onChange.mock.calls[0][0] as Profile).run_windows?.[0].days).toEqual(["mon", "sat"]);
+
+    fireEvent.change(screen.getByLabelText("Window 1 end"), { target: { value: "18:30" } });
+    expect((onChange.mock.calls[1][0] as Profile).run_windows?.[0].end).toBe("18:30");
+
+    fireEvent.click(screen.getByRole("button", { name: "Remove window 1" }));
+    expect("run_windows" in (onChange.mock.calls[2][0] as Profile)).toBe(false);
+  });
+});
@@ -0,0 +1,50 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { render, screen, fireEvent, waitFor } from "@testing-library/react";
+import "@testing-library/jest-dom/vitest";
+import { ProfileSecrets } from "../src/components/ProfileSecrets";
+
+const storage = vi.hoisted(() => ({
+  listProfileSecrets: vi.fn(),
+  setProfileSecret: vi.fn(),
+  deleteProfileSecret: vi.fn(),
+}));
+vi.mock("../src/tauriSecureStorage", () => storage);
+
+describe("ProfileSecrets", () => {
+  beforeEach(() => {
+    storage.listProfileSecrets.mockReset().mockResolvedValue([]);
+    storage.setProfileSecret.mockReset().mockResolvedValue(undefined);
+    storage.deleteProfileSecret.mockReset().mockResolvedValue(undefined);
+  });
+
+  it("lists secret names as references and removes them", async () => {
+    storage.listProfileSecrets.mockResolvedValue(["jira_token"]);
+    render(<ProfileSecrets profileId="agent" />);
+    const list = await screen.findByRole("list", { name: "Stored secrets" });
+    expect(list).toHaveTextContent("{{secret:jira_token}}");
+    expect(storage.listProfileSecrets).toHaveBeenCalledWith("agent");
+
+    fireEvent.click(screen.getByRole("button", { name: "Remove secret jira_token" }));
+    await waitFor(() => expect(storage.deleteProfileSecret).toHaveBeenCalledWith("agent", "jira_token"));
+  });
+
+  it("saves a secret and clears the value field", async () => {
+    render(<ProfileSecrets profileId="agent" />);
+    fireEvent.change(screen.getByLabelText("Secret name"), { target: { value: " jira_token " } });
+    fireEvent.change(screen.getByLabelText("Secret value"), { target: { value: "tok-123" } });
+    fireEvent.click(screen.getByRole("button", { name: "Save secret" }));
+
+    await waitFor(() => expect(storage.setProfileSecret).toHaveBeenCalledWith("agent", "jira_token", "tok-123"));
+    expect(await screen.findByRole("status")).toHaveTextContent("Saved {{secret:jira_token}}");
+    expect(screen.getByLabelText("Secret value")).toHaveValue("");
+  });
+
+  it("shows why a secret was refused", async () => {
+    storage.setProfileSecret.mockRejectedValue("Secret name 'jira token' may only contain letters, digits, '_' or '-'");
+    render(<ProfileSecrets profileId="agent" />);
+    fireEvent.change(screen.getByLabelText("Secret name"), { target: { value: "jira token" } });
+    fireEvent.change(screen.getByLabelText("Secret value"), { target: { value: "x" } });
+    fireEvent.click(screen.getByRole("button", { name: "Save secret" }));
+    expect(await screen.findByRole("alert")).toHaveTextContent("may only contain letters");
+  });
+});
@@ -0,0 +1,59 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { render, screen, fireEvent, waitFor } from "@testing-library/react";
+import "@testing-library/jest-dom/vitest";
+import { ProfileStats } from "../src/components/ProfileStats";
+import * as tauriBridge from "../src/tauriBridge";
+import { ProfileMetrics } from "../src/types";
+
+vi.mock("../src/tauriBridge", async () => {
+  const actual = await vi.importActual("../src/tauriBridge");
+  return {
+    ...actual,
+    getMetrics: vi.fn(),
+  };
+});
+
+const flaky: ProfileMetrics = {
+  profile_id: "flaky",
+  runs: 5,
+  succeeded_runs: 1,
+  failed_runs: 3,
+  success_rate: 0.25,
+  iterations: 12,
+  iteration_success_rate: 2 / 3,
+  avg_iteration_ms: 200,
+  avg_capture_ms: 5,
+  llm_calls: 10,
+  avg_llm_ms: 1500,
+  llm_cost_usd: 0.05,
+  interventions_per_run: 0.8,
+  errors_per_run: 1,
+  failure_reasons: [{ reason: "no progress", runs: 2 }],
+  last_run_at_ms: 5,
+};
+
+describe("ProfileStats", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+    vi.mocked(tauriBridge.getMetrics).mockResolvedValue([flaky]);
+  });
+
+  it("shows success rates, latencies and the top failure reason", async () => {
+    render(<ProfileStats />);
+    const row = (await screen.findByText("flaky")).closest("tr")!;
+    expect(row).toHaveTextContent("25%");
+    expect(row).toHaveTextContent("67%");
+    expect(row).toHaveTextContent("200ms");
+    expect(row).toHaveTextContent("1.5s");
+    expect(row).toHaveTextContent("0.8");
+    expect(row).toHaveTextContent("no progress (2×)");
+    expect(tauriBridge.getMetrics).toHaveBeenCalledWith(undefined, 30);
+  });
+
+  it("reloads for the chosen period", async () => {
+    render(<ProfileStats />);
+    await screen.findByText("flaky");
+    fireEvent.change(screen.getByRole("combobox"), { target: { value: "" } });
+    await waitFor(() => expect(tauriBridge.getMetrics).toHaveBeenLastCalledWith(undefined, undefined));
+  });
+});
@@ -0,0 +1,62 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { render, screen, fireEvent, waitFor } from "@testing-library/react";
+import "@testing-library/jest-dom/vitest";
+import { ProfileSyncSettings } from "../src/components/ProfileSyncSettings";
+
+const bridge = vi.hoisted(() => ({
+  profileSyncGet: vi.fn(),
+  profileSyncSet: vi.fn(),
+  profileSyncNow: vi.fn(),
+  profileSyncStatus: vi.fn(),
+}));
+vi.mock("../src/tauriBridge", () => bridge);
+
+describe("ProfileSyncSettings", () => {
+  beforeEach(() => {
+    bridge.profileSyncGet.mockReset().mockResolvedValue(null);
+    bridge.profileSyncSet.mockReset().mockResolvedValue(undefined);
+    bridge.profileSyncNow.mockReset();
+    bridge.profileSyncStatus.mockReset().mockResolvedValue(null);
+  });
+
+  it("loads a git source and syncs on demand", async () => {
+    bridge.profileSyncGet.mockResolvedValue({
+      source: { kind: "git", repo: "git@example.com:team/auto.git", branch: null, path: "profiles.yaml" },
+      interval_minutes: 30,
+      sha256: null,
+    });
+    bridge.profileSyncNow.mockResolvedValue({ sha256: "ab", added: ["nightly"], updated: [] });
+    render(<ProfileSyncSettings />);
+
+    expect(await screen.findByLabelText("Repository")).toHaveValue("git@example.com:team/auto.git");
+    expect(screen.getByLabelText("Sync interval in minutes")).toHaveValue(30);
+    fireEvent.click(screen.getByRole("button", { name: "Sync now" }));
+
+    await waitFor(() => expect(bridge.profileSyncNow).toHaveBeenCalled());
+    expect(bridge.profileSyncSet).toHaveBeenCalledWith({
+      source: { kind: "git", repo: "git@example.com:team/auto.git", branch: null, path: "profiles.yaml" },
+      interval_minutes: 30,
+      sha256: null,
+    });
+    expect(await screen.findByRole("status")).toHaveTextContent("1 added, 0 updated");
+  });
+
+  it("saves a pinned URL source and reports checksum failures", async () => {
+    render(<ProfileSyncSettings />);
+    fireEvent.change(screen.getByLabelText("Sync source"), { target: { value: "url" } });
+    fireEvent.change(screen.getByLabelText("Bundle URL"), { target: { value: " https://example.com/p.json " } });
+    fireEvent.change(screen.getByLabelText("Pinned SHA-256"), { target: { value: "f".repeat(64) } });
+    fireEvent.click(screen.getByRole("button", { name: "Save sync settings" }));
+    await waitFor(() =>
+      expect(bridge.profileSyncSet).toHaveBeenCalledWith({
+        source: { kind: "url", url: "https://example.com/p.json" },
+        interval_minutes: 60,
+        sha256: "f".repeat(64),
+      }),
+    );
+
+    bridge.profileSyncNow.mockRejectedValueOnce("Checksum mismatch for p.json");
+    fireEvent.click(screen.getByRole("button", { name: "Sync now" }));
+    expect(await screen.findByText("Error: Checksum mismatch for p.json")).toBeInTheDocument();
+  });
+});
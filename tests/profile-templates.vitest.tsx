@@ -0,0 +1,62 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { render, screen, fireEvent, waitFor } from "@testing-library/react";
+import "@testing-library/jest-dom/vitest";
+import { ProfileTemplates } from "../src/components/ProfileTemplates";
+import { ProfileTemplate, defaultPresetProfile } from "../src/types";
+
+const bridge = vi.hoisted(() => ({
+  listTemplates: vi.fn(),
+  createFromTemplate: vi.fn(),
+}));
+vi.mock("../src/tauriBridge", () => bridge);
+
+const template: ProfileTemplate = {
+  id: "build-failure-alert",
+  name: "Monitor a build and notify on failure",
+  description: "Reads the build output with local OCR.",
+  regions: [{ id: "build-output", name: "Build output" }],
+  parameters: [
+    { key: "failure_keywords", label: "Failure keywords", default: ["FAILED", "error:"] },
+    { key: "check_interval_sec", label: "Check interval (seconds)", default: 15 },
+  ],
+};
+
+describe("ProfileTemplates", () => {
+  beforeEach(() => {
+    bridge.listTemplates.mockReset().mockResolvedValue([template]);
+    bridge.createFromTemplate.mockReset();
+  });
+
+  it("creates a profile with typed parameter values and region rectangles", async () => {
+    const onCreated = vi.fn();
+    const created = { ...defaultPresetProfile(), id: "build-failure-alert", name: "Nightly build" };
+    bridge.createFromTemplate.mockResolvedValueOnce(created);
+    render(<ProfileTemplates onCreated={onCreated} />);
+
+    fireEvent.change(await screen.findByLabelText("Template"), { target: { value: "build-failure-alert" } });
+    expect(screen.getByLabelText("Failure keywords")).toHaveValue("FAILED, error:");
+    fireEvent.change(screen.getByLabelText("Profile name"), { target: { value: "Nightly build" } });
+    fireEvent.change(screen.getByLabelText("Build output"), { target: { value: "0, 40, 800, 600" } });
+    fireEvent.change(screen.getByLabelText("Check interval (seconds)"), { target: { value: "30" } });
+    fireEvent.click(screen.getByRole("button", { name: "Create profile" }));
+
+    await waitFor(() =>
+      expect(bridge.createFromTemplate).toHaveBeenCalledWith("build-failure-alert", {
+        name: "Nightly build",
+        regions: { "build-output": { x: 0, y: 40, width: 800, height: 600 } },
+        values: { failure_keywords: ["FAILED", "error:"], check_interval_sec: 30 },
+      }),
+    );
+    expect(onCreated).toHaveBeenCalledWith(created);
+    expect(await screen.findByText("Created “Nightly build”")).toBeInTheDocument();
+  });
+
+  it("requires a rectangle for every region", async () => {
+    render(<ProfileTemplates onCreated={vi.fn()} />);
+    fireEvent.change(await screen.findByLabelText("Template"), { target: { value: "build-failure-alert" } });
+    fireEvent.click(screen.getByRole("button", { name: "Create profile" }));
+
+    expect(await screen.findByRole("alert")).toHaveTextContent("Enter “Build output” as x, y, width, height");
+    expect(bridge.createFromTemplate).not.toHaveBeenCalled();
+  });
+});
@@ -0,0 +1,44 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { render, screen, fireEvent, waitFor } from "@testing-library/react";
+import "@testing-library/jest-dom/vitest";
+import { ProfileTransfer } from "../src/components/ProfileTransfer";
+import { defaultPresetProfile } from "../src/types";
+
+const bridge = vi.hoisted(() => ({
+  profileExport: vi.fn(),
+  profileImport: vi.fn(),
+}));
+vi.mock("../src/tauriBridge", () => bridge);
+
+describe("ProfileTransfer", () => {
+  const profile = { ...defaultPresetProfile(), id: "agent", name: "Agent" };
+
+  beforeEach(() => {
+    bridge.profileExport.mockReset().mockResolvedValue(undefined);
+    bridge.profileImport.mockReset();
+  });
+
+  it("exports the selected profile to the given path", async () => {
+    render(<ProfileTransfer selectedProfile={profile} onImported={vi.fn()} />);
+    fireEvent.change(screen.getByLabelText("Profile file path"), { target: { value: " /tmp/agent.yaml " } });
+    fireEvent.click(screen.getByRole("button", { name: "Export" }));
+
+    await waitFor(() => expect(bridge.profileExport).toHaveBeenCalledWith("agent", "/tmp/agent.yaml"));
+    expect(await screen.findByText("Exported “Agent”")).toBeInTheDocument();
+  });
+
+  it("reports the imported profile and surfaces validation errors", async () => {
+    const onImported = vi.fn();
+    bridge.profileImport.mockResolvedValueOnce({ ...profile, id: "agent-2" });
+    render(<ProfileTransfer selectedProfile={null} onImported={onImported} />);
+    expect(screen.getByRole("button", { name: "Import" })).toBeDisabled();
+
+    fireEvent.change(screen.getByLabelText("Profile file path"), { target: { value: "/tmp/agent.json" } });
+    fireEvent.click(screen.getByRole("button", { name: "Import" }));
+    await waitFor(() => expect(onImported).toHaveBeenCalledWith(expect.objectContaining({ id: "agent-2" })));
+
+    bridge.profileImport.mockRejectedValueOnce(new Error("Action references unknown region 'gone'"));
+    fireEvent.click(screen.getByRole("button", { name: "Import" }));
+    expect(await screen.findByRole("alert")).toHaveTextContent("unknown region 'gone'");
+  });
+});
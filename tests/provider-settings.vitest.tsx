@@ -0,0 +1,67 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { render, screen, fireEvent, waitFor } from "@testing-library/react";
+import "@testing-library/jest-dom/vitest";
+import { ProviderSettings } from "../src/components/ProviderSettings";
+
+const storage = vi.hoisted(() => ({
+  listProviders: vi.fn(),
+  setProvider: vi.fn(),
+  deleteProvider: vi.fn(),
+}));
+vi.mock("../src/tauriSecureStorage", () => storage);
+
+const DAY = 24 * 60 * 60 * 1000;
+
+describe("ProviderSettings", () => {
+  beforeEach(() => {
+    storage.listProviders.mockReset().mockResolvedValue([]);
+    storage.setProvider.mockReset().mockResolvedValue(undefined);
+    storage.deleteProvider.mockReset().mockResolvedValue(undefined);
+  });
+
+  it("lists providers without showing their keys", async () => {
+    storage.listProviders.mockResolvedValue([
+      { id: "openai", kind: "openai", base_url: null, model: "gpt-4o", has_key: true, key: { set_at_ms: Date.now() - 10 * DAY, last_used_ms: Date.now() - DAY, last_failed_ms: null } },
+      { id: "ollama", kind: "ollama", base_url: "http://localhost:11434", model: "llava", has_key: false, key: null },
+    ]);
+    render(<ProviderSettings />);
+    const list = await screen.findByRole("list", { name: "Configured providers" });
+    expect(list).toHaveTextContent("openai");
+    expect(list).toHaveTextContent("✓ key stored");
+    expect(list).toHaveTextContent("set 10 days ago, last worked 1 day ago");
+    expect(list).toHaveTextContent("http://localhost:11434");
+    fireEvent.click(screen.getByRole("button", { name: "Remove ollama" }));
+    await waitFor(() => expect(storage.deleteProvider).toHaveBeenCalledWith("ollama"));
+  });
+
+  it("saves a custom endpoint under its name", async () => {
+    render(<ProviderSettings />);
+    fireEvent.change(screen.getByLabelText("Provider"), { target: { value: "custom" } });
+    fireEvent.change(screen.getByLabelText("Endpoint name"), { target: { value: "lm-studio" } });
+    fireEvent.change(screen.getByLabelText("Provider API key"), { target: { value: "sk-local" } });
+    fireEvent.change(screen.getByLabelText("Base URL"), { target: { value: " http://localhost:1234/v1 " } });
+    fireEvent.click(screen.getByRole("button", { name: "Save provider" }));
+
+    await waitFor(() =>
+      expect(storage.setProvider).toHaveBeenCalledWith("custom:lm-studio", {
+        kind: "custom",
+        api_key: "sk-local",
+        base_url: "http://localhost:1234/v1",
+        model: null,
+      }),
+    );
+    expect(await screen.findByRole("status")).toHaveTextContent("Custom endpoint saved");
+    expect(screen.getByLabelText("Provider API key")).toHaveValue("");
+  });
+
+  it("keeps the stored key when none is entered and shows backend errors", async () => {
+    storage.setProvider.mockRejectedValue("Provider 'anthropic' needs an API key");
+    render(<ProviderSettings />);
+    fireEvent.change(screen.getByLabelText("Model"), { target: { value: "claude-sonnet" } });
+    fireEvent.click(screen.getByRole("button", { name: "Save provider" }));
+    await waitFor(() =>
+      expect(storage.setProvider).toHaveBeenCalledWith("anthropic", { kind: "anthropic", base_url: null, model: "claude-sonnet" }),
+    );
+    expect(await screen.findByRole("status")).toHaveTextContent("needs an API key");
+  });
+});
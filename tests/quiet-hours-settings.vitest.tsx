@@ -0,0 +1,64 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { render, screen, fireEvent, waitFor } from "@testing-library/react";
+import "@testing-library/jest-dom/vitest";
+import { QuietHoursSettings } from "../src/components/QuietHoursSettings";
+
+const bridge = vi.hoisted(() => ({
+  quietHoursGet: vi.fn(),
+  quietHoursSet: vi.fn(),
+}));
+vi.mock("../src/tauriBridge", () => bridge);
+
+describe("QuietHoursSettings", () => {
+  beforeEach(() => {
+    bridge.quietHoursGet.mockReset().mockResolvedValue(null);
+    bridge.quietHoursSet.mockReset().mockResolvedValue(undefined);
+  });
+
+  it("turns do not disturb on immediately", async () => {
+    render(<QuietHoursSettings />);
+    fireEvent.click(screen.getByLabelText("Do not disturb"));
+    await waitFor(() =>
+      expect(bridge.quietHoursSet).toHaveBeenCalledWith({
+        do_not_disturb: true,
+        windows: [],
+        log_suppressed: false,
+        critical: ["risk_exceeded"],
+      }),
+    );
+    expect(await screen.findByRole("status")).toHaveTextContent("Do not disturb is on");
+  });
+
+  it("saves a nightly window, critical events and log-only mode", async () => {
+    render(<QuietHoursSettings />);
+    fireEvent.click(screen.getByRole("button", { name: "Add window" }));
+    fireEvent.change(screen.getByLabelText("Window 1 start"), { target: { value: "23:00" } });
+    fireEvent.click(screen.getByLabelText("Always notify: Needs intervention"));
+    fireEvent.click(screen.getByLabelText("Log held-back notifications in the event log"));
+    fireEvent.click(screen.getByRole("button", { name: "Save quiet hours" }));
+
+    await waitFor(() =>
+      expect(bridge.quietHoursSet).toHaveBeenCalledWith({
+        do_not_disturb: false,
+        windows: [{ days: ["mon", "tue", "wed", "thu", "fri"], start: "23:00", end: "06:00" }],
+        log_suppressed: true,
+        critical: ["risk_exceeded", "intervention_needed"],
+      }),
+    );
+  });
+
+  it("shows validation errors from the backend", async () => {
+    bridge.quietHoursGet.mockResolvedValue({
+      do_not_disturb: false,
+      windows: [{ days: [], start: "22:00", end: "07:00" }],
+      log_suppressed: false,
+      critical: [],
+    });
+    bridge.quietHoursSet.mockRejectedValue("quiet_hours.windows[0].end: '' is not a time of day");
+    render(<QuietHoursSettings />);
+    expect(await screen.findByLabelText("Window 1 end")).toHaveValue("07:00");
+    expect(screen.getByLabelText("Always notify: Risk exceeded")).not.toBeChecked();
+    fireEvent.click(screen.getByRole("button", { name: "Save quiet hours" }));
+    expect(await screen.findByRole("status")).toHaveTextContent("is not a time of day");
+  });
+});
@@ -2,10 +2,10 @@ import { describe, it, expect, vi, beforeEach } from "vitest";
 import { render, screen, fireEvent, waitFor } from "@testing-library/react";
 import { RegionAuthoringPanel } from "../src/components/RegionAuthoringPanel";
 import * as tauriBridge from "../src/tauriBridge";
-import { Region } from "../src/types";
+import { PickedRegion, Region } from "../src/types";
 import { emitTestEvent } from "../src/eventBridge";
 
-const emitRegionPick = async (payload: { rect: Region["rect"]; thumbnail_png_base64?: string | null }) => {
+const emitRegionPick = async (payload: { rect: Region["rect"]; thumbnail_png_base64?: string | null; picked?: PickedRegion }) => {
   await Promise.resolve();
   emitTestEvent("loopautoma://region_pick_complete", payload);
 };
@@ -17,6 +17,10 @@ vi.mock("../src/tauriBridge", async () => {
     ...actual,
     regionPickerShow: vi.fn(),
     captureRegionThumbnail: vi.fn(),
+    startRegionPreview: vi.fn(),
+    stopRegionPreview: vi.fn(),
+    enumerateWindows: vi.fn(),
+    regionAnchorCreate: vi.fn(),
   };
 });
 
@@ -29,6 +33,15 @@ describe("RegionAuthoringPanel", () => {
     vi.clearAllMocks();
     vi.spyOn(tauriBridge, "regionPickerShow").mockResolvedValue(undefined);
     vi.spyOn(tauriBridge, "captureRegionThumbnail").mockResolvedValue("mock-thumbnail-base64");
+    vi.spyOn(tauriBridge, "startRegionPreview").mockResolvedValue(undefined);
+    vi.spyOn(tauriBridge, "stopRegionPreview").mockResolvedValue(undefined);
+    vi.spyOn(tauriBridge, "enumerateWindows").mockResolvedValue([]);
+    vi.spyOn(tauriBridge, "regionAnchorCreate").mockResolvedValue({
+      template_png: "anchor-png",
+      offset_x: 200,
+      offset_y: -4,
+      threshold: 0.8,
+    });
   });
 
   it("renders initial state with Define watch region button", () => {
@@ -105,6 +118,16 @@ describe("RegionAuthoringPanel", () => {
     });
   });
 
+  it("names the display a region was drawn on", async () => {
+    render(<RegionAuthoringPanel regions={[]} onRegionAdd={mockOnRegionAdd} />);
+    const rect = { x: 1920, y: 0, width: 200, height: 100 };
+    await emitRegionPick({ rect, picked: { region: { id: "region-1", rect }, display_id: 2, scale_factor: 2 } });
+
+    await waitFor(() => {
+      expect(screen.getByText("Region captured on display 2 (2× scale) — review details below.")).toBeInTheDocument();
+    });
+  });
+
   it("allows editing region ID and name in pending draft", async () => {
     const { rerender } = render(<RegionAuthoringPanel regions={[]} onRegionAdd={mockOnRegionAdd} />);
 
@@ -552,4 +575,79 @@ describe("RegionAuthoringPanel", () => {
       expect(screen.queryByText("Test")).not.toBeInTheDocument();
     });
   });
+
+  it("streams live preview frames into the region thumbnail", async () => {
+    const regions: Region[] = [
+      { id: "region-1", rect: { x: 0, y: 0, width: 100, height: 100 }, name: "Test" },
+    ];
+    render(<RegionAuthoringPanel regions={regions} />);
+
+    const toggle = screen.getByRole("button", { name: "Live preview" });
+    fireEvent.click(toggle);
+    await waitFor(() => expect(tauriBridge.startRegionPreview).toHaveBeenCalledWith("region-1", 2));
+    expect(toggle).toHaveAttribute("aria-pressed", "true");
+
+    emitTestEvent("loopautoma://region_preview", {
+      region_id: "region-1",
+      rect: regions[0].rect,
+      png_base64: "live-frame",
+      captured_at_ms: 1,
+    });
+    await waitFor(() =>
+      expect(screen.getByAltText("Test thumbnail")).toHaveAttribute("src", "data:image/png;base64,live-frame")
+    );
+
+    fireEvent.click(toggle);
+    await waitFor(() => expect(tauriBridge.stopRegionPreview).toHaveBeenCalledWith("region-1"));
+    expect(toggle).toHaveAttribute("aria-pressed", "false");
+  });
+
+  it("proposes a region from a listed window, clipped to the desktop", async () => {
+    vi.spyOn(tauriBridge, "enumerateWindows").mockResolvedValue([
+      { id: 7, title: "Terminal", process_name: "kitty", pid: 99, x: -10, y: 20, width: 810, height: 600, minimized: false },
+      { id: 8, title: "Hidden", process_name: "mail", pid: 100, x: 0, y: 0, width: 400, height: 300, minimized: true },
+    ]);
+    render(<RegionAuthoringPanel onRegionAdd={mockOnRegionAdd} />);
+
+    const select = await screen.findByRole("combobox", { name: "From window" });
+    expect(screen.queryByRole("option", { name: /Hidden/ })).not.toBeInTheDocument();
+    fireEvent.change(select, { target: { value: "7" } });
+
+    expect(await screen.findByText(/Proposed region: x=0, y=20, w=800, h=600/)).toBeInTheDocument();
+    expect(screen.getByDisplayValue("Terminal")).toBeInTheDocument();
+    expect(screen.getByText(/taken from window “Terminal” \(kitty\)/)).toBeInTheDocument();
+    await waitFor(() =>
+      expect(tauriBridge.captureRegionThumbnail).toHaveBeenCalledWith({ x: 0, y: 20, width: 800, height: 600 })
+    );
+  });
+
+  it("anchors a region to the picked area and removes the anchor again", async () => {
+    const region: Region = { id: "region-1", rect: { x: 300, y: 96, width: 200, height: 80 }, name: "Status" };
+    const onRegionAnchor = vi.fn().mockResolvedValue(undefined);
+    const onRegionAdd = vi.fn();
+
+    const { rerender } = render(
+      <RegionAuthoringPanel regions={[region]} onRegionAdd={onRegionAdd} onRegionAnchor={onRegionAnchor} />
+    );
+    fireEvent.click(screen.getByLabelText("Anchor region"));
+    await waitFor(() => expect(screen.getByText(/drag over a label or icon near the region/)).toBeInTheDocument());
+
+    await emitRegionPick({ rect: { x: 100, y: 100, width: 60, height: 20 } });
+
+    await waitFor(() =>
+      expect(onRegionAnchor).toHaveBeenCalledWith("region-1", expect.objectContaining({ offset_x: 200, offset_y: -4 }))
+    );
+    expect(tauriBridge.regionAnchorCreate).toHaveBeenCalledWith(region.rect, { x: 100, y: 100, width: 60, height: 20 });
+    expect(await screen.findByText(/Region anchored/)).toBeInTheDocument();
+    // The pick anchored the region instead of proposing a new one
+    expect(screen.queryByText(/Proposed region/)).not.toBeInTheDocument();
+
+    const anchored = { ...region, anchor: { template_png: "anchor-png", offset_x: 200, offset_y: -4, threshold: 0.8 } };
+    rerender(<RegionAuthoringPanel regions={[anchored]} onRegionAdd={onRegionAdd} onRegionAnchor={onRegionAnchor} />);
+    expect(screen.getByText("Anchored at +200, -4 from its template")).toBeInTheDocument();
+    expect(screen.getByLabelText("Anchor region")).toHaveAttribute("aria-pressed", "true");
+
+    fireEvent.click(screen.getByLabelText("Anchor region"));
+    expect(onRegionAnchor).toHaveBeenLastCalledWith("region-1", null);
+  });
 });
@@ -0,0 +1,71 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { render, screen, fireEvent, waitFor } from "@testing-library/react";
+import "@testing-library/jest-dom/vitest";
+import { RemoteApiSettings } from "../src/components/RemoteApiSettings";
+
+const bridge = vi.hoisted(() => ({
+  remoteApiGet: vi.fn(),
+  remoteApiSet: vi.fn(),
+  remoteApiStatus: vi.fn(),
+  remoteApiGenerateToken: vi.fn(),
+}));
+vi.mock("../src/tauriBridge", () => bridge);
+
+describe("RemoteApiSettings", () => {
+  beforeEach(() => {
+    bridge.remoteApiGet.mockReset().mockResolvedValue(null);
+    bridge.remoteApiSet.mockReset().mockResolvedValue("http://127.0.0.1:8787/api");
+    bridge.remoteApiStatus.mockReset().mockResolvedValue(null);
+    bridge.remoteApiGenerateToken.mockReset().mockResolvedValue("generated-token-0123456789abcdef");
+  });
+
+  it("saves a localhost API with a generated token and shows its URL", async () => {
+    render(<RemoteApiSettings />);
+    fireEvent.click(screen.getByLabelText("Enable remote API"));
+    fireEvent.click(screen.getByRole("button", { name: "Generate token" }));
+    await waitFor(() => expect(screen.getByLabelText("Remote API token")).toHaveValue("generated-token-0123456789abcdef"));
+    fireEvent.click(screen.getByRole("button", { name: "Save remote API settings" }));
+
+    await waitFor(() =>
+      expect(bridge.remoteApiSet).toHaveBeenCalledWith({
+        lan: false,
+        port: 8787,
+        token: "generated-token-0123456789abcdef",
+        tls: null,
+      })
+    );
+    expect(await screen.findByText("✓ Listening on http://127.0.0.1:8787/api")).toBeInTheDocument();
+  });
+
+  it("asks for certificate files when LAN access is allowed", async () => {
+    bridge.remoteApiGet.mockResolvedValue({ lan: false, port: 9000, token: "saved-token-0123456789abcdef", tls: null });
+    render(<RemoteApiSettings />);
+    fireEvent.click(await screen.findByLabelText("Allow LAN access"));
+    fireEvent.change(screen.getByLabelText("TLS certificate file"), { target: { value: "/etc/la/cert.pem" } });
+    fireEvent.change(screen.getByLabelText("TLS key file"), { target: { value: "/etc/la/key.pem" } });
+    fireEvent.click(screen.getByRole("button", { name: "Save remote API settings" }));
+
+    await waitFor(() =>
+      expect(bridge.remoteApiSet).toHaveBeenCalledWith({
+        lan: true,
+        port: 9000,
+        token: "saved-token-0123456789abcdef",
+        tls: { cert_path: "/etc/la/cert.pem", key_path: "/etc/la/key.pem" },
+      })
+    );
+  });
+
+  it("turns the API off and reports errors from the server", async () => {
+    bridge.remoteApiSet.mockRejectedValueOnce("Remote API failed to listen on 127.0.0.1:8787: address in use");
+    render(<RemoteApiSettings />);
+    fireEvent.click(screen.getByLabelText("Enable remote API"));
+    fireEvent.click(screen.getByRole("button", { name: "Save remote API settings" }));
+    expect(await screen.findByRole("status")).toHaveTextContent("address in use");
+
+    fireEvent.click(screen.getByLabelText("Enable remote API"));
+    bridge.remoteApiSet.mockResolvedValueOnce(null);
+    fireEvent.click(screen.getByRole("button", { name: "Save remote API settings" }));
+    await waitFor(() => expect(bridge.remoteApiSet).toHaveBeenLastCalledWith(null));
+    expect(await screen.findByText("✓ Remote API turned off")).toBeInTheDocument();
+  });
+});
@@ -0,0 +1,119 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { render, screen, fireEvent, waitFor, act } from "@testing-library/react";
+import "@testing-library/jest-dom/vitest";
+import { RunHistoryPanel } from "../src/components/RunHistoryPanel";
+import * as tauriBridge from "../src/tauriBridge";
+import { emitTestEvent } from "../src/eventBridge";
+import { RunRecord } from "../src/types";
+
+vi.mock("../src/tauriBridge", async () => {
+  const actual = await vi.importActual("../src/tauriBridge");
+  return {
+    ...actual,
+    listRuns: vi.fn(),
+    getRunDetails: vi.fn(),
+    pruneRuns: vi.fn(),
+    exportRunReport: vi.fn(),
+    exportRunFrames: vi.fn(),
+  };
+});
+
+const failedRun: RunRecord = {
+  id: 7,
+  profile_id: "agent",
+  started_at_ms: 1_000,
+  ended_at_ms: 61_000,
+  iterations: 4,
+  outcome: { type: "Failed", reason: "no progress" },
+  llm_calls: 3,
+  llm_cost_usd: 0.0125,
+  error_count: 1,
+};
+
+describe("RunHistoryPanel", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+    vi.mocked(tauriBridge.listRuns).mockResolvedValue([failedRun]);
+    vi.mocked(tauriBridge.pruneRuns).mockResolvedValue(2);
+    vi.mocked(tauriBridge.getRunDetails).mockResolvedValue({
+      ...failedRun,
+      llm_prompt_tokens: 4000,
+      llm_completion_tokens: 250,
+      errors: [{ at_ms: 30_000, message: "action 'Click': no display" }],
+      screenshots: ["/config/loopautoma/screenshots/agent-1000.png"],
+      timeline: [],
+      frames: [
+        { region_id: "status", iteration: 1, at_ms: 20_000, path: "/frames/status-00001.png" },
+        { region_id: "log", iteration: 1, at_ms: 20_000, path: "/frames/log-00001.png" },
+      ],
+      masked: [
+        { source: "status", kind: "email", count: 2 },
+        { source: "llm_prompt", kind: "api_key", count: 1 },
+      ],
+    });
+    vi.mocked(tauriBridge.exportRunReport).mockResolvedValue(undefined);
+    vi.mocked(tauriBridge.exportRunFrames).mockResolvedValue(1);
+  });
+
+  it("lists runs with outcome, LLM calls and cost", async () => {
+    render(<RunHistoryPanel />);
+    expect(await screen.findByText(/Failed: no progress · 4 iterations · 3 LLM calls \(\$0\.0125\) · 1 errors/)).toBeInTheDocument();
+    expect(tauriBridge.listRuns).toHaveBeenCalledWith({ limit: 50 });
+  });
+
+  it("shows errors and screenshots of the selected run", async () => {
+    render(<RunHistoryPanel />);
+    fireEvent.click(await screen.findByRole("button", { name: /agent/ }));
+
+    expect(await screen.findByText("action 'Click': no display")).toBeInTheDocument();
+    expect(screen.getByText("/config/loopautoma/screenshots/agent-1000.png")).toBeInTheDocument();
+    expect(screen.getByText(/Ran 60s · 4000 prompt \/ 250 completion tokens/)).toBeInTheDocument();
+    expect(screen.getByText("Masked 2 emails in status, 1 API key in llm_prompt")).toBeInTheDocument();
+    expect(tauriBridge.getRunDetails).toHaveBeenCalledWith(7);
+  });
+
+  it("exports a report of the selected run to the entered path", async () => {
+    render(<RunHistoryPanel />);
+    fireEvent.click(await screen.findByRole("button", { name: /agent/ }));
+    const exportButton = await screen.findByRole("button", { name: "Export report" });
+    expect(exportButton).toBeDisabled();
+    fireEvent.change(screen.getByLabelText("Report file path"), { target: { value: " /tmp/run-7.html " } });
+    fireEvent.click(exportButton);
+
+    expect(await screen.findByText("Report written to /tmp/run-7.html.")).toBeInTheDocument();
+    expect(tauriBridge.exportRunReport).toHaveBeenCalledWith(7, "/tmp/run-7.html");
+  });
+
+  it("exports an animation of the chosen region's frames", async () => {
+    render(<RunHistoryPanel />);
+    fireEvent.click(await screen.findByRole("button", { name: /agent/ }));
+    const exportButton = await screen.findByRole("button", { name: "Export animation" });
+    expect(exportButton).toBeDisabled();
+    fireEvent.change(screen.getByLabelText("Animation region"), { target: { value: "log" } });
+    fireEvent.change(screen.getByLabelText("Animation file path"), { target: { value: "/tmp/run-7.gif" } });
+    fireEvent.click(exportButton);
+
+    expect(await screen.findByText("Animation of 1 frame written to /tmp/run-7.gif.")).toBeInTheDocument();
+    expect(tauriBridge.exportRunFrames).toHaveBeenCalledWith(7, "log", "/tmp/run-7.gif");
+  });
+
+  it("prunes with the entered rules and reloads", async () => {
+    render(<RunHistoryPanel />);
+    await screen.findByText(/no progress/);
+    fireEvent.change(screen.getByLabelText("Keep latest"), { target: { value: "10" } });
+    fireEvent.click(screen.getByRole("button", { name: "Prune" }));
+
+    expect(await screen.findByText("Removed 2 runs.")).toBeInTheDocument();
+    expect(tauriBridge.pruneRuns).toHaveBeenCalledWith(30, 10);
+    expect(tauriBridge.listRuns).toHaveBeenCalledTimes(2);
+  });
+
+  it("reloads when a run is recorded", async () => {
+    render(<RunHistoryPanel />);
+    await screen.findByText(/no progress/);
+    await act(async () => {
+      emitTestEvent("loopautoma://run_recorded", 8);
+    });
+    await waitFor(() => expect(tauriBridge.listRuns).toHaveBeenCalledTimes(2));
+  });
+});
@@ -0,0 +1,51 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { render, screen, fireEvent, waitFor } from "@testing-library/react";
+import "@testing-library/jest-dom/vitest";
+import { SettingsTransfer } from "../src/components/SettingsTransfer";
+
+const bridge = vi.hoisted(() => ({
+  settingsExport: vi.fn(),
+  settingsImport: vi.fn(),
+}));
+vi.mock("../src/tauriBridge", () => bridge);
+
+describe("SettingsTransfer", () => {
+  beforeEach(() => {
+    bridge.settingsExport.mockReset().mockResolvedValue(undefined);
+    bridge.settingsImport.mockReset();
+  });
+
+  it("exports to the given path", async () => {
+    render(<SettingsTransfer />);
+    expect(screen.getByRole("button", { name: "Export settings" })).toBeDisabled();
+    fireEvent.change(screen.getByLabelText("Settings file path"), { target: { value: " /tmp/settings.json " } });
+    fireEvent.click(screen.getByRole("button", { name: "Export settings" }));
+
+    await waitFor(() => expect(bridge.settingsExport).toHaveBeenCalledWith("/tmp/settings.json"));
+    expect(await screen.findByRole("status")).toHaveTextContent("exported without credentials");
+  });
+
+  it("lists secrets to enter again after an import", async () => {
+    bridge.settingsImport.mockResolvedValue({
+      imported: ["audio.volume", "llm.providers"],
+      ignored: ["ui.theme"],
+      reenter: ["anthropic_api_key", "smtp"],
+    });
+    render(<SettingsTransfer />);
+    fireEvent.change(screen.getByLabelText("Settings file path"), { target: { value: "/tmp/settings.json" } });
+    fireEvent.click(screen.getByRole("button", { name: "Import settings" }));
+
+    expect(await screen.findByRole("status")).toHaveTextContent("Imported 2 settings (skipped unknown: ui.theme)");
+    const list = screen.getByRole("list", { name: "Secrets to re-enter" });
+    expect(list).toHaveTextContent("anthropic_api_key");
+    expect(list).toHaveTextContent("smtp");
+  });
+
+  it("shows why an import was refused", async () => {
+    bridge.settingsImport.mockRejectedValue("audio.volume: Volume must be between 0.0 and 1.0");
+    render(<SettingsTransfer />);
+    fireEvent.change(screen.getByLabelText("Settings file path"), { target: { value: "/tmp/settings.json" } });
+    fireEvent.click(screen.getByRole("button", { name: "Import settings" }));
+    expect(await screen.findByRole("alert")).toHaveTextContent("Volume must be between");
+  });
+});
@@ -3,6 +3,7 @@ import { render, screen, fireEvent, waitFor } from "@testing-library/react";
 import { SettingsPanel } from "../src/components/SettingsPanel";
 import * as secureStorage from "../src/tauriSecureStorage";
 import * as tauriBridge from "../src/tauriBridge";
+import { emitTestEvent } from "../src/eventBridge";
 
 // Mock secure storage to prevent Tauri invocation errors
 const secureStorageMocks = vi.hoisted(() => ({
@@ -11,6 +12,11 @@ const secureStorageMocks = vi.hoisted(() => ({
     deleteOpenAIKey: vi.fn().mockResolvedValue(undefined),
     getOpenAIModel: vi.fn().mockResolvedValue("gpt-4o"),
     setOpenAIModel: vi.fn().mockResolvedValue(undefined),
+    listProviders: vi.fn().mockResolvedValue([]),
+    setProvider: vi.fn().mockResolvedValue(undefined),
+    deleteProvider: vi.fn().mockResolvedValue(undefined),
+    detectEnvCredentials: vi.fn().mockResolvedValue([]),
+    importEnvCredentials: vi.fn().mockResolvedValue([]),
 }));
 
 vi.mock("../src/tauriSecureStorage", () => secureStorageMocks);
@@ -22,6 +28,35 @@ const tauriBridgeMocks = vi.hoisted(() => ({
     audioGetEnabled: vi.fn().mockResolvedValue(false),
     audioSetVolume: vi.fn().mockResolvedValue(undefined),
     audioGetVolume: vi.fn().mockResolvedValue(0.5),
+    audioGetCustomSounds: vi.fn().mockResolvedValue({}),
+    audioSetCustomSound: vi.fn().mockResolvedValue(undefined),
+    audioGetSoundMap: vi.fn().mockResolvedValue({}),
+    audioSetSoundMap: vi.fn().mockResolvedValue(undefined),
+    audioTestAlert: vi.fn().mockResolvedValue(undefined),
+    profilesEncryptionGet: vi.fn().mockResolvedValue(false),
+    profilesEncryptionSet: vi.fn().mockResolvedValue(undefined),
+    profileSyncGet: vi.fn().mockResolvedValue(null),
+    profileSyncSet: vi.fn().mockResolvedValue(undefined),
+    profileSyncNow: vi.fn(),
+    profileSyncStatus: vi.fn().mockResolvedValue(null),
+    settingsExport: vi.fn().mockResolvedValue(undefined),
+    settingsImport: vi.fn(),
+    quietHoursGet: vi.fn().mockResolvedValue(null),
+    quietHoursSet: vi.fn().mockResolvedValue(undefined),
+    logLevelGet: vi.fn().mockResolvedValue("info"),
+    logLevelSet: vi.fn().mockResolvedValue(undefined),
+    tailLogs: vi.fn().mockResolvedValue([]),
+    webhooksGet: vi.fn().mockResolvedValue([]),
+    webhooksSet: vi.fn().mockResolvedValue(undefined),
+    webhookTest: vi.fn().mockResolvedValue(undefined),
+    smtpGet: vi.fn().mockResolvedValue(null),
+    smtpSet: vi.fn().mockResolvedValue(undefined),
+    smtpTest: vi.fn().mockResolvedValue(undefined),
+    remoteApiGet: vi.fn().mockResolvedValue(null),
+    remoteApiSet: vi.fn().mockResolvedValue(null),
+    remoteApiStatus: vi.fn().mockResolvedValue(null),
+    remoteApiGenerateToken: vi.fn().mockResolvedValue(""),
+    automationCapabilities: vi.fn().mockResolvedValue(null),
 }));
 
 vi.mock("../src/tauriBridge", () => tauriBridgeMocks);
@@ -30,6 +65,7 @@ beforeEach(() => {
     vi.clearAllMocks();
     vi.mocked(tauriBridge.audioGetEnabled).mockResolvedValue(false);
     vi.mocked(tauriBridge.audioGetVolume).mockResolvedValue(0.5);
+    vi.mocked(tauriBridge.profilesEncryptionGet).mockResolvedValue(false);
 });
 
 describe("SettingsPanel", () => {
@@ -65,6 +101,25 @@ describe("SettingsPanel", () => {
         expect(screen.getByText(/OpenAI Integration/i)).toBeTruthy();
     });
 
+    it("reloads settings changed elsewhere", async () => {
+        render(
+            <SettingsPanel
+                isOpen={true}
+                onClose={vi.fn()}
+                theme="dark"
+                onThemeChange={vi.fn()}
+                fontSize={13}
+                onFontSizeChange={vi.fn()}
+            />
+        );
+        await waitFor(() => expect(tauriBridge.audioGetEnabled).toHaveBeenCalledTimes(1));
+
+        vi.mocked(tauriBridge.audioGetEnabled).mockResolvedValue(true);
+        emitTestEvent("loopautoma://settings", { key: "audio.enabled", removed: false });
+        await waitFor(() => expect(tauriBridge.audioGetEnabled).toHaveBeenCalledTimes(2));
+        await waitFor(() => expect(secureStorage.getOpenAIKeyStatus).toHaveBeenCalledTimes(2));
+    });
+
     it("shows theme selector with current value", () => {
         render(
             <SettingsPanel
@@ -668,4 +723,30 @@ describe("SettingsPanel", () => {
             expect(tauriBridge.audioTestCompleted).toHaveBeenCalled();
         });
     });
+
+    it("toggles profile encryption and reports keyring errors", async () => {
+        render(
+            <SettingsPanel
+                isOpen={true}
+                onClose={vi.fn()}
+                theme="dark"
+                onThemeChange={vi.fn()}
+                fontSize={13}
+                onFontSizeChange={vi.fn()}
+            />
+        );
+
+        const checkbox = await screen.findByRole("checkbox", { name: /encrypt profiles on disk/i });
+        fireEvent.click(checkbox);
+        await waitFor(() => {
+            expect(tauriBridge.profilesEncryptionSet).toHaveBeenCalledWith(true);
+        });
+        expect(await screen.findByText(/Profiles are now stored encrypted/i)).toBeInTheDocument();
+        expect(checkbox).toBeChecked();
+
+        vi.mocked(tauriBridge.profilesEncryptionSet).mockRejectedValueOnce("OS keyring unavailable");
+        fireEvent.click(checkbox);
+        expect(await screen.findByText(/Error: OS keyring unavailable/)).toBeInTheDocument();
+        expect(checkbox).toBeChecked();
+    });
 });
@@ -10,6 +10,9 @@ vi.mock("@tauri-apps/api/event", () => ({
   listen: vi.fn().mockImplementation((_channel, _callback) => Promise.resolve(mockOff)),
 }));
 
+const bridge = vi.hoisted(() => ({ profilesLoad: vi.fn() }));
+vi.mock("../src/tauriBridge", () => bridge);
+
 const dispatchRuntimeEvent = (payload: Event) => {
   window.dispatchEvent(new CustomEvent("loopautoma://event", { detail: { payload } }));
 };
@@ -31,6 +34,19 @@ describe("Store hooks", () => {
 
       expect(result.current.config).toEqual(cfg);
     });
+
+    it("reloads the config after a remote profile sync", async () => {
+      const synced = defaultProfilesConfig();
+      synced.profiles[0].name = "Synced";
+      bridge.profilesLoad.mockResolvedValue(synced);
+      const { result } = renderHook(() => useProfiles());
+
+      act(() => {
+        window.dispatchEvent(new CustomEvent("loopautoma://profiles_synced", { detail: { payload: { added: [], updated: [synced.profiles[0].id] } } }));
+      });
+
+      await waitFor(() => expect(result.current.config?.profiles[0].name).toBe("Synced"));
+    });
   });
 
   describe("useEventStream", () => {
@@ -113,6 +129,57 @@ describe("Store hooks", () => {
       });
     });
 
+    it("reports a run queued outside its run window without marking it paused", async () => {
+      const { result } = renderHook(() => useRunState());
+
+      act(() => {
+        result.current.setRunningProfileId("test-id");
+        dispatchRuntimeEvent({ type: "MonitorStateChanged", state: "OutsideRunWindow" } as Event);
+      });
+
+      await waitFor(() => {
+        expect(result.current.outsideRunWindow).toBe(true);
+      });
+      expect(result.current.paused).toBe(false);
+      expect(result.current.runningProfileId).toBe("test-id");
+    });
+
+    it("treats a run held by a timed-out action as paused until it runs again", async () => {
+      const { result } = renderHook(() => useRunState());
+
+      act(() => {
+        result.current.setRunningProfileId("test-id");
+        dispatchRuntimeEvent({ type: "MonitorStateChanged", state: "ActionTimedOut" } as Event);
+      });
+
+      await waitFor(() => {
+        expect(result.current.actionTimedOut).toBe(true);
+      });
+      expect(result.current.paused).toBe(true);
+      expect(result.current.runningProfileId).toBe("test-id");
+
+      act(() => {
+        dispatchRuntimeEvent({ type: "MonitorStateChanged", state: "Running" } as Event);
+      });
+
+      await waitFor(() => {
+        expect(result.current.actionTimedOut).toBe(false);
+      });
+      expect(result.current.paused).toBe(false);
+    });
+
+    it("marks a profile started from the tray menu as running", async () => {
+      const { result } = renderHook(() => useRunState());
+
+      act(() => {
+        window.dispatchEvent(new CustomEvent("loopautoma://tray_profile_started", { detail: { payload: "nightly" } }));
+      });
+
+      await waitFor(() => {
+        expect(result.current.runningProfileId).toBe("nightly");
+      });
+    });
+
     it("keeps runningProfileId on MonitorStateChanged to Running", async () => {
       const { result } = renderHook(() => useRunState());
 
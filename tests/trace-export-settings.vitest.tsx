@@ -0,0 +1,66 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { render, screen, fireEvent, waitFor } from "@testing-library/react";
+import "@testing-library/jest-dom/vitest";
+import { TraceExportSettings, parseHeaders } from "../src/components/TraceExportSettings";
+
+const bridge = vi.hoisted(() => ({
+  otlpTracesGet: vi.fn(),
+  otlpTracesSet: vi.fn(),
+  otlpTracesStatus: vi.fn(),
+}));
+vi.mock("../src/tauriBridge", () => bridge);
+
+describe("TraceExportSettings", () => {
+  beforeEach(() => {
+    bridge.otlpTracesGet.mockReset().mockResolvedValue(null);
+    bridge.otlpTracesSet.mockReset().mockResolvedValue("https://otlp.example/v1/traces");
+    bridge.otlpTracesStatus.mockReset().mockResolvedValue(null);
+  });
+
+  it("saves the endpoint with its headers and shows where traces go", async () => {
+    bridge.otlpTracesStatus.mockResolvedValueOnce(null).mockResolvedValue({
+      url: "https://otlp.example/v1/traces",
+      exported: 0,
+      dropped: 0,
+      last_error: null,
+    });
+    render(<TraceExportSettings />);
+    fireEvent.click(screen.getByLabelText("Export traces"));
+    fireEvent.change(screen.getByLabelText("OTLP endpoint"), { target: { value: "https://otlp.example " } });
+    fireEvent.change(screen.getByLabelText("OTLP headers"), { target: { value: "x-api-key: k1\n\nx-team: ops" } });
+    fireEvent.click(screen.getByRole("button", { name: "Save trace settings" }));
+
+    await waitFor(() =>
+      expect(bridge.otlpTracesSet).toHaveBeenCalledWith({
+        endpoint: "https://otlp.example",
+        headers: { "x-api-key": "k1", "x-team": "ops" },
+      })
+    );
+    expect(await screen.findByText("✓ Sending traces to https://otlp.example/v1/traces")).toBeInTheDocument();
+    expect(screen.getByText(/0 span\(s\) sent, 0 dropped/)).toBeInTheDocument();
+  });
+
+  it("loads the saved settings and turns the export off", async () => {
+    bridge.otlpTracesGet.mockResolvedValue({ endpoint: "http://collector:4318", headers: { "x-api-key": "k1" } });
+    bridge.otlpTracesSet.mockResolvedValue(null);
+    render(<TraceExportSettings />);
+    await waitFor(() => expect(screen.getByLabelText("OTLP endpoint")).toHaveValue("http://collector:4318"));
+    expect(screen.getByLabelText("OTLP headers")).toHaveValue("x-api-key: k1");
+    fireEvent.click(screen.getByLabelText("Export traces"));
+    fireEvent.click(screen.getByRole("button", { name: "Save trace settings" }));
+
+    await waitFor(() => expect(bridge.otlpTracesSet).toHaveBeenCalledWith(null));
+    expect(await screen.findByText("✓ Trace export turned off")).toBeInTheDocument();
+  });
+
+  it("rejects header lines without a name", async () => {
+    expect(() => parseHeaders("no colon here")).toThrow("Name: value");
+    render(<TraceExportSettings />);
+    fireEvent.click(screen.getByLabelText("Export traces"));
+    fireEvent.change(screen.getByLabelText("OTLP headers"), { target: { value: ": value" } });
+    fireEvent.click(screen.getByRole("button", { name: "Save trace settings" }));
+
+    expect(await screen.findByRole("status")).toHaveTextContent("needs the form Name: value");
+    expect(bridge.otlpTracesSet).not.toHaveBeenCalled();
+  });
+});
@@ -0,0 +1,59 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { render, screen, fireEvent, waitFor } from "@testing-library/react";
+import "@testing-library/jest-dom/vitest";
+import { VoiceCommandSettings } from "../src/components/VoiceCommandSettings";
+import { ProfileVoiceEditor } from "../src/components/ProfileVoiceEditor";
+import { defaultPresetProfile, Profile } from "../src/types";
+
+const bridge = vi.hoisted(() => ({
+  voiceCommandsGet: vi.fn(),
+  voiceCommandsSet: vi.fn(),
+  voiceCommandsStatus: vi.fn(),
+}));
+vi.mock("../src/tauriBridge", () => bridge);
+
+describe("VoiceCommandSettings", () => {
+  beforeEach(() => {
+    bridge.voiceCommandsGet.mockReset().mockResolvedValue(null);
+    bridge.voiceCommandsSet.mockReset().mockResolvedValue(["loopautoma continue", "loopautoma stop"]);
+    bridge.voiceCommandsStatus.mockReset().mockResolvedValue(null);
+  });
+
+  it("listens with the entered model and lists the phrases", async () => {
+    render(<VoiceCommandSettings />);
+    fireEvent.click(screen.getByLabelText("Enable voice commands"));
+    fireEvent.change(screen.getByLabelText("Vosk model directory"), { target: { value: " /models/vosk-small " } });
+    fireEvent.click(screen.getByRole("button", { name: "Save voice settings" }));
+
+    await waitFor(() =>
+      expect(bridge.voiceCommandsSet).toHaveBeenCalledWith({ model_path: "/models/vosk-small", wake_word: "loopautoma" })
+    );
+    expect(await screen.findByRole("status")).toHaveTextContent("✓ Listening for 2 phrases");
+    expect(screen.getByLabelText("Voice phrases")).toHaveTextContent("loopautoma continue");
+  });
+
+  it("loads the saved settings and shows why listening failed", async () => {
+    bridge.voiceCommandsGet.mockResolvedValue({ model_path: "/models/vosk-small", wake_word: "computer" });
+    bridge.voiceCommandsSet.mockRejectedValue("'resume' is not in the vocabulary of the Vosk model; choose words it knows");
+    render(<VoiceCommandSettings />);
+    await waitFor(() => expect(screen.getByLabelText("Wake word")).toHaveValue("computer"));
+    fireEvent.click(screen.getByRole("button", { name: "Save voice settings" }));
+
+    expect(await screen.findByRole("status")).toHaveTextContent("Error: 'resume' is not in the vocabulary");
+  });
+});
+
+describe("ProfileVoiceEditor", () => {
+  it("sets phrases and drops the voice config when both are cleared", () => {
+    const profile: Profile = { ...defaultPresetProfile(), id: "agent", name: "Agent" };
+    const onProfileChange = vi.fn();
+    const { rerender } = render(<ProfileVoiceEditor profile={profile} onProfileChange={onProfileChange} />);
+    fireEvent.change(screen.getByLabelText("Start phrase"), { target: { value: "continue" } });
+    const withVoice = onProfileChange.mock.calls[0][0] as Profile;
+    expect(withVoice.voice).toEqual({ start: "continue" });
+
+    rerender(<ProfileVoiceEditor profile={withVoice} onProfileChange={onProfileChange} />);
+    fireEvent.change(screen.getByLabelText("Start phrase"), { target: { value: " " } });
+    expect((onProfileChange.mock.calls[1][0] as Profile).voice).toBeUndefined();
+  });
+});
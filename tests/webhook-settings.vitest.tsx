@@ -0,0 +1,59 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { render, screen, fireEvent, waitFor } from "@testing-library/react";
+import "@testing-library/jest-dom/vitest";
+import { WebhookSettings } from "../src/components/WebhookSettings";
+
+const bridge = vi.hoisted(() => ({
+  webhooksGet: vi.fn(),
+  webhooksSet: vi.fn(),
+  webhookTest: vi.fn(),
+}));
+vi.mock("../src/tauriBridge", () => bridge);
+
+describe("WebhookSettings", () => {
+  beforeEach(() => {
+    bridge.webhooksGet.mockReset().mockResolvedValue([]);
+    bridge.webhooksSet.mockReset().mockResolvedValue(undefined);
+    bridge.webhookTest.mockReset().mockResolvedValue(undefined);
+  });
+
+  it("adds a Telegram target with a screenshot and saves it", async () => {
+    render(<WebhookSettings />);
+    fireEvent.click(screen.getByRole("button", { name: "Add webhook" }));
+    fireEvent.change(screen.getByLabelText("Webhook 1 service"), { target: { value: "telegram" } });
+    fireEvent.change(screen.getByLabelText("Webhook 1 bot token"), { target: { value: "123:abc" } });
+    fireEvent.change(screen.getByLabelText("Webhook 1 chat id"), { target: { value: "-100" } });
+    fireEvent.click(screen.getByLabelText("Webhook 1 screenshot"));
+    fireEvent.click(screen.getByRole("button", { name: "Save webhooks" }));
+
+    await waitFor(() =>
+      expect(bridge.webhooksSet).toHaveBeenCalledWith([
+        { target: { kind: "telegram", token: "123:abc", chat_id: "-100" }, include_screenshot: true },
+      ]),
+    );
+    expect(await screen.findByRole("status")).toHaveTextContent("Webhooks saved");
+  });
+
+  it("disables screenshots for Slack and reports test failures", async () => {
+    const slack = { target: { kind: "slack", url: "https://hooks.slack.com/services/x" }, include_screenshot: false };
+    bridge.webhooksGet.mockResolvedValue([slack]);
+    bridge.webhookTest.mockRejectedValue("Webhook rejected with 404 Not Found: no_service");
+    render(<WebhookSettings />);
+
+    expect(await screen.findByLabelText("Webhook 1 URL")).toHaveValue("https://hooks.slack.com/services/x");
+    expect(screen.getByLabelText("Webhook 1 screenshot")).toBeDisabled();
+    fireEvent.click(screen.getByRole("button", { name: "Send test" }));
+
+    await waitFor(() => expect(bridge.webhookTest).toHaveBeenCalledWith(slack));
+    expect(await screen.findByRole("status")).toHaveTextContent("no_service");
+  });
+
+  it("removes a target", async () => {
+    bridge.webhooksGet.mockResolvedValue([{ target: { kind: "discord", url: "https://discord.com/api/webhooks/1/a" } }]);
+    render(<WebhookSettings />);
+    await screen.findByLabelText("Webhook 1 URL");
+    fireEvent.click(screen.getByRole("button", { name: "Remove" }));
+    fireEvent.click(screen.getByRole("button", { name: "Save webhooks" }));
+    await waitFor(() => expect(bridge.webhooksSet).toHaveBeenCalledWith([]));
+  });
+});